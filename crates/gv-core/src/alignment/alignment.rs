@@ -1,21 +1,26 @@
 use crate::alignment::{
-    coverage::{BaseCoverage, DEFAULT_COVERAGE, calculate_basewise_coverage},
-    read::{AlignedRead, RenderingContext, calculate_rendering_contexts},
+    coverage::{BaseCoverage, DEFAULT_COVERAGE, ModificationCoverage, calculate_basewise_coverage},
+    read::{AlignedRead, RenderingContext, RenderingContextModifier, calculate_rendering_contexts},
 };
 use crate::error::TGVError;
 use crate::intervals::{GenomeInterval, Region};
-use crate::message::{AlignmentFilter, AlignmentSort};
+use crate::message::{AlignmentColor, AlignmentFilter, AlignmentGroup, AlignmentSort};
 use crate::sequence::Sequence;
-use std::collections::{BTreeMap, HashMap, hash_map::Entry};
+use crate::variant::VariantAllele;
+use clap::ValueEnum;
+use itertools::Itertools;
+use noodles::sam::alignment::record::Flags;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, hash_map::Entry};
 
 pub(super) const RENDERING_CONTEXT_NOT_CALCULATED: u64 = u64::MAX;
 
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
 pub(super) enum BaseSortKey {
     A,
-    T,
     C,
     G,
+    T,
     N,
     OtherBase,
     Deletion,
@@ -43,6 +48,308 @@ pub(super) struct SortableStackItem {
     pub sort_key: Option<BaseSortKey>,
 }
 
+/// Categories of reads that can be hidden from the alignment track. Hiding is opt-in with `:hide`, except for secondary
+/// and supplementary reads, which are hidden by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum HiddenReadCategory {
+    Unmapped,
+    Secondary,
+    Supplementary,
+    Duplicate,
+}
+
+impl HiddenReadCategory {
+    /// The hidden category of a read with these flags. Reads in several categories count toward the first one.
+    pub fn of(flags: Flags) -> Option<Self> {
+        if flags.is_unmapped() {
+            Some(Self::Unmapped)
+        } else if flags.is_secondary() {
+            Some(Self::Secondary)
+        } else if flags.is_supplementary() {
+            Some(Self::Supplementary)
+        } else if flags.is_duplicate() {
+            Some(Self::Duplicate)
+        } else {
+            None
+        }
+    }
+
+    /// Categories that are shown unless hidden with `:hide`.
+    pub fn shown_by_default() -> BTreeSet<Self> {
+        BTreeSet::from([Self::Unmapped, Self::Duplicate])
+    }
+
+    pub fn all() -> BTreeSet<Self> {
+        BTreeSet::from([
+            Self::Unmapped,
+            Self::Secondary,
+            Self::Supplementary,
+            Self::Duplicate,
+        ])
+    }
+}
+
+impl std::str::FromStr for HiddenReadCategory {
+    type Err = TGVError;
+
+    /// Parse `"unmapped"`, `"secondary"`, `"supplementary"`, or `"duplicate"`.
+    fn from_str(s: &str) -> Result<Self, TGVError> {
+        match s {
+            "unmapped" => Ok(Self::Unmapped),
+            "secondary" => Ok(Self::Secondary),
+            "supplementary" => Ok(Self::Supplementary),
+            "duplicate" => Ok(Self::Duplicate),
+            _ => Err(TGVError::ParsingError(format!(
+                "Invalid read category `{s}`. Expected \"unmapped\", \"secondary\", \"supplementary\", or \"duplicate\"."
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for HiddenReadCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HiddenReadCategory::Unmapped => write!(f, "unmapped"),
+            HiddenReadCategory::Secondary => write!(f, "secondary"),
+            HiddenReadCategory::Supplementary => write!(f, "supplementary"),
+            HiddenReadCategory::Duplicate => write!(f, "duplicate"),
+        }
+    }
+}
+
+/// Pair orientation: strand and segment number (1 = first, 2 = last) of the left read, then of the right read.
+/// Proper pairs in forward-reverse libraries are F1R2 or F2R1. R1F2 / R2F1 suggest tandem duplications, and same-strand
+/// pairs suggest inversions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PairOrientation {
+    F1R2,
+    F2R1,
+    R1F2,
+    R2F1,
+    F1F2,
+    F2F1,
+    R1R2,
+    R2R1,
+
+    /// Unpaired reads, reads with an unmapped mate or a mate on another contig, and reads with missing mate info.
+    Other,
+}
+
+impl PairOrientation {
+    /// Groups in display order.
+    pub const ALL: [Self; 9] = [
+        Self::F1R2,
+        Self::F2R1,
+        Self::R1F2,
+        Self::R2F1,
+        Self::F1F2,
+        Self::F2F1,
+        Self::R1R2,
+        Self::R2R1,
+        Self::Other,
+    ];
+
+    /// Orientation of a paired read with these flags. is_leftmost: whether the read starts at or before its mate.
+    pub fn of(flags: Flags, is_leftmost: bool) -> Self {
+        if !flags.is_segmented() || flags.is_unmapped() || flags.is_mate_unmapped() {
+            return Self::Other;
+        }
+
+        let read_is_first = match (flags.is_first_segment(), flags.is_last_segment()) {
+            (true, false) => true,
+            (false, true) => false,
+            _ => return Self::Other,
+        };
+
+        // (is_reverse, is_first) of the left and right reads.
+        let read = (flags.is_reverse_complemented(), read_is_first);
+        let mate = (flags.is_mate_reverse_complemented(), !read_is_first);
+        let (left, right) = if is_leftmost {
+            (read, mate)
+        } else {
+            (mate, read)
+        };
+
+        match (left, right) {
+            ((false, true), (true, false)) => Self::F1R2,
+            ((false, false), (true, true)) => Self::F2R1,
+            ((true, true), (false, false)) => Self::R1F2,
+            ((true, false), (false, true)) => Self::R2F1,
+            ((false, true), (false, false)) => Self::F1F2,
+            ((false, false), (false, true)) => Self::F2F1,
+            ((true, true), (true, false)) => Self::R1R2,
+            ((true, false), (true, true)) => Self::R2R1,
+            _ => Self::Other,
+        }
+    }
+
+    /// Orientation of a read. Reads with a mate on another contig or without a mate position are other.
+    pub fn of_read(read: &AlignedRead) -> Self {
+        let record = &read.record;
+        match (
+            record.mate_alignment_start(),
+            record.reference_sequence_id(),
+            record.mate_reference_sequence_id(),
+        ) {
+            (Some(mate_start), Some(contig), Some(mate_contig)) if contig == mate_contig => {
+                Self::of(record.flags(), read.start <= mate_start.get() as u64)
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Haplotype of a read from the HP tag of phased reads, e.g. from WhatsHap or HiPhase. Phased haplotypes sort by their
+/// number, then unphased reads.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Haplotype {
+    /// HP value.
+    Phased(String),
+
+    /// Reads without HP.
+    Unphased,
+}
+
+impl Haplotype {
+    pub fn of_read(read: &AlignedRead) -> Self {
+        match read.tag_value("HP") {
+            Some(value) => Self::Phased(value),
+            None => Self::Unphased,
+        }
+    }
+
+    fn sort_key(&self) -> (bool, i64, &str) {
+        match self {
+            Self::Phased(value) => match value.parse::<i64>() {
+                Ok(n) => (false, n, ""),
+                Err(_) => (false, i64::MAX, value.as_str()),
+            },
+            Self::Unphased => (true, 0, ""),
+        }
+    }
+}
+
+impl Ord for Haplotype {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Haplotype {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Haplotype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Phased(value) => write!(f, "HP={value}"),
+            Self::Unphased => write!(f, "Unphased"),
+        }
+    }
+}
+
+/// Expected insert size range of the read library, in bases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsertRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl Default for InsertRange {
+    fn default() -> Self {
+        Self { min: 50, max: 1000 }
+    }
+}
+
+impl std::str::FromStr for InsertRange {
+    type Err = TGVError;
+
+    /// Parse MIN,MAX (e.g. 200,600).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_once(',')
+            .and_then(|(min, max)| {
+                Some(Self {
+                    min: min.trim().parse().ok()?,
+                    max: max.trim().parse().ok()?,
+                })
+            })
+            .filter(|range| range.min <= range.max)
+            .ok_or_else(|| {
+                TGVError::ValueError(format!(
+                    "Invalid insert size range {s}. Expected MIN,MAX with MIN <= MAX, e.g. 200,600"
+                ))
+            })
+    }
+}
+
+/// Class of a read pair by orientation and insert size, relative to the expected insert size range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairClass {
+    Normal,
+    TooSmall,
+    TooLarge,
+
+    /// Orientation other than F1R2 / F2R1, regardless of the insert size.
+    Discordant,
+}
+
+impl PairClass {
+    pub fn of(orientation: PairOrientation, insert_size: u64, range: &InsertRange) -> Self {
+        match orientation {
+            PairOrientation::R1F2
+            | PairOrientation::R2F1
+            | PairOrientation::F1F2
+            | PairOrientation::F2F1
+            | PairOrientation::R1R2
+            | PairOrientation::R2R1 => Self::Discordant,
+            PairOrientation::F1R2 | PairOrientation::F2R1 | PairOrientation::Other => {
+                if insert_size < range.min {
+                    Self::TooSmall
+                } else if insert_size > range.max {
+                    Self::TooLarge
+                } else {
+                    Self::Normal
+                }
+            }
+        }
+    }
+}
+
+/// Number of reads skipped while loading an alignment, by category.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HiddenReadCounts {
+    pub unmapped: usize,
+    pub secondary: usize,
+    pub supplementary: usize,
+    pub duplicate: usize,
+}
+
+impl HiddenReadCounts {
+    pub fn total(&self) -> usize {
+        self.unmapped + self.secondary + self.supplementary + self.duplicate
+    }
+
+    /// Whether a read with these flags is hidden. Hidden reads are tallied.
+    /// Reads in the `shown` categories are kept.
+    pub fn hide(&mut self, flags: Flags, shown: &BTreeSet<HiddenReadCategory>) -> bool {
+        let Some(category) = HiddenReadCategory::of(flags) else {
+            return false;
+        };
+        if shown.contains(&category) {
+            return false;
+        }
+        match category {
+            HiddenReadCategory::Unmapped => self.unmapped += 1,
+            HiddenReadCategory::Secondary => self.secondary += 1,
+            HiddenReadCategory::Supplementary => self.supplementary += 1,
+            HiddenReadCategory::Duplicate => self.duplicate += 1,
+        }
+        true
+    }
+}
+
 /// An alignment stack
 #[derive(Debug, Default)]
 pub struct Alignment {
@@ -70,6 +377,19 @@ pub struct Alignment {
     /// Calculated as needed.
     coverage: BTreeMap<u64, BaseCoverage>,
 
+    /// Base modification calls at each position. Keys are 1-based, inclusive.
+    /// Positions without calls are absent. Empty unless aggregate_modifications is set.
+    modification_coverage: BTreeMap<u64, ModificationCoverage>,
+
+    /// Whether base modifications are aggregated into modification_coverage. MM / ML tags are not parsed otherwise.
+    aggregate_modifications: bool,
+
+    /// Reads skipped while loading.
+    pub hidden_read_counts: HiddenReadCounts,
+
+    /// Whether loading stopped at the max reads cap, leaving reads of the region unloaded.
+    pub overflow: bool,
+
     /// The left bound of region with complete data.
     /// 1-based, inclusive.
     data_complete_left_bound: u64,
@@ -80,9 +400,34 @@ pub struct Alignment {
 
     // Whether to display the read
     pub show_read: Vec<bool>,
+
+    /// Whether a shown read is left out of the display by downsampling. Downsampled reads still count toward coverage.
+    /// Empty if no reads are downsampled.
+    pub downsampled: Vec<bool>,
+
+    /// Displayed reads per column. None displays all shown reads. Applied when filtering.
+    pub downsample_depth: Option<usize>,
+
+    /// Coloring of aligned segments.
+    pub color: AlignmentColor,
+
+    /// Whether read bases are drawn complemented, to match the reference under `:revcomp`.
+    pub complement_bases: bool,
+
+    /// Read bases with a lower quality score are drawn as matches. 0 draws all mismatches.
+    min_mismatch_quality: u8,
+
+    /// Grouping of reads into vertical blocks.
+    pub group: AlignmentGroup,
 }
 
 impl Alignment {
+    /// Displayed reads per column until the frontend sets the depth from the track height.
+    pub const DEFAULT_DOWNSAMPLE_DEPTH: usize = 100;
+
+    /// Seed of the downsampling random number generator, so that the same reads are kept on every load.
+    const DOWNSAMPLE_SEED: u64 = 0x7467_7600_5eed;
+
     /// Check if data in [left, right] is all loaded.
     /// 1-based, inclusive.
     pub fn has_complete_data(&self, region: &Region) -> bool {
@@ -108,6 +453,97 @@ impl Alignment {
         self.ys_index.len()
     }
 
+    /// Number of shown reads covering the variant position, and how many of them carry the alternate allele.
+    pub fn allele_support(&self, allele: &VariantAllele) -> (usize, usize) {
+        self.reads
+            .iter()
+            .zip(self.show_read.iter())
+            .filter(|(read, shown)| {
+                **shown && read.start <= allele.position && read.end >= allele.position
+            })
+            .fold((0, 0), |(covering, supporting), (read, _)| {
+                (covering + 1, supporting + read.supports(allele) as usize)
+            })
+    }
+
+    /// Shown reads overlapping the region, in the loaded order. Reads hidden by filters are skipped.
+    pub fn shown_reads_overlapping(&self, region: &Region) -> Vec<&AlignedRead> {
+        if region.contig_index() != self.contig_index {
+            return Vec::new();
+        }
+
+        self.reads
+            .iter()
+            .zip(self.show_read.iter())
+            .filter(|(read, shown)| {
+                **shown && read.start <= region.end() && read.end >= region.start()
+            })
+            .map(|(read, _)| read)
+            .collect()
+    }
+
+    /// Read counts of inserted sequences of shown reads anchored at position (the base after the insertion).
+    pub fn insertions_at(
+        &mut self,
+        position: u64,
+        reference_sequence: &Sequence,
+    ) -> Result<HashMap<Vec<u8>, usize>, TGVError> {
+        let mut counts = HashMap::<Vec<u8>, usize>::new();
+        for read_index in 0..self.reads.len() {
+            if !self.show_read[read_index] || !self.reads[read_index].has_insertion_at(position) {
+                continue;
+            }
+
+            let context_index = match self.get_rendering_context_index(read_index) {
+                Some(context_index) => context_index,
+                None => self.calculate_read_rendering_context(read_index, reference_sequence)?,
+            };
+            for context in self.rendering_contexts[context_index as usize].iter() {
+                for modifier in context.modifiers.iter() {
+                    if let RenderingContextModifier::Insertion(anchor, bases) = modifier
+                        && *anchor == position
+                    {
+                        *counts.entry(bases.clone()).or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Coverage from left to right (1-based, inclusive) in bedGraph format: contig, 0-based start, end, and depth. Each
+    /// line is a bin of bin_size bases with the mean depth of its bases; the last bin may be shorter. Reads hidden by
+    /// filters are not counted, as in the coverage track.
+    pub fn coverage_bedgraph(
+        &self,
+        contig_name: &str,
+        left: u64,
+        right: u64,
+        bin_size: u64,
+    ) -> String {
+        let mut bedgraph = String::new();
+        let mut bin_start = left;
+        while bin_start <= right {
+            let bin_end = u64::min(bin_start + bin_size.max(1) - 1, right);
+            let depth = (bin_start..=bin_end)
+                .map(|position| self.coverage_at(position).total as u64)
+                .sum::<u64>();
+            let bin_length = bin_end - bin_start + 1;
+            let depth = if depth % bin_length == 0 {
+                (depth / bin_length).to_string()
+            } else {
+                format!("{:.2}", depth as f64 / bin_length as f64)
+            };
+            bedgraph.push_str(&format!(
+                "{contig_name}\t{}\t{bin_end}\t{depth}\n",
+                bin_start - 1
+            ));
+            bin_start = bin_end + 1;
+        }
+        bedgraph
+    }
+
     /// Basewise coverage at position.
     /// 1-based, inclusive.
     pub fn coverage_at(&self, pos: u64) -> &BaseCoverage {
@@ -117,8 +553,30 @@ impl Alignment {
         }
     }
 
+    /// Leftmost position of the highest coverage in [start, end], or None without coverage there.
+    /// 1-based, inclusive.
+    pub fn highest_coverage_position(&self, start: u64, end: u64) -> Option<u64> {
+        self.coverage
+            .range(start..=end)
+            .filter(|(_, coverage)| coverage.total > 0)
+            .max_by_key(|(position, coverage)| (coverage.total, Reverse(**position)))
+            .map(|(position, _)| *position)
+    }
+
+    /// Base modification calls at position.
+    /// 1-based, inclusive.
+    pub fn modification_coverage_at(&self, pos: u64) -> Option<&ModificationCoverage> {
+        self.modification_coverage.get(&pos)
+    }
+
     /// Return the read at x_coordinate, yth track
     pub fn read_overlapping(&self, left: u64, right: u64, y: usize) -> Option<&AlignedRead> {
+        self.read_index_overlapping(left, right, y)
+            .map(|index| &self.reads[index])
+    }
+
+    /// Index in reads of the read at x_coordinate, yth track.
+    pub fn read_index_overlapping(&self, left: u64, right: u64, y: usize) -> Option<usize> {
         if y >= self.depth() {
             return None;
         }
@@ -126,7 +584,7 @@ impl Alignment {
         self.ys_index[y]
             .iter()
             .find(|i_read| self.reads[**i_read].full_read_overlaps(left, right))
-            .map(|index| &self.reads[*index])
+            .copied()
     }
 
     pub fn from_aligned_reads(
@@ -143,11 +601,21 @@ impl Alignment {
             reads,
             contig_index,
             coverage: BTreeMap::new(),
+            modification_coverage: BTreeMap::new(),
+            aggregate_modifications: false,
+            hidden_read_counts: HiddenReadCounts::default(),
+            overflow: false,
             data_complete_left_bound: data_complete_bound.0,
             data_complete_right_bound: data_complete_bound.1,
             ys: ys.clone(),
             show_read: show_reads,
+            downsampled: Vec::new(),
+            downsample_depth: None,
             ys_index: Vec::new(),
+            color: AlignmentColor::Default,
+            complement_bases: false,
+            min_mismatch_quality: 0,
+            group: AlignmentGroup::Default,
         };
         alignment
             .build_y_index()?
@@ -160,10 +628,10 @@ impl Alignment {
         let mut ys_index = vec![Vec::new(); *self.ys.iter().max().unwrap_or(&0) + 1];
         self.ys
             .iter()
-            .zip(self.show_read.iter())
+            .zip(self.displayed_reads())
             .enumerate()
-            .for_each(|(i, (y, show_read))| {
-                if *show_read {
+            .for_each(|(i, (y, displayed))| {
+                if displayed {
                     ys_index[*y].push(i)
                 }
             });
@@ -181,6 +649,18 @@ impl Alignment {
         }
     }
 
+    /// Draw mismatched bases with a quality score below min_mismatch_quality as matches. Rendering contexts are
+    /// recalculated when the threshold changes.
+    pub fn set_min_mismatch_quality(&mut self, min_mismatch_quality: u8) {
+        if self.min_mismatch_quality == min_mismatch_quality {
+            return;
+        }
+        self.min_mismatch_quality = min_mismatch_quality;
+        self.rendering_contexts.clear();
+        self.read_rendering_context_indexes
+            .fill(RENDERING_CONTEXT_NOT_CALCULATED);
+    }
+
     /// Calculate and write rendering context for read_index.
     /// The new context is added to the end of the context vector.
     /// Returns the index of the new contexts.
@@ -195,11 +675,10 @@ impl Alignment {
         calculate_rendering_contexts(
             &mut contexts,
             read.start,
-            &read.record.flags(),
+            &read.record,
             read.record.cigar().as_ref(),
-            read.record.sequence(),
-            read.record.data(),
             reference_sequence,
+            self.min_mismatch_quality,
         )?;
 
         self.rendering_contexts.push(contexts);
@@ -252,6 +731,50 @@ impl Alignment {
 
         self.coverage = coverage_hashmap.into_iter().collect();
 
+        self.build_modification_coverage()
+    }
+
+    /// Aggregate base modifications of the shown reads for the modification pileup, or not. The modification coverage
+    /// is rebuilt when the setting changes.
+    pub fn set_aggregate_modifications(&mut self, aggregate: bool) -> Result<(), TGVError> {
+        if self.aggregate_modifications == aggregate {
+            return Ok(());
+        }
+        self.aggregate_modifications = aggregate;
+        self.build_modification_coverage()?;
+        Ok(())
+    }
+
+    fn build_modification_coverage(&mut self) -> Result<&mut Self, TGVError> {
+        self.modification_coverage = BTreeMap::new();
+        if !self.aggregate_modifications {
+            return Ok(self);
+        }
+
+        for (read, show_read) in self.reads.iter().zip(self.show_read.iter()) {
+            if !*show_read {
+                continue;
+            }
+
+            // Use the most likely modification per position in a read (same as the inline display).
+            let mut best_probabilities: HashMap<u64, u8> = HashMap::new();
+            for (position, _, probability) in read.base_modifications()? {
+                best_probabilities
+                    .entry(position)
+                    .and_modify(|best_probability| {
+                        *best_probability = u8::max(*best_probability, probability)
+                    })
+                    .or_insert(probability);
+            }
+
+            for (position, probability) in best_probabilities {
+                self.modification_coverage
+                    .entry(position)
+                    .or_default()
+                    .update(probability);
+            }
+        }
+
         Ok(self)
     }
 
@@ -263,8 +786,13 @@ impl Alignment {
         for (i, read) in self.reads.iter().enumerate() {
             self.show_read[i] = read.passes_filter(&filter)
         }
+        self.downsampled = match self.downsample_depth {
+            Some(depth) => downsample_reads(&self.reads, &self.show_read, depth),
+            None => Vec::new(),
+        };
 
-        self.ys = stack_tracks_for_reads(&self.reads, &self.show_read);
+        self.ys =
+            self.stack_in_groups(|show_reads| stack_tracks_for_reads(&self.reads, show_reads));
         self.build_y_index()?.build_coverage(reference_sequence)?;
 
         Ok(())
@@ -272,6 +800,7 @@ impl Alignment {
 
     pub fn sort(&mut self, option: AlignmentSort) -> Result<(), TGVError> {
         match option {
+            AlignmentSort::Default => self.sort_by_start(),
             AlignmentSort::BaseAt(position) => self.sort_by_base_at(position),
             option => Err(TGVError::ValueError(format!(
                 "Alignment sorting is not implemented yet for option {option}"
@@ -279,29 +808,205 @@ impl Alignment {
         }
     }
 
+    /// Restore the default stacking by read start.
+    fn sort_by_start(&mut self) -> Result<(), TGVError> {
+        self.ys =
+            self.stack_in_groups(|show_reads| stack_tracks_for_reads(&self.reads, show_reads));
+        self.build_y_index()?;
+
+        Ok(())
+    }
+
     fn sort_by_base_at(&mut self, position: u64) -> Result<(), TGVError> {
         self.ensure_position_has_complete_data(position)?;
 
-        let items = self
-            .reads
-            .iter()
-            .zip(self.show_read.iter())
-            .map(|(read, show_read)| SortableStackItem {
-                show: *show_read,
-                stacking_start: read.stacking_start(),
-                stacking_end: read.stacking_end(),
-                sort_key: read_base_sort_key_at(read, position),
-            })
-            .collect::<Vec<_>>();
-
-        self.ys = stack_tracks_by_sort_key(&items, 3);
+        self.ys = self.stack_in_groups(|show_reads| {
+            let items = self
+                .reads
+                .iter()
+                .zip(show_reads.iter())
+                .map(|(read, show_read)| SortableStackItem {
+                    show: *show_read,
+                    stacking_start: read.stacking_start(),
+                    stacking_end: read.stacking_end(),
+                    sort_key: read_base_sort_key_at(read, position),
+                })
+                .collect::<Vec<_>>();
+
+            stack_tracks_by_sort_key(&items, 3)
+        });
         self.build_y_index()?;
 
         Ok(())
     }
+
+    /// Whether each read is displayed: shown by filters and not downsampled.
+    pub fn displayed_reads(&self) -> Vec<bool> {
+        self.show_read
+            .iter()
+            .enumerate()
+            .map(|(i, show_read)| *show_read && !self.downsampled.get(i).copied().unwrap_or(false))
+            .collect()
+    }
+
+    /// Number of shown reads left out of the display by downsampling.
+    pub fn downsampled_count(&self) -> usize {
+        self.downsampled
+            .iter()
+            .filter(|downsampled| **downsampled)
+            .count()
+    }
+
+    /// Stack each read group in its own block of rows. Blocks are separated by a blank row. With `:group hp`, the row
+    /// before each block is its label row instead, see group_labels.
+    /// stack: rows of displayed reads, given which reads are displayed.
+    fn stack_in_groups(&self, stack: impl Fn(&[bool]) -> Vec<usize>) -> Vec<usize> {
+        let displayed_reads = self.displayed_reads();
+        let (read_groups, n_groups) = match self.group {
+            AlignmentGroup::Default => return stack(&displayed_reads),
+            AlignmentGroup::Orientation => {
+                let read_groups = self
+                    .reads
+                    .iter()
+                    .map(|read| {
+                        let orientation = PairOrientation::of_read(read);
+                        PairOrientation::ALL
+                            .iter()
+                            .position(|group| *group == orientation)
+                            .unwrap_or_default()
+                    })
+                    .collect::<Vec<_>>();
+                (read_groups, PairOrientation::ALL.len())
+            }
+            AlignmentGroup::Haplotype => {
+                let haplotypes = self
+                    .reads
+                    .iter()
+                    .map(Haplotype::of_read)
+                    .collect::<Vec<_>>();
+                let groups = haplotypes.iter().sorted().dedup().collect::<Vec<_>>();
+                let read_groups = haplotypes
+                    .iter()
+                    .map(|haplotype| groups.binary_search(&haplotype).unwrap_or_default())
+                    .collect::<Vec<_>>();
+                (read_groups, groups.len())
+            }
+        };
+
+        let mut ys = vec![0; self.reads.len()];
+        let mut y_offset = (self.group == AlignmentGroup::Haplotype) as usize;
+        for group in 0..n_groups {
+            let show_reads = displayed_reads
+                .iter()
+                .zip(read_groups.iter())
+                .map(|(show_read, read_group)| *show_read && *read_group == group)
+                .collect::<Vec<_>>();
+            if !show_reads.contains(&true) {
+                continue;
+            }
+
+            let group_ys = stack(&show_reads);
+            let mut depth = 0;
+            for (i, show_read) in show_reads.iter().enumerate() {
+                if *show_read {
+                    ys[i] = y_offset + group_ys[i];
+                    depth = depth.max(group_ys[i] + 1);
+                }
+            }
+            y_offset += depth + 1;
+        }
+
+        ys
+    }
+
+    /// Label rows of `:group hp`: the row before the block of each haplotype with displayed reads, in display order.
+    /// Empty with other groupings.
+    pub fn group_labels(&self) -> Vec<(usize, Haplotype)> {
+        if self.group != AlignmentGroup::Haplotype {
+            return Vec::new();
+        }
+        let mut first_rows = BTreeMap::new();
+        for (i, displayed) in self.displayed_reads().iter().enumerate() {
+            if !*displayed || self.ys[i] == 0 {
+                continue;
+            }
+            let first_row = first_rows
+                .entry(Haplotype::of_read(&self.reads[i]))
+                .or_insert(self.ys[i]);
+            *first_row = (*first_row).min(self.ys[i]);
+        }
+        first_rows
+            .into_iter()
+            .map(|(haplotype, first_row)| (first_row - 1, haplotype))
+            .collect()
+    }
+
+    /// Displayed reads of each haplotype covering the position, as in group_labels. Coverage split by haplotype.
+    pub fn haplotype_depths_at(&self, position: u64) -> HashMap<Haplotype, usize> {
+        let mut depths = HashMap::new();
+        for (read, displayed) in self.reads.iter().zip(self.displayed_reads()) {
+            if displayed && read.start <= position && position <= read.end {
+                *depths.entry(Haplotype::of_read(read)).or_insert(0) += 1;
+            }
+        }
+        depths
+    }
 }
 
-fn stack_tracks_for_reads(reads: &Vec<AlignedRead>, show_reads: &Vec<bool>) -> Vec<usize> {
+/// Choose shown reads to leave out of the display so that at most depth reads cover each column.
+/// Reads are visited in a random order and displayed if every column they cover has room, so each column keeps a
+/// random subset of its reads. Deterministic given DOWNSAMPLE_SEED.
+/// Returns whether each read is downsampled.
+fn downsample_reads(reads: &[AlignedRead], show_reads: &[bool], depth: usize) -> Vec<bool> {
+    let mut downsampled = vec![false; reads.len()];
+    let mut read_indexes = (0..reads.len())
+        .filter(|i| show_reads[*i] && reads[*i].start <= reads[*i].end)
+        .collect::<Vec<_>>();
+    let (Some(left), Some(right)) = (
+        read_indexes.iter().map(|i| reads[*i].start).min(),
+        read_indexes.iter().map(|i| reads[*i].end).max(),
+    ) else {
+        return downsampled;
+    };
+
+    // Fisher-Yates shuffle
+    let mut rng = SplitMix64(Alignment::DOWNSAMPLE_SEED);
+    for i in (1..read_indexes.len()).rev() {
+        read_indexes.swap(i, (rng.next() % (i as u64 + 1)) as usize);
+    }
+
+    let mut displayed_depths = vec![0; (right - left + 1) as usize];
+    for i in read_indexes {
+        let columns = (reads[i].start - left) as usize..=(reads[i].end - left) as usize;
+        if displayed_depths[columns.clone()]
+            .iter()
+            .all(|displayed_depth| *displayed_depth < depth)
+        {
+            displayed_depths[columns]
+                .iter_mut()
+                .for_each(|displayed_depth| *displayed_depth += 1);
+        } else {
+            downsampled[i] = true;
+        }
+    }
+
+    downsampled
+}
+
+/// Small deterministic random number generator. See: https://prng.di.unimi.it/splitmix64.c
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+fn stack_tracks_for_reads(reads: &[AlignedRead], show_reads: &[bool]) -> Vec<usize> {
     let mut track_left_bounds: Vec<u64> = Vec::new();
     let mut track_right_bounds: Vec<u64> = Vec::new();
 
@@ -413,16 +1118,19 @@ pub(super) fn find_track(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::message::IsolatedAllele;
     use noodles::sam::{
         self,
         alignment::{
             record::{
                 Flags,
                 cigar::{Op, op::Kind},
+                data::field::Tag,
             },
-            record_buf::Cigar,
+            record_buf::{Cigar, data::Data, data::field::Value},
         },
     };
+    use rstest::rstest;
     use std::collections::BTreeMap;
 
     fn read(
@@ -459,14 +1167,510 @@ mod tests {
             ys,
             ys_index: Vec::new(),
             coverage: BTreeMap::new(),
+            modification_coverage: BTreeMap::new(),
+            aggregate_modifications: false,
+            hidden_read_counts: HiddenReadCounts::default(),
+            overflow: false,
             data_complete_left_bound: data_complete_bound.0,
             data_complete_right_bound: data_complete_bound.1,
             show_read,
+            downsampled: Vec::new(),
+            downsample_depth: None,
+            color: AlignmentColor::Default,
+            complement_bases: false,
+            min_mismatch_quality: 0,
+            group: AlignmentGroup::Default,
         };
         alignment.build_y_index().unwrap();
         alignment
     }
 
+    #[rstest]
+    #[case(1, 0, Some(0))]
+    #[case(10, 0, Some(0))]
+    #[case(12, 0, None)]
+    #[case(22, 0, Some(2))]
+    #[case(7, 1, Some(1))]
+    #[case(3, 1, None)]
+    #[case(3, 2, None)]
+    fn test_read_index_overlapping(
+        #[case] position: u64,
+        #[case] y: usize,
+        #[case] expected: Option<usize>,
+    ) {
+        let alignment = alignment_with_reads(
+            vec![
+                read("r0", 1, [(Kind::Match, 10)], b"AAAAAAAAAA"),
+                read("r1", 5, [(Kind::Match, 10)], b"CCCCCCCCCC"),
+                read("r2", 20, [(Kind::Match, 5)], b"GGGGG"),
+            ],
+            (1, 100),
+        );
+        assert_eq!(
+            alignment.read_index_overlapping(position, position, y),
+            expected
+        );
+    }
+
+    fn read_with_base_modifications(
+        name: &str,
+        start: u64,
+        sequence: &[u8],
+        ml: Vec<u8>,
+    ) -> AlignedRead {
+        let mut data = Data::default();
+        data.insert(Tag::BASE_MODIFICATIONS, Value::from("C+m,0,0;"));
+        data.insert(Tag::BASE_MODIFICATION_PROBABILITIES, Value::from(ml));
+
+        let record = sam::alignment::RecordBuf::builder()
+            .set_name(name)
+            .set_flags(Flags::default())
+            .set_alignment_start(noodles::core::Position::try_from(start as usize).unwrap())
+            .set_cigar([Op::new(Kind::Match, sequence.len())].into_iter().collect())
+            .set_sequence(sam::alignment::record_buf::Sequence::from(sequence))
+            .set_data(data)
+            .build();
+
+        AlignedRead::try_from(record).unwrap()
+    }
+
+    #[test]
+    fn hidden_read_counts_tally_hidden_categories_except_the_shown_ones() {
+        let flags = [
+            Flags::default(),
+            Flags::UNMAPPED,
+            Flags::SECONDARY,
+            Flags::SECONDARY | Flags::DUPLICATE,
+            Flags::SUPPLEMENTARY,
+            Flags::DUPLICATE,
+        ];
+
+        let mut counts = HiddenReadCounts::default();
+        let hidden = flags.map(|flags| counts.hide(flags, &BTreeSet::new()));
+        assert_eq!(hidden, [false, true, true, true, true, true]);
+        assert_eq!(
+            counts,
+            HiddenReadCounts {
+                unmapped: 1,
+                secondary: 2,
+                supplementary: 1,
+                duplicate: 1,
+            }
+        );
+
+        let mut counts = HiddenReadCounts::default();
+        let hidden = flags.map(|flags| {
+            counts.hide(
+                flags,
+                &BTreeSet::from([HiddenReadCategory::Secondary, HiddenReadCategory::Duplicate]),
+            )
+        });
+        assert_eq!(hidden, [false, true, false, false, true, false]);
+        assert_eq!(counts.total(), 2);
+
+        let mut counts = HiddenReadCounts::default();
+        let hidden = flags.map(|flags| counts.hide(flags, &HiddenReadCategory::all()));
+        assert_eq!(hidden, [false; 6]);
+        assert_eq!(counts.total(), 0);
+    }
+
+    #[rstest]
+    #[case(99, true, PairOrientation::F1R2)]
+    #[case(147, false, PairOrientation::F1R2)]
+    #[case(163, true, PairOrientation::F2R1)]
+    #[case(83, false, PairOrientation::F2R1)]
+    #[case(99, false, PairOrientation::R2F1)]
+    #[case(147, true, PairOrientation::R2F1)]
+    #[case(65, true, PairOrientation::F1F2)]
+    #[case(129, true, PairOrientation::F2F1)]
+    #[case(113, true, PairOrientation::R1R2)]
+    #[case(177, true, PairOrientation::R2R1)]
+    #[case(73, true, PairOrientation::Other)] // mate unmapped
+    #[case(16, true, PairOrientation::Other)] // unpaired
+    #[case(193, true, PairOrientation::Other)] // both first and last segment
+    fn pair_orientation_of_flags(
+        #[case] flags: u16,
+        #[case] is_leftmost: bool,
+        #[case] expected: PairOrientation,
+    ) {
+        assert_eq!(
+            PairOrientation::of(Flags::from(flags), is_leftmost),
+            expected
+        );
+    }
+
+    #[test]
+    fn orientation_groups_are_stacked_in_blocks_separated_by_a_blank_row() {
+        let paired_read = |name: &str, start: u64, flags: u16, mate_start: usize| {
+            let record = sam::alignment::RecordBuf::builder()
+                .set_name(name)
+                .set_flags(Flags::from(flags))
+                .set_reference_sequence_id(0)
+                .set_alignment_start(noodles::core::Position::try_from(start as usize).unwrap())
+                .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+                .set_sequence(sam::alignment::record_buf::Sequence::from(b"ACGT"))
+                .set_mate_reference_sequence_id(0)
+                .set_mate_alignment_start(noodles::core::Position::try_from(mate_start).unwrap())
+                .build();
+            AlignedRead::try_from(record).unwrap()
+        };
+
+        let mut alignment = alignment_with_reads(
+            vec![
+                paired_read("f1r2", 10, 99, 100),
+                paired_read("other", 20, 16, 1),
+                paired_read("f2r1", 30, 163, 100),
+                paired_read("f1r2_2", 40, 99, 100),
+            ],
+            (1, 200),
+        );
+        alignment.group = AlignmentGroup::Orientation;
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+
+        // F1R2 reads share row 0, then a blank row, F2R1, a blank row, and other reads.
+        assert_eq!(alignment.ys, vec![0, 4, 2, 0]);
+        assert_eq!(alignment.depth(), 5);
+        assert!(alignment.ys_index[1].is_empty());
+    }
+
+    #[rstest]
+    #[case(IsolatedAllele::Alternate, vec![false, true, false, true, false])]
+    #[case(IsolatedAllele::Reference, vec![true, false, true, false, false])]
+    fn isolated_alleles_keep_the_reads_carrying_them(
+        #[case] isolated_allele: IsolatedAllele,
+        #[case] expected: Vec<bool>,
+    ) {
+        // A het C>T SNV at 12: two reference reads, two alternate reads, and a read ending before it.
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("ref_1", 10, [(Kind::Match, 4)], b"AACG"),
+                read("alt_1", 10, [(Kind::Match, 4)], b"AATG"),
+                read("ref_2", 11, [(Kind::Match, 4)], b"ACGG"),
+                read("alt_2", 12, [(Kind::Match, 4)], b"TGGA"),
+                read("before", 5, [(Kind::Match, 4)], b"AAAA"),
+            ],
+            (1, 200),
+        );
+        let allele = VariantAllele {
+            contig_index: 0,
+            position: 12,
+            reference: b"C".to_vec(),
+            alternate: b"T".to_vec(),
+        };
+
+        alignment
+            .filter(
+                AlignmentFilter::Allele(isolated_allele, allele),
+                &Sequence::default(),
+            )
+            .unwrap();
+
+        assert_eq!(alignment.show_read, expected);
+    }
+
+    #[test]
+    fn haplotype_groups_are_stacked_under_label_rows() {
+        let phased_read = |name: &str, start: u64, hp: Option<Value>| {
+            let mut data = Data::default();
+            if let Some(hp) = hp {
+                data.insert(Tag::new(b'H', b'P'), hp);
+            }
+            let record = sam::alignment::RecordBuf::builder()
+                .set_name(name)
+                .set_alignment_start(noodles::core::Position::try_from(start as usize).unwrap())
+                .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+                .set_sequence(sam::alignment::record_buf::Sequence::from(b"ACGT"))
+                .set_data(data)
+                .build();
+            AlignedRead::try_from(record).unwrap()
+        };
+
+        let mut alignment = alignment_with_reads(
+            vec![
+                phased_read("unphased", 10, None),
+                phased_read("hp2", 10, Some(Value::from(2u8))),
+                phased_read("hp1", 10, Some(Value::from(1i32))),
+                phased_read("hp1_overlapping", 12, Some(Value::from(1u8))),
+                phased_read("hp1_string", 20, Some(Value::from("1"))),
+            ],
+            (1, 200),
+        );
+        alignment.group = AlignmentGroup::Haplotype;
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+
+        // A label row, HP=1 in two rows, a label row, HP=2, a label row, and unphased reads.
+        assert_eq!(alignment.ys, vec![6, 4, 1, 2, 1]);
+        assert_eq!(
+            alignment.group_labels(),
+            vec![
+                (0, Haplotype::Phased("1".to_string())),
+                (3, Haplotype::Phased("2".to_string())),
+                (5, Haplotype::Unphased),
+            ]
+        );
+        assert_eq!(
+            alignment.haplotype_depths_at(12),
+            HashMap::from([
+                (Haplotype::Phased("1".to_string()), 2),
+                (Haplotype::Phased("2".to_string()), 1),
+                (Haplotype::Unphased, 1),
+            ])
+        );
+
+        alignment.group = AlignmentGroup::Default;
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+        assert_eq!(alignment.group_labels(), Vec::new());
+    }
+
+    #[test]
+    fn haplotypes_sort_by_number_then_unphased() {
+        let haplotypes = ["unphased", "10", "2", "1", "b"];
+        let sorted = haplotypes
+            .into_iter()
+            .map(|haplotype| match haplotype {
+                "unphased" => Haplotype::Unphased,
+                value => Haplotype::Phased(value.to_string()),
+            })
+            .sorted()
+            .map(|haplotype| haplotype.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(sorted, vec!["HP=1", "HP=2", "HP=10", "HP=b", "Unphased"]);
+    }
+
+    #[rstest]
+    // a: 10-13, b: 12-15, c: 20-21
+    #[case(1, 100, Some(12))]
+    #[case(14, 100, Some(14))]
+    #[case(16, 100, Some(20))]
+    #[case(16, 19, None)]
+    fn test_highest_coverage_position(
+        #[case] start: u64,
+        #[case] end: u64,
+        #[case] expected: Option<u64>,
+    ) {
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("a", 10, [(Kind::Match, 4)], b"AAAA"),
+                read("b", 12, [(Kind::Match, 4)], b"AAAA"),
+                read("c", 20, [(Kind::Match, 2)], b"AA"),
+            ],
+            (1, 100),
+        );
+        alignment.build_coverage(&Sequence::default()).unwrap();
+        assert_eq!(alignment.highest_coverage_position(start, end), expected);
+    }
+
+    #[test]
+    fn build_coverage_bins_base_modification_probabilities_of_visible_reads() {
+        let mut alignment = alignment_with_reads(
+            vec![
+                read_with_base_modifications("a", 10, b"CC", vec![255, 100]),
+                read_with_base_modifications("b", 10, b"CC", vec![20, 200]),
+                read_with_base_modifications("hidden", 10, b"CC", vec![255, 255]),
+                read("unmodified", 10, [(Kind::Match, 2)], b"CC"),
+            ],
+            (1, 100),
+        );
+        alignment.show_read[2] = false;
+
+        alignment.build_coverage(&Sequence::default()).unwrap();
+        assert_eq!(alignment.modification_coverage_at(10), None);
+
+        alignment.set_aggregate_modifications(true).unwrap();
+
+        assert_eq!(
+            alignment.modification_coverage_at(10),
+            Some(&ModificationCoverage {
+                high: 1,
+                medium: 0,
+                low: 1
+            })
+        );
+        assert_eq!(
+            alignment.modification_coverage_at(11),
+            Some(&ModificationCoverage {
+                high: 1,
+                medium: 1,
+                low: 0
+            })
+        );
+        assert_eq!(alignment.modification_coverage_at(12), None);
+    }
+
+    #[rstest]
+    #[case(
+        1,
+        "chr1\t8\t9\t0\nchr1\t9\t10\t3\nchr1\t10\t11\t3\nchr1\t11\t12\t2\nchr1\t12\t13\t1\nchr1\t13\t14\t0\n"
+    )]
+    #[case(2, "chr1\t8\t10\t1.50\nchr1\t10\t12\t2.50\nchr1\t12\t14\t0.50\n")]
+    #[case(4, "chr1\t8\t12\t2\nchr1\t12\t14\t0.50\n")]
+    fn coverage_bedgraph_bins_the_pileup(#[case] bin_size: u64, #[case] expected: &str) {
+        // Depth 3, 3, 2, 1 at 10-13.
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("a", 10, [(Kind::Match, 4)], b"ACGT"),
+                read("b", 10, [(Kind::Match, 3)], b"ACG"),
+                read("c", 10, [(Kind::Match, 2)], b"AC"),
+                read("hidden", 10, [(Kind::Match, 4)], b"ACGT"),
+            ],
+            (1, 100),
+        );
+        alignment.show_read[3] = false;
+        alignment.build_coverage(&Sequence::default()).unwrap();
+
+        assert_eq!(
+            alignment.coverage_bedgraph("chr1", 9, 14, bin_size),
+            expected
+        );
+    }
+
+    #[test]
+    fn build_coverage_counts_bases_of_a_het_position() {
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("ref1", 10, [(Kind::Match, 4)], b"ACGT"),
+                read("ref2", 10, [(Kind::Match, 4)], b"ACGT"),
+                read("alt1", 10, [(Kind::Match, 4)], b"AGGT"),
+                read("alt2", 10, [(Kind::Match, 4)], b"AGGT"),
+            ],
+            (1, 100),
+        );
+        alignment
+            .build_coverage(&Sequence {
+                start: 10,
+                sequence: b"ACGT".to_vec(),
+                contig_index: 0,
+            })
+            .unwrap();
+
+        let het = alignment.coverage_at(11);
+        assert_eq!((het.C, het.G, het.total), (2, 2, 4));
+        assert_eq!(het.minor_allele_fraction(), Some(0.5));
+        assert!(het.is_mixed(BaseCoverage::DEFAULT_ALLELE_FRACTION_THRESHOLD));
+        assert!(
+            !alignment
+                .coverage_at(10)
+                .is_mixed(BaseCoverage::DEFAULT_ALLELE_FRACTION_THRESHOLD)
+        );
+    }
+
+    #[test]
+    fn build_coverage_counts_deletions_apart_from_depth() {
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("a", 10, [(Kind::Match, 3)], b"ACG"),
+                read(
+                    "del",
+                    10,
+                    [(Kind::Match, 1), (Kind::Deletion, 1), (Kind::Match, 1)],
+                    b"AG",
+                ),
+            ],
+            (1, 100),
+        );
+        alignment
+            .build_coverage(&Sequence {
+                start: 10,
+                sequence: b"ACG".to_vec(),
+                contig_index: 0,
+            })
+            .unwrap();
+
+        let coverage = alignment.coverage_at(11);
+        assert_eq!((coverage.total, coverage.deletion), (1, 1));
+        assert_eq!(coverage.minor_allele_fraction(), Some(0.5));
+    }
+
+    #[rstest]
+    #[case(PairOrientation::F1R2, 49, PairClass::TooSmall)]
+    #[case(PairOrientation::F1R2, 50, PairClass::Normal)]
+    #[case(PairOrientation::F2R1, 400, PairClass::Normal)]
+    #[case(PairOrientation::F2R1, 1000, PairClass::Normal)]
+    #[case(PairOrientation::F1R2, 1001, PairClass::TooLarge)]
+    #[case(PairOrientation::R1F2, 400, PairClass::Discordant)]
+    #[case(PairOrientation::F1F2, 5000, PairClass::Discordant)]
+    #[case(PairOrientation::Other, 5000, PairClass::TooLarge)]
+    fn test_pair_class(
+        #[case] orientation: PairOrientation,
+        #[case] insert_size: u64,
+        #[case] expected: PairClass,
+    ) {
+        assert_eq!(
+            PairClass::of(orientation, insert_size, &InsertRange::default()),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case("200,600", Some(InsertRange { min: 200, max: 600 }))]
+    #[case(" 0 , 10 ", Some(InsertRange { min: 0, max: 10 }))]
+    #[case("600,200", None)]
+    #[case("200", None)]
+    #[case("a,b", None)]
+    fn test_parse_insert_range(#[case] input: &str, #[case] expected: Option<InsertRange>) {
+        assert_eq!(input.parse::<InsertRange>().ok(), expected);
+    }
+
+    #[test]
+    fn downsample_caps_displayed_reads_per_column_and_keeps_coverage() {
+        let reads = (0..30)
+            .map(|i| read(&format!("r{i}"), 10 + i % 3, [(Kind::Match, 4)], b"ACGT"))
+            .chain(std::iter::once(read(
+                "apart",
+                50,
+                [(Kind::Match, 4)],
+                b"ACGT",
+            )))
+            .collect::<Vec<_>>();
+        let mut alignment = alignment_with_reads(reads, (1, 100));
+        alignment.build_coverage(&Sequence::default()).unwrap();
+        alignment.downsample_depth = Some(5);
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+
+        let displayed = alignment.displayed_reads();
+        for position in 10..=15 {
+            let covering = alignment
+                .reads
+                .iter()
+                .zip(displayed.iter())
+                .filter(|(read, displayed)| {
+                    **displayed && read.start <= position && read.end >= position
+                })
+                .count();
+            assert!(covering <= 5, "{covering} reads displayed at {position}");
+        }
+        assert_eq!(alignment.coverage_at(12).total, 30);
+        assert_eq!(alignment.downsampled_count(), 30 - 5);
+        // A read away from the dense column is always displayed.
+        assert!(displayed[30]);
+        assert_eq!(
+            alignment.ys_index.iter().map(Vec::len).sum::<usize>(),
+            displayed.iter().filter(|displayed| **displayed).count()
+        );
+
+        // The same reads are kept on every run.
+        let previous = alignment.downsampled.clone();
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+        assert_eq!(alignment.downsampled, previous);
+
+        alignment.downsample_depth = None;
+        alignment
+            .filter(AlignmentFilter::Default, &Sequence::default())
+            .unwrap();
+        assert_eq!(alignment.downsampled_count(), 0);
+        assert_eq!(alignment.coverage_at(12).total, 30);
+    }
+
     #[test]
     fn sort_by_base_orders_visible_reads_by_base_event_kind() {
         let mut alignment = alignment_with_reads(
@@ -486,14 +1690,14 @@ mod tests {
 
         alignment.sort(AlignmentSort::BaseAt(12)).unwrap();
 
-        assert_eq!(alignment.ys, vec![3, 1, 6, 0, 4, 5, 2, 0]);
+        assert_eq!(alignment.ys, vec![2, 3, 6, 0, 4, 5, 1, 0]);
         assert_eq!(
             alignment.ys_index,
             vec![
                 vec![3],
-                vec![1],
                 vec![6],
                 vec![0],
+                vec![1],
                 vec![4],
                 vec![5],
                 vec![2]
@@ -501,6 +1705,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn insertions_at_counts_inserted_sequences_of_shown_reads() {
+        let insertion = |name, bases: &[u8]| {
+            let sequence = [b"AAA".as_slice(), bases, b"CCC"].concat();
+            read(
+                name,
+                10,
+                [
+                    (Kind::Match, 3),
+                    (Kind::Insertion, bases.len()),
+                    (Kind::Match, 3),
+                ],
+                &sequence,
+            )
+        };
+        let mut alignment = alignment_with_reads(
+            vec![
+                insertion("t", b"T"),
+                insertion("tt-1", b"TT"),
+                insertion("tt-2", b"TT"),
+                insertion("hidden", b"G"),
+                read("no-insertion", 10, [(Kind::Match, 6)], b"AAACCC"),
+            ],
+            (1, 100),
+        );
+        alignment.show_read[3] = false;
+
+        assert_eq!(
+            alignment.insertions_at(13, &Sequence::default()).unwrap(),
+            HashMap::from([(b"TT".to_vec(), 2), (b"T".to_vec(), 1)])
+        );
+        assert!(
+            alignment
+                .insertions_at(12, &Sequence::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn sort_by_base_is_stable_and_default_restores_start_stacking() {
+        let mut alignment = alignment_with_reads(
+            vec![
+                read("t-1", 10, [(Kind::Match, 5)], b"AAAAT"),
+                read("c", 11, [(Kind::Match, 4)], b"AAAC"),
+                read("t-2", 12, [(Kind::Match, 3)], b"AAT"),
+                read("no-coverage", 20, [(Kind::Match, 5)], b"AAAAA"),
+                read("a", 13, [(Kind::Match, 2)], b"AA"),
+            ],
+            (1, 100),
+        );
+        let default_ys = alignment.ys.clone();
+
+        alignment.sort(AlignmentSort::BaseAt(14)).unwrap();
+
+        // A, C, then T reads in their original order. The read not covering the position packs into the first row.
+        assert_eq!(alignment.ys, vec![2, 1, 3, 0, 0]);
+        assert_eq!(
+            alignment.ys_index,
+            vec![vec![3, 4], vec![1], vec![0], vec![2]]
+        );
+
+        alignment.sort(AlignmentSort::Default).unwrap();
+
+        assert_eq!(alignment.ys, default_ys);
+    }
+
     #[test]
     fn sort_by_base_packs_remaining_reads_into_sorted_rows_when_possible() {
         let mut alignment = alignment_with_reads(
@@ -1,5 +1,6 @@
 use crate::error::TGVError;
 use crate::sequence::Sequence;
+use noodles::bgzf::VirtualPosition;
 use noodles::sam::{
     self,
     alignment::{record::cigar::op::Kind, record_buf::Cigar},
@@ -88,7 +89,21 @@ pub fn calculate_basewise_coverage(
 
             Kind::Insertion => {}
 
-            Kind::Deletion | Kind::Skip => {}
+            Kind::Deletion => {
+                for i in 0..len {
+                    let base_coordinate = reference_pivot + i;
+                    output
+                        .entry(base_coordinate as u64)
+                        .or_insert(BaseCoverage::new(
+                            reference_sequence
+                                .base_at(base_coordinate as u64)
+                                .unwrap_or(b'N'),
+                        ))
+                        .update_deletion()
+                }
+            }
+
+            Kind::Skip => {}
 
             Kind::SequenceMismatch | Kind::SequenceMatch | Kind::Match => {
                 for i in 0..len {
@@ -130,12 +145,17 @@ pub struct BaseCoverage {
     // Softclip count
     pub softclip: usize,
 
+    // Deletion count, excluded from total
+    pub deletion: usize,
+
     // reference_base
     pub reference_base: u8,
 }
 
 impl BaseCoverage {
-    pub const MAX_DISPLAY_ALLELE_FREQUENCY_RECIPROCOL: usize = 100;
+    /// Minor-allele fraction above which the coverage bar is split by base.
+    pub const DEFAULT_ALLELE_FRACTION_THRESHOLD: f64 = 0.2;
+
     pub fn new(reference_base: u8) -> Self {
         Self {
             A: 0,
@@ -145,6 +165,7 @@ impl BaseCoverage {
             N: 0,
             total: 0,
             softclip: 0,
+            deletion: 0,
             reference_base,
         }
     }
@@ -166,29 +187,63 @@ impl BaseCoverage {
         self.softclip += 1
     }
 
+    pub fn update_deletion(&mut self) {
+        self.deletion += 1
+    }
+
     pub fn add(&mut self, other: &BaseCoverage) {
         self.A += other.A;
         self.T += other.T;
         self.C += other.C;
         self.G += other.G;
+        self.N += other.N;
         self.total += other.total;
         self.softclip += other.softclip;
+        self.deletion += other.deletion;
     }
 
+    /// Depth of the most common allele other than the reference base, counting deletions as an allele.
+    /// None if the reference base is unknown.
     pub fn max_alt_depth(&self) -> Option<usize> {
-        match self.reference_base {
-            b'A' | b'a' => Some(usize::max(self.C, self.T)),
-            b'T' | b't' => Some(usize::max(self.A, self.C)),
-            b'C' | b'c' => Some(usize::max(self.A, self.T)),
-            b'G' | b'g' => Some(usize::max(self.C, self.T)),
-            _ => None,
+        let alt_depths = [
+            (b'A', self.A),
+            (b'T', self.T),
+            (b'C', self.C),
+            (b'G', self.G),
+        ];
+        let reference_base = self.reference_base.to_ascii_uppercase();
+        if !alt_depths.iter().any(|(base, _)| *base == reference_base) {
+            return None;
+        }
+
+        alt_depths
+            .into_iter()
+            .filter(|(base, _)| *base != reference_base)
+            .map(|(_, depth)| depth)
+            .chain(std::iter::once(self.deletion))
+            .max()
+    }
+
+    /// Fraction of the most common non-reference allele among aligned bases and deletions.
+    /// None if the reference base is unknown or the position has no coverage.
+    pub fn minor_allele_fraction(&self) -> Option<f64> {
+        let depth = self.total + self.deletion;
+        if depth == 0 {
+            return None;
         }
+        Some(self.max_alt_depth()? as f64 / depth as f64)
+    }
+
+    /// Whether the minor-allele fraction is above threshold.
+    pub fn is_mixed(&self, threshold: f64) -> bool {
+        self.minor_allele_fraction()
+            .is_some_and(|fraction| fraction > threshold)
     }
 
     pub fn describe(&self) -> String {
         format!(
-            "A:{}, T:{}, C:{}, G:{}, N:{}, total:{}",
-            self.A, self.T, self.C, self.G, self.N, self.total
+            "A:{}, T:{}, C:{}, G:{}, N:{}, del:{}, total:{}",
+            self.A, self.T, self.C, self.G, self.N, self.deletion, self.total
         )
     }
 }
@@ -207,5 +262,229 @@ pub static DEFAULT_COVERAGE: BaseCoverage = BaseCoverage {
     N: 0,
     total: 0,
     softclip: 0,
+    deletion: 0,
     reference_base: b'N',
 };
+
+/// Base modification calls at a position across reads, binned by ML probability (0-255).
+/// Each read contributes its most likely modification at the position.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModificationCoverage {
+    /// Probability >= 70%.
+    pub high: usize,
+    /// Probability in [30%, 70%).
+    pub medium: usize,
+    /// Probability < 30%.
+    pub low: usize,
+}
+
+impl ModificationCoverage {
+    pub const HIGH_PROBABILITY: u8 = 179;
+    pub const MEDIUM_PROBABILITY: u8 = 77;
+
+    pub fn update(&mut self, probability: u8) {
+        if probability >= Self::HIGH_PROBABILITY {
+            self.high += 1;
+        } else if probability >= Self::MEDIUM_PROBABILITY {
+            self.medium += 1;
+        } else {
+            self.low += 1;
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.high + self.medium + self.low
+    }
+}
+
+/// Coarse alignment density across a whole contig, estimated from the BAM index (BAI) linear index.
+/// The linear index stores the file offset of the first read in each 16 kb window, so the offset difference between
+/// consecutive windows approximates the bytes of reads starting in a window.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverageOverview {
+    pub contig_index: usize,
+
+    /// Estimated uncompressed bytes of reads per window. Window i covers [i * WINDOW_SIZE + 1, (i + 1) * WINDOW_SIZE].
+    pub densities: Vec<u64>,
+}
+
+impl CoverageOverview {
+    /// BAI linear index window size.
+    pub const WINDOW_SIZE: u64 = 1 << 14;
+
+    /// Approximate BGZF compression ratio, used to convert compressed offsets to uncompressed bytes across blocks.
+    const COMPRESSION_RATIO: u64 = 3;
+
+    /// linear_index: first read offsets per window. end: offset after the last read on the contig, if known.
+    pub fn from_linear_index(
+        contig_index: usize,
+        linear_index: &[VirtualPosition],
+        end: Option<VirtualPosition>,
+    ) -> Self {
+        // Windows without reads may be recorded as 0. Carry the last offset forward so that offsets are monotonic.
+        let mut offsets = Vec::with_capacity(linear_index.len() + 1);
+        for offset in linear_index.iter().chain(end.iter()) {
+            match offsets.last() {
+                Some(last) if offset < last => offsets.push(*last),
+                _ => offsets.push(*offset),
+            }
+        }
+
+        let mut densities = vec![0; linear_index.len()];
+        for (i, window) in offsets.windows(2).enumerate() {
+            let (start, end) = (window[0], window[1]);
+            densities[i] = if start.compressed() == end.compressed() {
+                (end.uncompressed() - start.uncompressed()) as u64
+            } else {
+                (end.compressed() - start.compressed()) * Self::COMPRESSION_RATIO
+            };
+        }
+
+        Self {
+            contig_index,
+            densities,
+        }
+    }
+
+    /// Windows at local maxima of the density, highest first. See [coverage_peaks].
+    pub fn peaks(&self) -> Vec<usize> {
+        coverage_peaks(&self.densities)
+    }
+
+    /// Bases of a window. 1-based, inclusive.
+    pub fn window_range(window: usize) -> (u64, u64) {
+        let start = window as u64 * Self::WINDOW_SIZE + 1;
+        (start, start + Self::WINDOW_SIZE - 1)
+    }
+
+    /// Sum of densities of windows overlapping [start, end]. 1-based, inclusive.
+    pub fn density_in(&self, start: u64, end: u64) -> u64 {
+        let first_window = (start.saturating_sub(1) / Self::WINDOW_SIZE) as usize;
+        let last_window = (end.saturating_sub(1) / Self::WINDOW_SIZE) as usize;
+        self.densities
+            .iter()
+            .skip(first_window)
+            .take((last_window + 1).saturating_sub(first_window))
+            .sum()
+    }
+}
+
+/// Indices of local maxima of a binned coverage profile, highest first and left to right on ties. A flat top counts
+/// once, at its middle. Empty bins are never peaks.
+pub fn coverage_peaks(values: &[u64]) -> Vec<usize> {
+    let mut peaks = Vec::new();
+    let mut start = 0;
+    while start < values.len() {
+        let mut end = start + 1;
+        while end < values.len() && values[end] == values[start] {
+            end += 1;
+        }
+        let value = values[start];
+        let above_left = start == 0 || values[start - 1] < value;
+        let above_right = end == values.len() || values[end] < value;
+        if value > 0 && above_left && above_right {
+            peaks.push((start + end - 1) / 2);
+        }
+        start = end;
+    }
+    peaks.sort_by_key(|&i| (std::cmp::Reverse(values[i]), i));
+    peaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn offset(compressed: u64, uncompressed: u16) -> VirtualPosition {
+        VirtualPosition::new(compressed, uncompressed).unwrap()
+    }
+
+    fn coverage(reference_base: u8, bases: &[u8], deletion: usize) -> BaseCoverage {
+        let mut coverage = BaseCoverage::new(reference_base);
+        bases.iter().for_each(|base| coverage.update(*base));
+        (0..deletion).for_each(|_| coverage.update_deletion());
+        coverage
+    }
+
+    #[rstest]
+    #[case(&[1, 5, 2, 9, 3, 7, 0], vec![3, 5, 1])]
+    // Edges can be peaks.
+    #[case(&[8, 2, 4], vec![0, 2])]
+    // A flat top counts once, at its middle.
+    #[case(&[1, 6, 6, 6, 1, 6], vec![2, 5])]
+    // Shoulders are not peaks.
+    #[case(&[1, 4, 4, 9], vec![3])]
+    // Ties are left to right.
+    #[case(&[3, 0, 3, 0, 5], vec![4, 0, 2])]
+    #[case(&[0, 0, 0], vec![])]
+    #[case(&[], vec![])]
+    fn coverage_peaks_are_in_descending_order(
+        #[case] values: &[u64],
+        #[case] expected: Vec<usize>,
+    ) {
+        let peaks = coverage_peaks(values);
+        assert_eq!(peaks, expected);
+        assert!(
+            peaks
+                .windows(2)
+                .all(|pair| values[pair[0]] >= values[pair[1]])
+        );
+    }
+
+    #[rstest]
+    #[case(coverage(b'A', b"AAAAGGGG", 0), Some(4), Some(0.5))]
+    #[case(coverage(b'g', b"GGGGGGGGGA", 0), Some(1), Some(0.1))]
+    #[case(coverage(b'C', b"CCC", 1), Some(1), Some(0.25))]
+    #[case(coverage(b'T', b"", 0), Some(0), None)]
+    #[case(coverage(b'N', b"ACGT", 0), None, None)]
+    fn test_minor_allele_fraction(
+        #[case] coverage: BaseCoverage,
+        #[case] expected_depth: Option<usize>,
+        #[case] expected_fraction: Option<f64>,
+    ) {
+        assert_eq!(coverage.max_alt_depth(), expected_depth);
+        assert_eq!(coverage.minor_allele_fraction(), expected_fraction);
+    }
+
+    #[rstest]
+    #[case(coverage(b'A', b"AAAAGGGG", 0), true)]
+    #[case(coverage(b'A', b"AAAAG", 0), false)]
+    #[case(coverage(b'A', b"AAAGG", 0), true)]
+    #[case(coverage(b'A', b"AAAA", 0), false)]
+    fn test_is_mixed(#[case] coverage: BaseCoverage, #[case] expected: bool) {
+        assert_eq!(
+            coverage.is_mixed(BaseCoverage::DEFAULT_ALLELE_FRACTION_THRESHOLD),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_coverage_overview_from_linear_index() {
+        let overview = CoverageOverview::from_linear_index(
+            0,
+            &[offset(0, 100), offset(0, 400), offset(0, 0), offset(100, 0)],
+            Some(offset(200, 50)),
+        );
+
+        // The empty third window carries the second offset forward.
+        assert_eq!(overview.densities, vec![300, 0, 300, 300]);
+    }
+
+    #[rstest]
+    #[case(1, 1, 1)]
+    #[case(1, CoverageOverview::WINDOW_SIZE, 1)]
+    #[case(1, CoverageOverview::WINDOW_SIZE + 1, 3)]
+    #[case(2 * CoverageOverview::WINDOW_SIZE + 1, 10 * CoverageOverview::WINDOW_SIZE, 4)]
+    fn test_coverage_overview_density_in(
+        #[case] start: u64,
+        #[case] end: u64,
+        #[case] expected: u64,
+    ) {
+        let overview = CoverageOverview {
+            contig_index: 0,
+            densities: vec![1, 2, 4],
+        };
+        assert_eq!(overview.density_in(start, end), expected);
+    }
+}
@@ -0,0 +1,233 @@
+use crate::{
+    alignment::AlignedRead,
+    intervals::{GenomeInterval, Region},
+};
+use noodles::sam::alignment::record::cigar::op::Kind;
+use std::collections::BTreeMap;
+
+/// Bar width of the most common length.
+const MAX_BAR_WIDTH: usize = 30;
+
+/// Counts of insertion and deletion lengths in reads, for `:indels`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndelHistogram {
+    /// Insertion length in bp to the number of insertions.
+    pub insertions: BTreeMap<usize, usize>,
+
+    /// Deletion length in bp to the number of deletions.
+    pub deletions: BTreeMap<usize, usize>,
+}
+
+impl IndelHistogram {
+    /// Tally the insertions and deletions of the read in the region. See [`AlignedRead::indels`].
+    pub fn add_read(&mut self, read: &AlignedRead, region: &Region) {
+        for (kind, length) in read.indels(region.start(), region.end()) {
+            let counts = match kind {
+                Kind::Insertion => &mut self.insertions,
+                _ => &mut self.deletions,
+            };
+            *counts.entry(length).or_default() += 1;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.insertions.is_empty() && self.deletions.is_empty()
+    }
+
+    /// Popup lines: insertion and then deletion lengths, shortest first, each with its count and a bar of the bar
+    /// glyph scaled to the most common length, e.g. ` 3 bp ▅▅▅▅ 12`.
+    pub fn lines(&self, bar: &str) -> Vec<String> {
+        let max_count = self
+            .insertions
+            .values()
+            .chain(self.deletions.values())
+            .max()
+            .copied()
+            .unwrap_or(0);
+        let length_width = self
+            .insertions
+            .keys()
+            .chain(self.deletions.keys())
+            .max()
+            .map_or(1, |length| length.to_string().len());
+
+        let mut lines = Vec::new();
+        for (name, counts) in [
+            ("Insertions", &self.insertions),
+            ("Deletions", &self.deletions),
+        ] {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            if counts.is_empty() {
+                lines.push(format!("{name}: none"));
+                continue;
+            }
+            lines.push(format!("{name}: {}", counts.values().sum::<usize>()));
+            lines.extend(counts.iter().map(|(length, count)| {
+                format!(
+                    "{length:>length_width$} bp {} {count}",
+                    bar.repeat((count * MAX_BAR_WIDTH).div_ceil(max_count))
+                )
+            }));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intervals::Focus;
+    use noodles::sam::{
+        self,
+        alignment::{record::cigar::Op, record_buf::Cigar},
+    };
+
+    fn read(start: u64, cigar_ops: &[(Kind, usize)]) -> AlignedRead {
+        let cigar: Cigar = cigar_ops
+            .iter()
+            .map(|(kind, len)| Op::new(*kind, *len))
+            .collect();
+        let query_length = cigar_ops
+            .iter()
+            .filter(|(kind, _)| kind.consumes_read())
+            .map(|(_, len)| len)
+            .sum::<usize>();
+        let record = sam::alignment::RecordBuf::builder()
+            .set_alignment_start(noodles::core::Position::try_from(start as usize).unwrap())
+            .set_cigar(cigar)
+            .set_sequence(sam::alignment::record_buf::Sequence::from(vec![
+                b'A';
+                query_length
+            ]))
+            .build();
+        AlignedRead::try_from(record).unwrap()
+    }
+
+    /// [100, 200]
+    fn region() -> Region {
+        Region {
+            focus: Focus {
+                contig_index: 0,
+                position: 150,
+            },
+            half_width: 50,
+        }
+    }
+
+    #[test]
+    fn indels_are_tallied_by_length() {
+        let reads = [
+            // 1I anchored at 111, 3D at 121-123, 3D at 134-136
+            read(
+                101,
+                &[
+                    (Kind::Match, 10),
+                    (Kind::Insertion, 1),
+                    (Kind::Match, 10),
+                    (Kind::Deletion, 3),
+                    (Kind::Match, 10),
+                    (Kind::Deletion, 3),
+                    (Kind::Match, 10),
+                ],
+            ),
+            // 1I anchored at 151 after a soft clip, and a 1000-bp intron that is not a deletion
+            read(
+                141,
+                &[
+                    (Kind::SoftClip, 5),
+                    (Kind::Match, 10),
+                    (Kind::Insertion, 1),
+                    (Kind::Match, 10),
+                    (Kind::Skip, 1000),
+                    (Kind::Match, 10),
+                ],
+            ),
+            // 12I anchored at 171, and 2D at 181-182
+            read(
+                161,
+                &[
+                    (Kind::Match, 10),
+                    (Kind::Insertion, 12),
+                    (Kind::Match, 10),
+                    (Kind::Deletion, 2),
+                    (Kind::Match, 10),
+                ],
+            ),
+            // No indels
+            read(101, &[(Kind::Match, 50)]),
+        ];
+
+        let mut histogram = IndelHistogram::default();
+        for read in reads.iter() {
+            histogram.add_read(read, &region());
+        }
+
+        assert_eq!(histogram.insertions, BTreeMap::from([(1, 2), (12, 1)]));
+        assert_eq!(histogram.deletions, BTreeMap::from([(2, 1), (3, 2)]));
+    }
+
+    #[test]
+    fn indels_outside_the_region_are_not_tallied() {
+        let reads = [
+            // 6D at 86-91 ends before the region
+            read(
+                81,
+                &[(Kind::Match, 5), (Kind::Deletion, 6), (Kind::Match, 50)],
+            ),
+            // 5D at 98-102 and 4D at 196-199 overlap the region; 2D at 201-202 does not
+            read(
+                91,
+                &[
+                    (Kind::Match, 7),
+                    (Kind::Deletion, 5),
+                    (Kind::Match, 93),
+                    (Kind::Deletion, 4),
+                    (Kind::Match, 1),
+                    (Kind::Deletion, 2),
+                    (Kind::Match, 10),
+                ],
+            ),
+            // 2I anchored at 101 is in the region; 3I anchored at 201 is not
+            read(
+                91,
+                &[
+                    (Kind::Match, 10),
+                    (Kind::Insertion, 2),
+                    (Kind::Match, 100),
+                    (Kind::Insertion, 3),
+                    (Kind::Match, 10),
+                ],
+            ),
+        ];
+
+        let mut histogram = IndelHistogram::default();
+        for read in reads.iter() {
+            histogram.add_read(read, &region());
+        }
+
+        assert_eq!(histogram.insertions, BTreeMap::from([(2, 1)]));
+        assert_eq!(histogram.deletions, BTreeMap::from([(4, 1), (5, 1)]));
+    }
+
+    #[test]
+    fn lines_are_bars_scaled_to_the_most_common_length() {
+        let histogram = IndelHistogram {
+            insertions: BTreeMap::from([(1, 6), (12, 2)]),
+            deletions: BTreeMap::new(),
+        };
+        assert_eq!(
+            histogram.lines("#"),
+            vec![
+                "Insertions: 8".to_string(),
+                format!(" 1 bp {} 6", "#".repeat(30)),
+                format!("12 bp {} 2", "#".repeat(10)),
+                String::new(),
+                "Deletions: none".to_string(),
+            ]
+        );
+        assert!(!histogram.is_empty());
+        assert!(IndelHistogram::default().is_empty());
+    }
+}
@@ -1,10 +1,19 @@
 mod alignment;
 mod coverage;
+mod indels;
 mod paired_alignment;
 mod read;
 mod repository;
-pub use alignment::Alignment;
-pub use coverage::BaseCoverage;
+mod supplementary;
+pub use alignment::{
+    Alignment, Haplotype, HiddenReadCategory, HiddenReadCounts, InsertRange, PairClass,
+    PairOrientation,
+};
+pub use coverage::{BaseCoverage, CoverageOverview, ModificationCoverage};
+pub use indels::IndelHistogram;
 pub use paired_alignment::PairedAlignment;
 pub use read::{AlignedRead, RenderingContext, RenderingContextKind, RenderingContextModifier};
-pub use repository::{AlignmentRepositoryEnum, is_url};
+pub use repository::{
+    AlignmentRepositoryEnum, ReadSpan, default_bam_index, is_url, path_without_query,
+};
+pub use supplementary::{SplitRead, SupplementaryAlignment, parse_sa_tag};
@@ -1,8 +1,9 @@
 use crate::{
     alignment::{
         alignment::{
-            Alignment, BaseSortKey, RENDERING_CONTEXT_NOT_CALCULATED, SortableStackItem,
-            find_track, read_base_sort_key_at, stack_tracks_by_sort_key,
+            Alignment, BaseSortKey, InsertRange, PairClass, PairOrientation,
+            RENDERING_CONTEXT_NOT_CALCULATED, SortableStackItem, find_track, read_base_sort_key_at,
+            stack_tracks_by_sort_key,
         },
         read::{AlignedRead, ReadPair, RenderingContext, calculate_paired_context},
     },
@@ -36,6 +37,9 @@ pub struct PairedAlignment {
 
     /// Whether to display the pair.
     pub show_pair: Vec<bool>,
+
+    /// Expected insert size range, used to classify pairs.
+    pub insert_range: InsertRange,
 }
 
 impl PairedAlignment {
@@ -57,6 +61,7 @@ impl PairedAlignment {
             pair_rendering_context_index: vec![RENDERING_CONTEXT_NOT_CALCULATED; n_pair],
             ys,
             ys_index: Vec::new(),
+            insert_range: InsertRange::default(),
         };
 
         paired_alignment.ys = stack_tracks_for_pairs(
@@ -91,6 +96,21 @@ impl PairedAlignment {
             .map(|index| &self.read_pairs[*index])
     }
 
+    /// Class of the pair by orientation and insert size. The insert size is the span of the two aligned mates.
+    /// None if the mate is not loaded.
+    pub fn pair_class(&self, reads: &[AlignedRead], pair_index: usize) -> Option<PairClass> {
+        let pair = &self.read_pairs[pair_index];
+        let (read_1, read_2) = (&reads[pair.read_1_index], &reads[pair.read_2_index?]);
+        let insert_size =
+            u64::max(read_1.end, read_2.end) - u64::min(read_1.start, read_2.start) + 1;
+
+        Some(PairClass::of(
+            PairOrientation::of_read(read_1),
+            insert_size,
+            &self.insert_range,
+        ))
+    }
+
     /// If rendering context is calculated for read_index, return the rendering context index in self.rendering_contexts
     /// Return None if not yet calculated.
     pub fn get_pair_rendering_context_index(&self, pair_index: usize) -> Option<u64> {
@@ -100,6 +120,13 @@ impl PairedAlignment {
         }
     }
 
+    /// Drop the calculated pair rendering contexts, to recalculate them from the read contexts.
+    pub fn clear_rendering_contexts(&mut self) {
+        self.rendering_contexts.clear();
+        self.pair_rendering_context_index
+            .fill(RENDERING_CONTEXT_NOT_CALCULATED);
+    }
+
     /// Calculate and write rendering context for read_index.
     /// The new context is added to the end of the context vector.
     /// Returns the index of the new contexts.
@@ -151,6 +178,7 @@ impl PairedAlignment {
 
     pub fn sort(&mut self, alignment: &Alignment, option: AlignmentSort) -> Result<(), TGVError> {
         match option {
+            AlignmentSort::Default => self.sort_by_start(alignment),
             AlignmentSort::BaseAt(position) => self.sort_by_base_at(alignment, position),
             option => Err(TGVError::ValueError(format!(
                 "Paired alignment sorting is not implemented yet for option {option}"
@@ -158,6 +186,12 @@ impl PairedAlignment {
         }
     }
 
+    /// Restore the default stacking by pair start.
+    fn sort_by_start(&mut self, alignment: &Alignment) -> Result<(), TGVError> {
+        self.ys = stack_tracks_for_pairs(&alignment.reads, &self.read_pairs, &self.show_pair);
+        self.build_y_index()
+    }
+
     fn sort_by_base_at(&mut self, alignment: &Alignment, position: u64) -> Result<(), TGVError> {
         alignment.ensure_position_has_complete_data(position)?;
 
@@ -410,13 +444,61 @@ mod tests {
             .sort(&alignment, AlignmentSort::BaseAt(10))
             .unwrap();
 
-        assert_eq!(paired_alignment.ys, vec![1, 0, 2, 3]);
+        assert_eq!(paired_alignment.ys, vec![2, 0, 1, 3]);
         assert_eq!(
             paired_alignment.ys_index,
-            vec![vec![1], vec![0], vec![2], vec![3]]
+            vec![vec![1], vec![2], vec![0], vec![3]]
         );
     }
 
+    #[test]
+    fn pair_class_uses_the_span_of_both_mates() {
+        let alignment = alignment_from_reads(vec![
+            read("small", 10, [(Kind::Match, 5)], b"AAAAA"),
+            read("small", 20, [(Kind::Match, 5)], b"AAAAA"),
+            read("normal", 10, [(Kind::Match, 5)], b"AAAAA"),
+            read("normal", 55, [(Kind::Match, 5)], b"AAAAA"),
+            read("single", 30, [(Kind::Match, 5)], b"AAAAA"),
+        ]);
+        let mut paired_alignment = PairedAlignment::new(&alignment).unwrap();
+        paired_alignment.insert_range = InsertRange { min: 20, max: 60 };
+
+        let classes = (0..paired_alignment.read_pairs.len())
+            .map(|pair_index| {
+                let pair = &paired_alignment.read_pairs[pair_index];
+                (
+                    alignment.reads[pair.read_1_index].start,
+                    paired_alignment.pair_class(&alignment.reads, pair_index),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(classes.contains(&(10, Some(PairClass::TooSmall))));
+        assert!(classes.contains(&(10, Some(PairClass::Normal))));
+        assert!(classes.contains(&(30, None)));
+    }
+
+    #[test]
+    fn paired_sort_default_restores_start_stacking() {
+        let alignment = alignment_from_reads(vec![
+            read("t", 10, [(Kind::Match, 1)], b"T"),
+            read("t", 30, [(Kind::Match, 1)], b"T"),
+            read("a", 10, [(Kind::Match, 1)], b"A"),
+            read("a", 30, [(Kind::Match, 1)], b"A"),
+        ]);
+        let mut paired_alignment = PairedAlignment::new(&alignment).unwrap();
+        let default_ys = paired_alignment.ys.clone();
+
+        paired_alignment
+            .sort(&alignment, AlignmentSort::BaseAt(10))
+            .unwrap();
+        assert_eq!(paired_alignment.ys, vec![1, 0]);
+
+        paired_alignment
+            .sort(&alignment, AlignmentSort::Default)
+            .unwrap();
+        assert_eq!(paired_alignment.ys, default_ys);
+    }
+
     #[test]
     fn paired_sort_visibility_follows_underlying_reads() {
         let mut alignment = alignment_from_reads(vec![
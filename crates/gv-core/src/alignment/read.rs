@@ -1,6 +1,9 @@
+use crate::alignment::supplementary::{SupplementaryAlignment, parse_sa_tag};
 use crate::error::TGVError;
-use crate::message::AlignmentFilter;
+use crate::message::{AlignmentFilter, IsolatedAllele};
 use crate::sequence::Sequence;
+use crate::strand::Strand;
+use crate::variant::VariantAllele;
 // use rust_htslib::bam::{record::Seq, Read, Record};
 //
 use itertools::Itertools;
@@ -23,7 +26,7 @@ use noodles::sam::{
         base_modifications::group::{Group, Modification},
     },
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum RenderingContextModifier {
@@ -34,7 +37,8 @@ pub enum RenderingContextModifier {
     Reverse,
 
     /// The previous cigar is an insertion. Annotate this at the beginning of this segment.
-    Insertion(u64),
+    /// (Position of the segment start, inserted bases)
+    Insertion(u64, Vec<u8>),
 
     /// Mismatch at location with base
     Mismatch(u64, u8),
@@ -109,6 +113,45 @@ pub struct AlignedRead {
 }
 
 impl AlignedRead {
+    /// (Reference position, modification, probability) parsed from the MM and ML tags.
+    pub fn base_modifications(&self) -> Result<Vec<(u64, Modification, u8)>, TGVError> {
+        base_modifications_from_data(
+            self.record.data(),
+            &self.record.flags(),
+            self.record.sequence(),
+            self.record.cigar().as_ref(),
+            self.start,
+        )
+    }
+
+    /// Other alignments of the read from the SA tag. Empty if the read is not split.
+    pub fn supplementary_alignments(&self) -> Result<Vec<SupplementaryAlignment>, TGVError> {
+        match self.record.data().get(&Tag::OTHER_ALIGNMENTS) {
+            Some(Value::String(value)) => parse_sa_tag(&value.to_string()),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Value of an auxiliary tag, e.g. HP or RG. Character, integer, float, string, and hex values are supported;
+    /// None if the tag is missing, is not two characters, or holds an array.
+    pub fn tag_value(&self, tag: &str) -> Option<String> {
+        let &[first, second] = tag.as_bytes() else {
+            return None;
+        };
+        match self.record.data().get(&Tag::new(first, second))? {
+            Value::Character(c) => Some(char::from(*c).to_string()),
+            Value::String(value) | Value::Hex(value) => Some(value.to_string()),
+            Value::Float(value) => Some(value.to_string()),
+            Value::Array(_) => None,
+            value => value.as_int().map(|value| value.to_string()),
+        }
+    }
+
+    /// Reference span from the cigar, excluding softclips.
+    pub fn reference_span(&self) -> u64 {
+        self.end + 1 - self.start
+    }
+
     pub fn stacking_start(&self) -> u64 {
         u64::max(self.start.saturating_sub(self.leading_softclips), 1)
     }
@@ -117,9 +160,8 @@ impl AlignedRead {
         self.end.saturating_add(self.trailing_softclips)
     }
 
-    /// Read details
+    /// Read details on one line, modeled after IGV's read popup. Missing fields are shown as placeholders.
     pub fn describe(&self) -> Result<String, TGVError> {
-        // FIXME: improve display information
         // Example IGV display:
         // Read name = HISEQ1:29:HA2WPADXX:1:1216:5183:9385
         // Read length = 148bp
@@ -145,6 +187,7 @@ impl AlignedRead {
         // AS = 0
         // Hidden tags: MDLocation = chr20:78,249
         // Base = C @ QV 30
+        let flags = self.record.flags();
         let read_name = self
             .record
             .name()
@@ -155,19 +198,98 @@ impl AlignedRead {
             .mapping_quality()
             .map(|quality| quality.get().to_string())
             .unwrap_or_else(|| ".".to_string());
-        let flags = u16::from(self.record.flags());
+        let strand = if flags.is_reverse_complemented() {
+            "-"
+        } else {
+            "+"
+        };
         let cigar = cigar_to_string(self.record.cigar())?;
+        let insert_size = match self.record.template_length() {
+            0 => ".".to_string(),
+            length => length.to_string(),
+        };
+        let tag = |tag: Tag| {
+            self.record
+                .data()
+                .get(&tag)
+                .and_then(|value| value.as_int())
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| ".".to_string())
+        };
 
         Ok(format!(
-            "{}  Flags={}  MAPQ={}  Cigar={}",
-            //String::from_utf8_lossy(&self.record.sequence()[..]),
+            "{}  Flags={}  MAPQ={}  Span={}-{} ({})  Cigar={}  Length={}bp  Clipping={}  Mate={}  Insert={}  NM={}  AS={}",
             read_name,
-            flags,
+            u16::from(flags),
             mapping_quality,
-            cigar
+            self.start,
+            self.end,
+            strand,
+            cigar,
+            self.reference_span(),
+            self.describe_clipping(),
+            self.describe_mate(),
+            insert_size,
+            tag(Tag::EDIT_DISTANCE),
+            tag(Tag::ALIGNMENT_SCORE),
         ))
     }
 
+    /// Clipping at each end, e.g. "left 5H2S, right 3S". "None" if the read is not clipped.
+    fn describe_clipping(&self) -> String {
+        let clips = |ops: &mut dyn Iterator<Item = &Op>| {
+            ops.take_while(|op| matches!(op.kind(), Kind::SoftClip | Kind::HardClip))
+                .map(|op| {
+                    format!(
+                        "{}{}",
+                        op.len(),
+                        if op.kind() == Kind::SoftClip {
+                            "S"
+                        } else {
+                            "H"
+                        }
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let ops = self.record.cigar().as_ref();
+        let left = clips(&mut ops.iter()).concat();
+        let mut right = clips(&mut ops.iter().rev());
+        right.reverse();
+        let right = right.concat();
+
+        [("left", left), ("right", right)]
+            .into_iter()
+            .filter(|(_, clips)| !clips.is_empty())
+            .map(|(side, clips)| format!("{} {}", side, clips))
+            .reduce(|left, right| format!("{}, {}", left, right))
+            .unwrap_or_else(|| "None".to_string())
+    }
+
+    /// Mate start and strand. "." if the read is not paired or the mate position is missing.
+    fn describe_mate(&self) -> String {
+        let flags = self.record.flags();
+        if !flags.is_segmented() {
+            return ".".to_string();
+        }
+        if flags.is_mate_unmapped() {
+            return "unmapped".to_string();
+        }
+        let Some(mate_start) = self.record.mate_alignment_start() else {
+            return ".".to_string();
+        };
+        let strand = if flags.is_mate_reverse_complemented() {
+            "-"
+        } else {
+            "+"
+        };
+        if self.record.mate_reference_sequence_id() == self.record.reference_sequence_id() {
+            format!("{} ({})", mate_start, strand)
+        } else {
+            format!("other contig:{} ({})", mate_start, strand)
+        }
+    }
+
     /// Whether the alignment segment (including softclips) covers a x_coordinate (1-based).
     pub fn full_read_overlaps(&self, left: u64, right: u64) -> bool {
         self.stacking_start() <= right && self.stacking_end() >= left
@@ -325,9 +447,145 @@ impl AlignedRead {
         false
     }
 
+    /// Insertions and deletions in [left, right] as cigar operation kinds and lengths, in read order. Insertions are
+    /// in the range if their anchor (the base after the inserted sequence) is, and deletions if they overlap it.
+    /// Reference skips are introns, not deletions.
+    /// 1-based, inclusive.
+    pub fn indels(&self, left: u64, right: u64) -> Vec<(Kind, usize)> {
+        let mut indels = Vec::new();
+        let mut reference_pivot = self.start;
+
+        for op in self.record.cigar().as_ref() {
+            if reference_pivot > right {
+                break;
+            }
+            let kind = op.kind();
+            let next_reference_pivot = if kind.consumes_reference() {
+                reference_pivot.saturating_add(op.len() as u64)
+            } else {
+                reference_pivot
+            };
+
+            match kind {
+                Kind::Insertion if reference_pivot >= left => indels.push((kind, op.len())),
+                Kind::Deletion if next_reference_pivot > left => indels.push((kind, op.len())),
+                _ => {}
+            }
+            reference_pivot = next_reference_pivot;
+        }
+
+        indels
+    }
+
+    /// Whether a cigar operation of kind and length starts at the reference coordinate. None matches any length.
+    /// Insertions start at the reference base after the inserted sequence.
+    fn has_op_at(&self, kind: Kind, coordinate: u64, length: Option<usize>) -> bool {
+        let mut reference_pivot = self.start;
+
+        for op in self.record.cigar().as_ref() {
+            if op.kind() == kind
+                && reference_pivot == coordinate
+                && length.is_none_or(|length| op.len() == length)
+            {
+                return true;
+            }
+
+            if op.kind().consumes_reference() {
+                reference_pivot = reference_pivot.saturating_add(op.len() as u64);
+            }
+        }
+
+        false
+    }
+
+    /// Whether the read carries the alternate allele.
+    /// SNVs and MNVs match on aligned bases. Indels match on an insertion or deletion of the same length after the
+    /// shared leading bases.
+    pub fn supports(&self, allele: &VariantAllele) -> bool {
+        let (reference, alternate) = (&allele.reference, &allele.alternate);
+
+        if reference.len() == alternate.len() {
+            return alternate.iter().enumerate().all(|(i, base)| {
+                self.base_at(allele.position + i as u64)
+                    .is_some_and(|read_base| read_base.eq_ignore_ascii_case(base))
+            });
+        }
+
+        let shared = reference
+            .iter()
+            .zip(alternate.iter())
+            .take_while(|(reference_base, alternate_base)| {
+                reference_base.eq_ignore_ascii_case(alternate_base)
+            })
+            .count();
+        let coordinate = allele.position + shared as u64;
+
+        if alternate.len() > reference.len() {
+            self.has_op_at(
+                Kind::Insertion,
+                coordinate,
+                Some(alternate.len() - reference.len()),
+            )
+        } else {
+            self.has_op_at(
+                Kind::Deletion,
+                coordinate,
+                Some(reference.len() - alternate.len()),
+            )
+        }
+    }
+
+    /// Whether the read carries the reference allele. SNVs and MNVs match on aligned bases. Indels match reads that
+    /// span the reference bases and the base after them without an insertion or deletion after the shared leading bases.
+    pub fn supports_reference(&self, allele: &VariantAllele) -> bool {
+        let (reference, alternate) = (&allele.reference, &allele.alternate);
+
+        if reference.len() == alternate.len() {
+            return reference.iter().enumerate().all(|(i, base)| {
+                self.base_at(allele.position + i as u64)
+                    .is_some_and(|read_base| read_base.eq_ignore_ascii_case(base))
+            });
+        }
+
+        let shared = reference
+            .iter()
+            .zip(alternate.iter())
+            .take_while(|(reference_base, alternate_base)| {
+                reference_base.eq_ignore_ascii_case(alternate_base)
+            })
+            .count();
+        let coordinate = allele.position + shared as u64;
+
+        self.start <= allele.position
+            && self.end >= allele.position + reference.len() as u64
+            && !self.has_op_at(Kind::Insertion, coordinate, None)
+            && !self.has_op_at(Kind::Deletion, coordinate, None)
+    }
+
+    /// Whether the read passes the filter. Composite filters (And, Or, and Not) are evaluated recursively.
     pub fn passes_filter(&self, filter: &AlignmentFilter) -> bool {
         match filter {
             AlignmentFilter::Default => true,
+            AlignmentFilter::False => false,
+
+            AlignmentFilter::StartsIn(start, end) => {
+                (*start as u64..=*end as u64).contains(&self.start)
+            }
+            AlignmentFilter::EndsIn(start, end) => {
+                (*start as u64..=*end as u64).contains(&self.end)
+            }
+            AlignmentFilter::Overlaps(start, end) => {
+                self.start <= *end as u64 && self.end >= *start as u64
+            }
+
+            AlignmentFilter::Strand(strand) => {
+                let is_reverse = self.record.flags().is_reverse_complemented();
+                match strand {
+                    Strand::Forward => !is_reverse,
+                    Strand::Reverse => is_reverse,
+                }
+            }
+
             AlignmentFilter::Base(position, base) => {
                 if let Some(base_u8) = self.base_at(*position) {
                     *base as u8 == base_u8
@@ -338,12 +596,48 @@ impl AlignedRead {
 
             AlignmentFilter::BaseSoftclip(position) => self.is_softclip_at(*position),
 
+            AlignmentFilter::Allele(IsolatedAllele::Alternate, allele) => self.supports(allele),
+            AlignmentFilter::Allele(IsolatedAllele::Reference, allele) => {
+                self.supports_reference(allele)
+            }
+
             // They should be not be passed here.
             // They should be translated upstream.
             AlignmentFilter::BaseAtCurrentPosition(_)
             | AlignmentFilter::BaseAtCurrentPositionSoftClip => true,
 
-            _ => true, // TODO
+            AlignmentFilter::ReadLengthGE(length) => self.reference_span() >= *length,
+            AlignmentFilter::ReadLengthLE(length) => self.reference_span() <= *length,
+
+            // Reads without MAPQ (255) fail both comparisons.
+            AlignmentFilter::MappingQualityGE(mapping_quality) => self
+                .record
+                .mapping_quality()
+                .is_some_and(|quality| quality.get() as u16 >= *mapping_quality),
+            AlignmentFilter::MappingQualityLE(mapping_quality) => self
+                .record
+                .mapping_quality()
+                .is_some_and(|quality| quality.get() as u16 <= *mapping_quality),
+            AlignmentFilter::MinMapq(mapping_quality) => self
+                .record
+                .mapping_quality()
+                .is_none_or(|quality| quality.get() >= *mapping_quality),
+
+            AlignmentFilter::FlagsAll(flags) => {
+                u16::from(self.record.flags()) as u32 & *flags == *flags
+            }
+            AlignmentFilter::FlagsAny(flags) => u16::from(self.record.flags()) as u32 & *flags != 0,
+            AlignmentFilter::FlagsEqual(flags) => u16::from(self.record.flags()) as u32 == *flags,
+
+            AlignmentFilter::Tag(_, _) => true, // TODO
+
+            AlignmentFilter::Not(filter) => !self.passes_filter(filter),
+            AlignmentFilter::And(filter1, filter2) => {
+                self.passes_filter(filter1) && self.passes_filter(filter2)
+            }
+            AlignmentFilter::Or(filter1, filter2) => {
+                self.passes_filter(filter1) || self.passes_filter(filter2)
+            }
         }
     }
 
@@ -511,15 +805,28 @@ fn get_reference_position_from_seq_position(
 }
 
 /// See: https://samtools.github.io/hts-specs/SAMv1.pdf
+/// Mismatched bases with a quality score below min_mismatch_quality are drawn as matches. Bases without quality
+/// scores are always drawn as mismatches.
 pub fn calculate_rendering_contexts(
     rendering_context: &mut Vec<RenderingContext>,
     reference_start: u64, // 1-based. Alignment start, not softclip start
-    flags: &Flags,
+    record: &RecordBuf,
     cigars: &[Op],
-    seq: &sam::alignment::record_buf::Sequence,
-    data: &Data,
     reference_sequence: &Sequence,
+    min_mismatch_quality: u8,
 ) -> Result<(), TGVError> {
+    let flags = &record.flags();
+    let seq = record.sequence();
+    let data = record.data();
+    // Whether the base at a 0-based query position is drawn as a mismatch if it differs from the reference.
+    let passes_quality = |query_index: usize| {
+        record
+            .quality_scores()
+            .as_ref()
+            .get(query_index)
+            .is_none_or(|quality| *quality >= min_mismatch_quality)
+    };
+
     rendering_context.clear();
     if cigars.is_empty() || seq.is_empty() {
         return Ok(());
@@ -532,6 +839,7 @@ pub fn calculate_rendering_contexts(
 
     let mut cigar_index_with_arrow_annotation = None;
     let is_reverse = flags.is_reverse_complemented();
+    let md_mismatches = mismatch_coordinates_from_md(data, cigars, reference_start);
 
     for (i_op, op) in cigars.iter().enumerate() {
         let kind = op.kind();
@@ -550,7 +858,6 @@ pub fn calculate_rendering_contexts(
         let mut new_contexts = Vec::new();
 
         // let mut new_contexts = Vec::new();
-        let add_insertion: bool = annotate_insertion_in_next_cigar.is_some();
         let l = op.len();
         match kind {
             Kind::SoftClip => {
@@ -598,7 +905,11 @@ pub fn calculate_rendering_contexts(
             Kind::Insertion => {
                 // The next loop catches on this flag and add an insertion modifier.
                 // Insertion is displayed at the next cigar segment.
-                annotate_insertion_in_next_cigar = Some(l);
+                annotate_insertion_in_next_cigar = Some(
+                    (query_pivot..next_query_pivot)
+                        .filter_map(|coordinate| seq.get(coordinate - 1))
+                        .collect_vec(),
+                );
             }
 
             Kind::Deletion | Kind::Skip => {
@@ -620,6 +931,7 @@ pub fn calculate_rendering_contexts(
                     end: next_reference_pivot as u64 - 1,
                     kind: RenderingContextKind::Match,
                     modifiers: (query_pivot..next_query_pivot)
+                        .filter(|coordinate| passes_quality(coordinate - 1))
                         .map(|coordinate| {
                             let reference_coordinate = coordinate - query_pivot + reference_pivot;
 
@@ -642,30 +954,21 @@ pub fn calculate_rendering_contexts(
 
             Kind::Match => {
                 // M
-                // check reference sequence for mismatches
-                // FEAT:
-                // Parse base mismatches from the MD field: https://samtools.github.io/hts-specs/SAMtags.pdf#page=3
-
+                // Mismatches from the MD tag, or from the reference sequence if MD is absent.
                 let modifiers: Vec<RenderingContextModifier> = (0..l)
                     .filter_map(|i| {
-                        let reference_position = reference_pivot + i;
-                        if let Some(reference_base) =
-                            reference_sequence.base_at(reference_position as u64)
-                        // convert to 1-based
-                        {
-                            let query_position = reference_pivot + i;
-                            let query_base = seq.get(query_pivot + i - 1).unwrap();
-                            if !matches_base(query_base, reference_base) {
-                                Some(RenderingContextModifier::Mismatch(
-                                    query_position as u64,
-                                    query_base,
-                                ))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
+                        let reference_position = (reference_pivot + i) as u64;
+                        let query_base = seq.get(query_pivot + i - 1)?;
+                        let is_mismatch = match &md_mismatches {
+                            Some(md_mismatches) => md_mismatches.contains(&reference_position),
+                            None => reference_sequence.base_at(reference_position).is_some_and(
+                                |reference_base| !matches_base(query_base, reference_base),
+                            ),
+                        };
+
+                        (is_mismatch && passes_quality(query_pivot + i - 1)).then_some(
+                            RenderingContextModifier::Mismatch(reference_position, query_base),
+                        )
                     })
                     .collect_vec();
                 new_contexts.push(RenderingContext {
@@ -688,15 +991,15 @@ pub fn calculate_rendering_contexts(
             continue;
         }
 
-        if add_insertion {
-            // Insertion (detected in the previous loop) notated at the beginning of the first segment.
-            if let Some(context) = new_contexts.first_mut() {
-                context.add_modifier(RenderingContextModifier::Insertion(
-                    annotate_insertion_in_next_cigar.unwrap() as u64,
-                ))
-            }
+        // Insertion (detected in the previous loop) notated at the beginning of the first segment.
+        if let Some(inserted_bases) = annotate_insertion_in_next_cigar.take()
+            && let Some(context) = new_contexts.first_mut()
+        {
+            context.add_modifier(RenderingContextModifier::Insertion(
+                context.start,
+                inserted_bases,
+            ))
         };
-        annotate_insertion_in_next_cigar = None;
 
         if is_reverse {
             // reverse: first one
@@ -725,45 +1028,117 @@ pub fn calculate_rendering_contexts(
         })
     }
 
+    for (pos, modification, prob) in
+        base_modifications_from_data(data, flags, seq, cigars, reference_start)?.into_iter()
+    {
+        for context in rendering_context.iter_mut() {
+            if (context.start..=context.end).contains(&pos) {
+                context
+                    .modifiers
+                    .push(RenderingContextModifier::BaseModification(
+                        pos,
+                        modification,
+                        prob,
+                    ));
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reference coordinates of mismatched bases in aligned segments, parsed from the MD tag.
+/// None if the MD tag is absent or malformed.
+/// See: https://samtools.github.io/hts-specs/SAMtags.pdf#page=3
+fn mismatch_coordinates_from_md(
+    data: &Data,
+    cigars: &[Op],
+    reference_start: u64,
+) -> Option<HashSet<u64>> {
+    let Some(Value::String(md)) = data.get(&Tag::MISMATCHED_POSITIONS) else {
+        return None;
+    };
+    let md: &[u8] = md.as_ref();
+
+    // MD walks aligned bases (M / = / X) in order. Deleted bases are described separately after `^`.
+    let mut aligned_coordinates = Vec::new();
+    let mut reference_pivot = reference_start;
+    for op in cigars {
+        let kind = op.kind();
+        if matches!(
+            kind,
+            Kind::Match | Kind::SequenceMatch | Kind::SequenceMismatch
+        ) {
+            aligned_coordinates.extend(reference_pivot..reference_pivot + op.len() as u64);
+        }
+        if kind.consumes_reference() {
+            reference_pivot += op.len() as u64;
+        }
+    }
+
+    let mut mismatches = HashSet::new();
+    let mut i_aligned = 0;
+    let mut n_matches = 0;
+    let mut in_deletion = false;
+    for byte in md {
+        match byte {
+            b'0'..=b'9' => {
+                n_matches = n_matches * 10 + (byte - b'0') as usize;
+                in_deletion = false;
+            }
+            b'^' => {
+                i_aligned += n_matches;
+                n_matches = 0;
+                in_deletion = true;
+            }
+            byte if byte.is_ascii_alphabetic() => {
+                if in_deletion {
+                    continue;
+                }
+                i_aligned += n_matches;
+                n_matches = 0;
+                mismatches.insert(*aligned_coordinates.get(i_aligned)?);
+                i_aligned += 1;
+            }
+            _ => return None,
+        }
+    }
+
+    Some(mismatches)
+}
+
+/// Base modifications from the MM and ML tags, mapped to reference coordinates.
+/// Returns an empty vector if the read has no MM tag.
+fn base_modifications_from_data(
+    data: &Data,
+    flags: &Flags,
+    seq: &sam::alignment::record_buf::Sequence,
+    cigars: &[Op],
+    reference_start: u64,
+) -> Result<Vec<(u64, Modification, u8)>, TGVError> {
     const LEGACY_BASE_MODIFICATION_TAG: Tag = Tag::new(b'M', b'm');
     const LEGACY_BASE_MODIFICATION_PROBABILITY_TAG: Tag = Tag::new(b'M', b'l');
 
     // Fetch MM tag (string, type Z).
-    if let Some(Value::String(s)) = data
+    let Some(Value::String(s)) = data
         .get(&Tag::BASE_MODIFICATIONS)
         .or_else(|| data.get(&LEGACY_BASE_MODIFICATION_TAG))
-    {
-        let ml_string = String::from_utf8_lossy(s.as_ref()).into_owned();
-
-        let ml_bytes = match data
-            .get(&Tag::BASE_MODIFICATION_PROBABILITIES)
-            .or_else(|| data.get(&LEGACY_BASE_MODIFICATION_PROBABILITY_TAG))
-        {
-            Some(Value::Array(Array::UInt8(values))) => Some(values.clone()),
-            _ => None,
-        };
-        let base_modification_modifiers =
-            extract_base_modifications(ml_string, ml_bytes, flags, seq, cigars, reference_start)?;
-
-        for (pos, modification, prob) in base_modification_modifiers.into_iter() {
-            for context in rendering_context.iter_mut() {
-                if (context.start..=context.end).contains(&pos) {
-                    context
-                        .modifiers
-                        .push(RenderingContextModifier::BaseModification(
-                            pos,
-                            modification,
-                            prob,
-                        ));
-                    break;
-                }
-            }
-        }
+    else {
+        return Ok(Vec::new());
     };
+    let ml_string = String::from_utf8_lossy(s.as_ref()).into_owned();
 
     // Fetch ML tag (uint8 array, type B:C).
+    let ml_bytes = match data
+        .get(&Tag::BASE_MODIFICATION_PROBABILITIES)
+        .or_else(|| data.get(&LEGACY_BASE_MODIFICATION_PROBABILITY_TAG))
+    {
+        Some(Value::Array(Array::UInt8(values))) => Some(values.clone()),
+        _ => None,
+    };
 
-    Ok(())
+    extract_base_modifications(ml_string, ml_bytes, flags, seq, cigars, reference_start)
 }
 
 /// Read 1 is the forward read, read 2 is the reverse read
@@ -890,7 +1265,7 @@ pub fn calculate_paired_context(
                             .iter()
                             .filter_map(|modifier| match modifier {
                                 RenderingContextModifier::Mismatch(pos, _)
-                                | RenderingContextModifier::Insertion(pos) => {
+                                | RenderingContextModifier::Insertion(pos, _) => {
                                     if *pos < next_start {
                                         Some(modifier.clone())
                                     } else {
@@ -933,7 +1308,7 @@ pub fn calculate_paired_context(
                             .iter()
                             .filter_map(|modifier| match modifier {
                                 RenderingContextModifier::Mismatch(pos, _)
-                                | RenderingContextModifier::Insertion(pos) => {
+                                | RenderingContextModifier::Insertion(pos, _) => {
                                     if *pos > previous_end {
                                         Some(modifier.clone())
                                     } else {
@@ -997,27 +1372,27 @@ pub fn get_overlapped_pair_rendering_text(
     let mut modifiers = vec![];
 
     modifiers_1.iter().for_each(|modifier| match modifier {
-        RenderingContextModifier::Mismatch(pos, _) | RenderingContextModifier::Insertion(pos) => {
+        RenderingContextModifier::Mismatch(pos, _)
+        | RenderingContextModifier::Insertion(pos, _) => {
             base_modifier_lookup.insert(*pos, modifier.clone());
         }
         _ => modifiers.push(modifier.clone()),
     });
 
     modifiers_2.iter().for_each(|modifier| match modifier {
-        RenderingContextModifier::Mismatch(pos, _) | RenderingContextModifier::Insertion(pos) => {
-            match base_modifier_lookup.remove(pos) {
-                Some(other_modifier) => {
-                    if *modifier == other_modifier {
-                        modifiers.push(other_modifier)
-                    } else {
-                        modifiers.push(RenderingContextModifier::PairConflict(*pos))
-                    }
-                }
-                None => {
-                    base_modifier_lookup.insert(*pos, modifier.clone());
+        RenderingContextModifier::Mismatch(pos, _)
+        | RenderingContextModifier::Insertion(pos, _) => match base_modifier_lookup.remove(pos) {
+            Some(other_modifier) => {
+                if *modifier == other_modifier {
+                    modifiers.push(other_modifier)
+                } else {
+                    modifiers.push(RenderingContextModifier::PairConflict(*pos))
                 }
             }
-        }
+            None => {
+                base_modifier_lookup.insert(*pos, modifier.clone());
+            }
+        },
         _ => modifiers.push(modifier.clone()),
     });
 
@@ -1033,21 +1408,30 @@ pub fn get_overlapped_pair_rendering_text(
     }
 }
 
-pub fn matches_base(base1: u8, base2: u8) -> bool {
-    if base1 == base2 {
-        return true;
-    }
+/// Whether a read base matches a reference base, ignoring case. A read base also matches an IUPAC ambiguity code in
+/// the reference that includes it, e.g. A and G match R. A, C, G, and T otherwise only match themselves.
+pub fn matches_base(read_base: u8, reference_base: u8) -> bool {
+    let read_base = read_base.to_ascii_uppercase();
+    let reference_base = reference_base.to_ascii_uppercase();
+
+    read_base == reference_base || iupac_bases(reference_base).contains(&read_base)
+}
 
-    match (base1, base2) {
-        (b'A', b'a')
-        | (b'a', b'A')
-        | (b'C', b'c')
-        | (b'c', b'C')
-        | (b'G', b'g')
-        | (b'g', b'G')
-        | (b'T', b't')
-        | (b't', b'T') => true,
-        _ => false,
+/// Bases of an IUPAC ambiguity code. Empty for A, C, G, T, and other characters.
+fn iupac_bases(code: u8) -> &'static [u8] {
+    match code {
+        b'R' => b"AG",
+        b'Y' => b"CT",
+        b'S' => b"CG",
+        b'W' => b"AT",
+        b'K' => b"GT",
+        b'M' => b"AC",
+        b'B' => b"CGT",
+        b'D' => b"AGT",
+        b'H' => b"ACT",
+        b'V' => b"ACG",
+        b'N' => b"ACGT",
+        _ => b"",
     }
 }
 
@@ -1143,6 +1527,92 @@ mod tests {
         AlignedRead::try_from(record).unwrap()
     }
 
+    #[rstest]
+    #[case(AlignmentFilter::Default, true)]
+    #[case(AlignmentFilter::False, false)]
+    #[case(AlignmentFilter::MappingQualityGE(20).and(AlignmentFilter::Base(11, 'C')), true)]
+    #[case(AlignmentFilter::MappingQualityGE(40).and(AlignmentFilter::Base(11, 'C')), false)]
+    #[case(AlignmentFilter::MappingQualityGE(20).and(AlignmentFilter::Base(11, 'G')), false)]
+    #[case(AlignmentFilter::MappingQualityGE(40).or(AlignmentFilter::Base(11, 'C')), true)]
+    #[case(AlignmentFilter::MappingQualityLE(20).not(), true)]
+    #[case(AlignmentFilter::Strand(Strand::Reverse), false)]
+    #[case(AlignmentFilter::Overlaps(13, 20), true)]
+    #[case(AlignmentFilter::StartsIn(11, 20), false)]
+    #[case(AlignmentFilter::ReadLengthGE(4), true)]
+    #[case(AlignmentFilter::ReadLengthGE(5), false)]
+    #[case(AlignmentFilter::ReadLengthLE(3), false)]
+    fn passes_filter_evaluates_composite_filters(
+        #[case] filter: AlignmentFilter,
+        #[case] expected: bool,
+    ) {
+        let record = sam::alignment::RecordBuf::builder()
+            .set_alignment_start(noodles::core::Position::try_from(10).unwrap())
+            .set_mapping_quality(MappingQuality::new(30).unwrap())
+            .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+            .set_sequence(sam::alignment::record_buf::Sequence::from(
+                b"ACGT".as_slice(),
+            ))
+            .build();
+        let read = AlignedRead::try_from(record).unwrap();
+
+        assert_eq!(read.passes_filter(&filter), expected);
+    }
+
+    #[rstest]
+    #[case("HP", Value::from(2u8), Some("2"))]
+    #[case("HP", Value::from(-1i32), Some("-1"))]
+    #[case("RG", Value::from("sample1"), Some("sample1"))]
+    #[case("RG", Value::Character(b'A'), Some("A"))]
+    #[case("RG", Value::from(0.5f32), Some("0.5"))]
+    #[case("RG", Value::from(vec![1u8, 2]), None)]
+    #[case("PS", Value::from(2u8), None)]
+    #[case("H", Value::from(2u8), None)]
+    fn tag_values_are_strings(
+        #[case] tag: &str,
+        #[case] value: Value,
+        #[case] expected: Option<&str>,
+    ) {
+        let mut data = Data::default();
+        data.insert(Tag::new(b'H', b'P'), value.clone());
+        data.insert(Tag::READ_GROUP, value);
+        let record = sam::alignment::RecordBuf::builder()
+            .set_alignment_start(noodles::core::Position::try_from(10).unwrap())
+            .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+            .set_data(data)
+            .build();
+        let read = AlignedRead::try_from(record).unwrap();
+
+        assert_eq!(read.tag_value(tag).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case(Some(19), 20, false)]
+    #[case(Some(20), 20, true)]
+    #[case(Some(21), 20, true)]
+    #[case(Some(0), 1, false)]
+    #[case(None, 60, true)]
+    fn passes_min_mapq_filter_at_threshold_and_without_mapq(
+        #[case] mapping_quality: Option<u8>,
+        #[case] threshold: u8,
+        #[case] expected: bool,
+    ) {
+        let mut builder = sam::alignment::RecordBuf::builder()
+            .set_alignment_start(noodles::core::Position::try_from(10).unwrap())
+            .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+            .set_sequence(sam::alignment::record_buf::Sequence::from(
+                b"ACGT".as_slice(),
+            ));
+        if let Some(mapping_quality) = mapping_quality {
+            builder = builder.set_mapping_quality(MappingQuality::new(mapping_quality).unwrap());
+        }
+        let read = AlignedRead::try_from(builder.build()).unwrap();
+
+        assert_eq!(
+            read.passes_filter(&AlignmentFilter::MinMapq(threshold)),
+            expected
+        );
+    }
+
     #[test]
     fn describe_shows_sam_style_flags_and_cigar_without_start() -> Result<(), TGVError> {
         let cigar: Cigar = [Op::new(Kind::Match, 4), Op::new(Kind::SoftClip, 2)]
@@ -1159,7 +1629,94 @@ mod tests {
 
         let read = AlignedRead::try_from(record)?;
 
-        assert_eq!(read.describe()?, "r0  Flags=80  MAPQ=60  Cigar=4M2S");
+        assert_eq!(
+            read.describe()?,
+            "r0  Flags=80  MAPQ=60  Span=3-6 (-)  Cigar=4M2S  Length=4bp  Clipping=right 2S  Mate=.  Insert=.  NM=.  AS=."
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn describe_shows_placeholders_for_missing_name_mapq_and_mate() -> Result<(), TGVError> {
+        let record = sam::alignment::RecordBuf::builder()
+            .set_flags(Flags::from(0))
+            .set_alignment_start(noodles::core::Position::try_from(3).unwrap())
+            .set_cigar([Op::new(Kind::Match, 4)].into_iter().collect())
+            .build();
+
+        let read = AlignedRead::try_from(record)?;
+
+        assert_eq!(
+            read.describe()?,
+            "<missing>  Flags=0  MAPQ=.  Span=3-6 (+)  Cigar=4M  Length=4bp  Clipping=None  Mate=.  Insert=.  NM=.  AS=."
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    // Paired, mate on the same contig, on the reverse strand.
+    #[case(Flags::from(99), Some(0), Some(200), 250, "Mate=200 (-)  Insert=250")]
+    // Paired, mate on another contig.
+    #[case(
+        Flags::from(65),
+        Some(1),
+        Some(200),
+        0,
+        "Mate=other contig:200 (+)  Insert=."
+    )]
+    // Paired, mate position missing.
+    #[case(Flags::from(97), None, None, 0, "Mate=.  Insert=.")]
+    // Paired, mate unmapped.
+    #[case(Flags::from(73), None, None, 0, "Mate=unmapped  Insert=.")]
+    fn test_describe_mate(
+        #[case] flags: Flags,
+        #[case] mate_reference_sequence_id: Option<usize>,
+        #[case] mate_start: Option<usize>,
+        #[case] template_length: i32,
+        #[case] expected: &str,
+    ) -> Result<(), TGVError> {
+        let mut data = Data::default();
+        data.insert(Tag::EDIT_DISTANCE, Value::from(1u8));
+        data.insert(Tag::ALIGNMENT_SCORE, Value::from(-5i32));
+
+        let mut builder = sam::alignment::RecordBuf::builder()
+            .set_name("r0")
+            .set_flags(flags)
+            .set_reference_sequence_id(0)
+            .set_alignment_start(noodles::core::Position::try_from(100).unwrap())
+            .set_mapping_quality(MappingQuality::new(60).unwrap())
+            .set_cigar(
+                [
+                    Op::new(Kind::HardClip, 5),
+                    Op::new(Kind::SoftClip, 2),
+                    Op::new(Kind::Match, 10),
+                    Op::new(Kind::SoftClip, 3),
+                ]
+                .into_iter()
+                .collect(),
+            )
+            .set_template_length(template_length)
+            .set_data(data);
+        if let Some(id) = mate_reference_sequence_id {
+            builder = builder.set_mate_reference_sequence_id(id);
+        }
+        if let Some(start) = mate_start {
+            builder =
+                builder.set_mate_alignment_start(noodles::core::Position::try_from(start).unwrap());
+        }
+
+        let read = AlignedRead::try_from(builder.build())?;
+
+        assert_eq!(
+            read.describe()?,
+            format!(
+                "r0  Flags={}  MAPQ=60  Span=100-109 (+)  Cigar=5H2S10M3S  Length=10bp  Clipping=left 5H2S, right 3S  {}  NM=1  AS=-5",
+                u16::from(flags),
+                expected
+            )
+        );
 
         Ok(())
     }
@@ -1191,6 +1748,85 @@ mod tests {
         assert_eq!(read.base_at(16), None);
     }
 
+    #[rstest]
+    #[case(10, b"A", b"A", true)]
+    #[case(10, b"A", b"T", false)]
+    #[case(10, b"AT", b"AT", true)]
+    #[case(11, b"TG", b"TC", false)]
+    #[case(11, b"T", b"TC", true)]
+    #[case(11, b"T", b"TCC", false)]
+    #[case(10, b"A", b"AC", false)]
+    #[case(13, b"CGA", b"C", true)]
+    #[case(13, b"CG", b"C", false)]
+    #[case(12, b"GC", b"G", false)]
+    fn supports_matches_snvs_and_indels(
+        #[case] position: u64,
+        #[case] reference: &[u8],
+        #[case] alternate: &[u8],
+        #[case] expected: bool,
+    ) {
+        // 10-11: AT, C inserted before 12, 12-13: GC, 14-15 deleted, 16-17: TT.
+        let read = read_from_parts(
+            10,
+            [
+                (Kind::Match, 2),
+                (Kind::Insertion, 1),
+                (Kind::Match, 2),
+                (Kind::Deletion, 2),
+                (Kind::Match, 2),
+            ],
+            b"ATCGCTT",
+        );
+        let allele = VariantAllele {
+            contig_index: 0,
+            position,
+            reference: reference.to_vec(),
+            alternate: alternate.to_vec(),
+        };
+
+        assert_eq!(read.supports(&allele), expected);
+    }
+
+    #[rstest]
+    #[case(10, b"A", b"T", true)]
+    #[case(10, b"T", b"A", false)]
+    #[case(12, b"GC", b"AA", true)]
+    // The insertion before 12 and the deletion at 14-15 are not the reference allele.
+    #[case(11, b"T", b"TC", false)]
+    #[case(11, b"T", b"TCC", false)]
+    #[case(13, b"CGA", b"C", false)]
+    // Spanned without an indel.
+    #[case(10, b"A", b"AC", true)]
+    #[case(16, b"TT", b"T", false)]
+    #[case(12, b"G", b"GA", true)]
+    fn supports_reference_matches_snvs_and_indels(
+        #[case] position: u64,
+        #[case] reference: &[u8],
+        #[case] alternate: &[u8],
+        #[case] expected: bool,
+    ) {
+        // 10-11: AT, C inserted before 12, 12-13: GC, 14-15 deleted, 16-17: TT.
+        let read = read_from_parts(
+            10,
+            [
+                (Kind::Match, 2),
+                (Kind::Insertion, 1),
+                (Kind::Match, 2),
+                (Kind::Deletion, 2),
+                (Kind::Match, 2),
+            ],
+            b"ATCGCTT",
+        );
+        let allele = VariantAllele {
+            contig_index: 0,
+            position,
+            reference: reference.to_vec(),
+            alternate: alternate.to_vec(),
+        };
+
+        assert_eq!(read.supports_reference(&allele), expected);
+    }
+
     #[test]
     fn is_deletion_at_detects_deletions_and_reference_skips() {
         let read = read_from_parts(
@@ -1351,11 +1987,10 @@ mod tests {
         calculate_rendering_contexts(
             &mut contexts,
             10,
-            &record_buf.flags(),
+            &record_buf,
             &cigars,
-            record_buf.sequence(),
-            record_buf.data(),
             &Sequence::default(),
+            0,
         )
         .unwrap();
 
@@ -1430,7 +2065,7 @@ mod tests {
         kind: RenderingContextKind::Deletion,
         modifiers:vec![RenderingContextModifier::Forward]
     }])]
-    // Test insertion
+    // Test insertion: the inserted bases are captured
     #[case(10, vec![(Kind::Match, 3), (Kind::Insertion, 2), (Kind::Match, 3)], b"AAATTCCC", false, Sequence::default(), vec![RenderingContext{
         start:10,
         end:12,
@@ -1440,7 +2075,7 @@ mod tests {
         start:13,
         end:15,
         kind: RenderingContextKind::Match,
-        modifiers:vec![RenderingContextModifier::Insertion(2), RenderingContextModifier::Forward]
+        modifiers:vec![RenderingContextModifier::Insertion(13, b"TT".to_vec()), RenderingContextModifier::Forward]
     }])]
     // Test soft clips
     #[case(10, vec![(Kind::SoftClip, 2), (Kind::Match, 3), (Kind::SoftClip, 1)], b"GGATTC", true, Sequence::default(), vec![
@@ -1507,7 +2142,7 @@ mod tests {
             start:12,
             end:13,
             kind: RenderingContextKind::Match,
-            modifiers:vec![RenderingContextModifier::Insertion(1)]
+            modifiers:vec![RenderingContextModifier::Insertion(12, b"C".to_vec())]
         },
         RenderingContext{
             start:14,
@@ -1575,14 +2210,189 @@ mod tests {
         calculate_rendering_contexts(
             &mut contexts,
             reference_start,
-            &record_buf.flags(),
+            &record_buf,
+            &cigars,
+            &reference_sequence,
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(contexts, expected_rendering_contexts)
+    }
+
+    #[rstest]
+    // Mismatches from MD, without a reference sequence
+    #[case(10, vec![(Kind::Match, 4)], b"ATGC", "1A0C1", Sequence::default(), vec![RenderingContext{
+        start:10,
+        end:13,
+        kind: RenderingContextKind::Match,
+        modifiers:vec![
+            RenderingContextModifier::Mismatch(11, b'T'),
+            RenderingContextModifier::Mismatch(12, b'G'),
+            RenderingContextModifier::Forward
+        ]
+    }])]
+    // Deletions are skipped by `^` in MD. Soft clips and insertions are not in MD.
+    #[case(10, vec![(Kind::SoftClip, 1), (Kind::Match, 2), (Kind::Insertion, 1), (Kind::Deletion, 2), (Kind::Match, 2)],
+           b"GATCGA", "2^TT0C1", Sequence::default(), vec![
+        RenderingContext{
+            start:9,
+            end:9,
+            kind: RenderingContextKind::SoftClip(b'G'),
+            modifiers:vec![]
+        },
+        RenderingContext{
+            start:10,
+            end:11,
+            kind: RenderingContextKind::Match,
+            modifiers:vec![]
+        },
+        RenderingContext{
+            start:12,
+            end:13,
+            kind: RenderingContextKind::Deletion,
+            modifiers:vec![RenderingContextModifier::Insertion(12, b"C".to_vec())]
+        },
+        RenderingContext{
+            start:14,
+            end:15,
+            kind: RenderingContextKind::Match,
+            modifiers:vec![RenderingContextModifier::Mismatch(14, b'G'), RenderingContextModifier::Forward]
+        }
+    ])]
+    // MD takes precedence over the reference sequence
+    #[case(10, vec![(Kind::Match, 3)], b"ATT", "3", Sequence{start: 10, sequence: b"AAA".to_vec(), contig_index: 0}, vec![RenderingContext{
+        start:10,
+        end:12,
+        kind: RenderingContextKind::Match,
+        modifiers:vec![RenderingContextModifier::Forward]
+    }])]
+    // Malformed MD falls back to the reference sequence
+    #[case(10, vec![(Kind::Match, 3)], b"ATT", "3T", Sequence{start: 10, sequence: b"AAT".to_vec(), contig_index: 0}, vec![RenderingContext{
+        start:10,
+        end:12,
+        kind: RenderingContextKind::Match,
+        modifiers:vec![RenderingContextModifier::Mismatch(11, b'T'), RenderingContextModifier::Forward]
+    }])]
+    fn test_calculate_rendering_contexts_with_md(
+        #[case] reference_start: u64, // 1-based
+        #[case] cigars: Vec<(Kind, usize)>,
+        #[case] seq: &[u8],
+        #[case] md: &str,
+        #[case] reference_sequence: Sequence,
+        #[case] expected_rendering_contexts: Vec<RenderingContext>,
+    ) {
+        let cigars = cigars
+            .into_iter()
+            .map(|(kind, length)| Op::new(kind, length))
+            .collect::<Vec<Op>>();
+        let mut data = Data::default();
+        data.insert(Tag::MISMATCHED_POSITIONS, Value::from(md));
+
+        let record_buf = sam::alignment::RecordBuf::builder()
+            .set_sequence(sam::alignment::record_buf::Sequence::from(seq))
+            .set_data(data)
+            .build();
+
+        let mut contexts = Vec::new();
+        calculate_rendering_contexts(
+            &mut contexts,
+            reference_start,
+            &record_buf,
             &cigars,
-            &record_buf.sequence(),
-            &record_buf.data(),
             &reference_sequence,
+            0,
         )
         .unwrap();
 
         assert_eq!(contexts, expected_rendering_contexts)
     }
+
+    #[rstest]
+    #[case(Kind::Match, vec![30, 10, 20, 25], 0, vec![11, 12, 13])]
+    #[case(Kind::Match, vec![30, 10, 20, 25], 20, vec![12, 13])]
+    #[case(Kind::Match, vec![30, 10, 20, 25], 40, vec![])]
+    #[case(Kind::SequenceMismatch, vec![30, 10, 20, 25], 20, vec![10, 12, 13])]
+    // Reads without quality scores keep all mismatches.
+    #[case(Kind::Match, vec![], 40, vec![11, 12, 13])]
+    fn calculate_rendering_contexts_hides_low_quality_mismatches(
+        #[case] kind: Kind,
+        #[case] quality_scores: Vec<u8>,
+        #[case] min_mismatch_quality: u8,
+        #[case] expected_mismatches: Vec<u64>,
+    ) {
+        let cigars = vec![Op::new(kind, 4)];
+        let record_buf = sam::alignment::RecordBuf::builder()
+            .set_sequence(sam::alignment::record_buf::Sequence::from(b"ATGC"))
+            .set_quality_scores(sam::alignment::record_buf::QualityScores::from(
+                quality_scores,
+            ))
+            .build();
+        let reference_sequence = Sequence {
+            start: 10,
+            sequence: b"AAAA".to_vec(),
+            contig_index: 0,
+        };
+
+        let mut contexts = Vec::new();
+        calculate_rendering_contexts(
+            &mut contexts,
+            10,
+            &record_buf,
+            &cigars,
+            &reference_sequence,
+            min_mismatch_quality,
+        )
+        .unwrap();
+
+        let mismatches = contexts
+            .iter()
+            .flat_map(|context| context.modifiers.iter())
+            .filter_map(|modifier| match modifier {
+                RenderingContextModifier::Mismatch(position, _) => Some(*position),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(mismatches, expected_mismatches);
+    }
+
+    #[rstest]
+    #[case(b'R', b"AG", b"CT")]
+    #[case(b'Y', b"CT", b"AG")]
+    #[case(b'S', b"CG", b"AT")]
+    #[case(b'W', b"AT", b"CG")]
+    #[case(b'K', b"GT", b"AC")]
+    #[case(b'M', b"AC", b"GT")]
+    #[case(b'B', b"CGT", b"A")]
+    #[case(b'D', b"AGT", b"C")]
+    #[case(b'H', b"ACT", b"G")]
+    #[case(b'V', b"ACG", b"T")]
+    #[case(b'N', b"ACGTN", b"")]
+    #[case(b'A', b"Aa", b"CGTNR")]
+    #[case(b'c', b"Cc", b"AGTNY")]
+    fn test_matches_base(
+        #[case] reference_base: u8,
+        #[case] matching: &[u8],
+        #[case] mismatching: &[u8],
+    ) {
+        for reference_base in [reference_base, reference_base.to_ascii_lowercase()] {
+            for read_base in matching {
+                assert!(
+                    matches_base(*read_base, reference_base),
+                    "{} should match {}",
+                    *read_base as char,
+                    reference_base as char
+                );
+                assert!(matches_base(read_base.to_ascii_lowercase(), reference_base));
+            }
+            for read_base in mismatching {
+                assert!(
+                    !matches_base(*read_base, reference_base),
+                    "{} should not match {}",
+                    *read_base as char,
+                    reference_base as char
+                );
+            }
+        }
+    }
 }
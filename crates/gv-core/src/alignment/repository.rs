@@ -1,23 +1,33 @@
 use crate::{
-    alignment::{AlignedRead, Alignment},
+    alignment::{AlignedRead, Alignment, CoverageOverview, HiddenReadCategory, HiddenReadCounts},
     contig_header::ContigHeader,
     error::TGVError,
-    intervals::{GenomeInterval, Region},
+    intervals::{Focus, GenomeInterval, Region},
     sequence::Sequence,
     settings::{AlignmentPath, BamSource},
 };
 
 use async_compat::{Compat, CompatExt};
 use futures::TryStreamExt;
+use http::{
+    Method, Request, Response, StatusCode,
+    header::{CONTENT_LENGTH, CONTENT_RANGE, HeaderValue, RANGE},
+};
 use itertools::Itertools;
 use noodles::cram::{self as cram};
+use noodles::csi::binning_index::ReferenceSequence as _;
 use noodles::fasta::{self as fasta, repository::adapters::IndexedReader as FastaIndexedReader};
 use noodles::sam::Header;
 use noodles::{
     bam::{self, bai},
     sam::alignment::RecordBuf,
 };
-use opendal::{FuturesAsyncReader, Operator, services};
+use opendal::{
+    Buffer, FuturesAsyncReader, Operator,
+    raw::{HttpBody, HttpClient, HttpFetch},
+    services,
+};
+use std::collections::BTreeSet;
 use std::fs;
 use std::path::Path;
 use std::time::Instant;
@@ -66,16 +76,14 @@ impl BamRepository {
 pub struct CramRepository {
     cram_path: String,
     crai_path: String,
-    fasta_path: String,
-    fai_path: String,
 
-    //index: crai::Index,
     header: Header,
 
     reader: cram::io::indexed_reader::IndexedReader<fs::File>,
 }
 
 impl CramRepository {
+    /// Open a CRAM file. Reads are decoded against the FASTA reference, and regions are queried with the .crai index.
     async fn new(
         cram_path: &str,
         crai_path: &str,
@@ -89,33 +97,138 @@ impl CramRepository {
             )));
         }
 
+        let fai = fasta::fai::fs::read(fai_path).map_err(|e| {
+            TGVError::IOError(format!(
+                "Cannot read the reference index {fai_path} needed to decode {cram_path}: {e}"
+            ))
+        })?;
         let repository = fasta::io::indexed_reader::Builder::default()
+            .set_index(fai)
             .build_from_path(fasta_path)
             .map(FastaIndexedReader::new)
             .map(fasta::Repository::new)?;
 
+        let crai = cram::crai::fs::read(crai_path)
+            .map_err(|e| TGVError::IOError(format!("Cannot read CRAM index {crai_path}: {e}")))?;
         let mut reader = cram::io::indexed_reader::Builder::default()
             .set_reference_sequence_repository(repository)
+            .set_index(crai)
             .build_from_path(cram_path)?;
 
         let header = reader.read_header()?;
 
-        // let index = fs::File::open(fai_path)
-        //     .map(crai::io::Reader::new)?
-        //     .read_index()?;
-
         Ok(Self {
             cram_path: cram_path.to_string(),
             crai_path: crai_path.to_string(),
-            fasta_path: fasta_path.to_string(),
-            fai_path: fai_path.to_string(),
-            //index,
             header,
             reader,
         })
     }
 }
 
+/// BAM stream read from standard input. Without an index, all records are read on startup and kept in memory.
+pub struct StdinBamRepository {
+    header: Header,
+
+    records: Vec<RecordBuf>,
+}
+
+impl StdinBamRepository {
+    pub async fn from_reader<R>(reader: R) -> Result<Self, TGVError>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let mut reader = bam::r#async::io::Reader::new(reader);
+        let header = reader
+            .read_header()
+            .await
+            .map_err(|e| TGVError::IOError(format!("Cannot read a BAM header from stdin: {e}")))?;
+        let records = reader.record_bufs(&header).try_collect::<Vec<_>>().await?;
+
+        Ok(Self { header, records })
+    }
+
+    /// Records overlapping a region. Records are scanned in full because there is no index.
+    fn query(&self, region: &noodles::core::Region) -> Vec<&RecordBuf> {
+        let Some(reference_sequence_id) = self
+            .header
+            .reference_sequences()
+            .get_index_of(region.name())
+        else {
+            return Vec::new();
+        };
+        let interval = region.interval();
+
+        self.records
+            .iter()
+            .filter(|record| record.reference_sequence_id() == Some(reference_sequence_id))
+            .filter(
+                |record| match (record.alignment_start(), record.alignment_end()) {
+                    (Some(start), Some(end)) => {
+                        interval
+                            .start()
+                            .is_none_or(|query_start| end >= query_start)
+                            && interval.end().is_none_or(|query_end| start <= query_end)
+                    }
+                    _ => false,
+                },
+            )
+            .collect()
+    }
+
+    /// Contig name and 1-based, inclusive range of the reads on the contig of the first mapped read.
+    fn span(&self) -> Option<(String, u64, u64)> {
+        let reference_sequence_id = self
+            .records
+            .iter()
+            .find_map(|record| record.reference_sequence_id())?;
+        let (start, end) = self
+            .records
+            .iter()
+            .filter(|record| record.reference_sequence_id() == Some(reference_sequence_id))
+            .filter_map(|record| Some((record.alignment_start()?, record.alignment_end()?)))
+            .fold((usize::MAX, 0), |(start, end), (read_start, read_end)| {
+                (start.min(read_start.get()), end.max(read_end.get()))
+            });
+        if start > end {
+            return None;
+        }
+        let (contig_name, _) = self
+            .header
+            .reference_sequences()
+            .get_index(reference_sequence_id)?;
+
+        Some((contig_name.to_string(), start as u64, end as u64))
+    }
+}
+
+/// Region covered by reads loaded without an index (from stdin). Navigation stays within it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadSpan {
+    pub contig_index: usize,
+
+    /// 1-based, inclusive.
+    pub start: u64,
+
+    /// 1-based, inclusive.
+    pub end: u64,
+}
+
+impl ReadSpan {
+    /// Move the focus into the span. Foci on another contig move to the start of the span.
+    pub fn clamp(&self, focus: Focus) -> Focus {
+        let position = if focus.contig_index == self.contig_index {
+            focus.position.clamp(self.start, self.end)
+        } else {
+            self.start
+        };
+        Focus {
+            contig_index: self.contig_index,
+            position,
+        }
+    }
+}
+
 pub struct RemoteBamRepository {
     bam_path: String,
     bai_path: String,
@@ -129,27 +242,19 @@ pub struct RemoteBamRepository {
 }
 
 impl RemoteBamRepository {
-    pub async fn new(s3_bam_path: &str, s3_bai_path: &str) -> Result<Self, TGVError> {
-        let (bucket, name) = s3_bam_path
-            .strip_prefix("s3://")
-            .unwrap()
-            .split_once("/")
-            .unwrap();
-
-        let builder = services::S3::default().bucket(bucket);
-
-        let operator = Operator::new(builder)?.finish();
-
-        let _index = Self::read_index(s3_bai_path).await?;
+    pub async fn new(bam_url: &str, bai_url: &str) -> Result<Self, TGVError> {
+        // Read the index first so that a missing index fails before the BAM is touched.
+        let index = Self::read_index(bai_url).await?;
 
+        let location = RemoteLocation::parse(bam_url)?;
         log::info!(
-            "Object storage request: operation=read object_url={} bucket={} key={} context=remote BAM header",
-            s3_bam_path,
-            bucket,
-            name
+            "Object storage request: operation=read object_url={} location={:?} context=remote BAM header",
+            bam_url,
+            location,
         );
+        let operator = location.operator()?;
         let stream = operator
-            .reader(name)
+            .reader(location.key())
             .await?
             .into_futures_async_read(..)
             .await?;
@@ -158,11 +263,9 @@ impl RemoteBamRepository {
 
         let header = reader.read_header().await?;
 
-        let index = Self::read_index(s3_bai_path).await?;
-
         Ok(Self {
-            bam_path: s3_bam_path.to_string(),
-            bai_path: s3_bai_path.to_string(),
+            bam_path: bam_url.to_string(),
+            bai_path: bai_url.to_string(),
 
             index,
 
@@ -171,32 +274,149 @@ impl RemoteBamRepository {
         })
     }
 
-    async fn read_index(s3_bai_path: &str) -> Result<bai::Index, TGVError> {
-        let (bucket, name) = s3_bai_path
-            .strip_prefix("s3://")
-            .unwrap()
-            .split_once("/")
-            .unwrap();
-
-        let builder = services::S3::default().bucket(bucket);
-
-        let operator = Operator::new(builder)?.finish();
+    async fn read_index(bai_url: &str) -> Result<bai::Index, TGVError> {
+        let location = RemoteLocation::parse(bai_url)?;
+        let index_error = |e: &dyn std::fmt::Display| {
+            TGVError::IOError(format!("Cannot read BAM index {bai_url}: {e}"))
+        };
 
         log::info!(
-            "Object storage request: operation=read object_url={} bucket={} key={} context=remote BAM index",
-            s3_bai_path,
-            bucket,
-            name
+            "Object storage request: operation=read object_url={} location={:?} context=remote BAM index",
+            bai_url,
+            location,
         );
-        let stream = operator
-            .reader(name)
-            .await?
+        let stream = location
+            .operator()?
+            .reader(location.key())
+            .await
+            .map_err(|e| index_error(&e))?
             .into_futures_async_read(..)
-            .await?;
+            .await
+            .map_err(|e| index_error(&e))?;
 
         let mut reader = bai::r#async::io::Reader::new(stream.compat());
 
-        Ok(reader.read_index().await?)
+        reader.read_index().await.map_err(|e| index_error(&e))
+    }
+}
+
+/// Location of a remote file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RemoteLocation {
+    /// `s3://bucket/key`
+    S3 { bucket: String, key: String },
+
+    /// `http(s)://host/key?query`. endpoint is the URL up to the path, e.g. `https://host:8080`. The query string
+    /// (e.g. the signature of a presigned S3 or GCS URL) is sent with every request.
+    Http {
+        endpoint: String,
+        key: String,
+        query: Option<String>,
+    },
+}
+
+impl RemoteLocation {
+    fn parse(url: &str) -> Result<Self, TGVError> {
+        let invalid = || {
+            TGVError::IOError(format!(
+                "Invalid remote file URL {url}. Expected s3://bucket/key or http(s)://host/path."
+            ))
+        };
+
+        if let Some(location) = url.strip_prefix("s3://") {
+            return match location.split_once('/') {
+                Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok(Self::S3 {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                }),
+                _ => Err(invalid()),
+            };
+        }
+
+        let parsed = url::Url::parse(url).map_err(|_| invalid())?;
+        let key = parsed.path().trim_start_matches('/');
+        if !matches!(parsed.scheme(), "http" | "https") || !parsed.has_host() || key.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Self::Http {
+            endpoint: parsed[..url::Position::BeforePath].to_string(),
+            key: key.to_string(),
+            query: parsed.query().map(str::to_string),
+        })
+    }
+
+    fn key(&self) -> &str {
+        match self {
+            Self::S3 { key, .. } | Self::Http { key, .. } => key,
+        }
+    }
+
+    /// Operator for range reads. S3 credentials are read from the environment.
+    fn operator(&self) -> Result<Operator, TGVError> {
+        Ok(match self {
+            Self::S3 { bucket, .. } => {
+                Operator::new(services::S3::default().bucket(bucket))?.finish()
+            }
+            Self::Http {
+                endpoint, query, ..
+            } => {
+                let operator =
+                    Operator::new(services::Http::default().endpoint(endpoint))?.finish();
+                if let Some(query) = query.clone() {
+                    operator.update_http_client(|client| {
+                        HttpClient::with(QueryFetch { client, query })
+                    });
+                }
+                operator
+            }
+        })
+    }
+}
+
+/// HTTP client that appends a query string to every request. opendal percent-encodes the key, so a query can't be
+/// part of it.
+///
+/// Presigned URLs are signed for GET, so the HEAD request that opendal sends for the file size is sent as a GET of
+/// the first byte instead, and the size is read from its Content-Range.
+struct QueryFetch {
+    client: HttpClient,
+    query: String,
+}
+
+impl HttpFetch for QueryFetch {
+    async fn fetch(&self, mut request: Request<Buffer>) -> opendal::Result<Response<HttpBody>> {
+        let uri = match request.uri().query() {
+            Some(_) => format!("{}&{}", request.uri(), self.query),
+            None => format!("{}?{}", request.uri(), self.query),
+        };
+        *request.uri_mut() = uri.parse().map_err(|e| {
+            opendal::Error::new(opendal::ErrorKind::Unexpected, "invalid URL with query")
+                .set_source(e)
+        })?;
+        if request.method() != Method::HEAD {
+            return self.client.fetch(request).await;
+        }
+
+        *request.method_mut() = Method::GET;
+        request
+            .headers_mut()
+            .insert(RANGE, HeaderValue::from_static("bytes=0-0"));
+        let mut response = self.client.fetch(request).await?;
+        let total = response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit_once('/'))
+            .and_then(|(_, total)| HeaderValue::from_str(total).ok());
+        if response.status() == StatusCode::PARTIAL_CONTENT
+            && let Some(total) = total
+        {
+            *response.status_mut() = StatusCode::OK;
+            response.headers_mut().remove(CONTENT_RANGE);
+            response.headers_mut().insert(CONTENT_LENGTH, total);
+        }
+        Ok(response)
     }
 }
 
@@ -214,6 +434,7 @@ pub enum AlignmentRepositoryEnum {
     Bam(BamRepository),
     RemoteBam(RemoteBamRepository),
     Cram(CramRepository),
+    Stdin(StdinBamRepository),
 }
 
 impl AlignmentRepositoryEnum {
@@ -222,7 +443,7 @@ impl AlignmentRepositoryEnum {
             AlignmentPath::Bam {
                 path,
                 index,
-                source: BamSource::S3,
+                source: BamSource::S3 | BamSource::Http,
             } => Ok(AlignmentRepositoryEnum::RemoteBam(
                 RemoteBamRepository::new(path, index).await?,
             )),
@@ -241,8 +462,26 @@ impl AlignmentRepositoryEnum {
             } => Ok(AlignmentRepositoryEnum::Cram(
                 CramRepository::new(path, crai, fasta, fai).await?,
             )),
+            AlignmentPath::Stdin => Ok(AlignmentRepositoryEnum::Stdin(
+                StdinBamRepository::from_reader(tokio::io::stdin()).await?,
+            )),
         }
     }
+
+    /// Region covered by the reads of a stdin stream. None for indexed files.
+    pub fn read_span(&self, contig_header: &ContigHeader) -> Result<Option<ReadSpan>, TGVError> {
+        let AlignmentRepositoryEnum::Stdin(inner) = self else {
+            return Ok(None);
+        };
+        let Some((contig_name, start, end)) = inner.span() else {
+            return Ok(None);
+        };
+        Ok(Some(ReadSpan {
+            contig_index: contig_header.try_get_index_by_str(&contig_name)?,
+            start,
+            end,
+        }))
+    }
 }
 
 impl AlignmentRepositoryEnum {
@@ -251,6 +490,8 @@ impl AlignmentRepositoryEnum {
         region: &Region,
         reference_sequence: &Sequence,
         contig_header: &ContigHeader,
+        shown_read_categories: &BTreeSet<HiddenReadCategory>,
+        max_reads: usize,
     ) -> Result<Alignment, TGVError> {
         let started = Instant::now();
         let (source_kind, data_path, index_path) = match self {
@@ -263,6 +504,7 @@ impl AlignmentRepositoryEnum {
             AlignmentRepositoryEnum::Cram(inner) => {
                 ("CRAM", inner.cram_path.clone(), inner.crai_path.clone())
             }
+            AlignmentRepositoryEnum::Stdin(_) => ("stdin BAM", "-".to_string(), String::new()),
         };
         log::debug!(
             "Reading alignment records: source_type={} path={} index={} region={:?}",
@@ -287,20 +529,42 @@ impl AlignmentRepositoryEnum {
             }
         };
 
-        let records = match query_region {
+        let mut records = Vec::new();
+        let mut hidden_read_counts = HiddenReadCounts::default();
+        let mut overflow = false;
+        // Keep reads that are not hidden, up to max_reads. False stops loading. Hidden reads don't count toward the
+        // cap, and a region with exactly max_reads reads does not overflow.
+        let mut keep = |read: AlignedRead| {
+            if hidden_read_counts.hide(read.record.flags(), shown_read_categories) {
+                return true;
+            }
+            if records.len() >= max_reads {
+                overflow = true;
+                return false;
+            }
+            records.push(read);
+            true
+        };
+        match query_region {
             Some(region) => {
-                let mut records = Vec::new();
                 match self {
                     AlignmentRepositoryEnum::Bam(inner) => {
+                        // The BGZF reader returns no records after reaching the end of the file, even after
+                        // seeking. Reopen the file so that a region can be queried again.
+                        inner.reader = File::open(&inner.bam_path)
+                            .await
+                            .map(bam::r#async::io::Reader::new)?;
                         let mut query = inner
                             .reader
                             .query(&inner.header, &inner.index, &region)?
                             .records();
 
                         while let Some(record) = query.try_next().await? {
-                            records.push(AlignedRead::try_from(
+                            if !keep(AlignedRead::try_from(
                                 RecordBuf::try_from_alignment_record(&inner.header, &record)?,
-                            )?);
+                            )?) {
+                                break;
+                            }
                         }
                     }
                     AlignmentRepositoryEnum::RemoteBam(inner) => {
@@ -316,22 +580,29 @@ impl AlignmentRepositoryEnum {
                             .records();
 
                         while let Some(record) = query.try_next().await? {
-                            records.push(AlignedRead::try_from(
+                            if !keep(AlignedRead::try_from(
                                 RecordBuf::try_from_alignment_record(&inner.header, &record)?,
-                            )?);
+                            )?) {
+                                break;
+                            }
                         }
                     }
                     AlignmentRepositoryEnum::Cram(inner) => {
-                        let query = inner.reader.query(&inner.header, &region)?; //&inner.index,
-
-                        //while let Some(record_buf) = query.try_next().await? {
+                        let query = inner.reader.query(&inner.header, &region)?;
                         for record in query {
-                            records.push(AlignedRead::try_from(record?)?);
+                            if !keep(AlignedRead::try_from(record?)?) {
+                                break;
+                            }
+                        }
+                    }
+                    AlignmentRepositoryEnum::Stdin(inner) => {
+                        for record in inner.query(&region) {
+                            if !keep(AlignedRead::try_from(record.clone())?) {
+                                break;
+                            }
                         }
                     }
                 };
-
-                records
             }
             None => {
                 log::debug!(
@@ -342,12 +613,11 @@ impl AlignmentRepositoryEnum {
                     region,
                     started.elapsed().as_millis(),
                 );
-                Vec::new()
             }
         };
 
         let record_count = records.len();
-        let alignment = match Alignment::from_aligned_reads(
+        let mut alignment = match Alignment::from_aligned_reads(
             records,
             region.contig_index(),
             (region.start(), region.end()),
@@ -367,20 +637,94 @@ impl AlignmentRepositoryEnum {
                 return Err(e);
             }
         };
+        alignment.hidden_read_counts = hidden_read_counts;
+        alignment.overflow = overflow;
+        if overflow {
+            log::warn!(
+                "Stopped loading alignment records at the cap: source_type={} path={} index={} region={:?} max_reads={}",
+                source_kind,
+                data_path,
+                index_path,
+                region,
+                max_reads,
+            );
+        }
 
         log::debug!(
-            "Read alignment records: source_type={} path={} index={} region={:?} records={} elapsed_ms={}",
+            "Read alignment records: source_type={} path={} index={} region={:?} records={} hidden={} elapsed_ms={}",
             source_kind,
             data_path,
             index_path,
             region,
             record_count,
+            alignment.hidden_read_counts.total(),
             started.elapsed().as_millis(),
         );
 
         Ok(alignment)
     }
 
+    /// Coarse coverage of a whole contig from the BAM index. None for CRAM files, stdin streams, and contigs not in
+    /// the header.
+    pub fn read_coverage_overview(
+        &self,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<CoverageOverview>, TGVError> {
+        let (header, index) = match self {
+            AlignmentRepositoryEnum::Bam(inner) => (&inner.header, &inner.index),
+            AlignmentRepositoryEnum::RemoteBam(inner) => (&inner.header, &inner.index),
+            AlignmentRepositoryEnum::Cram(_) | AlignmentRepositoryEnum::Stdin(_) => {
+                return Ok(None);
+            }
+        };
+
+        let Some(reference_sequence) = contig_header
+            .try_get(contig_index)?
+            .get_alignment_name()
+            .and_then(|name| header.reference_sequences().get_index_of(name.as_bytes()))
+            .and_then(|index_in_header| index.reference_sequences().get(index_in_header))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(CoverageOverview::from_linear_index(
+            contig_index,
+            reference_sequence.index(),
+            reference_sequence
+                .metadata()
+                .map(|metadata| metadata.end_position()),
+        )))
+    }
+
+    /// Write records to a BAM file with the header of this alignment file, so that reference sequence ids are kept.
+    /// Returns the number of records written.
+    pub fn write_bam<'a>(
+        &self,
+        path: &str,
+        records: impl IntoIterator<Item = &'a RecordBuf>,
+    ) -> Result<usize, TGVError> {
+        use noodles::sam::alignment::io::Write as _;
+
+        let header = match self {
+            AlignmentRepositoryEnum::Bam(inner) => &inner.header,
+            AlignmentRepositoryEnum::RemoteBam(inner) => &inner.header,
+            AlignmentRepositoryEnum::Cram(inner) => &inner.header,
+            AlignmentRepositoryEnum::Stdin(inner) => &inner.header,
+        };
+
+        let mut writer = bam::io::Writer::new(fs::File::create(path)?);
+        writer.write_header(header)?;
+        let mut record_count = 0;
+        for record in records {
+            writer.write_alignment_record(header, record)?;
+            record_count += 1;
+        }
+        writer.try_finish()?;
+
+        Ok(record_count)
+    }
+
     /// Read BAM headers and return contig namesa and lengths.
     /// Note that this function does not interprete the contig name as contg vs chromosome.
     pub fn read_header(&self) -> Result<Vec<(String, Option<usize>)>, TGVError> {
@@ -388,6 +732,7 @@ impl AlignmentRepositoryEnum {
             AlignmentRepositoryEnum::Bam(inner) => &inner.header,
             AlignmentRepositoryEnum::RemoteBam(inner) => &inner.header,
             AlignmentRepositoryEnum::Cram(inner) => &inner.header,
+            AlignmentRepositoryEnum::Stdin(inner) => &inner.header,
         };
         get_contig_names_and_lengths_from_header(header)
     }
@@ -399,3 +744,288 @@ pub fn is_url(path: &str) -> bool {
         || path.starts_with("https://")
         || path.starts_with("gs://")
 }
+
+/// Path of a file without the query string of a URL, e.g. of a presigned URL, for telling the file format from the
+/// extension. Local paths are returned as is.
+pub fn path_without_query(path: &str) -> &str {
+    match path.split_once('?') {
+        Some((path, _)) if is_url(path) => path,
+        _ => path,
+    }
+}
+
+/// Default index of a BAM file: `.bai` appended to the path, before the query string of a URL.
+pub fn default_bam_index(path: &str) -> String {
+    let without_query = path_without_query(path);
+    format!("{without_query}.bai{}", &path[without_query.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("data/tumor.bam", "data/tumor.bam", "data/tumor.bam.bai")]
+    #[case("odd?name.bam", "odd?name.bam", "odd?name.bam.bai")]
+    #[case(
+        "https://example.org/x.bam?X-Amz-Signature=abc",
+        "https://example.org/x.bam",
+        "https://example.org/x.bam.bai?X-Amz-Signature=abc"
+    )]
+    #[case("s3://bucket/x.bam", "s3://bucket/x.bam", "s3://bucket/x.bam.bai")]
+    fn test_path_without_query_and_default_bam_index(
+        #[case] path: &str,
+        #[case] expected_path: &str,
+        #[case] expected_index: &str,
+    ) {
+        assert_eq!(path_without_query(path), expected_path);
+        assert_eq!(default_bam_index(path), expected_index);
+    }
+
+    #[rstest]
+    #[case("s3://bucket/dir/reads.bam", Some(RemoteLocation::S3 {
+        bucket: "bucket".to_string(),
+        key: "dir/reads.bam".to_string(),
+    }))]
+    #[case("https://example.org:8080/data/reads.bam", Some(RemoteLocation::Http {
+        endpoint: "https://example.org:8080".to_string(),
+        key: "data/reads.bam".to_string(),
+        query: None,
+    }))]
+    #[case("http://example.org/reads.bam.bai", Some(RemoteLocation::Http {
+        endpoint: "http://example.org".to_string(),
+        key: "reads.bam.bai".to_string(),
+        query: None,
+    }))]
+    #[case("https://bucket.s3.amazonaws.com/reads.bam?X-Amz-Expires=3600&X-Amz-Signature=abc", Some(RemoteLocation::Http {
+        endpoint: "https://bucket.s3.amazonaws.com".to_string(),
+        key: "reads.bam".to_string(),
+        query: Some("X-Amz-Expires=3600&X-Amz-Signature=abc".to_string()),
+    }))]
+    #[case("s3://bucket", None)]
+    #[case("s3:///reads.bam", None)]
+    #[case("https://example.org/", None)]
+    #[case("gs://bucket/reads.bam", None)]
+    fn test_remote_location_parse(#[case] url: &str, #[case] expected: Option<RemoteLocation>) {
+        assert_eq!(RemoteLocation::parse(url).ok(), expected);
+    }
+
+    #[tokio::test]
+    async fn missing_remote_index_is_an_io_error() {
+        // Nothing listens on the discard port.
+        let result = AlignmentRepositoryEnum::new(&AlignmentPath::Bam {
+            path: "http://127.0.0.1:9/reads.bam".to_string(),
+            index: "http://127.0.0.1:9/reads.bam.bai".to_string(),
+            source: BamSource::Http,
+        })
+        .await;
+
+        match result {
+            Err(TGVError::IOError(message)) => assert!(
+                message.starts_with("Cannot read BAM index http://127.0.0.1:9/reads.bam.bai"),
+                "{message}"
+            ),
+            Err(e) => panic!("Expected an IOError, got {e}"),
+            Ok(_) => panic!("Expected an IOError"),
+        }
+    }
+
+    /// Serve ranges of files in tests/data like a presigned URL: GET only, and only with the signature.
+    async fn serve_presigned(listener: tokio::net::TcpListener) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buffer).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..n]);
+            }
+            let request = String::from_utf8_lossy(&request).to_lowercase();
+            let target = request.split(' ').nth(1).unwrap_or_default();
+            let range = request
+                .lines()
+                .find_map(|line| line.strip_prefix("range: bytes="))
+                .and_then(|range| range.split_once('-'));
+            let (head, body) = match (
+                request.starts_with("get "),
+                target.split_once("?sig=abc"),
+                range,
+            ) {
+                (true, Some((path, "")), Some((start, end))) => {
+                    let bytes = std::fs::read(format!(
+                        "{}/../tgv/tests/data{path}",
+                        env!("CARGO_MANIFEST_DIR")
+                    ))
+                    .unwrap();
+                    let start: usize = start.parse().unwrap();
+                    let end = end
+                        .parse::<usize>()
+                        .map_or(bytes.len(), |end| (end + 1).min(bytes.len()));
+                    (
+                        format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {start}-{}/{}\r\nContent-Length: {}",
+                            end - 1,
+                            bytes.len(),
+                            end - start
+                        ),
+                        bytes[start..end].to_vec(),
+                    )
+                }
+                _ => (
+                    "HTTP/1.1 403 Forbidden\r\nContent-Length: 0".to_string(),
+                    Vec::new(),
+                ),
+            };
+            let _ = stream
+                .write_all(format!("{head}\r\nConnection: close\r\n\r\n").as_bytes())
+                .await;
+            let _ = stream.write_all(&body).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn presigned_url_query_is_sent_with_every_request() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(serve_presigned(listener));
+
+        let repository = AlignmentRepositoryEnum::new(&AlignmentPath::Bam {
+            path: format!("http://{address}/covid.sorted.bam?sig=abc"),
+            index: format!("http://{address}/covid.sorted.bam.bai?sig=abc"),
+            source: BamSource::Http,
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            repository.read_header().unwrap()[0].0,
+            "MN908947.3".to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn local_bam_region_is_read_again_after_reaching_the_end_of_the_file() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tgv/tests/data/covid.sorted.bam"
+        );
+        let mut repository = AlignmentRepositoryEnum::new(&AlignmentPath::Bam {
+            path: path.to_string(),
+            index: format!("{path}.bai"),
+            source: BamSource::Local,
+        })
+        .await
+        .unwrap();
+        let mut contig_header = ContigHeader::new(crate::reference::Reference::NoReference);
+        contig_header.update_or_add_contig(
+            "MN908947.3".to_string(),
+            Some(29903),
+            Vec::new(),
+            crate::contig_header::ContigSource::Alignment,
+        );
+        let region = Region {
+            focus: Focus {
+                contig_index: 0,
+                position: 154,
+            },
+            half_width: 200,
+        };
+        let sequence = Sequence {
+            start: 1,
+            sequence: Vec::new(),
+            contig_index: 0,
+        };
+
+        // The covid BAM is small enough that the first query reads to the end of the file.
+        let mut read_counts = Vec::new();
+        for _ in 0..2 {
+            let alignment = repository
+                .read_alignment(
+                    &region,
+                    &sequence,
+                    &contig_header,
+                    &HiddenReadCategory::all(),
+                    usize::MAX,
+                )
+                .await
+                .unwrap();
+            read_counts.push(alignment.reads.len());
+        }
+        assert_eq!(read_counts, vec![95, 95]);
+    }
+
+    #[tokio::test]
+    async fn stdin_bam_stream_is_read_into_memory() {
+        let bytes = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tgv/tests/data/covid.sorted.bam"
+        ))
+        .unwrap();
+        let repository = StdinBamRepository::from_reader(bytes.as_slice())
+            .await
+            .unwrap();
+        assert_eq!(repository.records.len(), 95);
+
+        let (contig_name, start, end) = repository.span().unwrap();
+        assert_eq!(contig_name, "MN908947.3");
+        assert!(start <= end);
+
+        let region = |start: usize, end: usize| {
+            noodles::core::Region::new(
+                "MN908947.3",
+                noodles::core::Position::try_from(start).unwrap()
+                    ..=noodles::core::Position::try_from(end).unwrap(),
+            )
+        };
+        let reads = repository.query(&region(start as usize, end as usize));
+        assert_eq!(reads.len(), repository.records.len());
+        assert!(
+            repository
+                .query(&region(end as usize + 1, end as usize + 100))
+                .is_empty()
+        );
+        assert!(
+            repository
+                .query(&noodles::core::Region::new("chr1", ..))
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn stdin_stream_without_a_bam_header_is_an_io_error() {
+        let result = StdinBamRepository::from_reader(b"not a bam".as_slice()).await;
+        assert!(matches!(result, Err(TGVError::IOError(_))));
+    }
+
+    #[rstest]
+    #[case(0, 50, 100)]
+    #[case(0, 150, 150)]
+    #[case(0, 300, 200)]
+    #[case(1, 150, 100)]
+    fn read_span_keeps_the_focus_within_the_reads(
+        #[case] contig_index: usize,
+        #[case] position: u64,
+        #[case] expected: u64,
+    ) {
+        let read_span = ReadSpan {
+            contig_index: 0,
+            start: 100,
+            end: 200,
+        };
+        assert_eq!(
+            read_span.clamp(Focus {
+                contig_index,
+                position
+            }),
+            Focus {
+                contig_index: 0,
+                position: expected
+            }
+        );
+    }
+}
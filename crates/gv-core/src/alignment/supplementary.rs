@@ -0,0 +1,238 @@
+use crate::{alignment::AlignedRead, contig_header::ContigHeader, error::TGVError, strand::Strand};
+
+/// Another alignment of a split read, from an entry of the SA tag: `rname,pos,strand,CIGAR,mapQ,NM;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupplementaryAlignment {
+    pub contig_name: String,
+
+    /// Alignment start. 1-based.
+    pub position: u64,
+    pub strand: Strand,
+    pub cigar: String,
+}
+
+impl SupplementaryAlignment {
+    /// Reference bases covered by the cigar.
+    pub fn reference_span(&self) -> u64 {
+        let mut span = 0;
+        let mut length = 0;
+        for c in self.cigar.chars() {
+            match c {
+                '0'..='9' => length = length * 10 + c.to_digit(10).unwrap_or_default() as u64,
+                'M' | 'D' | 'N' | '=' | 'X' => {
+                    span += length;
+                    length = 0;
+                }
+                _ => length = 0,
+            }
+        }
+        span
+    }
+
+    /// Alignment end. 1-based, inclusive.
+    pub fn end(&self) -> u64 {
+        self.position + self.reference_span().max(1) - 1
+    }
+
+    pub fn to_locus_str(&self) -> String {
+        format!("{}:{} ({})", self.contig_name, self.position, self.strand)
+    }
+}
+
+/// Parse an SA tag value into its alignments. Empty entries, e.g. after the trailing semicolon, are skipped.
+pub fn parse_sa_tag(value: &str) -> Result<Vec<SupplementaryAlignment>, TGVError> {
+    value
+        .split(';')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let invalid =
+                || TGVError::AlignmentParseError(format!("Invalid SA tag entry `{entry}`"));
+            let fields = entry.trim().split(',').collect::<Vec<_>>();
+            let [contig_name, position, strand, cigar, ..] = fields.as_slice() else {
+                return Err(invalid());
+            };
+            let position = position.parse::<u64>().map_err(|_| invalid())?;
+            let strand = match *strand {
+                "+" => Strand::Forward,
+                "-" => Strand::Reverse,
+                _ => return Err(invalid()),
+            };
+            if contig_name.is_empty() || position == 0 || cigar.is_empty() {
+                return Err(invalid());
+            }
+            Ok(SupplementaryAlignment {
+                contig_name: contig_name.to_string(),
+                position,
+                strand,
+                cigar: cigar.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The clicked read and its other alignments, drawn as connectors while its details are pinned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitRead {
+    pub alignment_index: usize,
+    pub read_name: String,
+    pub contig_index: usize,
+
+    /// Alignment start of the clicked read. 1-based. Tells it apart from other alignments with the same name.
+    pub start: u64,
+
+    /// Alignments on the contig of the read.
+    pub linked: Vec<SupplementaryAlignment>,
+
+    /// Alignments on other contigs, or on contigs missing from the reference.
+    pub elsewhere: Vec<SupplementaryAlignment>,
+}
+
+impl SplitRead {
+    /// None if the read has no SA tag.
+    pub fn new(
+        alignment_index: usize,
+        contig_index: usize,
+        read: &AlignedRead,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Self>, TGVError> {
+        let alignments = read.supplementary_alignments()?;
+        if alignments.is_empty() {
+            return Ok(None);
+        }
+        let (linked, elsewhere) = alignments.into_iter().partition(|alignment| {
+            contig_header.resolve(&alignment.contig_name).ok() == Some(contig_index)
+        });
+        Ok(Some(Self {
+            alignment_index,
+            read_name: read
+                .record
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            contig_index,
+            start: read.start,
+            linked,
+            elsewhere,
+        }))
+    }
+
+    /// Whether the read is the clicked one.
+    pub fn is_read(&self, read: &AlignedRead) -> bool {
+        read.start == self.start
+            && read
+                .record
+                .name()
+                .is_some_and(|name| *name == *self.read_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn alignment(
+        contig_name: &str,
+        position: u64,
+        strand: Strand,
+        cigar: &str,
+    ) -> SupplementaryAlignment {
+        SupplementaryAlignment {
+            contig_name: contig_name.to_string(),
+            position,
+            strand,
+            cigar: cigar.to_string(),
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "MN908947.3,21552,+,69S73M,53,0;",
+        vec![alignment("MN908947.3", 21552, Strand::Forward, "69S73M")]
+    )]
+    #[case(
+        "chr5,1000,-,30M120H,60,2;chr12,2000,+,50S100M,0,0",
+        vec![
+            alignment("chr5", 1000, Strand::Reverse, "30M120H"),
+            alignment("chr12", 2000, Strand::Forward, "50S100M"),
+        ]
+    )]
+    #[case("", vec![])]
+    fn sa_tags_are_parsed(#[case] value: &str, #[case] expected: Vec<SupplementaryAlignment>) {
+        assert_eq!(parse_sa_tag(value).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("chr5,1000,-;")]
+    #[case("chr5,x,-,30M,60,2;")]
+    #[case("chr5,1000,*,30M,60,2;")]
+    #[case(",1000,+,30M,60,2;")]
+    fn invalid_sa_tags_are_errors(#[case] value: &str) {
+        assert!(parse_sa_tag(value).is_err());
+    }
+
+    #[test]
+    fn split_reads_link_alignments_on_the_same_contig() {
+        use crate::contig_header::ContigSource;
+        use crate::reference::Reference;
+        use noodles::sam::alignment::{
+            RecordBuf,
+            record::{
+                cigar::{Op, op::Kind},
+                data::field::Tag,
+            },
+            record_buf::data::field::Value,
+        };
+
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        for contig_name in ["chr1", "chr2"] {
+            contig_header.update_or_add_contig(
+                contig_name.to_string(),
+                Some(10_000),
+                Vec::new(),
+                ContigSource::Sequence,
+            );
+        }
+        let record = RecordBuf::builder()
+            .set_name("r0")
+            .set_alignment_start(noodles::core::Position::try_from(100).unwrap())
+            .set_cigar([Op::new(Kind::Match, 10)].into_iter().collect())
+            .set_data(
+                [(
+                    Tag::OTHER_ALIGNMENTS,
+                    Value::from("chr1,5000,-,10M,60,0;2,300,+,10M,60,0;chrUn,1,+,10M,0,0;"),
+                )]
+                .into_iter()
+                .collect(),
+            )
+            .build();
+        let read = AlignedRead::try_from(record).unwrap();
+
+        let split_read = SplitRead::new(0, 0, &read, &contig_header)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            split_read.linked,
+            vec![alignment("chr1", 5000, Strand::Reverse, "10M")]
+        );
+        assert_eq!(
+            split_read.elsewhere,
+            vec![
+                alignment("2", 300, Strand::Forward, "10M"),
+                alignment("chrUn", 1, Strand::Forward, "10M"),
+            ]
+        );
+        assert!(split_read.is_read(&read));
+    }
+
+    #[rstest]
+    #[case("69S73M", 73)]
+    #[case("10M2I5M3D4N6=1X20S", 29)]
+    #[case("30H", 0)]
+    fn reference_span_counts_reference_bases(#[case] cigar: &str, #[case] expected: u64) {
+        assert_eq!(
+            alignment("chr1", 1, Strand::Forward, cigar).reference_span(),
+            expected
+        );
+    }
+}
@@ -2,6 +2,8 @@ use crate::{
     contig_header::ContigHeader,
     error::TGVError,
     intervals::{GenomeInterval, SortedIntervalCollection},
+    strand::Strand,
+    tracks::blocks_to_exons,
 };
 use noodles::bed::{self};
 
@@ -16,6 +18,23 @@ pub struct BedInterval {
     start: u64,
     end: u64,
 
+    /// Name (column 4).
+    pub name: Option<String>,
+
+    /// Feature color (itemRgb, column 9). None if the column is missing or "0".
+    pub item_rgb: Option<(u8, u8, u8)>,
+
+    /// Strand (column 6). None if the column is missing or ".".
+    pub strand: Option<Strand>,
+
+    /// Thick part (thickStart and thickEnd, columns 7-8). None if the columns are missing.
+    /// 1-based, inclusive. Empty if thickStart equals thickEnd.
+    thick: Option<(u64, u64)>,
+
+    /// Blocks (columns 10-12). Empty if the columns are missing or malformed.
+    /// 1-based, inclusive.
+    blocks: Vec<(u64, u64)>,
+
     record: bed::Record<3>,
 }
 
@@ -26,26 +45,176 @@ impl BedInterval {
         contig_header: &ContigHeader,
     ) -> Result<Self, TGVError> {
         let start = record.feature_start()?.get() as u64; // Noodles already converted to 1-based, inclusive
+        let other_fields = record.other_fields();
+        let name = other_fields
+            .get(0)
+            .map(|name| name.to_string())
+            .filter(|name| !name.is_empty() && name != ".");
+        let item_rgb = other_fields
+            .get(5)
+            .and_then(|item_rgb| parse_item_rgb(&item_rgb.to_string()));
+        let strand = other_fields
+            .get(2)
+            .and_then(|strand| Strand::from_str(strand.to_string()).ok());
+        let end = match record.feature_end() {
+            Some(end) => end?.get() as u64,
+            None => start, // BED end is 0-based, exclusive
+        };
+        let field = |i: usize| other_fields.get(i).map(|field| field.to_string());
+        let thick = match (field(3), field(4)) {
+            (Some(thick_start), Some(thick_end)) => parse_thick(&thick_start, &thick_end),
+            _ => None,
+        };
+        let blocks = match (field(6), field(7), field(8)) {
+            (Some(block_count), Some(block_sizes), Some(block_starts)) => {
+                parse_blocks(&block_count, &block_sizes, &block_starts, start, end)
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
         Ok(Self {
             contig_index: contig_header
                 .try_get_index_by_str(&record.reference_sequence_name().to_string())?,
             index,
             start, // BED start is 0-based, inclusive
-            end: match record.feature_end() {
-                Some(end) => end?.get() as u64,
-                None => start, // BED end is 0-based, exclusive
-            },
+            end,
+            name,
+            item_rgb,
+            strand,
+            thick,
+            blocks,
             record,
         })
     }
 
     pub fn describe(&self) -> String {
-        format!(
-            "BED interval: {}:{}-{}",
-            self.record.reference_sequence_name(),
-            self.start,
-            self.end
-        )
+        match &self.name {
+            Some(name) => format!(
+                "BED interval: {}:{}-{} {}",
+                self.record.reference_sequence_name(),
+                self.start,
+                self.end,
+                name
+            ),
+            None => format!(
+                "BED interval: {}:{}-{}",
+                self.record.reference_sequence_name(),
+                self.start,
+                self.end
+            ),
+        }
+    }
+
+    /// Parts of the feature as drawn, in order. Blocks are split into thick and thin parts by thickStart and thickEnd,
+    /// and joined by gaps. Features without blocks are one block. Features without thickStart and thickEnd are thick.
+    pub fn segments(&self) -> Vec<BedSegment> {
+        let blocks = if self.blocks.is_empty() {
+            vec![(self.start, self.end)]
+        } else {
+            self.blocks.clone()
+        };
+        let (thick_start, thick_end) = self.thick.unwrap_or((self.start, self.end));
+
+        let mut segments = Vec::new();
+        let mut previous_end: Option<u64> = None;
+        for (start, end) in blocks {
+            if let Some(previous_end) = previous_end
+                && previous_end + 1 < start
+            {
+                segments.push(BedSegment {
+                    start: previous_end + 1,
+                    end: start - 1,
+                    kind: BedSegmentKind::Gap,
+                });
+            }
+            previous_end = Some(end);
+
+            let parts = [
+                (start, u64::min(end, thick_start - 1), BedSegmentKind::Thin),
+                (
+                    u64::max(start, thick_start),
+                    u64::min(end, thick_end),
+                    BedSegmentKind::Thick,
+                ),
+                (u64::max(start, thick_end + 1), end, BedSegmentKind::Thin),
+            ];
+            segments.extend(
+                parts
+                    .into_iter()
+                    .filter(|(start, end, _)| start <= end)
+                    .map(|(start, end, kind)| BedSegment { start, end, kind }),
+            );
+        }
+
+        segments
+    }
+}
+
+/// Kind of a drawn part of a BED feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BedSegmentKind {
+    /// Between thickStart and thickEnd.
+    Thick,
+
+    /// Outside thickStart and thickEnd, e.g. UTRs.
+    Thin,
+
+    /// Between two blocks, e.g. introns.
+    Gap,
+}
+
+/// Drawn part of a BED feature.
+/// 1-based, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BedSegment {
+    pub start: u64,
+    pub end: u64,
+    pub kind: BedSegmentKind,
+}
+
+/// Parse thickStart and thickEnd (0-based, half-open) to 1-based, inclusive coordinates. Malformed values are ignored.
+fn parse_thick(thick_start: &str, thick_end: &str) -> Option<(u64, u64)> {
+    let thick_start = thick_start.trim().parse::<u64>().ok()?;
+    let thick_end = thick_end.trim().parse::<u64>().ok()?;
+    (thick_start <= thick_end).then_some((thick_start + 1, thick_end))
+}
+
+/// Parse blockCount, blockSizes, and blockStarts to 1-based, inclusive coordinates. None if the values are malformed,
+/// or the blocks are unsorted or outside the feature.
+fn parse_blocks(
+    block_count: &str,
+    block_sizes: &str,
+    block_starts: &str,
+    start: u64,
+    end: u64,
+) -> Option<Vec<(u64, u64)>> {
+    let block_count = block_count.trim().parse::<usize>().ok()?;
+    let (block_starts, block_ends) = blocks_to_exons(start - 1, block_sizes, block_starts).ok()?;
+    if block_count == 0 || block_starts.len() != block_count {
+        return None;
+    }
+
+    let blocks = block_starts
+        .into_iter()
+        .zip(block_ends)
+        .filter(|(block_start, block_end)| block_start < block_end)
+        .map(|(block_start, block_end)| (block_start + 1, block_end))
+        .collect::<Vec<_>>();
+    let valid = !blocks.is_empty()
+        && blocks.iter().all(|(_, block_end)| *block_end <= end)
+        && blocks.windows(2).all(|pair| pair[0].1 < pair[1].0);
+    valid.then_some(blocks)
+}
+
+/// Parse an itemRgb value, e.g. "255,0,0". "0" and malformed values have no color.
+pub fn parse_item_rgb(item_rgb: &str) -> Option<(u8, u8, u8)> {
+    let channels = item_rgb
+        .split(',')
+        .map(|channel| channel.trim().parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()?;
+    match channels.as_slice() {
+        [red, green, blue] => Some((*red, *green, *blue)),
+        _ => None,
     }
 }
 
@@ -103,3 +272,143 @@ impl BedRepository {
         SortedIntervalCollection::new(records)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{contig_header::ContigSource, reference::Reference};
+    use rstest::rstest;
+    use std::io::Write;
+
+    #[rstest]
+    #[case("255,0,0", Some((255, 0, 0)))]
+    #[case("0,128,255", Some((0, 128, 255)))]
+    #[case(" 12, 34 ,56", Some((12, 34, 56)))]
+    #[case("0", None)]
+    #[case("", None)]
+    #[case(".", None)]
+    #[case("255,0", None)]
+    #[case("256,0,0", None)]
+    #[case("255,0,0,0", None)]
+    fn test_parse_item_rgb(#[case] item_rgb: &str, #[case] expected: Option<(u8, u8, u8)>) {
+        assert_eq!(parse_item_rgb(item_rgb), expected);
+    }
+
+    fn read_interval(line: &str) -> BedInterval {
+        let mut bed_file = tempfile::NamedTempFile::with_suffix(".bed").unwrap();
+        writeln!(bed_file, "{}", line).unwrap();
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        contig_header.update_or_add_contig(
+            "chr1".to_string(),
+            Some(1000),
+            Vec::new(),
+            ContigSource::Sequence,
+        );
+
+        BedRepository {
+            bed_path: bed_file.path().to_string_lossy().to_string(),
+        }
+        .read_bed(&contig_header)
+        .unwrap()
+        .intervals
+        .remove(0)
+    }
+
+    fn segment(start: u64, end: u64, kind: BedSegmentKind) -> BedSegment {
+        BedSegment { start, end, kind }
+    }
+
+    #[rstest]
+    #[case("chr1\t100\t200", None, vec![segment(101, 200, BedSegmentKind::Thick)])]
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t-",
+        Some(Strand::Reverse),
+        vec![segment(101, 200, BedSegmentKind::Thick)]
+    )]
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t+\t120\t180",
+        Some(Strand::Forward),
+        vec![
+            segment(101, 120, BedSegmentKind::Thin),
+            segment(121, 180, BedSegmentKind::Thick),
+            segment(181, 200, BedSegmentKind::Thin),
+        ]
+    )]
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t.\t100\t100",
+        None,
+        vec![segment(101, 200, BedSegmentKind::Thin)]
+    )]
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t+\t110\t190\t0\t3\t20,30,10,\t0,40,90,",
+        Some(Strand::Forward),
+        vec![
+            segment(101, 110, BedSegmentKind::Thin),
+            segment(111, 120, BedSegmentKind::Thick),
+            segment(121, 140, BedSegmentKind::Gap),
+            segment(141, 170, BedSegmentKind::Thick),
+            segment(171, 190, BedSegmentKind::Gap),
+            segment(191, 200, BedSegmentKind::Thin),
+        ]
+    )]
+    // Malformed blocks fall back to one block.
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t+\t100\t200\t0\t2\t20,30\t0",
+        Some(Strand::Forward),
+        vec![segment(101, 200, BedSegmentKind::Thick)]
+    )]
+    #[case(
+        "chr1\t100\t200\tgene1\t0\t+\t100\t200\t0\t2\t20,30\t0,150",
+        Some(Strand::Forward),
+        vec![segment(101, 200, BedSegmentKind::Thick)]
+    )]
+    fn test_segments(
+        #[case] line: &str,
+        #[case] expected_strand: Option<Strand>,
+        #[case] expected: Vec<BedSegment>,
+    ) {
+        let interval = read_interval(line);
+        assert_eq!(interval.strand, expected_strand);
+        assert_eq!(interval.segments(), expected);
+    }
+
+    #[test]
+    fn read_bed_parses_names_and_item_rgb() {
+        let mut bed_file = tempfile::NamedTempFile::with_suffix(".bed").unwrap();
+        write!(
+            bed_file,
+            "chr1\t10\t20\n\
+             chr1\t30\t40\tpeak1\n\
+             chr1\t50\t60\tpeak2\t0\t+\t50\t60\t0\n\
+             chr1\t70\t80\tpeak3\t0\t+\t70\t80\t255,0,0\n"
+        )
+        .unwrap();
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        contig_header.update_or_add_contig(
+            "chr1".to_string(),
+            Some(100),
+            Vec::new(),
+            ContigSource::Sequence,
+        );
+
+        let bed_track = BedRepository {
+            bed_path: bed_file.path().to_string_lossy().to_string(),
+        }
+        .read_bed(&contig_header)
+        .unwrap();
+
+        assert_eq!(
+            bed_track
+                .intervals
+                .iter()
+                .map(|interval| (interval.name.as_deref(), interval.item_rgb))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, None),
+                (Some("peak1"), None),
+                (Some("peak2"), None),
+                (Some("peak3"), Some((255, 0, 0))),
+            ]
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use crate::{
+    contig_header::{Contig, ContigHeader},
+    error::TGVError,
+    intervals::{GenomeInterval, Region},
+};
+use bigtools::{BigWigRead, Value, utils::reopen::ReopenableFile};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Signal value over an interval of a bigWig file.
+/// 1-based, inclusive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalInterval {
+    pub start: u64,
+    pub end: u64,
+    pub value: f32,
+}
+
+/// bigWig signal in a loaded region.
+#[derive(Debug, Clone, Default)]
+pub struct BigWigTrack {
+    /// Loaded region. None if no data have been loaded.
+    region: Option<Region>,
+
+    /// Sorted by start. bigWig intervals do not overlap.
+    pub intervals: Vec<SignalInterval>,
+}
+
+impl BigWigTrack {
+    pub fn new(region: Region, intervals: Vec<SignalInterval>) -> Self {
+        let mut intervals = intervals;
+        intervals.sort_by_key(|interval| interval.start);
+        Self {
+            region: Some(region),
+            intervals,
+        }
+    }
+
+    pub fn has_complete_data(&self, region: &Region) -> bool {
+        self.region.as_ref().is_some_and(|loaded| {
+            loaded.contig_index() == region.contig_index()
+                && loaded.start() <= region.start()
+                && loaded.end() >= region.end()
+        })
+    }
+
+    /// Maximum signal in [left, right]. Positions without data are zero.
+    /// 1-based, inclusive.
+    pub fn max_value(&self, left: u64, right: u64) -> f32 {
+        self.value_range(left, right).1
+    }
+
+    /// Minimum and maximum signal in [left, right]. Positions without data are zero.
+    /// 1-based, inclusive.
+    pub fn value_range(&self, left: u64, right: u64) -> (f32, f32) {
+        let first = self
+            .intervals
+            .partition_point(|interval| interval.end < left);
+        // Start of the first position not covered by the intervals so far.
+        let mut covered_until = left;
+        let mut has_gap = false;
+        let mut range: Option<(f32, f32)> = None;
+        for interval in self.intervals[first..]
+            .iter()
+            .take_while(|interval| interval.start <= right)
+        {
+            has_gap |= interval.start > covered_until;
+            covered_until = u64::max(covered_until, interval.end + 1);
+            range = Some(match range {
+                Some((min, max)) => (min.min(interval.value), max.max(interval.value)),
+                None => (interval.value, interval.value),
+            });
+        }
+        has_gap |= covered_until <= right;
+
+        match range {
+            Some((min, max)) if has_gap => (min.min(0.0), max.max(0.0)),
+            Some(range) => range,
+            None => (0.0, 0.0),
+        }
+    }
+}
+
+/// bigWig file opened for region queries.
+pub struct BigWigRepository {
+    pub bigwig_path: String,
+
+    reader: Box<BigWigRead<ReopenableFile>>,
+
+    /// Contig name in the bigWig file -> contig length.
+    chrom_lengths: HashMap<String, u32>,
+}
+
+impl std::fmt::Debug for BigWigRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BigWigRepository")
+            .field("bigwig_path", &self.bigwig_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BigWigRepository {
+    pub fn new(bigwig_path: &str) -> Result<Self, TGVError> {
+        let reader = Box::new(BigWigRead::open_file(bigwig_path)?);
+        let chrom_lengths = reader
+            .chroms()
+            .iter()
+            .map(|chrom_info| (chrom_info.name.clone(), chrom_info.length))
+            .collect();
+
+        Ok(Self {
+            bigwig_path: bigwig_path.to_string(),
+            reader,
+            chrom_lengths,
+        })
+    }
+
+    /// Name of the contig in the bigWig file. None if the file has no signal on the contig.
+    fn chrom_name(&self, contig: &Contig) -> Option<String> {
+        std::iter::once(&contig.name)
+            .chain(contig.aliases.iter())
+            .find(|name| self.chrom_lengths.contains_key(*name))
+            .cloned()
+    }
+
+    pub fn read_signal(
+        &mut self,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<BigWigTrack, TGVError> {
+        let Some(chrom_name) = self.chrom_name(contig_header.try_get(region.contig_index())?)
+        else {
+            return Ok(BigWigTrack::new(region.clone(), Vec::new()));
+        };
+
+        // bigWig coordinates are 0-based, half-open.
+        let start = u64::min(region.start() - 1, u32::MAX as u64) as u32;
+        let end = u32::min(
+            u64::min(region.end(), u32::MAX as u64) as u32,
+            self.chrom_lengths[&chrom_name],
+        );
+        if start >= end {
+            return Ok(BigWigTrack::new(region.clone(), Vec::new()));
+        }
+
+        let started = Instant::now();
+        let values = self
+            .reader
+            .get_interval(&chrom_name, start, end)?
+            .collect::<Result<Vec<Value>, _>>()?;
+        log::info!(
+            "bigWig query: path={} contig={} start={} end={} intervals={} elapsed_ms={}",
+            self.bigwig_path,
+            chrom_name,
+            start,
+            end,
+            values.len(),
+            started.elapsed().as_millis()
+        );
+
+        Ok(BigWigTrack::new(
+            region.clone(),
+            values
+                .into_iter()
+                .map(|value| SignalInterval {
+                    start: value.start as u64 + 1,
+                    end: value.end as u64,
+                    value: value.value,
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{intervals::Focus, reference::Reference};
+    use rstest::rstest;
+
+    fn interval(start: u64, end: u64, value: f32) -> SignalInterval {
+        SignalInterval { start, end, value }
+    }
+
+    fn region(contig_index: usize, position: u64, half_width: u64) -> Region {
+        Region {
+            focus: Focus {
+                contig_index,
+                position,
+            },
+            half_width,
+        }
+    }
+
+    #[rstest]
+    #[case(1, 10, 2.0)]
+    #[case(11, 20, 0.0)]
+    #[case(5, 25, 3.5)]
+    #[case(31, 40, 1.0)]
+    #[case(100, 200, 0.0)]
+    fn test_max_value(#[case] left: u64, #[case] right: u64, #[case] expected: f32) {
+        let track = BigWigTrack::new(
+            region(0, 100, 100),
+            vec![
+                interval(31, 40, 1.0),
+                interval(1, 10, 2.0),
+                interval(21, 30, 3.5),
+            ],
+        );
+        assert_eq!(track.max_value(left, right), expected);
+    }
+
+    #[rstest]
+    #[case(1, 10, (-2.0, -2.0))]
+    #[case(5, 15, (-2.0, 3.0))]
+    #[case(25, 35, (-1.0, 0.0))]
+    #[case(31, 40, (-1.0, -1.0))]
+    #[case(41, 50, (0.0, 0.0))]
+    fn test_value_range(#[case] left: u64, #[case] right: u64, #[case] expected: (f32, f32)) {
+        let track = BigWigTrack::new(
+            region(0, 100, 100),
+            vec![
+                interval(31, 40, -1.0),
+                interval(1, 10, -2.0),
+                interval(11, 20, 3.0),
+            ],
+        );
+        assert_eq!(track.value_range(left, right), expected);
+    }
+
+    #[rstest]
+    #[case(region(0, 100, 10), true)]
+    #[case(region(0, 100, 50), true)]
+    #[case(region(0, 100, 51), false)]
+    #[case(region(1, 100, 10), false)]
+    fn test_has_complete_data(#[case] query: Region, #[case] expected: bool) {
+        let track = BigWigTrack::new(region(0, 100, 50), Vec::new());
+        assert_eq!(track.has_complete_data(&query), expected);
+        assert!(!BigWigTrack::default().has_complete_data(&query));
+    }
+
+    #[test]
+    fn read_signal_from_bigwig() {
+        let mut repository = BigWigRepository::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tgv/tests/data/covid.signal.bw"
+        ))
+        .unwrap();
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        contig_header.update_or_add_contig(
+            "MN908947.3".to_string(),
+            Some(29903),
+            Vec::new(),
+            crate::contig_header::ContigSource::Alignment,
+        );
+
+        let track = repository
+            .read_signal(&region(0, 150, 50), &contig_header)
+            .unwrap();
+        assert_eq!(
+            track.intervals,
+            vec![interval(101, 150, 5.0), interval(151, 200, 10.0)]
+        );
+        assert_eq!(track.max_value(120, 160), 10.0);
+        // The gap between intervals has no data.
+        assert_eq!(track.max_value(201, 300), 0.0);
+    }
+}
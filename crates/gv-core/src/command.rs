@@ -1,22 +1,80 @@
 use crate::{
+    cytoband::split_band,
     error::TGVError,
-    message::{AlignmentDisplayOption, AlignmentFilter, AlignmentSort, Message, Movement},
+    genetic_code::GeneticCode,
+    message::{
+        AlignmentColor, AlignmentDisplayOption, AlignmentFilter, AlignmentGroup, AlignmentSort,
+        CoordinateBase, CursorAnchor, Downsample, GeneFrame, IsolatedAllele, Message, Movement,
+    },
+    sequence::Motif,
+    strand::Strand,
+    variant::{QualThreshold, VariantFilter},
 };
 use nom::{
     IResult, Parser,
     branch::alt,
-    bytes::complete::{tag, tag_no_case},
-    character::complete::{char, multispace0, u64},
-    combinator::{opt, value},
+    bytes::complete::{tag, tag_no_case, take_while_m_n},
+    character::complete::{char, multispace0, multispace1, u64},
+    combinator::{map, opt, value},
     multi::{many0, separated_list0},
     sequence::{delimited, preceded, separated_pair, terminated},
 };
 
 /// Supported commands:
 /// :q: Quit.
+/// :session save [NAME]: Save the session, as :w [NAME].
 /// :h: Help.
 /// :1234: Go to position 1234 on the same contig.
 /// :12:1234: Go to position 1234 on contig 12.
+/// :track list: List available gene tracks.
+/// :track use NAME: Use gene track NAME. :track NAME for short.
+/// :aa code NAME: Use genetic code NAME (standard, vertmito, invertmito, or auto).
+/// :codon: Translate the codon at the cursor.
+/// :whatgene: Report the gene and the exon, intron, UTR, or CDS at the cursor, or the distance to the nearest gene.
+/// :indels: Show a histogram of insertion and deletion lengths of the shown reads in view.
+/// :filter clear: Remove all alignment filters. Filters otherwise stack: reads must pass all of them.
+/// :goto GENE:p.NNN: Go to the codon of protein residue NNN of GENE.
+/// :goto GENE +2kb: Frame GENE with 2 kb on each side. :goto GENE 150%: Frame GENE in a window 150% of its length.
+/// :goto 17q21.31: Frame cytoband q21.31 of contig 17. :goto 17q21 frames q21 and its sub-bands.
+/// :gene PREFIX: Pick from genes whose names contain PREFIX, prefix matches first.
+/// :masking on: Dim soft-masked (lowercase) reference bases. :masking off to show them like other bases.
+/// :repeats: Toggle underlining homopolymers and short tandem repeats (period <= 6) in the sequence row. :repeats N
+/// underlines repeats of at least N bp, :repeats off turns the highlight off.
+/// :cpg: Toggle marking CG dinucleotides in the sequence row.
+/// :revcomp: Toggle showing the reference and read bases complemented, without reversing the view.
+/// :ruler 10kb: Put major ruler ticks every 10 kb (bp, kb, and mb units). :ruler auto to space them by the zoom.
+/// :coords 0: Show 0-based, half-open coordinates on the ruler and in the status bar. :coords 1 for 1-based.
+/// :cov-max N: Cap the coverage axis at depth N. :cov-max auto to scale to the maximum depth in view.
+/// :downsample N: Display at most N reads per column. :downsample auto to fit the track height, :downsample off to
+/// display all reads.
+/// :view pairs: View reads as pairs, coloring the gap by insert size. :view linear to view reads one by one.
+/// :mapq N: Hide reads with MAPQ below N. Reads without MAPQ are kept. :mapq 0 to reset.
+/// :mismatch-qual N: Show mismatched read bases with quality below N as matches. :mismatch-qual 0 to reset.
+/// :colorby tag HP: Color reads by the value of tag HP. :color default to reset.
+/// :group orientation: Group reads by pair orientation. :group default to reset.
+/// :group hp: Group reads by the HP tag of phased reads: HP=1, HP=2, ..., then unphased reads.
+/// :sort base: Sort reads by the base at the cursor: A, C, G, T, then deletions. :sort none to reset.
+/// :show hidden: Include unmapped, secondary, supplementary, and duplicate reads.
+/// :show CATEGORY: Include reads of CATEGORY (e.g. supplementary). :hide CATEGORY to hide them again.
+/// :support: Highlight reads carrying the alternate allele of the variant nearest to the cursor.
+/// :support clear: Stop highlighting variant-supporting reads.
+/// :isolate alt / :isolate ref: Show only reads carrying the alternate / reference allele of the variant at the cursor.
+/// :isolate off to show all reads again.
+/// :vcf-filter [PASS] [qual>N] [missing-qual=show|hide]: Hide variants failing FILTER or QUAL. :vcf-filter clear to
+/// reset.
+/// :vcf-info AF,DP: List INFO fields AF and DP in the variant popup. :vcf-info all to list all fields.
+/// :export-reads PATH: Write the shown reads in view to a BAM file.
+/// :export-cov PATH [BIN]: Write the coverage in view to a bedGraph file, per base or in bins of BIN bases.
+/// :seq [-] [PATH]: Write the reference sequence in view as FASTA, reverse-complemented with -. Printed on exit without
+/// a path.
+/// :mark NAME: Save the focus as a bookmark. :goto @NAME: Jump to the bookmark.
+/// :loci next / :loci prev: Go to the next / previous locus of the locus list, as n / N.
+/// :find ACGT: Go to the next match of ACGT on the contig after the cursor. :find -r ACGT also matches the reverse
+/// complement. n / N repeat the search until :find clear.
+/// :peak: Go to the highest coverage peak of the contig. :peak next visits the next peak in descending coverage.
+/// :reload: Re-open the alignment, variant, BED, and bigWig files from disk, e.g. after a pipeline rewrote them.
+/// :center: Pan the view so that the cursor is centered, as gz. :center start / :center end put the cursor at the left
+/// / right edge.
 pub fn parse(input: &str) -> Result<Vec<Message>, TGVError> {
     if input == "q" {
         return Ok(vec![Message::Quit]);
@@ -30,12 +88,407 @@ pub fn parse(input: &str) -> Result<Vec<Message>, TGVError> {
         return Ok(vec![message]);
     }
 
+    if let Some(message) = parse_session_command(input, "session save", Message::SaveSession) {
+        return Ok(vec![message]);
+    }
+
+    if input == "track list" {
+        return Ok(vec![Message::ListTracks]);
+    }
+
+    if let Some(track_name) = input
+        .strip_prefix("track use ")
+        .or(input.strip_prefix("track "))
+    {
+        let track_name = track_name.trim();
+        if track_name.is_empty() || track_name == "use" {
+            return Err(TGVError::RegisterError(
+                "Usage: track use TRACK_NAME".to_string(),
+            ));
+        }
+        return Ok(vec![Message::UseTrack(track_name.to_string())]);
+    }
+
+    if let Some(code_name) = input.strip_prefix("aa code ") {
+        let genetic_code = match code_name.trim() {
+            "auto" => None,
+            code_name => Some(code_name.parse::<GeneticCode>()?),
+        };
+        return Ok(vec![Message::SetGeneticCode(genetic_code)]);
+    }
+
+    if input == "codon" {
+        return Ok(vec![Message::LookupCodon]);
+    }
+
+    if input == "whatgene" {
+        return Ok(vec![Message::LookupGene]);
+    }
+
+    if input == "indels" {
+        return Ok(vec![Message::ShowIndelHistogram]);
+    }
+
+    if input == "reload" {
+        return Ok(vec![Message::ReloadFiles]);
+    }
+
+    if let Some(pattern) = input.strip_prefix("gene ") {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(TGVError::RegisterError("Usage: gene PREFIX".to_string()));
+        }
+        return Ok(vec![Message::FindGene(pattern.to_string())]);
+    }
+
     if input == "h" {
         return Err(TGVError::RegisterError(
             "TODO: help screen is not implemented".to_string(),
         ));
     }
 
+    if let Some(direction) = input.strip_prefix("loci ") {
+        return match direction.trim() {
+            "next" => Ok(vec![Message::Move(Movement::NextLocus(1))]),
+            "prev" => Ok(vec![Message::Move(Movement::PreviousLocus(1))]),
+            _ => Err(TGVError::RegisterError(
+                "Usage: loci next or loci prev".to_string(),
+            )),
+        };
+    }
+
+    if let Some(pattern) = input.strip_prefix("find ") {
+        let usage = || TGVError::RegisterError("Usage: find [-r] ACGT, or find clear".to_string());
+        let motif = match pattern.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["clear"] => return Ok(vec![Message::SetMotif(None)]),
+            [bases] => Motif::new(bases, false),
+            ["-r", bases] => Motif::new(bases, true),
+            _ => return Err(usage()),
+        }?;
+        return Ok(vec![
+            Message::SetMotif(Some(motif)),
+            Message::Move(Movement::NextMatches(1)),
+        ]);
+    }
+
+    match input {
+        "peak" => return Ok(vec![Message::Move(Movement::HighestPeak)]),
+        "peak next" => return Ok(vec![Message::Move(Movement::NextPeaks(1))]),
+        _ if input.starts_with("peak ") => {
+            return Err(TGVError::RegisterError(
+                "Usage: peak or peak next".to_string(),
+            ));
+        }
+        _ => {}
+    }
+
+    if input == "center" {
+        return Ok(vec![Message::CenterCursor(CursorAnchor::Center)]);
+    }
+
+    if let Some(anchor) = input.strip_prefix("center ") {
+        let anchor = match anchor.trim() {
+            "start" => CursorAnchor::Start,
+            "end" => CursorAnchor::End,
+            _ => {
+                return Err(TGVError::RegisterError(
+                    "Usage: center, center start, or center end".to_string(),
+                ));
+            }
+        };
+        return Ok(vec![Message::CenterCursor(anchor)]);
+    }
+
+    if let Some(name) = input.strip_prefix("goto @") {
+        let name = parse_bookmark_name(name, "Usage: goto @NAME")?;
+        return Ok(vec![Message::Move(Movement::Bookmark(name))]);
+    }
+
+    if let Some(name) = input
+        .strip_prefix("mark")
+        .filter(|name| name.is_empty() || name.starts_with(' '))
+    {
+        let name = parse_bookmark_name(name, "Usage: mark NAME")?;
+        return Ok(vec![Message::SetBookmark(name)]);
+    }
+
+    if let Some((gene_name, frame)) = input
+        .strip_prefix("goto ")
+        .and_then(|target| target.trim().split_once(' '))
+    {
+        let usage =
+            || TGVError::RegisterError("Usage: goto GENE +FLANK or goto GENE PERCENT%".to_string());
+        let frame = frame.trim();
+        let frame = if let Some(flank) = frame.strip_prefix('+') {
+            GeneFrame::Flank(parse_length(flank).ok_or_else(usage)?)
+        } else if let Some(percent) = frame.strip_suffix('%') {
+            GeneFrame::Percent(
+                percent
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|percent| *percent > 0)
+                    .ok_or_else(usage)?,
+            )
+        } else {
+            return Err(usage());
+        };
+        return Ok(vec![Message::FrameGene(gene_name.to_string(), frame)]);
+    }
+
+    if let Some((contig, band)) = input
+        .strip_prefix("goto ")
+        .and_then(|target| split_band(target.trim()))
+    {
+        if contig.is_empty() {
+            return Err(TGVError::RegisterError(format!(
+                "Band {band} is ambiguous without a contig. Usage: goto CONTIGBAND, e.g. goto 17{band}"
+            )));
+        }
+        return Ok(vec![Message::FrameBand(
+            contig.to_string(),
+            band.to_string(),
+        )]);
+    }
+
+    if let Some(target) = input.strip_prefix("goto ") {
+        let usage = || TGVError::RegisterError("Usage: goto GENE:p.RESIDUE".to_string());
+        let (gene_name, residue) = target.trim().split_once(":p.").ok_or_else(usage)?;
+        let residue = residue.parse::<u64>().map_err(|_| usage())?;
+        if gene_name.is_empty() {
+            return Err(usage());
+        }
+        return Ok(vec![Message::Move(Movement::GeneResidue(
+            gene_name.to_string(),
+            residue,
+        ))]);
+    }
+
+    if input == "show hidden" {
+        return Ok(vec![Message::ShowHiddenReads]);
+    }
+
+    if let Some(category) = input.strip_prefix("show ") {
+        return Ok(vec![Message::ShowReads(category.trim().parse()?)]);
+    }
+
+    if let Some(category) = input.strip_prefix("hide ") {
+        return Ok(vec![Message::HideReads(category.trim().parse()?)]);
+    }
+
+    if input == "support" {
+        return Ok(vec![Message::HighlightVariantSupport]);
+    }
+
+    if input == "support clear" {
+        return Ok(vec![Message::ClearVariantSupport]);
+    }
+
+    if let Some(allele) = input.strip_prefix("isolate") {
+        return match allele.trim() {
+            "alt" => Ok(vec![Message::IsolateAllele(Some(
+                IsolatedAllele::Alternate,
+            ))]),
+            "ref" => Ok(vec![Message::IsolateAllele(Some(
+                IsolatedAllele::Reference,
+            ))]),
+            "off" => Ok(vec![Message::IsolateAllele(None)]),
+            _ => Err(TGVError::RegisterError(
+                "Usage: isolate alt, isolate ref, or isolate off".to_string(),
+            )),
+        };
+    }
+
+    if let Some(arguments) = input.strip_prefix("export-cov") {
+        let usage = || TGVError::RegisterError("Usage: export-cov PATH.bedgraph [BIN]".to_string());
+        let (path, bin_size) = match arguments.split_whitespace().collect::<Vec<_>>()[..] {
+            [path] => (path, 1),
+            [path, bin_size] => (
+                path,
+                bin_size
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|bin_size| *bin_size > 0)
+                    .ok_or_else(usage)?,
+            ),
+            _ => return Err(usage()),
+        };
+        return Ok(vec![Message::ExportCoverage(path.to_string(), bin_size)]);
+    }
+
+    if let Some(path) = input.strip_prefix("export-reads") {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err(TGVError::RegisterError(
+                "Usage: export-reads PATH.bam".to_string(),
+            ));
+        }
+        return Ok(vec![Message::ExportReads(path.to_string())]);
+    }
+
+    if let Some(fields) = input.strip_prefix("vcf-info ") {
+        let fields = fields
+            .split(',')
+            .map(|field| field.trim().to_string())
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<_>>();
+        return match fields.as_slice() {
+            [] => Err(TGVError::RegisterError(
+                "Usage: vcf-info FIELD,FIELD,... or vcf-info all".to_string(),
+            )),
+            [field] if field == "all" => Ok(vec![Message::SetVariantInfoFields(None)]),
+            _ => Ok(vec![Message::SetVariantInfoFields(Some(fields))]),
+        };
+    }
+
+    if let Some(arguments) = input.strip_prefix("vcf-filter")
+        && (arguments.is_empty() || arguments.starts_with(' '))
+    {
+        return Ok(vec![Message::SetVariantFilter(parse_variant_filter(
+            arguments,
+        )?)]);
+    }
+
+    if let Some(arguments) = input.strip_prefix("seq")
+        && (arguments.is_empty() || arguments.starts_with(' '))
+    {
+        let mut arguments = arguments.split_whitespace().peekable();
+        let strand = match arguments.next_if(|argument| *argument == "+" || *argument == "-") {
+            Some("-") => Strand::Reverse,
+            _ => Strand::Forward,
+        };
+        let path = arguments.next().map(str::to_string);
+        if arguments.next().is_some() {
+            return Err(TGVError::RegisterError(
+                "Usage: seq [+|-] [PATH.fa]".to_string(),
+            ));
+        }
+        return Ok(vec![Message::ExportSequence(strand, path)]);
+    }
+
+    if let Some(masking) = input.strip_prefix("masking ") {
+        return match masking.trim() {
+            "on" => Ok(vec![Message::SetMasking(true)]),
+            "off" => Ok(vec![Message::SetMasking(false)]),
+            _ => Err(TGVError::RegisterError(
+                "Usage: masking on or masking off".to_string(),
+            )),
+        };
+    }
+
+    if input == "repeats" {
+        return Ok(vec![Message::ToggleRepeats]);
+    }
+
+    if let Some(min_length) = input.strip_prefix("repeats ") {
+        let min_length = match min_length.trim() {
+            "off" => None,
+            min_length => Some(
+                min_length
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|min_length| *min_length >= 2)
+                    .ok_or(TGVError::RegisterError(
+                        "Usage: repeats, repeats N (at least 2), or repeats off".to_string(),
+                    ))?,
+            ),
+        };
+        return Ok(vec![Message::SetRepeatMinLength(min_length)]);
+    }
+
+    if input == "cpg" {
+        return Ok(vec![Message::ToggleCpg]);
+    }
+
+    if input == "revcomp" {
+        return Ok(vec![Message::ToggleRevcomp]);
+    }
+
+    if let Some(interval) = input.strip_prefix("ruler ") {
+        let ruler_interval = match interval.trim() {
+            "auto" => None,
+            interval => Some(parse_length(interval).ok_or(TGVError::RegisterError(
+                "Usage: ruler LENGTH (e.g. 10kb) or ruler auto".to_string(),
+            ))?),
+        };
+        return Ok(vec![Message::SetRulerInterval(ruler_interval)]);
+    }
+
+    if let Some(base) = input.strip_prefix("coords ") {
+        let coordinate_base = match base.trim() {
+            "0" => CoordinateBase::Zero,
+            "1" => CoordinateBase::One,
+            _ => {
+                return Err(TGVError::RegisterError(
+                    "Usage: coords 0 or coords 1".to_string(),
+                ));
+            }
+        };
+        return Ok(vec![Message::SetCoordinateBase(coordinate_base)]);
+    }
+
+    if let Some(depth) = input.strip_prefix("cov-max ") {
+        let coverage_max = match depth.trim() {
+            "auto" => None,
+            depth => Some(
+                depth
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|depth| *depth > 0)
+                    .ok_or(TGVError::RegisterError(
+                        "Usage: cov-max N or cov-max auto".to_string(),
+                    ))?,
+            ),
+        };
+        return Ok(vec![Message::SetCoverageMax(coverage_max)]);
+    }
+
+    if let Some(depth) = input.strip_prefix("downsample ") {
+        let downsample = match depth.trim() {
+            "auto" => Downsample::Auto,
+            "off" => Downsample::Off,
+            depth => Downsample::Depth(
+                depth
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|depth| *depth > 0)
+                    .ok_or(TGVError::RegisterError(
+                        "Usage: downsample N, downsample auto, or downsample off".to_string(),
+                    ))?,
+            ),
+        };
+        return Ok(vec![Message::SetDownsample(downsample)]);
+    }
+
+    if let Some(view) = input.strip_prefix("view ") {
+        return match view.trim() {
+            "pairs" => Ok(vec![Message::SetViewAsPairs(true)]),
+            "linear" => Ok(vec![Message::SetViewAsPairs(false)]),
+            _ => Err(TGVError::RegisterError(
+                "Usage: view pairs or view linear".to_string(),
+            )),
+        };
+    }
+
+    if let Some(mapping_quality) = input.strip_prefix("mapq ") {
+        let mapping_quality = mapping_quality
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| TGVError::RegisterError("Usage: mapq N".to_string()))?;
+        return Ok(vec![Message::SetMinMapq(mapping_quality)]);
+    }
+
+    if let Some(base_quality) = input.strip_prefix("mismatch-qual ") {
+        let base_quality = base_quality
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| TGVError::RegisterError("Usage: mismatch-qual N".to_string()))?;
+        return Ok(vec![Message::SetMismatchQuality(base_quality)]);
+    }
+
+    if input.eq_ignore_ascii_case("filter clear") {
+        return Ok(vec![Message::ClearAlignmentFilter]);
+    }
+
     if let Ok((_, true)) = restore_default_options(input) {
         // TODO: this results in resetting twice now.
         return Ok(vec![Message::SetAlignmentOption(vec![])]);
@@ -77,6 +530,72 @@ pub fn parse(input: &str) -> Result<Vec<Message>, TGVError> {
     }
 }
 
+/// Bookmark names are one word.
+/// Parse a positive length in bp, e.g. 500, 1,000, 500bp, 10kb, or 1.5mb. Units are case-insensitive.
+fn parse_length(input: &str) -> Option<u64> {
+    let input = input.to_lowercase().replace(',', "");
+    let (number, unit) = if let Some(number) = input.strip_suffix("mb") {
+        (number, 1_000_000.0)
+    } else if let Some(number) = input.strip_suffix("kb") {
+        (number, 1_000.0)
+    } else {
+        (input.strip_suffix("bp").unwrap_or(&input), 1.0)
+    };
+    let length = number.trim().parse::<f64>().ok()? * unit;
+    (length >= 1.0 && length.fract() == 0.0 && length <= u64::MAX as f64).then_some(length as u64)
+}
+
+fn parse_bookmark_name(name: &str, usage: &str) -> Result<String, TGVError> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return Err(TGVError::RegisterError(usage.to_string()));
+    }
+    Ok(name.to_string())
+}
+
+/// Parse variant filter arguments, e.g. `PASS qual>30 missing-qual=hide`.
+fn parse_variant_filter(input: &str) -> Result<VariantFilter, TGVError> {
+    let usage = || {
+        TGVError::RegisterError(
+            "Usage: vcf-filter [PASS] [qual>N] [missing-qual=show|hide], or vcf-filter clear"
+                .to_string(),
+        )
+    };
+    let parse_threshold = |threshold: &str| {
+        threshold
+            .parse::<f32>()
+            .ok()
+            .filter(|threshold| threshold.is_finite())
+            .ok_or_else(usage)
+    };
+
+    let arguments = input.split_whitespace().collect::<Vec<_>>();
+    match arguments.as_slice() {
+        [] => return Err(usage()),
+        ["clear"] => return Ok(VariantFilter::default()),
+        _ => {}
+    }
+
+    let mut filter = VariantFilter::default();
+    for argument in arguments {
+        let argument = argument.to_ascii_lowercase();
+        if argument == "pass" {
+            filter.pass_only = true;
+        } else if let Some(threshold) = argument.strip_prefix("qual>=") {
+            filter.qual = Some(QualThreshold::AtLeast(parse_threshold(threshold)?));
+        } else if let Some(threshold) = argument.strip_prefix("qual>") {
+            filter.qual = Some(QualThreshold::Above(parse_threshold(threshold)?));
+        } else if argument == "missing-qual=hide" {
+            filter.hide_missing_qual = true;
+        } else if argument == "missing-qual=show" {
+            filter.hide_missing_qual = false;
+        } else {
+            return Err(usage());
+        }
+    }
+    Ok(filter)
+}
+
 fn parse_session_command(
     input: &str,
     command: &str,
@@ -96,7 +615,14 @@ fn parse_session_command(
 
 /// Highest level parser
 fn parse_display_options(input: &str) -> IResult<&str, Vec<AlignmentDisplayOption>> {
-    many0(alt((parse_view_as_pair, parse_filter, parse_sort))).parse(input)
+    many0(alt((
+        parse_view_as_pair,
+        parse_filter,
+        parse_sort,
+        parse_color,
+        parse_group,
+    )))
+    .parse(input)
 }
 
 fn restore_default_options(input: &str) -> IResult<&str, bool> {
@@ -156,6 +682,7 @@ fn sort_unit(input: &str) -> IResult<&str, AlignmentSort> {
     alt((
         base_sort_unit,
         strand_sort_unit,
+        value(AlignmentSort::Default, tag_no_case("NONE")),
         value(AlignmentSort::Start, tag_no_case("START")),
         value(AlignmentSort::MappingQuality, tag_no_case("MAPQ")),
         value(AlignmentSort::Sample, tag_no_case("SAMPLE")),
@@ -220,6 +747,54 @@ fn parse_filter(input: &str) -> IResult<&str, AlignmentDisplayOption> {
     .map(|(input, filter)| (input, AlignmentDisplayOption::Filter(filter)))
 }
 
+fn parse_color(input: &str) -> IResult<&str, AlignmentDisplayOption> {
+    delimited(
+        preceded(
+            multispace0,
+            alt((tag_no_case("COLORBY"), tag_no_case("COLOR"))),
+        ),
+        delimited(
+            multispace0,
+            alt((
+                map(
+                    preceded(
+                        terminated(tag_no_case("TAG"), multispace1),
+                        take_while_m_n(2, 2, |c: char| c.is_ascii_alphanumeric()),
+                    ),
+                    |tag: &str| AlignmentColor::Tag(tag.to_string()),
+                ),
+                value(AlignmentColor::ReadLength, tag_no_case("LENGTH")),
+                value(AlignmentColor::Default, tag_no_case("DEFAULT")),
+            )),
+            multispace0,
+        ),
+        multispace0,
+    )
+    .parse(input)
+    .map(|(input, color)| (input, AlignmentDisplayOption::Color(color)))
+}
+
+fn parse_group(input: &str) -> IResult<&str, AlignmentDisplayOption> {
+    delimited(
+        preceded(multispace0, tag_no_case("GROUP")),
+        delimited(
+            multispace0,
+            alt((
+                value(AlignmentGroup::Orientation, tag_no_case("ORIENTATION")),
+                value(
+                    AlignmentGroup::Haplotype,
+                    alt((tag_no_case("HAPLOTYPE"), tag_no_case("HP"))),
+                ),
+                value(AlignmentGroup::Default, tag_no_case("DEFAULT")),
+            )),
+            multispace0,
+        ),
+        multispace0,
+    )
+    .parse(input)
+    .map(|(input, group)| (input, AlignmentDisplayOption::Group(group)))
+}
+
 fn parse_sort(input: &str) -> IResult<&str, AlignmentDisplayOption> {
     delimited(
         preceded(
@@ -266,15 +841,38 @@ fn node_base_filter(input: &str) -> IResult<&str, AlignmentFilter> {
 
     Ok((input, filter))
 }
+fn node_length_filter(input: &str) -> IResult<&str, AlignmentFilter> {
+    let (input, (comparison, length)) = preceded(
+        tag_no_case("LENGTH"),
+        (
+            delimited(multispace0, alt((tag(">="), tag("<="))), multispace0),
+            u64,
+        ),
+    )
+    .parse(input)?;
+
+    let filter = match comparison {
+        ">=" => AlignmentFilter::ReadLengthGE(length),
+        _ => AlignmentFilter::ReadLengthLE(length),
+    };
+
+    Ok((input, filter))
+}
+
 fn node_filter(input: &str) -> IResult<&str, AlignmentFilter> {
-    delimited(multispace0, alt((node_base_filter,)), multispace0).parse(input)
+    delimited(
+        multispace0,
+        alt((node_base_filter, node_length_filter)),
+        multispace0,
+    )
+    .parse(input)
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::message::Message;
+    use crate::{alignment::HiddenReadCategory, message::Message};
     use rstest::rstest;
 
     #[rstest]
@@ -295,6 +893,7 @@ mod tests {
     #[case("STRAND(5)", AlignmentSort::StrandAt(5))]
     // Test simple keywords
     #[case("START", AlignmentSort::Start)]
+    #[case("none", AlignmentSort::Default)]
     #[case("MAPQ", AlignmentSort::MappingQuality)]
     #[case("readname", AlignmentSort::ReadName)]
     // Test with DESC/DEC
@@ -361,6 +960,8 @@ mod tests {
     #[case("BASE=softclip", AlignmentFilter::BaseAtCurrentPositionSoftClip)]
     #[case("BASE(123)=softclip", AlignmentFilter::BaseSoftclip(123))]
     #[case("BASE(123) = A", AlignmentFilter::Base(123, 'A'))]
+    #[case("LENGTH>=1000", AlignmentFilter::ReadLengthGE(1000))]
+    #[case("length <= 500", AlignmentFilter::ReadLengthLE(500))]
     fn test_parse_alignment_filter(#[case] input: &str, #[case] expected: AlignmentFilter) {
         let (remaining, filter) = node_filter(input).unwrap();
 
@@ -402,6 +1003,164 @@ mod tests {
     #[case("w /tmp/test.toml", Ok(vec![Message::SaveSession(Some("/tmp/test.toml".to_string()))]))]
     #[case("wq", Ok(vec![Message::SaveAndQuit(None)]))]
     #[case("wq session-name", Ok(vec![Message::SaveAndQuit(Some("session-name".to_string()))]))]
+    #[case("track list", Ok(vec![Message::ListTracks]))]
+    #[case("reload", Ok(vec![Message::ReloadFiles]))]
+    #[case("track use refGene", Ok(vec![Message::UseTrack("refGene".to_string())]))]
+    #[case("track refGene", Ok(vec![Message::UseTrack("refGene".to_string())]))]
+    #[case("track use", Err(TGVError::RegisterError("Usage: track use TRACK_NAME".to_string())))]
+    #[case("track use  ", Err(TGVError::RegisterError("Usage: track use TRACK_NAME".to_string())))]
+    #[case("aa code vertmito", Ok(vec![Message::SetGeneticCode(Some(GeneticCode::VertebrateMitochondrial))]))]
+    #[case("aa code auto", Ok(vec![Message::SetGeneticCode(None)]))]
+    #[case("codon", Ok(vec![Message::LookupCodon]))]
+    #[case("whatgene", Ok(vec![Message::LookupGene]))]
+    #[case("indels", Ok(vec![Message::ShowIndelHistogram]))]
+    #[case("gene TP5", Ok(vec![Message::FindGene("TP5".to_string())]))]
+    #[case("gene  brca ", Ok(vec![Message::FindGene("brca".to_string())]))]
+    #[case("gene  ", Err(TGVError::RegisterError("Usage: gene PREFIX".to_string())))]
+    #[case("filter clear", Ok(vec![Message::ClearAlignmentFilter]))]
+    #[case("show hidden", Ok(vec![Message::ShowHiddenReads]))]
+    #[case("cpg", Ok(vec![Message::ToggleCpg]))]
+    #[case("revcomp", Ok(vec![Message::ToggleRevcomp]))]
+    #[case("ruler auto", Ok(vec![Message::SetRulerInterval(None)]))]
+    #[case("ruler 500", Ok(vec![Message::SetRulerInterval(Some(500))]))]
+    #[case("ruler 500bp", Ok(vec![Message::SetRulerInterval(Some(500))]))]
+    #[case("ruler 10kb", Ok(vec![Message::SetRulerInterval(Some(10_000))]))]
+    #[case("ruler 10KB", Ok(vec![Message::SetRulerInterval(Some(10_000))]))]
+    #[case("ruler 1.5mb", Ok(vec![Message::SetRulerInterval(Some(1_500_000))]))]
+    #[case("ruler 1,000", Ok(vec![Message::SetRulerInterval(Some(1_000))]))]
+    #[case("coords 0", Ok(vec![Message::SetCoordinateBase(CoordinateBase::Zero)]))]
+    #[case("coords 1", Ok(vec![Message::SetCoordinateBase(CoordinateBase::One)]))]
+    #[case("coords 2", Err(TGVError::RegisterError("Usage: coords 0 or coords 1".to_string())))]
+    #[case("ruler 0", Err(TGVError::RegisterError("Usage: ruler LENGTH (e.g. 10kb) or ruler auto".to_string())))]
+    #[case("ruler 1.5bp", Err(TGVError::RegisterError("Usage: ruler LENGTH (e.g. 10kb) or ruler auto".to_string())))]
+    #[case("ruler wide", Err(TGVError::RegisterError("Usage: ruler LENGTH (e.g. 10kb) or ruler auto".to_string())))]
+    #[case("masking on", Ok(vec![Message::SetMasking(true)]))]
+    #[case("masking off", Ok(vec![Message::SetMasking(false)]))]
+    #[case("repeats", Ok(vec![Message::ToggleRepeats]))]
+    #[case("repeats 8", Ok(vec![Message::SetRepeatMinLength(Some(8))]))]
+    #[case("repeats off", Ok(vec![Message::SetRepeatMinLength(None)]))]
+    #[case("repeats 1", Err(TGVError::RegisterError("Usage: repeats, repeats N (at least 2), or repeats off".to_string())))]
+    #[case("masking dim", Err(TGVError::RegisterError("Usage: masking on or masking off".to_string())))]
+    #[case("cov-max 100", Ok(vec![Message::SetCoverageMax(Some(100))]))]
+    #[case("cov-max auto", Ok(vec![Message::SetCoverageMax(None)]))]
+    #[case("cov-max 0", Err(TGVError::RegisterError("Usage: cov-max N or cov-max auto".to_string())))]
+    #[case("cov-max high", Err(TGVError::RegisterError("Usage: cov-max N or cov-max auto".to_string())))]
+    #[case("downsample 500", Ok(vec![Message::SetDownsample(Downsample::Depth(500))]))]
+    #[case("downsample auto", Ok(vec![Message::SetDownsample(Downsample::Auto)]))]
+    #[case("downsample off", Ok(vec![Message::SetDownsample(Downsample::Off)]))]
+    #[case("downsample 0", Err(TGVError::RegisterError("Usage: downsample N, downsample auto, or downsample off".to_string())))]
+    #[case("view pairs", Ok(vec![Message::SetViewAsPairs(true)]))]
+    #[case("view linear", Ok(vec![Message::SetViewAsPairs(false)]))]
+    #[case("view stacked", Err(TGVError::RegisterError("Usage: view pairs or view linear".to_string())))]
+    #[case("mapq 20", Ok(vec![Message::SetMinMapq(20)]))]
+    #[case("mapq 0", Ok(vec![Message::SetMinMapq(0)]))]
+    #[case("mapq high", Err(TGVError::RegisterError("Usage: mapq N".to_string())))]
+    #[case("mismatch-qual 20", Ok(vec![Message::SetMismatchQuality(20)]))]
+    #[case("mismatch-qual 0", Ok(vec![Message::SetMismatchQuality(0)]))]
+    #[case("mismatch-qual low", Err(TGVError::RegisterError("Usage: mismatch-qual N".to_string())))]
+    #[case("show supplementary", Ok(vec![Message::ShowReads(HiddenReadCategory::Supplementary)]))]
+    #[case("hide secondary", Ok(vec![Message::HideReads(HiddenReadCategory::Secondary)]))]
+    #[case("show mapped", Err(TGVError::ParsingError("".to_string())))]
+    #[case("support", Ok(vec![Message::HighlightVariantSupport]))]
+    #[case("support clear", Ok(vec![Message::ClearVariantSupport]))]
+    #[case("isolate alt", Ok(vec![Message::IsolateAllele(Some(IsolatedAllele::Alternate))]))]
+    #[case("isolate ref", Ok(vec![Message::IsolateAllele(Some(IsolatedAllele::Reference))]))]
+    #[case("isolate off", Ok(vec![Message::IsolateAllele(None)]))]
+    #[case("isolate", Err(TGVError::RegisterError("Usage: isolate alt, isolate ref, or isolate off".to_string())))]
+    #[case("vcf-filter PASS qual>30", Ok(vec![Message::SetVariantFilter(VariantFilter {
+        pass_only: true,
+        qual: Some(QualThreshold::Above(30.0)),
+        hide_missing_qual: false,
+    })]))]
+    #[case("vcf-filter qual>=2.5 missing-qual=hide", Ok(vec![Message::SetVariantFilter(VariantFilter {
+        pass_only: false,
+        qual: Some(QualThreshold::AtLeast(2.5)),
+        hide_missing_qual: true,
+    })]))]
+    #[case("vcf-info AF,DP", Ok(vec![Message::SetVariantInfoFields(Some(vec!["AF".to_string(), "DP".to_string()]))]))]
+    #[case("vcf-info AF, DB ", Ok(vec![Message::SetVariantInfoFields(Some(vec!["AF".to_string(), "DB".to_string()]))]))]
+    #[case("vcf-info all", Ok(vec![Message::SetVariantInfoFields(None)]))]
+    #[case("vcf-info ,", Err(TGVError::RegisterError("Usage: vcf-info FIELD,FIELD,... or vcf-info all".to_string())))]
+    #[case("vcf-filter clear", Ok(vec![Message::SetVariantFilter(VariantFilter::default())]))]
+    #[case("vcf-filter", Err(TGVError::RegisterError("Usage: vcf-filter [PASS] [qual>N] [missing-qual=show|hide], or vcf-filter clear".to_string())))]
+    #[case("vcf-filter qual>high", Err(TGVError::RegisterError("Usage: vcf-filter [PASS] [qual>N] [missing-qual=show|hide], or vcf-filter clear".to_string())))]
+    #[case("vcf-filter PASS depth>3", Err(TGVError::RegisterError("Usage: vcf-filter [PASS] [qual>N] [missing-qual=show|hide], or vcf-filter clear".to_string())))]
+    #[case("export-reads out.bam", Ok(vec![Message::ExportReads("out.bam".to_string())]))]
+    #[case("seq", Ok(vec![Message::ExportSequence(Strand::Forward, None)]))]
+    #[case("seq -", Ok(vec![Message::ExportSequence(Strand::Reverse, None)]))]
+    #[case("seq + out.fa", Ok(vec![Message::ExportSequence(Strand::Forward, Some("out.fa".to_string()))]))]
+    #[case("seq - out.fa", Ok(vec![Message::ExportSequence(Strand::Reverse, Some("out.fa".to_string()))]))]
+    #[case("seq out.fa", Ok(vec![Message::ExportSequence(Strand::Forward, Some("out.fa".to_string()))]))]
+    #[case("seq - out.fa extra", Err(TGVError::RegisterError("Usage: seq [+|-] [PATH.fa]".to_string())))]
+    #[case("export-reads ", Err(TGVError::RegisterError("Usage: export-reads PATH.bam".to_string())))]
+    #[case("export-cov out.bedgraph", Ok(vec![Message::ExportCoverage("out.bedgraph".to_string(), 1)]))]
+    #[case("export-cov out.bedgraph 10", Ok(vec![Message::ExportCoverage("out.bedgraph".to_string(), 10)]))]
+    #[case("export-cov", Err(TGVError::RegisterError("Usage: export-cov PATH.bedgraph [BIN]".to_string())))]
+    #[case("export-cov out.bedgraph 0", Err(TGVError::RegisterError("Usage: export-cov PATH.bedgraph [BIN]".to_string())))]
+    #[case("export-cov out.bedgraph 10 20", Err(TGVError::RegisterError("Usage: export-cov PATH.bedgraph [BIN]".to_string())))]
+    #[case("goto BRCA1:p.185", Ok(vec![Movement::GeneResidue("BRCA1".to_string(), 185).into()]))]
+    #[case("session save", Ok(vec![Message::SaveSession(None)]))]
+    #[case("session save panel", Ok(vec![Message::SaveSession(Some("panel".to_string()))]))]
+    #[case("mark site1", Ok(vec![Message::SetBookmark("site1".to_string())]))]
+    #[case("mark  site1 ", Ok(vec![Message::SetBookmark("site1".to_string())]))]
+    #[case("mark", Err(TGVError::RegisterError("Usage: mark NAME".to_string())))]
+    #[case("mark two words", Err(TGVError::RegisterError("Usage: mark NAME".to_string())))]
+    #[case("goto BRCA1 +2kb", Ok(vec![Message::FrameGene("BRCA1".to_string(), GeneFrame::Flank(2_000))]))]
+    #[case("goto BRCA1 +500", Ok(vec![Message::FrameGene("BRCA1".to_string(), GeneFrame::Flank(500))]))]
+    #[case("goto BRCA1  150%", Ok(vec![Message::FrameGene("BRCA1".to_string(), GeneFrame::Percent(150))]))]
+    #[case("goto BRCA1 2kb", Err(TGVError::RegisterError("Usage: goto GENE +FLANK or goto GENE PERCENT%".to_string())))]
+    #[case("goto BRCA1 +", Err(TGVError::RegisterError("Usage: goto GENE +FLANK or goto GENE PERCENT%".to_string())))]
+    #[case("goto BRCA1 0%", Err(TGVError::RegisterError("Usage: goto GENE +FLANK or goto GENE PERCENT%".to_string())))]
+    #[case("goto BRCA1 1.5%", Err(TGVError::RegisterError("Usage: goto GENE +FLANK or goto GENE PERCENT%".to_string())))]
+    #[case("goto 17q21.31", Ok(vec![Message::FrameBand("17".to_string(), "q21.31".to_string())]))]
+    #[case("goto chrXp11", Ok(vec![Message::FrameBand("chrX".to_string(), "p11".to_string())]))]
+    #[case("goto 11qB1.3", Ok(vec![Message::FrameBand("11".to_string(), "qB1.3".to_string())]))]
+    #[case("goto q21", Err(TGVError::RegisterError("Band q21 is ambiguous without a contig. Usage: goto CONTIGBAND, e.g. goto 17q21".to_string())))]
+    #[case("goto @site1", Ok(vec![Movement::Bookmark("site1".to_string()).into()]))]
+    #[case("loci next", Ok(vec![Movement::NextLocus(1).into()]))]
+    #[case("loci prev", Ok(vec![Movement::PreviousLocus(1).into()]))]
+    #[case("center", Ok(vec![Message::CenterCursor(CursorAnchor::Center)]))]
+    #[case("peak", Ok(vec![Movement::HighestPeak.into()]))]
+    #[case("peak next", Ok(vec![Movement::NextPeaks(1).into()]))]
+    #[case("peak 3", Err(TGVError::RegisterError("Usage: peak or peak next".to_string())))]
+    #[case("find ACGT", Ok(vec![
+        Message::SetMotif(Some(Motif::new("ACGT", false).unwrap())),
+        Movement::NextMatches(1).into(),
+    ]))]
+    #[case("find -r acgt", Ok(vec![
+        Message::SetMotif(Some(Motif::new("ACGT", true).unwrap())),
+        Movement::NextMatches(1).into(),
+    ]))]
+    #[case("find clear", Ok(vec![Message::SetMotif(None)]))]
+    #[case("find ", Err(TGVError::RegisterError("Usage: find [-r] ACGT, or find clear".to_string())))]
+    #[case("find -r", Err(TGVError::ParsingError("Invalid motif `-r`: expected bases A, C, G, and T".to_string())))]
+    #[case("center start", Ok(vec![Message::CenterCursor(CursorAnchor::Start)]))]
+    #[case("center end", Ok(vec![Message::CenterCursor(CursorAnchor::End)]))]
+    #[case("center top", Err(TGVError::RegisterError("Usage: center, center start, or center end".to_string())))]
+    #[case("goto @", Err(TGVError::RegisterError("Usage: goto @NAME".to_string())))]
+    #[case("goto BRCA1:185", Err(TGVError::RegisterError("Usage: goto GENE:p.RESIDUE".to_string())))]
+    #[case("goto :p.185", Err(TGVError::RegisterError("Usage: goto GENE:p.RESIDUE".to_string())))]
+    #[case("filter length>=1000", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Filter(AlignmentFilter::ReadLengthGE(1000)),
+    ])]))]
+    #[case("color length", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Color(AlignmentColor::ReadLength),
+    ])]))]
+    #[case("colorby tag HP", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Color(AlignmentColor::Tag("HP".to_string())),
+    ])]))]
+    #[case("color tag rg", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Color(AlignmentColor::Tag("rg".to_string())),
+    ])]))]
+    #[case("group orientation", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Group(AlignmentGroup::Orientation),
+    ])]))]
+    #[case("group hp", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Group(AlignmentGroup::Haplotype),
+    ])]))]
+    #[case("group default", Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Group(AlignmentGroup::Default),
+    ])]))]
     #[case("1234", Ok(vec![Movement::Position(1234).into()]))]
     #[case("chr1:1000", Ok(vec![Movement::ContigNamePosition(
         "chr1".to_string(),
@@ -201,7 +201,7 @@ pub enum ContigSource {
 }
 
 /// A collection of contigs. This helps relative contig movements.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContigHeader {
     reference: Reference,
     pub contigs: Vec<Contig>,
@@ -261,6 +261,19 @@ impl ContigHeader {
             )))
     }
 
+    /// Look up a contig typed by the user, e.g. in `:goto` or `-r`. Names missing from the header are retried with the
+    /// `chr` prefix added or removed (case-insensitive) and with the mitochondrial aliases M, MT, chrM, and chrMT, so
+    /// that chr22 finds 22 on Ensembl references and 22 finds chr22 on UCSC ones. chromAlias aliases of the track
+    /// database are in the lookup already.
+    pub fn resolve(&self, contig_name: &str) -> Result<usize, TGVError> {
+        self.try_get_index_by_str(contig_name).or_else(|e| {
+            contig_name_variants(contig_name)
+                .iter()
+                .find_map(|variant| self.contig_lookup.get(variant).cloned())
+                .ok_or(e)
+        })
+    }
+
     pub fn try_update_cytoband(
         &mut self,
         contig_index: usize,
@@ -342,6 +355,27 @@ impl ContigHeader {
     }
 }
 
+/// Other spellings of a contig name, in the order they are tried.
+fn contig_name_variants(contig_name: &str) -> Vec<String> {
+    let bare = match contig_name.get(..3) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("chr") => &contig_name[3..],
+        _ => contig_name,
+    };
+    if bare.is_empty() {
+        return Vec::new();
+    }
+    let bares = if bare.eq_ignore_ascii_case("M") || bare.eq_ignore_ascii_case("MT") {
+        vec!["M".to_string(), "MT".to_string()]
+    } else {
+        vec![bare.to_string(), bare.to_ascii_uppercase()]
+    };
+    bares
+        .iter()
+        .flat_map(|bare| [format!("chr{bare}"), bare.clone()])
+        .filter(|variant| variant != contig_name)
+        .collect()
+}
+
 impl Display for ContigHeader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for contig in &self.contigs {
@@ -350,3 +384,67 @@ impl Display for ContigHeader {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn contig_header(contig_names: &[&str]) -> ContigHeader {
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        for contig_name in contig_names {
+            contig_header.update_or_add_contig(
+                contig_name.to_string(),
+                Some(10_000),
+                Vec::new(),
+                ContigSource::Sequence,
+            );
+        }
+        contig_header
+    }
+
+    #[rstest]
+    // Ensembl-style names
+    #[case(&["1", "22", "X", "MT"], "chr22", "22")]
+    #[case(&["1", "22", "X", "MT"], "CHR1", "1")]
+    #[case(&["1", "22", "X", "MT"], "chrx", "X")]
+    #[case(&["1", "22", "X", "MT"], "chrM", "MT")]
+    #[case(&["1", "22", "X", "MT"], "M", "MT")]
+    // UCSC-style names
+    #[case(&["chr1", "chr22", "chrX", "chrM"], "22", "chr22")]
+    #[case(&["chr1", "chr22", "chrX", "chrM"], "Chr1", "chr1")]
+    #[case(&["chr1", "chr22", "chrX", "chrM"], "x", "chrX")]
+    #[case(&["chr1", "chr22", "chrX", "chrM"], "MT", "chrM")]
+    #[case(&["chr1", "chr22", "chrX", "chrM"], "chrMT", "chrM")]
+    // Non-standard contigs
+    #[case(&["chr1", "chr22_KI270731v1_random"], "22_KI270731v1_random", "chr22_KI270731v1_random")]
+    #[case(&["1", "KI270731.1"], "chrKI270731.1", "KI270731.1")]
+    fn contig_names_resolve_to_aliases(
+        #[case] contig_names: &[&str],
+        #[case] query: &str,
+        #[case] expected: &str,
+    ) {
+        let contig_header = contig_header(contig_names);
+        let contig_index = contig_header.resolve(query).unwrap();
+        assert_eq!(contig_header.try_get(contig_index).unwrap().name, expected);
+    }
+
+    #[test]
+    fn chrom_alias_aliases_resolve() {
+        let mut contig_header = contig_header(&["NC_045512.2"]);
+        contig_header.update_or_add_contig(
+            "NC_045512.2".to_string(),
+            None,
+            vec!["MN908947.3".to_string()],
+            ContigSource::Track,
+        );
+        assert_eq!(contig_header.resolve("MN908947.3").unwrap(), 0);
+    }
+
+    #[rstest]
+    #[case(&["1", "22"], "chr23")]
+    #[case(&["1", "22"], "chr")]
+    fn unknown_contigs_are_not_resolved(#[case] contig_names: &[&str], #[case] query: &str) {
+        assert!(contig_header(contig_names).resolve(query).is_err());
+    }
+}
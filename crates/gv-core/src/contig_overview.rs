@@ -0,0 +1,238 @@
+use crate::{alignment::CoverageOverview, feature::Gene, intervals::GenomeInterval};
+
+/// Whole-contig summary of genes and coverage, binned for display.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContigOverview {
+    pub contig_index: usize,
+
+    pub contig_length: u64,
+
+    /// Number of genes starting in each bin.
+    pub gene_counts: Vec<usize>,
+
+    /// Coverage density in each bin. Empty if no coverage overview is available.
+    pub coverage: Vec<u64>,
+}
+
+impl ContigOverview {
+    /// Bin the contig into at most max_bins bins. Bins are never shorter than 1 bp.
+    pub fn new(
+        contig_index: usize,
+        contig_length: u64,
+        max_bins: usize,
+        genes: &[Gene],
+        coverage_overview: Option<&CoverageOverview>,
+    ) -> Self {
+        let bins = bin_count(contig_length, max_bins);
+
+        let mut gene_counts = vec![0; bins];
+        genes
+            .iter()
+            .filter(|gene| gene.contig_index() == contig_index)
+            .for_each(|gene| gene_counts[bin_index(contig_length, bins, gene.start())] += 1);
+
+        let coverage = coverage_overview
+            .filter(|overview| overview.contig_index == contig_index)
+            .map(|overview| {
+                (0..bins)
+                    .map(|bin| {
+                        let (start, end) = bin_range(contig_length, bins, bin);
+                        overview.density_in(start, end)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            contig_index,
+            contig_length,
+            gene_counts,
+            coverage,
+        }
+    }
+
+    pub fn bins(&self) -> usize {
+        self.gene_counts.len()
+    }
+
+    /// Range of a bin.
+    /// 1-based, inclusive.
+    pub fn bin_range(&self, bin: usize) -> (u64, u64) {
+        bin_range(self.contig_length, self.bins(), bin)
+    }
+
+    /// Bin containing a position.
+    /// 1-based.
+    pub fn bin_index(&self, position: u64) -> usize {
+        bin_index(self.contig_length, self.bins(), position)
+    }
+
+    /// Whether each bin is gene-dense: it has at least half as many genes as the densest bin.
+    pub fn gene_dense_bins(&self) -> Vec<bool> {
+        let max_count = self.gene_counts.iter().copied().max().unwrap_or(0);
+        self.gene_counts
+            .iter()
+            .map(|count| *count > 0 && count * 2 >= max_count)
+            .collect()
+    }
+}
+
+/// Number of bins for a contig: max_bins, or fewer so that every bin spans at least 1 bp.
+fn bin_count(contig_length: u64, max_bins: usize) -> usize {
+    usize::max(1, u64::min(contig_length, max_bins as u64) as usize)
+}
+
+/// Range of bin when the contig is split into bins bins of near-equal length.
+/// 1-based, inclusive.
+fn bin_range(contig_length: u64, bins: usize, bin: usize) -> (u64, u64) {
+    let bins = bins as u64;
+    let bin = bin as u64;
+    (
+        bin * contig_length / bins + 1,
+        (bin + 1) * contig_length / bins,
+    )
+}
+
+/// Bin containing position: the first bin whose end is at or after position. Positions past the contig end are in
+/// the last bin.
+fn bin_index(contig_length: u64, bins: usize, position: u64) -> usize {
+    if contig_length == 0 {
+        return 0;
+    }
+    let bin = (position.max(1) * bins as u64 - 1) / contig_length;
+    usize::min(bin as usize, bins - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strand::Strand;
+    use rstest::rstest;
+
+    fn gene(contig_index: usize, start: u64, end: u64) -> Gene {
+        Gene {
+            id: format!("gene{}", start),
+            name: format!("gene{}", start),
+            strand: Strand::Forward,
+            contig_index,
+            transcription_start: start,
+            transcription_end: end,
+            cds_start: start,
+            cds_end: end,
+            exon_starts: vec![start],
+            exon_ends: vec![end],
+            has_exons: true,
+        }
+    }
+
+    #[rstest]
+    #[case(29_903, 80, 80)]
+    #[case(50, 80, 50)]
+    #[case(0, 80, 1)]
+    #[case(1_000, 0, 1)]
+    fn test_bin_count(
+        #[case] contig_length: u64,
+        #[case] max_bins: usize,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(bin_count(contig_length, max_bins), expected);
+    }
+
+    #[rstest]
+    #[case(10, 3, 0, (1, 3))]
+    #[case(10, 3, 1, (4, 6))]
+    #[case(10, 3, 2, (7, 10))]
+    #[case(29_903, 80, 0, (1, 373))]
+    #[case(29_903, 80, 79, (29_530, 29_903))]
+    #[case(100, 100, 41, (42, 42))]
+    fn test_bin_range(
+        #[case] contig_length: u64,
+        #[case] bins: usize,
+        #[case] bin: usize,
+        #[case] expected: (u64, u64),
+    ) {
+        assert_eq!(bin_range(contig_length, bins, bin), expected);
+    }
+
+    #[rstest]
+    #[case(10, 3, 1, 0)]
+    #[case(10, 3, 3, 0)]
+    #[case(10, 3, 4, 1)]
+    #[case(10, 3, 6, 1)]
+    #[case(10, 3, 7, 2)]
+    #[case(10, 3, 10, 2)]
+    #[case(10, 3, 11, 2)]
+    #[case(29_903, 80, 373, 0)]
+    #[case(29_903, 80, 374, 1)]
+    fn test_bin_index(
+        #[case] contig_length: u64,
+        #[case] bins: usize,
+        #[case] position: u64,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(bin_index(contig_length, bins, position), expected);
+    }
+
+    #[test]
+    fn bin_index_is_consistent_with_bin_range() {
+        for (contig_length, bins) in [(10, 3), (997, 80), (29_903, 80), (80, 80)] {
+            for bin in 0..bins {
+                let (start, end) = bin_range(contig_length, bins, bin);
+                assert_eq!(bin_index(contig_length, bins, start), bin);
+                assert_eq!(bin_index(contig_length, bins, end), bin);
+            }
+        }
+    }
+
+    #[test]
+    fn genes_are_counted_in_the_bin_of_their_start() {
+        let overview = ContigOverview::new(
+            0,
+            100,
+            10,
+            &[
+                gene(0, 1, 50),
+                gene(0, 5, 8),
+                gene(0, 95, 100),
+                gene(1, 5, 8),
+            ],
+            None,
+        );
+        assert_eq!(overview.gene_counts, vec![2, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert!(overview.coverage.is_empty());
+        assert_eq!(overview.bin_range(9), (91, 100));
+        assert_eq!(overview.bin_index(42), 4);
+    }
+
+    #[test]
+    fn gene_dense_bins_have_at_least_half_of_the_densest_bin() {
+        let overview = ContigOverview {
+            contig_index: 0,
+            contig_length: 50,
+            gene_counts: vec![0, 4, 1, 2, 3],
+            coverage: Vec::new(),
+        };
+        assert_eq!(
+            overview.gene_dense_bins(),
+            vec![false, true, false, true, true]
+        );
+        assert_eq!(
+            ContigOverview::default().gene_dense_bins(),
+            Vec::<bool>::new()
+        );
+    }
+
+    #[test]
+    fn coverage_is_binned_from_the_coverage_overview() {
+        let window = CoverageOverview::WINDOW_SIZE;
+        let coverage_overview = CoverageOverview {
+            contig_index: 0,
+            densities: vec![1, 2, 3, 4],
+        };
+        let overview = ContigOverview::new(0, 4 * window, 2, &[], Some(&coverage_overview));
+        assert_eq!(overview.coverage, vec![3, 7]);
+
+        let other_contig = ContigOverview::new(1, 4 * window, 2, &[], Some(&coverage_overview));
+        assert!(other_contig.coverage.is_empty());
+    }
+}
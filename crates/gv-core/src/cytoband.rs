@@ -1,4 +1,4 @@
-use crate::{error::TGVError, reference::Reference};
+use crate::{error::TGVError, intervals::GenomeInterval, reference::Reference};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Stain {
@@ -45,6 +45,41 @@ pub struct CytobandSegment {
     pub stain: Stain,
 }
 
+impl GenomeInterval for CytobandSegment {
+    fn contig_index(&self) -> usize {
+        self.contig_index
+    }
+
+    fn start(&self) -> u64 {
+        self.start
+    }
+
+    fn end(&self) -> u64 {
+        self.end
+    }
+}
+
+/// Split a band locus such as 17q21.31 into the contig and the band: ("17", "q21.31"). The band is an arm (p or q),
+/// optionally followed by a region and sub-bands that start with a digit or an uppercase letter (mouse bands, e.g.
+/// 11qB1.3). The contig is empty for a band without one, e.g. q21. None if the locus is not a band, e.g. a gene
+/// name or a band range.
+pub fn split_band(locus: &str) -> Option<(&str, &str)> {
+    let arm = locus.rfind(['p', 'q'])?;
+    let (contig, band) = locus.split_at(arm);
+    let region = &band[1..];
+    let is_band = contig
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && region
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+        && region
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.');
+    is_band.then_some((contig, band))
+}
+
 #[derive(Debug, Clone)]
 pub struct Cytoband {
     pub reference: Option<Reference>,
@@ -83,4 +118,160 @@ impl Cytoband {
     pub fn length(&self) -> u64 {
         self.end()
     }
+
+    /// Centromere position: the end of the first acen (centromeric) band, where the p arm meets the q arm. The acen
+    /// bands of the two arms are adjacent. None if the contig has no acen band.
+    pub fn centromere(&self) -> Option<u64> {
+        self.segments
+            .iter()
+            .find(|segment| segment.stain == Stain::Acen)
+            .map(|segment| segment.end)
+    }
+
+    /// Span of a band and its sub-bands as one segment named after the band. Band names are hierarchical, so
+    /// sub-bands start with the band: q21 spans q21.1 to q21.33, and q spans the whole q arm. Errors if the contig has
+    /// no bands or the band is not on it.
+    pub fn band(&self, band: &str, contig_name: &str) -> Result<CytobandSegment, TGVError> {
+        if !self
+            .segments
+            .iter()
+            .any(|segment| segment.name.starts_with(['p', 'q']))
+        {
+            return Err(TGVError::ValueError(format!(
+                "No cytoband data for {contig_name}"
+            )));
+        }
+
+        let mut matches = self
+            .segments
+            .iter()
+            .filter(|segment| segment.name.starts_with(band));
+        let first = matches.next().ok_or_else(|| {
+            TGVError::ValueError(format!("Band {band} not found on {contig_name}"))
+        })?;
+        let end = matches.next_back().unwrap_or(first).end;
+        Ok(CytobandSegment {
+            contig_index: first.contig_index,
+            start: first.start,
+            end,
+            name: band.to_string(),
+            stain: first.stain.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn cytoband(stains: &[(u64, u64, &str)]) -> Cytoband {
+        Cytoband {
+            reference: None,
+            contig_index: 0,
+            segments: stains
+                .iter()
+                .map(|(start, end, stain)| CytobandSegment {
+                    contig_index: 0,
+                    start: *start,
+                    end: *end,
+                    name: String::new(),
+                    stain: Stain::try_from(*stain).unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn centromere_is_between_the_acen_bands() {
+        let with_centromere = cytoband(&[
+            (1, 100, "gneg"),
+            (101, 150, "acen"),
+            (151, 200, "acen"),
+            (201, 400, "gpos50"),
+        ]);
+        assert_eq!(with_centromere.centromere(), Some(150));
+
+        let without_centromere = cytoband(&[(1, 100, "gneg"), (101, 400, "gpos50")]);
+        assert_eq!(without_centromere.centromere(), None);
+    }
+
+    /// hg38 chr17 bands around the centromere and q21, 1-based.
+    fn chr17() -> Cytoband {
+        let bands = [
+            (16_100_001, 22_700_000, "p11.2", "gneg"),
+            (22_700_001, 25_100_000, "p11.1", "acen"),
+            (25_100_001, 27_400_000, "q11.1", "acen"),
+            (27_400_001, 33_500_000, "q11.2", "gneg"),
+            (33_500_001, 39_800_000, "q12", "gpos50"),
+            (39_800_001, 40_200_000, "q21.1", "gneg"),
+            (40_200_001, 42_800_000, "q21.2", "gpos25"),
+            (42_800_001, 46_800_000, "q21.31", "gneg"),
+            (46_800_001, 49_300_000, "q21.32", "gpos25"),
+            (49_300_001, 52_100_000, "q21.33", "gneg"),
+            (52_100_001, 59_500_000, "q22", "gpos75"),
+        ];
+        Cytoband {
+            reference: None,
+            contig_index: 16,
+            segments: bands
+                .iter()
+                .map(|(start, end, name, stain)| CytobandSegment {
+                    contig_index: 16,
+                    start: *start,
+                    end: *end,
+                    name: name.to_string(),
+                    stain: Stain::try_from(*stain).unwrap(),
+                })
+                .collect(),
+        }
+    }
+
+    #[rstest]
+    #[case("q21.31", Some((42_800_001, 46_800_000)))]
+    #[case("q21.3", Some((42_800_001, 52_100_000)))]
+    #[case("q21", Some((39_800_001, 52_100_000)))]
+    #[case("q1", Some((25_100_001, 39_800_000)))]
+    #[case("p11", Some((16_100_001, 25_100_000)))]
+    #[case("q", Some((25_100_001, 59_500_000)))]
+    #[case("q21.4", None)]
+    #[case("q99", None)]
+    fn test_band(#[case] band: &str, #[case] expected: Option<(u64, u64)>) {
+        let result = chr17().band(band, "chr17");
+        match expected {
+            Some((start, end)) => {
+                let segment = result.unwrap();
+                assert_eq!((segment.start, segment.end), (start, end));
+                assert_eq!(segment.name, band);
+                assert_eq!(segment.contig_index, 16);
+            }
+            None => assert_eq!(
+                result.unwrap_err().to_string(),
+                format!("Value error: Band {band} not found on chr17")
+            ),
+        }
+    }
+
+    #[test]
+    fn contigs_without_cytobands_have_no_bands() {
+        let cytoband = Cytoband::default(&Reference::Hg38, 0, 1_000, "chrUn");
+        assert_eq!(
+            cytoband.band("q21", "chrUn").unwrap_err().to_string(),
+            "Value error: No cytoband data for chrUn"
+        );
+    }
+
+    #[rstest]
+    #[case("17q21.31", Some(("17", "q21.31")))]
+    #[case("chr17q21", Some(("chr17", "q21")))]
+    #[case("Xp11.23", Some(("X", "p11.23")))]
+    #[case("17q", Some(("17", "q")))]
+    #[case("11qB1.3", Some(("11", "qB1.3")))]
+    #[case("q21", Some(("", "q21")))]
+    #[case("TP53", None)]
+    #[case("Sqstm1", None)]
+    #[case("17q21-q22", None)]
+    fn test_split_band(#[case] locus: &str, #[case] expected: Option<(&str, &str)>) {
+        assert_eq!(split_band(locus), expected);
+    }
 }
@@ -0,0 +1,182 @@
+use crate::{error::TGVError, reference::Reference, retry::is_unreachable};
+use std::future::Future;
+use strum::Display;
+
+/// Where the gene track and sequence of a UCSC reference are served from. Shown in the status bar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum DataSource {
+    /// Local cache downloaded with `tgv download`.
+    #[strum(to_string = "offline cache")]
+    Cache,
+
+    /// UCSC MySQL or API.
+    #[strum(to_string = "UCSC live")]
+    Ucsc,
+
+    /// Ensembl REST API.
+    #[strum(to_string = "Ensembl live")]
+    Ensembl,
+}
+
+/// Which source the default backend tries first. The other source is the fallback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourcePreference {
+    /// Use the local cache if downloaded. Otherwise, use UCSC.
+    #[default]
+    Cache,
+
+    /// Use UCSC if reachable. Otherwise, use the local cache.
+    Live,
+}
+
+impl SourcePreference {
+    /// Sources to try, in order. The cache is skipped if it has not been downloaded.
+    pub fn order(&self, cache_downloaded: bool) -> Vec<DataSource> {
+        let order = match self {
+            SourcePreference::Cache => [DataSource::Cache, DataSource::Ucsc],
+            SourcePreference::Live => [DataSource::Ucsc, DataSource::Cache],
+        };
+        order
+            .into_iter()
+            .filter(|source| cache_downloaded || *source != DataSource::Cache)
+            .collect()
+    }
+
+    /// Open the first source that works, in the order above. A source that fails is logged and the next one is
+    /// tried. If UCSC is unreachable and there is no cache to fall back to, the error explains how to get one.
+    pub async fn open<T, F, Fut>(
+        &self,
+        reference: &Reference,
+        cache_downloaded: bool,
+        mut open: F,
+    ) -> Result<(T, DataSource), TGVError>
+    where
+        F: FnMut(DataSource) -> Fut,
+        Fut: Future<Output = Result<T, TGVError>>,
+    {
+        let mut error = None;
+        for source in self.order(cache_downloaded) {
+            match open(source).await {
+                Ok(value) => {
+                    log::info!("Data source: reference={reference} source={source}");
+                    return Ok((value, source));
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Data source unavailable; trying the next one: reference={reference} source={source} error={e}"
+                    );
+                    error = Some(e);
+                }
+            }
+        }
+
+        // UCSC is always tried, so there is an error.
+        let error = error.unwrap();
+        if cache_downloaded {
+            Err(error)
+        } else {
+            Err(explain_unreachable(
+                error,
+                &reference.to_string(),
+                reference,
+            ))
+        }
+    }
+}
+
+/// Explain a network error while fetching data that is not in the local cache: what is missing, and how to browse
+/// it offline. Other errors are returned as they are.
+pub(crate) fn explain_unreachable(
+    error: TGVError,
+    missing: &str,
+    reference: &Reference,
+) -> TGVError {
+    if !is_unreachable(&error) {
+        return error;
+    }
+    TGVError::NetworkError(format!(
+        "{missing} is not in the local cache and UCSC is unreachable. Check the network, or run `tgv download \
+         {reference}` while online to browse offline. ({error})"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(SourcePreference::Cache, true, vec![DataSource::Cache, DataSource::Ucsc])]
+    #[case(SourcePreference::Cache, false, vec![DataSource::Ucsc])]
+    #[case(SourcePreference::Live, true, vec![DataSource::Ucsc, DataSource::Cache])]
+    #[case(SourcePreference::Live, false, vec![DataSource::Ucsc])]
+    fn test_source_order(
+        #[case] preference: SourcePreference,
+        #[case] cache_downloaded: bool,
+        #[case] expected: Vec<DataSource>,
+    ) {
+        assert_eq!(preference.order(cache_downloaded), expected);
+    }
+
+    /// Open a source with a simulated network. UCSC fails with a network error when the network is down.
+    async fn open(
+        preference: SourcePreference,
+        cache_downloaded: bool,
+        network_up: bool,
+    ) -> Result<DataSource, TGVError> {
+        preference
+            .open(&Reference::Hg38, cache_downloaded, |source| async move {
+                match source {
+                    DataSource::Ucsc if !network_up => Err(TGVError::NetworkError(
+                        "UCSC host us is unreachable".to_string(),
+                    )),
+                    source => Ok(source),
+                }
+            })
+            .await
+            .map(|(source, opened)| {
+                assert_eq!(source, opened);
+                opened
+            })
+    }
+
+    #[rstest]
+    #[case(SourcePreference::Cache, true, true, Some(DataSource::Cache))]
+    #[case(SourcePreference::Cache, true, false, Some(DataSource::Cache))]
+    #[case(SourcePreference::Cache, false, true, Some(DataSource::Ucsc))]
+    #[case(SourcePreference::Cache, false, false, None)]
+    #[case(SourcePreference::Live, true, true, Some(DataSource::Ucsc))]
+    #[case(SourcePreference::Live, true, false, Some(DataSource::Cache))]
+    #[case(SourcePreference::Live, false, true, Some(DataSource::Ucsc))]
+    #[case(SourcePreference::Live, false, false, None)]
+    #[tokio::test]
+    async fn test_fallback(
+        #[case] preference: SourcePreference,
+        #[case] cache_downloaded: bool,
+        #[case] network_up: bool,
+        #[case] expected: Option<DataSource>,
+    ) {
+        let result = open(preference, cache_downloaded, network_up).await;
+        match expected {
+            Some(expected) => assert_eq!(result.unwrap(), expected),
+            None => {
+                let message = result.unwrap_err().to_string();
+                assert!(
+                    message.contains("hg38 is not in the local cache"),
+                    "{message}"
+                );
+                assert!(message.contains("tgv download hg38"), "{message}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_explained() {
+        let result = SourcePreference::Cache
+            .open(&Reference::Hg38, false, |_| async {
+                Err::<(), _>(TGVError::ValueError("Unsupported reference".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(TGVError::ValueError(_))));
+    }
+}
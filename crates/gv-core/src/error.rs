@@ -1,4 +1,4 @@
-use bigtools::{BBIReadError, BigBedReadOpenError};
+use bigtools::{BBIReadError, BigBedReadOpenError, BigWigReadOpenError};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -25,6 +25,9 @@ pub enum TGVError {
     #[error("IO Error: {0}")]
     IOError(String),
 
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
     #[error("State error: {0}")]
     StateError(String),
 
@@ -43,6 +46,9 @@ pub enum TGVError {
     #[error("BigBed file parsing error: {0}")]
     BigBedFileParsingError(#[from] BigBedReadOpenError),
 
+    #[error("BigWig file parsing error: {0}")]
+    BigWigFileParsingError(#[from] BigWigReadOpenError),
+
     #[error("BigBed interal parsing error: {0}")]
     BigBedIntervalError(#[from] BBIReadError),
 
@@ -1,4 +1,6 @@
-use crate::{intervals::GenomeInterval, strand::Strand};
+use crate::{error::TGVError, intervals::GenomeInterval, strand::Strand};
+use itertools::Itertools;
+use strum::Display;
 
 // A feature is a interval on a contig.
 
@@ -66,10 +68,10 @@ The current schema is taken from hg38/hg19 ncbiRefSeqSelected. But for other gen
 }
 
 If someone has experience in this, please help.
-For now, I don't interprete sub-gene features for API responses with this format.
+For API responses with this format, exons are computed from blockSizes and chromStarts.
 
 */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Gene {
     pub id: String,
 
@@ -105,6 +107,27 @@ impl GenomeInterval for Gene {
     }
 }
 
+/// Part of a transcript at a position, as reported by `:whatgene`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum GeneRegion {
+    /// Coding part of the exon with the number, counted in transcription order.
+    #[strum(to_string = "exon {0}, CDS")]
+    Cds(usize),
+
+    #[strum(to_string = "exon {0}, 5' UTR")]
+    FivePrimeUtr(usize),
+
+    #[strum(to_string = "exon {0}, 3' UTR")]
+    ThreePrimeUtr(usize),
+
+    /// Exon of a non-coding transcript.
+    #[strum(to_string = "exon {0}")]
+    Exon(usize),
+
+    #[strum(to_string = "intron {0}")]
+    Intron(usize),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[allow(clippy::upper_case_acronyms)]
 enum ExonPosition {
@@ -236,4 +259,264 @@ impl Gene {
 
         output
     }
+    /// Exons in genome order. A gene without exon records is a single exon. 1-based, inclusive.
+    fn exons(&self) -> Vec<(u64, u64)> {
+        if !self.has_exons {
+            return vec![(self.transcription_start, self.transcription_end)];
+        }
+        self.exon_starts
+            .iter()
+            .copied()
+            .zip(self.exon_ends.iter().copied())
+            .sorted()
+            .collect()
+    }
+
+    /// Exon, intron, UTR, or CDS at a position, numbered in transcription order. None outside the transcript.
+    /// 1-based.
+    pub fn region_at(&self, position: u64) -> Option<GeneRegion> {
+        if position < self.transcription_start || position > self.transcription_end {
+            return None;
+        }
+
+        let exons = self.exons();
+        let number = |index: usize| match self.strand {
+            Strand::Forward => index + 1,
+            Strand::Reverse => exons.len() - index,
+        };
+
+        let Some(index) = exons
+            .iter()
+            .position(|(start, end)| *start <= position && position <= *end)
+        else {
+            // Intron i lies between exons i and i + 1 in genome order.
+            let index = exons.iter().filter(|(_, end)| *end < position).count();
+            let number = match self.strand {
+                Strand::Forward => index,
+                Strand::Reverse => exons.len() - index,
+            };
+            return Some(GeneRegion::Intron(number));
+        };
+
+        let number = number(index);
+        Some(if self.cds_start > self.cds_end {
+            GeneRegion::Exon(number)
+        } else if position >= self.cds_start && position <= self.cds_end {
+            GeneRegion::Cds(number)
+        } else if (position < self.cds_start) == (self.strand == Strand::Forward) {
+            GeneRegion::FivePrimeUtr(number)
+        } else {
+            GeneRegion::ThreePrimeUtr(number)
+        })
+    }
+
+    /// Coding segments in genome order. 1-based, inclusive.
+    fn cds_segments(&self) -> Vec<(u64, u64)> {
+        if self.cds_start > self.cds_end {
+            return Vec::new();
+        }
+        if !self.has_exons {
+            return vec![(self.cds_start, self.cds_end)];
+        }
+
+        self.exon_starts
+            .iter()
+            .zip(self.exon_ends.iter())
+            .map(|(start, end)| {
+                (
+                    u64::max(*start, self.cds_start),
+                    u64::min(*end, self.cds_end),
+                )
+            })
+            .filter(|(start, end)| start <= end)
+            .sorted()
+            .collect()
+    }
+
+    /// Genome coordinate of the first base of the codon for protein residue `residue` (p.1 is the start codon), in
+    /// the gene's strand orientation. 1-based.
+    pub fn residue_position(&self, residue: u64) -> Result<u64, TGVError> {
+        let segments = self.cds_segments();
+        if segments.is_empty() {
+            return Err(TGVError::ValueError(format!(
+                "Gene {} has no coding sequence",
+                self.name
+            )));
+        }
+
+        let n_residues = segments
+            .iter()
+            .map(|(start, end)| end - start + 1)
+            .sum::<u64>()
+            / 3;
+        if residue == 0 || residue > n_residues {
+            return Err(TGVError::ValueError(format!(
+                "Residue p.{} is out of range for {} (p.1-p.{})",
+                residue, self.name, n_residues
+            )));
+        }
+
+        let mut offset = (residue - 1) * 3;
+        let ordered_segments: Vec<(u64, u64)> = match self.strand {
+            Strand::Forward => segments,
+            Strand::Reverse => segments.into_iter().rev().collect(),
+        };
+        for (start, end) in ordered_segments {
+            let length = end - start + 1;
+            if offset < length {
+                return Ok(match self.strand {
+                    Strand::Forward => start + offset,
+                    Strand::Reverse => end - offset,
+                });
+            }
+            offset -= length;
+        }
+
+        unreachable!("residue is within the coding sequence")
+    }
+
+    /// Codons with at least one base in [left, right]: the protein residue (p.1 is the start codon) and the genome
+    /// positions of the three bases in reading order. Codons can span exon junctions. A trailing partial codon at the
+    /// end of the coding sequence is skipped. 1-based, inclusive.
+    pub fn codons_overlapping(&self, left: u64, right: u64) -> Vec<(u64, [u64; 3])> {
+        if self.cds_end < left || self.cds_start > right {
+            return Vec::new();
+        }
+
+        let segments = self.cds_segments();
+        let positions: Vec<u64> = match self.strand {
+            Strand::Forward => segments
+                .iter()
+                .flat_map(|(start, end)| *start..=*end)
+                .collect(),
+            Strand::Reverse => segments
+                .iter()
+                .rev()
+                .flat_map(|(start, end)| (*start..=*end).rev())
+                .collect(),
+        };
+
+        positions
+            .chunks_exact(3)
+            .enumerate()
+            .filter(|(_, codon)| {
+                codon
+                    .iter()
+                    .any(|position| left <= *position && *position <= right)
+            })
+            .map(|(i, codon)| (i as u64 + 1, [codon[0], codon[1], codon[2]]))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn gene(strand: Strand, cds: (u64, u64), exons: &[(u64, u64)]) -> Gene {
+        Gene {
+            id: "gene".to_string(),
+            name: "gene".to_string(),
+            strand,
+            contig_index: 0,
+            transcription_start: exons.first().map_or(cds.0, |exon| exon.0),
+            transcription_end: exons.last().map_or(cds.1, |exon| exon.1),
+            cds_start: cds.0,
+            cds_end: cds.1,
+            exon_starts: exons.iter().map(|exon| exon.0).collect(),
+            exon_ends: exons.iter().map(|exon| exon.1).collect(),
+            has_exons: !exons.is_empty(),
+        }
+    }
+
+    // CDS 105-130 over exons 101-110 and 121-140: coding bases 105-110 and 121-130. 16 bases, 5 residues.
+    #[rstest]
+    #[case(Strand::Forward, 1, Ok(105))]
+    #[case(Strand::Forward, 2, Ok(108))]
+    #[case(Strand::Forward, 3, Ok(121))]
+    #[case(Strand::Forward, 5, Ok(127))]
+    #[case(Strand::Reverse, 1, Ok(130))]
+    #[case(Strand::Reverse, 4, Ok(121))]
+    #[case(Strand::Reverse, 5, Ok(108))]
+    #[case(Strand::Forward, 0, Err(()))]
+    #[case(Strand::Forward, 6, Err(()))]
+    fn test_residue_position(
+        #[case] strand: Strand,
+        #[case] residue: u64,
+        #[case] expected: Result<u64, ()>,
+    ) {
+        let gene = gene(strand, (105, 130), &[(101, 110), (121, 140)]);
+        assert_eq!(gene.residue_position(residue).map_err(|_| ()), expected);
+    }
+
+    #[test]
+    fn test_residue_position_requires_cds() {
+        let gene = gene(Strand::Forward, (26, 25), &[(1, 50)]);
+        assert!(gene.residue_position(1).is_err());
+    }
+
+    // Same coding bases as above: 105-110 and 121-130. The last base in reading order is a partial codon.
+    #[rstest]
+    #[case(Strand::Forward, 1, 200, vec![(1, [105, 106, 107]), (2, [108, 109, 110]), (3, [121, 122, 123]), (4, [124, 125, 126]), (5, [127, 128, 129])])]
+    #[case(Strand::Forward, 109, 121, vec![(2, [108, 109, 110]), (3, [121, 122, 123])])]
+    #[case(Strand::Forward, 130, 200, vec![])]
+    #[case(Strand::Reverse, 1, 200, vec![(1, [130, 129, 128]), (2, [127, 126, 125]), (3, [124, 123, 122]), (4, [121, 110, 109]), (5, [108, 107, 106])])]
+    #[case(Strand::Reverse, 110, 115, vec![(4, [121, 110, 109])])]
+    #[case(Strand::Reverse, 105, 105, vec![])]
+    #[case(Strand::Forward, 111, 120, vec![])]
+    fn test_codons_overlapping(
+        #[case] strand: Strand,
+        #[case] left: u64,
+        #[case] right: u64,
+        #[case] expected: Vec<(u64, [u64; 3])>,
+    ) {
+        let gene = gene(strand, (105, 130), &[(101, 110), (121, 140)]);
+        assert_eq!(gene.codons_overlapping(left, right), expected);
+    }
+
+    // CDS 105-130 over exons 101-110, 121-140, and 151-160.
+    #[rstest]
+    #[case(Strand::Forward, 101, Some(GeneRegion::FivePrimeUtr(1)))]
+    #[case(Strand::Forward, 104, Some(GeneRegion::FivePrimeUtr(1)))]
+    #[case(Strand::Forward, 105, Some(GeneRegion::Cds(1)))]
+    #[case(Strand::Forward, 111, Some(GeneRegion::Intron(1)))]
+    #[case(Strand::Forward, 130, Some(GeneRegion::Cds(2)))]
+    #[case(Strand::Forward, 131, Some(GeneRegion::ThreePrimeUtr(2)))]
+    #[case(Strand::Forward, 150, Some(GeneRegion::Intron(2)))]
+    #[case(Strand::Forward, 160, Some(GeneRegion::ThreePrimeUtr(3)))]
+    #[case(Strand::Forward, 100, None)]
+    #[case(Strand::Forward, 161, None)]
+    // On the minus strand, exons are numbered from the right and the 5' UTR is on the right.
+    #[case(Strand::Reverse, 101, Some(GeneRegion::ThreePrimeUtr(3)))]
+    #[case(Strand::Reverse, 105, Some(GeneRegion::Cds(3)))]
+    #[case(Strand::Reverse, 115, Some(GeneRegion::Intron(2)))]
+    #[case(Strand::Reverse, 135, Some(GeneRegion::FivePrimeUtr(2)))]
+    #[case(Strand::Reverse, 145, Some(GeneRegion::Intron(1)))]
+    #[case(Strand::Reverse, 155, Some(GeneRegion::FivePrimeUtr(1)))]
+    fn test_region_at(
+        #[case] strand: Strand,
+        #[case] position: u64,
+        #[case] expected: Option<GeneRegion>,
+    ) {
+        let gene = gene(strand, (105, 130), &[(101, 110), (121, 140), (151, 160)]);
+        assert_eq!(gene.region_at(position), expected);
+    }
+
+    #[rstest]
+    #[case(&[(101, 110), (121, 140)], 105, Some(GeneRegion::Exon(1)))]
+    #[case(&[(101, 110), (121, 140)], 115, Some(GeneRegion::Intron(1)))]
+    #[case(&[], 105, Some(GeneRegion::Exon(1)))]
+    fn test_region_at_non_coding(
+        #[case] exons: &[(u64, u64)],
+        #[case] position: u64,
+        #[case] expected: Option<GeneRegion>,
+    ) {
+        let mut gene = gene(Strand::Forward, (141, 140), exons);
+        if exons.is_empty() {
+            gene.transcription_start = 101;
+            gene.transcription_end = 140;
+        }
+        assert_eq!(gene.region_at(position), expected);
+    }
 }
@@ -0,0 +1,146 @@
+use crate::{intervals::GenomeInterval, sequence::Sequence};
+
+/// Shortest run of Ns shown as a gap. Shorter runs are usually ambiguous bases rather than assembly gaps.
+pub const MIN_N_RUN_LENGTH: usize = 10;
+
+/// An assembly gap: a record of the UCSC `gap` table, or a run of Ns in the reference sequence.
+/// 1-based, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssemblyGap {
+    pub contig_index: usize,
+    pub start: u64,
+    pub end: u64,
+
+    /// Gap type from the gap table, e.g. `telomere` or `contig`. None for runs of Ns.
+    pub gap_type: Option<String>,
+}
+
+impl GenomeInterval for AssemblyGap {
+    fn contig_index(&self) -> usize {
+        self.contig_index
+    }
+
+    fn start(&self) -> u64 {
+        self.start
+    }
+
+    fn end(&self) -> u64 {
+        self.end
+    }
+}
+
+/// Assembly gaps of a contig, shown with `:gaps`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapTrack {
+    pub contig_index: usize,
+
+    /// Records of the gap table, sorted by start. None if the reference has no gap table; gaps are then detected as
+    /// runs of Ns in the loaded sequence.
+    pub gaps: Option<Vec<AssemblyGap>>,
+}
+
+impl GapTrack {
+    /// Gaps overlapping [start, end]. Without a gap table, runs of Ns in the loaded part of the sequence.
+    /// 1-based, inclusive.
+    pub fn gaps_in(&self, sequence: &Sequence, start: u64, end: u64) -> Vec<AssemblyGap> {
+        match &self.gaps {
+            Some(gaps) => gaps
+                .iter()
+                .filter(|gap| gap.overlaps(self.contig_index, start, end))
+                .cloned()
+                .collect(),
+            None if sequence.contig_index == self.contig_index => {
+                n_runs(sequence, MIN_N_RUN_LENGTH)
+                    .into_iter()
+                    .filter(|gap| gap.overlaps(self.contig_index, start, end))
+                    .collect()
+            }
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Runs of at least min_length Ns (case-insensitive) in a sequence.
+pub fn n_runs(sequence: &Sequence, min_length: usize) -> Vec<AssemblyGap> {
+    let mut gaps = Vec::new();
+    let mut i = 0;
+    while i < sequence.sequence.len() {
+        if !sequence.sequence[i].eq_ignore_ascii_case(&b'N') {
+            i += 1;
+            continue;
+        }
+        let first = i;
+        while i < sequence.sequence.len() && sequence.sequence[i].eq_ignore_ascii_case(&b'N') {
+            i += 1;
+        }
+        if i - first >= min_length.max(1) {
+            gaps.push(AssemblyGap {
+                contig_index: sequence.contig_index,
+                start: sequence.start + first as u64,
+                end: sequence.start + i as u64 - 1,
+                gap_type: None,
+            });
+        }
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn sequence(start: u64, bases: &[u8]) -> Sequence {
+        Sequence {
+            start,
+            sequence: bases.to_vec(),
+            contig_index: 0,
+        }
+    }
+
+    fn gap(start: u64, end: u64) -> AssemblyGap {
+        AssemblyGap {
+            contig_index: 0,
+            start,
+            end,
+            gap_type: None,
+        }
+    }
+
+    #[rstest]
+    #[case(b"ACNNNNGT", 3, vec![gap(102, 105)])]
+    #[case(b"ACNNNNGT", 5, vec![])]
+    // Soft-masked Ns, runs at both ends
+    #[case(b"NNNACGnnnnNT", 3, vec![gap(100, 102), gap(106, 110)])]
+    #[case(b"ACNGT", 1, vec![gap(102, 102)])]
+    #[case(b"", 1, vec![])]
+    fn runs_of_ns_are_gaps(
+        #[case] bases: &[u8],
+        #[case] min_length: usize,
+        #[case] expected: Vec<AssemblyGap>,
+    ) {
+        assert_eq!(n_runs(&sequence(100, bases), min_length), expected);
+    }
+
+    #[test]
+    fn gap_tables_take_precedence_over_runs_of_ns() {
+        let sequence = sequence(1, &[b"ACGT".as_slice(), &[b'N'; 20], b"ACGT"].concat());
+        let without_table = GapTrack {
+            contig_index: 0,
+            gaps: None,
+        };
+        assert_eq!(without_table.gaps_in(&sequence, 1, 28), vec![gap(5, 24)]);
+        assert_eq!(without_table.gaps_in(&sequence, 25, 28), vec![]);
+
+        let telomere = AssemblyGap {
+            gap_type: Some("telomere".to_string()),
+            ..gap(1, 10_000)
+        };
+        let with_table = GapTrack {
+            contig_index: 0,
+            gaps: Some(vec![telomere.clone()]),
+        };
+        assert_eq!(with_table.gaps_in(&sequence, 1, 28), vec![telomere]);
+        assert_eq!(with_table.gaps_in(&sequence, 10_001, 20_000), vec![]);
+    }
+}
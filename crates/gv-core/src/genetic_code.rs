@@ -0,0 +1,128 @@
+use crate::{contig_header::Contig, error::TGVError};
+
+/// NCBI translation tables.
+/// See: https://www.ncbi.nlm.nih.gov/Taxonomy/Utils/wprintgc.cgi
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneticCode {
+    /// Table 1.
+    Standard,
+    /// Table 2. TGA = Trp, ATA = Met, AGA / AGG = stop.
+    VertebrateMitochondrial,
+    /// Table 5. TGA = Trp, ATA = Met, AGA / AGG = Ser.
+    InvertebrateMitochondrial,
+}
+
+impl GeneticCode {
+    /// Contig names treated as mitochondrial when the genetic code is chosen automatically.
+    const MITOCHONDRIAL_CONTIG_NAMES: [&'static str; 4] = ["chrm", "chrmt", "mt", "m"];
+
+    /// Amino acids for codons in TCAG order: TTT, TTC, TTA, TTG, TCT, ..., GGG.
+    fn amino_acids(&self) -> &'static [u8; 64] {
+        match self {
+            GeneticCode::Standard => {
+                b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG"
+            }
+            GeneticCode::VertebrateMitochondrial => {
+                b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSS**VVVVAAAADDEEGGGG"
+            }
+            GeneticCode::InvertebrateMitochondrial => {
+                b"FFLLSSSSYY**CCWWLLLLPPPPHHQQRRRRIIMMTTTTNNKKSSSSVVVVAAAADDEEGGGG"
+            }
+        }
+    }
+
+    /// Translate a codon to a one-letter amino acid. Stop codons are `*`.
+    /// None if the codon is not 3 bases or contains non-ACGT bases.
+    pub fn translate(&self, codon: &[u8]) -> Option<u8> {
+        if codon.len() != 3 {
+            return None;
+        }
+
+        let mut index = 0;
+        for base in codon {
+            index = index * 4
+                + match base.to_ascii_uppercase() {
+                    b'T' | b'U' => 0,
+                    b'C' => 1,
+                    b'A' => 2,
+                    b'G' => 3,
+                    _ => return None,
+                };
+        }
+
+        Some(self.amino_acids()[index])
+    }
+
+    /// Vertebrate mitochondrial code for mitochondrial contigs (chrM, MT, ...), otherwise the standard code.
+    pub fn for_contig(contig: &Contig) -> Self {
+        if std::iter::once(&contig.name)
+            .chain(contig.aliases.iter())
+            .any(|name| Self::MITOCHONDRIAL_CONTIG_NAMES.contains(&name.to_lowercase().as_str()))
+        {
+            GeneticCode::VertebrateMitochondrial
+        } else {
+            GeneticCode::Standard
+        }
+    }
+}
+
+impl std::str::FromStr for GeneticCode {
+    type Err = TGVError;
+
+    /// Parse `"standard"`, `"vertmito"`, or `"invertmito"`. NCBI table numbers are also accepted.
+    fn from_str(s: &str) -> Result<Self, TGVError> {
+        match s {
+            "standard" | "1" => Ok(Self::Standard),
+            "vertmito" | "2" => Ok(Self::VertebrateMitochondrial),
+            "invertmito" | "5" => Ok(Self::InvertebrateMitochondrial),
+            _ => Err(TGVError::ParsingError(format!(
+                "Invalid genetic code `{s}`. Expected \"standard\", \"vertmito\", \"invertmito\", or \"auto\"."
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for GeneticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GeneticCode::Standard => write!(f, "standard"),
+            GeneticCode::VertebrateMitochondrial => write!(f, "vertmito"),
+            GeneticCode::InvertebrateMitochondrial => write!(f, "invertmito"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(GeneticCode::Standard, b"ATG", Some(b'M'))]
+    #[case(GeneticCode::Standard, b"TGA", Some(b'*'))]
+    #[case(GeneticCode::Standard, b"ATA", Some(b'I'))]
+    #[case(GeneticCode::Standard, b"AGA", Some(b'R'))]
+    #[case(GeneticCode::Standard, b"ggc", Some(b'G'))]
+    #[case(GeneticCode::VertebrateMitochondrial, b"TGA", Some(b'W'))]
+    #[case(GeneticCode::VertebrateMitochondrial, b"ATA", Some(b'M'))]
+    #[case(GeneticCode::VertebrateMitochondrial, b"AGA", Some(b'*'))]
+    #[case(GeneticCode::InvertebrateMitochondrial, b"AGG", Some(b'S'))]
+    #[case(GeneticCode::Standard, b"ANG", None)]
+    #[case(GeneticCode::Standard, b"AT", None)]
+    fn test_translate(
+        #[case] genetic_code: GeneticCode,
+        #[case] codon: &[u8],
+        #[case] expected: Option<u8>,
+    ) {
+        assert_eq!(genetic_code.translate(codon), expected);
+    }
+
+    #[rstest]
+    #[case("chrM", GeneticCode::VertebrateMitochondrial)]
+    #[case("MT", GeneticCode::VertebrateMitochondrial)]
+    #[case("chr1", GeneticCode::Standard)]
+    #[case("NC_000913.3", GeneticCode::Standard)]
+    fn test_for_contig(#[case] name: &str, #[case] expected: GeneticCode) {
+        assert_eq!(GeneticCode::for_contig(&Contig::new(name, None)), expected);
+    }
+}
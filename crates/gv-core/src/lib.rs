@@ -1,16 +1,24 @@
 pub mod alignment;
 pub mod bed;
+pub mod bigwig;
 pub mod command;
 pub mod contig_header;
+pub mod contig_overview;
 pub mod cytoband;
+pub mod data_source;
 pub mod error;
 pub mod feature;
+pub mod gaps;
+pub mod genetic_code;
 pub mod intervals;
+pub mod loci;
 pub mod logging;
 pub mod message;
 pub mod normal;
 pub mod reference;
+pub mod regions;
 pub mod repository;
+pub mod retry;
 pub mod sequence;
 pub mod settings;
 pub mod state;
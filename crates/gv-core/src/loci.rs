@@ -0,0 +1,239 @@
+use crate::error::TGVError;
+use std::{fmt, str::FromStr};
+
+/// A locus to review: a position on a contig or a gene.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Locus {
+    /// Contig name and 1-based position. The name can be an alias.
+    ContigPosition(String, u64),
+
+    Gene(String),
+}
+
+impl FromStr for Locus {
+    type Err = TGVError;
+
+    /// Parse `contig:pos` (e.g. `chr17:7,572,659`) or a gene name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || TGVError::ParsingError(format!("Invalid locus `{s}`: expected CONTIG:POS or GENE"));
+        let s = s.trim();
+        if s.is_empty() || s.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        match s.split_once(':') {
+            Some((contig_name, position)) => {
+                let position = position
+                    .replace(',', "")
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|position| *position > 0)
+                    .ok_or_else(invalid)?;
+                if contig_name.is_empty() {
+                    return Err(invalid());
+                }
+                Ok(Locus::ContigPosition(contig_name.to_string(), position))
+            }
+            None => Ok(Locus::Gene(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Locus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Locus::ContigPosition(contig_name, position) => write!(f, "{contig_name}:{position}"),
+            Locus::Gene(gene_name) => write!(f, "{gene_name}"),
+        }
+    }
+}
+
+/// Loci loaded with `--loci`, visited in order with n / N.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocusList {
+    pub loci: Vec<Locus>,
+
+    /// Index of the locus visited last. None before the first visit.
+    pub current: Option<usize>,
+}
+
+impl LocusList {
+    /// Parse a locus list with one locus per line. Blank lines and lines starting with `#` are ignored. Lines that
+    /// are not a locus are skipped; a warning is returned for each of them.
+    pub fn parse(content: &str) -> (Self, Vec<String>) {
+        let mut loci = Vec::new();
+        let mut warnings = Vec::new();
+        for (line_index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.parse::<Locus>() {
+                Ok(locus) => loci.push(locus),
+                Err(_) => warnings.push(format!(
+                    "Skipped line {} of the locus list: `{line}` is not CONTIG:POS or GENE",
+                    line_index + 1
+                )),
+            }
+        }
+        (
+            Self {
+                loci,
+                current: None,
+            },
+            warnings,
+        )
+    }
+
+    pub fn from_path(path: &str) -> Result<(Self, Vec<String>), TGVError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| TGVError::IOError(format!("Failed to read the locus list {path}: {e}")))?;
+        Ok(Self::parse(&content))
+    }
+
+    /// Visit the locus n steps after the current one, wrapping around after the last. The first step from no visit
+    /// lands on the first locus.
+    pub fn next(&mut self, n: usize) -> Result<&Locus, TGVError> {
+        let len = self.checked_len()?;
+        let index = match self.current {
+            Some(current) => (current + n % len) % len,
+            None => (n.max(1) - 1) % len,
+        };
+        self.current = Some(index);
+        Ok(&self.loci[index])
+    }
+
+    /// Visit the locus n steps before the current one, wrapping around before the first. The first step from no
+    /// visit lands on the last locus.
+    pub fn previous(&mut self, n: usize) -> Result<&Locus, TGVError> {
+        let len = self.checked_len()?;
+        let index = match self.current {
+            Some(current) => (current + len - n % len) % len,
+            None => len - 1 - (n.max(1) - 1) % len,
+        };
+        self.current = Some(index);
+        Ok(&self.loci[index])
+    }
+
+    /// Progress through the list, e.g. `3/42`. None before the first visit.
+    pub fn progress(&self) -> Option<String> {
+        self.current
+            .map(|current| format!("{}/{}", current + 1, self.loci.len()))
+    }
+
+    fn checked_len(&self) -> Result<usize, TGVError> {
+        match self.loci.len() {
+            0 => Err(TGVError::StateError(
+                "No loci are loaded. Load a locus list with --loci FILE".to_string(),
+            )),
+            len => Ok(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("chr17:7572659", Ok(Locus::ContigPosition("chr17".to_string(), 7572659)))]
+    #[case(" 17:7,572,659 ", Ok(Locus::ContigPosition("17".to_string(), 7572659)))]
+    #[case("TP53", Ok(Locus::Gene("TP53".to_string())))]
+    #[case("chr17:", Err(()))]
+    #[case(":100", Err(()))]
+    #[case("chr17:0", Err(()))]
+    #[case("chr17:abc", Err(()))]
+    #[case("TP53 BRCA1", Err(()))]
+    #[case("", Err(()))]
+    fn test_parse_locus(#[case] input: &str, #[case] expected: Result<Locus, ()>) {
+        assert_eq!(input.parse::<Locus>().map_err(|_| ()), expected);
+    }
+
+    #[test]
+    fn unparseable_lines_are_skipped_with_a_warning() {
+        let (loci, warnings) =
+            LocusList::parse("# review list\nchr1:100\n\nKRAS\nchr1:x\nchr2:2,000\nTP53 extra\n");
+        assert_eq!(
+            loci.loci,
+            vec![
+                Locus::ContigPosition("chr1".to_string(), 100),
+                Locus::Gene("KRAS".to_string()),
+                Locus::ContigPosition("chr2".to_string(), 2000),
+            ]
+        );
+        assert_eq!(loci.current, None);
+        assert_eq!(
+            warnings,
+            vec![
+                "Skipped line 5 of the locus list: `chr1:x` is not CONTIG:POS or GENE",
+                "Skipped line 7 of the locus list: `TP53 extra` is not CONTIG:POS or GENE",
+            ]
+        );
+    }
+
+    fn locus_list(len: usize, current: Option<usize>) -> LocusList {
+        LocusList {
+            loci: (1..=len as u64)
+                .map(|position| Locus::ContigPosition("chr1".to_string(), position))
+                .collect(),
+            current,
+        }
+    }
+
+    #[rstest]
+    #[case(3, None, 1, 0)]
+    #[case(3, None, 2, 1)]
+    #[case(3, Some(0), 1, 1)]
+    #[case(3, Some(2), 1, 0)]
+    #[case(3, Some(1), 5, 0)]
+    #[case(1, Some(0), 1, 0)]
+    fn next_wraps_around(
+        #[case] len: usize,
+        #[case] current: Option<usize>,
+        #[case] n: usize,
+        #[case] expected: usize,
+    ) {
+        let mut loci = locus_list(len, current);
+        let locus = loci.next(n).unwrap().clone();
+        assert_eq!(locus, loci.loci[expected]);
+        assert_eq!(loci.current, Some(expected));
+    }
+
+    #[rstest]
+    #[case(3, None, 1, 2)]
+    #[case(3, None, 2, 1)]
+    #[case(3, Some(2), 1, 1)]
+    #[case(3, Some(0), 1, 2)]
+    #[case(3, Some(1), 5, 2)]
+    #[case(1, Some(0), 1, 0)]
+    fn previous_wraps_around(
+        #[case] len: usize,
+        #[case] current: Option<usize>,
+        #[case] n: usize,
+        #[case] expected: usize,
+    ) {
+        let mut loci = locus_list(len, current);
+        let locus = loci.previous(n).unwrap().clone();
+        assert_eq!(locus, loci.loci[expected]);
+        assert_eq!(loci.current, Some(expected));
+    }
+
+    #[test]
+    fn progress_counts_from_one() {
+        let mut loci = locus_list(42, None);
+        assert_eq!(loci.progress(), None);
+        loci.next(3).unwrap();
+        assert_eq!(loci.progress(), Some("3/42".to_string()));
+        loci.previous(3).unwrap();
+        assert_eq!(loci.progress(), Some("42/42".to_string()));
+    }
+
+    #[test]
+    fn stepping_an_empty_list_is_an_error() {
+        let mut loci = LocusList::default();
+        assert!(loci.next(1).is_err());
+        assert!(loci.previous(1).is_err());
+        assert_eq!(loci.current, None);
+    }
+}
@@ -1,5 +1,12 @@
-use crate::strand::Strand;
-
+use crate::{
+    alignment::HiddenReadCategory,
+    genetic_code::GeneticCode,
+    sequence::Motif,
+    strand::Strand,
+    variant::{VariantAllele, VariantFilter},
+};
+
+use serde::{Deserialize, Serialize};
 use strum::Display;
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
@@ -14,6 +21,108 @@ pub enum Message {
     SaveSession(Option<String>),
     SaveAndQuit(Option<String>),
     SetAlignmentOption(Vec<AlignmentDisplayOption>),
+    /// Remove all alignment filters. Sorting and pairing are kept.
+    ClearAlignmentFilter,
+    /// Switch alignment tracks between the paired (true) and linear views. Other options are kept.
+    SetViewAsPairs(bool),
+    /// Hide reads with MAPQ below the threshold, replacing the previous threshold. 0 removes the threshold.
+    SetMinMapq(u8),
+    /// Show only reads carrying an allele of the variant at the cursor, replacing the previous allele. None shows reads
+    /// of all alleles.
+    IsolateAllele(Option<IsolatedAllele>),
+
+    /// Show gene tracks available for the reference.
+    ListTracks,
+    /// Override the preferred gene track.
+    UseTrack(String),
+
+    /// List genes whose names match a pattern to pick one to go to.
+    FindGene(String),
+
+    /// Override the genetic code used for translation. None chooses the code by contig.
+    SetGeneticCode(Option<GeneticCode>),
+
+    /// Cap the coverage axis at the depth. None scales the axis to the maximum depth in view.
+    SetCoverageMax(Option<usize>),
+
+    /// Show soft-masked (lowercase) reference bases dimmed, or like other bases.
+    SetMasking(bool),
+
+    /// Toggle underlining homopolymers and short tandem repeats in the sequence row.
+    ToggleRepeats,
+
+    /// Underline repeats at least this long in the sequence row. None turns the highlight off.
+    SetRepeatMinLength(Option<usize>),
+
+    /// Toggle marking CG dinucleotides in the sequence row.
+    ToggleCpg,
+
+    /// Show the whole focus contig with gene counts and coverage binned.
+    ShowContigOverview,
+
+    /// Make the other pane of a split screen active.
+    SwitchPane,
+
+    /// Make the next alignment track the one that keys and commands act on, cycling back to all tracks after the last.
+    CycleAlignmentTrack,
+
+    /// Frame a gene in the view, padded by the frame.
+    FrameGene(String, GeneFrame),
+
+    /// Frame a cytoband and its sub-bands in the view: (contig, band), e.g. ("17", "q21").
+    FrameBand(String, String),
+
+    /// Pan the view so that the cursor is at the anchor, keeping the cursor position.
+    CenterCursor(CursorAnchor),
+
+    /// Toggle showing the complement of the reference and read bases. The view is not reversed.
+    ToggleRevcomp,
+
+    /// Show mismatched read bases with a quality score below the threshold as matches. 0 shows all mismatches.
+    SetMismatchQuality(u8),
+
+    /// Set the interval between major ruler ticks in bp. None spaces the ticks by the zoom.
+    SetRulerInterval(Option<u64>),
+    /// Set whether coordinates are displayed 0-based or 1-based.
+    SetCoordinateBase(CoordinateBase),
+    /// Cap the number of displayed reads per column. Coverage still counts all reads.
+    SetDownsample(Downsample),
+    /// Translate the codon starting at the focus.
+    LookupCodon,
+    /// Report the gene and the exon, intron, UTR, or CDS at the cursor, or the distance to the nearest gene.
+    LookupGene,
+    /// Show the sequences inserted at the cursor in a popup.
+    ShowInsertions,
+    /// Show a histogram of insertion and deletion lengths of the shown reads in view in a popup.
+    ShowIndelHistogram,
+    /// Include reads of every hidden category (unmapped, secondary, supplementary, duplicate) in alignment tracks.
+    ShowHiddenReads,
+    /// Include reads of a hidden category in alignment tracks.
+    ShowReads(HiddenReadCategory),
+    /// Hide reads of a category.
+    HideReads(HiddenReadCategory),
+    /// Highlight reads carrying the alternate allele of the variant nearest to the cursor, and dim the rest.
+    HighlightVariantSupport,
+    /// Stop highlighting variant-supporting reads.
+    ClearVariantSupport,
+    /// Write the shown reads of the first alignment track in view to a BAM file at the path.
+    ExportReads(String),
+    /// Write the coverage of the first alignment track in view to a bedGraph file at the path, in bins of the size.
+    ExportCoverage(String, u64),
+    /// Show REF/ALT, QUAL, FILTER, INFO, and sample genotypes of the variants at the cursor in a popup.
+    ShowVariantDetails,
+    /// INFO fields listed in the variant popup. None lists all fields.
+    SetVariantInfoFields(Option<Vec<String>>),
+    /// Show only variants passing the filter in variant tracks. Replaces the previous filter.
+    SetVariantFilter(VariantFilter),
+    /// Write the reference sequence in view on the strand as FASTA to the path, or to stdout on exit without a path.
+    ExportSequence(Strand, Option<String>),
+    /// Save the focus under the name, replacing a bookmark with the same name.
+    SetBookmark(String),
+    /// Set the motif searched with `:find`. None stops repeating the search with n / N.
+    SetMotif(Option<Motif>),
+    /// Re-open the alignment, variant, BED, and bigWig files from disk and reload the data in view.
+    ReloadFiles,
 
     Message(String),
 }
@@ -38,6 +147,34 @@ impl From<Zoom> for Message {
 pub enum Zoom {
     Out(u64),
     In(u64),
+
+    /// Zoom to a preset around the cursor, from any zoom.
+    Preset(ZoomPreset),
+}
+
+/// Zoom levels of `g1`, `g2`, and `g3`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Display)]
+pub enum ZoomPreset {
+    /// 1 bp per column.
+    Base,
+
+    /// About 1 kb: codons and exons.
+    Gene,
+
+    /// About 5 kb.
+    Kilobases,
+}
+
+impl ZoomPreset {
+    /// Bases per column in an area of width columns.
+    pub fn zoom(&self, width: u16) -> u64 {
+        let span = match self {
+            ZoomPreset::Base => return 1,
+            ZoomPreset::Gene => 1_000u64,
+            ZoomPreset::Kilobases => 5_000,
+        };
+        u64::max(1, span.div_ceil(u64::max(1, width as u64)))
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Display)]
@@ -76,12 +213,32 @@ pub enum Movement {
     NextGenesEnd(usize),
     PreviousGenesStart(usize),
     PreviousGenesEnd(usize),
+    /// Variants in all variant tracks, continuing on the next / previous contigs at the end of a contig.
+    NextVariants(usize),
+    PreviousVariants(usize),
 
     NextContig(usize),
     PreviousContig(usize),
     ContigIndex(usize),
 
     Gene(String),
+    /// First base of the codon for a protein residue of a gene, e.g. BRCA1:p.185.
+    GeneResidue(String, u64),
+    /// Locus saved with `:mark`.
+    Bookmark(String),
+    /// Loci of the locus list, wrapping around at the ends.
+    NextLocus(usize),
+    PreviousLocus(usize),
+    /// Starts of the target regions, wrapping around at the ends.
+    NextRegions(usize),
+    PreviousRegions(usize),
+    /// Matches of the `:find` motif on the focus contig, wrapping around at the ends.
+    NextMatches(usize),
+    PreviousMatches(usize),
+    /// Highest coverage peak of the focus contig, estimated from the index of the first BAM.
+    HighestPeak,
+    /// Peaks in descending coverage after the last visited one, wrapping around after the lowest.
+    NextPeaks(usize),
 
     Default, // Calculate a default location based on the genome context
 
@@ -109,10 +266,134 @@ pub enum AlignmentDisplayOption {
     #[strum(to_string = "Sort: {0}")]
     Sort(AlignmentSort),
 
+    #[strum(to_string = "Color: {0}")]
+    Color(AlignmentColor),
+
+    #[strum(to_string = "Group: {0}")]
+    Group(AlignmentGroup),
+
     ViewAsPairs,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Display)]
+/// How reads are partitioned into vertical blocks.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Display)]
+pub enum AlignmentGroup {
+    #[default]
+    Default,
+
+    /// Pair orientation (F1R2, F2R1, ...). Reads without a mapped mate on the same contig are grouped as other.
+    Orientation,
+
+    /// Haplotype from the HP tag of phased reads, each block under a label row. Reads without HP are unphased.
+    #[strum(to_string = "HP")]
+    Haplotype,
+}
+
+/// How aligned segments are colored.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Display)]
+pub enum AlignmentColor {
+    #[default]
+    Default,
+
+    /// Gradient by the reference span of the read.
+    #[strum(to_string = "Length")]
+    ReadLength,
+
+    /// A color per value of an auxiliary tag, e.g. HP or RG.
+    #[strum(to_string = "Tag {0}")]
+    Tag(String),
+}
+
+/// Allele of a variant whose reads are kept with `:isolate`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Display, Serialize, Deserialize)]
+pub enum IsolatedAllele {
+    #[strum(to_string = "REF")]
+    Reference,
+
+    #[strum(to_string = "ALT")]
+    Alternate,
+}
+
+/// Padding around a gene framed with `:goto GENE +FLANK` or `:goto GENE PERCENT%`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Display)]
+pub enum GeneFrame {
+    /// Pad each side of the gene by a flank in bp.
+    #[strum(to_string = "+{0}bp")]
+    Flank(u64),
+
+    /// Window width as a percentage of the gene length. At 150%, the gene occupies two thirds of the window.
+    #[strum(to_string = "{0}%")]
+    Percent(u64),
+}
+
+/// Where `:center` puts the cursor in the view.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Display)]
+pub enum CursorAnchor {
+    #[default]
+    Center,
+
+    /// Left edge of the view.
+    Start,
+
+    /// Right edge of the view.
+    End,
+}
+
+/// Base of the displayed coordinates. Coordinates are always 1-based internally; this only changes the ruler and the
+/// status bar.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Display)]
+pub enum CoordinateBase {
+    /// 1-based, inclusive, as in VCF, GFF, and SAM.
+    #[default]
+    #[strum(to_string = "1-based")]
+    One,
+
+    /// 0-based, half-open, as in BED and BAM.
+    #[strum(to_string = "0-based")]
+    Zero,
+}
+
+impl CoordinateBase {
+    /// Displayed coordinate of a 1-based position.
+    pub fn position(&self, position: u64) -> u64 {
+        match self {
+            CoordinateBase::One => position,
+            CoordinateBase::Zero => position.saturating_sub(1),
+        }
+    }
+
+    /// 1-based position of a displayed coordinate.
+    pub fn from_displayed(&self, coordinate: u64) -> u64 {
+        match self {
+            CoordinateBase::One => coordinate,
+            CoordinateBase::Zero => coordinate + 1,
+        }
+    }
+
+    /// Displayed range of the 1-based, inclusive interval [start, end]. 0-based ranges are half-open, so the end
+    /// stays the same.
+    pub fn range(&self, start: u64, end: u64) -> (u64, u64) {
+        match self {
+            CoordinateBase::One => (start, end),
+            CoordinateBase::Zero => (start.saturating_sub(1), end),
+        }
+    }
+}
+
+/// Cap on the number of displayed reads per column.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Display)]
+pub enum Downsample {
+    /// Cap derived from the height of the alignment track.
+    #[default]
+    Auto,
+
+    #[strum(to_string = "{0}")]
+    Depth(usize),
+
+    Off,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Display, Serialize, Deserialize)]
 pub enum AlignmentFilter {
     Default,
 
@@ -145,12 +426,29 @@ pub enum AlignmentFilter {
 
     BaseAtCurrentPositionSoftClip,
 
+    /// Carries the allele of the variant. Indels match on the insertion or deletion; the reference allele of an indel
+    /// matches reads spanning the locus without it.
+    #[strum(to_string = "{0} {1}")]
+    Allele(IsolatedAllele, VariantAllele),
+
+    /// Reference span (from the cigar) greater or equal than
+    #[strum(to_string = "Length>={0}")]
+    ReadLengthGE(u64),
+
+    /// Reference span (from the cigar) smaller or equal than
+    #[strum(to_string = "Length<={0}")]
+    ReadLengthLE(u64),
+
     /// MAPQ greater or equal than
     MappingQualityGE(u16),
 
     /// MAPQ smaller or equal than
     MappingQualityLE(u16),
 
+    /// MAPQ greater or equal than. Unlike MappingQualityGE, reads without MAPQ (255) pass.
+    #[strum(to_string = "MAPQ>={0}")]
+    MinMapq(u8),
+
     /// All bits in the flag are 1 (equivalent to samtools view -f)
     FlagsAll(u32),
 
@@ -198,6 +496,26 @@ impl AlignmentFilter {
         }
     }
 
+    /// The filter with MinMapq thresholds removed from AND chains.
+    pub fn without_min_mapq(self) -> Self {
+        match self {
+            Self::MinMapq(_) => Self::Default,
+            Self::And(filter1, filter2) => {
+                filter1.without_min_mapq().and(filter2.without_min_mapq())
+            }
+            self_ => self_,
+        }
+    }
+
+    /// The filter with isolated alleles removed from AND chains.
+    pub fn without_allele(self) -> Self {
+        match self {
+            Self::Allele(_, _) => Self::Default,
+            Self::And(filter1, filter2) => filter1.without_allele().and(filter2.without_allele()),
+            self_ => self_,
+        }
+    }
+
     pub fn not(self) -> Self {
         match self {
             Self::Strand(strand) => Self::Strand(strand.reverse()),
@@ -1,5 +1,11 @@
 use crate::error::TGVError;
-use crate::message::{Message, Movement, Scroll, Zoom};
+use crate::message::{
+    AlignmentDisplayOption, AlignmentGroup, CursorAnchor, Message, Movement, Scroll, Zoom,
+    ZoomPreset,
+};
+use itertools::Itertools;
+use std::collections::BTreeMap;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
 
 #[derive(Clone, Debug, Default)]
 pub struct NormalModeRegister {
@@ -24,119 +30,284 @@ const LARGE_VERTICAL_STEP: usize = 30;
 
 const ZOOM_STEP: u64 = 2;
 
-/// Translate key input to a state message. This does not mute states. States are muted downstream by handling state messages.
-pub fn update_by_char(current: &mut String, char: char) -> Result<Vec<Message>, TGVError> {
-    // Add to registers
-    match char {
-        '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-            if current.is_empty() || current.parse::<usize>().is_ok() {
-                current.push(char);
-                return Ok(vec![]); // Don't clear the register
-            }
+/// Normal mode actions that can be bound to keys. Names are used in the key binding file, e.g. `pan_left`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, EnumIter, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    PanLeft,
+    PanRight,
+    PanLeftFast,
+    PanRightFast,
+    ScrollDown,
+    ScrollUp,
+    ScrollDownFast,
+    ScrollUpFast,
+    ScrollTop,
+    ScrollBottom,
+    ZoomIn,
+    ZoomOut,
+    ZoomBase,
+    ZoomGene,
+    ZoomKilobases,
+    NextExonStart,
+    PreviousExonStart,
+    NextExonEnd,
+    PreviousExonEnd,
+    NextGene,
+    PreviousGene,
+    NextGeneEnd,
+    PreviousGeneEnd,
+    NextVariant,
+    PreviousVariant,
+    NextLocus,
+    PreviousLocus,
+    NextRegion,
+    PreviousRegion,
+    ShowInsertions,
+    ShowVariantDetails,
+    ContigOverview,
+    GroupOrientation,
+    SwitchPane,
+    CycleAlignmentTrack,
+    CenterCursor,
+}
+
+impl Action {
+    /// Default key sequence.
+    pub fn default_keys(&self) -> &'static str {
+        match self {
+            Action::PanLeft => "h",
+            Action::PanRight => "l",
+            Action::PanLeftFast => "y",
+            Action::PanRightFast => "p",
+            Action::ScrollDown => "j",
+            Action::ScrollUp => "k",
+            Action::ScrollDownFast => "}",
+            Action::ScrollUpFast => "{",
+            Action::ScrollTop => "gg",
+            Action::ScrollBottom => "gG",
+            Action::ZoomIn => "z",
+            Action::ZoomOut => "o",
+            Action::ZoomBase => "g1",
+            Action::ZoomGene => "g2",
+            Action::ZoomKilobases => "g3",
+            Action::NextExonStart => "w",
+            Action::PreviousExonStart => "b",
+            Action::NextExonEnd => "e",
+            Action::PreviousExonEnd => "ge",
+            Action::NextGene => "W",
+            Action::PreviousGene => "B",
+            Action::NextGeneEnd => "E",
+            Action::PreviousGeneEnd => "gE",
+            Action::NextVariant => "]v",
+            Action::PreviousVariant => "[v",
+            Action::NextLocus => "n",
+            Action::PreviousLocus => "N",
+            Action::NextRegion => "]r",
+            Action::PreviousRegion => "[r",
+            Action::ShowInsertions => "i",
+            Action::ShowVariantDetails => "V",
+            Action::ContigOverview => "O",
+            Action::GroupOrientation => "go",
+            Action::SwitchPane => "gw",
+            Action::CycleAlignmentTrack => "gt",
+            Action::CenterCursor => "gz",
         }
-        '0' => match current.len() {
-            0 => return Err(TGVError::RegisterError("Empty current".to_string())),
-            _ => {
-                if current.parse::<usize>().is_ok() {
-                    current.push('0');
-                    return Ok(vec![]); // Don't clear the register
-                }
-            }
-        },
+    }
 
-        'g' => {
-            if current.is_empty() || current.parse::<usize>().is_ok() {
-                current.push('g');
-                return Ok(vec![]); // Don't clear the register
-            } else {
-                current.push('g');
+    /// Messages of the action repeated n times. Scrolls act on the alignment track alignment_index.
+    pub fn messages(&self, n: usize, alignment_index: usize) -> Vec<Message> {
+        let message = match self {
+            Action::PanLeft => Movement::Left(n as u64 * SMALL_HORIZONTAL_STEP).into(),
+            Action::PanRight => Movement::Right(n as u64 * SMALL_HORIZONTAL_STEP).into(),
+            Action::PanLeftFast => Movement::Left(n as u64 * LARGE_HORIZONTAL_STEP).into(),
+            Action::PanRightFast => Movement::Right(n as u64 * LARGE_HORIZONTAL_STEP).into(),
+            Action::ScrollDown => Scroll::Down {
+                index: alignment_index,
+                n: n * SMALL_VERTICAL_STEP,
             }
+            .into(),
+            Action::ScrollUp => Scroll::Up {
+                index: alignment_index,
+                n: n * SMALL_VERTICAL_STEP,
+            }
+            .into(),
+            Action::ScrollDownFast => Scroll::Down {
+                index: alignment_index,
+                n: n * LARGE_VERTICAL_STEP,
+            }
+            .into(),
+            Action::ScrollUpFast => Scroll::Up {
+                index: alignment_index,
+                n: n * LARGE_VERTICAL_STEP,
+            }
+            .into(),
+            Action::ScrollTop => Scroll::Position(0).into(),
+            Action::ScrollBottom => Scroll::Bottom.into(),
+            Action::ZoomIn => Zoom::In(n as u64 * ZOOM_STEP).into(),
+            Action::ZoomOut => Zoom::Out(n as u64 * ZOOM_STEP).into(),
+            Action::ZoomBase => Zoom::Preset(ZoomPreset::Base).into(),
+            Action::ZoomGene => Zoom::Preset(ZoomPreset::Gene).into(),
+            Action::ZoomKilobases => Zoom::Preset(ZoomPreset::Kilobases).into(),
+            Action::NextExonStart => Movement::NextExonsStart(n).into(),
+            Action::PreviousExonStart => Movement::PreviousExonsStart(n).into(),
+            Action::NextExonEnd => Movement::NextExonsEnd(n).into(),
+            Action::PreviousExonEnd => Movement::PreviousExonsEnd(n).into(),
+            Action::NextGene => Movement::NextGenesStart(n).into(),
+            Action::PreviousGene => Movement::PreviousGenesStart(n).into(),
+            Action::NextGeneEnd => Movement::NextGenesEnd(n).into(),
+            Action::PreviousGeneEnd => Movement::PreviousGenesEnd(n).into(),
+            Action::NextVariant => Movement::NextVariants(n).into(),
+            Action::PreviousVariant => Movement::PreviousVariants(n).into(),
+            Action::NextLocus => Movement::NextLocus(n).into(),
+            Action::PreviousLocus => Movement::PreviousLocus(n).into(),
+            Action::NextRegion => Movement::NextRegions(n).into(),
+            Action::PreviousRegion => Movement::PreviousRegions(n).into(),
+            Action::ShowInsertions => Message::ShowInsertions,
+            Action::ShowVariantDetails => Message::ShowVariantDetails,
+            Action::ContigOverview => Message::ShowContigOverview,
+            Action::GroupOrientation => {
+                Message::SetAlignmentOption(vec![AlignmentDisplayOption::Group(
+                    AlignmentGroup::Orientation,
+                )])
+            }
+            Action::SwitchPane => Message::SwitchPane,
+            Action::CycleAlignmentTrack => Message::CycleAlignmentTrack,
+            Action::CenterCursor => Message::CenterCursor(CursorAnchor::Center),
+        };
+        vec![message]
+    }
+}
+
+/// Key sequences of normal mode actions. A count typed before a sequence repeats the action, e.g. 5h.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBindings {
+    actions: BTreeMap<String, Action>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            actions: Action::iter()
+                .map(|action| (action.default_keys().to_string(), action))
+                .collect(),
         }
-        _ => {
-            current.push(char); // proceed to interpretation
-        }
-    };
-    let input = current.clone();
-    current.clear();
-    parse_input(input)
+    }
 }
 
-fn parse_input(input: String) -> Result<Vec<Message>, TGVError> {
-    let mut n_movement_chars = "".to_string();
-    for char in input.chars() {
-        match char {
-            '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' => {
-                n_movement_chars += &char.to_string();
+impl KeyBindings {
+    /// Bind actions, by name, to key sequences instead of their default keys. Unknown actions, invalid key
+    /// sequences, keys bound to two actions, and keys that are a prefix of another binding are errors.
+    pub fn with_overrides<'a>(
+        overrides: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, TGVError> {
+        let mut keys = Action::iter()
+            .map(|action| (action, action.default_keys().to_string()))
+            .collect::<BTreeMap<_, _>>();
+
+        for (name, action_keys) in overrides {
+            let action = name.parse::<Action>().map_err(|_| {
+                TGVError::ParsingError(format!(
+                    "Unknown action `{name}` in key bindings. Actions: {}",
+                    Action::iter().map(|action| action.to_string()).join(", ")
+                ))
+            })?;
+            if action_keys.is_empty()
+                || action_keys.starts_with(|c: char| c.is_ascii_digit())
+                || action_keys.contains(|c: char| c == ':' || c.is_whitespace())
+            {
+                return Err(TGVError::ParsingError(format!(
+                    "Invalid keys `{action_keys}` for {action}: keys cannot be empty, start with a digit (a count), or contain `:` or spaces"
+                )));
+            }
+            keys.insert(action, action_keys.to_string());
+        }
+
+        let mut actions = BTreeMap::new();
+        for (action, action_keys) in keys {
+            if let Some(other) = actions.insert(action_keys.clone(), action) {
+                return Err(TGVError::ParsingError(format!(
+                    "Key `{action_keys}` is bound to both {other} and {action}"
+                )));
             }
-            _ => {
-                break;
+        }
+        for ((keys, action), (other_keys, other)) in actions.iter().tuple_combinations() {
+            let (prefix, prefix_action, longer, longer_action) = if keys.len() <= other_keys.len() {
+                (keys, action, other_keys, other)
+            } else {
+                (other_keys, other, keys, action)
+            };
+            if longer.starts_with(prefix.as_str()) {
+                return Err(TGVError::ParsingError(format!(
+                    "Key `{prefix}` of {prefix_action} is the start of `{longer}` of {longer_action}, so `{longer}` could never be typed"
+                )));
             }
         }
+
+        Ok(Self { actions })
+    }
+
+    /// Key sequence of an action.
+    pub fn keys(&self, action: Action) -> Option<&str> {
+        self.actions
+            .iter()
+            .find(|(_, bound)| **bound == action)
+            .map(|(keys, _)| keys.as_str())
     }
 
-    let suffix: String = input.chars().skip(n_movement_chars.len()).collect();
-
-    let n_movements = if n_movement_chars.is_empty() {
-        1
-    } else {
-        n_movement_chars.parse::<usize>()?
-    };
-
-    match suffix.as_str() {
-        "ge" => Ok(vec![Message::from(Movement::PreviousExonsEnd(n_movements))]),
-        "gE" => Ok(vec![Message::from(Movement::PreviousGenesEnd(n_movements))]),
-        "gg" => Ok(vec![Message::from(Scroll::Position(0))]),
-        "gG" => Ok(vec![Message::from(Scroll::Bottom)]),
-        "w" => Ok(vec![Message::from(Movement::NextExonsStart(n_movements))]),
-        "b" => Ok(vec![Message::from(Movement::PreviousExonsStart(
-            n_movements,
-        ))]),
-        "e" => Ok(vec![Message::from(Movement::NextExonsEnd(n_movements))]),
-        "W" => Ok(vec![Message::from(Movement::NextGenesStart(n_movements))]),
-        "B" => Ok(vec![Message::from(Movement::PreviousGenesStart(
-            n_movements,
-        ))]),
-        "E" => Ok(vec![Message::from(Movement::NextGenesEnd(n_movements))]),
-        "h" => Ok(vec![Message::from(Movement::Left(
-            n_movements as u64 * SMALL_HORIZONTAL_STEP,
-        ))]),
-        "l" => Ok(vec![Message::from(Movement::Right(
-            n_movements as u64 * SMALL_HORIZONTAL_STEP,
-        ))]),
-        "j" => Ok(vec![Message::from(Scroll::Down {
-            index: 0,
-            n: n_movements * SMALL_VERTICAL_STEP,
-        })]),
-        "k" => Ok(vec![Message::from(Scroll::Up {
-            index: 0,
-            n: n_movements * SMALL_VERTICAL_STEP,
-        })]),
-
-        "y" => Ok(vec![Message::from(Movement::Left(
-            LARGE_HORIZONTAL_STEP * n_movements as u64,
-        ))]),
-        "p" => Ok(vec![Message::from(Movement::Right(
-            LARGE_HORIZONTAL_STEP * n_movements as u64,
-        ))]),
-
-        "z" => Ok(vec![Message::from(Zoom::In(
-            ZOOM_STEP * n_movements as u64,
-        ))]),
-        "o" => Ok(vec![Message::from(Zoom::Out(
-            ZOOM_STEP * n_movements as u64,
-        ))]),
-        "{" => Ok(vec![Message::from(Scroll::Up {
-            index: 0,
-            n: LARGE_VERTICAL_STEP * n_movements,
-        })]),
-        "}" => Ok(vec![Message::from(Scroll::Down {
-            index: 0,
-            n: LARGE_VERTICAL_STEP * n_movements,
-        })]),
-        _ => Err(TGVError::RegisterError(format!(
+    /// Translate key input to a state message. This does not mute states. States are muted downstream by handling
+    /// state messages. Scrolls act on the alignment track alignment_index.
+    pub fn update_by_char(
+        &self,
+        current: &mut String,
+        char: char,
+        alignment_index: usize,
+    ) -> Result<Vec<Message>, TGVError> {
+        let count_length = current.chars().take_while(char::is_ascii_digit).count();
+
+        // Digits before any key are a count.
+        if char.is_ascii_digit() && count_length == current.len() {
+            if char == '0' && current.is_empty() {
+                return Err(TGVError::RegisterError("Empty current".to_string()));
+            }
+            current.push(char);
+            return Ok(vec![]);
+        }
+
+        current.push(char);
+        let keys = &current[count_length..];
+        if let Some(action) = self.actions.get(keys).copied() {
+            return self.update_by_action(current, action, alignment_index);
+        }
+        if self.actions.keys().any(|bound| bound.starts_with(keys)) {
+            return Ok(vec![]); // Don't clear the register
+        }
+
+        let input = std::mem::take(current);
+        Err(TGVError::RegisterError(format!(
             "Invalid normal mode input: {}",
             input
-        ))),
+        )))
+    }
+
+    /// Messages of an action, repeated by the count typed so far. Clears the input.
+    pub fn update_by_action(
+        &self,
+        current: &mut String,
+        action: Action,
+        alignment_index: usize,
+    ) -> Result<Vec<Message>, TGVError> {
+        let count = current
+            .chars()
+            .take_while(char::is_ascii_digit)
+            .collect::<String>();
+        current.clear();
+        let n = if count.is_empty() {
+            1
+        } else {
+            count.parse::<usize>()?
+        };
+        Ok(action.messages(n, alignment_index))
     }
 }
 
@@ -144,15 +315,25 @@ fn parse_input(input: String) -> Result<Vec<Message>, TGVError> {
 mod tests {
 
     use super::*;
-    use crate::message::{Movement, Scroll, Zoom};
+    use crate::message::{Movement, Scroll, Zoom, ZoomPreset};
     use rstest::rstest;
 
     #[rstest]
     #[case("",'g', Ok(vec![]))]
     #[case("g",'g', Ok(vec![Scroll::Position(0).into()]))]
     #[case("g",'G', Ok(vec![Scroll::Bottom.into()]))]
+    #[case("g",'w', Ok(vec![Message::SwitchPane]))]
+    #[case("g",'z', Ok(vec![Message::CenterCursor(CursorAnchor::Center)]))]
+    #[case("",'O', Ok(vec![Message::ShowContigOverview]))]
+    #[case("",'n', Ok(vec![Movement::NextLocus(1).into()]))]
+    #[case("3",'N', Ok(vec![Movement::PreviousLocus(3).into()]))]
+    #[case("]",'r', Ok(vec![Movement::NextRegions(1).into()]))]
+    #[case("2[",'r', Ok(vec![Movement::PreviousRegions(2).into()]))]
     #[case("",'1', Ok(vec![]))]
-    #[case("g",'1', Err(TGVError::RegisterError("Invalid input: g".to_string())))]
+    #[case("g",'1', Ok(vec![Zoom::Preset(ZoomPreset::Base).into()]))]
+    #[case("g",'2', Ok(vec![Zoom::Preset(ZoomPreset::Gene).into()]))]
+    #[case("3g",'3', Ok(vec![Zoom::Preset(ZoomPreset::Kilobases).into()]))]
+    #[case("g",'9', Err(TGVError::RegisterError("Invalid input: g9".to_string())))]
     #[case("", 'w', Ok(vec![Movement::NextExonsStart(1).into()]))]
     #[case("", 'b', Ok(vec![Movement::PreviousExonsStart(1).into()]))]
     #[case("", 'e', Ok(vec![Movement::NextExonsEnd(1).into()]))]
@@ -166,12 +347,22 @@ mod tests {
     #[case("", '}', Ok(vec![Scroll::Down { index: 0, n: 30 }.into()]))]
     #[case("g", 'e', Ok(vec![Movement::PreviousExonsEnd(1).into()]))]
     #[case("g", 'E', Ok(vec![Movement::PreviousGenesEnd(1).into()]))]
+    #[case("g", 'o', Ok(vec![Message::SetAlignmentOption(vec![
+        AlignmentDisplayOption::Group(AlignmentGroup::Orientation),
+    ])]))]
+    #[case("", 'i', Ok(vec![Message::ShowInsertions]))]
+    #[case("", 'V', Ok(vec![Message::ShowVariantDetails]))]
+    #[case("", ']', Ok(vec![]))]
+    #[case("]", 'v', Ok(vec![Movement::NextVariants(1).into()]))]
+    #[case("[", 'v', Ok(vec![Movement::PreviousVariants(1).into()]))]
+    #[case("2[", 'v', Ok(vec![Movement::PreviousVariants(2).into()]))]
     #[case("3", 'w', Ok(vec![Movement::NextExonsStart(3).into()]))]
     #[case("5", 'l', Ok(vec![Movement::Right(5).into()]))]
     #[case("10", 'z', Ok(vec![Zoom::In(20).into()]))]
     #[case("", 'x', Err(TGVError::RegisterError("Invalid normal mode input: x".to_string())))]
     #[case("g", 'x', Err(TGVError::RegisterError("Invalid normal mode input: gx".to_string())))]
     #[case("3", 'x', Err(TGVError::RegisterError("Invalid normal mode input: 3x".to_string())))]
+    #[case("]", 'x', Err(TGVError::RegisterError("Invalid normal mode input: ]x".to_string())))]
     #[case("3g", 'x', Err(TGVError::RegisterError("Invalid normal mode input: 3gx".to_string())))]
     fn test_normal_mode_translate(
         #[case] existing_buffer: &str,
@@ -181,7 +372,7 @@ mod tests {
         // Test the translation
         let mut buffer = existing_buffer.to_string();
 
-        let result = update_by_char(&mut buffer, key);
+        let result = KeyBindings::default().update_by_char(&mut buffer, key, 0);
         match (&result, &expected) {
             (Ok(result), Ok(expected)) => assert_eq!(result, expected),
             (Err(e), Err(expected)) => {} // OK
@@ -191,4 +382,82 @@ mod tests {
             ),
         }
     }
+
+    fn type_keys(key_bindings: &KeyBindings, keys: &str) -> Vec<Message> {
+        let mut buffer = String::new();
+        keys.chars()
+            .flat_map(|key| key_bindings.update_by_char(&mut buffer, key, 0).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn remapped_keys_replace_the_defaults() {
+        let key_bindings = KeyBindings::with_overrides([
+            ("pan_left", "a"),
+            ("zoom_in", "+"),
+            ("scroll_top", "tt"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            type_keys(&key_bindings, "a"),
+            vec![Movement::Left(1).into()]
+        );
+        assert_eq!(
+            type_keys(&key_bindings, "3a"),
+            vec![Movement::Left(3).into()]
+        );
+        assert_eq!(type_keys(&key_bindings, "+"), vec![Zoom::In(2).into()]);
+        assert_eq!(
+            type_keys(&key_bindings, "tt"),
+            vec![Scroll::Position(0).into()]
+        );
+        assert_eq!(
+            type_keys(&key_bindings, "l"),
+            vec![Movement::Right(1).into()]
+        );
+        assert_eq!(key_bindings.keys(Action::PanLeft), Some("a"));
+
+        let mut buffer = String::new();
+        assert!(key_bindings.update_by_char(&mut buffer, 'h', 0).is_err());
+        assert!(buffer.is_empty());
+    }
+
+    #[rstest]
+    #[case(&[("pan_leftt", "a")], "Unknown action `pan_leftt` in key bindings")]
+    #[case(&[("pan_left", "j")], "Key `j` is bound to both pan_left and scroll_down")]
+    #[case(&[("pan_left", "a"), ("pan_right", "a")], "Key `a` is bound to both pan_left and pan_right")]
+    #[case(&[("pan_left", "g")], "Key `g` of pan_left is the start of `g")]
+    #[case(&[("pan_left", "2h")], "Invalid keys `2h` for pan_left")]
+    #[case(&[("pan_left", ":")], "Invalid keys `:` for pan_left")]
+    #[case(&[("pan_left", "")], "Invalid keys `` for pan_left")]
+    fn invalid_key_bindings_are_errors(#[case] overrides: &[(&str, &str)], #[case] expected: &str) {
+        match KeyBindings::with_overrides(overrides.iter().copied()) {
+            Err(TGVError::ParsingError(message)) => {
+                assert!(message.starts_with(expected), "{message}")
+            }
+            result => panic!("Expected a parsing error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn scrolls_act_on_the_given_alignment_track() {
+        let mut buffer = String::new();
+        assert_eq!(
+            KeyBindings::default()
+                .update_by_char(&mut buffer, 'j', 2)
+                .unwrap(),
+            vec![Scroll::Down { index: 2, n: 1 }.into()]
+        );
+    }
+
+    #[test]
+    fn swapped_keys_are_not_duplicates() {
+        let key_bindings =
+            KeyBindings::with_overrides([("pan_left", "l"), ("pan_right", "h")]).unwrap();
+        assert_eq!(
+            type_keys(&key_bindings, "h"),
+            vec![Movement::Right(1).into()]
+        );
+    }
 }
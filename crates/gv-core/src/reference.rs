@@ -147,9 +147,13 @@ impl std::str::FromStr for Reference {
         Ok(Self::UcscGenome(s.to_string()))
     }
 }
+/// Serialized form, e.g. in session files. Custom references keep the full path, unlike the display name.
 impl From<Reference> for String {
     fn from(r: Reference) -> Self {
-        r.to_string()
+        match r {
+            Reference::BYOIndexedFasta(path) | Reference::BYOTwoBit(path) => path,
+            r => r.to_string(),
+        }
     }
 }
 
@@ -0,0 +1,346 @@
+use crate::{contig_header::ContigHeader, error::TGVError, intervals::Focus};
+
+/// A target region. 1-based, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetRegion {
+    pub contig_index: usize,
+    pub start: u64,
+    pub end: u64,
+}
+
+impl TargetRegion {
+    /// Distance from the focus on the same contig. 0 inside the region.
+    fn distance(&self, position: u64) -> u64 {
+        if position < self.start {
+            self.start - position
+        } else {
+            position.saturating_sub(self.end)
+        }
+    }
+
+    fn start_focus(&self) -> Focus {
+        Focus {
+            contig_index: self.contig_index,
+            position: self.start,
+        }
+    }
+
+    pub fn to_locus_str(&self, contig_header: &ContigHeader) -> String {
+        match contig_header.try_get(self.contig_index) {
+            Ok(contig) => format!("{}:{}-{}", contig.name, self.start, self.end),
+            Err(_) => format!("{}-{}", self.start, self.end),
+        }
+    }
+}
+
+/// Target regions loaded with `--regions`, e.g. the capture regions of a panel. Movements stay within them.
+/// Sorted by contig and start, with overlapping and adjacent regions merged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TargetRegions {
+    regions: Vec<TargetRegion>,
+}
+
+impl TargetRegions {
+    /// Parse the first three columns of a BED file. Header, track, browser, and comment lines are ignored. Lines
+    /// that are not an interval or are on a contig missing from the reference are skipped; a warning is returned
+    /// for each of them. It is an error if no region is left.
+    pub fn parse(
+        content: &str,
+        contig_header: &ContigHeader,
+    ) -> Result<(Self, Vec<String>), TGVError> {
+        let mut regions = Vec::new();
+        let mut warnings = Vec::new();
+        for (line_index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty()
+                || line.starts_with('#')
+                || line.starts_with("track")
+                || line.starts_with("browser")
+            {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let (Some(contig_name), Some(start), Some(end)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                warnings.push(format!(
+                    "Skipped line {} of the target regions: `{line}` is not CONTIG START END",
+                    line_index + 1
+                ));
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (start.parse::<u64>(), end.parse::<u64>()) else {
+                warnings.push(format!(
+                    "Skipped line {} of the target regions: `{line}` is not CONTIG START END",
+                    line_index + 1
+                ));
+                continue;
+            };
+            if start >= end {
+                warnings.push(format!(
+                    "Skipped line {} of the target regions: `{line}` is empty",
+                    line_index + 1
+                ));
+                continue;
+            }
+            let Ok(contig_index) = contig_header.try_get_index_by_str(contig_name) else {
+                warnings.push(format!(
+                    "Skipped line {} of the target regions: contig {contig_name} is not in the reference",
+                    line_index + 1
+                ));
+                continue;
+            };
+            // BED is 0-based, half-open.
+            regions.push(TargetRegion {
+                contig_index,
+                start: start + 1,
+                end,
+            });
+        }
+        if regions.is_empty() {
+            return Err(TGVError::ValueError(
+                "No target regions are loaded: the BED file has no interval on the reference"
+                    .to_string(),
+            ));
+        }
+        Ok((Self::new(regions), warnings))
+    }
+
+    pub fn from_path(
+        path: &str,
+        contig_header: &ContigHeader,
+    ) -> Result<(Self, Vec<String>), TGVError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            TGVError::IOError(format!("Failed to read the target regions {path}: {e}"))
+        })?;
+        Self::parse(&content, contig_header)
+    }
+
+    fn new(mut regions: Vec<TargetRegion>) -> Self {
+        regions.sort_by_key(|region| (region.contig_index, region.start));
+        let mut merged: Vec<TargetRegion> = Vec::with_capacity(regions.len());
+        for region in regions {
+            match merged.last_mut() {
+                Some(last)
+                    if last.contig_index == region.contig_index && region.start <= last.end + 1 =>
+                {
+                    last.end = last.end.max(region.end);
+                }
+                _ => merged.push(region),
+            }
+        }
+        Self { regions: merged }
+    }
+
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Start of the first region.
+    pub fn first(&self) -> Option<Focus> {
+        self.regions.first().map(TargetRegion::start_focus)
+    }
+
+    /// Region closest to the focus: the region containing it, then the nearest region on the same contig (the left
+    /// one on ties), then the first region of the next contig with regions, wrapping around after the last one.
+    pub fn nearest(&self, focus: &Focus) -> Option<&TargetRegion> {
+        self.regions
+            .iter()
+            .filter(|region| region.contig_index == focus.contig_index)
+            .min_by_key(|region| region.distance(focus.position))
+            .or_else(|| {
+                self.regions
+                    .iter()
+                    .find(|region| region.contig_index > focus.contig_index)
+            })
+            .or_else(|| self.regions.first())
+    }
+
+    /// Move the focus into the nearest region. Foci within a region are kept.
+    pub fn snap(&self, focus: Focus) -> Focus {
+        match self.nearest(&focus) {
+            Some(region) if region.contig_index == focus.contig_index => Focus {
+                contig_index: focus.contig_index,
+                position: focus.position.clamp(region.start, region.end),
+            },
+            Some(region) => region.start_focus(),
+            None => focus,
+        }
+    }
+
+    /// Start of the n-th region starting after the focus, wrapping around after the last region.
+    pub fn next(&self, focus: &Focus, n: usize) -> Result<Focus, TGVError> {
+        let len = self.checked_len()?;
+        let first_after = self.regions.partition_point(|region| {
+            (region.contig_index, region.start) <= (focus.contig_index, focus.position)
+        });
+        Ok(self.regions[(first_after + n.max(1) - 1) % len].start_focus())
+    }
+
+    /// Start of the n-th region starting before the focus, wrapping around before the first region.
+    pub fn previous(&self, focus: &Focus, n: usize) -> Result<Focus, TGVError> {
+        let len = self.checked_len()?;
+        let before = self.regions.partition_point(|region| {
+            (region.contig_index, region.start) < (focus.contig_index, focus.position)
+        });
+        let index = (before + len - n.max(1) % len) % len;
+        Ok(self.regions[index].start_focus())
+    }
+
+    fn checked_len(&self) -> Result<usize, TGVError> {
+        match self.regions.len() {
+            0 => Err(TGVError::StateError(
+                "No target regions are loaded. Load a BED file with --regions FILE".to_string(),
+            )),
+            len => Ok(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contig_header::ContigSource;
+    use crate::reference::Reference;
+    use rstest::rstest;
+
+    fn contig_header() -> ContigHeader {
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        for contig_name in ["chr1", "chr2", "chr3"] {
+            contig_header.update_or_add_contig(
+                contig_name.to_string(),
+                Some(10_000),
+                Vec::new(),
+                ContigSource::Sequence,
+            );
+        }
+        contig_header
+    }
+
+    fn focus(contig_index: usize, position: u64) -> Focus {
+        Focus {
+            contig_index,
+            position,
+        }
+    }
+
+    /// chr1:101-200, chr1:501-600, chr3:1001-1100.
+    fn regions() -> TargetRegions {
+        TargetRegions::parse(
+            "chr3\t1000\t1100\nchr1\t500\t600\tEXON2\nchr1\t100\t200\tEXON1\n",
+            &contig_header(),
+        )
+        .unwrap()
+        .0
+    }
+
+    #[test]
+    fn bed_lines_are_parsed_and_merged() {
+        let (regions, warnings) = TargetRegions::parse(
+            "track name=panel\n# capture\nchr1\t100\t200\nchr1\t150\t300\nchr1\t300\t310\nchrUn\t1\t5\nchr2 10\nchr2\t20\t20\nchr2\t0\t10\n",
+            &contig_header(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            regions.regions,
+            vec![
+                TargetRegion {
+                    contig_index: 0,
+                    start: 101,
+                    end: 310,
+                },
+                TargetRegion {
+                    contig_index: 1,
+                    start: 1,
+                    end: 10,
+                },
+            ]
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "Skipped line 6 of the target regions: contig chrUn is not in the reference",
+                "Skipped line 7 of the target regions: `chr2 10` is not CONTIG START END",
+                "Skipped line 8 of the target regions: `chr2\t20\t20` is empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn bed_without_regions_on_the_reference_is_an_error() {
+        assert!(TargetRegions::parse("chrUn\t1\t5\n", &contig_header()).is_err());
+    }
+
+    #[rstest]
+    // Inside a region
+    #[case(focus(0, 150), focus(0, 150))]
+    #[case(focus(0, 101), focus(0, 101))]
+    // Before the first region of the contig
+    #[case(focus(0, 1), focus(0, 101))]
+    // Between regions: the nearest edge, the left region on ties
+    #[case(focus(0, 300), focus(0, 200))]
+    #[case(focus(0, 450), focus(0, 501))]
+    #[case(focus(0, 350), focus(0, 200))]
+    // After the last region of the contig
+    #[case(focus(0, 9000), focus(0, 600))]
+    // A contig without regions: the next contig with regions
+    #[case(focus(1, 5000), focus(2, 1001))]
+    fn foci_snap_to_the_nearest_region(#[case] before: Focus, #[case] expected: Focus) {
+        assert_eq!(regions().snap(before), expected);
+    }
+
+    #[test]
+    fn foci_after_the_last_contig_with_regions_snap_to_the_first_region() {
+        let regions = TargetRegions::new(vec![TargetRegion {
+            contig_index: 0,
+            start: 101,
+            end: 200,
+        }]);
+        assert_eq!(regions.snap(focus(2, 5)), focus(0, 101));
+    }
+
+    #[rstest]
+    #[case(focus(0, 1), 1, focus(0, 101))]
+    #[case(focus(0, 101), 1, focus(0, 501))]
+    #[case(focus(0, 150), 2, focus(2, 1001))]
+    #[case(focus(1, 5000), 1, focus(2, 1001))]
+    // Wrap around after the last region
+    #[case(focus(2, 1001), 1, focus(0, 101))]
+    #[case(focus(0, 101), 3, focus(0, 101))]
+    fn next_region_is_in_genome_order(
+        #[case] from: Focus,
+        #[case] n: usize,
+        #[case] expected: Focus,
+    ) {
+        assert_eq!(regions().next(&from, n).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case(focus(2, 1050), 1, focus(2, 1001))]
+    #[case(focus(2, 1001), 1, focus(0, 501))]
+    #[case(focus(2, 1001), 2, focus(0, 101))]
+    #[case(focus(1, 5000), 1, focus(0, 501))]
+    // Wrap around before the first region
+    #[case(focus(0, 101), 1, focus(2, 1001))]
+    #[case(focus(0, 1), 4, focus(2, 1001))]
+    fn previous_region_is_in_genome_order(
+        #[case] from: Focus,
+        #[case] n: usize,
+        #[case] expected: Focus,
+    ) {
+        assert_eq!(regions().previous(&from, n).unwrap(), expected);
+    }
+
+    #[test]
+    fn jumping_without_regions_is_an_error() {
+        let regions = TargetRegions::default();
+        assert!(regions.next(&focus(0, 1), 1).is_err());
+        assert!(regions.previous(&focus(0, 1), 1).is_err());
+        assert_eq!(regions.snap(focus(0, 1)), focus(0, 1));
+    }
+}
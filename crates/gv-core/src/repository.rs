@@ -1,11 +1,13 @@
 use crate::{
-    alignment::AlignmentRepositoryEnum,
+    alignment::{AlignmentRepositoryEnum, ReadSpan, is_url},
     bed::BedRepository,
+    bigwig::BigWigRepository,
     contig_header::{ContigHeader, ContigSource},
+    data_source::DataSource,
     error::TGVError,
     reference::Reference,
     sequence::SequenceRepositoryEnum,
-    settings::{FilePath, Settings},
+    settings::{AlignmentPath, FilePath, Settings},
     tracks::{TrackService, TrackServiceEnum},
     variant::VariantRepository,
 };
@@ -18,6 +20,7 @@ pub enum RepositoryFileIndex {
     Alignment(usize),
     Variant(usize),
     Bed(usize),
+    BigWig(usize),
 }
 
 pub struct Repository {
@@ -27,6 +30,8 @@ pub struct Repository {
 
     pub bed_repositories: Vec<BedRepository>,
 
+    pub bigwig_repositories: Vec<BigWigRepository>,
+
     pub track_service: Option<TrackServiceEnum>,
 
     pub sequence_service: Option<SequenceRepositoryEnum>,
@@ -44,10 +49,18 @@ impl Repository {
         );
 
         let mut track_service = TrackServiceEnum::new(settings).await?;
-        let mut sequence_service = SequenceRepositoryEnum::new(settings)?;
+        let mut sequence_service = SequenceRepositoryEnum::new(settings).await?;
+        // MySQL connections to a custom UCSC host are made above. API requests are made on demand, so check that
+        // the host is reachable before the first one fails mid-session.
+        if matches!(track_service, Some(TrackServiceEnum::Api(_)))
+            || matches!(sequence_service, Some(SequenceRepositoryEnum::UCSCApi(_)))
+        {
+            settings.ucsc_host.check_reachable().await?;
+        }
         let mut alignment_repositories = Vec::new();
         let mut variant_repositories = Vec::new();
         let mut bed_repositories = Vec::new();
+        let mut bigwig_repositories = Vec::new();
         let mut repository_file_indexes = Vec::new();
 
         for file_path in &settings.file_paths {
@@ -72,6 +85,11 @@ impl Repository {
                     });
                     repository_file_indexes.push(RepositoryFileIndex::Bed(index));
                 }
+                FilePath::BigWigPath(bigwig_path) => {
+                    let index = bigwig_repositories.len();
+                    bigwig_repositories.push(BigWigRepository::new(bigwig_path)?);
+                    repository_file_indexes.push(RepositoryFileIndex::BigWig(index));
+                }
             }
         }
 
@@ -245,14 +263,16 @@ impl Repository {
                     //         );
                     //     });
                 }
+                RepositoryFileIndex::BigWig(_) => {}
             }
         }
 
         log::info!(
-            "Repository resources are ready: alignment_repositories={} variant_repositories={} bed_repositories={} file_order={:?} contigs={} elapsed_ms={}",
+            "Repository resources are ready: alignment_repositories={} variant_repositories={} bed_repositories={} bigwig_repositories={} file_order={:?} contigs={} elapsed_ms={}",
             alignment_repositories.len(),
             variant_repositories.len(),
             bed_repositories.len(),
+            bigwig_repositories.len(),
             repository_file_indexes,
             contig_header.contigs.len(),
             started.elapsed().as_millis(),
@@ -264,6 +284,7 @@ impl Repository {
                 alignment_repositories,
                 variant_repositories,
                 bed_repositories,
+                bigwig_repositories,
                 track_service,
                 sequence_service,
             },
@@ -272,6 +293,103 @@ impl Repository {
         ))
     }
 
+    /// Re-open the alignment, variant, BED, and bigWig files of settings from disk, e.g. after a pipeline rewrote
+    /// them. All files are opened before any is replaced, so a missing file leaves the loaded files in place. Reads
+    /// piped from stdin cannot be re-read and are kept. Returns warnings about alignment indexes older than their
+    /// files.
+    pub async fn reload_files(&mut self, settings: &Settings) -> Result<Vec<String>, TGVError> {
+        let started = Instant::now();
+        let mut warnings = Vec::new();
+        let mut alignment_repositories = Vec::new();
+        let mut variant_repositories = Vec::new();
+        let mut bed_repositories = Vec::new();
+        let mut bigwig_repositories = Vec::new();
+
+        for file_path in &settings.file_paths {
+            match file_path {
+                FilePath::AlignmentPath(AlignmentPath::Stdin) => alignment_repositories.push(None),
+                FilePath::AlignmentPath(
+                    alignment_path @ (AlignmentPath::Bam { path, index, .. }
+                    | AlignmentPath::Cram {
+                        path, crai: index, ..
+                    }),
+                ) => {
+                    check_file_exists(path)?;
+                    check_file_exists(index)?;
+                    warnings.extend(stale_index_warning(path, index));
+                    alignment_repositories
+                        .push(Some(AlignmentRepositoryEnum::new(alignment_path).await?));
+                }
+                FilePath::VariantPath(vcf_path) => {
+                    check_file_exists(vcf_path)?;
+                    variant_repositories.push(VariantRepository {
+                        vcf_path: vcf_path.clone(),
+                    });
+                }
+                FilePath::BedPath(bed_path) => {
+                    check_file_exists(bed_path)?;
+                    bed_repositories.push(BedRepository {
+                        bed_path: bed_path.clone(),
+                    });
+                }
+                FilePath::BigWigPath(bigwig_path) => {
+                    check_file_exists(bigwig_path)?;
+                    bigwig_repositories.push(BigWigRepository::new(bigwig_path)?);
+                }
+            }
+        }
+
+        for (alignment_repository, reloaded) in self
+            .alignment_repositories
+            .iter_mut()
+            .zip(alignment_repositories)
+        {
+            if let Some(reloaded) = reloaded {
+                *alignment_repository = reloaded;
+            }
+        }
+        self.variant_repositories = variant_repositories;
+        self.bed_repositories = bed_repositories;
+        self.bigwig_repositories = bigwig_repositories;
+
+        log::info!(
+            "Reloaded files: files={} warnings={} elapsed_ms={}",
+            settings.file_paths.len(),
+            warnings.len(),
+            started.elapsed().as_millis()
+        );
+        Ok(warnings)
+    }
+
+    /// Region covered by reads piped from stdin, if any.
+    pub fn read_span(&self, contig_header: &ContigHeader) -> Result<Option<ReadSpan>, TGVError> {
+        for alignment_repository in &self.alignment_repositories {
+            if let Some(read_span) = alignment_repository.read_span(contig_header)? {
+                return Ok(Some(read_span));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Where the gene track and sequence of the reference are served from. If either is live, the live source is
+    /// reported. None for annotation and reference files.
+    pub fn data_source(&self, reference: &Reference) -> Option<DataSource> {
+        let track = self
+            .track_service
+            .as_ref()
+            .and_then(TrackServiceEnum::data_source);
+        let sequence = self
+            .sequence_service
+            .as_ref()
+            .and_then(|sequence_service| sequence_service.data_source(reference));
+        [track, sequence]
+            .into_iter()
+            .flatten()
+            .find(|source| *source != DataSource::Cache)
+            .or(track)
+            .or(sequence)
+    }
+
     pub fn track_service_checked(&mut self) -> Result<&mut TrackServiceEnum, TGVError> {
         match self.track_service.as_mut() {
             Some(track_service) => Ok(track_service),
@@ -300,3 +418,25 @@ impl Repository {
         Ok(())
     }
 }
+
+/// Error if a local file is gone. URLs are not checked.
+fn check_file_exists(path: &str) -> Result<(), TGVError> {
+    if is_url(path) || Path::new(path).exists() {
+        Ok(())
+    } else {
+        Err(TGVError::IOError(format!(
+            "Cannot reload {path}: the file no longer exists"
+        )))
+    }
+}
+
+/// Warning if a local index was modified before its file: it likely points at the records of the old file.
+fn stale_index_warning(path: &str, index: &str) -> Option<String> {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(path), modified(index)) {
+        (Ok(file_modified), Ok(index_modified)) if index_modified < file_modified => Some(format!(
+            "{index} is older than {path}. Re-index the file to read the new records."
+        )),
+        _ => None,
+    }
+}
@@ -0,0 +1,243 @@
+use crate::error::TGVError;
+use reqwest::StatusCode;
+use std::future::Future;
+use std::time::Duration;
+
+/// Bounded retries with exponential backoff for UCSC API requests and MySQL connections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first one.
+    pub max_retries: u32,
+
+    /// Wait before the first retry. Doubled before each further retry.
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Fail on the first error.
+    pub const NONE: RetryPolicy = RetryPolicy {
+        max_retries: 0,
+        initial_backoff: Duration::ZERO,
+    };
+
+    /// Wait before retry number `retry` (0 for the first retry).
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+    }
+
+    /// Run an operation, retrying transient network errors. The last error is returned once retries are exhausted.
+    /// context describes the operation in logs, e.g. `UCSC sequence query`.
+    pub async fn run<T, E, F, Fut>(&self, context: &str, mut operation: F) -> Result<T, TGVError>
+    where
+        E: Into<TGVError>,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut retry = 0;
+        loop {
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e.into(),
+            };
+            if !is_transient(&error) {
+                return Err(error);
+            }
+            if retry >= self.max_retries {
+                if self.max_retries > 0 {
+                    log::warn!(
+                        "Giving up after retries: context={context} retries={} error={error}",
+                        self.max_retries
+                    );
+                }
+                return Err(error);
+            }
+
+            let backoff = self.backoff(retry);
+            retry += 1;
+            log::warn!(
+                "Retrying after a transient error: context={context} retry={retry}/{} backoff_ms={} error={error}",
+                self.max_retries,
+                backoff.as_millis()
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
+}
+
+/// HTTP statuses of an overloaded or restarting server, worth retrying.
+const TRANSIENT_STATUSES: [StatusCode; 3] = [
+    StatusCode::BAD_GATEWAY,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::GATEWAY_TIMEOUT,
+];
+
+/// Send an HTTP request for [`RetryPolicy::run`]. Responses with a transient status (502, 503, 504) are returned as
+/// errors so that they are retried. Other responses are returned as is.
+pub(crate) async fn send_request(
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let response = request.send().await?;
+    if TRANSIENT_STATUSES.contains(&response.status()) {
+        return response.error_for_status();
+    }
+    Ok(response)
+}
+
+/// Errors worth retrying: failed connections and requests, timed-out HTTP requests, and transient HTTP statuses
+/// returned by [`send_request`]. MySQL pool timeouts are not retried: an unreachable host fails over to the other UCSC mirror
+/// instead.
+fn is_transient(error: &TGVError) -> bool {
+    match error {
+        TGVError::UcscApiIOError(e) => {
+            e.is_connect()
+                || e.is_timeout()
+                || e.is_request()
+                || e.status()
+                    .is_some_and(|status| TRANSIENT_STATUSES.contains(&status))
+        }
+        TGVError::SqlxError(sqlx::Error::Io(_)) => true,
+        _ => false,
+    }
+}
+
+/// Errors from a network that is down or a host that cannot be reached, including transient errors that outlasted
+/// the retries.
+pub(crate) fn is_unreachable(error: &TGVError) -> bool {
+    is_transient(error)
+        || matches!(
+            error,
+            TGVError::NetworkError(_) | TGVError::SqlxError(sqlx::Error::PoolTimedOut)
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn connection_reset() -> TGVError {
+        TGVError::SqlxError(sqlx::Error::Io(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset,
+        )))
+    }
+
+    const FAST: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(1),
+    };
+
+    #[rstest]
+    #[case(0, 500)]
+    #[case(1, 1000)]
+    #[case(3, 4000)]
+    fn backoff_doubles(#[case] retry: u32, #[case] expected_ms: u64) {
+        assert_eq!(
+            RetryPolicy::default().backoff(retry),
+            Duration::from_millis(expected_ms)
+        );
+    }
+
+    /// Run an operation that fails with error the first `failures` times. Returns the result and the attempts made.
+    async fn run_failing(
+        policy: RetryPolicy,
+        failures: usize,
+        error: fn() -> TGVError,
+    ) -> (Result<usize, TGVError>, usize) {
+        let mut attempts = 0;
+        let result = policy
+            .run("test", || {
+                attempts += 1;
+                let attempt = attempts;
+                async move {
+                    if attempt <= failures {
+                        Err(error())
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+        (result, attempts)
+    }
+
+    #[rstest]
+    #[case(FAST, 0, Some(1), 1)]
+    #[case(FAST, 1, Some(2), 2)]
+    #[case(FAST, 2, Some(3), 3)]
+    // Retries are exhausted.
+    #[case(FAST, 3, None, 3)]
+    #[case(RetryPolicy::NONE, 1, None, 1)]
+    #[tokio::test]
+    async fn transient_errors_are_retried(
+        #[case] policy: RetryPolicy,
+        #[case] failures: usize,
+        #[case] expected: Option<usize>,
+        #[case] expected_attempts: usize,
+    ) {
+        let (result, attempts) = run_failing(policy, failures, connection_reset).await;
+        assert_eq!(result.ok(), expected);
+        assert_eq!(attempts, expected_attempts);
+    }
+
+    /// Serve one response per status on successive connections. Return the URL.
+    async fn serve_statuses(statuses: Vec<&'static str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 4096];
+                let _ = socket.read(&mut buffer).await.unwrap();
+                let response =
+                    format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[rstest]
+    #[case(vec!["503 Service Unavailable", "200 OK"], Ok(200))]
+    #[case(vec!["502 Bad Gateway", "504 Gateway Timeout", "200 OK"], Ok(200))]
+    // Retries are exhausted.
+    #[case(vec!["503 Service Unavailable"; 3], Err(503))]
+    // Other statuses are returned to the caller.
+    #[case(vec!["500 Internal Server Error"], Ok(500))]
+    #[case(vec!["404 Not Found"], Ok(404))]
+    #[tokio::test]
+    async fn transient_statuses_are_retried(
+        #[case] statuses: Vec<&'static str>,
+        #[case] expected: Result<u16, u16>,
+    ) {
+        let url = serve_statuses(statuses).await;
+        let client = reqwest::Client::new();
+        let result = FAST.run("test", || send_request(client.get(&url))).await;
+        let status = match result {
+            Ok(response) => Ok(response.status().as_u16()),
+            Err(TGVError::UcscApiIOError(e)) => Err(e.status().unwrap().as_u16()),
+            Err(e) => panic!("unexpected error: {e}"),
+        };
+        assert_eq!(status, expected);
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_not_retried() {
+        let (result, attempts) = run_failing(FAST, 1, || {
+            TGVError::ValueError("Unsupported reference".to_string())
+        })
+        .await;
+        assert!(matches!(result, Err(TGVError::ValueError(_))));
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,217 @@
+/// Window length and minimum length of CpG island candidates in bp (Gardiner-Garden and Frommer, 1987).
+pub const CPG_ISLAND_WINDOW: usize = 200;
+
+/// Minimum GC content of a CpG island window.
+const CPG_ISLAND_MIN_GC_CONTENT: f64 = 0.5;
+
+/// Minimum observed / expected CpG ratio of a CpG island window.
+const CPG_ISLAND_MIN_OBSERVED_EXPECTED: f64 = 0.6;
+
+/// C, G, and CpG counts of a sequence window. Bases are counted case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CpgComposition {
+    pub c: u64,
+    pub g: u64,
+
+    /// CG dinucleotides.
+    pub cpg: u64,
+
+    /// A, C, G, and T bases. N and other IUPAC codes are excluded.
+    pub acgt: u64,
+}
+
+impl CpgComposition {
+    pub fn of(bases: &[u8]) -> Self {
+        let mut composition = Self {
+            cpg: bases.windows(2).filter(|pair| is_cpg(pair)).count() as u64,
+            ..Self::default()
+        };
+        for base in bases {
+            composition.add_base(*base, 1);
+        }
+        composition
+    }
+
+    /// Add (delta 1) or remove (delta -1) a base. CpGs are not counted.
+    fn add_base(&mut self, base: u8, delta: i64) {
+        match base.to_ascii_uppercase() {
+            b'C' => self.c = self.c.saturating_add_signed(delta),
+            b'G' => self.g = self.g.saturating_add_signed(delta),
+            b'A' | b'T' => {}
+            _ => return,
+        }
+        self.acgt = self.acgt.saturating_add_signed(delta);
+    }
+
+    /// Observed / expected CpG ratio: CpG * (A + C + G + T) / (C * G).
+    /// None if the window has no C or no G.
+    pub fn observed_expected(&self) -> Option<f64> {
+        if self.c == 0 || self.g == 0 {
+            return None;
+        }
+
+        Some((self.cpg * self.acgt) as f64 / (self.c * self.g) as f64)
+    }
+
+    /// GC content: (G + C) / (A + C + G + T).
+    /// None if the window has no A, C, G, or T.
+    pub fn gc_content(&self) -> Option<f64> {
+        if self.acgt == 0 {
+            return None;
+        }
+
+        Some((self.c + self.g) as f64 / self.acgt as f64)
+    }
+
+    /// Whether the window meets the Gardiner-Garden criteria: GC content above 50% and observed / expected CpG
+    /// above 0.6.
+    pub fn is_island(&self) -> bool {
+        self.gc_content()
+            .is_some_and(|gc_content| gc_content > CPG_ISLAND_MIN_GC_CONTENT)
+            && self
+                .observed_expected()
+                .is_some_and(|ratio| ratio > CPG_ISLAND_MIN_OBSERVED_EXPECTED)
+    }
+}
+
+/// A CpG island candidate in a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpgIsland {
+    /// Index of the first base in the sequence. 0-based.
+    pub start: usize,
+
+    /// Index after the last base. 0-based, exclusive.
+    pub end: usize,
+}
+
+fn is_cpg(pair: &[u8]) -> bool {
+    pair[0].eq_ignore_ascii_case(&b'C') && pair[1].eq_ignore_ascii_case(&b'G')
+}
+
+/// Whether each base is part of a CG dinucleotide. Bases are compared case-insensitively.
+pub fn cpg_mask(bases: &[u8]) -> Vec<bool> {
+    let mut in_cpg = vec![false; bases.len()];
+    for (i, pair) in bases.windows(2).enumerate() {
+        if is_cpg(pair) {
+            in_cpg[i] = true;
+            in_cpg[i + 1] = true;
+        }
+    }
+    in_cpg
+}
+
+/// CpG island candidates: windows of window bases sliding by 1 bp that meet the Gardiner-Garden criteria, merged where
+/// they overlap. Candidates are at least window bases long. A CpG is counted in a window if both bases are in it.
+pub fn cpg_islands(bases: &[u8], window: usize) -> Vec<CpgIsland> {
+    if window == 0 || bases.len() < window {
+        return Vec::new();
+    }
+
+    // Counts in a window are updated as it slides: one base enters on the right and one leaves on the left.
+    let mut composition = CpgComposition::of(&bases[..window]);
+    let mut islands: Vec<CpgIsland> = Vec::new();
+    for start in 0..=bases.len() - window {
+        if start > 0 {
+            let (left, right) = (start - 1, start + window - 1);
+            composition.add_base(bases[left], -1);
+            composition.add_base(bases[right], 1);
+            if is_cpg(&bases[left..=left + 1]) {
+                composition.cpg -= 1;
+            }
+            if is_cpg(&bases[right - 1..=right]) {
+                composition.cpg += 1;
+            }
+        }
+
+        if !composition.is_island() {
+            continue;
+        }
+        let end = start + window;
+        match islands.last_mut() {
+            Some(island) if island.end >= start => island.end = end,
+            _ => islands.push(CpgIsland { start, end }),
+        }
+    }
+    islands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn cpgs_are_marked() {
+        assert_eq!(
+            cpg_mask(b"ACGTcgCCGG"),
+            [
+                false, true, true, false, true, true, false, true, true, false
+            ]
+        );
+        assert_eq!(cpg_mask(b"GCGC"), [false, true, true, false]);
+        assert!(cpg_mask(b"").is_empty());
+    }
+
+    #[rstest]
+    #[case(b"CGCGCGCG", Some(2.0))]
+    #[case(b"ACGT", Some(4.0))]
+    #[case(b"CCGG", Some(1.0))]
+    #[case(b"GGCC", Some(0.0))]
+    // Ns are excluded from the length.
+    #[case(b"CCGGNNNN", Some(1.0))]
+    #[case(b"AAAA", None)]
+    #[case(b"", None)]
+    fn test_observed_expected(#[case] bases: &[u8], #[case] expected: Option<f64>) {
+        assert_eq!(CpgComposition::of(bases).observed_expected(), expected);
+    }
+
+    #[test]
+    fn test_cpg_composition() {
+        assert_eq!(
+            CpgComposition::of(b"AcgTGCNg"),
+            CpgComposition {
+                c: 2,
+                g: 3,
+                cpg: 1,
+                acgt: 7,
+            }
+        );
+    }
+
+    #[rstest]
+    // Windows from index 6 to 14 have GC content above 50% and observed / expected CpG above 0.6.
+    #[case(b"AAAAAAAAAACGCGCGCGCGAAAAAAAAAA", 10, vec![CpgIsland { start: 6, end: 24 }])]
+    // GC-rich without CpGs.
+    #[case(b"AAAAAAAAAAGGGGGCCCCCAAAAAAAAAA", 10, vec![])]
+    #[case(b"CGCGCGCG", 10, vec![])]
+    #[case(b"CGCGAAAAAAAAAACGCG", 4, vec![CpgIsland { start: 0, end: 5 }, CpgIsland { start: 13, end: 18 }])]
+    fn test_cpg_islands(
+        #[case] bases: &[u8],
+        #[case] window: usize,
+        #[case] expected: Vec<CpgIsland>,
+    ) {
+        assert_eq!(cpg_islands(bases, window), expected);
+    }
+
+    #[test]
+    fn sliding_counts_match_window_counts() {
+        let bases = b"ACGTTCGCGGNNCGAACGCGTACGGGCCCGCGATCGaaaacgcg";
+        let window = 8;
+
+        let mut expected: Vec<CpgIsland> = Vec::new();
+        for start in 0..=bases.len() - window {
+            if !CpgComposition::of(&bases[start..start + window]).is_island() {
+                continue;
+            }
+            match expected.last_mut() {
+                Some(island) if island.end >= start => island.end = start + window,
+                _ => expected.push(CpgIsland {
+                    start,
+                    end: start + window,
+                }),
+            }
+        }
+        assert!(!expected.is_empty());
+        assert_eq!(cpg_islands(bases, window), expected);
+    }
+}
@@ -1,19 +1,27 @@
+mod cpg;
 mod fasta;
+mod motif;
+mod repeats;
 mod twobit;
 mod ucsc_api;
 
 pub use crate::sequence::{
-    fasta::IndexedFastaSequenceRepository, twobit::TwoBitSequenceRepository,
+    cpg::{CPG_ISLAND_WINDOW, CpgComposition, CpgIsland, cpg_islands, cpg_mask},
+    fasta::IndexedFastaSequenceRepository,
+    motif::{MOTIF_SEARCH_CHUNK_SIZE, Motif, MotifMatch, SearchChunk},
+    repeats::{DEFAULT_REPEAT_MIN_LENGTH, MAX_REPEAT_PERIOD, TandemRepeat, tandem_repeats},
+    twobit::TwoBitSequenceRepository,
     ucsc_api::UCSCApiSequenceRepository,
 };
 use crate::{
     contig_header::{Contig, ContigHeader},
+    data_source::DataSource,
     error::TGVError,
     intervals::{GenomeInterval, Region},
     reference::Reference,
     settings::{BackendType, Settings},
+    strand::Strand,
 };
-use std::path::Path;
 /// Sequences of a genome region.
 #[derive(Debug, Default)]
 pub struct Sequence {
@@ -65,12 +73,147 @@ impl Sequence {
         Some(self.sequence[(coordinate - self.start) as usize])
     }
 
+    /// The base at the coordinate and up to flank bases on each side, clipped to the loaded sequence: (left flank,
+    /// base, right flank). None if the base is not loaded.
+    /// 1-based.
+    pub fn base_context(&self, coordinate: u64, flank: u64) -> Option<(&[u8], u8, &[u8])> {
+        let base = self.base_at(coordinate)?;
+        let index = (coordinate - self.start) as usize;
+        let left = index.saturating_sub(flank as usize);
+        let right = usize::min(index + 1 + flank as usize, self.sequence.len());
+        Some((
+            &self.sequence[left..index],
+            base,
+            &self.sequence[index + 1..right],
+        ))
+    }
+
+    /// Codon at genome positions in reading order, read on the strand. Reverse-strand bases are complemented.
+    /// None if any base is not loaded.
+    pub fn codon(&self, positions: &[u64; 3], strand: &Strand) -> Option<Vec<u8>> {
+        positions
+            .iter()
+            .map(|position| {
+                self.base_at(*position).map(|base| match strand {
+                    Strand::Forward => base,
+                    Strand::Reverse => complement(base),
+                })
+            })
+            .collect()
+    }
+
+    /// Bases in [start, end] read on the strand. Reverse-strand sequences are reverse-complemented.
+    /// None if any base is not loaded.
+    /// 1-based, inclusive.
+    pub fn stranded_sequence(&self, start: u64, end: u64, strand: &Strand) -> Option<Vec<u8>> {
+        if start < self.start || end > self.end() || start > end || self.sequence.is_empty() {
+            return None;
+        }
+
+        let bases = &self.sequence[(start - self.start) as usize..=(end - self.start) as usize];
+        Some(match strand {
+            Strand::Forward => bases.to_vec(),
+            Strand::Reverse => bases.iter().rev().map(|base| complement(*base)).collect(),
+        })
+    }
+
     /// Whether the sequence has complete data in [left, right].
     /// 1-based, inclusive.
     pub fn has_complete_data(&self, region: &Region) -> bool {
         (region.contig_index() == self.contig_index)
             && ((region.start() >= self.start) && (region.end() <= self.end()))
     }
+
+    /// Count bases in [start, end]. Positions outside of the loaded sequence are skipped.
+    /// 1-based, inclusive.
+    pub fn base_composition(&self, start: u64, end: u64) -> BaseComposition {
+        let mut composition = BaseComposition::default();
+        if self.sequence.is_empty() {
+            return composition;
+        }
+
+        let start = u64::max(start, self.start);
+        let end = u64::min(end, self.end());
+        if start > end {
+            return composition;
+        }
+
+        for base in &self.sequence[(start - self.start) as usize..=(end - self.start) as usize] {
+            match base {
+                b'A' | b'a' => composition.a += 1,
+                b'C' | b'c' => composition.c += 1,
+                b'G' | b'g' => composition.g += 1,
+                b'T' | b't' => composition.t += 1,
+                _ => composition.other += 1,
+            }
+        }
+
+        composition
+    }
+}
+
+/// Bases per line of FASTA records.
+const FASTA_LINE_WIDTH: usize = 60;
+
+/// FASTA record of the sequence under the header, wrapped at 60 bases per line.
+pub fn format_fasta(header: &str, sequence: &[u8]) -> String {
+    let mut fasta = format!(">{header}\n");
+    for line in sequence.chunks(FASTA_LINE_WIDTH) {
+        fasta.push_str(&String::from_utf8_lossy(line));
+        fasta.push('\n');
+    }
+    fasta
+}
+
+/// Complementary base. Case is kept. N and other IUPAC codes are returned as is.
+pub fn complement(base: u8) -> u8 {
+    match base {
+        b'A' => b'T',
+        b'T' => b'A',
+        b'C' => b'G',
+        b'G' => b'C',
+        b'a' => b't',
+        b't' => b'a',
+        b'c' => b'g',
+        b'g' => b'c',
+        _ => base,
+    }
+}
+
+/// Base counts of a sequence window.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BaseComposition {
+    pub a: u64,
+    pub c: u64,
+    pub g: u64,
+    pub t: u64,
+
+    /// N and other IUPAC codes.
+    pub other: u64,
+}
+
+impl BaseComposition {
+    /// GC skew: (G - C) / (G + C).
+    /// None if the window has no G or C.
+    pub fn gc_skew(&self) -> Option<f64> {
+        let gc = self.g + self.c;
+        if gc == 0 {
+            return None;
+        }
+
+        Some((self.g as f64 - self.c as f64) / gc as f64)
+    }
+
+    /// GC content: (G + C) / (A + C + G + T). N and other IUPAC codes are excluded from the denominator.
+    /// None if the window has no A, C, G, or T.
+    pub fn gc_content(&self) -> Option<f64> {
+        let acgt = self.a + self.c + self.g + self.t;
+        if acgt == 0 {
+            return None;
+        }
+
+        Some((self.g + self.c) as f64 / acgt as f64)
+    }
 }
 
 pub enum SequenceRepositoryEnum {
@@ -80,7 +223,7 @@ pub enum SequenceRepositoryEnum {
 }
 
 impl SequenceRepositoryEnum {
-    pub fn new(settings: &Settings) -> Result<Option<Self>, TGVError> {
+    pub async fn new(settings: &Settings) -> Result<Option<Self>, TGVError> {
         match (&settings.backend, &settings.reference) {
             (_, Reference::NoReference) => Ok(None),
             (_, Reference::BYOIndexedFasta(path)) => Ok(Some(Self::IndexedFasta(
@@ -90,29 +233,53 @@ impl SequenceRepositoryEnum {
                 &settings.reference,
             )))),
 
-            (BackendType::Ucsc, _) => Ok(Some(Self::UCSCApi(UCSCApiSequenceRepository::new(
-                &settings.reference,
-                &settings.ucsc_host,
-            )?))),
+            (BackendType::Ucsc | BackendType::Ensembl, _) => {
+                Ok(Some(Self::UCSCApi(UCSCApiSequenceRepository::new(
+                    &settings.reference,
+                    &settings.ucsc_host,
+                    settings.retry,
+                )?)))
+            }
             (BackendType::Local, _) => Ok(Some(Self::TwoBit(TwoBitSequenceRepository::new(
                 &settings.reference,
             )))), // add paths later
-            (BackendType::Default, _) => {
-                // If the local cache is available, use the local cache.
-                // Otherwise, use the UCSC DB / API.
-                if Path::new(&settings.reference.cache_dir(&settings.cache_dir)).exists() {
-                    Ok(Some(Self::TwoBit(TwoBitSequenceRepository::new(
-                        &settings.reference,
-                    ))))
-                } else {
-                    Ok(Some(Self::UCSCApi(UCSCApiSequenceRepository::new(
-                        &settings.reference,
-                        &settings.ucsc_host,
-                    )?)))
-                }
+            (BackendType::Default, reference) => {
+                let cache_downloaded = settings.cache_downloaded();
+                let (repository, _) = settings
+                    .source_preference
+                    .open(reference, cache_downloaded, |source| async move {
+                        match source {
+                            DataSource::Cache => {
+                                Ok(Self::TwoBit(TwoBitSequenceRepository::new(reference)))
+                            }
+                            DataSource::Ucsc | DataSource::Ensembl => {
+                                // Sequences are queried on demand. With a cache to fall back to, check that the
+                                // API is reachable first.
+                                if cache_downloaded {
+                                    settings.ucsc_host.probe_api().await?;
+                                }
+                                Ok(Self::UCSCApi(UCSCApiSequenceRepository::new(
+                                    reference,
+                                    &settings.ucsc_host,
+                                    settings.retry,
+                                )?))
+                            }
+                        }
+                    })
+                    .await?;
+                Ok(Some(repository))
             }
         }
     }
+
+    /// Where the repository serves sequences from. None for reference files.
+    pub fn data_source(&self, reference: &Reference) -> Option<DataSource> {
+        match (self, reference) {
+            (Self::UCSCApi(_), _) => Some(DataSource::Ucsc),
+            (Self::TwoBit(_), Reference::BYOTwoBit(_)) | (Self::IndexedFasta(_), _) => None,
+            (Self::TwoBit(_), _) => Some(DataSource::Cache),
+        }
+    }
 }
 
 impl SequenceRepositoryEnum {
@@ -144,3 +311,144 @@ impl SequenceRepositoryEnum {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn sequence(start: u64, bases: &[u8]) -> Sequence {
+        Sequence {
+            start,
+            sequence: bases.to_vec(),
+            contig_index: 0,
+        }
+    }
+
+    #[rstest]
+    #[case(13, 2, Some((&b"Ac"[..], b'G', &b"GC"[..])))]
+    #[case(10, 2, Some((&b""[..], b'g', &b"Ac"[..])))]
+    #[case(16, 3, Some((&b"GGC"[..], b'a', &b"N"[..])))]
+    #[case(13, 0, Some((&b""[..], b'G', &b""[..])))]
+    #[case(9, 2, None)]
+    #[case(18, 2, None)]
+    fn test_base_context(
+        #[case] coordinate: u64,
+        #[case] flank: u64,
+        #[case] expected: Option<(&[u8], u8, &[u8])>,
+    ) {
+        // 10..=17
+        let sequence = sequence(10, b"gAcGGCaN");
+        assert_eq!(sequence.base_context(coordinate, flank), expected);
+    }
+
+    #[rstest]
+    #[case(10, 17, (2, 2, 3, 0, 1))]
+    #[case(12, 13, (0, 1, 1, 0, 0))]
+    #[case(1, 11, (1, 0, 1, 0, 0))]
+    #[case(16, 100, (1, 0, 0, 0, 1))]
+    #[case(18, 100, (0, 0, 0, 0, 0))]
+    fn test_base_composition(
+        #[case] start: u64,
+        #[case] end: u64,
+        #[case] expected: (u64, u64, u64, u64, u64),
+    ) {
+        // 10..=17
+        let sequence = sequence(10, b"gAcGGCaN");
+        let composition = sequence.base_composition(start, end);
+
+        assert_eq!(
+            (
+                composition.a,
+                composition.c,
+                composition.g,
+                composition.t,
+                composition.other
+            ),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(b'A', b'T')]
+    #[case(b'C', b'G')]
+    #[case(b'G', b'C')]
+    #[case(b'T', b'A')]
+    // Soft-masked bases stay lowercase.
+    #[case(b'a', b't')]
+    #[case(b'c', b'g')]
+    #[case(b'g', b'c')]
+    #[case(b't', b'a')]
+    #[case(b'N', b'N')]
+    #[case(b'n', b'n')]
+    #[case(b'R', b'R')]
+    fn test_complement(#[case] base: u8, #[case] expected: u8) {
+        assert_eq!(complement(base), expected);
+        assert_eq!(complement(expected), base);
+    }
+
+    #[rstest]
+    #[case(b"GGGG", Some(1.0))]
+    #[case(b"CCCC", Some(-1.0))]
+    #[case(b"GGGC", Some(0.5))]
+    #[case(b"GCAT", Some(0.0))]
+    #[case(b"ATNN", None)]
+    fn test_gc_skew(#[case] bases: &[u8], #[case] expected: Option<f64>) {
+        let sequence = sequence(1, bases);
+        assert_eq!(sequence.base_composition(1, 4).gc_skew(), expected);
+    }
+
+    #[rstest]
+    #[case([1, 2, 3], Strand::Forward, Some(b"ATG".to_vec()))]
+    #[case([6, 5, 4], Strand::Reverse, Some(b"ATG".to_vec()))]
+    #[case([4, 3, 2], Strand::Reverse, Some(b"GCA".to_vec()))]
+    #[case([8, 9, 10], Strand::Forward, Some(b"nNa".to_vec()))]
+    // Partial codon at the edge of the loaded sequence.
+    #[case([11, 12, 13], Strand::Forward, None)]
+    #[case([1, 0, 13], Strand::Reverse, None)]
+    fn test_codon(
+        #[case] positions: [u64; 3],
+        #[case] strand: Strand,
+        #[case] expected: Option<Vec<u8>>,
+    ) {
+        let sequence = sequence(1, b"ATGCATTnNaa");
+        assert_eq!(sequence.codon(&positions, &strand), expected);
+    }
+
+    #[rstest]
+    #[case(1, 4, Strand::Forward, Some(b"ATGC".to_vec()))]
+    #[case(1, 4, Strand::Reverse, Some(b"GCAT".to_vec()))]
+    #[case(7, 11, Strand::Reverse, Some(b"ttNnA".to_vec()))]
+    #[case(5, 5, Strand::Reverse, Some(b"T".to_vec()))]
+    #[case(9, 12, Strand::Forward, None)]
+    #[case(0, 3, Strand::Forward, None)]
+    fn test_stranded_sequence(
+        #[case] start: u64,
+        #[case] end: u64,
+        #[case] strand: Strand,
+        #[case] expected: Option<Vec<u8>>,
+    ) {
+        let sequence = sequence(1, b"ATGCATTnNaa");
+        assert_eq!(sequence.stranded_sequence(start, end, &strand), expected);
+    }
+
+    #[rstest]
+    #[case(b"", ">chr1:1-0\n")]
+    #[case(b"ACGT", ">chr1:1-0\nACGT\n")]
+    #[case(&[b'A'; 61], concat!(">chr1:1-0\n", "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA\n", "A\n"))]
+    fn test_format_fasta(#[case] bases: &[u8], #[case] expected: &str) {
+        assert_eq!(format_fasta("chr1:1-0", bases), expected);
+    }
+
+    #[rstest]
+    #[case(1, 8, Some(0.6))]
+    #[case(1, 4, Some(0.5))]
+    #[case(3, 6, Some(1.0))]
+    #[case(5, 8, Some(1.0))]
+    #[case(7, 8, None)]
+    #[case(9, 12, Some(0.0))]
+    fn test_gc_content(#[case] start: u64, #[case] end: u64, #[case] expected: Option<f64>) {
+        let sequence = sequence(1, b"ATGCNGNNaaTT");
+        assert_eq!(sequence.base_composition(start, end).gc_content(), expected);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{error::TGVError, sequence::complement, strand::Strand};
+use std::fmt;
+
+/// Bases fetched from the reference at a time while searching.
+pub const MOTIF_SEARCH_CHUNK_SIZE: u64 = 1_000_000;
+
+/// A sequence searched in the reference with `:find`, e.g. a primer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Motif {
+    /// Uppercase bases.
+    bases: Vec<u8>,
+
+    /// Whether the reverse complement also matches.
+    pub both_strands: bool,
+}
+
+/// A match of a motif. Position is the leftmost base on the forward strand. 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MotifMatch {
+    pub position: u64,
+    pub strand: Strand,
+
+    /// Whether the search wrapped around the contig end (or start when searching backward).
+    pub wrapped: bool,
+}
+
+impl Motif {
+    /// Parse a motif of A, C, G, and T, case-insensitive.
+    pub fn new(bases: &str, both_strands: bool) -> Result<Self, TGVError> {
+        if bases.is_empty()
+            || !bases
+                .bytes()
+                .all(|base| matches!(base.to_ascii_uppercase(), b'A' | b'C' | b'G' | b'T'))
+        {
+            return Err(TGVError::ParsingError(format!(
+                "Invalid motif `{bases}`: expected bases A, C, G, and T"
+            )));
+        }
+        Ok(Self {
+            bases: bases.to_ascii_uppercase().into_bytes(),
+            both_strands,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.bases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bases.is_empty()
+    }
+
+    /// Strand of the motif found at the start of window. Palindromes match on the forward strand.
+    fn strand_at(&self, window: &[u8]) -> Option<Strand> {
+        if window.eq_ignore_ascii_case(&self.bases) {
+            Some(Strand::Forward)
+        } else if self.both_strands
+            && window
+                .iter()
+                .rev()
+                .zip(self.bases.iter())
+                .all(|(base, motif_base)| complement(base.to_ascii_uppercase()) == *motif_base)
+        {
+            Some(Strand::Reverse)
+        } else {
+            None
+        }
+    }
+
+    /// Ranges of match starts to search in order, one fetch each. Forward searches start after `from` and wrap around
+    /// at the contig end; backward searches start before `from` and wrap around at the contig start. `from` itself is
+    /// searched last, so that a match at `from` is found only after wrapping around.
+    pub fn search_chunks(
+        &self,
+        contig_length: u64,
+        from: u64,
+        forward: bool,
+        chunk_size: u64,
+    ) -> Vec<SearchChunk> {
+        let length = self.len() as u64;
+        if length > contig_length {
+            return Vec::new();
+        }
+        let last_start = contig_length - length + 1;
+        let from = from.clamp(1, last_start);
+        let chunk_size = chunk_size.max(1);
+
+        let ranges = if forward {
+            [(from + 1, last_start, false), (1, from, true)]
+        } else {
+            [(1, from - 1, false), (from, last_start, true)]
+        };
+        ranges
+            .into_iter()
+            .flat_map(|(start, end, wrapped)| {
+                let mut chunks = (start..=end)
+                    .step_by(chunk_size as usize)
+                    .map(|chunk_start| SearchChunk {
+                        start: chunk_start,
+                        end: u64::min(chunk_start + chunk_size - 1, end),
+                        wrapped,
+                    })
+                    .collect::<Vec<_>>();
+                if !forward {
+                    chunks.reverse();
+                }
+                chunks
+            })
+            .collect()
+    }
+
+    /// First match in the chunk, or the last one when searching backward. bases are the reference from chunk.start to
+    /// chunk.fetch_end(motif).
+    pub fn find_in_chunk(
+        &self,
+        chunk: &SearchChunk,
+        bases: &[u8],
+        forward: bool,
+    ) -> Option<MotifMatch> {
+        let mut windows = bases
+            .windows(self.len())
+            .take((chunk.end - chunk.start + 1) as usize)
+            .enumerate()
+            .filter_map(|(i, window)| self.strand_at(window).map(|strand| (i, strand)));
+        let (i, strand) = if forward {
+            windows.next()
+        } else {
+            windows.next_back()
+        }?;
+        Some(MotifMatch {
+            position: chunk.start + i as u64,
+            strand,
+            wrapped: chunk.wrapped,
+        })
+    }
+}
+
+/// Match starts searched with one fetch of the reference. 1-based, inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchChunk {
+    pub start: u64,
+    pub end: u64,
+
+    /// Whether the chunk is after wrapping around the contig.
+    pub wrapped: bool,
+}
+
+impl SearchChunk {
+    /// Last base to fetch: the end of a match starting at the chunk end. 1-based, inclusive.
+    pub fn fetch_end(&self, motif: &Motif) -> u64 {
+        self.end + motif.len() as u64 - 1
+    }
+}
+
+impl fmt::Display for Motif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.bases))?;
+        if self.both_strands {
+            write!(f, " (both strands)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// GACC at 3 and 17, its reverse complement GGTC at 9, and ATGG at 7.
+    const CONTIG: &[u8] = b"TTGACCatggtcaaTTGACCTT";
+
+    fn search(motif: &Motif, from: u64, forward: bool, chunk_size: u64) -> Option<MotifMatch> {
+        motif
+            .search_chunks(CONTIG.len() as u64, from, forward, chunk_size)
+            .iter()
+            .find_map(|chunk| {
+                let bases = &CONTIG[(chunk.start - 1) as usize..chunk.fetch_end(motif) as usize];
+                motif.find_in_chunk(chunk, bases, forward)
+            })
+    }
+
+    fn found(position: u64, strand: Strand, wrapped: bool) -> Option<MotifMatch> {
+        Some(MotifMatch {
+            position,
+            strand,
+            wrapped,
+        })
+    }
+
+    #[rstest]
+    #[case("ACGT", Ok("ACGT"))]
+    #[case("acgt", Ok("ACGT"))]
+    #[case("", Err(()))]
+    #[case("ACNT", Err(()))]
+    #[case("AC GT", Err(()))]
+    fn motifs_are_bases(#[case] bases: &str, #[case] expected: Result<&str, ()>) {
+        assert_eq!(
+            Motif::new(bases, false)
+                .map(|motif| motif.to_string())
+                .map_err(|_| ()),
+            expected.map(String::from)
+        );
+    }
+
+    #[rstest]
+    // Matches are case-insensitive.
+    #[case("GACC", 1, found(3, Strand::Forward, false))]
+    #[case("GACC", 3, found(17, Strand::Forward, false))]
+    #[case("ATGG", 1, found(7, Strand::Forward, false))]
+    // Wrap around after the last match.
+    #[case("GACC", 17, found(3, Strand::Forward, true))]
+    // The only match is found again after wrapping around.
+    #[case("ATGG", 7, found(7, Strand::Forward, true))]
+    #[case("CCCC", 1, None)]
+    fn forward_search_finds_the_next_match(
+        #[case] bases: &str,
+        #[case] from: u64,
+        #[case] expected: Option<MotifMatch>,
+    ) {
+        let motif = Motif::new(bases, false).unwrap();
+        for chunk_size in [1, 3, 5, 100] {
+            assert_eq!(
+                search(&motif, from, true, chunk_size),
+                expected,
+                "chunk_size={chunk_size}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case("GACC", 22, found(17, Strand::Forward, false))]
+    #[case("GACC", 17, found(3, Strand::Forward, false))]
+    // Wrap around before the first match.
+    #[case("GACC", 3, found(17, Strand::Forward, true))]
+    #[case("CCCC", 22, None)]
+    fn backward_search_finds_the_previous_match(
+        #[case] bases: &str,
+        #[case] from: u64,
+        #[case] expected: Option<MotifMatch>,
+    ) {
+        let motif = Motif::new(bases, false).unwrap();
+        for chunk_size in [1, 3, 5, 100] {
+            assert_eq!(
+                search(&motif, from, false, chunk_size),
+                expected,
+                "chunk_size={chunk_size}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(false, 4, found(17, Strand::Forward, false))]
+    #[case(true, 4, found(9, Strand::Reverse, false))]
+    #[case(true, 9, found(17, Strand::Forward, false))]
+    fn reverse_complements_match_with_both_strands(
+        #[case] both_strands: bool,
+        #[case] from: u64,
+        #[case] expected: Option<MotifMatch>,
+    ) {
+        let motif = Motif::new("GACC", both_strands).unwrap();
+        for chunk_size in [1, 4, 100] {
+            assert_eq!(
+                search(&motif, from, true, chunk_size),
+                expected,
+                "chunk_size={chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn palindromes_match_on_the_forward_strand() {
+        let motif = Motif::new("TTAA", true).unwrap();
+        assert_eq!(motif.strand_at(b"ttaa"), Some(Strand::Forward));
+    }
+
+    #[test]
+    fn motifs_longer_than_the_contig_are_not_found() {
+        let motif = Motif::new(&"A".repeat(30), false).unwrap();
+        assert_eq!(search(&motif, 1, true, 10), None);
+    }
+}
@@ -0,0 +1,114 @@
+/// Longest repeat unit detected as a short tandem repeat.
+pub const MAX_REPEAT_PERIOD: usize = 6;
+
+/// Default minimum length of highlighted repeats in bp.
+pub const DEFAULT_REPEAT_MIN_LENGTH: usize = 5;
+
+/// A homopolymer (period 1) or a short tandem repeat in a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TandemRepeat {
+    /// Index of the first base in the sequence. 0-based.
+    pub start: usize,
+
+    /// Index after the last base. 0-based, exclusive.
+    pub end: usize,
+
+    /// Length of the repeat unit.
+    pub period: usize,
+}
+
+/// Homopolymers of at least min_length bases and tandem repeats with a period of 2 to 6 bases of at least min_length
+/// bases and three copies. Bases are compared case-insensitively, and Ns are never part of a repeat. Each stretch is
+/// reported with its shortest period, e.g. ACACACAC as period 2 only. Partial copies at the end are included.
+pub fn tandem_repeats(bases: &[u8], min_length: usize) -> Vec<TandemRepeat> {
+    let same = |i: usize, j: usize| {
+        let (a, b) = (bases[i].to_ascii_uppercase(), bases[j].to_ascii_uppercase());
+        a == b && a != b'N'
+    };
+
+    let mut repeats = Vec::new();
+    for period in 1..=MAX_REPEAT_PERIOD {
+        let min_length = if period == 1 {
+            min_length
+        } else {
+            usize::max(min_length, 3 * period)
+        };
+
+        // Positions i with bases[i] == bases[i - period] form runs. A run over [first, last) spans
+        // [first - period, last).
+        let mut i = period;
+        while i < bases.len() {
+            if !same(i, i - period) {
+                i += 1;
+                continue;
+            }
+            let first = i;
+            while i < bases.len() && same(i, i - period) {
+                i += 1;
+            }
+            let (start, end) = (first - period, i);
+
+            // Units with a shorter period are reported with that period.
+            let unit_has_shorter_period = (1..period)
+                .filter(|shorter| period % shorter == 0)
+                .any(|shorter| (start + shorter..start + period).all(|j| same(j, j - shorter)));
+            if end - start >= min_length && !unit_has_shorter_period {
+                repeats.push(TandemRepeat { start, end, period });
+            }
+        }
+    }
+    repeats.sort_by_key(|repeat| (repeat.start, repeat.period));
+    repeats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn repeat(start: usize, end: usize, period: usize) -> TandemRepeat {
+        TandemRepeat { start, end, period }
+    }
+
+    #[rstest]
+    #[case(b"GCAAAAATG", 5, vec![repeat(2, 7, 1)])]
+    #[case(b"GCAAAATG", 5, vec![])]
+    #[case(b"GCAAAATG", 4, vec![repeat(2, 6, 1)])]
+    #[case(b"TTTTTaaaaaa", 5, vec![repeat(0, 5, 1), repeat(5, 11, 1)])]
+    #[case(b"AAaaAC", 5, vec![repeat(0, 5, 1)])]
+    #[case(b"NNNNNNNN", 5, vec![])]
+    #[case(b"", 5, vec![])]
+    fn homopolymers_are_detected(
+        #[case] bases: &[u8],
+        #[case] min_length: usize,
+        #[case] expected: Vec<TandemRepeat>,
+    ) {
+        assert_eq!(tandem_repeats(bases, min_length), expected);
+    }
+
+    #[rstest]
+    #[case(b"GACACACG", 5, vec![repeat(1, 7, 2)])]
+    // Three copies are needed even if the run is long enough.
+    #[case(b"GACACG", 4, vec![])]
+    // A partial copy at the end is included.
+    #[case(b"GCACACACG", 5, vec![repeat(1, 8, 2)])]
+    #[case(b"ACACACAC", 5, vec![repeat(0, 8, 2)])]
+    #[case(b"atatatat", 5, vec![repeat(0, 8, 2)])]
+    #[case(b"TCAGCAGCAGT", 5, vec![repeat(1, 10, 3)])]
+    #[case(b"GATTACAGATTACAGATTACA", 5, vec![])]
+    fn short_tandem_repeats_are_detected(
+        #[case] bases: &[u8],
+        #[case] min_length: usize,
+        #[case] expected: Vec<TandemRepeat>,
+    ) {
+        assert_eq!(tandem_repeats(bases, min_length), expected);
+    }
+
+    #[test]
+    fn homopolymers_next_to_dinucleotide_repeats_are_both_reported() {
+        assert_eq!(
+            tandem_repeats(b"CAAAAAAGTGTGTGTC", 5),
+            vec![repeat(1, 7, 1), repeat(7, 15, 2)]
+        );
+    }
+}
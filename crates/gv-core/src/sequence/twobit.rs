@@ -30,7 +30,9 @@ impl TwoBitSequenceRepository {
 
     pub fn add_2bit_file(&mut self, path: &str) -> Result<(), TGVError> {
         let tb: TwoBitFile<std::io::BufReader<std::fs::File>> = twobit::TwoBitFile::open(path)
-            .map_err(|e| TGVError::IOError(format!("Failed to open 2bit file {}: {}", &path, e)))?;
+            .map_err(|e| TGVError::IOError(format!("Failed to open 2bit file {}: {}", &path, e)))?
+            // Keep soft-masked (repeat) bases lowercase.
+            .enable_softmask(true);
 
         let buffer_index = self.buffers.len();
         tb.chrom_names().into_iter().for_each(|chrom_name| {
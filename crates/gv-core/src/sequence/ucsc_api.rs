@@ -1,9 +1,11 @@
 use crate::contig_header::{Contig, ContigHeader};
+use crate::data_source::explain_unreachable;
 use crate::error::TGVError;
 use crate::intervals::{GenomeInterval, Region};
 use crate::reference::Reference;
+use crate::retry::RetryPolicy;
 use crate::sequence::Sequence;
-use crate::tracks::{UcscHost, schema::*};
+use crate::tracks::{UcscApiUrl, UcscHost, schema::*};
 use reqwest::Client;
 use serde::Deserialize;
 use std::time::Instant;
@@ -17,15 +19,25 @@ pub struct UCSCApiSequenceRepository {
     /// None: Not queried yet
     /// Some(hub_url): Queried and cached.
     hub_url: Option<String>,
+
+    /// Base URL of the UCSC API.
+    api_url: UcscApiUrl,
+
+    retry: RetryPolicy,
 }
 
 impl UCSCApiSequenceRepository {
-    pub fn new(reference: &Reference, _host: &UcscHost) -> Result<Self, TGVError> {
-        // FIXME: decide API url based on host
+    pub fn new(
+        reference: &Reference,
+        host: &UcscHost,
+        retry: RetryPolicy,
+    ) -> Result<Self, TGVError> {
         Ok(Self {
             client: Client::new(),
             reference: reference.clone(),
             hub_url: None,
+            api_url: UcscApiUrl::from(host),
+            retry,
         })
     }
 
@@ -36,9 +48,10 @@ impl UCSCApiSequenceRepository {
         start: u64,
         end: u64,
     ) -> Result<String, TGVError> {
-        match &self.reference {
+        match self.reference.clone() {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => Ok(format!(
-                "https://api.genome.ucsc.edu/getData/sequence?genome={};chrom={};start={};end={}",
+                "{}/getData/sequence?genome={};chrom={};start={};end={}",
+                self.api_url,
                 self.reference,
                 contig_name,
                 start - 1, // start is 0-based, inclusive.
@@ -46,12 +59,13 @@ impl UCSCApiSequenceRepository {
             )),
             Reference::UcscAccession(genome) => {
                 if self.hub_url.is_none() {
-                    let hub_url = self.get_hub_url_for_genark_accession(genome).await?;
+                    let hub_url = self.get_hub_url_for_genark_accession(&genome).await?;
                     self.hub_url = Some(hub_url);
                 }
                 let hub_url = self.hub_url.as_ref().unwrap();
                 Ok(format!(
-                    "https://api.genome.ucsc.edu/getData/sequence?hubUrl={}&genome={};chrom={};start={};end={}",
+                    "{}/getData/sequence?hubUrl={}&genome={};chrom={};start={};end={}",
+                    self.api_url,
                     hub_url,
                     genome,
                     contig_name,
@@ -66,16 +80,21 @@ impl UCSCApiSequenceRepository {
     }
 
     pub async fn get_hub_url_for_genark_accession(
-        &self,
+        &mut self,
         accession: &str,
     ) -> Result<String, TGVError> {
-        let url = format!(
-            "https://api.genome.ucsc.edu/list/genarkGenomes?genome={}",
-            accession
-        );
+        let url = format!("{}/list/genarkGenomes?genome={}", self.api_url, accession);
         log::info!("HTTP request: method=GET url={url} context=UCSC sequence GenArk hub lookup");
         let started = Instant::now();
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .api_url
+            .get(
+                &self.client,
+                self.retry,
+                "UCSC sequence GenArk hub lookup",
+                &url,
+            )
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC sequence GenArk hub lookup elapsed_ms={}",
             response.status(),
@@ -125,7 +144,21 @@ impl UCSCApiSequenceRepository {
             region.end()
         );
         let started = Instant::now();
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .api_url
+            .get(&self.client, self.retry, "UCSC sequence query", &url)
+            .await
+            .map_err(|e| {
+                explain_unreachable(
+                    e,
+                    &format!(
+                        "Sequence of {contig_name}:{}-{}",
+                        region.start(),
+                        region.end()
+                    ),
+                    &self.reference,
+                )
+            })?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC sequence query elapsed_ms={}",
             response.status(),
@@ -151,23 +184,23 @@ impl UCSCApiSequenceRepository {
     }
 
     pub async fn get_all_contigs(&mut self) -> Result<Vec<Contig>, TGVError> {
-        let query_url = match &self.reference {
+        let query_url = match self.reference.clone() {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => {
                 format!(
-                    "https://api.genome.ucsc.edu/list/chromosomes?genome={}",
-                    self.reference
+                    "{}/list/chromosomes?genome={}",
+                    self.api_url, self.reference
                 )
             }
             Reference::UcscAccession(genome) => {
                 let hub_url = self.hub_url.clone().unwrap_or({
-                    let hub_url = self.get_hub_url_for_genark_accession(genome).await?;
+                    let hub_url = self.get_hub_url_for_genark_accession(&genome).await?;
                     self.hub_url = Some(hub_url.clone());
                     hub_url
                 });
 
                 format!(
-                    "https://api.genome.ucsc.edu/list/chromosomes?hubUrl={};genome={}",
-                    hub_url, genome
+                    "{}/list/chromosomes?hubUrl={};genome={}",
+                    self.api_url, hub_url, genome
                 )
             }
             _ => {
@@ -183,7 +216,15 @@ impl UCSCApiSequenceRepository {
             self.reference
         );
         let started = Instant::now();
-        let response = self.client.get(&query_url).send().await?;
+        let response = self
+            .api_url
+            .get(
+                &self.client,
+                self.retry,
+                "UCSC sequence chromosome list",
+                &query_url,
+            )
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC sequence chromosome list elapsed_ms={}",
             response.status(),
@@ -1,6 +1,12 @@
+use crate::alignment::{HiddenReadCategory, InsertRange};
+use crate::data_source::SourcePreference;
+use crate::error::TGVError;
 use crate::reference::Reference;
+use crate::retry::RetryPolicy;
 use crate::tracks::UcscHost;
 use clap::ValueEnum;
+use std::collections::BTreeSet;
+use std::path::Path;
 
 #[derive(Clone, Debug, PartialEq, Eq, ValueEnum, Default)]
 pub enum BackendType {
@@ -10,6 +16,9 @@ pub enum BackendType {
     /// Always use local database.
     Local,
 
+    /// Query genes from the Ensembl REST API. Sequences come from the UCSC API.
+    Ensembl,
+
     /// If local cache is available, use it. Otherwise, use UCSC DB / API.
     #[default]
     Default,
@@ -23,6 +32,23 @@ pub enum BamSource {
 
     /// File on AWS S3 or S3-compatible object storage.
     S3,
+
+    /// File on an HTTP(S) server. Byte ranges are fetched with range requests.
+    Http,
+}
+
+impl BamSource {
+    /// Source of a BAM path: `s3://` URLs are on S3, `http://` and `https://` URLs are on HTTP servers, and other paths
+    /// are local.
+    pub fn of(path: &str) -> Self {
+        if path.starts_with("s3://") {
+            BamSource::S3
+        } else if path.starts_with("http://") || path.starts_with("https://") {
+            BamSource::Http
+        } else {
+            BamSource::Local
+        }
+    }
 }
 
 /// Alignment input file with the auxiliary files required to read it.
@@ -42,6 +68,25 @@ pub enum AlignmentPath {
         fasta: String,
         fai: String,
     },
+
+    /// Unindexed BAM stream piped to standard input, passed as `-`. All reads are loaded on startup.
+    Stdin,
+}
+
+impl AlignmentPath {
+    /// File name shown as the track label, e.g. tumor.bam.
+    pub fn label(&self) -> String {
+        match self {
+            AlignmentPath::Bam { path, .. } | AlignmentPath::Cram { path, .. } => path
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .map(|name| name.split(['?', '#']).next().unwrap_or(name))
+                .unwrap_or(path)
+                .to_string(),
+            AlignmentPath::Stdin => "stdin".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -49,8 +94,12 @@ pub enum FilePath {
     AlignmentPath(AlignmentPath),
     VariantPath(String),
     BedPath(String),
+    BigWigPath(String),
 }
 
+/// Default cap on the reads loaded per alignment track, enough for deep coverage of a few kb.
+pub const DEFAULT_MAX_READS: usize = 250_000;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Settings {
     pub file_paths: Vec<FilePath>,
@@ -60,6 +109,33 @@ pub struct Settings {
     pub ucsc_host: UcscHost,
 
     pub cache_dir: String,
+
+    /// bigBed gene annotation to use as the gene track instead of the reference's tracks.
+    pub bigbed_path: Option<String>,
+
+    /// GTF or GFF3 gene annotation to use as the gene track instead of the reference's tracks.
+    pub gtf_path: Option<String>,
+
+    /// Cache UCSC API gene track responses under cache_dir.
+    pub track_cache: bool,
+
+    /// Gene track (e.g. refGene) to use instead of the preferred track of the reference.
+    pub gene_track: Option<String>,
+
+    /// Read categories (e.g. supplementary) to include in alignment tracks. Reads of other categories are hidden.
+    pub shown_read_categories: BTreeSet<HiddenReadCategory>,
+
+    /// Expected insert size range of paired reads.
+    pub insert_range: InsertRange,
+
+    /// Maximum number of reads loaded per alignment track.
+    pub max_reads: usize,
+
+    /// Retries of UCSC API requests and MySQL connections.
+    pub retry: RetryPolicy,
+
+    /// Whether the default backend tries the local cache or UCSC first.
+    pub source_preference: SourcePreference,
     //pub palette: Palette,
 }
 
@@ -70,7 +146,191 @@ impl Default for Settings {
             reference: Reference::default(),
             backend: BackendType::default(), // Default backend
             ucsc_host: UcscHost::default(),
-            cache_dir: shellexpand::tilde("~/.tgv").to_string(),
+            cache_dir: cache_dir(None),
+            bigbed_path: None,
+            gtf_path: None,
+            track_cache: true,
+            gene_track: None,
+            shown_read_categories: HiddenReadCategory::shown_by_default(),
+            insert_range: InsertRange::default(),
+            max_reads: DEFAULT_MAX_READS,
+            retry: RetryPolicy::default(),
+            source_preference: SourcePreference::default(),
         }
     }
 }
+
+impl Settings {
+    /// Whether a gene annotation file (bigBed, GTF, or GFF3) is used as the gene track.
+    pub fn has_gene_annotation_file(&self) -> bool {
+        self.bigbed_path.is_some() || self.gtf_path.is_some()
+    }
+
+    /// Whether the reference has been downloaded to the local cache with `tgv download`.
+    pub fn cache_downloaded(&self) -> bool {
+        Path::new(&self.reference.cache_dir(&self.cache_dir)).exists()
+    }
+}
+
+/// Cache directory of earlier versions. It also holds logs and sessions.
+const LEGACY_CACHE_DIR: &str = "~/.tgv";
+
+/// Cache directory: the given directory (e.g. from `--cache-dir`), then `$TGV_CACHE_DIR`, then
+/// `$XDG_CACHE_HOME/tgv`, then `~/.cache/tgv`. `~` is expanded. If neither a directory nor `$TGV_CACHE_DIR` is given,
+/// and only the legacy `~/.tgv` holds cached data, `~/.tgv` is used.
+pub fn cache_dir(cache_dir: Option<&str>) -> String {
+    let tgv_cache_dir = std::env::var("TGV_CACHE_DIR").ok();
+    let resolved = resolve_cache_dir(
+        cache_dir,
+        tgv_cache_dir.as_deref(),
+        std::env::var("XDG_CACHE_HOME").ok().as_deref(),
+    );
+    if [cache_dir, tgv_cache_dir.as_deref()]
+        .into_iter()
+        .flatten()
+        .any(|dir| !dir.is_empty())
+    {
+        return resolved;
+    }
+    legacy_cache_dir_fallback(resolved, &shellexpand::tilde(LEGACY_CACHE_DIR))
+}
+
+/// The legacy cache directory if the default one does not exist yet and the legacy one holds cached data. The
+/// default cache directory otherwise.
+fn legacy_cache_dir_fallback(cache_dir: String, legacy_cache_dir: &str) -> String {
+    if Path::new(&cache_dir).exists() || !has_cached_data(Path::new(legacy_cache_dir)) {
+        return cache_dir;
+    }
+    log::info!(
+        "Using the legacy cache directory: cache_dir={legacy_cache_dir} default_cache_dir={cache_dir}"
+    );
+    legacy_cache_dir.to_string()
+}
+
+/// Whether a cache directory holds references downloaded with `tgv download` (`REFERENCE/tracks.sqlite`) or cached
+/// API responses (`api_cache`).
+fn has_cached_data(dir: &Path) -> bool {
+    dir.join("api_cache").is_dir()
+        || std::fs::read_dir(dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().join("tracks.sqlite").is_file())
+        })
+}
+
+/// Empty environment variables are treated as unset, as in the XDG base directory specification.
+fn resolve_cache_dir(
+    cache_dir: Option<&str>,
+    tgv_cache_dir: Option<&str>,
+    xdg_cache_home: Option<&str>,
+) -> String {
+    let cache_dir = match (
+        cache_dir.filter(|dir| !dir.is_empty()),
+        tgv_cache_dir.filter(|dir| !dir.is_empty()),
+        xdg_cache_home.filter(|dir| !dir.is_empty()),
+    ) {
+        (Some(dir), _, _) | (None, Some(dir), _) => dir.to_string(),
+        (None, None, Some(xdg_cache_home)) => {
+            format!("{}/tgv", xdg_cache_home.trim_end_matches('/'))
+        }
+        (None, None, None) => "~/.cache/tgv".to_string(),
+    };
+    shellexpand::tilde(&cache_dir).to_string()
+}
+
+/// Create the cache directory if missing.
+pub fn create_cache_dir(cache_dir: &str) -> Result<(), TGVError> {
+    std::fs::create_dir_all(cache_dir).map_err(|e| {
+        TGVError::IOError(format!(
+            "Failed to create the cache directory {cache_dir}: {e}"
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn home(path: &str) -> String {
+        shellexpand::tilde(&format!("~/{path}")).to_string()
+    }
+
+    #[rstest]
+    #[case(Some("/data/tgv"), Some("/env/tgv"), Some("/xdg"), "/data/tgv".to_string())]
+    #[case(None, Some("/env/tgv"), Some("/xdg"), "/env/tgv".to_string())]
+    #[case(None, None, Some("/xdg"), "/xdg/tgv".to_string())]
+    #[case(None, None, Some("/xdg/"), "/xdg/tgv".to_string())]
+    #[case(None, Some(""), Some(""), home(".cache/tgv"))]
+    #[case(None, None, None, home(".cache/tgv"))]
+    #[case(Some("~/.tgv"), None, None, home(".tgv"))]
+    #[case(None, Some("~/tgv-cache"), None, home("tgv-cache"))]
+    fn test_resolve_cache_dir(
+        #[case] cache_dir: Option<&str>,
+        #[case] tgv_cache_dir: Option<&str>,
+        #[case] xdg_cache_home: Option<&str>,
+        #[case] expected: String,
+    ) {
+        assert_eq!(
+            resolve_cache_dir(cache_dir, tgv_cache_dir, xdg_cache_home),
+            expected
+        );
+    }
+
+    #[rstest]
+    // Downloaded references and cached API responses are in the legacy directory.
+    #[case(false, &["hg38/tracks.sqlite"], true)]
+    #[case(false, &["api_cache/hg38/refGene.json"], true)]
+    // The default directory is in use.
+    #[case(true, &["hg38/tracks.sqlite"], false)]
+    // Logs and sessions are not cached data.
+    #[case(false, &["tgv_2026-10-15.log", "sessions/default.toml"], false)]
+    #[case(false, &[], false)]
+    fn test_legacy_cache_dir_fallback(
+        #[case] cache_dir_exists: bool,
+        #[case] legacy_files: &[&str],
+        #[case] expect_legacy: bool,
+    ) {
+        let home = tempfile::tempdir().unwrap();
+        let cache_dir = home.path().join(".cache").join("tgv");
+        let legacy_cache_dir = home.path().join(".tgv");
+        if cache_dir_exists {
+            std::fs::create_dir_all(&cache_dir).unwrap();
+        }
+        for file in legacy_files {
+            let path = legacy_cache_dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        let resolved = legacy_cache_dir_fallback(
+            cache_dir.to_str().unwrap().to_string(),
+            legacy_cache_dir.to_str().unwrap(),
+        );
+        let expected = if expect_legacy {
+            legacy_cache_dir
+        } else {
+            cache_dir
+        };
+        assert_eq!(resolved, expected.to_str().unwrap());
+    }
+
+    #[rstest]
+    #[case(AlignmentPath::Bam { path: "data/tumor.bam".to_string(), index: "data/tumor.bam.bai".to_string(), source: BamSource::Local }, "tumor.bam")]
+    #[case(AlignmentPath::Bam { path: "https://example.org/normal.bam?token=1".to_string(), index: "".to_string(), source: BamSource::Http }, "normal.bam")]
+    #[case(AlignmentPath::Cram { path: "normal.cram".to_string(), crai: "".to_string(), fasta: "".to_string(), fai: "".to_string() }, "normal.cram")]
+    #[case(AlignmentPath::Stdin, "stdin")]
+    fn test_alignment_label(#[case] path: AlignmentPath, #[case] expected: &str) {
+        assert_eq!(path.label(), expected);
+    }
+
+    #[test]
+    fn missing_cache_dir_is_created() {
+        let parent = tempfile::tempdir().unwrap();
+        let cache_dir = parent.path().join("cache").join("tgv");
+        create_cache_dir(cache_dir.to_str().unwrap()).unwrap();
+        assert!(cache_dir.is_dir());
+        // Existing directories are kept.
+        create_cache_dir(cache_dir.to_str().unwrap()).unwrap();
+    }
+}
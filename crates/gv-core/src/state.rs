@@ -2,29 +2,73 @@ use crate::sequence::SequenceRepositoryEnum;
 use crate::tracks::{TrackService, TrackServiceEnum};
 use crate::variant::VariantRepository;
 use crate::{
-    alignment::{Alignment, AlignmentRepositoryEnum, PairedAlignment},
+    alignment::{
+        AlignedRead, Alignment, AlignmentRepositoryEnum, CoverageOverview, HiddenReadCategory,
+        IndelHistogram, InsertRange, PairedAlignment, ReadSpan, SplitRead,
+    },
     bed::{BedRepository, BedTrack},
+    bigwig::{BigWigRepository, BigWigTrack},
     contig_header::ContigHeader,
+    contig_overview::ContigOverview,
     cytoband::Cytoband,
+    data_source::DataSource,
     error::TGVError,
-    feature::Gene,
+    feature::{Gene, SubGeneFeature},
+    gaps::GapTrack,
+    genetic_code::GeneticCode,
     intervals::{Focus, GenomeInterval, Region},
-    message::{AlignmentDisplayOption, AlignmentFilter, AlignmentSort, Movement},
+    loci::{Locus, LocusList},
+    message::{
+        AlignmentDisplayOption, AlignmentFilter, AlignmentSort, CoordinateBase, Downsample,
+        GeneFrame, Movement,
+    },
     reference::Reference,
+    regions::TargetRegions,
     //register::Registers,
     //rendering::{MainLayout, layout::resize_node},
     repository::Repository,
-    sequence::Sequence,
+    sequence::{MOTIF_SEARCH_CHUNK_SIZE, Motif, Sequence, format_fasta},
+    settings::DEFAULT_MAX_READS,
+    strand::Strand,
     track::Track,
-    variant::VariantTrack,
+    variant::{Variant, VariantAllele, VariantFilter, VariantTrack},
 };
 use itertools::Itertools;
+use std::collections::{BTreeSet, HashMap};
 use std::time::Instant;
 
+/// Data loaded for the region in view. A split screen keeps the data of the inactive pane here, and swaps it with
+/// the state's data when that pane becomes active.
+#[derive(Debug, Default)]
+pub struct ViewData {
+    pub alignments: Vec<Alignment>,
+    pub paired_alignments: Vec<Option<PairedAlignment>>,
+    pub coverage_overview: Option<CoverageOverview>,
+    pub signals: Vec<BigWigTrack>,
+    pub track: Track<Gene>,
+    pub sequence: Sequence,
+}
+
+impl ViewData {
+    /// Drop the data loaded from alignment and bigWig files so that the next load re-reads them.
+    pub fn clear_file_data(&mut self) {
+        self.alignments.fill_with(Alignment::default);
+        self.paired_alignments.fill_with(|| None);
+        self.coverage_overview = None;
+        self.signals.fill_with(BigWigTrack::default);
+    }
+}
+
 /// Holds states of the application.
 pub struct State {
     pub messages: Vec<String>,
 
+    /// Details of the read clicked in an alignment track, pinned to the status bar until the next click or Esc.
+    pub pinned_read: Option<String>,
+
+    /// Other alignments of the pinned read from its SA tag, drawn as connectors.
+    pub split_read: Option<SplitRead>,
+
     pub contig_header: ContigHeader,
     pub reference: Reference,
 
@@ -34,19 +78,108 @@ pub struct State {
     pub alignment_options: Vec<Vec<AlignmentDisplayOption>>,
     pub paired_alignments: Vec<Option<PairedAlignment>>,
 
+    /// Whole-contig coverage overview of the first alignment track.
+    pub coverage_overview: Option<CoverageOverview>,
+
+    /// Genes across the whole contig, binned for the minimap strip.
+    pub minimap: Option<ContigOverview>,
+
+    /// Assembly gaps of the contig in view, loaded while the gap track is shown.
+    pub gaps: Option<GapTrack>,
+
     /// Variant track data.
     /// Index always matches with VariantRepository index
     pub variants: Vec<VariantTrack>,
     pub variant_loaded: Vec<bool>, // Temporary hack before proper implemetation for the indexed VCF IO
 
+    /// Variants shown in all variant tracks.
+    pub variant_filter: VariantFilter,
+
+    /// INFO fields listed in the variant popup. None lists all fields.
+    pub variant_info_fields: Option<Vec<String>>,
+
     /// Bed track data
     /// Index always matches with BedRepository index
     pub bed_intervals: Vec<BedTrack>,
     pub bed_loaded: Vec<bool>, // Temporary hack before proper implemetation for large bed file io
 
+    /// bigWig signal track data.
+    /// Index always matches with BigWigRepository index
+    pub signals: Vec<BigWigTrack>,
+
     pub track: Track<Gene>,
 
     pub sequence: Sequence,
+
+    /// Genetic code override for translation. None chooses the code by contig.
+    pub genetic_code: Option<GeneticCode>,
+
+    /// Coverage axis maximum. Deeper coverage is clipped. None scales the axis to the maximum depth in view.
+    pub coverage_max: Option<usize>,
+
+    /// Whether soft-masked (lowercase) reference bases are shown dimmed.
+    pub show_masking: bool,
+
+    /// Minimum length of homopolymers and short tandem repeats underlined in the sequence row. None turns the
+    /// highlight off.
+    pub repeat_min_length: Option<usize>,
+
+    /// Whether CG dinucleotides are marked in the sequence row.
+    pub show_cpg: bool,
+
+    /// Whether the reference and read bases are shown complemented, reading the minus strand 3' to 5'.
+    pub revcomp: bool,
+
+    /// Mismatched read bases with a lower quality score are shown as matches. 0 shows all mismatches.
+    pub min_mismatch_quality: u8,
+
+    /// Whether the base modification pileup is shown. Base modifications are aggregated only while it is.
+    pub show_modification_pileup: bool,
+
+    /// Interval between major ruler ticks in bp. None spaces the ticks by the zoom.
+    pub ruler_interval: Option<u64>,
+
+    /// Base of the coordinates shown on the ruler and in the status bar.
+    pub coordinate_base: CoordinateBase,
+
+    /// Cap on displayed reads per column.
+    pub downsample: Downsample,
+
+    /// Depth of `Downsample::Auto`. The frontend sets it from the alignment track height.
+    pub downsample_auto_depth: usize,
+
+    /// Expected insert size range. Pairs outside of it are colored in the paired view.
+    pub insert_range: InsertRange,
+
+    /// Maximum number of reads loaded per alignment track.
+    pub max_reads: usize,
+
+    /// Where the gene track and sequence of the reference are served from. None for annotation and reference files.
+    pub data_source: Option<DataSource>,
+
+    /// Hidden read categories that are loaded into alignment tracks. Reads of other categories are hidden.
+    pub shown_read_categories: BTreeSet<HiddenReadCategory>,
+
+    /// Variant allele whose supporting reads are highlighted.
+    pub supported_allele: Option<VariantAllele>,
+
+    /// Loci saved with `:mark`, by name. Kept until TGV exits.
+    pub bookmarks: HashMap<String, Focus>,
+
+    /// Loci loaded with `--loci`, visited with n / N.
+    pub loci: LocusList,
+
+    /// Region covered by reads piped from stdin. Without an index, movements are kept within it.
+    pub read_span: Option<ReadSpan>,
+
+    /// Target regions loaded with `--regions`. Movements are kept within them if any.
+    pub target_regions: TargetRegions,
+
+    /// Motif searched with `:find`. n / N repeat the search while it is set.
+    pub motif: Option<Motif>,
+
+    /// Contig index and rank of the coverage peak last visited with `:peak`. 0 is the highest.
+    pub peak_rank: Option<(usize, usize)>,
 }
 
 impl State {
@@ -60,17 +193,48 @@ impl State {
 
             // /settings: settings.clone(),
             messages: Vec::new(),
+            pinned_read: None,
+            split_read: None,
 
             alignments: Vec::new(),
             alignment_options: Vec::new(),
             paired_alignments: Vec::new(),
+            coverage_overview: None,
+            minimap: None,
+            gaps: None,
 
             track: Track::<Gene>::default(),
             sequence: Sequence::default(),
+            genetic_code: None,
+            coverage_max: None,
+            show_masking: true,
+            repeat_min_length: None,
+            show_cpg: false,
+            revcomp: false,
+            min_mismatch_quality: 0,
+            show_modification_pileup: false,
+            ruler_interval: None,
+            coordinate_base: CoordinateBase::default(),
+            downsample: Downsample::Auto,
+            downsample_auto_depth: Alignment::DEFAULT_DOWNSAMPLE_DEPTH,
+            insert_range: InsertRange::default(),
+            max_reads: DEFAULT_MAX_READS,
+            data_source: None,
+            shown_read_categories: HiddenReadCategory::shown_by_default(),
+            supported_allele: None,
             variants: Vec::new(),
             variant_loaded: Vec::new(),
+            variant_filter: VariantFilter::default(),
+            variant_info_fields: None,
             bed_intervals: Vec::new(),
             bed_loaded: Vec::new(),
+            signals: Vec::new(),
+            bookmarks: HashMap::new(),
+            loci: LocusList::default(),
+            read_span: None,
+            target_regions: TargetRegions::default(),
+            motif: None,
+            peak_rank: None,
             contig_header: contigs,
         })
     }
@@ -95,24 +259,61 @@ impl State {
     }
 }
 
+/// Region around an interval, padded by the frame and clamped to [1, contig_length]. The half width is rounded up so
+/// that the region covers the whole padded interval.
+pub fn framed_region(
+    interval: &impl GenomeInterval,
+    frame: GeneFrame,
+    contig_length: Option<u64>,
+) -> Region {
+    let (left, right) = match frame {
+        GeneFrame::Flank(flank) => (
+            interval.start().saturating_sub(flank),
+            interval.end().saturating_add(flank),
+        ),
+        GeneFrame::Percent(percent) => {
+            let half_width = interval.length().saturating_mul(percent) / 200;
+            (
+                interval.middle().saturating_sub(half_width),
+                interval.middle().saturating_add(half_width),
+            )
+        }
+    };
+    let left = left.max(1);
+    let right = match contig_length {
+        Some(contig_length) => right.min(contig_length),
+        None => right,
+    };
+
+    Region {
+        focus: Focus {
+            contig_index: interval.contig_index(),
+            position: left + (right - left) / 2,
+        },
+        half_width: (right - left).div_ceil(2),
+    }
+}
+
 impl State {
     pub async fn movement(
-        &self,
+        &mut self,
         focus: Focus,
         zoom: u64,
         repository: &mut Repository,
         movement: Movement,
     ) -> Result<Focus, TGVError> {
-        match movement {
+        // Panning along the edge of a target region is not worth a note.
+        let report_off_target = !matches!(
+            movement,
+            Movement::Left(_) | Movement::Right(_) | Movement::Default
+        );
+        let focus = match movement {
             Movement::Left(n) => Ok(focus.move_left(n * zoom)),
             Movement::Right(n) => Ok(focus.move_right(n * zoom)),
             Movement::Position(position) => Ok(focus.move_to(position)),
-            Movement::ContigNamePosition(contig_name, position) => Ok(Focus {
-                contig_index: self
-                    .contig_header
-                    .try_get_index_by_str(contig_name.as_ref())?,
-                position,
-            }),
+            Movement::ContigNamePosition(contig_name, position) => {
+                self.contig_name_position(&contig_name, position)
+            }
             Movement::NextExonsStart(n) => self.next_exons_start(focus, repository, n).await,
             Movement::NextExonsEnd(n) => self.next_exons_end(focus, repository, n).await,
             Movement::PreviousExonsStart(n) => {
@@ -125,6 +326,8 @@ impl State {
                 self.previous_genes_start(focus, repository, n).await
             }
             Movement::PreviousGenesEnd(n) => self.previous_genes_end(focus, repository, n).await,
+            Movement::NextVariants(n) => self.next_variants(focus, n),
+            Movement::PreviousVariants(n) => self.previous_variants(focus, n),
 
             Movement::NextContig(n) => Ok(self.next_contig(focus, n)),
             Movement::PreviousContig(n) => Ok(self.previous_contig(focus, n)),
@@ -134,9 +337,256 @@ impl State {
             }),
 
             Movement::Gene(name) => self.gene(repository, name.as_ref()).await,
+            Movement::GeneResidue(name, residue) => {
+                self.gene_residue(repository, name.as_ref(), residue).await
+            }
+            Movement::Bookmark(name) => self.bookmark(&name),
+            Movement::NextLocus(n) => {
+                let locus = self.loci.next(n)?.clone();
+                self.locus(repository, &locus).await
+            }
+            Movement::PreviousLocus(n) => {
+                let locus = self.loci.previous(n)?.clone();
+                self.locus(repository, &locus).await
+            }
+            Movement::NextRegions(n) => self.target_regions.next(&focus, n),
+            Movement::PreviousRegions(n) => self.target_regions.previous(&focus, n),
+            Movement::NextMatches(n) => self.motif_matches(focus, repository, n, true).await,
+            Movement::HighestPeak => self.coverage_peak(focus, repository, None).await,
+            Movement::NextPeaks(n) => self.coverage_peak(focus, repository, Some(n)).await,
+            Movement::PreviousMatches(n) => self.motif_matches(focus, repository, n, false).await,
 
             Movement::Default => self.default_focus(repository).await,
+        }?;
+        let focus = self.restrict_to_target_regions(focus, report_off_target);
+        Ok(self.restrict_to_read_span(focus))
+    }
+
+    /// Move the focus into the nearest target region, if regions are loaded. With report, a status note names the
+    /// region moved to.
+    pub fn restrict_to_target_regions(&mut self, focus: Focus, report: bool) -> Focus {
+        let restricted = self.target_regions.snap(focus.clone());
+        if restricted != focus && report {
+            let locus = focus
+                .to_locus_str(&self.contig_header)
+                .unwrap_or_else(|_| focus.position.to_string());
+            let region = self
+                .target_regions
+                .nearest(&focus)
+                .map(|region| region.to_locus_str(&self.contig_header))
+                .unwrap_or_default();
+            self.add_message(format!(
+                "{locus} is outside target regions. Moved to the nearest region {region}"
+            ));
+        }
+        restricted
+    }
+
+    /// Keep the focus within the reads piped from stdin, with a status note when it is moved.
+    pub fn restrict_to_read_span(&mut self, focus: Focus) -> Focus {
+        let Some(read_span) = &self.read_span else {
+            return focus;
+        };
+        let restricted = read_span.clamp(focus.clone());
+        if restricted != focus {
+            let locus = match self.contig_header.try_get(read_span.contig_index) {
+                Ok(contig) => format!("{}:{}-{}", contig.name, read_span.start, read_span.end),
+                Err(_) => format!("{}-{}", read_span.start, read_span.end),
+            };
+            self.add_message(format!(
+                "Reads from stdin cover {locus} only. Stdin has no index, so the view stays within them."
+            ));
+        }
+        restricted
+    }
+
+    /// Focus on a contig typed by the user. Aliases, e.g. chr22 for 22, are reported with the name in the reference.
+    fn contig_name_position(
+        &mut self,
+        contig_name: &str,
+        position: u64,
+    ) -> Result<Focus, TGVError> {
+        let contig_index = self.contig_header.resolve(contig_name)?;
+        let resolved_name = &self.contig_header.try_get(contig_index)?.name;
+        if resolved_name != contig_name {
+            self.add_message(format!("{contig_name} is {resolved_name} in the reference"));
+        }
+        Ok(Focus {
+            contig_index,
+            position,
+        })
+    }
+
+    async fn locus(&self, repository: &mut Repository, locus: &Locus) -> Result<Focus, TGVError> {
+        match locus {
+            Locus::ContigPosition(contig_name, position) => Ok(Focus {
+                contig_index: self.contig_header.resolve(contig_name)?,
+                position: *position,
+            }),
+            Locus::Gene(gene_name) => self.gene(repository, gene_name).await,
+        }
+    }
+
+    /// Start of the n-th match of the `:find` motif after (or before) the focus on the focus contig, wrapping around at
+    /// the contig ends. The reference is fetched in chunks, so long contigs are searched incrementally.
+    async fn motif_matches(
+        &mut self,
+        focus: Focus,
+        repository: &mut Repository,
+        n: usize,
+        forward: bool,
+    ) -> Result<Focus, TGVError> {
+        let motif = self.motif.clone().ok_or(TGVError::StateError(
+            "No motif is searched. Search one with :find ACGT".to_string(),
+        ))?;
+        let sequence_service = repository
+            .sequence_service
+            .as_mut()
+            .ok_or(TGVError::StateError(
+                "Motif search needs a reference sequence".to_string(),
+            ))?;
+        let contig_name = self.contig_name(&focus)?.clone();
+        let contig_length = self
+            .contig_length(&focus)?
+            .ok_or(TGVError::StateError(format!(
+                "The length of {contig_name} is unknown. Cannot search it"
+            )))?;
+
+        let mut position = focus.position;
+        let mut last_match = None;
+        let mut wrapped = false;
+        for _ in 0..n.max(1) {
+            let mut found = None;
+            for chunk in
+                motif.search_chunks(contig_length, position, forward, MOTIF_SEARCH_CHUNK_SIZE)
+            {
+                let fetch_end = chunk.fetch_end(&motif);
+                let half_width = (fetch_end - chunk.start).div_ceil(2);
+                let region = Region {
+                    focus: focus.clone().move_to(chunk.start + half_width),
+                    half_width,
+                };
+                let sequence = sequence_service
+                    .query_sequence(&region, &self.contig_header)
+                    .await?;
+                let bases = sequence
+                    .sequence
+                    .get((chunk.start.saturating_sub(sequence.start)) as usize..)
+                    .unwrap_or_default();
+                found = motif.find_in_chunk(&chunk, bases, forward);
+                if found.is_some() {
+                    break;
+                }
+            }
+            let found = found.ok_or(TGVError::StateError(format!(
+                "{motif} not found on {contig_name}"
+            )))?;
+            position = found.position;
+            wrapped |= found.wrapped;
+            last_match = Some(found);
+        }
+
+        if let Some(found) = last_match {
+            let wrapped = if wrapped {
+                format!("Search wrapped around {contig_name}. ")
+            } else {
+                String::new()
+            };
+            self.add_message(format!(
+                "{wrapped}Found {motif} at {contig_name}:{} ({})",
+                found.position, found.strand
+            ));
+        }
+        Ok(focus.move_to(position))
+    }
+
+    /// Coverage peak of the focus contig: the highest one, or the n-th one after the last visited peak in descending
+    /// coverage. Peaks are local maxima of the coverage overview, which are 16 kb windows. The reads of the window are
+    /// then loaded into the first alignment track, and the focus moves to the highest coverage in the window.
+    async fn coverage_peak(
+        &mut self,
+        focus: Focus,
+        repository: &mut Repository,
+        n: Option<usize>,
+    ) -> Result<Focus, TGVError> {
+        let alignment_repository =
+            repository
+                .alignment_repositories
+                .first_mut()
+                .ok_or(TGVError::StateError(
+                    "No alignments are loaded. :peak needs an indexed BAM file".to_string(),
+                ))?;
+        if self
+            .coverage_overview
+            .as_ref()
+            .map(|overview| overview.contig_index)
+            != Some(focus.contig_index)
+        {
+            self.load_coverage_overview(focus.contig_index, alignment_repository)?;
+        }
+        let contig_name = self.contig_name(&focus)?.clone();
+        let peaks = self
+            .coverage_overview
+            .as_ref()
+            .ok_or(TGVError::StateError(
+                "Coverage peaks need a BAM index. CRAM files and stdin are not supported"
+                    .to_string(),
+            ))?
+            .peaks();
+        if peaks.is_empty() {
+            return Err(TGVError::StateError(format!("No reads on {contig_name}")));
+        }
+
+        let rank = match (n, self.peak_rank) {
+            (Some(n), Some((contig_index, rank))) if contig_index == focus.contig_index => {
+                (rank + n.max(1)) % peaks.len()
+            }
+            _ => 0,
+        };
+        self.peak_rank = Some((focus.contig_index, rank));
+        self.add_message(format!(
+            "Coverage peak {}/{} on {contig_name}",
+            rank + 1,
+            peaks.len()
+        ));
+
+        let (start, end) = CoverageOverview::window_range(peaks[rank]);
+        let end = match self.contig_length(&focus)? {
+            Some(length) => end.min(length),
+            None => end,
+        };
+        let window = Region {
+            focus: focus.clone().move_to(start + (end - start) / 2),
+            half_width: (end - start).div_ceil(2),
+        };
+        if !self.alignments[0].has_complete_data(&window) {
+            self.load_alignment_data(0, &window, alignment_repository)
+                .await?;
         }
+        let position = self.alignments[0]
+            .highest_coverage_position(start, end)
+            .unwrap_or(window.focus.position);
+        Ok(focus.move_to(position))
+    }
+
+    /// Save the focus under the name. Return the replaced bookmark, if any.
+    pub fn set_bookmark(&mut self, name: String, focus: Focus) -> Option<Focus> {
+        self.bookmarks.insert(name, focus)
+    }
+
+    pub fn bookmark(&self, name: &str) -> Result<Focus, TGVError> {
+        self.bookmarks
+            .get(name)
+            .cloned()
+            .ok_or(TGVError::StateError(format!(
+                "Unknown bookmark @{}. List bookmarks with :marks",
+                name
+            )))
+    }
+
+    /// Bookmark names, sorted.
+    pub fn bookmark_names(&self) -> Vec<&String> {
+        self.bookmarks.keys().sorted().collect()
     }
 
     pub fn add_message(&mut self, message: String) {
@@ -149,6 +599,63 @@ impl State {
         self.paired_alignments.push(None);
     }
 
+    /// View data with no data loaded yet, one for each alignment and signal track.
+    pub fn empty_view_data(&self) -> ViewData {
+        ViewData {
+            alignments: self
+                .alignments
+                .iter()
+                .map(|_| Alignment::default())
+                .collect(),
+            paired_alignments: self.paired_alignments.iter().map(|_| None).collect(),
+            coverage_overview: None,
+            signals: self
+                .signals
+                .iter()
+                .map(|_| BigWigTrack::default())
+                .collect(),
+            track: Track::default(),
+            sequence: Sequence::default(),
+        }
+    }
+
+    /// Swap the data loaded for the view with data. Variant and BED tracks are loaded whole and stay shared.
+    /// Alignment options changed since data was loaded are not applied; see `reapply_alignment_options`.
+    pub fn swap_view_data(&mut self, data: &mut ViewData) {
+        std::mem::swap(&mut self.alignments, &mut data.alignments);
+        std::mem::swap(&mut self.paired_alignments, &mut data.paired_alignments);
+        std::mem::swap(&mut self.coverage_overview, &mut data.coverage_overview);
+        std::mem::swap(&mut self.signals, &mut data.signals);
+        std::mem::swap(&mut self.track, &mut data.track);
+        std::mem::swap(&mut self.sequence, &mut data.sequence);
+    }
+
+    /// Drop the data loaded from alignment, variant, BED, and bigWig files so that the next load re-reads them, e.g.
+    /// after the files are reloaded from disk. Reads pinned to the status bar are unpinned.
+    pub fn clear_file_data(&mut self) {
+        self.alignments.fill_with(Alignment::default);
+        self.paired_alignments.fill_with(|| None);
+        self.coverage_overview = None;
+        self.variants.fill_with(VariantTrack::default);
+        self.variant_loaded.fill(false);
+        self.bed_intervals.fill_with(BedTrack::default);
+        self.bed_loaded.fill(false);
+        self.signals.fill_with(BigWigTrack::default);
+        self.pinned_read = None;
+        self.split_read = None;
+    }
+
+    pub fn load_coverage_overview(
+        &mut self,
+        contig_index: usize,
+        alignment_repository: &AlignmentRepositoryEnum,
+    ) -> Result<&mut Self, TGVError> {
+        log::debug!("Loading coverage overview: contig_index={}", contig_index);
+        self.coverage_overview =
+            alignment_repository.read_coverage_overview(contig_index, &self.contig_header)?;
+        Ok(self)
+    }
+
     pub async fn load_alignment_data(
         &mut self,
         index: usize,
@@ -165,7 +672,13 @@ impl State {
             region,
         );
         let alignment = match alignment_repository
-            .read_alignment(region, &self.sequence, &self.contig_header)
+            .read_alignment(
+                region,
+                &self.sequence,
+                &self.contig_header,
+                &self.shown_read_categories,
+                self.max_reads,
+            )
             .await
         {
             Ok(alignment) => alignment,
@@ -245,6 +758,90 @@ impl State {
         Ok(self)
     }
 
+    /// Names of gene tracks available for the reference.
+    pub async fn available_track_names(
+        &self,
+        track_service: &mut TrackServiceEnum,
+    ) -> Result<Vec<String>, TGVError> {
+        track_service
+            .get_available_track_names(&self.reference)
+            .await
+    }
+
+    /// Use track_name as the gene track. Loaded gene data are dropped so that the next load refetches them.
+    pub async fn use_track(
+        &mut self,
+        track_name: &str,
+        track_service: &mut TrackServiceEnum,
+    ) -> Result<&mut Self, TGVError> {
+        let available_track_names = self.available_track_names(track_service).await?;
+        if !available_track_names.iter().any(|name| name == track_name) {
+            return Err(TGVError::ValueError(format!(
+                "Track {track_name} is not available for {}. Use :track list to see available tracks.",
+                self.reference
+            )));
+        }
+
+        log::info!("Switching gene track: track={}", track_name);
+        track_service.set_preferred_track_name(track_name.to_string());
+        self.track = Track::<Gene>::default();
+
+        Ok(self)
+    }
+
+    /// Genetic code for translating the contig at focus.
+    pub fn genetic_code(&self, focus: &Focus) -> Result<GeneticCode, TGVError> {
+        match self.genetic_code {
+            Some(genetic_code) => Ok(genetic_code),
+            None => self
+                .contig_header
+                .try_get(focus.contig_index)
+                .map(GeneticCode::for_contig),
+        }
+    }
+
+    /// Forward-strand codon starting at focus and its translation.
+    pub fn codon_at(&self, focus: &Focus) -> Result<(Vec<u8>, Option<u8>), TGVError> {
+        let codon = (focus.position..focus.position + 3)
+            .map(|position| self.sequence.base_at(position))
+            .collect::<Option<Vec<u8>>>()
+            .filter(|_| self.sequence.contig_index == focus.contig_index)
+            .ok_or(TGVError::StateError(
+                "Sequence is not loaded at the cursor. Zoom in to load the sequence.".to_string(),
+            ))?;
+        let amino_acid = self.genetic_code(focus)?.translate(&codon);
+
+        Ok((codon, amino_acid))
+    }
+
+    /// FASTA record of the reference sequence in the region, read on the strand. The region is clipped at the contig
+    /// end. The header is `contig:start-end`, followed by `(-)` on the reverse strand.
+    pub fn reference_fasta(&self, region: &Region, strand: &Strand) -> Result<String, TGVError> {
+        if self.reference == Reference::NoReference {
+            return Err(TGVError::StateError(
+                "No reference is loaded. Provide a reference with -g.".to_string(),
+            ));
+        }
+
+        let contig = self.contig_header.try_get(region.contig_index())?;
+        let start = region.start();
+        let end = contig
+            .length
+            .map_or(region.end(), |length| u64::min(region.end(), length));
+        let sequence = Some(&self.sequence)
+            .filter(|sequence| sequence.contig_index == region.contig_index())
+            .and_then(|sequence| sequence.stranded_sequence(start, end, strand))
+            .ok_or(TGVError::StateError(
+                "Sequence is not loaded in the view.".to_string(),
+            ))?;
+
+        let header = match strand {
+            Strand::Forward => format!("{}:{}-{}", contig.name, start, end),
+            Strand::Reverse => format!("{}:{}-{}(-)", contig.name, start, end),
+        };
+        Ok(format_fasta(&header, &sequence))
+    }
+
     pub async fn load_sequence_data(
         &mut self,
         region: &Region,
@@ -394,6 +991,76 @@ impl State {
         Ok(self)
     }
 
+    pub fn add_signal_track(&mut self) {
+        self.signals.push(BigWigTrack::default());
+    }
+
+    pub async fn load_signal_data(
+        &mut self,
+        index: usize,
+        region: &Region,
+        bigwig_repository: &mut BigWigRepository,
+    ) -> Result<&mut Self, TGVError> {
+        let started = Instant::now();
+        log::debug!("Loading bigWig data: track={} region={:?}", index, region);
+        let signal = match bigwig_repository.read_signal(region, &self.contig_header) {
+            Ok(signal) => signal,
+            Err(e) => {
+                log::warn!(
+                    "Failed to load bigWig data: track={} region={:?} elapsed_ms={} error={e}",
+                    index,
+                    region,
+                    started.elapsed().as_millis(),
+                );
+                return Err(e);
+            }
+        };
+        let interval_count = signal.intervals.len();
+        let Some(signal_track) = self.signals.get_mut(index) else {
+            let e = TGVError::StateError(format!("bigWig index out of bounds: {index}"));
+            log::warn!(
+                "Failed to store bigWig data: track={} region={:?} elapsed_ms={} error={e}",
+                index,
+                region,
+                started.elapsed().as_millis(),
+            );
+            return Err(e);
+        };
+        *signal_track = signal;
+        log::debug!(
+            "Loaded bigWig data: track={} region={:?} intervals={} elapsed_ms={}",
+            index,
+            region,
+            interval_count,
+            started.elapsed().as_millis(),
+        );
+        Ok(self)
+    }
+
+    /// Load the assembly gaps of a contig from the gap table of the track service. Without a gap table, gaps are
+    /// detected as runs of Ns in the loaded sequence.
+    pub async fn load_gaps(
+        &mut self,
+        contig_index: usize,
+        repository: &mut Repository,
+    ) -> Result<&mut Self, TGVError> {
+        let gaps = match repository.track_service.as_mut() {
+            Some(track_service) => {
+                track_service
+                    .get_gaps(&self.reference, contig_index, &self.contig_header)
+                    .await?
+            }
+            None => None,
+        };
+        log::debug!(
+            "Loaded gaps: contig_index={} gap_table_rows={:?}",
+            contig_index,
+            gaps.as_ref().map(Vec::len)
+        );
+        self.gaps = Some(GapTrack { contig_index, gaps });
+        Ok(self)
+    }
+
     pub async fn ensure_complete_cytoband_data(
         &mut self,
         region: &Region,
@@ -415,47 +1082,423 @@ impl State {
             Ok(false)
         }
     }
-}
 
-impl State {
-    /// Main function to route state message handling.
-    pub fn set_alignment_options(
-        &mut self,
-        index: usize,
+    /// Gene covering the focus at distance 0, or else the nearest gene on the contig and its distance in bp. None if
+    /// the contig has no genes.
+    pub async fn nearest_gene(
+        &self,
         focus: &Focus,
-        options: Vec<AlignmentDisplayOption>,
-    ) -> Result<(), TGVError> {
-        let options = options
-            .into_iter()
-            .map(|option| match option {
-                AlignmentDisplayOption::Filter(AlignmentFilter::BaseAtCurrentPosition(base)) => {
-                    AlignmentDisplayOption::Filter(AlignmentFilter::Base(focus.position, base))
-                }
+        repository: &mut Repository,
+    ) -> Result<Option<(Gene, u64)>, TGVError> {
+        let track_service = repository.track_service_checked()?;
+        if let Some(gene) = track_service
+            .query_gene_covering(
+                &self.reference,
+                focus.contig_index,
+                focus.position,
+                &self.contig_header,
+            )
+            .await?
+        {
+            return Ok(Some((gene, 0)));
+        }
 
-                AlignmentDisplayOption::Filter(AlignmentFilter::BaseAtCurrentPositionSoftClip) => {
-                    AlignmentDisplayOption::Filter(AlignmentFilter::BaseSoftclip(focus.position))
-                }
+        // Either side may have no genes.
+        let before = track_service
+            .query_k_genes_before(
+                &self.reference,
+                focus.contig_index,
+                focus.position,
+                1,
+                &self.contig_header,
+            )
+            .await
+            .ok()
+            .filter(|gene| gene.contig_index == focus.contig_index && gene.end() < focus.position)
+            .map(|gene| {
+                let distance = focus.position - gene.end();
+                (gene, distance)
+            });
+        let after = track_service
+            .query_k_genes_after(
+                &self.reference,
+                focus.contig_index,
+                focus.position,
+                1,
+                &self.contig_header,
+            )
+            .await
+            .ok()
+            .filter(|gene| gene.contig_index == focus.contig_index && gene.start() > focus.position)
+            .map(|gene| {
+                let distance = gene.start() - focus.position;
+                (gene, distance)
+            });
 
-                AlignmentDisplayOption::Sort(sort) => AlignmentDisplayOption::Sort(
-                    resolve_alignment_sort_current_position(sort, focus.position),
-                ),
+        Ok([before, after]
+            .into_iter()
+            .flatten()
+            .min_by_key(|(_, distance)| *distance))
+    }
+}
 
-                option => option,
-            })
-            .collect_vec();
+impl State {
+    /// Show reads of every hidden category.
+    pub fn show_all_reads(&mut self) {
+        self.set_shown_read_categories(HiddenReadCategory::all());
+    }
 
-        self.alignment_options[index] = options.clone();
+    /// Show reads of a hidden category. Other categories are left as they are.
+    pub fn show_hidden_reads(&mut self, category: HiddenReadCategory) {
+        let mut shown_read_categories = self.shown_read_categories.clone();
+        shown_read_categories.insert(category);
+        self.set_shown_read_categories(shown_read_categories);
+    }
 
-        let view_as_pairs = options.contains(&AlignmentDisplayOption::ViewAsPairs);
-        let mut applied_sorts = Vec::new();
+    /// Hide reads of a category. Other categories are left as they are.
+    pub fn hide_reads(&mut self, category: HiddenReadCategory) {
+        let mut shown_read_categories = self.shown_read_categories.clone();
+        shown_read_categories.remove(&category);
+        self.set_shown_read_categories(shown_read_categories);
+    }
 
-        options
-            .iter()
-            .cloned()
-            .try_for_each(|option| match option {
-                AlignmentDisplayOption::Filter(filter) => {
-                    self.alignments[index].filter(filter, &self.sequence)
-                }
+    /// Loaded alignments are cleared so that they are re-read. No-op if the categories are unchanged.
+    fn set_shown_read_categories(&mut self, shown_read_categories: BTreeSet<HiddenReadCategory>) {
+        if self.shown_read_categories == shown_read_categories {
+            return;
+        }
+        self.shown_read_categories = shown_read_categories;
+        for alignment in self.alignments.iter_mut() {
+            *alignment = Alignment::default();
+        }
+    }
+
+    /// Shown variants overlapping the focus, with the index of their variant track.
+    pub fn variants_at(&self, focus: &Focus) -> Result<Vec<(usize, &Variant)>, TGVError> {
+        let mut variants = Vec::new();
+        for (index, variant_track) in self.variants.iter().enumerate() {
+            for variant in
+                variant_track.overlapping(focus.contig_index, focus.position, focus.position)?
+            {
+                if self.variant_filter.passes_variant(variant) {
+                    variants.push((index, variant));
+                }
+            }
+        }
+
+        if variants.is_empty() {
+            return Err(TGVError::StateError("No variant at the cursor".to_string()));
+        }
+        Ok(variants)
+    }
+
+    /// Alternate allele of the variant nearest to focus, within max_distance bases.
+    pub fn variant_allele_near(
+        &self,
+        focus: &Focus,
+        max_distance: u64,
+    ) -> Result<VariantAllele, TGVError> {
+        let left = focus.position.saturating_sub(max_distance);
+        let right = focus.position.saturating_add(max_distance);
+
+        let mut nearest: Option<(u64, &Variant)> = None;
+        for variant_track in self.variants.iter() {
+            for variant in variant_track
+                .overlapping(focus.contig_index, left, right)?
+                .into_iter()
+                .filter(|variant| self.variant_filter.passes_variant(variant))
+            {
+                let distance = if focus.position < variant.start() {
+                    variant.start() - focus.position
+                } else {
+                    focus.position.saturating_sub(variant.end())
+                };
+                if nearest.is_none_or(|(nearest_distance, _)| distance < nearest_distance) {
+                    nearest = Some((distance, variant));
+                }
+            }
+        }
+
+        let (_, variant) = nearest.ok_or(TGVError::StateError(
+            "No variant near the cursor".to_string(),
+        ))?;
+        variant.alternate_allele()
+    }
+
+    /// Shown reads of an alignment track overlapping the region. Reads hidden by filters are skipped.
+    pub fn shown_reads_overlapping(
+        &self,
+        index: usize,
+        region: &Region,
+    ) -> Result<Vec<&AlignedRead>, TGVError> {
+        let alignment = self
+            .alignments
+            .get(index)
+            .ok_or(TGVError::StateError("No alignments are loaded".to_string()))?;
+        if !alignment.has_complete_data(region) {
+            return Err(TGVError::StateError(
+                "Reads are not loaded in the view. Zoom in to load reads.".to_string(),
+            ));
+        }
+
+        Ok(alignment.shown_reads_overlapping(region))
+    }
+
+    /// Coverage of an alignment track in the region as bedGraph, in bins of bin_size bases. See
+    /// [`Alignment::coverage_bedgraph`].
+    pub fn coverage_bedgraph(
+        &self,
+        index: usize,
+        region: &Region,
+        bin_size: u64,
+    ) -> Result<String, TGVError> {
+        let alignment = self
+            .alignments
+            .get(index)
+            .ok_or(TGVError::StateError("No alignments are loaded".to_string()))?;
+        if !alignment.has_complete_data(region) {
+            return Err(TGVError::StateError(
+                "Reads are not loaded in the view. Zoom in to load reads.".to_string(),
+            ));
+        }
+        let contig = self.contig_header.try_get(region.contig_index())?;
+        let right = match contig.length {
+            Some(length) => u64::min(region.end(), length),
+            None => region.end(),
+        };
+
+        Ok(alignment.coverage_bedgraph(&contig.name, region.start(), right, bin_size))
+    }
+
+    /// Insertion and deletion lengths of the shown reads in the region in all alignment tracks. Reads hidden by filters
+    /// are not counted, so that the histogram matches the view.
+    pub fn indel_histogram(&self, region: &Region) -> Result<IndelHistogram, TGVError> {
+        if self.alignments.is_empty() {
+            return Err(TGVError::StateError("No alignments are loaded".to_string()));
+        }
+
+        let mut histogram = IndelHistogram::default();
+        for alignment in self.alignments.iter() {
+            if !alignment.has_complete_data(region) {
+                return Err(TGVError::StateError(
+                    "Reads are not loaded in the view. Zoom in to load reads.".to_string(),
+                ));
+            }
+            for read in alignment.shown_reads_overlapping(region) {
+                histogram.add_read(read, region);
+            }
+        }
+
+        if histogram.is_empty() {
+            return Err(TGVError::StateError(
+                "No insertions or deletions in the view".to_string(),
+            ));
+        }
+
+        Ok(histogram)
+    }
+
+    /// Inserted sequences anchored at focus in all alignment tracks, with read counts. The most common is first.
+    pub fn insertions_at(&mut self, focus: &Focus) -> Result<Vec<(Vec<u8>, usize)>, TGVError> {
+        let mut counts = HashMap::<Vec<u8>, usize>::new();
+        for alignment in self.alignments.iter_mut() {
+            if alignment.contig_index != focus.contig_index {
+                continue;
+            }
+            for (bases, count) in alignment.insertions_at(focus.position, &self.sequence)? {
+                *counts.entry(bases).or_default() += count;
+            }
+        }
+
+        if counts.is_empty() {
+            return Err(TGVError::StateError(
+                "No insertion at the cursor".to_string(),
+            ));
+        }
+
+        Ok(counts
+            .into_iter()
+            .sorted_by(|(bases_1, count_1), (bases_2, count_2)| {
+                count_2.cmp(count_1).then_with(|| bases_1.cmp(bases_2))
+            })
+            .collect())
+    }
+
+    /// Apply options on top of the current ones. New filters are and-ed with the current filter. Other options replace
+    /// the current option of the same kind, e.g. a new sort replaces the current sort and keeps the color.
+    pub fn add_alignment_options(
+        &mut self,
+        index: usize,
+        focus: &Focus,
+        options: Vec<AlignmentDisplayOption>,
+    ) -> Result<(), TGVError> {
+        let current_options = self.alignment_options[index]
+            .iter()
+            .filter(|current| {
+                matches!(current, AlignmentDisplayOption::Filter(_))
+                    || !options.iter().any(|option| {
+                        std::mem::discriminant(*current) == std::mem::discriminant(option)
+                    })
+            })
+            .cloned()
+            .collect_vec();
+        let options = current_options.into_iter().chain(options).collect_vec();
+        self.set_alignment_options(index, focus, options)
+    }
+
+    /// Remove filters and keep other options.
+    pub fn clear_alignment_filter(&mut self, index: usize, focus: &Focus) -> Result<(), TGVError> {
+        let options = self.alignment_options[index]
+            .iter()
+            .filter(|option| !matches!(option, AlignmentDisplayOption::Filter(_)))
+            .cloned()
+            .collect_vec();
+        self.set_alignment_options(index, focus, options)
+    }
+
+    /// Switch between the paired and linear views and keep other options.
+    pub fn set_view_as_pairs(
+        &mut self,
+        index: usize,
+        focus: &Focus,
+        view_as_pairs: bool,
+    ) -> Result<(), TGVError> {
+        let mut options = self.alignment_options[index]
+            .iter()
+            .filter(|option| **option != AlignmentDisplayOption::ViewAsPairs)
+            .cloned()
+            .collect_vec();
+        if view_as_pairs {
+            options.push(AlignmentDisplayOption::ViewAsPairs);
+        }
+        self.set_alignment_options(index, focus, options)
+    }
+
+    /// Replace the minimum MAPQ filter and keep other options. 0 removes it.
+    pub fn set_min_mapq(
+        &mut self,
+        index: usize,
+        focus: &Focus,
+        mapping_quality: u8,
+    ) -> Result<(), TGVError> {
+        let mut options = self.alignment_options[index]
+            .iter()
+            .cloned()
+            .filter_map(|option| match option {
+                AlignmentDisplayOption::Filter(filter) => match filter.without_min_mapq() {
+                    AlignmentFilter::Default => None,
+                    filter => Some(AlignmentDisplayOption::Filter(filter)),
+                },
+                option => Some(option),
+            })
+            .collect_vec();
+        if mapping_quality > 0 {
+            options.push(AlignmentDisplayOption::Filter(AlignmentFilter::MinMapq(
+                mapping_quality,
+            )));
+        }
+        self.set_alignment_options(index, focus, options)
+    }
+
+    /// Replace the isolated allele filter and keep other options. None removes it.
+    pub fn set_isolated_allele(
+        &mut self,
+        index: usize,
+        focus: &Focus,
+        filter: Option<AlignmentFilter>,
+    ) -> Result<(), TGVError> {
+        let mut options = self.alignment_options[index]
+            .iter()
+            .cloned()
+            .filter_map(|option| match option {
+                AlignmentDisplayOption::Filter(filter) => match filter.without_allele() {
+                    AlignmentFilter::Default => None,
+                    filter => Some(AlignmentDisplayOption::Filter(filter)),
+                },
+                option => Some(option),
+            })
+            .collect_vec();
+        if let Some(filter) = filter {
+            options.push(AlignmentDisplayOption::Filter(filter));
+        }
+        self.set_alignment_options(index, focus, options)
+    }
+
+    /// Main function to route state message handling.
+    pub fn set_alignment_options(
+        &mut self,
+        index: usize,
+        focus: &Focus,
+        options: Vec<AlignmentDisplayOption>,
+    ) -> Result<(), TGVError> {
+        let options = options
+            .into_iter()
+            .map(|option| match option {
+                AlignmentDisplayOption::Filter(AlignmentFilter::BaseAtCurrentPosition(base)) => {
+                    AlignmentDisplayOption::Filter(AlignmentFilter::Base(focus.position, base))
+                }
+
+                AlignmentDisplayOption::Filter(AlignmentFilter::BaseAtCurrentPositionSoftClip) => {
+                    AlignmentDisplayOption::Filter(AlignmentFilter::BaseSoftclip(focus.position))
+                }
+
+                AlignmentDisplayOption::Sort(sort) => AlignmentDisplayOption::Sort(
+                    resolve_alignment_sort_current_position(sort, focus.position),
+                ),
+
+                option => option,
+            })
+            .collect_vec();
+
+        // Filters compose: a read is shown only if it passes all of them.
+        let (filters, mut options): (Vec<_>, Vec<_>) = options
+            .into_iter()
+            .partition(|option| matches!(option, AlignmentDisplayOption::Filter(_)));
+        let filter = filters
+            .into_iter()
+            .filter_map(|option| match option {
+                AlignmentDisplayOption::Filter(filter) => Some(filter),
+                _ => None,
+            })
+            .fold(AlignmentFilter::Default, AlignmentFilter::and);
+        if filter != AlignmentFilter::Default {
+            options.insert(0, AlignmentDisplayOption::Filter(filter.clone()));
+        }
+
+        self.alignment_options[index] = options.clone();
+
+        self.alignments[index].downsample_depth = self.downsample_depth();
+        self.alignments[index].complement_bases = self.revcomp;
+        self.alignments[index].set_min_mismatch_quality(self.min_mismatch_quality);
+        self.alignments[index].set_aggregate_modifications(self.show_modification_pileup)?;
+        self.alignments[index].group = options
+            .iter()
+            .find_map(|option| match option {
+                AlignmentDisplayOption::Group(group) => Some(group.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        // Always re-apply so that cleared filters show all reads again. Reads are stacked in groups.
+        self.alignments[index].filter(filter, &self.sequence)?;
+        self.alignments[index].color = options
+            .iter()
+            .find_map(|option| match option {
+                AlignmentDisplayOption::Color(color) => Some(color.clone()),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let view_as_pairs = options.contains(&AlignmentDisplayOption::ViewAsPairs);
+        let mut applied_sorts = Vec::new();
+
+        options
+            .iter()
+            .cloned()
+            .try_for_each(|option| match option {
+                AlignmentDisplayOption::Filter(_)
+                | AlignmentDisplayOption::Color(_)
+                | AlignmentDisplayOption::Group(_) => Ok(()),
 
                 AlignmentDisplayOption::Sort(sort) => {
                     match self.alignments[index].sort(sort.clone()) {
@@ -471,6 +1514,7 @@ impl State {
 
         if view_as_pairs {
             let mut paired_alignment = PairedAlignment::new(&self.alignments[index])?;
+            paired_alignment.insert_range = self.insert_range;
             for sort in applied_sorts {
                 match paired_alignment.sort(&self.alignments[index], sort) {
                     Ok(()) => {}
@@ -486,6 +1530,77 @@ impl State {
         Ok(())
     }
 
+    /// Show the reference and read bases complemented, or not.
+    pub fn set_revcomp(&mut self, revcomp: bool) {
+        self.revcomp = revcomp;
+        for alignment in self.alignments.iter_mut() {
+            alignment.complement_bases = revcomp;
+        }
+    }
+
+    /// Show mismatched read bases with a quality score below the threshold as matches. 0 shows all mismatches.
+    pub fn set_min_mismatch_quality(&mut self, min_mismatch_quality: u8) {
+        self.min_mismatch_quality = min_mismatch_quality;
+        for alignment in self.alignments.iter_mut() {
+            alignment.set_min_mismatch_quality(min_mismatch_quality);
+        }
+        for paired_alignment in self.paired_alignments.iter_mut().flatten() {
+            paired_alignment.clear_rendering_contexts();
+        }
+    }
+
+    /// Show the base modification pileup, or not. Base modifications are aggregated only while it is shown.
+    pub fn set_show_modification_pileup(&mut self, shown: bool) -> Result<(), TGVError> {
+        self.show_modification_pileup = shown;
+        for alignment in self.alignments.iter_mut() {
+            alignment.set_aggregate_modifications(shown)?;
+        }
+        Ok(())
+    }
+
+    /// Displayed reads per column. None displays all reads.
+    pub fn downsample_depth(&self) -> Option<usize> {
+        match self.downsample {
+            Downsample::Auto => Some(self.downsample_auto_depth),
+            Downsample::Depth(depth) => Some(depth),
+            Downsample::Off => None,
+        }
+    }
+
+    /// Set the downsampling cap and re-stack the loaded alignments.
+    pub fn set_downsample(
+        &mut self,
+        downsample: Downsample,
+        focus: &Focus,
+    ) -> Result<(), TGVError> {
+        self.downsample = downsample;
+        self.reapply_alignment_options(focus)
+    }
+
+    /// Set the depth of `Downsample::Auto`. Loaded alignments are re-stacked if the depth is in use and changed.
+    pub fn set_downsample_auto_depth(
+        &mut self,
+        depth: usize,
+        focus: &Focus,
+    ) -> Result<(), TGVError> {
+        if depth == self.downsample_auto_depth {
+            return Ok(());
+        }
+        self.downsample_auto_depth = depth;
+        if self.downsample == Downsample::Auto {
+            self.reapply_alignment_options(focus)?;
+        }
+        Ok(())
+    }
+
+    /// Re-apply the alignment options to the loaded alignments.
+    pub fn reapply_alignment_options(&mut self, focus: &Focus) -> Result<(), TGVError> {
+        for index in 0..self.alignments.len() {
+            self.set_alignment_options(index, focus, self.alignment_options[index].clone())?;
+        }
+        Ok(())
+    }
+
     //Self::get_data_requirements(state, repository)
 }
 
@@ -506,7 +1621,7 @@ fn resolve_alignment_sort_current_position(sort: AlignmentSort, position: u64) -
 // Movement handling
 impl State {
     pub async fn next_genes_start(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -533,7 +1648,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let gene = match gene {
+            Ok(gene) if gene.start() > focus.position => gene,
+            gene => match self
+                .wrapped_gene(focus.contig_index, repository, true)
+                .await?
+            {
+                Some(gene) => gene,
+                None => gene?,
+            },
+        };
 
         Ok(Focus {
             contig_index: gene.contig_index,
@@ -542,7 +1667,7 @@ impl State {
     }
 
     pub async fn next_genes_end(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -568,7 +1693,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let gene = match gene {
+            Ok(gene) if gene.end() + 1 > focus.position => gene,
+            gene => match self
+                .wrapped_gene(focus.contig_index, repository, true)
+                .await?
+            {
+                Some(gene) => gene,
+                None => gene?,
+            },
+        };
 
         Ok(Focus {
             contig_index: gene.contig_index,
@@ -577,7 +1712,7 @@ impl State {
     }
 
     pub async fn previous_genes_start(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -603,7 +1738,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let gene = match gene {
+            Ok(gene) if gene.start() - 1 < focus.position => gene,
+            gene => match self
+                .wrapped_gene(focus.contig_index, repository, false)
+                .await?
+            {
+                Some(gene) => gene,
+                None => gene?,
+            },
+        };
 
         Ok(Focus {
             contig_index: gene.contig_index,
@@ -612,7 +1757,7 @@ impl State {
     }
 
     pub async fn previous_genes_end(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -638,7 +1783,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let gene = match gene {
+            Ok(gene) if gene.end() - 1 < focus.position => gene,
+            gene => match self
+                .wrapped_gene(focus.contig_index, repository, false)
+                .await?
+            {
+                Some(gene) => gene,
+                None => gene?,
+            },
+        };
 
         Ok(Focus {
             contig_index: gene.contig_index,
@@ -647,7 +1802,7 @@ impl State {
     }
 
     pub async fn next_exons_start(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -673,7 +1828,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let exon = match exon {
+            Ok(exon) if exon.start() + 1 > focus.position => exon,
+            exon => match self
+                .wrapped_exon(focus.contig_index, repository, true)
+                .await?
+            {
+                Some(exon) => exon,
+                None => exon?,
+            },
+        };
 
         Ok(Focus {
             contig_index: exon.contig_index,
@@ -682,7 +1847,7 @@ impl State {
     }
 
     pub async fn next_exons_end(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -708,7 +1873,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let exon = match exon {
+            Ok(exon) if exon.end() + 1 > focus.position => exon,
+            exon => match self
+                .wrapped_exon(focus.contig_index, repository, true)
+                .await?
+            {
+                Some(exon) => exon,
+                None => exon?,
+            },
+        };
 
         Ok(Focus {
             contig_index: exon.contig_index,
@@ -717,7 +1892,7 @@ impl State {
     }
 
     pub async fn previous_exons_start(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -743,7 +1918,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let exon = match exon {
+            Ok(exon) if exon.start() - 1 < focus.position => exon,
+            exon => match self
+                .wrapped_exon(focus.contig_index, repository, false)
+                .await?
+            {
+                Some(exon) => exon,
+                None => exon?,
+            },
+        };
 
         Ok(Focus {
             contig_index: exon.contig_index,
@@ -752,7 +1937,7 @@ impl State {
     }
 
     pub async fn previous_exons_end(
-        &self,
+        &mut self,
         focus: Focus,
         repository: &mut Repository,
         n: usize,
@@ -779,7 +1964,17 @@ impl State {
                 n,
                 &self.contig_header,
             )
-            .await?;
+            .await;
+        let exon = match exon {
+            Ok(exon) if exon.end() - 1 < focus.position => exon,
+            exon => match self
+                .wrapped_exon(focus.contig_index, repository, false)
+                .await?
+            {
+                Some(exon) => exon,
+                None => exon?,
+            },
+        };
 
         Ok(Focus {
             contig_index: exon.contig_index,
@@ -787,6 +1982,122 @@ impl State {
         })
     }
 
+    /// Contigs to search when gene or exon navigation runs past the end of a contig: the following (forward) or
+    /// preceding contigs in the reference order, wrapping around to the contig itself.
+    fn wrapped_contig_indexes(&self, contig_index: usize, forward: bool) -> Vec<usize> {
+        let n_contigs = self.contig_header.contigs.len();
+        (1..=n_contigs)
+            .map(|offset| match forward {
+                true => (contig_index + offset) % n_contigs,
+                false => (contig_index + n_contigs - offset) % n_contigs,
+            })
+            .collect()
+    }
+
+    /// Note that navigation continued past the last (forward) or first feature of a contig.
+    fn add_wrap_message(
+        &mut self,
+        from: usize,
+        to: usize,
+        forward: bool,
+        feature: &str,
+    ) -> Result<(), TGVError> {
+        let (edge, other_edge) = match forward {
+            true => ("last", "first"),
+            false => ("first", "last"),
+        };
+        let from_name = self.contig_header.try_get(from)?.name.clone();
+        let message = if from == to {
+            format!(
+                "Past the {edge} {feature} of {from_name}, wrapped to the {other_edge} {feature}"
+            )
+        } else {
+            format!(
+                "Past the {edge} {feature} of {from_name}, moved to {}",
+                self.contig_header.try_get(to)?.name
+            )
+        };
+        self.add_message(message);
+        Ok(())
+    }
+
+    /// Last coordinate to search for the last feature of a contig.
+    fn contig_search_end(&self, contig_index: usize) -> u64 {
+        self.contig_header.contigs[contig_index]
+            .length
+            .map_or(u32::MAX as u64, |length| length + 1)
+    }
+
+    /// First (forward) or last gene of the next contig with genes in that direction. None if no contig has genes.
+    async fn wrapped_gene(
+        &mut self,
+        contig_index: usize,
+        repository: &mut Repository,
+        forward: bool,
+    ) -> Result<Option<Gene>, TGVError> {
+        for index in self.wrapped_contig_indexes(contig_index, forward) {
+            let track_service = repository.track_service_checked()?;
+            let gene = match forward {
+                true => {
+                    track_service
+                        .query_k_genes_after(&self.reference, index, 0, 1, &self.contig_header)
+                        .await
+                }
+                false => {
+                    track_service
+                        .query_k_genes_before(
+                            &self.reference,
+                            index,
+                            self.contig_search_end(index),
+                            1,
+                            &self.contig_header,
+                        )
+                        .await
+                }
+            };
+            if let Ok(gene) = gene {
+                self.add_wrap_message(contig_index, index, forward, "gene")?;
+                return Ok(Some(gene));
+            }
+        }
+        Ok(None)
+    }
+
+    /// First (forward) or last exon of the next contig with genes in that direction. None if no contig has genes.
+    async fn wrapped_exon(
+        &mut self,
+        contig_index: usize,
+        repository: &mut Repository,
+        forward: bool,
+    ) -> Result<Option<SubGeneFeature>, TGVError> {
+        for index in self.wrapped_contig_indexes(contig_index, forward) {
+            let track_service = repository.track_service_checked()?;
+            let exon = match forward {
+                true => {
+                    track_service
+                        .query_k_exons_after(&self.reference, index, 0, 1, &self.contig_header)
+                        .await
+                }
+                false => {
+                    track_service
+                        .query_k_exons_before(
+                            &self.reference,
+                            index,
+                            self.contig_search_end(index),
+                            1,
+                            &self.contig_header,
+                        )
+                        .await
+                }
+            };
+            if let Ok(exon) = exon {
+                self.add_wrap_message(contig_index, index, forward, "exon")?;
+                return Ok(Some(exon));
+            }
+        }
+        Ok(None)
+    }
+
     pub async fn gene(
         &self,
         repository: &mut Repository,
@@ -802,6 +2113,165 @@ impl State {
             })
     }
 
+    /// Region framing a gene, padded by the frame.
+    pub async fn framed_gene(
+        &self,
+        repository: &mut Repository,
+        gene_name: &str,
+        frame: GeneFrame,
+    ) -> Result<Region, TGVError> {
+        let gene = repository
+            .track_service_checked()?
+            .query_gene_name(&self.reference, gene_name, &self.contig_header)
+            .await?;
+        let contig_length = self.contig_header.try_get(gene.contig_index())?.length;
+        Ok(framed_region(&gene, frame, contig_length))
+    }
+
+    /// Region of a cytoband and its sub-bands, e.g. q21 of 17. The contig's cytoband is loaded if it is not yet.
+    pub async fn framed_band(
+        &mut self,
+        repository: &mut Repository,
+        contig_name: &str,
+        band: &str,
+    ) -> Result<Region, TGVError> {
+        let contig_index = self.contig_header.resolve(contig_name)?;
+        self.ensure_complete_cytoband_data(
+            &Region {
+                focus: Focus {
+                    contig_index,
+                    position: 1,
+                },
+                half_width: 0,
+            },
+            repository,
+        )
+        .await?;
+        let contig = self.contig_header.try_get(contig_index)?;
+        let segment = contig
+            .cytoband
+            .as_ref()
+            .ok_or_else(|| TGVError::ValueError(format!("No cytoband data for {contig_name}")))?
+            .band(band, contig_name)?;
+        Ok(framed_region(&segment, GeneFrame::Flank(0), contig.length))
+    }
+
+    /// Genes and coverage across a whole contig, in at most max_bins bins. Coverage is binned from the coverage
+    /// overview if it is loaded for the contig.
+    pub async fn contig_overview(
+        &self,
+        repository: &mut Repository,
+        contig_index: usize,
+        max_bins: usize,
+    ) -> Result<ContigOverview, TGVError> {
+        let contig_length =
+            self.contig_header
+                .try_get(contig_index)?
+                .length
+                .ok_or(TGVError::StateError(
+                    "Contig length is unknown. Cannot show the contig overview".to_string(),
+                ))?;
+        let genes = match repository.track_service.as_mut() {
+            Some(track_service) => {
+                let region = Region {
+                    focus: Focus {
+                        contig_index,
+                        position: contig_length / 2 + 1,
+                    },
+                    half_width: contig_length / 2,
+                };
+                track_service
+                    .query_genes_overlapping(&self.reference, &region, &self.contig_header)
+                    .await?
+            }
+            None => Vec::new(),
+        };
+        Ok(ContigOverview::new(
+            contig_index,
+            contig_length,
+            max_bins,
+            &genes,
+            self.coverage_overview.as_ref(),
+        ))
+    }
+
+    /// Names of genes containing a pattern, prefix matches first.
+    pub async fn gene_names_matching(
+        &self,
+        repository: &mut Repository,
+        pattern: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, TGVError> {
+        repository
+            .track_service_checked()?
+            .query_gene_names_matching(&self.reference, pattern, limit, &self.contig_header)
+            .await
+    }
+
+    pub async fn gene_residue(
+        &self,
+        repository: &mut Repository,
+        gene_name: &str,
+        residue: u64,
+    ) -> Result<Focus, TGVError> {
+        let gene = repository
+            .track_service_checked()?
+            .query_gene_name(&self.reference, gene_name, &self.contig_header)
+            .await?;
+        Ok(Focus {
+            contig_index: gene.contig_index(),
+            position: gene.residue_position(residue)?,
+        })
+    }
+
+    /// Sorted, deduplicated starts of shown variants in all variant tracks.
+    fn variant_starts(&self) -> Result<Vec<Focus>, TGVError> {
+        let starts = self
+            .variants
+            .iter()
+            .flat_map(|variant_track| variant_track.intervals.iter())
+            .filter(|variant| self.variant_filter.passes_variant(variant))
+            .map(|variant| (variant.contig_index(), variant.start()))
+            .sorted()
+            .dedup()
+            .map(|(contig_index, position)| Focus {
+                contig_index,
+                position,
+            })
+            .collect::<Vec<_>>();
+        if starts.is_empty() {
+            return Err(TGVError::StateError("No variants are loaded".to_string()));
+        }
+        Ok(starts)
+    }
+
+    /// Start of the n-th variant after the focus. Wraps around after the last variant.
+    fn next_variants(&self, focus: Focus, n: usize) -> Result<Focus, TGVError> {
+        if n == 0 {
+            return Ok(focus);
+        }
+
+        let starts = self.variant_starts()?;
+        let after = starts.partition_point(|start| {
+            (start.contig_index, start.position) <= (focus.contig_index, focus.position)
+        });
+        Ok(starts[(after + n - 1) % starts.len()].clone())
+    }
+
+    /// Start of the n-th variant before the focus. Wraps around before the first variant.
+    fn previous_variants(&self, focus: Focus, n: usize) -> Result<Focus, TGVError> {
+        if n == 0 {
+            return Ok(focus);
+        }
+
+        let starts = self.variant_starts()?;
+        let before = starts.partition_point(|start| {
+            (start.contig_index, start.position) < (focus.contig_index, focus.position)
+        });
+        let index = (before as i64 - n as i64).rem_euclid(starts.len() as i64);
+        Ok(starts[index as usize].clone())
+    }
+
     fn next_contig(&self, focus: Focus, n: usize) -> Focus {
         Focus {
             contig_index: self.contig_header.next(focus.contig_index, n),
@@ -818,6 +2288,16 @@ impl State {
     }
 
     pub async fn default_focus(&self, repository: &mut Repository) -> Result<Focus, TGVError> {
+        if let Some(first_region) = self.target_regions.first() {
+            return Ok(first_region);
+        }
+        if let Some(read_span) = &self.read_span {
+            return Ok(Focus {
+                contig_index: read_span.contig_index,
+                position: read_span.start,
+            });
+        }
+
         match self.reference {
             Reference::Hg38 | Reference::Hg19 => {
                 return self.gene(repository, "TP53").await;
@@ -867,7 +2347,6 @@ impl State {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::alignment::AlignedRead;
     use crate::contig_header::ContigHeader;
     use noodles::sam::{
         self,
@@ -958,6 +2437,49 @@ mod tests {
         assert_eq!(state.alignments[0].ys, vec![1, 0]);
     }
 
+    #[test]
+    fn set_min_mapq_replaces_the_threshold_and_keeps_other_options() {
+        let mut state = state_with_alignment(alignment_from_reads(
+            vec![read("a", 12, [(Kind::Match, 1)], b"A")],
+            (1, 100),
+        ));
+        let focus = Focus {
+            contig_index: 0,
+            position: 12,
+        };
+        state
+            .set_alignment_options(
+                0,
+                &focus,
+                vec![
+                    AlignmentDisplayOption::Filter(AlignmentFilter::ReadLengthGE(1)),
+                    AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(12)),
+                ],
+            )
+            .unwrap();
+
+        state.set_min_mapq(0, &focus, 20).unwrap();
+        state.set_min_mapq(0, &focus, 30).unwrap();
+        assert_eq!(
+            state.alignment_options[0],
+            vec![
+                AlignmentDisplayOption::Filter(
+                    AlignmentFilter::ReadLengthGE(1).and(AlignmentFilter::MinMapq(30))
+                ),
+                AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(12)),
+            ]
+        );
+
+        state.set_min_mapq(0, &focus, 0).unwrap();
+        assert_eq!(
+            state.alignment_options[0],
+            vec![
+                AlignmentDisplayOption::Filter(AlignmentFilter::ReadLengthGE(1)),
+                AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(12)),
+            ]
+        );
+    }
+
     #[test]
     fn set_alignment_options_treats_unloaded_base_sort_as_noop() {
         let alignment = alignment_from_reads(
@@ -991,6 +2513,54 @@ mod tests {
         assert_eq!(state.alignments[0].ys, original_ys);
     }
 
+    #[test]
+    fn added_alignment_filters_stack_until_cleared() {
+        let alignment = alignment_from_reads(
+            vec![
+                read("a", 12, [(Kind::Match, 2)], b"AC"),
+                read("b", 12, [(Kind::Match, 2)], b"AG"),
+                read("c", 12, [(Kind::Match, 2)], b"TC"),
+            ],
+            (1, 100),
+        );
+        let mut state = state_with_alignment(alignment);
+        let focus = Focus {
+            contig_index: 0,
+            position: 12,
+        };
+
+        state
+            .add_alignment_options(
+                0,
+                &focus,
+                vec![AlignmentDisplayOption::Filter(
+                    AlignmentFilter::BaseAtCurrentPosition('A'),
+                )],
+            )
+            .unwrap();
+        state
+            .add_alignment_options(
+                0,
+                &focus,
+                vec![AlignmentDisplayOption::Filter(AlignmentFilter::Base(
+                    13, 'C',
+                ))],
+            )
+            .unwrap();
+
+        assert_eq!(
+            state.alignment_options[0],
+            vec![AlignmentDisplayOption::Filter(
+                AlignmentFilter::Base(12, 'A').and(AlignmentFilter::Base(13, 'C'))
+            )]
+        );
+        assert_eq!(state.alignments[0].show_read, vec![true, false, false]);
+
+        state.clear_alignment_filter(0, &focus).unwrap();
+        assert!(state.alignment_options[0].is_empty());
+        assert_eq!(state.alignments[0].show_read, vec![true, true, true]);
+    }
+
     #[test]
     fn set_alignment_options_still_propagates_unsupported_sort_errors() {
         let alignment =
@@ -1011,4 +2581,274 @@ mod tests {
 
         assert!(matches!(error, TGVError::ValueError(_)));
     }
+
+    fn state_with_sequence(reference: Reference, bases: &[u8]) -> State {
+        let mut contig_header = ContigHeader::new(reference.clone());
+        contig_header.update_or_add_contig(
+            "chr1".to_string(),
+            Some(bases.len() as u64),
+            Vec::new(),
+            crate::contig_header::ContigSource::Sequence,
+        );
+        let mut state = State::new(reference, contig_header).unwrap();
+        state.sequence = Sequence {
+            start: 1,
+            sequence: bases.to_vec(),
+            contig_index: 0,
+        };
+        state
+    }
+
+    fn region(position: u64, half_width: u64) -> Region {
+        Region {
+            focus: Focus {
+                contig_index: 0,
+                position,
+            },
+            half_width,
+        }
+    }
+
+    #[test]
+    fn reference_fasta_reads_both_strands() {
+        let state = state_with_sequence(
+            Reference::BYOIndexedFasta("genome.fa".to_string()),
+            b"AACCGGTTAC",
+        );
+
+        assert_eq!(
+            state
+                .reference_fasta(&region(4, 2), &Strand::Forward)
+                .unwrap(),
+            ">chr1:2-6\nACCGG\n"
+        );
+        assert_eq!(
+            state
+                .reference_fasta(&region(4, 2), &Strand::Reverse)
+                .unwrap(),
+            ">chr1:2-6(-)\nCCGGT\n"
+        );
+    }
+
+    #[test]
+    fn reference_fasta_clips_the_window_at_the_contig_end() {
+        let state = state_with_sequence(
+            Reference::BYOIndexedFasta("genome.fa".to_string()),
+            b"AACCGGTTAC",
+        );
+
+        assert_eq!(
+            state
+                .reference_fasta(&region(9, 4), &Strand::Forward)
+                .unwrap(),
+            ">chr1:5-10\nGGTTAC\n"
+        );
+        assert_eq!(
+            state
+                .reference_fasta(&region(9, 4), &Strand::Reverse)
+                .unwrap(),
+            ">chr1:5-10(-)\nGTAACC\n"
+        );
+        // The window also starts past the contig end.
+        assert!(matches!(
+            state.reference_fasta(&region(20, 4), &Strand::Forward),
+            Err(TGVError::StateError(_))
+        ));
+    }
+
+    #[test]
+    fn reference_fasta_requires_a_reference() {
+        let state = state_with_sequence(Reference::NoReference, b"AACCGGTTAC");
+
+        assert_eq!(
+            state
+                .reference_fasta(&region(4, 2), &Strand::Forward)
+                .unwrap_err()
+                .to_string(),
+            "State error: No reference is loaded. Provide a reference with -g."
+        );
+    }
+
+    #[test]
+    fn bookmarks_are_set_overwritten_and_looked_up() {
+        let mut state = state_with_sequence(Reference::NoReference, b"AACCGGTTAC");
+        let focus = |position| Focus {
+            contig_index: 0,
+            position,
+        };
+
+        assert_eq!(state.set_bookmark("b".to_string(), focus(3)), None);
+        assert_eq!(state.set_bookmark("a".to_string(), focus(5)), None);
+        assert_eq!(
+            state.set_bookmark("b".to_string(), focus(7)),
+            Some(focus(3))
+        );
+
+        assert_eq!(state.bookmark("b").unwrap(), focus(7));
+        assert_eq!(state.bookmark_names(), vec!["a", "b"]);
+        assert_eq!(
+            state.bookmark("c").unwrap_err().to_string(),
+            "State error: Unknown bookmark @c. List bookmarks with :marks"
+        );
+    }
+
+    #[tokio::test]
+    async fn gene_names_matching_lists_prefix_matches_first() {
+        use crate::contig_header::ContigSource;
+        use crate::tracks::LocalDbTrackService;
+
+        let reference = Reference::UcscAccession("GCF_000005845.2".to_string());
+        let mut repository = Repository {
+            alignment_repositories: Vec::new(),
+            variant_repositories: Vec::new(),
+            bed_repositories: Vec::new(),
+            bigwig_repositories: Vec::new(),
+            track_service: Some(TrackServiceEnum::LocalDb(
+                LocalDbTrackService::new(
+                    &reference,
+                    concat!(env!("CARGO_MANIFEST_DIR"), "/../tgv/tests/data/cache"),
+                )
+                .await
+                .unwrap(),
+            )),
+            sequence_service: None,
+        };
+        let mut contig_header = ContigHeader::new(reference.clone());
+        contig_header.update_or_add_contig(
+            "NC_000913.3".to_string(),
+            Some(4641652),
+            Vec::new(),
+            ContigSource::Track,
+        );
+        let state = State::new(reference, contig_header).unwrap();
+
+        assert_eq!(
+            state
+                .gene_names_matching(&mut repository, "lac", 6)
+                .await
+                .unwrap(),
+            vec!["lacA", "lacI", "lacY", "lacZ", "alaC", "mlaC"]
+        );
+        assert_eq!(
+            state
+                .gene_names_matching(&mut repository, "THR", 3)
+                .await
+                .unwrap(),
+            vec!["thrA", "thrB", "thrC"]
+        );
+        assert!(
+            state
+                .gene_names_matching(&mut repository, "zzzz", 6)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn use_track_switches_gene_tracks_and_rejects_unknown_tracks() {
+        use crate::contig_header::ContigSource;
+        use crate::tracks::LocalDbTrackService;
+
+        // Copy the E. coli cache and add a refGene track with the genes after 3 kb only.
+        let cache_dir = tempfile::tempdir().unwrap();
+        let db_dir = cache_dir.path().join("GCF_000005845.2");
+        std::fs::create_dir(&db_dir).unwrap();
+        std::fs::copy(
+            concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../tgv/tests/data/cache/GCF_000005845.2/tracks.sqlite"
+            ),
+            db_dir.join("tracks.sqlite"),
+        )
+        .unwrap();
+        let pool = sqlx::SqlitePool::connect(&format!(
+            "sqlite://{}",
+            db_dir.join("tracks.sqlite").display()
+        ))
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE refGene AS SELECT * FROM ncbiGene WHERE txStart > 3000")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let reference = Reference::UcscAccession("GCF_000005845.2".to_string());
+        let mut track_service = TrackServiceEnum::LocalDb(
+            LocalDbTrackService::new(&reference, cache_dir.path().to_str().unwrap())
+                .await
+                .unwrap(),
+        );
+        let mut contig_header = ContigHeader::new(reference.clone());
+        contig_header.update_or_add_contig(
+            "NC_000913.3".to_string(),
+            Some(4641652),
+            Vec::new(),
+            ContigSource::Track,
+        );
+        let mut state = State::new(reference, contig_header).unwrap();
+        let region = Region {
+            focus: Focus {
+                contig_index: 0,
+                position: 5000,
+            },
+            half_width: 5000,
+        };
+
+        state
+            .load_track_data(&region, &mut track_service)
+            .await
+            .unwrap();
+        assert!(state.track.genes().iter().any(|gene| gene.start() < 3000));
+
+        state
+            .use_track("refGene", &mut track_service)
+            .await
+            .unwrap();
+        assert!(state.track.genes().is_empty());
+        state
+            .load_track_data(&region, &mut track_service)
+            .await
+            .unwrap();
+        assert!(!state.track.genes().is_empty());
+        assert!(state.track.genes().iter().all(|gene| gene.start() > 3000));
+
+        assert!(
+            state
+                .use_track("knownGene", &mut track_service)
+                .await
+                .is_err()
+        );
+    }
+
+    #[rstest::rstest]
+    #[case(GeneFrame::Flank(2_000), Some(1_000_000), (8_000, 22_000))]
+    #[case(GeneFrame::Flank(50_000), Some(30_000), (1, 30_000))]
+    #[case(GeneFrame::Percent(150), Some(1_000_000), (7_500, 22_500))]
+    #[case(GeneFrame::Percent(100), Some(1_000_000), (10_000, 20_000))]
+    #[case(GeneFrame::Percent(50), Some(1_000_000), (12_500, 17_500))]
+    #[case(GeneFrame::Percent(1_000), None, (1, 65_000))]
+    fn test_framed_region(
+        #[case] frame: GeneFrame,
+        #[case] contig_length: Option<u64>,
+        #[case] expected: (u64, u64),
+    ) {
+        let gene = Gene {
+            id: "gene1".to_string(),
+            name: "gene1".to_string(),
+            strand: Strand::Forward,
+            contig_index: 2,
+            transcription_start: 10_001,
+            transcription_end: 20_000,
+            cds_start: 10_001,
+            cds_end: 20_000,
+            exon_starts: vec![10_001],
+            exon_ends: vec![20_000],
+            has_exons: true,
+        };
+        let region = framed_region(&gene, frame, contig_length);
+        assert_eq!(region.contig_index(), 2);
+        assert_eq!((region.start(), region.end()), expected);
+    }
 }
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use strum::Display;
 
 use crate::error::TGVError;
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
 pub enum Strand {
     #[strum[to_string = "+"]]
     Forward,
@@ -282,6 +282,8 @@ impl Track<Gene> {
         let mut exons_by_start = BTreeMap::new();
         let mut exons_by_end = BTreeMap::new();
 
+        let mut feature_lookup = HashMap::new();
+
         for (i_gene, gene) in genes.iter().enumerate() {
             if gene.start() < most_left_bound {
                 most_left_bound = gene.start();
@@ -297,6 +299,7 @@ impl Track<Gene> {
 
             features_by_start.insert(gene.start(), i_gene);
             features_by_end.insert(gene.end(), i_gene);
+            feature_lookup.entry(gene.name.clone()).or_insert(i_gene);
         }
 
         Ok(Self {
@@ -310,7 +313,7 @@ impl Track<Gene> {
             data_complete_right_bound: data_complete_bound.1,
             exons_by_start,
             exons_by_end,
-            feature_lookup: HashMap::new(),
+            feature_lookup,
         })
     }
 
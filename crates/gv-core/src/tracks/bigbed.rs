@@ -0,0 +1,487 @@
+use crate::tracks::{BigBedConverter, TrackCache, TrackService};
+use crate::{
+    contig_header::{Contig, ContigHeader},
+    cytoband::Cytoband,
+    error::TGVError,
+    feature::{Gene, SubGeneFeature},
+    intervals::{GenomeInterval, Region},
+    reference::Reference,
+    strand::Strand,
+    track::Track,
+};
+use async_trait::async_trait;
+use bigtools::{BedEntry, BigBedRead, utils::reopen::ReopenableFile};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Instant;
+
+/// Exon coordinates from the BED12 blockSizes and chromStarts fields.
+/// Both input and output are 0-based, half-open, as in the bigBed file.
+/// Return: (exon starts, exon ends)
+pub(crate) fn blocks_to_exons(
+    chrom_start: u64,
+    block_sizes: &str,
+    chrom_starts: &str,
+) -> Result<(Vec<u64>, Vec<u64>), TGVError> {
+    // Example block_sizes, chrom_starts:
+    // 66,
+    // 0,
+    let parse = |s: &str| {
+        s.split(',')
+            .filter(|s| !s.trim().is_empty())
+            .map(|s| {
+                s.trim().parse::<u64>().map_err(|_| {
+                    TGVError::ValueError(format!("Failed to parse BED block field {}", s))
+                })
+            })
+            .collect::<Result<Vec<u64>, TGVError>>()
+    };
+    let block_sizes = parse(block_sizes)?;
+    let chrom_starts = parse(chrom_starts)?;
+
+    if block_sizes.len() != chrom_starts.len() {
+        return Err(TGVError::ValueError(
+            "blockSizes and chromStarts arrays have different lengths".to_string(),
+        ));
+    }
+
+    Ok(chrom_starts
+        .iter()
+        .zip(block_sizes.iter())
+        .map(|(block_start, block_size)| {
+            let exon_start = chrom_start + block_start;
+            (exon_start, exon_start + block_size)
+        })
+        .unzip())
+}
+
+/// Positions of gene fields in the tab-separated rest of a bigBed entry (after chrom, chromStart, and chromEnd).
+#[derive(Debug)]
+struct BigBedGeneColumns {
+    name: usize,
+    strand: usize,
+    cds_start: usize,
+    cds_end: usize,
+
+    /// None for BED6+2 files without exon blocks.
+    blocks: Option<(usize, usize)>,
+
+    /// Gene symbol, if the file has one. Otherwise, genes are named by the name field.
+    name2: Option<usize>,
+}
+
+impl BigBedGeneColumns {
+    /// Field names follow [`BigBedConverter::get_schema`].
+    fn from_field_names(field_names: &[String]) -> Result<Self, TGVError> {
+        let position = |name: &str| field_names.iter().position(|field| field == name);
+        let required = |name: &str| {
+            position(name).ok_or(TGVError::ValueError(format!(
+                "bigBed gene track is missing the {} field",
+                name
+            )))
+        };
+
+        Ok(Self {
+            name: required("name")?,
+            strand: required("strand")?,
+            cds_start: required("cdsStart")?,
+            cds_end: required("cdsEnd")?,
+            blocks: position("blockSizes").zip(position("chromStarts")),
+            name2: position("name2").or(position("geneName2")),
+        })
+    }
+
+    fn to_gene(&self, entry: &BedEntry, contig_index: usize) -> Result<Gene, TGVError> {
+        let fields: Vec<&str> = entry.rest.split('\t').collect();
+        let field = |index: usize| {
+            fields
+                .get(index)
+                .copied()
+                .ok_or(TGVError::ValueError(format!(
+                    "bigBed entry has {} fields, expected more than {}",
+                    fields.len(),
+                    index
+                )))
+        };
+        let coordinate = |index: usize| {
+            field(index)?.trim().parse::<u64>().map_err(|_| {
+                TGVError::ValueError(format!(
+                    "Failed to parse bigBed coordinate {:?}",
+                    fields[index]
+                ))
+            })
+        };
+
+        let (exon_starts, exon_ends) = match self.blocks {
+            Some((block_sizes, chrom_starts)) => blocks_to_exons(
+                entry.start as u64,
+                field(block_sizes)?,
+                field(chrom_starts)?,
+            )?,
+            None => (Vec::new(), Vec::new()),
+        };
+
+        let id = field(self.name)?.to_string();
+        let name = match self.name2.map(field).transpose()? {
+            Some(name2) if !name2.trim().is_empty() => name2.to_string(),
+            _ => id.clone(),
+        };
+
+        // bigBed coordinates are 0-based, half-open.
+        Ok(Gene {
+            id,
+            name,
+            strand: Strand::from_str(field(self.strand)?.to_string())?,
+            contig_index,
+            transcription_start: entry.start as u64 + 1,
+            transcription_end: entry.end as u64,
+            cds_start: coordinate(self.cds_start)? + 1,
+            cds_end: coordinate(self.cds_end)?,
+            has_exons: !exon_starts.is_empty(),
+            exon_starts: exon_starts.iter().map(|start| start + 1).collect(),
+            exon_ends,
+        })
+    }
+}
+
+/// Gene track read directly from a bigBed file.
+/// Regions are queried live from the file. Whole contigs are cached for gene name and gene / exon jump queries.
+pub struct BigBedTrackService {
+    path: String,
+
+    reader: Box<BigBedRead<ReopenableFile>>,
+
+    columns: BigBedGeneColumns,
+
+    /// Contig names in the bigBed file.
+    chrom_names: HashSet<String>,
+
+    cache: TrackCache,
+}
+
+impl std::fmt::Debug for BigBedTrackService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BigBedTrackService")
+            .field("path", &self.path)
+            .field("columns", &self.columns)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BigBedTrackService {
+    pub fn new(path: &str) -> Result<Self, TGVError> {
+        let mut reader = Box::new(BigBedRead::open_file(path)?);
+        let (field_names, _) = BigBedConverter::get_schema(&mut reader)?;
+        let columns = BigBedGeneColumns::from_field_names(&field_names)?;
+        let chrom_names = reader
+            .chroms()
+            .iter()
+            .map(|chrom_info| chrom_info.name.clone())
+            .collect();
+
+        Ok(Self {
+            path: path.to_string(),
+            reader,
+            columns,
+            chrom_names,
+            cache: TrackCache::default(),
+        })
+    }
+
+    /// Track name shown in `:track list`: the file name without the extension.
+    fn track_name(&self) -> String {
+        Path::new(&self.path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or(self.path.clone())
+    }
+
+    /// Name of the contig in the bigBed file. None if the contig has no genes in the file.
+    fn chrom_name(&self, contig: &Contig) -> Option<String> {
+        std::iter::once(&contig.name)
+            .chain(contig.aliases.iter())
+            .find(|name| self.chrom_names.contains(*name))
+            .cloned()
+    }
+
+    /// Genes overlapping [start, end).
+    /// 0-based, half-open.
+    fn query_interval(
+        &mut self,
+        chrom_name: &str,
+        contig_index: usize,
+        start: u32,
+        end: u32,
+    ) -> Result<Vec<Gene>, TGVError> {
+        log::info!(
+            "bigBed query: path={} contig={} contig_index={} start={} end={}",
+            self.path,
+            chrom_name,
+            contig_index,
+            start,
+            end
+        );
+        let started = Instant::now();
+        let entries = self
+            .reader
+            .get_interval(chrom_name, start, end)?
+            .collect::<Result<Vec<BedEntry>, _>>()?;
+        log::info!(
+            "bigBed query result: path={} entries={} elapsed_ms={}",
+            self.path,
+            entries.len(),
+            started.elapsed().as_millis()
+        );
+
+        entries
+            .iter()
+            .map(|entry| self.columns.to_gene(entry, contig_index))
+            .collect()
+    }
+
+    /// Load the whole contig to the cache.
+    fn query_track_if_not_cached(
+        &mut self,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<(), TGVError> {
+        if self.cache.contig_quried(&contig_index) {
+            return Ok(());
+        }
+
+        let contig = contig_header.try_get(contig_index)?;
+        let Some(chrom_name) = self.chrom_name(contig) else {
+            return Err(TGVError::StateError(format!(
+                "Contig {} (index = {}, aliases = {}) does not have track data.",
+                contig.name,
+                contig_index,
+                contig.aliases.join(",")
+            )));
+        };
+        let genes = self.query_interval(&chrom_name, contig_index, 0, u32::MAX)?;
+        self.cache.add_track(
+            contig_index,
+            Track::from_genes(genes, contig_index, (1, u64::MAX))?,
+        );
+        Ok(())
+    }
+
+    fn cached_track(&self, contig_index: usize) -> Result<&Track<Gene>, TGVError> {
+        self.cache
+            .tracks
+            .get(&contig_index)
+            .ok_or(TGVError::IOError(format!(
+                "Track not found for contig {}",
+                contig_index
+            )))
+    }
+}
+
+#[async_trait]
+impl TrackService for BigBedTrackService {
+    async fn close(&mut self) -> Result<(), TGVError> {
+        Ok(())
+    }
+
+    async fn get_all_contigs(&mut self, _reference: &Reference) -> Result<Vec<Contig>, TGVError> {
+        Ok(self
+            .reader
+            .chroms()
+            .iter()
+            .map(|chrom_info| Contig::new(&chrom_info.name, Some(chrom_info.length as u64)))
+            .collect())
+    }
+
+    async fn get_cytoband(
+        &mut self,
+        _reference: &Reference,
+        _contig_index: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Option<Cytoband>, TGVError> {
+        Ok(None)
+    }
+
+    async fn get_preferred_track_name(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Option<String>, TGVError> {
+        Ok(Some(self.track_name()))
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        Ok(vec![self.track_name()])
+    }
+
+    fn set_preferred_track_name(&mut self, _track_name: String) {
+        // The bigBed file is the only track.
+    }
+
+    async fn query_genes_overlapping(
+        &mut self,
+        _reference: &Reference,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<Gene>, TGVError> {
+        let Some(chrom_name) = self.chrom_name(contig_header.try_get(region.contig_index())?)
+        else {
+            return Ok(Vec::new()); // Contig doesn't have track data
+        };
+
+        // Region is 1-based, inclusive.
+        self.query_interval(
+            &chrom_name,
+            region.contig_index(),
+            region.start().saturating_sub(1) as u32,
+            u64::min(region.end(), u32::MAX as u64) as u32,
+        )
+    }
+
+    async fn query_gene_covering(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Gene>, TGVError> {
+        let Some(chrom_name) = self.chrom_name(contig_header.try_get(contig_index)?) else {
+            return Ok(None); // Contig doesn't have track data
+        };
+
+        Ok(self
+            .query_interval(
+                &chrom_name,
+                contig_index,
+                coord.saturating_sub(1) as u32,
+                coord as u32,
+            )?
+            .into_iter()
+            .next())
+    }
+
+    async fn query_gene_name(
+        &mut self,
+        _reference: &Reference,
+        gene_name: &str,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        if !self.cache.gene_quried(gene_name) {
+            // query all contigs in the file until the gene is found
+            for (contig_index, contig) in contig_header.contigs.iter().enumerate() {
+                if self.chrom_name(contig).is_some() {
+                    self.query_track_if_not_cached(contig_index, contig_header)?;
+
+                    if let Some(gene) = self.cache.get_gene(gene_name) {
+                        return Ok(gene.clone());
+                    }
+                }
+            }
+        }
+
+        Err(TGVError::IOError(format!("Gene {} not found", gene_name)))
+    }
+
+    async fn query_gene_names_matching(
+        &mut self,
+        _reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        for (contig_index, contig) in contig_header.contigs.iter().enumerate() {
+            if self.chrom_name(contig).is_some() {
+                self.query_track_if_not_cached(contig_index, contig_header)?;
+            }
+        }
+
+        Ok(self.cache.gene_names_matching(pattern, limit))
+    }
+
+    async fn query_k_genes_after(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?;
+        self.cached_track(contig_index)?
+            .get_saturating_k_genes_after(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_genes_before(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?;
+        self.cached_track(contig_index)?
+            .get_saturating_k_genes_before(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_exons_after(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?;
+        self.cached_track(contig_index)?
+            .get_saturating_k_exons_after(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+
+    async fn query_k_exons_before(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?;
+        self.cached_track(contig_index)?
+            .get_saturating_k_exons_before(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(100, "10,20,", "0,50,", vec![100, 150], vec![110, 170])]
+    #[case(100, "66", "0", vec![100], vec![166])]
+    #[case(0, "", "", vec![], vec![])]
+    fn test_blocks_to_exons(
+        #[case] chrom_start: u64,
+        #[case] block_sizes: &str,
+        #[case] chrom_starts: &str,
+        #[case] expected_starts: Vec<u64>,
+        #[case] expected_ends: Vec<u64>,
+    ) {
+        assert_eq!(
+            blocks_to_exons(chrom_start, block_sizes, chrom_starts).unwrap(),
+            (expected_starts, expected_ends)
+        );
+    }
+
+    #[test]
+    fn blocks_to_exons_rejects_mismatched_blocks() {
+        assert!(blocks_to_exons(0, "10,20,", "0,").is_err());
+    }
+}
@@ -1,5 +1,9 @@
-use crate::tracks::{TRACK_PREFERENCES, UcscApiTrackService, UcscDbTrackService};
-use crate::{error::TGVError, reference::Reference, tracks::UcscHost};
+use crate::tracks::{
+    TRACK_PREFERENCES, UcscApiTrackService, UcscDbTrackService,
+    bigbed::blocks_to_exons,
+    progress::{ProgressBar, ProgressUnit, write_chunks},
+};
+use crate::{error::TGVError, reference::Reference, retry::RetryPolicy, tracks::UcscHost};
 use bigtools::BigBedRead;
 use sqlx::{
     MySqlPool, Pool, Row,
@@ -7,6 +11,7 @@ use sqlx::{
     sqlite::{Sqlite, SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
 };
 use std::collections::HashMap;
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 use std::time::Instant;
 /// Download data from UCSC mariaDB to a local sqlite file.
@@ -15,11 +20,14 @@ pub struct UCSCDownloader {
 
     /// TGV main cache directory. Reference data are stored in cache_dir/reference_name/.
     cache_dir: String,
+
+    /// Retries of UCSC API requests and MySQL connections.
+    retry: RetryPolicy,
 }
 
 /// UCSC column type. Used to map MySQL types to SQLite types.
 #[derive(Debug)]
-enum UCSCColumnType {
+pub(super) enum UCSCColumnType {
     UnsignedInt,
     Int,
     Float,
@@ -40,13 +48,18 @@ impl UCSCColumnType {
 }
 
 impl UCSCDownloader {
-    pub fn new(reference: Reference, cache_dir: &str) -> Result<Self, TGVError> {
+    pub fn new(
+        reference: Reference,
+        cache_dir: &str,
+        retry: RetryPolicy,
+    ) -> Result<Self, TGVError> {
         let cache_dir = reference.cache_dir(cache_dir);
         std::fs::create_dir_all(Path::new(&cache_dir))
             .map_err(|e| TGVError::IOError(format!("Failed to create genome directory: {}", e)))?;
         Ok(Self {
             reference: reference.clone(),
             cache_dir,
+            retry,
         })
     }
 
@@ -113,9 +126,13 @@ impl UCSCDownloader {
             reference
         );
         let started = Instant::now();
-        let mysql_pool = MySqlPoolOptions::new()
-            .max_connections(5)
-            .connect(&mysql_url)
+        let mysql_pool = self
+            .retry
+            .run("UCSC MySQL connection", || {
+                MySqlPoolOptions::new()
+                    .max_connections(5)
+                    .connect(&mysql_url)
+            })
             .await?;
         log::info!(
             "Database connect result: database=ucsc-mysql context=download reference={} elapsed_ms={}",
@@ -132,6 +149,11 @@ impl UCSCDownloader {
             .transfer_gene_tracks(&mysql_pool, sqlite_pool)
             .await?;
 
+        // Some assemblies have no gap table. Gaps are then detected as runs of Ns.
+        if let Err(e) = self.transfer_table(&mysql_pool, sqlite_pool, "gap").await {
+            log::warn!("Skipped the gap table: reference={} error={}", reference, e);
+        }
+
         mysql_pool.close().await;
 
         self.download_genomes(sqlite_pool).await?;
@@ -144,14 +166,14 @@ impl UCSCDownloader {
     /// 1. Use UCSC API get hub url
     /// 2. Parse hub file for 2bit file paths and bigbed file paths
     /// 3. Download 2bit files
-    /// 4. Download Bigbed files. Convert to SQLite tables. (TODO: calculate exonStarts and exonEnds from blockStarts and blockSizes)
+    /// 4. Download Bigbed files. Convert to SQLite tables.
     async fn download_for_ucsc_accession(
         &self,
         reference: &Reference,
         sqlite_pool: &Pool<Sqlite>,
     ) -> Result<(), TGVError> {
         // 1. Get hub url
-        let mut ucsc_api_service = UcscApiTrackService::new()?;
+        let mut ucsc_api_service = UcscApiTrackService::new(&UcscHost::Us, None, self.retry)?;
         let hub_url = ucsc_api_service
             .get_hub_url_for_genark_accession(&reference.to_string())
             .await?;
@@ -429,7 +451,9 @@ impl UCSCDownloader {
 
         let mut transaction: sqlx::Transaction<'_, sqlx::Sqlite> = sqlite_pool.begin().await?;
         let started = Instant::now();
-        for row in &rows {
+        let mut progress_bar =
+            ProgressBar::new(table_name, Some(rows.len() as u64), ProgressUnit::Rows);
+        for (i, row) in rows.iter().enumerate() {
             let mut query = sqlx::query(&insert_sql);
             for col_name in &valid_columns {
                 // Bind values based on SQLite type
@@ -457,9 +481,11 @@ impl UCSCDownloader {
                 }
             }
             query.execute(&mut *transaction).await?;
+            progress_bar.set(i as u64 + 1);
         }
 
         transaction.commit().await?;
+        progress_bar.finish();
         log::info!(
             "Database batch result: database=local-sqlite context=insert transferred table rows table={} rows={} elapsed_ms={}",
             table_name,
@@ -550,72 +576,276 @@ impl UCSCDownloader {
     }
 
     /// Download a file to a directory with the same filename.
-    /// Skip if file already exists. Note that no cache invalidation here. TODO.
+    /// The file is written to `<name>.part` and renamed once its size matches the server's, so an interrupted
+    /// download is resumed with an HTTP range request on the next run, and the final file is never partial.
+    /// Existing files are skipped if their size matches the server's, and re-fetched otherwise. UCSC does not
+    /// publish checksums for gbdb files, so only the size is verified.
     async fn download_to_directory(&self, url: &str) -> Result<PathBuf, TGVError> {
-        let local_path = Path::new(&self.cache_dir).join(url.split("/").last().unwrap());
+        let file_name = url.split("/").last().unwrap();
+        let local_path = Path::new(&self.cache_dir).join(file_name);
+        let part_path = part_path(&local_path);
         let client = reqwest::Client::new();
 
+        if let Ok(metadata) = std::fs::metadata(&local_path) {
+            log::info!(
+                "HTTP request: method=HEAD url={} context=verify downloaded file size",
+                url
+            );
+            let remote_size = client
+                .head(url)
+                .send()
+                .await
+                .ok()
+                .filter(|response| response.status().is_success())
+                .and_then(|response| response.content_length());
+            if remote_size.is_none_or(|remote_size| remote_size == metadata.len()) {
+                println!("Already downloaded: {}", local_path.display());
+                return Ok(local_path);
+            }
+            println!(
+                "Re-downloading {}: {} bytes on disk, but the server has {} bytes",
+                local_path.display(),
+                metadata.len(),
+                remote_size.unwrap_or_default()
+            );
+        }
+
         println!("Downloading file: {}", local_path.display());
 
-        log::info!(
-            "HTTP request: method=GET url={} context=download file to {}",
-            url,
-            local_path.display()
-        );
-        // Download the file
-        let started = Instant::now();
-        match client.get(url).send().await {
-            Ok(response) => {
-                log::info!(
-                    "HTTP response: status={} url={} context=download file elapsed_ms={}",
-                    response.status(),
-                    url,
-                    started.elapsed().as_millis()
-                );
-                if response.status().is_success() {
-                    let content = response.bytes().await.map_err(|e| {
-                        TGVError::IOError(format!("Failed to read response bytes: {}", e))
-                    })?;
-                    log::debug!(
-                        "Downloaded HTTP response body: url={} bytes={} local_path={}",
+        // Resume from the partial file, or start over if the server ignores or rejects the range.
+        for resume_attempt in [true, false] {
+            let part_len = std::fs::metadata(&part_path).map_or(0, |metadata| metadata.len());
+            let mut request = client.get(url);
+            if resume_attempt && part_len > 0 {
+                request = request.header(reqwest::header::RANGE, format!("bytes={part_len}-"));
+            }
+
+            log::info!(
+                "HTTP request: method=GET url={} range_start={} context=download file to {}",
+                url,
+                part_len,
+                part_path.display()
+            );
+            let started = Instant::now();
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    log::warn!(
+                        "HTTP request failed: url={} error={} elapsed_ms={}",
                         url,
-                        content.len(),
-                        local_path.display()
+                        e,
+                        started.elapsed().as_millis()
                     );
-
-                    // Write file to disk
-                    std::fs::write(&local_path, content).map_err(|e| {
-                        TGVError::IOError(format!(
-                            "Failed to write file {}: {}",
-                            local_path.display(),
-                            e
-                        ))
-                    })?;
-
+                    println!("Failed to download {}: {}", local_path.display(), e);
+                    return Ok(local_path);
+                }
+            };
+            log::info!(
+                "HTTP response: status={} url={} context=download file elapsed_ms={}",
+                response.status(),
+                url,
+                started.elapsed().as_millis()
+            );
+
+            let content_range = response
+                .headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let resume = match resume_download(
+                part_len,
+                response.status().as_u16(),
+                content_range.as_deref(),
+                response.content_length(),
+            ) {
+                Some(Resume::Append(resume)) => resume,
+                Some(Resume::Complete(total)) => {
+                    finish_part_file(&part_path, &local_path, Some(total))?;
                     println!("Downloaded: {}", local_path.display());
-                } else {
+                    return Ok(local_path);
+                }
+                Some(Resume::Restart) if resume_attempt => {
+                    log::info!(
+                        "Range rejected or mismatched, restarting the download: url={}",
+                        url
+                    );
+                    continue;
+                }
+                _ => {
                     println!(
                         "Failed to download {}: HTTP {}",
                         local_path.display(),
                         response.status()
                     );
+                    return Ok(local_path);
                 }
+            };
+
+            if resume.offset > 0 {
+                println!("Resuming {} from {} bytes", file_name, resume.offset);
             }
-            Err(e) => {
-                log::warn!(
-                    "HTTP request failed: url={} error={} elapsed_ms={}",
-                    url,
-                    e,
-                    started.elapsed().as_millis()
-                );
-                println!("Failed to download {}: {}", local_path.display(), e);
-            }
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(resume.offset > 0)
+                .truncate(resume.offset == 0)
+                .open(&part_path)
+                .map_err(|e| {
+                    TGVError::IOError(format!(
+                        "Failed to write file {}: {}",
+                        part_path.display(),
+                        e
+                    ))
+                })?;
+            let mut progress_bar = ProgressBar::new(file_name, resume.total, ProgressUnit::Bytes);
+            let chunks = Box::pin(futures::stream::unfold(
+                response,
+                |mut response| async move {
+                    match response.chunk().await {
+                        Ok(Some(chunk)) => Some((Ok(chunk), response)),
+                        Ok(None) => None,
+                        Err(e) => Some((
+                            Err(TGVError::IOError(format!(
+                                "Failed to read response bytes: {}",
+                                e
+                            ))),
+                            response,
+                        )),
+                    }
+                },
+            ));
+            let written = write_chunks(chunks, &mut BufWriter::new(file), |n| {
+                progress_bar.set(resume.offset + n)
+            })
+            .await
+            .inspect_err(|_| {
+                println!(
+                    "Download of {} was interrupted. Rerun tgv download to resume it.",
+                    file_name
+                )
+            })?;
+            progress_bar.finish();
+            log::debug!(
+                "Downloaded HTTP response body: url={} bytes={} local_path={}",
+                url,
+                written,
+                part_path.display()
+            );
+
+            finish_part_file(&part_path, &local_path, resume.total)?;
+            println!("Downloaded: {}", local_path.display());
+            return Ok(local_path);
         }
 
         Ok(local_path)
     }
 }
 
+/// Partial download of a file: `<name>.part` next to it.
+fn part_path(local_path: &Path) -> PathBuf {
+    let mut part_path = local_path.as_os_str().to_owned();
+    part_path.push(".part");
+    PathBuf::from(part_path)
+}
+
+/// Where a download continues, and the expected size of the complete file if known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Append {
+    offset: u64,
+    total: Option<u64>,
+}
+
+/// How to continue a download from a partial file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Resume {
+    /// Write the response body to the partial file.
+    Append(Append),
+    /// The partial file is already complete, with this size. The response body is not part of the file.
+    Complete(u64),
+    /// The response doesn't continue the partial file. Request the whole file again.
+    Restart,
+}
+
+/// How to continue a download with part_len bytes on disk, given the status, Content-Range, and Content-Length
+/// of the response. None if the response has no usable body.
+///
+/// - 200: the server ignored the range (or none was sent). Start over.
+/// - 206 starting at part_len: append. Starting elsewhere: restart.
+/// - 416 with a total of part_len: the partial file is already complete. Otherwise: restart.
+fn resume_download(
+    part_len: u64,
+    status: u16,
+    content_range: Option<&str>,
+    content_length: Option<u64>,
+) -> Option<Resume> {
+    // Content-Range: bytes START-END/TOTAL, or bytes */TOTAL for 416. TOTAL can be *.
+    let (range, total) = content_range
+        .and_then(|range| range.strip_prefix("bytes "))
+        .and_then(|range| range.split_once('/'))
+        .map(|(range, total)| (range, total.parse::<u64>().ok()))
+        .unzip();
+    let total = total.flatten();
+    match status {
+        200 => Some(Resume::Append(Append {
+            offset: 0,
+            total: content_length,
+        })),
+        206 => {
+            let start = range
+                .and_then(|range| range.split_once('-'))
+                .and_then(|(start, _)| start.parse::<u64>().ok());
+            if start != Some(part_len) {
+                return Some(Resume::Restart);
+            }
+            Some(Resume::Append(Append {
+                offset: part_len,
+                total: total.or(content_length.map(|content_length| part_len + content_length)),
+            }))
+        }
+        416 if total == Some(part_len) => Some(Resume::Complete(part_len)),
+        416 => Some(Resume::Restart),
+        _ => None,
+    }
+}
+
+/// Rename the partial file to the local path if it has the expected size. A smaller file is kept to be resumed;
+/// a larger one is removed to be downloaded again.
+fn finish_part_file(
+    part_path: &Path,
+    local_path: &Path,
+    expected_size: Option<u64>,
+) -> Result<(), TGVError> {
+    let size = std::fs::metadata(part_path)?.len();
+    match expected_size {
+        Some(expected_size) if size < expected_size => {
+            return Err(TGVError::IOError(format!(
+                "Downloaded {} of {} bytes of {}. Rerun tgv download to resume it.",
+                size,
+                expected_size,
+                local_path.display()
+            )));
+        }
+        Some(expected_size) if size > expected_size => {
+            std::fs::remove_file(part_path)?;
+            return Err(TGVError::IOError(format!(
+                "Downloaded {} bytes of {}, but the server reported {} bytes. Rerun tgv download to download it again.",
+                size,
+                local_path.display(),
+                expected_size
+            )));
+        }
+        _ => {}
+    }
+    std::fs::rename(part_path, local_path).map_err(|e| {
+        TGVError::IOError(format!(
+            "Failed to move {} to {}: {}",
+            part_path.display(),
+            local_path.display(),
+            e
+        ))
+    })
+}
+
 /// Convert BigBed files to SQLite database
 pub struct BigBedConverter {}
 
@@ -626,7 +856,7 @@ impl BigBedConverter {
     ///   - https://genome.ucsc.edu/goldenpath/help/examples/bedExample2.as
     ///
     /// Return: (field_names, field_types)
-    fn get_schema(
+    pub(super) fn get_schema(
         bigbed_reader: &mut BigBedRead<bigtools::utils::reopen::ReopenableFile>,
     ) -> Result<(Vec<String>, Vec<UCSCColumnType>), TGVError> {
         use bigtools::bed::autosql::parse::FieldType;
@@ -650,8 +880,8 @@ impl BigBedConverter {
             }
 
             field_names.push(match field.name.as_str() {
-                "thickStart" => "txStart".to_string(), // To be consistent with UCSC db.
-                "thickEnd" => "txEnd".to_string(),     // To be consistent with UCSC db.
+                "thickStart" => "cdsStart".to_string(), // To be consistent with UCSC db.
+                "thickEnd" => "cdsEnd".to_string(),     // To be consistent with UCSC db.
                 name => name.to_string(),
             });
             field_types.push(match field.field_type {
@@ -707,9 +937,11 @@ impl BigBedConverter {
 
         let (field_names, schema) = Self::get_schema(&mut bigbed_reader)?;
 
-        // Special case:
-        // For gene tracks, bigBed files store blockSizes and chromStarts, but UCSC db stores exonStarts and exonEnds.
-        // Convert them to be consistent.
+        // Special cases for gene tracks, to be consistent with UCSC db:
+        // - bigBed files store the transcript as chromStart and chromEnd, but UCSC db stores txStart and txEnd.
+        // - bigBed files store blockSizes and chromStarts, but UCSC db stores exonStarts and exonEnds.
+        let need_tx_columns = field_names.contains(&"cdsStart".to_string())
+            && !field_names.contains(&"txStart".to_string());
         let need_exon_conversion = field_names.contains(&"blockSizes".to_string())
             && field_names.contains(&"chromStarts".to_string())
             && !(field_names.contains(&"exonStarts".to_string())
@@ -752,8 +984,11 @@ impl BigBedConverter {
             }
         }
 
+        if need_tx_columns {
+            query.push_str(", txStart INTEGER, txEnd INTEGER");
+        }
         if need_exon_conversion {
-            query.push_str(", exonStarts BLOB, exonEnds BLOB, cdsStart INTEGER, cdsEnd INTEGER");
+            query.push_str(", exonStarts BLOB, exonEnds BLOB");
         }
 
         let query_string = format!("CREATE TABLE {} ({})", track_name, query);
@@ -788,12 +1023,24 @@ impl BigBedConverter {
             track_name,
             "chrom, chromStart, chromEnd, ".to_string()
                 + &field_names.join(", ")
+                + if need_tx_columns {
+                    ", txStart, txEnd"
+                } else {
+                    ""
+                }
                 + if need_exon_conversion {
-                    ", exonStarts, exonEnds, cdsStart, cdsEnd"
+                    ", exonStarts, exonEnds"
                 } else {
                     ""
                 },
-            vec!["?"; field_names.len() + 3 + if need_exon_conversion { 4 } else { 0 }].join(", ")
+            vec![
+                "?";
+                field_names.len()
+                    + 3
+                    + if need_tx_columns { 2 } else { 0 }
+                    + if need_exon_conversion { 2 } else { 0 }
+            ]
+            .join(", ")
         );
         log::info!(
             "Database query: database=local-sqlite sql=\"{}\" context=prepare BigBed row insert track={} path={}",
@@ -866,19 +1113,18 @@ impl BigBedConverter {
                     }
                 }
 
+                if need_tx_columns {
+                    query = query.bind(interval.start as i64).bind(interval.end as i64);
+                }
+
                 // exonStarts and exonEnds calculation
                 if need_exon_conversion {
-                    let (exon_starts_blob, exon_ends_blob, cds_start, cds_end) =
-                        Self::convert_blocks_to_exons(
-                            interval.start,
-                            fields[block_sizes_field_index].as_bytes().to_vec(),
-                            fields[chrom_starts_field_index].as_bytes().to_vec(),
-                        )?;
-                    query = query
-                        .bind(exon_starts_blob)
-                        .bind(exon_ends_blob)
-                        .bind(cds_start)
-                        .bind(cds_end);
+                    let (exon_starts_blob, exon_ends_blob) = Self::convert_blocks_to_exons(
+                        interval.start,
+                        fields[block_sizes_field_index],
+                        fields[chrom_starts_field_index],
+                    )?;
+                    query = query.bind(exon_starts_blob).bind(exon_ends_blob);
                 }
 
                 query.execute(&mut *transaction).await?;
@@ -897,14 +1143,6 @@ impl BigBedConverter {
             started.elapsed().as_millis()
         );
 
-        // Special case:
-        // For gene tracks, bigBed files store blockSizes and chromStarts, but UCSC db stores exonStarts and exonEnds.
-        // Convert them to be consistent.
-
-        // if need_exon_conversion {
-        //     Self::add_exon_columns(track_name, sqlite_pool).await?;
-        // }
-
         println!(
             "Successfully converted {} records from BigBed to SQLite table '{}'",
             record_count, track_name
@@ -915,69 +1153,26 @@ impl BigBedConverter {
 
     // Note: UCSC database store exon information with exonStarts and exonEnds. But the bigbed files stores blockStarts and blockSize.
     // Compute them to new columns to reduce compute at run time.
-    /// Return: (exonStarts (blob), exonEnds (blob), cdsStart (u32), cdsEnd (u32))
+    /// Return: (exonStarts (blob), exonEnds (blob))
     fn convert_blocks_to_exons(
         chrom_start: u32,
-        block_sizes_blob: Vec<u8>,
-        chrom_starts_blob: Vec<u8>,
-    ) -> Result<(Vec<u8>, Vec<u8>, u32, u32), TGVError> {
-        let block_sizes_str = String::from_utf8(block_sizes_blob).map_err(|e| {
-            TGVError::ValueError(format!("Failed to convert block sizes to string: {}", e))
-        })?;
-        let chrom_starts_str = String::from_utf8(chrom_starts_blob).map_err(|e| {
-            TGVError::ValueError(format!("Failed to convert block starts to string: {}", e))
-        })?;
-
-        // Example block_sizes_str, chrom_starts_str:
-        // 66,
-        // 0,
-
-        let block_sizes: Vec<u32> = block_sizes_str
-            .split(',')
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim().parse::<u32>().unwrap())
-            .collect();
-
-        let chrom_starts: Vec<u32> = chrom_starts_str
-            .split(',')
-            .filter(|s| !s.trim().is_empty())
-            .map(|s| s.trim().parse::<u32>().unwrap())
-            .collect();
-
-        if block_sizes.len() != chrom_starts.len() {
-            return Err(TGVError::ValueError(
-                "blockSizes and chromStarts arrays have different lengths".to_string(),
-            ));
-        }
-
-        let mut exon_starts = Vec::new();
-        let mut exon_ends = Vec::new();
-
-        for (block_start, block_size) in chrom_starts.iter().zip(block_sizes.iter()) {
-            let exon_start = chrom_start + *block_start;
-            let exon_end = exon_start + *block_size;
-            exon_starts.push(exon_start);
-            exon_ends.push(exon_end);
-        }
-
-        let cds_start = exon_starts[0];
-        let cds_end = exon_ends[exon_ends.len() - 1];
+        block_sizes: &str,
+        chrom_starts: &str,
+    ) -> Result<(Vec<u8>, Vec<u8>), TGVError> {
+        let (exon_starts, exon_ends) =
+            blocks_to_exons(chrom_start as u64, block_sizes, chrom_starts)?;
 
         // Convert to blob
-        let exon_starts_str = exon_starts
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-        let exon_starts_blob = exon_starts_str.into_bytes();
-        let exon_ends_str = exon_ends
-            .iter()
-            .map(|x| x.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-        let exon_ends_blob = exon_ends_str.into_bytes();
-
-        Ok((exon_starts_blob, exon_ends_blob, cds_start, cds_end))
+        let to_blob = |coordinates: Vec<u64>| {
+            coordinates
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+                .into_bytes()
+        };
+
+        Ok((to_blob(exon_starts), to_blob(exon_ends)))
     }
 }
 
@@ -1070,3 +1265,221 @@ fn get_preferred_track_name_from_vec(names: &Vec<String>) -> Result<Option<Strin
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        contig_header::{ContigHeader, ContigSource},
+        tracks::schema::UcscGeneRow,
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 200, None, Some(100), Some(Resume::Append(Append { offset: 0, total: Some(100) })))]
+    // The server ignored the range: start over.
+    #[case(40, 200, None, Some(100), Some(Resume::Append(Append { offset: 0, total: Some(100) })))]
+    #[case(40, 206, Some("bytes 40-99/100"), Some(60), Some(Resume::Append(Append { offset: 40, total: Some(100) })))]
+    #[case(40, 206, Some("bytes 40-99/*"), Some(60), Some(Resume::Append(Append { offset: 40, total: Some(100) })))]
+    #[case(40, 206, Some("bytes 40-99/*"), None, Some(Resume::Append(Append { offset: 40, total: None })))]
+    // The range doesn't continue the partial file: request the whole file.
+    #[case(40, 206, Some("bytes 0-99/100"), Some(100), Some(Resume::Restart))]
+    #[case(40, 206, None, Some(60), Some(Resume::Restart))]
+    // The partial file is complete.
+    #[case(100, 416, Some("bytes */100"), None, Some(Resume::Complete(100)))]
+    #[case(120, 416, Some("bytes */100"), None, Some(Resume::Restart))]
+    #[case(0, 404, None, None, None)]
+    fn test_resume_download(
+        #[case] part_len: u64,
+        #[case] status: u16,
+        #[case] content_range: Option<&str>,
+        #[case] content_length: Option<u64>,
+        #[case] expected: Option<Resume>,
+    ) {
+        assert_eq!(
+            resume_download(part_len, status, content_range, content_length),
+            expected
+        );
+    }
+
+    /// Serve the responses in turn, one per connection. Return: the URL of `hg38.2bit` and the received requests.
+    async fn serve_responses(
+        responses: Vec<String>,
+    ) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hg38.2bit", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let received = requests.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 4096];
+                let n = socket.read(&mut buffer).await.unwrap();
+                received
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buffer[..n]).to_lowercase());
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    #[tokio::test]
+    async fn complete_part_file_is_renamed_without_the_416_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let downloader = UCSCDownloader {
+            reference: Reference::Hg38,
+            cache_dir: dir.path().to_str().unwrap().to_string(),
+            retry: RetryPolicy::NONE,
+        };
+        std::fs::write(dir.path().join("hg38.2bit.part"), b"ACGT").unwrap();
+        let (url, requests) = serve_responses(vec![response(
+            "416 Range Not Satisfiable",
+            "Content-Range: bytes */4\r\n",
+            "error page",
+        )])
+        .await;
+
+        let local_path = downloader.download_to_directory(&url).await.unwrap();
+
+        assert_eq!(std::fs::read(&local_path).unwrap(), b"ACGT");
+        assert!(!dir.path().join("hg38.2bit.part").exists());
+        assert!(requests.lock().unwrap()[0].contains("range: bytes=4-"));
+    }
+
+    #[tokio::test]
+    async fn mismatched_range_restarts_the_download_without_a_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let downloader = UCSCDownloader {
+            reference: Reference::Hg38,
+            cache_dir: dir.path().to_str().unwrap().to_string(),
+            retry: RetryPolicy::NONE,
+        };
+        std::fs::write(dir.path().join("hg38.2bit.part"), b"AC").unwrap();
+        let (url, requests) = serve_responses(vec![
+            response(
+                "206 Partial Content",
+                "Content-Range: bytes 0-5/6\r\n",
+                "ACGTTT",
+            ),
+            response("200 OK", "", "ACGTAA"),
+        ])
+        .await;
+
+        let local_path = downloader.download_to_directory(&url).await.unwrap();
+
+        assert_eq!(std::fs::read(&local_path).unwrap(), b"ACGTAA");
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].contains("range: bytes=2-"));
+        assert!(!requests[1].contains("range:"));
+    }
+
+    #[test]
+    fn complete_part_file_is_renamed() {
+        let dir = tempfile::tempdir().unwrap();
+        let local_path = dir.path().join("hg38.2bit");
+        let part_path = part_path(&local_path);
+        assert_eq!(part_path, dir.path().join("hg38.2bit.part"));
+        std::fs::write(&part_path, b"ACGT").unwrap();
+
+        finish_part_file(&part_path, &local_path, Some(4)).unwrap();
+
+        assert_eq!(std::fs::read(&local_path).unwrap(), b"ACGT");
+        assert!(!part_path.exists());
+    }
+
+    #[test]
+    fn short_part_file_is_kept_for_resuming() {
+        let dir = tempfile::tempdir().unwrap();
+        let local_path = dir.path().join("hg38.2bit");
+        let part_path = part_path(&local_path);
+        std::fs::write(&part_path, b"AC").unwrap();
+
+        assert!(finish_part_file(&part_path, &local_path, Some(4)).is_err());
+
+        assert!(!local_path.exists());
+        assert_eq!(std::fs::read(&part_path).unwrap(), b"AC");
+    }
+
+    #[test]
+    fn oversized_part_file_is_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let local_path = dir.path().join("hg38.2bit");
+        let part_path = part_path(&local_path);
+        std::fs::write(&part_path, b"ACGTACGT").unwrap();
+
+        assert!(finish_part_file(&part_path, &local_path, Some(4)).is_err());
+
+        assert!(!local_path.exists());
+        assert!(!part_path.exists());
+    }
+
+    #[test]
+    fn convert_blocks_to_exons_returns_absolute_exon_blobs() {
+        // NM_001142759.1 (DBT) on NC_072398.2. chromStart = 130929426, chromEnd = 130985030.
+        let (exon_starts, exon_ends) = BigBedConverter::convert_blocks_to_exons(
+            130929426,
+            "65,124,76,182,122,217,167,126,78,192,72,556,374,",
+            "0,8926,14265,18877,31037,33561,34781,36127,39014,43150,43484,53351,55230,",
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(exon_starts).unwrap(),
+            "130929426,130938352,130943691,130948303,130960463,130962987,130964207,130965553,130968440,130972576,130972910,130982777,130984656"
+        );
+        assert_eq!(
+            String::from_utf8(exon_ends).unwrap(),
+            "130929491,130938476,130943767,130948485,130960585,130963204,130964374,130965679,130968518,130972768,130972982,130983333,130985030"
+        );
+    }
+
+    #[tokio::test]
+    async fn save_to_sqlite_stores_bigbed_genes_as_ucsc_gene_rows() {
+        let bigbed_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../tgv/tests/data/cache/GCF_000005845.2/GCF_000005845.2_ASM584v2.ncbiGene.bb"
+        );
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        BigBedConverter::save_to_sqlite(bigbed_path, "ncbiGene", &pool)
+            .await
+            .unwrap();
+
+        let row: UcscGeneRow = sqlx::query_as("SELECT * FROM ncbiGene WHERE name = ?")
+            .bind("NP_414542.1")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let mut contig_header = ContigHeader::new(Reference::NoReference);
+        contig_header.update_or_add_contig(
+            "NC_000913.3".to_string(),
+            Some(4641652),
+            Vec::new(),
+            ContigSource::Track,
+        );
+        let gene = row.to_gene(&contig_header).unwrap();
+
+        // thrL: chromStart = 189, chromEnd = 255, one block.
+        assert_eq!(gene.name, "thrL");
+        assert_eq!(
+            (gene.transcription_start, gene.transcription_end),
+            (190, 255)
+        );
+        assert_eq!((gene.cds_start, gene.cds_end), (190, 255));
+        assert_eq!((gene.exon_starts, gene.exon_ends), (vec![190], vec![255]));
+    }
+}
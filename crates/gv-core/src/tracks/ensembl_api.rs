@@ -0,0 +1,926 @@
+use crate::tracks::{TrackCache, TrackService};
+use crate::{
+    contig_header::{Contig, ContigHeader},
+    cytoband::Cytoband,
+    error::TGVError,
+    feature::{Gene, SubGeneFeature},
+    intervals::{Focus, GenomeInterval, Region},
+    reference::Reference,
+    strand::Strand,
+    track::Track,
+};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Track name shown in `:track list`.
+const TRACK_NAME: &str = "ensemblCanonical";
+
+/// Half width of the window loaded around a query. One window is loaded per request.
+const WINDOW_HALF_WIDTH: u64 = 1_000_000;
+
+/// Ensembl rejects overlap/region queries longer than 5 Mb.
+const MAX_QUERY_HALF_WIDTH: u64 = 2_500_000;
+
+/// Ensembl REST server and species for a reference.
+fn ensembl_species(reference: &Reference) -> Result<(&'static str, &'static str), TGVError> {
+    match reference {
+        Reference::Hg38 => Ok(("https://rest.ensembl.org", "homo_sapiens")),
+        Reference::Hg19 => Ok(("https://grch37.rest.ensembl.org", "homo_sapiens")),
+        Reference::UcscGenome(genome) if genome == "mm39" => {
+            Ok(("https://rest.ensembl.org", "mus_musculus"))
+        }
+        Reference::UcscGenome(genome) if genome == "danRer11" => {
+            Ok(("https://rest.ensembl.org", "danio_rerio"))
+        }
+        _ => Err(TGVError::StateError(format!(
+            "The Ensembl backend does not support reference {}. Supported: hg38, hg19, mm39, danRer11.",
+            reference
+        ))),
+    }
+}
+
+/// Part of a region that can be loaded in one query. Regions wider than the Ensembl query limit only have data
+/// around their center.
+fn loadable_region(region: &Region) -> Region {
+    Region {
+        focus: region.focus.clone(),
+        half_width: u64::min(region.half_width, MAX_QUERY_HALF_WIDTH),
+    }
+}
+
+/// Ensembl seq_region name of a UCSC-style contig name: chr1 -> 1, chrM -> MT.
+fn ensembl_contig_name(contig_name: &str) -> &str {
+    match contig_name {
+        "chrM" => "MT",
+        _ => contig_name.strip_prefix("chr").unwrap_or(contig_name),
+    }
+}
+
+/// UCSC-style contig name of an Ensembl chromosome: 1 -> chr1, MT -> chrM.
+fn ucsc_contig_name(seq_region_name: &str) -> String {
+    match seq_region_name {
+        "MT" => "chrM".to_string(),
+        _ => format!("chr{}", seq_region_name),
+    }
+}
+
+/// Ensembl strands are integers (1, -1). "+" and "-" are also accepted.
+fn parse_strand(value: &serde_json::Value) -> Result<Strand, TGVError> {
+    let strand = match value {
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(1) => Some(Strand::Forward),
+            Some(-1) => Some(Strand::Reverse),
+            _ => None,
+        },
+        serde_json::Value::String(s) => match s.as_str() {
+            "+" | "1" => Some(Strand::Forward),
+            "-" | "-1" => Some(Strand::Reverse),
+            _ => None,
+        },
+        _ => None,
+    };
+    strand.ok_or(TGVError::ValueError(format!(
+        "Invalid Ensembl strand: {}",
+        value
+    )))
+}
+
+/// One feature of an overlap/region response.
+#[derive(Debug, Deserialize)]
+struct EnsemblFeature {
+    feature_type: String,
+
+    #[serde(default)]
+    id: Option<String>,
+
+    /// Gene ID of a transcript, or transcript ID of an exon / CDS.
+    #[serde(rename = "Parent", default)]
+    parent: Option<String>,
+
+    start: u64,
+    end: u64,
+    strand: serde_json::Value,
+
+    #[serde(default)]
+    external_name: Option<String>,
+
+    #[serde(default)]
+    is_canonical: Option<u8>,
+}
+
+/// Gene of a lookup/symbol response with expand=1.
+#[derive(Debug, Deserialize)]
+struct EnsemblLookupGene {
+    id: String,
+
+    #[serde(default)]
+    display_name: Option<String>,
+
+    seq_region_name: String,
+
+    #[serde(rename = "Transcript", default)]
+    transcripts: Vec<EnsemblLookupTranscript>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsemblLookupTranscript {
+    id: String,
+    start: u64,
+    end: u64,
+    strand: serde_json::Value,
+
+    #[serde(default)]
+    is_canonical: Option<u8>,
+
+    #[serde(rename = "Exon", default)]
+    exons: Vec<EnsemblLookupSegment>,
+
+    #[serde(rename = "Translation", default)]
+    translation: Option<EnsemblLookupSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsemblLookupSegment {
+    start: u64,
+    end: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsemblAssemblyResponse {
+    top_level_region: Vec<EnsemblTopLevelRegion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EnsemblTopLevelRegion {
+    name: String,
+    length: u64,
+    coord_system: String,
+}
+
+/// Transcript assembled from Ensembl features.
+/// Ensembl coordinates are 1-based, inclusive, the same as Gene, so they are used as-is.
+#[derive(Debug)]
+struct EnsemblTranscript {
+    id: String,
+    gene_id: Option<String>,
+    gene_name: Option<String>,
+    strand: Strand,
+    start: u64,
+    end: u64,
+    is_canonical: bool,
+    exons: Vec<(u64, u64)>,
+    cds: Vec<(u64, u64)>,
+}
+
+impl EnsemblTranscript {
+    fn to_gene(&self, contig_index: usize) -> Gene {
+        let mut exons = self.exons.clone();
+        exons.sort();
+
+        // Non-coding transcripts follow the UCSC convention of an empty CDS at the transcript end.
+        let (cds_start, cds_end) = match (
+            self.cds.iter().map(|cds| cds.0).min(),
+            self.cds.iter().map(|cds| cds.1).max(),
+        ) {
+            (Some(cds_start), Some(cds_end)) => (cds_start, cds_end),
+            _ => (self.end + 1, self.end),
+        };
+
+        Gene {
+            id: self.id.clone(),
+            name: self.gene_name.clone().unwrap_or(self.id.clone()),
+            strand: self.strand.clone(),
+            contig_index,
+            transcription_start: self.start,
+            transcription_end: self.end,
+            cds_start,
+            cds_end,
+            has_exons: !exons.is_empty(),
+            exon_starts: exons.iter().map(|exon| exon.0).collect(),
+            exon_ends: exons.iter().map(|exon| exon.1).collect(),
+        }
+    }
+}
+
+/// Keep the canonical transcripts of each gene. Genes without a canonical transcript keep all transcripts.
+fn canonical_transcripts(transcripts: Vec<EnsemblTranscript>) -> Vec<EnsemblTranscript> {
+    let mut has_canonical: HashMap<Option<String>, bool> = HashMap::new();
+    for transcript in transcripts.iter() {
+        *has_canonical.entry(transcript.gene_id.clone()).or_default() |= transcript.is_canonical;
+    }
+
+    transcripts
+        .into_iter()
+        .filter(|transcript| transcript.is_canonical || !has_canonical[&transcript.gene_id])
+        .collect()
+}
+
+/// Assemble genes from the gene, transcript, exon, and CDS features of overlap/region responses.
+fn parse_overlap_features(
+    features: Vec<EnsemblFeature>,
+    contig_index: usize,
+) -> Result<Vec<Gene>, TGVError> {
+    let mut gene_names: HashMap<String, String> = HashMap::new();
+    let mut transcripts: Vec<EnsemblTranscript> = Vec::new();
+    let mut transcript_lookup: HashMap<String, usize> = HashMap::new();
+    let mut parts: Vec<EnsemblFeature> = Vec::new();
+
+    for feature in features {
+        match feature.feature_type.as_str() {
+            "gene" => {
+                if let (Some(id), Some(name)) = (&feature.id, &feature.external_name) {
+                    gene_names.insert(id.clone(), name.clone());
+                }
+            }
+            "transcript" => {
+                let Some(id) = feature.id.clone() else {
+                    continue;
+                };
+                // Windows overlap, so the same transcript can be returned twice.
+                if transcript_lookup.contains_key(&id) {
+                    continue;
+                }
+                transcript_lookup.insert(id.clone(), transcripts.len());
+                transcripts.push(EnsemblTranscript {
+                    id,
+                    gene_id: feature.parent.clone(),
+                    gene_name: None,
+                    strand: parse_strand(&feature.strand)?,
+                    start: feature.start,
+                    end: feature.end,
+                    is_canonical: feature.is_canonical == Some(1),
+                    exons: Vec::new(),
+                    cds: Vec::new(),
+                });
+            }
+            "exon" | "cds" => parts.push(feature),
+            _ => {}
+        }
+    }
+
+    for part in parts {
+        let Some(index) = part
+            .parent
+            .as_ref()
+            .and_then(|parent| transcript_lookup.get(parent))
+        else {
+            continue;
+        };
+        let segments = match part.feature_type.as_str() {
+            "exon" => &mut transcripts[*index].exons,
+            _ => &mut transcripts[*index].cds,
+        };
+        if !segments.contains(&(part.start, part.end)) {
+            segments.push((part.start, part.end));
+        }
+    }
+
+    for transcript in transcripts.iter_mut() {
+        transcript.gene_name = transcript
+            .gene_id
+            .as_ref()
+            .and_then(|gene_id| gene_names.get(gene_id))
+            .cloned();
+    }
+
+    Ok(canonical_transcripts(transcripts)
+        .iter()
+        .map(|transcript| transcript.to_gene(contig_index))
+        .collect())
+}
+
+impl EnsemblLookupGene {
+    /// The canonical transcript of the gene, or the first transcript if none is canonical.
+    fn to_gene(&self, contig_index: usize) -> Result<Gene, TGVError> {
+        let transcript = self
+            .transcripts
+            .iter()
+            .find(|transcript| transcript.is_canonical == Some(1))
+            .or(self.transcripts.first())
+            .ok_or(TGVError::IOError(format!(
+                "Ensembl gene {} has no transcripts",
+                self.id
+            )))?;
+
+        Ok(EnsemblTranscript {
+            id: transcript.id.clone(),
+            gene_id: Some(self.id.clone()),
+            gene_name: self.display_name.clone(),
+            strand: parse_strand(&transcript.strand)?,
+            start: transcript.start,
+            end: transcript.end,
+            is_canonical: transcript.is_canonical == Some(1),
+            exons: transcript
+                .exons
+                .iter()
+                .map(|exon| (exon.start, exon.end))
+                .collect(),
+            cds: transcript
+                .translation
+                .iter()
+                .map(|translation| (translation.start, translation.end))
+                .collect(),
+        }
+        .to_gene(contig_index))
+    }
+}
+
+/// Gene track queried from the Ensembl REST API.
+/// Genes are loaded in windows around queried regions. Each gene is drawn with its canonical transcript.
+#[derive(Debug)]
+pub struct EnsemblApiTrackService {
+    client: Client,
+
+    /// Contig index -> the last loaded window.
+    cache: TrackCache,
+}
+
+impl EnsemblApiTrackService {
+    pub fn new() -> Result<Self, TGVError> {
+        Ok(Self {
+            client: Client::new(),
+            cache: TrackCache::default(),
+        })
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str, context: &str) -> Result<T, TGVError> {
+        log::info!("HTTP request: method=GET url={} context={}", url, context);
+        let started = Instant::now();
+        let response = self.client.get(url).send().await?;
+        log::info!(
+            "HTTP response: status={} url={} context={} elapsed_ms={}",
+            response.status(),
+            url,
+            context,
+            started.elapsed().as_millis()
+        );
+        if !response.status().is_success() {
+            return Err(TGVError::IOError(format!(
+                "Ensembl REST request failed with status {}: {}",
+                response.status(),
+                url
+            )));
+        }
+        Ok(response.json::<T>().await?)
+    }
+
+    /// Look up a gene symbol. Only a missing symbol is "not found": a 404, Ensembl's 400 for a symbol without a
+    /// lookup, or an empty response. Network and other HTTP errors are returned as is.
+    async fn lookup_symbol(
+        &self,
+        url: &str,
+        gene_name: &str,
+    ) -> Result<EnsemblLookupGene, TGVError> {
+        let context = "Ensembl gene lookup";
+        log::info!("HTTP request: method=GET url={} context={}", url, context);
+        let started = Instant::now();
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        log::info!(
+            "HTTP response: status={} url={} context={} elapsed_ms={}",
+            status,
+            url,
+            context,
+            started.elapsed().as_millis()
+        );
+        let body = response.text().await?;
+
+        let not_found = status == StatusCode::NOT_FOUND
+            || (status == StatusCode::BAD_REQUEST && body.contains("No valid lookup found"))
+            || (status.is_success() && matches!(body.trim(), "" | "{}" | "[]" | "null"));
+        if not_found {
+            return Err(TGVError::IOError(format!("Gene {} not found", gene_name)));
+        }
+        if !status.is_success() {
+            return Err(TGVError::IOError(format!(
+                "Ensembl REST request failed with status {}: {}",
+                status, url
+            )));
+        }
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    async fn query_overlap(
+        &self,
+        reference: &Reference,
+        contig_name: &str,
+        start: u64,
+        end: u64,
+        feature_types: &[&str],
+    ) -> Result<Vec<EnsemblFeature>, TGVError> {
+        let (server, species) = ensembl_species(reference)?;
+        let url = format!(
+            "{}/overlap/region/{}/{}:{}-{}?{};content-type=application/json",
+            server,
+            species,
+            ensembl_contig_name(contig_name),
+            start,
+            end,
+            feature_types
+                .iter()
+                .map(|feature_type| format!("feature={}", feature_type))
+                .collect::<Vec<_>>()
+                .join(";")
+        );
+        self.get_json(&url, "Ensembl overlap region").await
+    }
+
+    /// Load the window around a region to the cache, unless the cached window already covers it.
+    /// Regions wider than the Ensembl query limit are loaded around their center.
+    async fn query_window_if_not_cached(
+        &mut self,
+        reference: &Reference,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<&Track<Gene>>, TGVError> {
+        let contig_index = region.contig_index();
+        let Some(contig_name) = contig_header.try_get(contig_index)?.get_track_name() else {
+            return Ok(None); // Contig doesn't have track data
+        };
+        let window = Region {
+            focus: region.focus.clone(),
+            half_width: region
+                .half_width
+                .clamp(WINDOW_HALF_WIDTH, MAX_QUERY_HALF_WIDTH),
+        };
+
+        let cached = self
+            .cache
+            .tracks
+            .get(&contig_index)
+            .is_some_and(|track| track.has_complete_data(&loadable_region(region)));
+
+        if !cached {
+            let transcripts = self
+                .query_overlap(
+                    reference,
+                    contig_name,
+                    window.start(),
+                    window.end(),
+                    &["gene", "transcript"],
+                )
+                .await?;
+
+            // Exons and CDS are queried over the full span of the transcripts, so that transcripts crossing the
+            // window bounds are complete.
+            let span_start = transcripts
+                .iter()
+                .map(|feature| feature.start)
+                .fold(window.start(), u64::min);
+            let span_end = transcripts
+                .iter()
+                .map(|feature| feature.end)
+                .fold(window.end(), u64::max);
+            let (span_start, span_end) = if span_end - span_start < MAX_QUERY_HALF_WIDTH * 2 {
+                (span_start, span_end)
+            } else {
+                (window.start(), window.end())
+            };
+            let parts = self
+                .query_overlap(
+                    reference,
+                    contig_name,
+                    span_start,
+                    span_end,
+                    &["exon", "cds"],
+                )
+                .await?;
+
+            let genes = parse_overlap_features(
+                transcripts.into_iter().chain(parts).collect(),
+                contig_index,
+            )?;
+            log::debug!(
+                "Ensembl track response: reference={} contig={} start={} end={} genes={}",
+                reference,
+                contig_name,
+                window.start(),
+                window.end(),
+                genes.len()
+            );
+            self.cache.add_track(
+                contig_index,
+                Track::from_genes(genes, contig_index, (window.start(), window.end()))?,
+            );
+        }
+
+        Ok(self.cache.tracks.get(&contig_index))
+    }
+
+    /// Load the window starting (after = true) or ending (after = false) at a coordinate.
+    async fn query_window_from(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        after: bool,
+        contig_header: &ContigHeader,
+    ) -> Result<&Track<Gene>, TGVError> {
+        let position = if after {
+            coord.saturating_add(WINDOW_HALF_WIDTH)
+        } else {
+            coord.saturating_sub(WINDOW_HALF_WIDTH)
+        };
+        let region = Region {
+            focus: Focus {
+                contig_index,
+                position,
+            },
+            half_width: WINDOW_HALF_WIDTH,
+        };
+        self.query_window_if_not_cached(reference, &region, contig_header)
+            .await?
+            .ok_or(TGVError::StateError(format!(
+                "Contig {} (index = {}, aliases = {}) does not have track data.",
+                contig_header.contigs[contig_index].name,
+                contig_index,
+                contig_header.contigs[contig_index].aliases.join(",")
+            )))
+    }
+}
+
+#[async_trait]
+impl TrackService for EnsemblApiTrackService {
+    async fn close(&mut self) -> Result<(), TGVError> {
+        // reqwest client dones't need closing
+        Ok(())
+    }
+
+    async fn get_all_contigs(&mut self, reference: &Reference) -> Result<Vec<Contig>, TGVError> {
+        let (server, species) = ensembl_species(reference)?;
+        let url = format!(
+            "{}/info/assembly/{}?content-type=application/json",
+            server, species
+        );
+        let response: EnsemblAssemblyResponse =
+            self.get_json(&url, "Ensembl assembly info").await?;
+
+        // Scaffolds and patches are not listed.
+        let contigs = response
+            .top_level_region
+            .into_iter()
+            .filter(|region| region.coord_system == "chromosome")
+            .map(|region| {
+                let mut contig = Contig::new(&ucsc_contig_name(&region.name), Some(region.length));
+                if !contig.aliases.contains(&region.name) {
+                    contig.add_alias(&region.name);
+                }
+                contig
+            })
+            .collect();
+
+        Ok(Contig::contigs_sort(contigs))
+    }
+
+    async fn get_cytoband(
+        &mut self,
+        _reference: &Reference,
+        _contig_index: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Option<Cytoband>, TGVError> {
+        Ok(None)
+    }
+
+    async fn get_preferred_track_name(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Option<String>, TGVError> {
+        Ok(Some(TRACK_NAME.to_string()))
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        Ok(vec![TRACK_NAME.to_string()])
+    }
+
+    fn set_preferred_track_name(&mut self, _track_name: String) {
+        // Ensembl canonical transcripts are the only track.
+    }
+
+    async fn query_gene_track(
+        &mut self,
+        reference: &Reference,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<Track<Gene>, TGVError> {
+        let genes = self
+            .query_genes_overlapping(reference, region, contig_header)
+            .await?;
+
+        let loaded = loadable_region(region);
+        Track::from_genes(genes, region.contig_index(), (loaded.start(), loaded.end()))
+    }
+
+    async fn query_genes_overlapping(
+        &mut self,
+        reference: &Reference,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<Gene>, TGVError> {
+        Ok(self
+            .query_window_if_not_cached(reference, region, contig_header)
+            .await?
+            .map(|track| {
+                track
+                    .get_features_overlapping(region)
+                    .into_iter()
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn query_gene_covering(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Gene>, TGVError> {
+        let region = Region {
+            focus: Focus {
+                contig_index,
+                position: coord,
+            },
+            half_width: 0,
+        };
+        Ok(self
+            .query_window_if_not_cached(reference, &region, contig_header)
+            .await?
+            .and_then(|track| track.get_gene_at(coord))
+            .cloned())
+    }
+
+    async fn query_gene_name(
+        &mut self,
+        reference: &Reference,
+        gene_name: &str,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        if let Some(gene) = self.cache.get_gene(gene_name) {
+            return Ok(gene.clone());
+        }
+
+        let (server, species) = ensembl_species(reference)?;
+        let url = format!(
+            "{}/lookup/symbol/{}/{}?expand=1;content-type=application/json",
+            server, species, gene_name
+        );
+        let gene = self.lookup_symbol(&url, gene_name).await?;
+
+        gene.to_gene(contig_header.try_get_index_by_str(&gene.seq_region_name)?)
+    }
+
+    async fn query_gene_names_matching(
+        &mut self,
+        _reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        // The REST API has no name search. Only genes in loaded windows are matched.
+        Ok(self.cache.gene_names_matching(pattern, limit))
+    }
+
+    async fn query_k_genes_after(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_window_from(reference, contig_index, coord, true, contig_header)
+            .await?
+            .get_saturating_k_genes_after(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_genes_before(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_window_from(reference, contig_index, coord, false, contig_header)
+            .await?
+            .get_saturating_k_genes_before(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_exons_after(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_window_from(reference, contig_index, coord, true, contig_header)
+            .await?
+            .get_saturating_k_exons_after(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+
+    async fn query_k_exons_before(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_window_from(reference, contig_index, coord, false, contig_header)
+            .await?
+            .get_saturating_k_exons_before(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    /// Hand-written fixtures in the shape of Ensembl REST responses (overlap/region and lookup/symbol with expand=1),
+    /// with made-up IDs and coordinates. They were not captured from the API: it was unreachable when they were
+    /// written.
+    fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!(
+            "{}/../tgv/tests/data/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(serde_json::json!(1), Some(Strand::Forward))]
+    #[case(serde_json::json!(-1), Some(Strand::Reverse))]
+    #[case(serde_json::json!("+"), Some(Strand::Forward))]
+    #[case(serde_json::json!("-"), Some(Strand::Reverse))]
+    #[case(serde_json::json!(0), None)]
+    #[case(serde_json::json!("."), None)]
+    fn test_parse_strand(#[case] value: serde_json::Value, #[case] expected: Option<Strand>) {
+        assert_eq!(parse_strand(&value).ok(), expected);
+    }
+
+    #[rstest]
+    #[case("chr1", "1")]
+    #[case("chrX", "X")]
+    #[case("chrM", "MT")]
+    #[case("7", "7")]
+    fn test_ensembl_contig_name(#[case] contig_name: &str, #[case] expected: &str) {
+        assert_eq!(ensembl_contig_name(contig_name), expected);
+    }
+
+    #[rstest]
+    #[case("1", "chr1")]
+    #[case("MT", "chrM")]
+    fn test_ucsc_contig_name(#[case] seq_region_name: &str, #[case] expected: &str) {
+        assert_eq!(ucsc_contig_name(seq_region_name), expected);
+    }
+
+    #[test]
+    fn parse_overlap_fixture() {
+        let features: Vec<EnsemblFeature> =
+            serde_json::from_str(&fixture("ensembl_overlap.json")).unwrap();
+        let mut genes = parse_overlap_features(features, 3).unwrap();
+        genes.sort_by_key(|gene| gene.transcription_start);
+
+        // GENEA keeps only its canonical transcript. GENEB has no canonical transcript and keeps all.
+        assert_eq!(
+            genes,
+            vec![
+                Gene {
+                    id: "ENST00000000011".to_string(),
+                    name: "GENEA".to_string(),
+                    strand: Strand::Forward,
+                    contig_index: 3,
+                    transcription_start: 1001,
+                    transcription_end: 2200,
+                    cds_start: 1051,
+                    cds_end: 2100,
+                    has_exons: true,
+                    exon_starts: vec![1001, 1501, 2001],
+                    exon_ends: vec![1100, 1700, 2200],
+                },
+                Gene {
+                    id: "ENST00000000021".to_string(),
+                    name: "GENEB".to_string(),
+                    strand: Strand::Reverse,
+                    contig_index: 3,
+                    transcription_start: 3001,
+                    transcription_end: 3500,
+                    cds_start: 3501,
+                    cds_end: 3500,
+                    has_exons: true,
+                    exon_starts: vec![3001, 3401],
+                    exon_ends: vec![3100, 3500],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_overlap_features_deduplicates_windows() {
+        let features: Vec<EnsemblFeature> =
+            serde_json::from_str(&fixture("ensembl_overlap.json")).unwrap();
+        let repeated: Vec<EnsemblFeature> =
+            serde_json::from_str(&fixture("ensembl_overlap.json")).unwrap();
+        let genes =
+            parse_overlap_features(features.into_iter().chain(repeated).collect(), 0).unwrap();
+
+        assert_eq!(genes.len(), 2);
+        assert_eq!(genes[0].exon_starts, vec![1001, 1501, 2001]);
+    }
+
+    #[test]
+    fn parse_lookup_fixture() {
+        let gene: EnsemblLookupGene =
+            serde_json::from_str(&fixture("ensembl_lookup.json")).unwrap();
+        assert_eq!(gene.seq_region_name, "7");
+
+        let gene = gene.to_gene(6).unwrap();
+        assert_eq!(gene.id, "ENST00000000011");
+        assert_eq!(gene.name, "GENEA");
+        assert_eq!(gene.contig_index, 6);
+        assert_eq!((gene.cds_start, gene.cds_end), (1051, 2100));
+        assert_eq!(gene.exon_starts, vec![1001, 1501, 2001]);
+    }
+
+    #[test]
+    fn unsupported_reference() {
+        assert!(ensembl_species(&Reference::Hg38).is_ok());
+        assert!(
+            ensembl_species(&Reference::UcscAccession("GCF_000001405.40".to_string())).is_err()
+        );
+    }
+
+    /// Serve one response with the status line and body. Return the URL.
+    async fn serve_once(status: &'static str, body: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/lookup", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 4096];
+            let _ = socket.read(&mut buffer).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        url
+    }
+
+    #[rstest]
+    #[case("404 Not Found", "")]
+    #[case(
+        "400 Bad Request",
+        r#"{"error":"No valid lookup found for symbol NOTAGENE"}"#
+    )]
+    #[case("200 OK", "{}")]
+    #[tokio::test]
+    async fn missing_symbol_is_not_found(#[case] status: &'static str, #[case] body: &'static str) {
+        let url = serve_once(status, body).await;
+        let error = EnsemblApiTrackService::new()
+            .unwrap()
+            .lookup_symbol(&url, "NOTAGENE")
+            .await
+            .unwrap_err();
+        assert_eq!(error.to_string(), "IO Error: Gene NOTAGENE not found");
+    }
+
+    #[tokio::test]
+    async fn lookup_errors_other_than_a_missing_symbol_are_returned() {
+        let service = EnsemblApiTrackService::new().unwrap();
+
+        let url = serve_once("503 Service Unavailable", "").await;
+        let error = service.lookup_symbol(&url, "GENEA").await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "IO Error: Ensembl REST request failed with status 503 Service Unavailable: {url}"
+            )
+        );
+
+        // Nothing listens on the discard port.
+        let error = service
+            .lookup_symbol("http://127.0.0.1:9/lookup", "GENEA")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, TGVError::UcscApiIOError(_)), "{error}");
+    }
+}
@@ -0,0 +1,690 @@
+use crate::tracks::{TrackCache, TrackService, rank_gene_names};
+use crate::{
+    contig_header::{Contig, ContigHeader},
+    cytoband::Cytoband,
+    error::TGVError,
+    feature::{Gene, SubGeneFeature},
+    intervals::{GenomeInterval, Region},
+    reference::Reference,
+    strand::Strand,
+    track::Track,
+};
+use async_trait::async_trait;
+use flate2::read::MultiGzDecoder;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+/// Feature types that make a transcript's exons.
+const EXON_TYPES: [&str; 3] = ["exon", "five_prime_UTR", "three_prime_UTR"];
+
+/// Feature types that make a transcript's coding region. GTF CDS records exclude the stop codon.
+const CDS_TYPES: [&str; 3] = ["CDS", "start_codon", "stop_codon"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GffFormat {
+    /// Attributes are `key "value";` pairs. Exons are grouped by transcript_id.
+    Gtf,
+
+    /// Attributes are `key=value;` pairs. Exons are grouped by Parent.
+    Gff3,
+}
+
+impl GffFormat {
+    /// Format by the file extension, e.g. `.gtf` or `.gtf.gz`. Other files are GFF3.
+    fn of(path: &str) -> Self {
+        if without_gz_extension(&path.to_lowercase()).ends_with(".gtf") {
+            GffFormat::Gtf
+        } else {
+            GffFormat::Gff3
+        }
+    }
+
+    fn parse_attributes(&self, field: &str) -> HashMap<String, String> {
+        field
+            .split(';')
+            .map(str::trim)
+            .filter(|attribute| !attribute.is_empty())
+            .filter_map(|attribute| match self {
+                GffFormat::Gtf => attribute
+                    .split_once(' ')
+                    .map(|(key, value)| (key, value.trim().trim_matches('"'))),
+                GffFormat::Gff3 => attribute.split_once('='),
+            })
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+/// One line of a GTF / GFF3 file. Coordinates are 1-based, inclusive.
+#[derive(Debug)]
+struct GffRecord {
+    seqid: String,
+    feature_type: String,
+    start: u64,
+    end: u64,
+    strand: Strand,
+    attributes: HashMap<String, String>,
+}
+
+impl GffRecord {
+    /// None for comments, pragmas, and blank lines.
+    fn parse(line: &str, format: GffFormat) -> Result<Option<Self>, TGVError> {
+        if line.trim().is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() < 9 {
+            return Err(TGVError::ParsingError(format!(
+                "GTF/GFF3 line has {} fields, expected 9: {}",
+                fields.len(),
+                line
+            )));
+        }
+        let coordinate = |field: &str| {
+            field.parse::<u64>().map_err(|_| {
+                TGVError::ParsingError(format!("Failed to parse GTF/GFF3 coordinate {}", field))
+            })
+        };
+
+        Ok(Some(Self {
+            seqid: fields[0].to_string(),
+            feature_type: fields[2].to_string(),
+            start: coordinate(fields[3])?,
+            end: coordinate(fields[4])?,
+            // Unstranded features are drawn on the forward strand.
+            strand: Strand::from_str(fields[6].to_string()).unwrap_or(Strand::Forward),
+            attributes: format.parse_attributes(fields[8]),
+        }))
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .get(key)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Gene symbol of a gene or transcript record.
+    fn gene_name(&self) -> Option<&str> {
+        self.attribute("gene_name")
+            .or(self.attribute("gene"))
+            .or(self.attribute("Name"))
+    }
+
+    /// Transcripts this exon / CDS belongs to.
+    fn transcript_ids(&self, format: GffFormat) -> Vec<&str> {
+        match format {
+            GffFormat::Gtf => self.attribute("transcript_id").into_iter().collect(),
+            GffFormat::Gff3 => self
+                .attribute("Parent")
+                .map(|parents| parents.split(',').collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Exons and CDS segments collected for one transcript.
+#[derive(Debug)]
+struct TranscriptParts {
+    seqid: String,
+    strand: Strand,
+    exons: Vec<(u64, u64)>,
+    cds: Vec<(u64, u64)>,
+
+    /// Gene symbol from the exon / CDS records (GTF gene_name or GFF3 gene).
+    gene_name: Option<String>,
+}
+
+impl TranscriptParts {
+    /// contig_index is set when the contig's track is loaded.
+    fn to_gene(&self, id: &str, name: String) -> Gene {
+        // CDS and UTR records are parts of exons. Unioning them covers files without exon records (e.g. prokaryotic
+        // CDS-only genes).
+        let exons = merge_segments(self.exons.iter().chain(self.cds.iter()).copied().collect());
+        let transcription_start = exons.iter().map(|exon| exon.0).min().unwrap_or_default();
+        let transcription_end = exons.iter().map(|exon| exon.1).max().unwrap_or_default();
+
+        // Non-coding transcripts follow the UCSC convention of an empty CDS at the transcript end.
+        let (cds_start, cds_end) = match (
+            self.cds.iter().map(|cds| cds.0).min(),
+            self.cds.iter().map(|cds| cds.1).max(),
+        ) {
+            (Some(cds_start), Some(cds_end)) => (cds_start, cds_end),
+            _ => (transcription_end + 1, transcription_end),
+        };
+
+        Gene {
+            id: id.to_string(),
+            name,
+            strand: self.strand.clone(),
+            contig_index: 0,
+            transcription_start,
+            transcription_end,
+            cds_start,
+            cds_end,
+            has_exons: !exons.is_empty(),
+            exon_starts: exons.iter().map(|exon| exon.0).collect(),
+            exon_ends: exons.iter().map(|exon| exon.1).collect(),
+        }
+    }
+}
+
+/// Group exon and CDS records into one Gene per transcript. Return: contig name -> genes.
+fn parse_genes(content: &str, format: GffFormat) -> Result<HashMap<String, Vec<Gene>>, TGVError> {
+    let mut parts: HashMap<String, TranscriptParts> = HashMap::new();
+    let mut transcript_order = Vec::new();
+
+    // GFF3 ID -> record, for resolving transcript and gene names.
+    let mut records_by_id: HashMap<String, GffRecord> = HashMap::new();
+
+    for line in content.lines() {
+        let Some(record) = GffRecord::parse(line, format)? else {
+            continue;
+        };
+
+        let is_exon = EXON_TYPES.contains(&record.feature_type.as_str());
+        let is_cds = CDS_TYPES.contains(&record.feature_type.as_str());
+        if is_exon || is_cds {
+            for transcript_id in record.transcript_ids(format) {
+                let transcript = parts.entry(transcript_id.to_string()).or_insert_with(|| {
+                    transcript_order.push(transcript_id.to_string());
+                    TranscriptParts {
+                        seqid: record.seqid.clone(),
+                        strand: record.strand.clone(),
+                        exons: Vec::new(),
+                        cds: Vec::new(),
+                        gene_name: None,
+                    }
+                });
+                if is_exon {
+                    transcript.exons.push((record.start, record.end));
+                } else {
+                    transcript.cds.push((record.start, record.end));
+                }
+                if transcript.gene_name.is_none() {
+                    transcript.gene_name = record
+                        .attribute("gene_name")
+                        .or(record.attribute("gene"))
+                        .map(str::to_string);
+                }
+            }
+        }
+
+        if let Some(id) = record.attribute("ID") {
+            records_by_id.insert(id.to_string(), record);
+        }
+    }
+
+    let mut genes: HashMap<String, Vec<Gene>> = HashMap::new();
+    for transcript_id in transcript_order {
+        let transcript = &parts[&transcript_id];
+        let name = transcript
+            .gene_name
+            .clone()
+            .or_else(|| gff3_gene_name(&records_by_id, &transcript_id))
+            .unwrap_or(transcript_id.clone());
+
+        genes
+            .entry(transcript.seqid.clone())
+            .or_default()
+            .push(transcript.to_gene(&transcript_id, name));
+    }
+
+    Ok(genes)
+}
+
+/// Gene symbol of a GFF3 transcript: from its parent gene record, or from the transcript record itself.
+fn gff3_gene_name(
+    records_by_id: &HashMap<String, GffRecord>,
+    transcript_id: &str,
+) -> Option<String> {
+    let transcript = records_by_id.get(transcript_id)?;
+    transcript
+        .attribute("Parent")
+        .and_then(|parent| records_by_id.get(parent))
+        .and_then(|gene| gene.gene_name().or(gene.attribute("ID")))
+        .or(transcript.gene_name())
+        .map(str::to_string)
+}
+
+/// Path without a trailing `.gz`, in any case.
+fn without_gz_extension(path: &str) -> &str {
+    match path.len().checked_sub(3) {
+        Some(i) if path.is_char_boundary(i) && path[i..].eq_ignore_ascii_case(".gz") => &path[..i],
+        _ => path,
+    }
+}
+
+/// Content of an annotation file. Gzip (and bgzip) files are decompressed, by their magic bytes.
+fn read_annotation(path: &str) -> std::io::Result<String> {
+    let bytes = std::fs::read(path)?;
+    if !bytes.starts_with(&[0x1f, 0x8b]) {
+        return String::from_utf8(bytes)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e));
+    }
+    let mut content = String::new();
+    MultiGzDecoder::new(bytes.as_slice()).read_to_string(&mut content)?;
+    Ok(content)
+}
+
+/// Sorted, with overlapping and adjacent segments merged. 1-based, inclusive.
+fn merge_segments(mut segments: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    segments.sort();
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in segments {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = u64::max(last.1, end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Gene track parsed from a local GTF or GFF3 file.
+/// The whole file is read on startup. Transcripts are grouped from exon and CDS records, one Gene per transcript.
+#[derive(Debug)]
+pub struct GffTrackService {
+    path: String,
+
+    /// Contig name in the file -> genes. Contig indexes are not set.
+    genes_by_chrom: HashMap<String, Vec<Gene>>,
+
+    cache: TrackCache,
+}
+
+impl GffTrackService {
+    pub fn new(path: &str) -> Result<Self, TGVError> {
+        let content = read_annotation(path).map_err(|e| {
+            TGVError::IOError(format!("Cannot read gene annotation {}: {}", path, e))
+        })?;
+        let genes_by_chrom = parse_genes(&content, GffFormat::of(path))?;
+        log::info!(
+            "GTF/GFF3 loaded: path={} contigs={} transcripts={}",
+            path,
+            genes_by_chrom.len(),
+            genes_by_chrom.values().map(Vec::len).sum::<usize>()
+        );
+
+        Ok(Self {
+            path: path.to_string(),
+            genes_by_chrom,
+            cache: TrackCache::default(),
+        })
+    }
+
+    /// Track name shown in `:track list`: the file name without the extension.
+    fn track_name(&self) -> String {
+        Path::new(without_gz_extension(&self.path))
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or(self.path.clone())
+    }
+
+    /// Genes of a contig in the file, without contig indexes. None if the contig has no genes in the file.
+    fn chrom_genes(&self, contig: &Contig) -> Option<&Vec<Gene>> {
+        std::iter::once(&contig.name)
+            .chain(contig.aliases.iter())
+            .find_map(|name| self.genes_by_chrom.get(name))
+    }
+
+    /// Genes of a contig, with contig indexes set. Empty if the contig has no genes in the file.
+    fn contig_genes(
+        &self,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<Gene>, TGVError> {
+        Ok(self
+            .chrom_genes(contig_header.try_get(contig_index)?)
+            .map(|genes| {
+                genes
+                    .iter()
+                    .cloned()
+                    .map(|gene| Gene {
+                        contig_index,
+                        ..gene
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Load the whole contig to the cache.
+    fn query_track_if_not_cached(
+        &mut self,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<&Track<Gene>, TGVError> {
+        if !self.cache.contig_quried(&contig_index) {
+            let genes = self.contig_genes(contig_index, contig_header)?;
+            self.cache.add_track(
+                contig_index,
+                Track::from_genes(genes, contig_index, (1, u64::MAX))?,
+            );
+        }
+
+        self.cache
+            .tracks
+            .get(&contig_index)
+            .ok_or(TGVError::IOError(format!(
+                "Track not found for contig {}",
+                contig_index
+            )))
+    }
+}
+
+#[async_trait]
+impl TrackService for GffTrackService {
+    async fn close(&mut self) -> Result<(), TGVError> {
+        Ok(())
+    }
+
+    async fn get_all_contigs(&mut self, _reference: &Reference) -> Result<Vec<Contig>, TGVError> {
+        Ok(self
+            .genes_by_chrom
+            .keys()
+            .map(|name| Contig::new(name, None))
+            .collect())
+    }
+
+    async fn get_cytoband(
+        &mut self,
+        _reference: &Reference,
+        _contig_index: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Option<Cytoband>, TGVError> {
+        Ok(None)
+    }
+
+    async fn get_preferred_track_name(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Option<String>, TGVError> {
+        Ok(Some(self.track_name()))
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        _reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        Ok(vec![self.track_name()])
+    }
+
+    fn set_preferred_track_name(&mut self, _track_name: String) {
+        // The GTF/GFF3 file is the only track.
+    }
+
+    async fn query_genes_overlapping(
+        &mut self,
+        _reference: &Reference,
+        region: &Region,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<Gene>, TGVError> {
+        Ok(self
+            .query_track_if_not_cached(region.contig_index(), contig_header)?
+            .genes()
+            .iter()
+            .filter(|gene| gene.start() <= region.end() && gene.end() >= region.start())
+            .cloned()
+            .collect())
+    }
+
+    async fn query_gene_covering(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Gene>, TGVError> {
+        Ok(self
+            .query_track_if_not_cached(contig_index, contig_header)?
+            .genes()
+            .iter()
+            .find(|gene| gene.start() <= coord && gene.end() >= coord)
+            .cloned())
+    }
+
+    async fn query_gene_name(
+        &mut self,
+        _reference: &Reference,
+        gene_name: &str,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        for (contig_index, contig) in contig_header.contigs.iter().enumerate() {
+            if self.chrom_genes(contig).is_none() {
+                continue;
+            }
+            if let Some(gene) = self
+                .query_track_if_not_cached(contig_index, contig_header)?
+                .gene_by_name(gene_name)
+            {
+                return Ok(gene.clone());
+            }
+        }
+
+        Err(TGVError::IOError(format!("Gene {} not found", gene_name)))
+    }
+
+    async fn query_gene_names_matching(
+        &mut self,
+        _reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        Ok(rank_gene_names(
+            contig_header
+                .contigs
+                .iter()
+                .filter_map(|contig| self.chrom_genes(contig))
+                .flatten()
+                .map(|gene| gene.name.as_str()),
+            pattern,
+            limit,
+        ))
+    }
+
+    async fn query_k_genes_after(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?
+            .get_saturating_k_genes_after(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_genes_before(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Gene, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?
+            .get_saturating_k_genes_before(coord, k)
+            .ok_or(TGVError::IOError("No genes found".to_string()))
+            .cloned()
+    }
+
+    async fn query_k_exons_after(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?
+            .get_saturating_k_exons_after(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+
+    async fn query_k_exons_before(
+        &mut self,
+        _reference: &Reference,
+        contig_index: usize,
+        coord: u64,
+        k: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<SubGeneFeature, TGVError> {
+        self.query_track_if_not_cached(contig_index, contig_header)?
+            .get_saturating_k_exons_before(coord, k)
+            .ok_or(TGVError::IOError("No exons found".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const GFF3: &str = "##gff-version 3
+chr1\ttest\tgene\t101\t500\t.\t-\t.\tID=gene1;Name=ABC
+chr1\ttest\tmRNA\t101\t500\t.\t-\t.\tID=tx1;Parent=gene1
+chr1\ttest\texon\t101\t200\t.\t-\t.\tParent=tx1
+chr1\ttest\texon\t301\t500\t.\t-\t.\tParent=tx1
+chr1\ttest\tCDS\t151\t200\t.\t-\t2\tParent=tx1
+chr1\ttest\tCDS\t301\t400\t.\t-\t0\tParent=tx1
+chr1\ttest\tncRNA\t601\t700\t.\t+\t.\tID=tx2;gene=XYZ
+chr1\ttest\texon\t601\t700\t.\t+\t.\tParent=tx2
+chr2\ttest\tCDS\t11\t40\t.\t+\t0\tParent=gene3
+";
+
+    const GTF: &str = "chr1\ttest\texon\t101\t200\t.\t+\t.\tgene_id \"g1\"; transcript_id \"t1\"; gene_name \"ABC\";
+chr1\ttest\tCDS\t151\t200\t.\t+\t0\tgene_id \"g1\"; transcript_id \"t1\"; gene_name \"ABC\";
+chr1\ttest\texon\t301\t500\t.\t+\t.\tgene_id \"g1\"; transcript_id \"t1\"; gene_name \"ABC\";
+chr1\ttest\tCDS\t301\t397\t.\t+\t0\tgene_id \"g1\"; transcript_id \"t1\"; gene_name \"ABC\";
+chr1\ttest\tstop_codon\t398\t400\t.\t+\t0\tgene_id \"g1\"; transcript_id \"t1\"; gene_name \"ABC\";
+";
+
+    fn gene(id: &str, name: &str, strand: Strand, cds: (u64, u64), exons: &[(u64, u64)]) -> Gene {
+        Gene {
+            id: id.to_string(),
+            name: name.to_string(),
+            strand,
+            contig_index: 0,
+            transcription_start: exons.first().unwrap().0,
+            transcription_end: exons.last().unwrap().1,
+            cds_start: cds.0,
+            cds_end: cds.1,
+            exon_starts: exons.iter().map(|exon| exon.0).collect(),
+            exon_ends: exons.iter().map(|exon| exon.1).collect(),
+            has_exons: true,
+        }
+    }
+
+    #[test]
+    fn test_parse_gff3_genes() {
+        let genes = parse_genes(GFF3, GffFormat::Gff3).unwrap();
+
+        assert_eq!(
+            genes["chr1"],
+            vec![
+                gene(
+                    "tx1",
+                    "ABC",
+                    Strand::Reverse,
+                    (151, 400),
+                    &[(101, 200), (301, 500)]
+                ),
+                // Non-coding: empty CDS at the transcript end.
+                gene("tx2", "XYZ", Strand::Forward, (701, 700), &[(601, 700)]),
+            ]
+        );
+        // CDS-only genes without a gene record use the CDS as the exon and are named by the parent ID.
+        assert_eq!(
+            genes["chr2"],
+            vec![gene(
+                "gene3",
+                "gene3",
+                Strand::Forward,
+                (11, 40),
+                &[(11, 40)]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_gtf_genes() {
+        let genes = parse_genes(GTF, GffFormat::Gtf).unwrap();
+
+        // The stop codon is part of the CDS.
+        assert_eq!(
+            genes["chr1"],
+            vec![gene(
+                "t1",
+                "ABC",
+                Strand::Forward,
+                (151, 400),
+                &[(101, 200), (301, 500)]
+            )]
+        );
+    }
+
+    #[rstest]
+    #[case(vec![(301, 400), (101, 200)], vec![(101, 200), (301, 400)])]
+    #[case(vec![(101, 200), (150, 250)], vec![(101, 250)])]
+    #[case(vec![(101, 200), (201, 300)], vec![(101, 300)])]
+    #[case(vec![], vec![])]
+    fn test_merge_segments(#[case] segments: Vec<(u64, u64)>, #[case] expected: Vec<(u64, u64)>) {
+        assert_eq!(merge_segments(segments), expected);
+    }
+
+    #[rstest]
+    #[case(GffFormat::Gff3, "ID=tx1;Parent=gene1,gene2", &[("ID", "tx1"), ("Parent", "gene1,gene2")])]
+    #[case(GffFormat::Gtf, "gene_id \"g1\"; transcript_id \"t1\";", &[("gene_id", "g1"), ("transcript_id", "t1")])]
+    fn test_parse_attributes(
+        #[case] format: GffFormat,
+        #[case] field: &str,
+        #[case] expected: &[(&str, &str)],
+    ) {
+        assert_eq!(
+            format.parse_attributes(field),
+            expected
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>()
+        );
+    }
+
+    #[rstest]
+    #[case("genes.gtf", GffFormat::Gtf)]
+    #[case("genes.GTF.gz", GffFormat::Gtf)]
+    #[case("genes.gff3", GffFormat::Gff3)]
+    #[case("genes.gff3.gz", GffFormat::Gff3)]
+    #[case("genes.gz", GffFormat::Gff3)]
+    fn test_gff_format_of(#[case] path: &str, #[case] expected: GffFormat) {
+        assert_eq!(GffFormat::of(path), expected);
+    }
+
+    #[test]
+    fn gzipped_gtf_is_decompressed() {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("genes.gtf.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(GTF.as_bytes()).unwrap();
+        std::fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let service = GffTrackService::new(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            service.genes_by_chrom,
+            parse_genes(GTF, GffFormat::Gtf).unwrap()
+        );
+        assert_eq!(service.track_name(), "genes");
+    }
+
+    #[test]
+    fn parse_genes_rejects_truncated_lines() {
+        assert!(parse_genes("chr1\ttest\texon\t101", GffFormat::Gff3).is_err());
+    }
+}
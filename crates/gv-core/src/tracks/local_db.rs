@@ -1,9 +1,10 @@
-use crate::tracks::{TRACK_PREFERENCES, TrackCache, TrackService};
+use crate::tracks::{TRACK_PREFERENCES, TrackCache, TrackService, escape_like};
 use crate::{
     contig_header::{Contig, ContigHeader},
     cytoband::{Cytoband, CytobandSegment},
     error::TGVError,
     feature::{Gene, SubGeneFeature},
+    gaps::AssemblyGap,
     intervals::GenomeInterval,
     intervals::Region,
     reference::Reference,
@@ -12,7 +13,7 @@ use crate::{
 };
 use async_trait::async_trait;
 use sqlx::{
-    Row,
+    Column, Row,
     sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions},
 };
 use std::collections::HashMap;
@@ -139,6 +140,46 @@ impl LocalDbTrackService {
     }
 }
 
+impl LocalDbTrackService {
+    /// chrom name -> aliases in the chromAlias table of a UCSC hub, which has one column per naming authority
+    /// (refseq, genbank, ucsc, ...) instead of the (alias, chrom, source) rows of the UCSC database. Empty if the table
+    /// is missing.
+    async fn hub_chrom_aliases(&self) -> HashMap<String, Vec<String>> {
+        let rows = match sqlx::query("SELECT * FROM chromAlias")
+            .fetch_all(&*self.pool)
+            .await
+        {
+            Ok(rows) => rows,
+            Err(error) => {
+                log::debug!("No hub chromAlias table: error={}", error);
+                return HashMap::new();
+            }
+        };
+
+        let mut aliases: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let Ok(chrom) = row.try_get::<String, _>("chrom") else {
+                continue;
+            };
+            let mut chrom_aliases = Vec::new();
+            for column in row.columns() {
+                if matches!(column.name(), "chrom" | "chromStart" | "chromEnd") {
+                    continue;
+                }
+                if let Ok(alias) = row.try_get::<String, _>(column.ordinal())
+                    && !alias.is_empty()
+                    && alias != chrom
+                    && !chrom_aliases.contains(&alias)
+                {
+                    chrom_aliases.push(alias);
+                }
+            }
+            aliases.entry(chrom).or_default().extend(chrom_aliases);
+        }
+        aliases
+    }
+}
+
 #[async_trait]
 impl TrackService for LocalDbTrackService {
     async fn close(&mut self) -> Result<(), TGVError> {
@@ -188,12 +229,20 @@ impl TrackService for LocalDbTrackService {
                     fallback_sql
                 );
                 let fallback_started = Instant::now();
-                let contigs = sqlx::query_as(fallback_sql).fetch_all(&*self.pool).await?;
+                let mut contigs: Vec<ContigRow> =
+                    sqlx::query_as(fallback_sql).fetch_all(&*self.pool).await?;
                 log::info!(
                     "Database query result: database=local-sqlite context=get all contigs without aliases rows={} elapsed_ms={}",
                     contigs.len(),
                     fallback_started.elapsed().as_millis()
                 );
+                // Hubs keep their aliases in a chromAlias table of another layout.
+                let hub_aliases = self.hub_chrom_aliases().await;
+                for row in contigs.iter_mut() {
+                    if let Some(aliases) = hub_aliases.get(&row.chrom) {
+                        row.aliases = aliases.join(",");
+                    }
+                }
                 contigs
             }
         };
@@ -258,6 +307,51 @@ impl TrackService for LocalDbTrackService {
         }))
     }
 
+    async fn get_gaps(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Vec<AssemblyGap>>, TGVError> {
+        let contig_name = match contig_header.try_get(contig_index)?.get_track_name() {
+            Some(contig_name) => contig_name,
+            None => return Ok(None),
+        };
+        // Caches downloaded by earlier versions have no gap table.
+        let table_sql = "SELECT name FROM sqlite_master WHERE type='table' AND name = 'gap'";
+        if sqlx::query(table_sql)
+            .fetch_optional(&*self.pool)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let sql = "SELECT chromStart, chromEnd, type FROM gap WHERE chrom = ? ORDER BY chromStart";
+        log::info!(
+            "Database query: database=local-sqlite sql=\"{}\" context=get gaps reference={} contig={}",
+            sql,
+            reference,
+            contig_name
+        );
+        let started = Instant::now();
+        let gap_rows: Vec<GapRow> = sqlx::query_as(sql)
+            .bind(contig_name)
+            .fetch_all(&*self.pool)
+            .await?;
+        log::info!(
+            "Database query result: database=local-sqlite context=get gaps rows={} elapsed_ms={}",
+            gap_rows.len(),
+            started.elapsed().as_millis()
+        );
+
+        Ok(Some(
+            gap_rows
+                .into_iter()
+                .map(|row| row.to_assembly_gap(contig_index))
+                .collect(),
+        ))
+    }
+
     async fn get_preferred_track_name(
         &mut self,
         reference: &Reference,
@@ -268,16 +362,31 @@ impl TrackService for LocalDbTrackService {
             _ => {}
         }
 
-        let sql = "SELECT name FROM sqlite_master WHERE type='table' AND name NOT IN ('chromInfo', 'chromAlias', 'cytoBandIdeo')";
+        let available_gene_tracks = self.get_available_track_names(reference).await?;
+
+        for pref in TRACK_PREFERENCES {
+            if available_gene_tracks.contains(&pref.to_string()) {
+                return Ok(Some(pref.to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        let sql = "SELECT name FROM sqlite_master WHERE type='table' AND name NOT IN ('chromInfo', 'chromAlias', 'cytoBandIdeo', 'gap')";
         log::info!(
-            "Database query: database=local-sqlite sql=\"{}\" context=get preferred track reference={}",
+            "Database query: database=local-sqlite sql=\"{}\" context=get available tracks reference={}",
             sql,
             reference
         );
         let started = Instant::now();
         let gene_track_rows = sqlx::query(sql).fetch_all(&*self.pool).await?;
         log::info!(
-            "Database query result: database=local-sqlite context=get preferred track rows={} elapsed_ms={}",
+            "Database query result: database=local-sqlite context=get available tracks rows={} elapsed_ms={}",
             gene_track_rows.len(),
             started.elapsed().as_millis()
         );
@@ -287,13 +396,11 @@ impl TrackService for LocalDbTrackService {
             .map(|row| row.try_get::<String, &str>("name"))
             .collect::<Result<Vec<String>, sqlx::Error>>()?;
 
-        for pref in TRACK_PREFERENCES {
-            if available_gene_tracks.contains(&pref.to_string()) {
-                return Ok(Some(pref.to_string()));
-            }
-        }
+        Ok(available_gene_tracks)
+    }
 
-        Ok(None)
+    fn set_preferred_track_name(&mut self, track_name: String) {
+        self.cache.use_track(track_name);
     }
 
     async fn query_genes_overlapping(
@@ -435,6 +542,48 @@ impl TrackService for LocalDbTrackService {
             .to_gene(contig_header)
     }
 
+    async fn query_gene_names_matching(
+        &mut self,
+        reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        let track_name = self.get_preferred_track_name_with_cache(reference).await?;
+        let sql = format!(
+            "SELECT DISTINCT name2
+            FROM {}
+            WHERE name2 LIKE ? ESCAPE '\\'
+            ORDER BY name2 LIKE ? ESCAPE '\\' DESC, name2
+            LIMIT ?",
+            track_name
+        );
+        log::info!(
+            "Database query: database=local-sqlite sql=\"{}\" context=query gene names matching reference={} track={} pattern={}",
+            sql,
+            reference,
+            track_name,
+            pattern
+        );
+        let started = Instant::now();
+        let gene_name_rows = sqlx::query(sql.as_str())
+            .bind(format!("%{}%", escape_like(pattern)))
+            .bind(format!("{}%", escape_like(pattern)))
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await?;
+        log::info!(
+            "Database query result: database=local-sqlite context=query gene names matching rows={} elapsed_ms={}",
+            gene_name_rows.len(),
+            started.elapsed().as_millis()
+        );
+
+        Ok(gene_name_rows
+            .into_iter()
+            .map(|row| row.try_get::<String, &str>("name2"))
+            .collect::<Result<Vec<String>, sqlx::Error>>()?)
+    }
+
     async fn query_k_genes_after(
         &mut self,
         reference: &Reference,
@@ -1,5 +1,9 @@
+mod bigbed;
 mod downloader;
+mod ensembl_api;
+mod gff;
 mod local_db;
+mod progress;
 pub mod schema;
 mod ucsc_api;
 mod ucsc_db;
@@ -7,10 +11,13 @@ mod ucsc_db;
 use crate::{
     contig_header::{Contig, ContigHeader},
     cytoband::Cytoband,
+    data_source::DataSource,
     error::TGVError,
     feature::{Gene, SubGeneFeature},
+    gaps::AssemblyGap,
     intervals::{GenomeInterval, Region},
     reference::Reference,
+    retry::{RetryPolicy, is_unreachable, send_request},
     settings::{BackendType, Settings},
     track::Track,
 };
@@ -18,11 +25,17 @@ use async_trait::async_trait;
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
+pub use bigbed::BigBedTrackService;
+pub(crate) use bigbed::blocks_to_exons;
+use downloader::BigBedConverter;
 pub use downloader::UCSCDownloader;
+pub use ensembl_api::EnsemblApiTrackService;
+pub use gff::GffTrackService;
 pub use local_db::LocalDbTrackService;
 pub use ucsc_api::UcscApiTrackService;
-pub use ucsc_db::UcscDbTrackService;
+pub use ucsc_db::{AssemblyFilter, UcscDbTrackService};
 
 /// Default track ordering when rendering the gene track.
 const TRACK_PREFERENCES: [&str; 5] = [
@@ -43,7 +56,7 @@ pub struct TrackCache {
     /// Contig index -> whether the track has been quried
     contig_queried: HashSet<usize>,
 
-    /// Gene name -> index in tracks.
+    /// Gene name -> contig index of the track in tracks.
     /// If the gene name is not found, the value is None.
     gene_name_lookup: HashMap<String, usize>,
 
@@ -82,16 +95,66 @@ impl TrackCache {
     }
 
     pub fn add_track(&mut self, contig_index: usize, track: Track<Gene>) {
-        for (i, gene) in track.genes().iter().enumerate() {
-            self.gene_name_lookup.insert(gene.name.clone(), i);
+        for gene in track.genes().iter() {
+            self.gene_name_lookup
+                .insert(gene.name.clone(), contig_index);
         }
         self.tracks.insert(contig_index, track);
         self.contig_queried.insert(contig_index);
     }
 
+    /// Names of cached genes containing a pattern. See rank_gene_names for the order.
+    pub fn gene_names_matching(&self, pattern: &str, limit: usize) -> Vec<String> {
+        rank_gene_names(
+            self.gene_name_lookup.keys().map(String::as_str),
+            pattern,
+            limit,
+        )
+    }
+
     pub fn set_preferred_track_name(&mut self, preferred_track_name: Option<String>) {
         self.preferred_track_name = Some(preferred_track_name);
     }
+
+    /// Switch to another track. Cached genes are dropped because they came from the previous track.
+    pub fn use_track(&mut self, track_name: String) {
+        *self = TrackCache::default();
+        self.set_preferred_track_name(Some(track_name));
+    }
+}
+
+/// Deduplicated names containing a pattern, case-insensitively.
+/// Names starting with the pattern come first. Ties are sorted by name. At most limit names are returned.
+pub(crate) fn rank_gene_names<'a>(
+    names: impl IntoIterator<Item = &'a str>,
+    pattern: &str,
+    limit: usize,
+) -> Vec<String> {
+    let pattern = pattern.to_lowercase();
+    let mut matches = names
+        .into_iter()
+        .filter_map(|name| {
+            let lowercase_name = name.to_lowercase();
+            lowercase_name
+                .find(&pattern)
+                .map(|position| (position != 0, name))
+        })
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.dedup();
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+/// Escape `%`, `_`, and the escape character `\` so that a pattern is matched literally by SQL LIKE.
+pub(crate) fn escape_like(pattern: &str) -> String {
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 #[async_trait]
@@ -112,6 +175,17 @@ pub trait TrackService {
         contig_header: &ContigHeader,
     ) -> Result<Option<Cytoband>, TGVError>;
 
+    /// Return the records of the assembly gap table on a contig, sorted by start. None if the reference has no gap
+    /// table.
+    async fn get_gaps(
+        &mut self,
+        _reference: &Reference,
+        _contig_index: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Option<Vec<AssemblyGap>>, TGVError> {
+        Ok(None)
+    }
+
     /// Return a Track<Gene> that covers a region.
     async fn query_gene_track(
         &mut self,
@@ -131,6 +205,15 @@ pub trait TrackService {
         reference: &Reference,
     ) -> Result<Option<String>, TGVError>;
 
+    /// Given a reference, return names of all available gene tracks.
+    async fn get_available_track_names(
+        &mut self,
+        reference: &Reference,
+    ) -> Result<Vec<String>, TGVError>;
+
+    /// Override the preferred track name. Cached gene data from the previous track are dropped.
+    fn set_preferred_track_name(&mut self, track_name: String);
+
     /// Return a list of genes that overlap with a region.
     async fn query_genes_overlapping(
         &mut self,
@@ -155,6 +238,16 @@ pub trait TrackService {
         contig_header: &ContigHeader,
     ) -> Result<Gene, TGVError>;
 
+    /// Return at most limit gene names containing a pattern, case-insensitively.
+    /// Names starting with the pattern come first, then names are sorted alphabetically.
+    async fn query_gene_names_matching(
+        &mut self,
+        reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError>;
+
     /// Return the k-th gene after a contig:coordinate.
     async fn query_k_genes_after(
         &mut self,
@@ -204,43 +297,96 @@ pub enum TrackServiceEnum {
     Api(UcscApiTrackService),
     Db(UcscDbTrackService),
     LocalDb(LocalDbTrackService),
+    BigBed(BigBedTrackService),
+    Gff(GffTrackService),
+    Ensembl(EnsemblApiTrackService),
 }
 
 impl TrackServiceEnum {
     pub async fn new(settings: &Settings) -> Result<Option<Self>, TGVError> {
+        if let Some(bigbed_path) = &settings.bigbed_path {
+            return Ok(Some(Self::BigBed(BigBedTrackService::new(bigbed_path)?)));
+        }
+
+        if let Some(gtf_path) = &settings.gtf_path {
+            return Ok(Some(Self::Gff(GffTrackService::new(gtf_path)?)));
+        }
+
         match (&settings.backend, &settings.reference) {
             (_, Reference::NoReference)
             | (_, Reference::BYOIndexedFasta(_))
             | (_, Reference::BYOTwoBit(_)) => Ok(None),
-            (BackendType::Ucsc, Reference::UcscAccession(_)) => {
-                Ok(Some(Self::Api(UcscApiTrackService::new()?)))
-            }
-            (BackendType::Ucsc, _) => Ok(Some(Self::Db(
-                UcscDbTrackService::new(&settings.reference, &settings.ucsc_host).await?,
-            ))),
+            (BackendType::Ensembl, _) => Ok(Some(Self::Ensembl(EnsemblApiTrackService::new()?))),
+            (BackendType::Ucsc, _) => Ok(Some(Self::new_ucsc(settings).await?)),
             (BackendType::Local, _) => Ok(Some(TrackServiceEnum::LocalDb(
                 LocalDbTrackService::new(&settings.reference, &settings.cache_dir).await?,
             ))),
             (BackendType::Default, reference) => {
-                // If the local cache is available, use the local cache.
-                // Otherwise, use the UCSC DB / API.
-                match LocalDbTrackService::new(&settings.reference, &settings.cache_dir).await {
-                    Ok(ts) => Ok(Some(TrackServiceEnum::LocalDb(ts))),
-                    Err(TGVError::IOError(_e)) => match reference {
-                        Reference::UcscAccession(_) => {
-                            Ok(Some(TrackServiceEnum::Api(UcscApiTrackService::new()?)))
-                        }
-                        _ => Ok(Some(TrackServiceEnum::Db(
-                            UcscDbTrackService::new(&settings.reference, &settings.ucsc_host)
-                                .await?,
-                        ))),
-                    },
-
-                    Err(e) => Err(e),
-                }
+                let (service, _) = settings
+                    .source_preference
+                    .open(
+                        reference,
+                        settings.cache_downloaded(),
+                        |source| async move {
+                            match source {
+                                DataSource::Cache => Ok(TrackServiceEnum::LocalDb(
+                                    LocalDbTrackService::new(reference, &settings.cache_dir)
+                                        .await?,
+                                )),
+                                DataSource::Ucsc | DataSource::Ensembl => {
+                                    // API requests are made on demand. Check that the API is reachable so that the
+                                    // cache can be used instead.
+                                    if matches!(reference, Reference::UcscAccession(_)) {
+                                        settings.ucsc_host.probe_api().await?;
+                                    }
+                                    Self::new_ucsc(settings).await
+                                }
+                            }
+                        },
+                    )
+                    .await?;
+                Ok(Some(service))
             }
         }
     }
+
+    /// UCSC API service for GenArk accessions, and UCSC MySQL service for other genomes.
+    async fn new_ucsc(settings: &Settings) -> Result<Self, TGVError> {
+        match &settings.reference {
+            Reference::UcscAccession(_) => Ok(Self::Api(UcscApiTrackService::new(
+                &settings.ucsc_host,
+                settings.track_cache.then_some(settings.cache_dir.as_str()),
+                settings.retry,
+            )?)),
+            _ => Ok(Self::Db(
+                UcscDbTrackService::new(&settings.reference, &settings.ucsc_host, settings.retry)
+                    .await?,
+            )),
+        }
+    }
+
+    /// Where the service serves gene tracks from. None for annotation files.
+    pub fn data_source(&self) -> Option<DataSource> {
+        match self {
+            TrackServiceEnum::LocalDb(_) => Some(DataSource::Cache),
+            TrackServiceEnum::Api(_) | TrackServiceEnum::Db(_) => Some(DataSource::Ucsc),
+            TrackServiceEnum::Ensembl(_) => Some(DataSource::Ensembl),
+            TrackServiceEnum::BigBed(_) | TrackServiceEnum::Gff(_) => None,
+        }
+    }
+
+    /// The UCSC MySQL host in use, if the service queries UCSC MySQL.
+    pub fn ucsc_host(&self) -> Option<&UcscHost> {
+        match self {
+            TrackServiceEnum::Db(service) => Some(service.host()),
+            TrackServiceEnum::Api(_)
+            | TrackServiceEnum::LocalDb(_)
+            | TrackServiceEnum::BigBed(_)
+            | TrackServiceEnum::Gff(_)
+            | TrackServiceEnum::Ensembl(_) => None,
+        }
+    }
+
     /// Return a map of: contig name -> 2bit file basename, if available.
     /// If not available, the value is None.
     pub async fn get_contig_2bit_file_lookup(
@@ -262,6 +408,16 @@ impl TrackServiceEnum {
                     .get_contig_2bit_file_lookup(reference, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(_) => Err(TGVError::IOError(
+                "get_contig_2bit_file_lookup is not supported for BigBedTrackService".to_string(),
+            )),
+            TrackServiceEnum::Gff(_) => Err(TGVError::IOError(
+                "get_contig_2bit_file_lookup is not supported for GffTrackService".to_string(),
+            )),
+            TrackServiceEnum::Ensembl(_) => Err(TGVError::IOError(
+                "get_contig_2bit_file_lookup is not supported for EnsemblApiTrackService"
+                    .to_string(),
+            )),
         }
     }
 }
@@ -274,6 +430,9 @@ impl TrackService for TrackServiceEnum {
             TrackServiceEnum::Api(service) => service.close().await,
             TrackServiceEnum::Db(service) => service.close().await,
             TrackServiceEnum::LocalDb(service) => service.close().await,
+            TrackServiceEnum::BigBed(service) => service.close().await,
+            TrackServiceEnum::Gff(service) => service.close().await,
+            TrackServiceEnum::Ensembl(service) => service.close().await,
         }
     }
 
@@ -282,6 +441,9 @@ impl TrackService for TrackServiceEnum {
             TrackServiceEnum::Api(service) => service.get_all_contigs(reference).await,
             TrackServiceEnum::Db(service) => service.get_all_contigs(reference).await,
             TrackServiceEnum::LocalDb(service) => service.get_all_contigs(reference).await,
+            TrackServiceEnum::BigBed(service) => service.get_all_contigs(reference).await,
+            TrackServiceEnum::Gff(service) => service.get_all_contigs(reference).await,
+            TrackServiceEnum::Ensembl(service) => service.get_all_contigs(reference).await,
         }
     }
 
@@ -307,6 +469,45 @@ impl TrackService for TrackServiceEnum {
                     .get_cytoband(reference, contig_index, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .get_cytoband(reference, contig_index, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .get_cytoband(reference, contig_index, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .get_cytoband(reference, contig_index, contig_header)
+                    .await
+            }
+        }
+    }
+
+    async fn get_gaps(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Vec<AssemblyGap>>, TGVError> {
+        match self {
+            TrackServiceEnum::Db(service) => {
+                service
+                    .get_gaps(reference, contig_index, contig_header)
+                    .await
+            }
+            TrackServiceEnum::LocalDb(service) => {
+                service
+                    .get_gaps(reference, contig_index, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Api(_)
+            | TrackServiceEnum::BigBed(_)
+            | TrackServiceEnum::Gff(_)
+            | TrackServiceEnum::Ensembl(_) => Ok(None),
         }
     }
 
@@ -318,6 +519,38 @@ impl TrackService for TrackServiceEnum {
             TrackServiceEnum::Api(service) => service.get_preferred_track_name(reference).await,
             TrackServiceEnum::Db(service) => service.get_preferred_track_name(reference).await,
             TrackServiceEnum::LocalDb(service) => service.get_preferred_track_name(reference).await,
+            TrackServiceEnum::BigBed(service) => service.get_preferred_track_name(reference).await,
+            TrackServiceEnum::Gff(service) => service.get_preferred_track_name(reference).await,
+            TrackServiceEnum::Ensembl(service) => service.get_preferred_track_name(reference).await,
+        }
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        match self {
+            TrackServiceEnum::Api(service) => service.get_available_track_names(reference).await,
+            TrackServiceEnum::Db(service) => service.get_available_track_names(reference).await,
+            TrackServiceEnum::LocalDb(service) => {
+                service.get_available_track_names(reference).await
+            }
+            TrackServiceEnum::BigBed(service) => service.get_available_track_names(reference).await,
+            TrackServiceEnum::Gff(service) => service.get_available_track_names(reference).await,
+            TrackServiceEnum::Ensembl(service) => {
+                service.get_available_track_names(reference).await
+            }
+        }
+    }
+
+    fn set_preferred_track_name(&mut self, track_name: String) {
+        match self {
+            TrackServiceEnum::Api(service) => service.set_preferred_track_name(track_name),
+            TrackServiceEnum::Db(service) => service.set_preferred_track_name(track_name),
+            TrackServiceEnum::LocalDb(service) => service.set_preferred_track_name(track_name),
+            TrackServiceEnum::BigBed(service) => service.set_preferred_track_name(track_name),
+            TrackServiceEnum::Gff(service) => service.set_preferred_track_name(track_name),
+            TrackServiceEnum::Ensembl(service) => service.set_preferred_track_name(track_name),
         }
     }
 
@@ -343,6 +576,21 @@ impl TrackService for TrackServiceEnum {
                     .query_genes_overlapping(reference, region, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_genes_overlapping(reference, region, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_genes_overlapping(reference, region, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_genes_overlapping(reference, region, contig_header)
+                    .await
+            }
         }
     }
 
@@ -369,6 +617,21 @@ impl TrackService for TrackServiceEnum {
                     .query_gene_covering(reference, contig_index, coord, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_gene_covering(reference, contig_index, coord, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_gene_covering(reference, contig_index, coord, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_gene_covering(reference, contig_index, coord, contig_header)
+                    .await
+            }
         }
     }
 
@@ -394,6 +657,62 @@ impl TrackService for TrackServiceEnum {
                     .query_gene_name(reference, gene_name, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_gene_name(reference, gene_name, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_gene_name(reference, gene_name, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_gene_name(reference, gene_name, contig_header)
+                    .await
+            }
+        }
+    }
+
+    async fn query_gene_names_matching(
+        &mut self,
+        reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        match self {
+            TrackServiceEnum::Api(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Db(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
+            TrackServiceEnum::LocalDb(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_gene_names_matching(reference, pattern, limit, contig_header)
+                    .await
+            }
         }
     }
 
@@ -421,6 +740,21 @@ impl TrackService for TrackServiceEnum {
                     .query_k_genes_after(reference, contig_index, coord, k, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_k_genes_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_k_genes_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_k_genes_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
         }
     }
 
@@ -448,6 +782,21 @@ impl TrackService for TrackServiceEnum {
                     .query_k_genes_before(reference, contig_index, coord, k, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_k_genes_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_k_genes_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_k_genes_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
         }
     }
 
@@ -475,6 +824,21 @@ impl TrackService for TrackServiceEnum {
                     .query_k_exons_after(reference, contig_index, coord, k, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_k_exons_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_k_exons_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_k_exons_after(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
         }
     }
 
@@ -502,6 +866,21 @@ impl TrackService for TrackServiceEnum {
                     .query_k_exons_before(reference, contig_index, coord, k, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_k_exons_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_k_exons_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_k_exons_before(reference, contig_index, coord, k, contig_header)
+                    .await
+            }
         }
     }
     // Default helper methods delegate
@@ -527,10 +906,31 @@ impl TrackService for TrackServiceEnum {
                     .query_gene_track(reference, region, contig_header)
                     .await
             }
+            TrackServiceEnum::BigBed(service) => {
+                service
+                    .query_gene_track(reference, region, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Gff(service) => {
+                service
+                    .query_gene_track(reference, region, contig_header)
+                    .await
+            }
+            TrackServiceEnum::Ensembl(service) => {
+                service
+                    .query_gene_track(reference, region, contig_header)
+                    .await
+            }
         }
     }
 }
 
+/// HTTPS port of the UCSC API on custom hosts.
+const UCSC_API_PORT: u16 = 443;
+
+/// Time to wait for a custom UCSC host to accept a connection at startup.
+const UCSC_HOST_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(into = "String", try_from = "String")]
 #[derive(Default)]
@@ -538,6 +938,10 @@ pub enum UcscHost {
     #[default]
     Us,
     Eu,
+
+    /// A mirror of the UCSC Genome Browser, e.g. a local one: a host name with an optional MySQL port, such as
+    /// `mirror.example.org` or `mirror.example.org:3307`.
+    Custom(String),
 }
 
 impl From<UcscHost> for String {
@@ -556,15 +960,28 @@ impl TryFrom<String> for UcscHost {
 impl std::str::FromStr for UcscHost {
     type Err = TGVError;
 
-    /// Parse `"us"`, `"eu"`, or `"auto"` (resolved via timezone detection).
+    /// Parse `"us"`, `"eu"`, `"auto"` (resolved via timezone detection), or a custom host name with an optional
+    /// port.
     fn from_str(s: &str) -> Result<Self, TGVError> {
         match s {
             "us" => Ok(Self::Us),
             "eu" => Ok(Self::Eu),
             "auto" => Ok(Self::auto()),
-            _ => Err(TGVError::ParsingError(format!(
-                "Invalid ucsc_host `{s}`. Expected \"us\", \"eu\", or \"auto\"."
-            ))),
+            _ => match url::Url::parse(&format!("mysql://{s}")) {
+                Ok(url)
+                    if url.host_str().is_some_and(|host| !host.is_empty())
+                        && url.username().is_empty()
+                        && url.password().is_none()
+                        && url.path().is_empty()
+                        && url.query().is_none()
+                        && url.fragment().is_none() =>
+                {
+                    Ok(Self::Custom(s.to_string()))
+                }
+                _ => Err(TGVError::ParsingError(format!(
+                    "Invalid ucsc_host `{s}`. Expected \"us\", \"eu\", \"auto\", or a host name with an optional port, e.g. mirror.example.org:3306."
+                ))),
+            },
         }
     }
 }
@@ -574,18 +991,85 @@ impl std::string::ToString for UcscHost {
         match self {
             UcscHost::Us => "us".to_string(),
             UcscHost::Eu => "eu".to_string(),
+            UcscHost::Custom(host) => host.clone(),
         }
     }
 }
 
 impl UcscHost {
+    /// MySQL host, with the port of custom hosts.
     pub fn url(&self) -> String {
         match self {
             UcscHost::Us => "genome-mysql.soe.ucsc.edu".to_string(),
             UcscHost::Eu => "genome-euro-mysql.soe.ucsc.edu".to_string(),
+            UcscHost::Custom(host) => host.clone(),
+        }
+    }
+
+    /// Base URL of the REST API. Mirrors serve it from the hubApi CGI.
+    pub fn api_url(&self) -> String {
+        match self {
+            UcscHost::Us => "https://api.genome.ucsc.edu".to_string(),
+            UcscHost::Eu => "https://genome-euro.ucsc.edu/cgi-bin/hubApi".to_string(),
+            UcscHost::Custom(_) => format!("https://{}/cgi-bin/hubApi", self.host_name()),
+        }
+    }
+
+    /// Host name without the port.
+    fn host_name(&self) -> String {
+        let url = self.url();
+        url::Url::parse(&format!("mysql://{url}"))
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or(url)
+    }
+
+    /// The other mirror. Used for failover. Custom hosts have none: their errors are reported instead.
+    pub fn other(&self) -> Option<Self> {
+        match self {
+            UcscHost::Us => Some(UcscHost::Eu),
+            UcscHost::Eu => Some(UcscHost::Us),
+            UcscHost::Custom(_) => None,
+        }
+    }
+
+    /// Check that a custom host accepts HTTPS connections for its API. The UCSC hosts are not checked.
+    pub async fn check_reachable(&self) -> Result<(), TGVError> {
+        match self {
+            UcscHost::Custom(_) => self.probe_api().await,
+            _ => Ok(()),
         }
     }
 
+    /// Check that the host accepts HTTPS connections for its API.
+    pub async fn probe_api(&self) -> Result<(), TGVError> {
+        let api_host = url::Url::parse(&self.api_url())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| self.host_name());
+        let address = (api_host, UCSC_API_PORT);
+        log::info!(
+            "Checking the UCSC host: host={} address={address:?}",
+            self.to_string()
+        );
+        let error = match tokio::time::timeout(
+            UCSC_HOST_CHECK_TIMEOUT,
+            tokio::net::TcpStream::connect(address.clone()),
+        )
+        .await
+        {
+            Ok(Ok(_)) => return Ok(()),
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => format!("timed out after {} s", UCSC_HOST_CHECK_TIMEOUT.as_secs()),
+        };
+        Err(TGVError::NetworkError(format!(
+            "UCSC host {} is unreachable at {}:{}: {error}",
+            self.to_string(),
+            address.0,
+            address.1
+        )))
+    }
+
     /// Choose the host based on the local timezone.
     pub fn auto() -> Self {
         let offset = Local::now().offset().local_minus_utc() / 3600;
@@ -596,3 +1080,153 @@ impl UcscHost {
         }
     }
 }
+
+/// Base URL of a host's UCSC API. Requests fail over to the other mirror once the host is unreachable.
+#[derive(Debug, Clone)]
+pub(crate) struct UcscApiUrl {
+    url: String,
+
+    /// API URL of the other mirror. None for custom hosts and after a failover.
+    fallback_url: Option<String>,
+}
+
+impl From<&UcscHost> for UcscApiUrl {
+    fn from(host: &UcscHost) -> Self {
+        Self::new(host.api_url(), host.other().map(|other| other.api_url()))
+    }
+}
+
+impl std::fmt::Display for UcscApiUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
+impl UcscApiUrl {
+    pub(crate) fn new(url: String, fallback_url: Option<String>) -> Self {
+        Self { url, fallback_url }
+    }
+
+    /// GET a URL under this API URL, retrying transient errors and statuses per retry. If the host is unreachable, the request
+    /// is sent to the other mirror, which then serves all later requests.
+    pub(crate) async fn get(
+        &mut self,
+        client: &reqwest::Client,
+        retry: RetryPolicy,
+        context: &str,
+        url: &str,
+    ) -> Result<reqwest::Response, TGVError> {
+        let error = match retry.run(context, || send_request(client.get(url))).await {
+            Ok(response) => return Ok(response),
+            Err(e) => e,
+        };
+        let fallback_url = match self.fallback_url.take() {
+            Some(fallback_url) if is_unreachable(&error) => fallback_url,
+            fallback_url => {
+                self.fallback_url = fallback_url;
+                return Err(error);
+            }
+        };
+
+        log::warn!(
+            "UCSC API is unreachable; failing over: context={context} api_url={} fallback_api_url={fallback_url} error={error}",
+            self.url
+        );
+        let url = url.replacen(&self.url, &fallback_url, 1);
+        self.url = fallback_url;
+        retry.run(context, || send_request(client.get(&url))).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("lac", 10, vec!["lacA", "lacZ", "alaC", "mlaC"])]
+    #[case("LAC", 10, vec!["lacA", "lacZ", "alaC", "mlaC"])]
+    #[case("lac", 3, vec!["lacA", "lacZ", "alaC"])]
+    #[case("acz", 10, vec!["lacZ"])]
+    #[case("zzz", 10, vec![])]
+    fn test_rank_gene_names(
+        #[case] pattern: &str,
+        #[case] limit: usize,
+        #[case] expected: Vec<&str>,
+    ) {
+        let names = ["mlaC", "lacZ", "thrL", "alaC", "lacA", "lacZ"];
+        assert_eq!(rank_gene_names(names, pattern, limit), expected);
+    }
+
+    #[rstest]
+    #[case("TP53", "TP53")]
+    #[case("MIR_1", "MIR\\_1")]
+    #[case("50%", "50\\%")]
+    #[case("a\\b", "a\\\\b")]
+    fn test_escape_like(#[case] pattern: &str, #[case] expected: &str) {
+        assert_eq!(escape_like(pattern), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "us",
+        UcscHost::Us,
+        "mysql://genome@genome-mysql.soe.ucsc.edu/hg38",
+        "https://api.genome.ucsc.edu"
+    )]
+    #[case(
+        "eu",
+        UcscHost::Eu,
+        "mysql://genome@genome-euro-mysql.soe.ucsc.edu/hg38",
+        "https://genome-euro.ucsc.edu/cgi-bin/hubApi"
+    )]
+    #[case(
+        "mirror.example.org",
+        UcscHost::Custom("mirror.example.org".to_string()),
+        "mysql://genome@mirror.example.org/hg38",
+        "https://mirror.example.org/cgi-bin/hubApi"
+    )]
+    // The port is the MySQL port. The API is served over HTTPS.
+    #[case(
+        "10.0.0.5:3307",
+        UcscHost::Custom("10.0.0.5:3307".to_string()),
+        "mysql://genome@10.0.0.5:3307/hg38",
+        "https://10.0.0.5/cgi-bin/hubApi"
+    )]
+    fn test_ucsc_host(
+        #[case] input: &str,
+        #[case] expected: UcscHost,
+        #[case] mysql_url: &str,
+        #[case] api_url: &str,
+    ) {
+        let host: UcscHost = input.parse().unwrap();
+        assert_eq!(host, expected);
+        assert_eq!(host.to_string(), input);
+        assert_eq!(
+            UcscDbTrackService::get_mysql_url(&Reference::Hg38, &host).unwrap(),
+            mysql_url
+        );
+        assert_eq!(host.api_url(), api_url);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("mirror.example.org/hg38")]
+    #[case("genome@mirror.example.org")]
+    #[case("mirror example.org")]
+    #[case("mirror.example.org:port")]
+    fn test_invalid_ucsc_host(#[case] input: &str) {
+        assert!(matches!(
+            input.parse::<UcscHost>(),
+            Err(TGVError::ParsingError(_))
+        ));
+    }
+
+    #[rstest]
+    #[case(UcscHost::Us, Some(UcscHost::Eu))]
+    #[case(UcscHost::Eu, Some(UcscHost::Us))]
+    #[case(UcscHost::Custom("mirror.example.org".to_string()), None)]
+    fn test_ucsc_host_failover(#[case] host: UcscHost, #[case] expected: Option<UcscHost>) {
+        assert_eq!(host.other(), expected);
+    }
+}
@@ -0,0 +1,213 @@
+use crate::error::TGVError;
+use futures::{Stream, StreamExt};
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Minimum time between two redraws of a progress line.
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Width of the bar in characters.
+const BAR_WIDTH: usize = 30;
+
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProgressUnit {
+    Bytes,
+    Rows,
+}
+
+impl ProgressUnit {
+    fn format(&self, n: u64) -> String {
+        match self {
+            ProgressUnit::Rows => format!("{n} rows"),
+            ProgressUnit::Bytes => {
+                const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+                if n < 1024 {
+                    return format!("{n} B");
+                }
+                let mut value = n as f64 / 1024.0;
+                let mut unit = 0;
+                while value >= 1024.0 && unit < UNITS.len() - 1 {
+                    value /= 1024.0;
+                    unit += 1;
+                }
+                format!("{value:.1} {}", UNITS[unit])
+            }
+        }
+    }
+}
+
+/// A progress line on stderr: a bar when the total is known, and a spinner otherwise.
+/// Intermediate redraws are skipped when stderr is not a terminal, so logs get the final line only.
+pub(super) struct ProgressBar {
+    label: String,
+    total: Option<u64>,
+    unit: ProgressUnit,
+    current: u64,
+    frame: usize,
+    last_redraw: Option<Instant>,
+    is_terminal: bool,
+}
+
+impl ProgressBar {
+    pub fn new(label: impl Into<String>, total: Option<u64>, unit: ProgressUnit) -> Self {
+        Self {
+            label: label.into(),
+            total,
+            unit,
+            current: 0,
+            frame: 0,
+            last_redraw: None,
+            is_terminal: std::io::stderr().is_terminal(),
+        }
+    }
+
+    pub fn set(&mut self, current: u64) {
+        self.current = current;
+        if !self.is_terminal
+            || self
+                .last_redraw
+                .is_some_and(|last_redraw| last_redraw.elapsed() < REDRAW_INTERVAL)
+        {
+            return;
+        }
+        self.frame += 1;
+        self.last_redraw = Some(Instant::now());
+        eprint!("\r{}\x1b[K", self.line());
+        let _ = std::io::stderr().flush();
+    }
+
+    /// Draw the final state and end the line.
+    pub fn finish(&mut self) {
+        if self.is_terminal {
+            eprintln!("\r{}\x1b[K", self.line());
+        } else {
+            eprintln!("{}", self.line());
+        }
+    }
+
+    fn line(&self) -> String {
+        match self.total {
+            Some(total) if total > 0 => {
+                let fraction = (self.current as f64 / total as f64).min(1.0);
+                let filled = (fraction * BAR_WIDTH as f64) as usize;
+                format!(
+                    "{} [{}{}] {} / {} ({:.0}%)",
+                    self.label,
+                    "#".repeat(filled),
+                    "-".repeat(BAR_WIDTH - filled),
+                    self.unit.format(self.current),
+                    self.unit.format(total),
+                    fraction * 100.0
+                )
+            }
+            _ => format!(
+                "{} {} {}",
+                self.label,
+                SPINNER_FRAMES[self.frame % SPINNER_FRAMES.len()],
+                self.unit.format(self.current)
+            ),
+        }
+    }
+}
+
+/// Write a stream of chunks to the writer. on_progress is called after each chunk with the bytes written so far.
+/// Returns the number of bytes written.
+pub(super) async fn write_chunks<S, B>(
+    mut chunks: S,
+    writer: &mut impl Write,
+    mut on_progress: impl FnMut(u64),
+) -> Result<u64, TGVError>
+where
+    S: Stream<Item = Result<B, TGVError>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    let mut written = 0;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk?;
+        let chunk = chunk.as_ref();
+        if chunk.is_empty() {
+            continue;
+        }
+        writer.write_all(chunk)?;
+        written += chunk.len() as u64;
+        on_progress(written);
+    }
+    writer.flush()?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[tokio::test]
+    async fn progress_is_reported_with_increasing_byte_counts() {
+        let chunks: Vec<Result<Vec<u8>, TGVError>> = vec![
+            Ok(b"ACGT".to_vec()),
+            Ok(Vec::new()),
+            Ok(b"NNNNNNNN".to_vec()),
+            Ok(b"T".to_vec()),
+        ];
+        let mut body = Vec::new();
+        let mut progress = Vec::new();
+
+        let written = write_chunks(futures::stream::iter(chunks), &mut body, |n| {
+            progress.push(n)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(body, b"ACGTNNNNNNNNT");
+        assert_eq!(written, 13);
+        assert_eq!(progress, vec![4, 12, 13]);
+        assert!(progress.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[tokio::test]
+    async fn body_errors_stop_the_download() {
+        let chunks: Vec<Result<Vec<u8>, TGVError>> = vec![
+            Ok(b"ACGT".to_vec()),
+            Err(TGVError::IOError("connection reset".to_string())),
+            Ok(b"ACGT".to_vec()),
+        ];
+        let mut body = Vec::new();
+        let mut progress = Vec::new();
+
+        let result = write_chunks(futures::stream::iter(chunks), &mut body, |n| {
+            progress.push(n)
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(progress, vec![4]);
+    }
+
+    #[rstest]
+    #[case(
+        Some(200),
+        ProgressUnit::Bytes,
+        50,
+        "hg38.2bit [#######-----------------------] 50 B / 200 B (25%)"
+    )]
+    #[case(Some(3 * 1024 * 1024), ProgressUnit::Bytes, 1536 * 1024, "hg38.2bit [###############---------------] 1.5 MB / 3.0 MB (50%)")]
+    #[case(
+        Some(10),
+        ProgressUnit::Rows,
+        10,
+        "hg38.2bit [##############################] 10 rows / 10 rows (100%)"
+    )]
+    #[case(None, ProgressUnit::Bytes, 2048, "hg38.2bit | 2.0 KB")]
+    fn test_progress_line(
+        #[case] total: Option<u64>,
+        #[case] unit: ProgressUnit,
+        #[case] current: u64,
+        #[case] expected: &str,
+    ) {
+        let mut progress_bar = ProgressBar::new("hg38.2bit", total, unit);
+        progress_bar.current = current;
+        assert_eq!(progress_bar.line(), expected);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::tracks::bigbed::blocks_to_exons;
 use crate::{
     contig_header::{Contig, ContigHeader},
     cytoband::{Cytoband, CytobandSegment, Stain},
     error::TGVError,
     feature::Gene,
+    gaps::AssemblyGap,
     intervals::GenomeInterval,
     reference::Reference,
     strand::Strand,
@@ -182,6 +184,48 @@ impl CytobandSegmentRow {
     }
 }
 
+/// A row of the UCSC `gap` table.
+#[allow(non_snake_case)]
+pub struct GapRow {
+    chromStart: u64,
+    chromEnd: u64,
+    gap_type: String,
+}
+
+#[allow(non_snake_case)]
+impl FromRow<'_, SqliteRow> for GapRow {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        let chromStart: i64 = row.try_get("chromStart")?;
+        let chromEnd: i64 = row.try_get("chromEnd")?;
+        Ok(GapRow {
+            chromStart: chromStart as u64,
+            chromEnd: chromEnd as u64,
+            gap_type: row.try_get("type")?,
+        })
+    }
+}
+
+impl FromRow<'_, MySqlRow> for GapRow {
+    fn from_row(row: &MySqlRow) -> sqlx::Result<Self> {
+        Ok(GapRow {
+            chromStart: row.try_get("chromStart")?,
+            chromEnd: row.try_get("chromEnd")?,
+            gap_type: row.try_get("type")?,
+        })
+    }
+}
+
+impl GapRow {
+    pub fn to_assembly_gap(self, contig_index: usize) -> AssemblyGap {
+        AssemblyGap {
+            contig_index,
+            start: self.chromStart + 1,
+            end: self.chromEnd,
+            gap_type: Some(self.gap_type),
+        }
+    }
+}
+
 #[allow(non_snake_case)]
 #[derive(Debug)]
 pub struct ContigRow {
@@ -268,8 +312,6 @@ pub enum UcscGeneResponse {
         "geneType": ""
         }
 
-        I'm not sure if the implementation is correct.
-
         */
         chromStart: u64,
         chromEnd: u64,
@@ -277,6 +319,10 @@ pub enum UcscGeneResponse {
         strand: String,
         thickStart: u64,
         thickEnd: u64,
+        #[serde(default)]
+        blockSizes: Option<String>,
+        #[serde(default)]
+        chromStarts: Option<String>,
     },
 }
 
@@ -316,19 +362,29 @@ impl UcscGeneResponse {
                 strand,
                 thickStart,
                 thickEnd,
-            } => Ok(Gene {
-                id: name.clone(),
-                name,
-                strand: Strand::from_str(strand)?,
-                contig_index,
-                transcription_start: chromStart,
-                transcription_end: chromEnd,
-                cds_start: thickStart,
-                cds_end: thickEnd,
-                exon_starts: vec![],
-                exon_ends: vec![],
-                has_exons: false,
-            }),
+                blockSizes,
+                chromStarts,
+            } => {
+                let (exon_starts, exon_ends) = match (blockSizes, chromStarts) {
+                    (Some(block_sizes), Some(chrom_starts)) => {
+                        blocks_to_exons(chromStart, &block_sizes, &chrom_starts)?
+                    }
+                    _ => (Vec::new(), Vec::new()),
+                };
+                Ok(Gene {
+                    id: name.clone(),
+                    name,
+                    strand: Strand::from_str(strand)?,
+                    contig_index,
+                    transcription_start: chromStart,
+                    transcription_end: chromEnd,
+                    cds_start: thickStart,
+                    cds_end: thickEnd,
+                    has_exons: !exon_starts.is_empty(),
+                    exon_starts,
+                    exon_ends,
+                })
+            }
         }
     }
     /// Custom deserializer for comma-separated lists in UCSC response
@@ -438,3 +494,33 @@ impl UcscApiCytobandResponse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bigbed_style_gene_response_computes_exons_from_blocks() {
+        let response: UcscGeneResponse = serde_json::from_str(
+            r#"{
+                "chrom": "NC_072398.2",
+                "chromStart": 130929426,
+                "chromEnd": 130985030,
+                "name": "NM_001142759.1",
+                "strand": "+",
+                "thickStart": 130929440,
+                "thickEnd": 130982945,
+                "blockCount": 3,
+                "blockSizes": "65,124,374,",
+                "chromStarts": "0,8926,55230,"
+            }"#,
+        )
+        .unwrap();
+
+        let gene = response.to_gene(0).unwrap();
+        assert!(gene.has_exons);
+        assert_eq!(gene.exon_starts, vec![130929426, 130938352, 130984656]);
+        assert_eq!(gene.exon_ends, vec![130929491, 130938476, 130985030]);
+        assert_eq!((gene.cds_start, gene.cds_end), (130929440, 130982945));
+    }
+}
@@ -2,18 +2,34 @@ use crate::tracks::{TRACK_PREFERENCES, TrackCache, TrackService};
 use crate::{
     contig_header::{Contig, ContigHeader},
     cytoband::Cytoband,
+    data_source::explain_unreachable,
     error::TGVError,
     feature::{Gene, SubGeneFeature},
     intervals::GenomeInterval,
     intervals::Region,
     reference::Reference,
+    retry::RetryPolicy,
     track::Track,
-    tracks::schema::*,
+    tracks::{UcscApiUrl, UcscHost, schema::*},
 };
 use async_trait::async_trait;
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 
+/// Version of the on-disk track cache format. Cache files of other versions are refetched.
+const DISK_CACHE_VERSION: u32 = 1;
+
+/// On-disk cache of one contig's gene track response.
+#[derive(Debug, Serialize, Deserialize)]
+struct TrackDiskCache {
+    version: u32,
+
+    /// Gene records of the getData/track response.
+    genes: serde_json::Value,
+}
+
 // TODO: improved pattern:
 // Service doesn't save anything. No reference, no cache.
 // Ask these things to be passed in. And return them to store in the state.
@@ -28,17 +44,96 @@ pub struct UcscApiTrackService {
     /// None: Not initialized.
     /// Some(url): Queried and found.
     hub_url: Option<String>,
+
+    api_url: UcscApiUrl,
+
+    /// Directory of the on-disk track cache. None: gene tracks are always fetched.
+    disk_cache_dir: Option<PathBuf>,
+
+    retry: RetryPolicy,
 }
 
 impl UcscApiTrackService {
-    pub fn new() -> Result<Self, TGVError> {
+    /// ucsc_host: the host whose API is queried.
+    /// disk_cache_dir: the tgv cache directory to cache gene track responses under. None disables the disk cache.
+    /// retry: retries of failed requests.
+    pub fn new(
+        ucsc_host: &UcscHost,
+        disk_cache_dir: Option<&str>,
+        retry: RetryPolicy,
+    ) -> Result<Self, TGVError> {
         Ok(Self {
             client: Client::new(),
             cache: TrackCache::default(),
             hub_url: None,
+            api_url: UcscApiUrl::from(ucsc_host),
+            disk_cache_dir: disk_cache_dir.map(|dir| Path::new(dir).join("api_cache")),
+            retry,
+        })
+    }
+
+    /// Cache file of a contig's gene track.
+    /// Not under the reference's cache directory, whose existence means a downloaded reference.
+    fn disk_cache_path(
+        &self,
+        reference: &Reference,
+        contig_name: &str,
+        track_name: &str,
+    ) -> Option<PathBuf> {
+        self.disk_cache_dir.as_ref().map(|dir| {
+            dir.join(reference.to_string())
+                .join(track_name)
+                .join(format!("{}.json", contig_name))
         })
     }
 
+    /// Gene records from the disk cache. None if the cache is missing, unreadable, or of another version.
+    fn read_disk_cache(path: &Path) -> Option<serde_json::Value> {
+        let content = std::fs::read_to_string(path).ok()?;
+        match serde_json::from_str::<TrackDiskCache>(&content) {
+            Ok(cache) if cache.version == DISK_CACHE_VERSION => {
+                log::info!("UCSC track disk cache hit: path={}", path.display());
+                Some(cache.genes)
+            }
+            Ok(cache) => {
+                log::info!(
+                    "UCSC track disk cache is outdated: path={} version={} current_version={}",
+                    path.display(),
+                    cache.version,
+                    DISK_CACHE_VERSION
+                );
+                None
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to parse the UCSC track disk cache: path={} error={}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Write gene records to the disk cache. Failures are logged: the track is still shown.
+    fn write_disk_cache(path: &Path, genes: &serde_json::Value) {
+        let cache = TrackDiskCache {
+            version: DISK_CACHE_VERSION,
+            genes: genes.clone(),
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(path, serde_json::to_string(&cache)?));
+        if let Err(e) = result {
+            log::warn!(
+                "Failed to write the UCSC track disk cache: path={} error={}",
+                path.display(),
+                e
+            );
+        }
+    }
+
     /// Query the API to download the gene track data for a contig.
     pub async fn query_track_if_not_cached(
         &mut self,
@@ -72,10 +167,59 @@ impl UcscApiTrackService {
             )))?,
         };
 
+        let disk_cache_path = self.disk_cache_path(reference, contig_name, &preferred_track);
+        let genes = match disk_cache_path.as_deref().and_then(Self::read_disk_cache) {
+            Some(genes) => genes,
+            None => {
+                let genes = self
+                    .fetch_track(reference, contig_name, contig_index, &preferred_track)
+                    .await
+                    .map_err(|e| {
+                        explain_unreachable(e, &format!("Gene track of {contig_name}"), reference)
+                    })?;
+                if let Some(path) = disk_cache_path.as_deref() {
+                    Self::write_disk_cache(path, &genes);
+                }
+                genes
+            }
+        };
+
+        let response: Vec<UcscGeneResponse> = serde_json::from_value(genes)?;
+        log::debug!(
+            "UCSC track response: reference={} track={} contig={} genes={}",
+            reference,
+            preferred_track,
+            contig_name,
+            response.len()
+        );
+
+        self.cache.add_track(
+            contig_index,
+            Track::from_genes(
+                response
+                    .into_iter()
+                    .map(|response| response.to_gene(contig_index))
+                    .collect::<Result<Vec<Gene>, TGVError>>()?,
+                contig_index,
+                (1, u64::MAX),
+            )?,
+        );
+
+        Ok(())
+    }
+
+    /// Gene records of a contig from the getData/track API.
+    async fn fetch_track(
+        &mut self,
+        reference: &Reference,
+        contig_name: &str,
+        contig_index: usize,
+        preferred_track: &str,
+    ) -> Result<serde_json::Value, TGVError> {
         let query_url = match reference {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => format!(
-                "https://api.genome.ucsc.edu/getData/track?genome={}&track={}&chrom={}",
-                reference, preferred_track, contig_name
+                "{}/getData/track?genome={}&track={}&chrom={}",
+                self.api_url, reference, preferred_track, contig_name
             ),
             Reference::UcscAccession(genome) => {
                 let hub_url = self.hub_url.clone().unwrap_or({
@@ -84,8 +228,8 @@ impl UcscApiTrackService {
                     hub_url
                 });
                 format!(
-                    "https://api.genome.ucsc.edu/getData/track?hubUrl={}&genome={}&track={}&chrom={}",
-                    hub_url, genome, preferred_track, contig_name
+                    "{}/getData/track?hubUrl={}&genome={}&track={}&chrom={}",
+                    self.api_url, hub_url, genome, preferred_track, contig_name
                 )
             }
             _ => {
@@ -104,7 +248,10 @@ impl UcscApiTrackService {
             contig_index
         );
         let started = Instant::now();
-        let response = self.client.get(&query_url).send().await?;
+        let response = self
+            .api_url
+            .get(&self.client, self.retry, "UCSC track data", &query_url)
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC track data elapsed_ms={}",
             response.status(),
@@ -113,42 +260,25 @@ impl UcscApiTrackService {
         );
         let mut response: serde_json::Value = response.json().await?;
 
-        let response: Vec<UcscGeneResponse> =
-            serde_json::from_value(response[preferred_track.as_str()].take())?;
-        log::debug!(
-            "UCSC track response: reference={} track={} contig={} genes={}",
-            reference,
-            preferred_track,
-            contig_name,
-            response.len()
-        );
-
-        self.cache.add_track(
-            contig_index,
-            Track::from_genes(
-                response
-                    .into_iter()
-                    .map(|response| response.to_gene(contig_index))
-                    .collect::<Result<Vec<Gene>, TGVError>>()?,
-                contig_index,
-                (1, u64::MAX),
-            )?,
-        );
-
-        Ok(())
+        Ok(response[preferred_track].take())
     }
 
     pub async fn get_hub_url_for_genark_accession(
         &mut self,
         accession: &str,
     ) -> Result<String, TGVError> {
-        let url = format!(
-            "https://api.genome.ucsc.edu/list/genarkGenomes?genome={}",
-            accession
-        );
+        let url = format!("{}/list/genarkGenomes?genome={}", self.api_url, accession);
         log::info!("HTTP request: method=GET url={url} context=UCSC track GenArk hub lookup");
         let started = Instant::now();
-        let response = self.client.get(&url).send().await?;
+        let response = self
+            .api_url
+            .get(
+                &self.client,
+                self.retry,
+                "UCSC track GenArk hub lookup",
+                &url,
+            )
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC track GenArk hub lookup elapsed_ms={}",
             response.status(),
@@ -171,10 +301,7 @@ impl TrackService for UcscApiTrackService {
     async fn get_all_contigs(&mut self, reference: &Reference) -> Result<Vec<Contig>, TGVError> {
         let query_url = match reference {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => {
-                format!(
-                    "https://api.genome.ucsc.edu/list/chromosomes?genome={}",
-                    reference
-                )
+                format!("{}/list/chromosomes?genome={}", self.api_url, reference)
             }
             Reference::UcscAccession(genome) => {
                 let hub_url = self.hub_url.clone().unwrap_or({
@@ -184,8 +311,8 @@ impl TrackService for UcscApiTrackService {
                 });
 
                 format!(
-                    "https://api.genome.ucsc.edu/list/chromosomes?hubUrl={};genome={}",
-                    hub_url, genome
+                    "{}/list/chromosomes?hubUrl={};genome={}",
+                    self.api_url, hub_url, genome
                 )
             }
             _ => {
@@ -201,7 +328,15 @@ impl TrackService for UcscApiTrackService {
             reference
         );
         let started = Instant::now();
-        let response = self.client.get(&query_url).send().await?;
+        let response = self
+            .api_url
+            .get(
+                &self.client,
+                self.retry,
+                "UCSC track chromosome list",
+                &query_url,
+            )
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC track chromosome list elapsed_ms={}",
             response.status(),
@@ -244,8 +379,8 @@ impl TrackService for UcscApiTrackService {
         };
         let query_url = match reference {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => format!(
-                "https://api.genome.ucsc.edu/getData/track?genome={}&track=cytoBandIdeo&chrom={}",
-                reference, contig_name
+                "{}/getData/track?genome={}&track=cytoBandIdeo&chrom={}",
+                self.api_url, reference, contig_name
             ),
             Reference::UcscAccession(genome) => {
                 if self.hub_url.is_none() {
@@ -254,8 +389,8 @@ impl TrackService for UcscApiTrackService {
                 }
                 let hub_url = self.hub_url.as_ref().unwrap();
                 format!(
-                    "https://api.genome.ucsc.edu/getData/track?hubUrl={}&genome={}&track=cytoBandIdeo&chrom={}",
-                    hub_url, genome, contig_name
+                    "{}/getData/track?hubUrl={}&genome={}&track=cytoBandIdeo&chrom={}",
+                    self.api_url, hub_url, genome, contig_name
                 )
             }
             _ => {
@@ -273,7 +408,10 @@ impl TrackService for UcscApiTrackService {
             contig_index
         );
         let started = Instant::now();
-        let response = self.client.get(&query_url).send().await?;
+        let response = self
+            .api_url
+            .get(&self.client, self.retry, "UCSC cytoband track", &query_url)
+            .await?;
         log::info!(
             "HTTP response: status={} url={} context=UCSC cytoband track elapsed_ms={}",
             response.status(),
@@ -289,10 +427,29 @@ impl TrackService for UcscApiTrackService {
         &mut self,
         reference: &Reference,
     ) -> Result<Option<String>, TGVError> {
+        if let Reference::Hg19 | Reference::Hg38 = reference {
+            return Ok(Some("ncbiRefSeqSelect".to_string()));
+        }
+
+        let track_names = self.get_available_track_names(reference).await?;
+
+        for pref in TRACK_PREFERENCES {
+            if track_names.contains(&pref.to_string()) {
+                return Ok(Some(pref.to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
         let query_url = match reference {
             Reference::Hg19 | Reference::Hg38 | Reference::UcscGenome(_) => format!(
-                "https://api.genome.ucsc.edu/list/tracks?trackLeavesOnly=1;genome={}",
-                reference,
+                "{}/list/tracks?trackLeavesOnly=1;genome={}",
+                self.api_url, reference,
             ),
             Reference::UcscAccession(genome) => {
                 if self.hub_url.is_none() {
@@ -301,8 +458,8 @@ impl TrackService for UcscApiTrackService {
                 }
                 let hub_url = self.hub_url.as_ref().unwrap();
                 format!(
-                    "https://api.genome.ucsc.edu/list/tracks?trackLeavesOnly=1;hubUrl={};genome={}",
-                    hub_url, genome
+                    "{}/list/tracks?trackLeavesOnly=1;hubUrl={};genome={}",
+                    self.api_url, hub_url, genome
                 )
             }
             _ => {
@@ -311,46 +468,40 @@ impl TrackService for UcscApiTrackService {
                 ));
             }
         };
-        match reference.clone() {
-            Reference::Hg19 | Reference::Hg38 => Ok(Some("ncbiRefSeqSelect".to_string())),
-            reference => {
-                log::info!(
-                    "HTTP request: method=GET url={} context=UCSC track list reference={}",
-                    query_url,
-                    reference
-                );
-                let started = Instant::now();
-                let response = reqwest::get(&query_url).await?;
-                log::info!(
-                    "HTTP response: status={} url={} context=UCSC track list elapsed_ms={}",
-                    response.status(),
-                    query_url,
-                    started.elapsed().as_millis()
-                );
-                let response = response.json::<serde_json::Value>().await?;
-
-                let track_names = response
-                    .get(reference.to_string())
-                    .ok_or(TGVError::IOError(
-                        "Failed to get genome from UCSC API".to_string(),
-                    ))?
-                    .as_object()
-                    .ok_or(TGVError::IOError(
-                        "Failed to get genome from UCSC API".to_string(),
-                    ))?
-                    .keys()
-                    .cloned()
-                    .collect::<Vec<String>>();
-
-                for pref in TRACK_PREFERENCES {
-                    if track_names.contains(&pref.to_string()) {
-                        return Ok(Some(pref.to_string()));
-                    }
-                }
 
-                Ok(None)
-            }
-        }
+        log::info!(
+            "HTTP request: method=GET url={} context=UCSC track list reference={}",
+            query_url,
+            reference
+        );
+        let started = Instant::now();
+        let response = self
+            .api_url
+            .get(&self.client, self.retry, "UCSC track list", &query_url)
+            .await?;
+        log::info!(
+            "HTTP response: status={} url={} context=UCSC track list elapsed_ms={}",
+            response.status(),
+            query_url,
+            started.elapsed().as_millis()
+        );
+        let response = response.json::<serde_json::Value>().await?;
+
+        Ok(gene_track_names(
+            response
+                .get(reference.to_string())
+                .ok_or(TGVError::IOError(
+                    "Failed to get genome from UCSC API".to_string(),
+                ))?
+                .as_object()
+                .ok_or(TGVError::IOError(
+                    "Failed to get genome from UCSC API".to_string(),
+                ))?,
+        ))
+    }
+
+    fn set_preferred_track_name(&mut self, track_name: String) {
+        self.cache.use_track(track_name);
     }
 
     async fn query_genes_overlapping(
@@ -435,6 +586,17 @@ impl TrackService for UcscApiTrackService {
         Err(TGVError::IOError(format!("Gene {} not found", gene_name)))
     }
 
+    async fn query_gene_names_matching(
+        &mut self,
+        _reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        // The API has no name search, and fetching every contig's track is slow. Only cached tracks are matched.
+        Ok(self.cache.gene_names_matching(pattern, limit))
+    }
+
     async fn query_k_genes_after(
         &mut self,
         reference: &Reference,
@@ -570,3 +732,216 @@ impl TrackService for UcscApiTrackService {
             .ok_or(TGVError::IOError("No exons found".to_string()))
     }
 }
+
+/// Names of gene prediction tracks in a track list of the UCSC API. Genes are only parsed from tracks of type
+/// genePred or bigGenePred.
+fn gene_track_names(tracks: &serde_json::Map<String, serde_json::Value>) -> Vec<String> {
+    tracks
+        .iter()
+        .filter(|(_, track)| {
+            track
+                .get("type")
+                .and_then(|track_type| track_type.as_str())
+                .and_then(|track_type| track_type.split_whitespace().next())
+                .is_some_and(|track_type| matches!(track_type, "genePred" | "bigGenePred"))
+        })
+        .map(|(name, _)| name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contig_header::ContigSource;
+    use crate::intervals::Focus;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const TRACK_RESPONSE: &str = r#"{"ncbiRefSeqSelect": [{"name": "NM_000001.1", "name2": "GENE1", "strand": "+", "txStart": 100, "txEnd": 500, "cdsStart": 150, "cdsEnd": 450, "exonStarts": "100,300,", "exonEnds": "200,500,"}]}"#;
+
+    /// Serve TRACK_RESPONSE to every request, after closing the first `dropped` connections without a response.
+    /// Return: the API URL and the number of requests received.
+    async fn serve_track_response(dropped: usize) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let served = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 4096];
+                let _ = socket.read(&mut buffer).await.unwrap();
+                if served.fetch_add(1, Ordering::SeqCst) < dropped {
+                    continue;
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    TRACK_RESPONSE.len(),
+                    TRACK_RESPONSE
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        (api_url, requests)
+    }
+
+    fn service(api_url: &str, disk_cache_dir: Option<&str>) -> UcscApiTrackService {
+        UcscApiTrackService {
+            api_url: UcscApiUrl::new(api_url.to_string(), None),
+            ..UcscApiTrackService::new(
+                &UcscHost::Us,
+                disk_cache_dir,
+                RetryPolicy {
+                    max_retries: 1,
+                    initial_backoff: std::time::Duration::from_millis(1),
+                },
+            )
+            .unwrap()
+        }
+    }
+
+    async fn query_gene_names(service: &mut UcscApiTrackService) -> Vec<String> {
+        let mut contig_header = ContigHeader::new(Reference::Hg38);
+        contig_header.update_or_add_contig(
+            "chr1".to_string(),
+            Some(248956422),
+            Vec::new(),
+            ContigSource::Track,
+        );
+        let region = Region {
+            focus: Focus {
+                contig_index: 0,
+                position: 300,
+            },
+            half_width: 300,
+        };
+        service
+            .query_genes_overlapping(&Reference::Hg38, &region, &contig_header)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|gene| gene.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn disk_cache_skips_the_api_on_revisits() {
+        let (api_url, requests) = serve_track_response(0).await;
+        let cache_dir = tempfile::tempdir().unwrap();
+        let cache_dir = cache_dir.path().to_str();
+
+        let mut first = service(&api_url, cache_dir);
+        assert_eq!(query_gene_names(&mut first).await, vec!["GENE1"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A new service, as on the next launch, reads the disk cache.
+        let mut second = service(&api_url, cache_dir);
+        assert_eq!(query_gene_names(&mut second).await, vec!["GENE1"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Cache files of another version are refetched.
+        let path = second
+            .disk_cache_path(&Reference::Hg38, "chr1", "ncbiRefSeqSelect")
+            .unwrap();
+        std::fs::write(&path, r#"{"version": 0, "genes": []}"#).unwrap();
+        let mut third = service(&api_url, cache_dir);
+        assert_eq!(query_gene_names(&mut third).await, vec!["GENE1"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn disabled_disk_cache_always_fetches() {
+        let (api_url, requests) = serve_track_response(0).await;
+
+        for _ in 0..2 {
+            let mut service = service(&api_url, None);
+            assert_eq!(query_gene_names(&mut service).await, vec!["GENE1"]);
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn gene_names_are_matched_in_cached_tracks_only() {
+        let (api_url, requests) = serve_track_response(0).await;
+        let mut service = service(&api_url, None);
+        let contig_header = ContigHeader::new(Reference::Hg38);
+
+        assert!(
+            service
+                .query_gene_names_matching(&Reference::Hg38, "gene", 10, &contig_header)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        query_gene_names(&mut service).await;
+        assert_eq!(
+            service
+                .query_gene_names_matching(&Reference::Hg38, "gene", 10, &contig_header)
+                .await
+                .unwrap(),
+            vec!["GENE1"]
+        );
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn dropped_connections_are_retried() {
+        let (api_url, requests) = serve_track_response(1).await;
+        let mut retrying = service(&api_url, None);
+        assert_eq!(query_gene_names(&mut retrying).await, vec!["GENE1"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Without retries, the dropped connection fails the query. The contig is not in the disk cache, so the
+        // error explains how to browse offline.
+        let (api_url, requests) = serve_track_response(1).await;
+        let mut not_retrying = UcscApiTrackService {
+            retry: RetryPolicy::NONE,
+            ..service(&api_url, None)
+        };
+        match not_retrying
+            .query_track_if_not_cached(&Reference::Hg38, "chr1", 0)
+            .await
+        {
+            Err(TGVError::NetworkError(message)) => {
+                assert!(message.starts_with("Gene track of chr1 is not in the local cache"));
+                assert!(message.contains("tgv download hg38"));
+            }
+            result => panic!("Expected a network error, got {result:?}"),
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn unreachable_host_fails_over_to_the_other_mirror() {
+        let (fallback_url, requests) = serve_track_response(0).await;
+        // Nothing listens on the discard port.
+        let mut service = UcscApiTrackService {
+            api_url: UcscApiUrl::new("http://127.0.0.1:9".to_string(), Some(fallback_url.clone())),
+            ..service(&fallback_url, None)
+        };
+
+        assert_eq!(query_gene_names(&mut service).await, vec!["GENE1"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(service.api_url.to_string(), fallback_url);
+    }
+
+    #[test]
+    fn track_list_is_restricted_to_gene_tracks() {
+        let tracks = serde_json::json!({
+            "ncbiRefSeqSelect": {"shortLabel": "RefSeq Select", "type": "genePred"},
+            "hubRefSeq": {"shortLabel": "RefSeq", "type": "bigGenePred"},
+            "knownGene": {"shortLabel": "GENCODE", "type": "genePred knownGenePep knownGeneMrna"},
+            "cytoBandIdeo": {"shortLabel": "Chromosome Band", "type": "bed 4 +"},
+            "gc5BaseBw": {"shortLabel": "GC Percent", "type": "bigWig 0 100"},
+            "composite": {"shortLabel": "No type"},
+        });
+        assert_eq!(
+            gene_track_names(tracks.as_object().unwrap()),
+            vec!["hubRefSeq", "knownGene", "ncbiRefSeqSelect"]
+        );
+    }
+}
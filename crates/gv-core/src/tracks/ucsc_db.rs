@@ -3,9 +3,11 @@ use crate::{
     cytoband::{Cytoband, CytobandSegment},
     error::TGVError,
     feature::{Gene, SubGeneFeature},
+    gaps::AssemblyGap,
     intervals::GenomeInterval,
     intervals::Region,
     reference::Reference,
+    retry::{RetryPolicy, is_unreachable},
     track::Track,
     tracks::UcscHost,
     tracks::schema::*,
@@ -14,21 +16,122 @@ use async_trait::async_trait;
 use sqlx::{MySqlPool, Row, mysql::MySqlPoolOptions};
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 const UCSC_HGCENTRAL_URL: &str = "mysql://genome@genome-mysql.soe.ucsc.edu/hgcentral";
 
+/// Time to wait for a UCSC MySQL connection before failing over to the other host.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Filter of `tgv list`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssemblyFilter {
+    /// Case-insensitive substring of the assembly name or organism, e.g. zebrafish or danRer.
+    pub text: Option<String>,
+
+    /// UCSC clade of the assembly, e.g. mammal or insect.
+    pub clade: Option<String>,
+}
+
+impl AssemblyFilter {
+    /// Whether an assembly matches the text filter. The clade is not known here, so it is not checked.
+    pub fn matches(&self, name: &str, organism: &str) -> bool {
+        self.text.as_ref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            name.to_lowercase().contains(&text) || organism.to_lowercase().contains(&text)
+        })
+    }
+
+    /// Query of the hgcentral dbDb table with the filter in the WHERE clause, and the values to bind to it.
+    fn sql(&self, n: Option<usize>) -> (String, Vec<String>) {
+        let mut sql = "SELECT dbDb.name, dbDb.organism FROM dbDb".to_string();
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(clade) = &self.clade {
+            sql += " JOIN genomeClade ON genomeClade.genome = dbDb.genome";
+            conditions.push("LOWER(genomeClade.clade) = ?");
+            values.push(clade.to_lowercase());
+        }
+        if let Some(text) = &self.text {
+            let pattern = format!(
+                "%{}%",
+                text.to_lowercase()
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            conditions.push("(LOWER(dbDb.name) LIKE ? OR LOWER(dbDb.organism) LIKE ?)");
+            values.push(pattern.clone());
+            values.push(pattern);
+        }
+        if !conditions.is_empty() {
+            sql += &format!(" WHERE {}", conditions.join(" AND "));
+        }
+        if let Some(n) = n {
+            sql += &format!(" LIMIT {n}");
+        }
+        (sql, values)
+    }
+}
+
 #[derive(Debug)]
 pub struct UcscDbTrackService {
     pool: Arc<MySqlPool>,
 
+    host: UcscHost,
+
     cache: TrackCache,
 }
-use crate::tracks::{TRACK_PREFERENCES, TrackCache, TrackService};
+use crate::tracks::{TRACK_PREFERENCES, TrackCache, TrackService, escape_like};
 
 impl UcscDbTrackService {
     // Initialize the database connections. Reference is needed to find the corresponding schema.
-    pub async fn new(reference: &Reference, ucsc_host: &UcscHost) -> Result<Self, TGVError> {
+    // If ucsc_host is unreachable, the other mirror is tried before erroring. Failed connections are retried per
+    // retry before failing over. Custom hosts do not fail over.
+    pub async fn new(
+        reference: &Reference,
+        ucsc_host: &UcscHost,
+        retry: RetryPolicy,
+    ) -> Result<Self, TGVError> {
+        let (pool, host) = match UcscDbTrackService::connect(reference, ucsc_host, retry).await {
+            Ok(pool) => (pool, ucsc_host.clone()),
+            Err(e) => match ucsc_host.other() {
+                // Only an unreachable host fails over. Other errors, e.g. a missing database, would fail on the other host too.
+                Some(fallback_host) if is_unreachable(&e) => {
+                    log::warn!(
+                        "UCSC host is unreachable; failing over: host={} fallback_host={} error={e}",
+                        ucsc_host.to_string(),
+                        fallback_host.to_string()
+                    );
+                    (
+                        UcscDbTrackService::connect(reference, &fallback_host, retry).await?,
+                        fallback_host,
+                    )
+                }
+                _ => match e {
+                    TGVError::SqlxError(e) => {
+                        return Err(TGVError::NetworkError(format!(
+                            "Cannot connect to the UCSC MySQL server at {}: {e}",
+                            ucsc_host.url()
+                        )));
+                    }
+                    e => return Err(e),
+                },
+            },
+        };
+
+        Ok(Self {
+            pool: Arc::new(pool),
+            host,
+            cache: TrackCache::default(),
+        })
+    }
+
+    async fn connect(
+        reference: &Reference,
+        ucsc_host: &UcscHost,
+        retry: RetryPolicy,
+    ) -> Result<MySqlPool, TGVError> {
         let mysql_url = UcscDbTrackService::get_mysql_url(reference, ucsc_host)?;
         log::info!(
             "Database connect: database=ucsc-mysql connection={} context=reference={} host={}",
@@ -37,9 +140,13 @@ impl UcscDbTrackService {
             ucsc_host.to_string()
         );
         let started = Instant::now();
-        let pool = MySqlPoolOptions::new()
-            .max_connections(1)
-            .connect(&mysql_url)
+        let pool = retry
+            .run("UCSC MySQL connection", || {
+                MySqlPoolOptions::new()
+                    .max_connections(1)
+                    .acquire_timeout(CONNECT_TIMEOUT)
+                    .connect(&mysql_url)
+            })
             .await?;
         log::info!(
             "Database connect result: database=ucsc-mysql context=reference={} host={} elapsed_ms={}",
@@ -48,10 +155,12 @@ impl UcscDbTrackService {
             started.elapsed().as_millis()
         );
 
-        Ok(Self {
-            pool: Arc::new(pool),
-            cache: TrackCache::default(),
-        })
+        Ok(pool)
+    }
+
+    /// The UCSC host in use. Differs from the requested host after failover.
+    pub fn host(&self) -> &UcscHost {
+        &self.host
     }
 
     pub fn get_mysql_url(reference: &Reference, ucsc_host: &UcscHost) -> Result<String, TGVError> {
@@ -66,7 +175,11 @@ impl UcscDbTrackService {
         }
     }
 
-    pub async fn list_assemblies(n: Option<usize>) -> Result<Vec<(String, String)>, TGVError> {
+    /// Assemblies in UCSC hgcentral as (name, organism), filtered in the query.
+    pub async fn list_assemblies(
+        n: Option<usize>,
+        filter: &AssemblyFilter,
+    ) -> Result<Vec<(String, String)>, TGVError> {
         log::info!(
             "Database connect: database=ucsc-mysql connection={} context=list assemblies",
             UCSC_HGCENTRAL_URL
@@ -81,39 +194,23 @@ impl UcscDbTrackService {
             started.elapsed().as_millis()
         );
 
-        let rows = if let Some(n) = n {
-            let sql = "SELECT name, organism FROM dbDb LIMIT ?";
-            log::info!(
-                "Database query: database=ucsc-mysql sql=\"{}\" context=list assemblies limit={}",
-                sql,
-                n
-            );
-            let started = Instant::now();
-            let rows = sqlx::query(sql)
-                .bind(n as i32)
-                .fetch_all(&connection)
-                .await?;
-            log::info!(
-                "Database query result: database=ucsc-mysql context=list assemblies rows={} elapsed_ms={}",
-                rows.len(),
-                started.elapsed().as_millis()
-            );
-            rows
-        } else {
-            let sql = "SELECT name, organism FROM dbDb";
-            log::info!(
-                "Database query: database=ucsc-mysql sql=\"{}\" context=list assemblies",
-                sql
-            );
-            let started = Instant::now();
-            let rows = sqlx::query(sql).fetch_all(&connection).await?;
-            log::info!(
-                "Database query result: database=ucsc-mysql context=list assemblies rows={} elapsed_ms={}",
-                rows.len(),
-                started.elapsed().as_millis()
-            );
-            rows
-        };
+        let (sql, values) = filter.sql(n);
+        log::info!(
+            "Database query: database=ucsc-mysql sql=\"{}\" context=list assemblies values={:?}",
+            sql,
+            values
+        );
+        let started = Instant::now();
+        let mut query = sqlx::query(&sql);
+        for value in values {
+            query = query.bind(value);
+        }
+        let rows = query.fetch_all(&connection).await?;
+        log::info!(
+            "Database query result: database=ucsc-mysql context=list assemblies rows={} elapsed_ms={}",
+            rows.len(),
+            started.elapsed().as_millis()
+        );
 
         let mut assemblies = Vec::new();
         for row in rows {
@@ -353,6 +450,50 @@ impl TrackService for UcscDbTrackService {
         }))
     }
 
+    async fn get_gaps(
+        &mut self,
+        reference: &Reference,
+        contig_index: usize,
+        contig_header: &ContigHeader,
+    ) -> Result<Option<Vec<AssemblyGap>>, TGVError> {
+        let contig_name = match contig_header.try_get(contig_index)?.get_track_name() {
+            Some(contig_name) => contig_name,
+            None => return Ok(None),
+        };
+        // Some assemblies have no gap table.
+        if sqlx::query("SHOW TABLES LIKE 'gap'")
+            .fetch_optional(&*self.pool)
+            .await?
+            .is_none()
+        {
+            return Ok(None);
+        }
+        let sql = "SELECT chromStart, chromEnd, type FROM gap WHERE chrom = ? ORDER BY chromStart";
+        log::info!(
+            "Database query: database=ucsc-mysql sql=\"{}\" context=get gaps reference={} contig={}",
+            sql,
+            reference,
+            contig_name
+        );
+        let started = Instant::now();
+        let gap_rows: Vec<GapRow> = sqlx::query_as(sql)
+            .bind(contig_name)
+            .fetch_all(&*self.pool)
+            .await?;
+        log::info!(
+            "Database query result: database=ucsc-mysql context=get gaps rows={} elapsed_ms={}",
+            gap_rows.len(),
+            started.elapsed().as_millis()
+        );
+
+        Ok(Some(
+            gap_rows
+                .into_iter()
+                .map(|row| row.to_assembly_gap(contig_index))
+                .collect(),
+        ))
+    }
+
     async fn get_preferred_track_name(
         &mut self,
         reference: &Reference,
@@ -363,16 +504,32 @@ impl TrackService for UcscDbTrackService {
             _ => {}
         }
 
-        let sql = "SHOW TABLES";
+        let available_gene_tracks = self.get_available_track_names(reference).await?;
+
+        for pref in TRACK_PREFERENCES {
+            if available_gene_tracks.contains(&pref.to_string()) {
+                return Ok(Some(pref.to_string()));
+            }
+        }
+
+        Ok(None)
+    }
+
+    async fn get_available_track_names(
+        &mut self,
+        reference: &Reference,
+    ) -> Result<Vec<String>, TGVError> {
+        // Genes are read from tables with all columns of UcscGeneRow, i.e. genePred tables with name2.
+        let sql = "SELECT TABLE_NAME FROM information_schema.COLUMNS WHERE TABLE_SCHEMA = DATABASE() AND COLUMN_NAME IN ('name', 'chrom', 'strand', 'txStart', 'txEnd', 'cdsStart', 'cdsEnd', 'name2', 'exonStarts', 'exonEnds') GROUP BY TABLE_NAME HAVING COUNT(*) = 10";
         log::info!(
-            "Database query: database=ucsc-mysql sql=\"{}\" context=get preferred track reference={}",
+            "Database query: database=ucsc-mysql sql=\"{}\" context=get available tracks reference={}",
             sql,
             reference
         );
         let started = Instant::now();
         let gene_track_rows = sqlx::query(sql).fetch_all(&*self.pool).await?;
         log::info!(
-            "Database query result: database=ucsc-mysql context=get preferred track rows={} elapsed_ms={}",
+            "Database query result: database=ucsc-mysql context=get available tracks rows={} elapsed_ms={}",
             gene_track_rows.len(),
             started.elapsed().as_millis()
         );
@@ -381,13 +538,12 @@ impl TrackService for UcscDbTrackService {
             .into_iter()
             .map(|row| row.try_get::<String, usize>(0))
             .collect::<Result<Vec<String>, sqlx::Error>>()?;
-        for pref in TRACK_PREFERENCES {
-            if available_gene_tracks.contains(&pref.to_string()) {
-                return Ok(Some(pref.to_string()));
-            }
-        }
 
-        Ok(None)
+        Ok(available_gene_tracks)
+    }
+
+    fn set_preferred_track_name(&mut self, track_name: String) {
+        self.cache.use_track(track_name);
     }
 
     async fn query_genes_overlapping(
@@ -529,6 +685,48 @@ impl TrackService for UcscDbTrackService {
             .to_gene(contig_header)
     }
 
+    async fn query_gene_names_matching(
+        &mut self,
+        reference: &Reference,
+        pattern: &str,
+        limit: usize,
+        _contig_header: &ContigHeader,
+    ) -> Result<Vec<String>, TGVError> {
+        let track_name = self.get_preferred_track_name_with_cache(reference).await?;
+        let sql = format!(
+            "SELECT DISTINCT name2
+            FROM {}
+            WHERE name2 LIKE ?
+            ORDER BY name2 LIKE ? DESC, name2
+            LIMIT ?",
+            track_name
+        );
+        log::info!(
+            "Database query: database=ucsc-mysql sql=\"{}\" context=query gene names matching reference={} track={} pattern={}",
+            sql,
+            reference,
+            track_name,
+            pattern
+        );
+        let started = Instant::now();
+        let gene_name_rows = sqlx::query(sql.as_str())
+            .bind(format!("%{}%", escape_like(pattern)))
+            .bind(format!("{}%", escape_like(pattern)))
+            .bind(limit as i64)
+            .fetch_all(&*self.pool)
+            .await?;
+        log::info!(
+            "Database query result: database=ucsc-mysql context=query gene names matching rows={} elapsed_ms={}",
+            gene_name_rows.len(),
+            started.elapsed().as_millis()
+        );
+
+        Ok(gene_name_rows
+            .into_iter()
+            .map(|row| row.try_get::<String, &str>("name2"))
+            .collect::<Result<Vec<String>, sqlx::Error>>()?)
+    }
+
     async fn query_k_genes_after(
         &mut self,
         reference: &Reference,
@@ -770,3 +968,62 @@ impl TrackService for UcscDbTrackService {
             .ok_or(TGVError::IOError("No exons found".to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const ASSEMBLIES: [(&str, &str); 5] = [
+        ("hg38", "Human"),
+        ("mm39", "Mouse"),
+        ("danRer11", "Zebrafish"),
+        ("danRer10", "Zebrafish"),
+        ("dm6", "D. melanogaster"),
+    ];
+
+    #[rstest]
+    #[case(None, vec!["hg38", "mm39", "danRer11", "danRer10", "dm6"])]
+    #[case(Some("zebrafish"), vec!["danRer11", "danRer10"])]
+    #[case(Some("DANRER"), vec!["danRer11", "danRer10"])]
+    #[case(Some("er1"), vec!["danRer11", "danRer10"])]
+    #[case(Some("m"), vec!["hg38", "mm39", "dm6"])]
+    #[case(Some("yeast"), vec![])]
+    fn test_assembly_filter(#[case] text: Option<&str>, #[case] expected: Vec<&str>) {
+        let filter = AssemblyFilter {
+            text: text.map(str::to_string),
+            clade: None,
+        };
+        let names = ASSEMBLIES
+            .iter()
+            .filter(|(name, organism)| filter.matches(name, organism))
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, expected);
+    }
+
+    #[rstest]
+    #[case(AssemblyFilter::default(), None, "SELECT dbDb.name, dbDb.organism FROM dbDb", vec![])]
+    #[case(
+        AssemblyFilter { text: Some("Zebra_fish".to_string()), clade: None },
+        Some(10),
+        "SELECT dbDb.name, dbDb.organism FROM dbDb WHERE (LOWER(dbDb.name) LIKE ? OR LOWER(dbDb.organism) LIKE ?) LIMIT 10",
+        vec!["%zebra\\_fish%", "%zebra\\_fish%"],
+    )]
+    #[case(
+        AssemblyFilter { text: Some("dan".to_string()), clade: Some("Vertebrate".to_string()) },
+        None,
+        "SELECT dbDb.name, dbDb.organism FROM dbDb JOIN genomeClade ON genomeClade.genome = dbDb.genome WHERE LOWER(genomeClade.clade) = ? AND (LOWER(dbDb.name) LIKE ? OR LOWER(dbDb.organism) LIKE ?)",
+        vec!["vertebrate", "%dan%", "%dan%"],
+    )]
+    fn test_assembly_filter_sql(
+        #[case] filter: AssemblyFilter,
+        #[case] n: Option<usize>,
+        #[case] expected_sql: &str,
+        #[case] expected_values: Vec<&str>,
+    ) {
+        let (sql, values) = filter.sql(n);
+        assert_eq!(sql, expected_sql);
+        assert_eq!(values, expected_values);
+    }
+}
@@ -3,6 +3,7 @@ use crate::error::TGVError;
 use crate::intervals::{GenomeInterval, SortedIntervalCollection};
 use itertools::Itertools;
 use noodles::vcf::{self, variant::record::AlternateBases};
+use serde::{Deserialize, Serialize};
 
 pub type VariantTrack = SortedIntervalCollection<Variant>;
 
@@ -70,6 +71,218 @@ impl Variant {
     }
 }
 
+impl Variant {
+    /// The first alternate allele. Symbolic and breakend alleles (e.g. `<DEL>`) are not supported.
+    pub fn alternate_allele(&self) -> Result<VariantAllele, TGVError> {
+        let alternate_bases = self.record.alternate_bases();
+        let alternate = alternate_bases
+            .iter()
+            .next()
+            .transpose()?
+            .ok_or(TGVError::ValueError(format!(
+                "Variant at {} has no alternate allele",
+                self.start
+            )))?;
+
+        if !alternate.bytes().all(|base| base.is_ascii_alphabetic()) {
+            return Err(TGVError::ValueError(format!(
+                "Alternate allele {alternate} is not a sequence"
+            )));
+        }
+
+        Ok(VariantAllele {
+            contig_index: self.contig_index,
+            position: self.start,
+            reference: self.record.reference_bases().as_bytes().to_vec(),
+            alternate: alternate.as_bytes().to_vec(),
+        })
+    }
+}
+
+impl Variant {
+    /// Value of an INFO field. Flags have an empty value. None if the field is not in the record.
+    pub fn info_value(&self, key: &str) -> Option<String> {
+        self.record
+            .info()
+            .as_ref()
+            .split(';')
+            .map(|field| field.split_once('=').unwrap_or((field, "")))
+            .find(|(field_key, _)| *field_key == key)
+            .map(|(_, value)| value.to_string())
+    }
+
+    /// Popup lines with REF/ALT, QUAL, FILTER, INFO fields, and GT/DP/AD of each sample. All INFO fields are listed
+    /// if info_keys is None.
+    pub fn details(&self, info_keys: Option<&[String]>, sample_names: &[String]) -> Vec<String> {
+        let missing = |value: &str| match value {
+            "" => ".".to_string(),
+            value => value.to_string(),
+        };
+
+        let mut lines = vec![
+            format!(
+                "REF: {}  ALT: {}",
+                self.record.reference_bases(),
+                missing(self.record.alternate_bases().as_ref())
+            ),
+            format!(
+                "QUAL: {}  FILTER: {}",
+                self.record
+                    .quality_score()
+                    .and_then(|quality_score| quality_score.ok())
+                    .map_or(".".to_string(), |quality_score| quality_score.to_string()),
+                missing(self.record.filters().as_ref())
+            ),
+        ];
+
+        let info = match info_keys {
+            Some(info_keys) => info_keys
+                .iter()
+                .map(|key| match self.info_value(key).as_deref() {
+                    Some("") => key.to_string(),
+                    Some(value) => format!("{key}={value}"),
+                    None => format!("{key}=."),
+                })
+                .join(" "),
+            None => self.record.info().as_ref().replace(';', " "),
+        };
+        lines.push(format!("INFO: {}", missing(&info)));
+
+        let samples = self.record.samples();
+        let format_keys = samples.keys().iter().collect::<Vec<_>>();
+        for (index, sample) in samples.iter().enumerate() {
+            let values = sample.as_ref().split(':').collect::<Vec<_>>();
+            let fields = ["GT", "DP", "AD"]
+                .iter()
+                .map(|key| {
+                    let value = format_keys
+                        .iter()
+                        .position(|format_key| format_key == key)
+                        .and_then(|position| values.get(position))
+                        .map_or(".".to_string(), |value| missing(value));
+                    format!("{key}={value}")
+                })
+                .join(" ");
+            let sample_name = sample_names
+                .get(index)
+                .cloned()
+                .unwrap_or(format!("Sample {}", index + 1));
+            lines.push(format!("{sample_name}: {fields}"));
+        }
+
+        lines
+    }
+}
+
+/// QUAL threshold of a variant filter.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum QualThreshold {
+    /// QUAL > value.
+    Above(f32),
+    /// QUAL >= value.
+    AtLeast(f32),
+}
+
+/// Variants shown in variant tracks. The default shows all variants.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct VariantFilter {
+    /// Hide variants whose FILTER is not PASS.
+    pub pass_only: bool,
+
+    pub qual: Option<QualThreshold>,
+
+    /// Hide variants without QUAL ('.') when a QUAL threshold is set.
+    pub hide_missing_qual: bool,
+}
+
+// Thresholds are parsed from commands and are never NaN.
+impl Eq for VariantFilter {}
+
+impl VariantFilter {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether a variant with the FILTER column and QUAL is shown.
+    pub fn passes(&self, filters: &str, quality_score: Option<f32>) -> bool {
+        if self.pass_only && filters != "PASS" {
+            return false;
+        }
+
+        match (self.qual, quality_score) {
+            (None, _) => true,
+            (Some(_), None) => !self.hide_missing_qual,
+            (Some(QualThreshold::Above(threshold)), Some(quality_score)) => {
+                quality_score > threshold
+            }
+            (Some(QualThreshold::AtLeast(threshold)), Some(quality_score)) => {
+                quality_score >= threshold
+            }
+        }
+    }
+
+    pub fn passes_variant(&self, variant: &Variant) -> bool {
+        self.passes(
+            variant.record.filters().as_ref(),
+            variant
+                .record
+                .quality_score()
+                .and_then(|quality_score| quality_score.ok()),
+        )
+    }
+}
+
+impl std::fmt::Display for VariantFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut conditions = Vec::new();
+        if self.pass_only {
+            conditions.push("PASS".to_string());
+        }
+        match self.qual {
+            Some(QualThreshold::Above(threshold)) => conditions.push(format!("QUAL > {threshold}")),
+            Some(QualThreshold::AtLeast(threshold)) => {
+                conditions.push(format!("QUAL >= {threshold}"))
+            }
+            None => {}
+        }
+        if self.qual.is_some() {
+            conditions.push(match self.hide_missing_qual {
+                true => "missing QUAL hidden".to_string(),
+                false => "missing QUAL shown".to_string(),
+            });
+        }
+        match conditions.is_empty() {
+            true => write!(f, "all variants"),
+            false => write!(f, "{}", conditions.join(", ")),
+        }
+    }
+}
+
+/// Reference and alternate bases of a variant, used to find reads carrying the alternate allele.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantAllele {
+    pub contig_index: usize,
+
+    /// Variant start. 1-based.
+    pub position: u64,
+
+    pub reference: Vec<u8>,
+
+    pub alternate: Vec<u8>,
+}
+
+impl std::fmt::Display for VariantAllele {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}>{}",
+            self.position,
+            String::from_utf8_lossy(&self.reference),
+            String::from_utf8_lossy(&self.alternate)
+        )
+    }
+}
+
 impl GenomeInterval for Variant {
     fn contig_index(&self) -> usize {
         self.contig_index
@@ -117,6 +330,14 @@ impl VariantRepository {
     //     Ok(contigs)
     // }
 
+    /// Sample names in the VCF header.
+    pub fn read_sample_names(&self) -> Result<Vec<String>, TGVError> {
+        let mut vcf =
+            vcf::io::reader::Builder::default().build_from_path(self.vcf_path.as_str())?;
+        let header = vcf.read_header()?;
+        Ok(header.sample_names().iter().cloned().collect())
+    }
+
     pub fn read_variants(
         &self,
         contig_header: &ContigHeader,
@@ -150,3 +371,102 @@ impl VariantRepository {
         SortedIntervalCollection::new(variants)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn filter(
+        pass_only: bool,
+        qual: Option<QualThreshold>,
+        hide_missing_qual: bool,
+    ) -> VariantFilter {
+        VariantFilter {
+            pass_only,
+            qual,
+            hide_missing_qual,
+        }
+    }
+
+    #[rstest]
+    #[case(VariantFilter::default(), "q10", None, true)]
+    #[case(filter(true, None, false), "PASS", None, true)]
+    #[case(filter(true, None, false), "q10", Some(50.0), false)]
+    #[case(filter(true, None, false), "q10;s50", Some(50.0), false)]
+    // Missing FILTER ('.').
+    #[case(filter(true, None, false), "", Some(50.0), false)]
+    #[case(
+        filter(false, Some(QualThreshold::Above(30.0)), false),
+        "q10",
+        Some(30.5),
+        true
+    )]
+    #[case(
+        filter(false, Some(QualThreshold::Above(30.0)), false),
+        "PASS",
+        Some(30.0),
+        false
+    )]
+    #[case(
+        filter(false, Some(QualThreshold::AtLeast(30.0)), false),
+        "PASS",
+        Some(30.0),
+        true
+    )]
+    #[case(
+        filter(false, Some(QualThreshold::Above(30.0)), false),
+        "PASS",
+        None,
+        true
+    )]
+    #[case(
+        filter(false, Some(QualThreshold::Above(30.0)), true),
+        "PASS",
+        None,
+        false
+    )]
+    #[case(
+        filter(true, Some(QualThreshold::Above(30.0)), false),
+        "PASS",
+        Some(67.0),
+        true
+    )]
+    #[case(
+        filter(true, Some(QualThreshold::Above(30.0)), false),
+        "q10",
+        Some(67.0),
+        false
+    )]
+    #[case(
+        filter(true, Some(QualThreshold::Above(30.0)), false),
+        "PASS",
+        Some(3.0),
+        false
+    )]
+    // Missing QUAL is only filtered with a QUAL threshold.
+    #[case(filter(true, None, true), "PASS", None, true)]
+    fn test_variant_filter_passes(
+        #[case] variant_filter: VariantFilter,
+        #[case] filters: &str,
+        #[case] quality_score: Option<f32>,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(variant_filter.passes(filters, quality_score), expected);
+    }
+
+    #[rstest]
+    #[case(VariantFilter::default(), "all variants")]
+    #[case(filter(true, None, false), "PASS")]
+    #[case(
+        filter(true, Some(QualThreshold::Above(30.0)), true),
+        "PASS, QUAL > 30, missing QUAL hidden"
+    )]
+    #[case(
+        filter(false, Some(QualThreshold::AtLeast(2.5)), false),
+        "QUAL >= 2.5, missing QUAL shown"
+    )]
+    fn test_variant_filter_display(#[case] variant_filter: VariantFilter, #[case] expected: &str) {
+        assert_eq!(variant_filter.to_string(), expected);
+    }
+}
@@ -4,21 +4,45 @@ use crossterm::event::{self, Event, KeyEventKind};
 use ratatui::{Terminal, buffer::Buffer, prelude::Backend};
 
 use crate::{
-    layout::{AlignmentView, MainLayout},
-    message::Message,
+    layout::{AlignmentView, AreaType, MainLayout, Pane},
+    loading::{LoadedData, PendingLoads},
+    message::{Message, Movement},
     mouse::MouseRegister,
     register::{KeyRegisterType, Registers},
+    rendering::{Panes, Popup, contig_overview_x_range, to_thousand_separated},
     session::SessionFile,
     settings::Settings,
+    snapshot::{Snapshot, SnapshotGallery},
+    svg::buffer_to_svg,
+};
+use gv_core::{
+    error::TGVError,
+    intervals::{Focus, GenomeInterval, Region},
+    loci::LocusList,
+    message::{AlignmentDisplayOption, AlignmentFilter, CursorAnchor},
+    regions::TargetRegions,
+    repository::Repository,
+    sequence::DEFAULT_REPEAT_MIN_LENGTH,
+    settings::FilePath,
+    state::{State, ViewData},
+};
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
 };
-use gv_core::{error::TGVError, repository::Repository, settings::FilePath, state::State};
-use std::{path::PathBuf, time::Instant};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Scene {
     Main,
     Help,
     ContigList,
+    GeneList,
+    ContigOverview,
+    Snapshots,
+    Bookmarks,
+
+    /// The main view with a popup on top.
+    Popup,
 }
 
 pub struct App {
@@ -29,12 +53,28 @@ pub struct App {
     pub state: State,
     pub settings: Settings,
     pub repository: Repository,
+
+    /// Gene track and sequence loads running in the background. Their services are taken out of the repository
+    /// until the loads are applied.
+    pub pending_loads: PendingLoads,
+
     pub registers: Registers,
     pub mouse_register: MouseRegister,
 
     pub alignment_view: AlignmentView,
 
+    /// View and data of the inactive pane of a split screen. None without a split.
+    pub inactive_pane: Option<(AlignmentView, ViewData)>,
+
     pub scene: Scene,
+
+    pub snapshots: SnapshotGallery,
+
+    /// Content of the popup scene.
+    pub popup: Popup,
+
+    /// Text printed to stdout after the terminal is restored on exit.
+    pub exit_output: Vec<String>,
 }
 
 impl App {
@@ -47,20 +87,103 @@ impl App {
             session_path.display()
         );
 
+        let registers = Registers::new(settings.keys_path.as_deref())?;
+
         let (mut repository, contig_header, repository_file_indexes) =
             Repository::new(&settings.core).await?;
 
         let mut state = State::new(settings.core.reference.clone(), contig_header)?;
+        state.shown_read_categories = settings.core.shown_read_categories.clone();
+        state.insert_range = settings.core.insert_range;
+        state.max_reads = settings.core.max_reads;
+        state.data_source = repository.data_source(&settings.core.reference);
+        if let Some(gene_track) = &settings.core.gene_track {
+            state
+                .use_track(gene_track, repository.track_service_checked()?)
+                .await?;
+        }
 
         // Initiate empty track data
         settings.core.file_paths.iter().for_each(|path| match path {
             FilePath::AlignmentPath(_) => state.add_alignment_track(),
             FilePath::VariantPath(_) => state.add_variant_track(),
             FilePath::BedPath(_) => state.add_bed_track(),
+            FilePath::BigWigPath(_) => state.add_signal_track(),
         });
 
+        state.read_span = repository.read_span(&state.contig_header)?;
+        if let Some(read_span) = &state.read_span {
+            log::info!("Reads from stdin are loaded: read_span={read_span:?}");
+        }
+
+        if let Some(regions_path) = &settings.regions_path {
+            let (target_regions, warnings) =
+                TargetRegions::from_path(regions_path, &state.contig_header)?;
+            for warning in warnings {
+                log::warn!("{warning}: path={regions_path}");
+                state.add_message(warning);
+            }
+            log::info!(
+                "Loaded the target regions: path={} regions={}",
+                regions_path,
+                target_regions.len()
+            );
+            state.target_regions = target_regions;
+        }
+
         let focus = state.default_focus(&mut repository).await?;
 
+        // Restore filters and bookmarks from the session.
+        for (index, filters) in settings
+            .alignment_filters
+            .iter()
+            .enumerate()
+            .take(state.alignments.len())
+        {
+            if !filters.is_empty() {
+                let options = filters
+                    .iter()
+                    .cloned()
+                    .map(AlignmentDisplayOption::Filter)
+                    .collect();
+                state.set_alignment_options(index, &focus, options)?;
+            }
+        }
+        state.variant_filter = settings.variant_filter.clone();
+        for (name, locus) in settings.bookmarks.iter() {
+            let bookmark = locus.rsplit_once(':').and_then(|(contig_name, position)| {
+                Some(Focus {
+                    contig_index: state.contig_header.try_get_index_by_str(contig_name).ok()?,
+                    position: position.parse().ok()?,
+                })
+            });
+            match bookmark {
+                Some(bookmark) => {
+                    state.set_bookmark(name.clone(), bookmark);
+                }
+                None => {
+                    log::warn!("Skipping bookmark from the session: name={name} locus={locus}");
+                    state.add_message(format!(
+                        "Skipped bookmark @{name}: {locus} is not in the reference"
+                    ));
+                }
+            }
+        }
+
+        if let Some(loci_path) = &settings.loci_path {
+            let (loci, warnings) = LocusList::from_path(loci_path)?;
+            for warning in warnings {
+                log::warn!("{warning}: path={loci_path}");
+                state.add_message(warning);
+            }
+            log::info!(
+                "Loaded the locus list: path={} loci={}",
+                loci_path,
+                loci.loci.len()
+            );
+            state.loci = loci;
+        }
+
         let mut alignment_view = AlignmentView::new(focus, state.alignments.len());
         if let Some(zoom) = settings.zoom {
             alignment_view.zoom = zoom;
@@ -82,17 +205,25 @@ impl App {
             session_path,
             layout: MainLayout::new(&settings, &repository_file_indexes),
             alignment_view,
+            inactive_pane: None,
             state,
             settings: settings.clone(),
             repository,
-            registers: Registers::default(),
+            pending_loads: PendingLoads::default(),
+            registers,
             mouse_register: MouseRegister::default(),
             scene: Scene::Main,
+            snapshots: SnapshotGallery::default(),
+            popup: Popup::default(),
+            exit_output: Vec::new(),
         })
     }
 }
 
 impl App {
+    /// Wait after a resize event for further resize events before re-laying out.
+    const RESIZE_DEBOUNCE: Duration = Duration::from_millis(50);
+
     /// Main loop
     pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<(), TGVError> {
         log::info!("Starting the app event loop");
@@ -104,42 +235,60 @@ impl App {
 
         self.handle(self.settings.initial_state_messages.clone())
             .await?;
+        if self.settings.test_mode {
+            self.finish_loads().await?;
+        }
 
         self.alignment_view.self_correct(
-            &self.layout.main_area,
+            &self.layout.view_area(),
             self.state.contig_length(&self.alignment_view.focus)?,
         );
 
+        // Event read while debouncing a resize, handled in the next loop.
+        let mut pending_event: Option<Event> = None;
+
         while !self.exit {
             // Render
             // FIXME: improve rendering performance. Not all sections need to be re-rendered at every loop.
             //
-            let mut refresh_terminal = false;
-            let mut render_result = Ok(());
+            let mut render_result = Ok(false);
 
             terminal
                 .draw(|frame| {
-                    let buffer = frame.buffer_mut();
-                    refresh_terminal = self.layout.set_area(buffer.area);
-                    render_result = self.render(buffer);
+                    render_result = self.render_frame(frame.buffer_mut());
                 })
                 .map_err(|e| TGVError::IOError(format!("Failed to draw the terminal: {e}")))?;
-            render_result?;
+            let refresh_terminal = render_result?;
 
             if self.settings.test_mode {
                 break;
             }
 
             // handle events
+            // While loading in the background, wake up to apply finished loads and advance the spinner.
+            let next_event = match pending_event.take() {
+                Some(event) => Ok(Some(event)),
+                None if !self.pending_loads.is_empty() => {
+                    if event::poll(PendingLoads::SPINNER_INTERVAL)? {
+                        event::read().map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                }
+                None => event::read().map(Some),
+            };
             match {
-                match event::read() {
-                    Ok(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press => {
-                        let state_messages =
-                            self.registers.handle_key_event(key_event, &self.state)?;
+                match next_event {
+                    Ok(Some(Event::Key(key_event))) if key_event.kind == KeyEventKind::Press => {
+                        let state_messages = self.registers.handle_key_event(
+                            key_event,
+                            &self.state,
+                            &self.snapshots,
+                        )?;
                         self.handle(state_messages).await // TODO: this should not error out?
                     }
 
-                    Ok(Event::Mouse(mouse_event)) => {
+                    Ok(Some(Event::Mouse(mouse_event))) => {
                         let state_messages = self.mouse_register.handle_mouse_event(
                             &self.state,
                             &mut self.layout,
@@ -150,15 +299,25 @@ impl App {
                         self.handle(state_messages).await // TODO: this should not error out?
                     }
 
-                    Ok(Event::Resize(_width, _height)) => {
-                        log::debug!("Terminal resized to {_width}x{_height}");
-                        self.alignment_view.self_correct(
-                            &self.layout.main_area,
-                            self.state.contig_length(&self.alignment_view.focus)?,
-                        );
+                    Ok(Some(Event::Resize(mut width, mut height))) => {
+                        // Coalesce rapid resizes (e.g. dragging the window) into one re-layout at the next draw.
+                        while event::poll(Self::RESIZE_DEBOUNCE)? {
+                            match event::read()? {
+                                Event::Resize(next_width, next_height) => {
+                                    (width, height) = (next_width, next_height);
+                                }
+                                event => {
+                                    pending_event = Some(event);
+                                    break;
+                                }
+                            }
+                        }
+                        log::debug!("Terminal resized to {width}x{height}");
                         Ok(())
                     }
 
+                    Ok(None) => self.apply_finished_loads().await,
+
                     _ => Ok(()),
                 }
             } {
@@ -170,7 +329,7 @@ impl App {
             }
 
             self.alignment_view.self_correct(
-                &self.layout.main_area,
+                &self.layout.view_area(),
                 self.state.contig_length(&self.alignment_view.focus)?,
             );
 
@@ -185,21 +344,226 @@ impl App {
 
     /// close connections
     pub async fn close(mut self) -> Result<(), TGVError> {
+        // Services of loads in flight are closed too.
+        for loaded in self.pending_loads.take_finished(true).await {
+            match loaded {
+                Ok(LoadedData::Track { service, .. }) => {
+                    self.repository.track_service = Some(*service)
+                }
+                Ok(LoadedData::Sequence { service, .. }) => {
+                    self.repository.sequence_service = Some(*service)
+                }
+                Err(e) => log::warn!("{e}"),
+            }
+        }
         self.repository.close().await
     }
 
+    /// Put the services of finished background loads back into the repository and use the loaded data. Data of a
+    /// region that the view has since left are dropped, so that load_data replaces them with the new region.
+    /// Returns the first error after all loads are applied.
+    fn apply_loaded_data(
+        &mut self,
+        loaded: Vec<Result<LoadedData, TGVError>>,
+    ) -> Result<(), TGVError> {
+        let view_region = self.alignment_view.region(&self.layout.view_area());
+        let is_stale = |region: &Region| {
+            !region.overlaps(
+                view_region.contig_index(),
+                view_region.start(),
+                view_region.end(),
+            )
+        };
+        let mut result = Ok(());
+        for loaded in loaded {
+            match loaded {
+                Ok(LoadedData::Track {
+                    service, region, ..
+                }) if is_stale(&region) => {
+                    log::debug!("Dropping stale reference track data: region={region:?}");
+                    self.repository.track_service = Some(*service);
+                }
+                Ok(LoadedData::Sequence {
+                    service, region, ..
+                }) if is_stale(&region) => {
+                    log::debug!("Dropping stale sequence data: region={region:?}");
+                    self.repository.sequence_service = Some(*service);
+                }
+                Ok(LoadedData::Track {
+                    service,
+                    region,
+                    result: track,
+                }) => {
+                    self.repository.track_service = Some(*service);
+                    match track {
+                        Ok(track) => {
+                            log::debug!(
+                                "Loaded reference track data: region={:?} features={}",
+                                region,
+                                track.features.len(),
+                            );
+                            self.state.track = track;
+                        }
+                        Err(e) => {
+                            log::warn!(
+                                "Failed to load reference track data: region={region:?} error={e}"
+                            );
+                            result = result.and(Err(e));
+                        }
+                    }
+                }
+                Ok(LoadedData::Sequence {
+                    service,
+                    region,
+                    result: sequence,
+                }) => {
+                    self.repository.sequence_service = Some(*service);
+                    match sequence {
+                        Ok(sequence) => {
+                            log::debug!(
+                                "Loaded sequence data: region={:?} bases={}",
+                                region,
+                                sequence.len(),
+                            );
+                            self.state.sequence = sequence;
+                        }
+                        Err(e) => {
+                            log::warn!("Failed to load sequence data: region={region:?} error={e}");
+                            result = result.and(Err(e));
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("{e}");
+                    result = result.and(Err(e));
+                }
+            }
+        }
+        result
+    }
+
+    /// Apply the background loads that have finished, then load the data that waited for them (alignments wait for
+    /// the sequence). Does not block on loads still running.
+    async fn apply_finished_loads(&mut self) -> Result<(), TGVError> {
+        let loaded = self.pending_loads.take_finished(false).await;
+        if loaded.is_empty() {
+            return Ok(());
+        }
+        self.apply_loaded_data(loaded)?;
+        self.load_data().await
+    }
+
+    /// Wait for all background loads and apply them, including loads started by the data that waited for them.
+    pub async fn finish_loads(&mut self) -> Result<(), TGVError> {
+        while !self.pending_loads.is_empty() {
+            let loaded = self.pending_loads.take_finished(true).await;
+            self.apply_loaded_data(loaded)?;
+            self.load_data().await?;
+        }
+        Ok(())
+    }
+
     fn save_session_to_path(&mut self, path: PathBuf) -> Result<(), TGVError> {
         SessionFile::try_from(&*self).and_then(|s| s.write_to_path(&path))?;
         self.session_path = path;
         Ok(())
     }
 
+    /// Render the main view into an off-screen buffer and add it to the snapshot gallery.
+    fn capture_snapshot(&mut self, label: String) -> Result<(), TGVError> {
+        let mut buffer = Buffer::empty(self.layout.main_area);
+        crate::rendering::render_main(
+            &mut buffer,
+            &mut self.state,
+            &self.registers,
+            Panes {
+                layout: &self.layout,
+                alignment_view: &self.alignment_view,
+                inactive_pane: self.inactive_pane.as_mut(),
+            },
+            &self.mouse_register,
+            &self.settings.palette,
+        )?;
+        self.snapshots.push(Snapshot { label, buffer });
+        Ok(())
+    }
+
+    /// Render the main view into an off-screen buffer and write it to an SVG file.
+    fn export_svg(&mut self, path: &str) -> Result<(), TGVError> {
+        let mut buffer = Buffer::empty(self.layout.main_area);
+        crate::rendering::render_main(
+            &mut buffer,
+            &mut self.state,
+            &self.registers,
+            Panes {
+                layout: &self.layout,
+                alignment_view: &self.alignment_view,
+                inactive_pane: self.inactive_pane.as_mut(),
+            },
+            &self.mouse_register,
+            &self.settings.palette,
+        )?;
+        std::fs::write(path, buffer_to_svg(&buffer, &self.settings.palette))?;
+        Ok(())
+    }
+
     /// Handle messages after initialization. This blocks any error messages instead of propagating them.
     pub async fn handle(&mut self, messages: Vec<Message>) -> Result<(), TGVError> {
+        let display_options = (
+            self.state.alignment_options.clone(),
+            self.state.revcomp,
+            self.state.min_mismatch_quality,
+            self.state.downsample_depth(),
+        );
+        let result = self.handle_messages(messages).await;
+
+        // The inactive pane of a split screen shares alignment options with the active pane. Re-apply them even if
+        // a message failed, so that the inactive pane is never rendered with options its data lacks (e.g. pairs).
+        if let Some((alignment_view, data)) = self.inactive_pane.as_mut()
+            && display_options
+                != (
+                    self.state.alignment_options.clone(),
+                    self.state.revcomp,
+                    self.state.min_mismatch_quality,
+                    self.state.downsample_depth(),
+                )
+        {
+            self.state.swap_view_data(data);
+            let reapplied = self.state.reapply_alignment_options(&alignment_view.focus);
+            self.state.swap_view_data(data);
+            reapplied?;
+        }
+        result
+    }
+
+    async fn handle_messages(&mut self, messages: Vec<Message>) -> Result<(), TGVError> {
         self.state.messages.clear();
 
         for message in messages {
+            if needs_loaded_data(&message) {
+                self.finish_loads().await?;
+            }
+
             match message {
+                Message::Core(gv_core::message::Message::Move(
+                    movement @ (Movement::Left(_) | Movement::Right(_)),
+                )) if self.alignment_view.cursor.is_some() => {
+                    let cursor = self.alignment_view.cursor_focus().position;
+                    let position = match movement {
+                        Movement::Left(n) => cursor.saturating_sub(n * self.alignment_view.zoom),
+                        Movement::Right(n) => cursor.saturating_add(n * self.alignment_view.zoom),
+                        _ => cursor,
+                    };
+                    log::debug!(
+                        "Moving cursor: previous_cursor={} cursor={}",
+                        cursor,
+                        position
+                    );
+                    self.alignment_view
+                        .move_cursor(position, &self.layout.view_area());
+                    self.load_data().await?
+                }
+
                 Message::Core(gv_core::message::Message::Move(movement)) => {
                     let previous_focus = self.alignment_view.focus.clone();
                     log::debug!(
@@ -208,10 +572,17 @@ impl App {
                         previous_focus,
                         self.alignment_view.zoom,
                     );
+                    // Motif searches start from the cursor.
+                    let from = match movement {
+                        Movement::NextMatches(_) | Movement::PreviousMatches(_) => {
+                            self.alignment_view.cursor_focus()
+                        }
+                        _ => self.alignment_view.focus.clone(),
+                    };
                     let focus = self
                         .state
                         .movement(
-                            self.alignment_view.focus.clone(),
+                            from,
                             self.alignment_view.zoom,
                             &mut self.repository,
                             movement.clone(),
@@ -224,7 +595,103 @@ impl App {
                         previous_focus,
                         focus,
                     );
+                    if matches!(
+                        movement,
+                        Movement::NextVariants(_) | Movement::PreviousVariants(_)
+                    ) && focus.contig_index != previous_focus.contig_index
+                    {
+                        self.state.add_message(format!(
+                            "No more variants on {}. Moved to {}",
+                            self.state
+                                .contig_header
+                                .try_get(previous_focus.contig_index)?
+                                .name,
+                            self.state.contig_header.try_get(focus.contig_index)?.name,
+                        ));
+                    }
                     self.alignment_view.focus = focus;
+                    self.alignment_view.self_correct(
+                        &self.layout.view_area(),
+                        self.state.contig_length(&self.alignment_view.focus)?,
+                    );
+                    if self.alignment_view.cursor.is_some() {
+                        self.alignment_view.cursor = Some(self.alignment_view.focus.position);
+                    }
+                    self.load_data().await?
+                }
+
+                Message::Core(gv_core::message::Message::FrameGene(gene_name, frame)) => {
+                    let mut region = self
+                        .state
+                        .framed_gene(&mut self.repository, &gene_name, frame)
+                        .await?;
+                    region.focus = self.state.restrict_to_target_regions(region.focus, true);
+                    region.focus = self.state.restrict_to_read_span(region.focus);
+                    log::debug!(
+                        "Framing gene: gene={} frame={} region={:?}",
+                        gene_name,
+                        frame,
+                        region,
+                    );
+                    let contig_length = self.state.contig_length(&region.focus)?;
+                    self.alignment_view
+                        .frame(&region, &self.layout.view_area(), contig_length);
+                    if self.alignment_view.cursor.is_some() {
+                        self.alignment_view.cursor = Some(self.alignment_view.focus.position);
+                    }
+                    self.load_data().await?
+                }
+
+                Message::Core(gv_core::message::Message::FrameBand(contig_name, band)) => {
+                    let mut region = self
+                        .state
+                        .framed_band(&mut self.repository, &contig_name, &band)
+                        .await?;
+                    region.focus = self.state.restrict_to_target_regions(region.focus, true);
+                    region.focus = self.state.restrict_to_read_span(region.focus);
+                    log::debug!(
+                        "Framing band: contig={} band={} region={:?}",
+                        contig_name,
+                        band,
+                        region,
+                    );
+                    let contig_length = self.state.contig_length(&region.focus)?;
+                    self.alignment_view
+                        .frame(&region, &self.layout.view_area(), contig_length);
+                    if self.alignment_view.cursor.is_some() {
+                        self.alignment_view.cursor = Some(self.alignment_view.focus.position);
+                    }
+                    self.load_data().await?
+                }
+
+                Message::Core(gv_core::message::Message::SetMotif(motif)) => {
+                    if motif.is_none() && self.state.motif.is_some() {
+                        self.state
+                            .add_message("Search cleared. n / N jump to loci again".to_string());
+                    }
+                    self.state.motif = motif;
+                }
+
+                Message::Core(gv_core::message::Message::CenterCursor(anchor)) => {
+                    if self.alignment_view.cursor.is_none() && anchor != CursorAnchor::Center {
+                        self.state.add_message(
+                            "The cursor is always centered with cursor mode off. Turn it on with :cursor on"
+                                .to_string(),
+                        );
+                        continue;
+                    }
+                    log::debug!(
+                        "Centering cursor: anchor={:?} cursor={:?} focus={:?}",
+                        anchor,
+                        self.alignment_view.cursor,
+                        self.alignment_view.focus,
+                    );
+                    let contig_length = self.state.contig_length(&self.alignment_view.focus)?;
+                    self.alignment_view.center_cursor(
+                        anchor,
+                        &self.layout.view_area(),
+                        contig_length,
+                    );
                     self.load_data().await?
                 }
 
@@ -310,7 +777,7 @@ impl App {
                     );
                     self.alignment_view.zoom(
                         zoom.clone(),
-                        &self.layout.main_area,
+                        &self.layout.view_area(),
                         contig_length,
                     )?; // TODO
                     log::debug!(
@@ -329,14 +796,586 @@ impl App {
                         self.state.alignments.len(),
                         options,
                     );
-                    // TODO: introduce focus. Only apply option to the alignment in focus
-                    for index in 0..self.state.alignments.len() {
-                        self.state.set_alignment_options(
+                    // Options at the current position (e.g. :sort base) use the cursor.
+                    let cursor_focus = self.alignment_view.cursor_focus();
+                    for index in self.selected_alignment_indexes() {
+                        if options.is_empty() {
+                            // :clear resets all options.
+                            self.state
+                                .set_alignment_options(index, &cursor_focus, Vec::new())?;
+                        } else {
+                            self.state.add_alignment_options(
+                                index,
+                                &cursor_focus,
+                                options.clone(),
+                            )?;
+                        }
+                    }
+                }
+
+                Message::Core(gv_core::message::Message::SetMinMapq(mapping_quality)) => {
+                    for index in self.selected_alignment_indexes() {
+                        self.state.set_min_mapq(
+                            index,
+                            &self.alignment_view.focus,
+                            mapping_quality,
+                        )?;
+                    }
+                    self.state.add_message(match mapping_quality {
+                        0 => "Showing reads of any MAPQ".to_string(),
+                        _ => format!("Hiding reads with MAPQ < {mapping_quality}"),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::IsolateAllele(isolated_allele)) => {
+                    let filter = match isolated_allele {
+                        Some(isolated_allele) => {
+                            let allele = self
+                                .state
+                                .variant_allele_near(&self.alignment_view.cursor_focus(), 0)
+                                .map_err(|_| {
+                                    TGVError::StateError(
+                                        "No variant at the cursor. Move the cursor onto a variant to isolate its reads"
+                                            .to_string(),
+                                    )
+                                })?;
+                            Some(AlignmentFilter::Allele(isolated_allele, allele))
+                        }
+                        None => None,
+                    };
+                    for index in self.selected_alignment_indexes() {
+                        self.state.set_isolated_allele(
                             index,
                             &self.alignment_view.focus,
-                            options.clone(),
+                            filter.clone(),
                         )?;
                     }
+                    self.state.add_message(match filter {
+                        Some(filter) => {
+                            let shown = self
+                                .selected_alignment_indexes()
+                                .into_iter()
+                                .map(|index| {
+                                    self.state.alignments[index]
+                                        .show_read
+                                        .iter()
+                                        .filter(|show| **show)
+                                        .count()
+                                })
+                                .sum::<usize>();
+                            format!("Showing {shown} reads with {filter}")
+                        }
+                        None => "Showing reads of all alleles".to_string(),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetViewAsPairs(view_as_pairs)) => {
+                    for index in self.selected_alignment_indexes() {
+                        self.state.set_view_as_pairs(
+                            index,
+                            &self.alignment_view.focus,
+                            view_as_pairs,
+                        )?;
+                    }
+                    self.state.add_message(if view_as_pairs {
+                        "Viewing reads as pairs".to_string()
+                    } else {
+                        "Viewing reads one by one".to_string()
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::ClearAlignmentFilter) => {
+                    for index in self.selected_alignment_indexes() {
+                        self.state
+                            .clear_alignment_filter(index, &self.alignment_view.focus)?;
+                    }
+                    self.state
+                        .add_message("Cleared alignment filters".to_string());
+                }
+
+                Message::Core(gv_core::message::Message::ShowHiddenReads) => {
+                    self.state.show_all_reads();
+                    self.load_data().await?;
+                    self.state.add_message(
+                        "Showing unmapped, secondary, supplementary, and duplicate reads"
+                            .to_string(),
+                    );
+                }
+
+                Message::Core(gv_core::message::Message::ShowReads(category)) => {
+                    self.state.show_hidden_reads(category);
+                    self.load_data().await?;
+                    self.state.add_message(format!("Showing {category} reads"));
+                }
+
+                Message::Core(gv_core::message::Message::HideReads(category)) => {
+                    self.state.hide_reads(category);
+                    self.load_data().await?;
+                    self.state.add_message(format!("Hiding {category} reads"));
+                }
+
+                Message::Core(gv_core::message::Message::HighlightVariantSupport) => {
+                    let area = &self.layout.view_area();
+                    let max_distance =
+                        (self.alignment_view.right(area) - self.alignment_view.left(area)) / 2;
+                    let allele = self
+                        .state
+                        .variant_allele_near(&self.alignment_view.cursor_focus(), max_distance)?;
+                    let (covering, supporting) = self
+                        .state
+                        .alignments
+                        .iter()
+                        .map(|alignment| alignment.allele_support(&allele))
+                        .fold((0, 0), |(covering, supporting), (c, s)| {
+                            (covering + c, supporting + s)
+                        });
+                    self.state
+                        .add_message(format!("{supporting} of {covering} reads support {allele}"));
+                    self.state.supported_allele = Some(allele);
+                }
+
+                Message::Core(gv_core::message::Message::SetVariantFilter(variant_filter)) => {
+                    let (shown, total) = self
+                        .state
+                        .variants
+                        .iter()
+                        .flat_map(|variant_track| variant_track.intervals.iter())
+                        .fold((0, 0), |(shown, total), variant| {
+                            (
+                                shown + variant_filter.passes_variant(variant) as usize,
+                                total + 1,
+                            )
+                        });
+                    log::debug!(
+                        "Setting variant filter: filter={:?} shown={} total={}",
+                        variant_filter,
+                        shown,
+                        total
+                    );
+                    self.state.add_message(format!(
+                        "Showing {shown} of {total} variants: {variant_filter}"
+                    ));
+                    self.state.variant_filter = variant_filter;
+                }
+
+                Message::Core(gv_core::message::Message::ClearVariantSupport) => {
+                    self.state.supported_allele = None;
+                }
+
+                Message::Core(gv_core::message::Message::ListTracks) => {
+                    let track_service = self.repository.track_service_checked()?;
+                    let track_names = self.state.available_track_names(track_service).await?;
+                    log::debug!("Listing tracks: count={}", track_names.len());
+                    self.state.add_message(format!(
+                        "Available tracks ({}): {}",
+                        track_names.len(),
+                        track_names.join(", ")
+                    ));
+                }
+
+                Message::Core(gv_core::message::Message::ReloadFiles) => {
+                    let warnings = self.repository.reload_files(&self.settings.core).await?;
+                    self.state.clear_file_data();
+                    if let Some((_, data)) = self.inactive_pane.as_mut() {
+                        data.clear_file_data();
+                    }
+                    self.load_data().await?;
+                    let mut message =
+                        format!("Reloaded {} files", self.settings.core.file_paths.len());
+                    for warning in warnings {
+                        message += &format!(". {warning}");
+                    }
+                    self.state.add_message(message);
+                }
+
+                Message::Core(gv_core::message::Message::UseTrack(track_name)) => {
+                    let track_service = self.repository.track_service_checked()?;
+                    self.state.use_track(&track_name, track_service).await?;
+                    self.load_data().await?;
+                    self.state
+                        .add_message(format!("Using gene track {track_name}"));
+                }
+
+                Message::Core(gv_core::message::Message::FindGene(pattern)) => {
+                    let gene_names = self
+                        .state
+                        .gene_names_matching(&mut self.repository, &pattern, Self::GENE_LIST_LIMIT)
+                        .await?;
+                    log::debug!(
+                        "Listing genes: pattern={} count={}",
+                        pattern,
+                        gene_names.len()
+                    );
+                    if gene_names.is_empty() {
+                        self.state.add_message(format!("No genes match {pattern}"));
+                    } else {
+                        self.registers.gene_list = gene_names;
+                        self.registers.gene_list_cursor = 0;
+                        self.scene = Scene::GeneList;
+                        self.registers.current = KeyRegisterType::GeneList;
+                    }
+                }
+
+                Message::Core(gv_core::message::Message::ShowContigOverview) => {
+                    let contig_index = self.alignment_view.focus.contig_index;
+                    if let Some(alignment_repository) =
+                        self.repository.alignment_repositories.first()
+                        && self
+                            .state
+                            .coverage_overview
+                            .as_ref()
+                            .map(|overview| overview.contig_index)
+                            != Some(contig_index)
+                    {
+                        self.state
+                            .load_coverage_overview(contig_index, alignment_repository)?;
+                    }
+                    let overview = self
+                        .state
+                        .contig_overview(
+                            &mut self.repository,
+                            contig_index,
+                            self.layout.main_area.width as usize,
+                        )
+                        .await?;
+                    log::debug!(
+                        "Showing contig overview: contig_index={} bins={}",
+                        contig_index,
+                        overview.bins()
+                    );
+                    self.registers.contig_overview_cursor =
+                        overview.bin_index(self.alignment_view.focus.position);
+                    self.registers.contig_overview = overview;
+                    self.scene = Scene::ContigOverview;
+                    self.registers.current = KeyRegisterType::ContigOverview;
+                }
+
+                Message::Core(gv_core::message::Message::SwitchPane) => {
+                    self.switch_pane().await?;
+                }
+
+                Message::Core(gv_core::message::Message::CycleAlignmentTrack) => {
+                    let alignment_count = self.state.alignments.len();
+                    if alignment_count < 2 {
+                        self.state.add_message(
+                            "Only one alignment track is loaded. Load several BAM files to switch between them"
+                                .to_string(),
+                        );
+                        continue;
+                    }
+                    self.registers.alignment_track = match self.registers.alignment_track {
+                        None => Some(0),
+                        Some(index) if index + 1 < alignment_count => Some(index + 1),
+                        Some(_) => None,
+                    };
+                    self.state
+                        .add_message(match self.registers.alignment_track {
+                            Some(index) => format!(
+                                "Keys and commands act on track {}/{}: {}",
+                                index + 1,
+                                alignment_count,
+                                self.layout
+                                    .alignment_labels
+                                    .get(index)
+                                    .map_or("", String::as_str)
+                            ),
+                            None => "Commands act on all alignment tracks".to_string(),
+                        });
+                }
+
+                Message::Core(gv_core::message::Message::SetGeneticCode(genetic_code)) => {
+                    self.state.genetic_code = genetic_code;
+                    let genetic_code = self.state.genetic_code(&self.alignment_view.focus)?;
+                    self.state.add_message(match self.state.genetic_code {
+                        Some(_) => format!("Using genetic code {genetic_code}"),
+                        None => format!("Using genetic code by contig ({genetic_code})"),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetMasking(show_masking)) => {
+                    self.state.show_masking = show_masking;
+                    self.state.add_message(if show_masking {
+                        "Soft-masked bases are dimmed".to_string()
+                    } else {
+                        "Soft-masked bases are shown like other bases".to_string()
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::ToggleRepeats) => {
+                    self.state.repeat_min_length = match self.state.repeat_min_length {
+                        Some(_) => None,
+                        None => Some(DEFAULT_REPEAT_MIN_LENGTH),
+                    };
+                    self.add_repeats_message();
+                }
+
+                Message::Core(gv_core::message::Message::SetRepeatMinLength(min_length)) => {
+                    self.state.repeat_min_length = min_length;
+                    self.add_repeats_message();
+                }
+
+                Message::Core(gv_core::message::Message::ToggleCpg) => {
+                    self.state.show_cpg = !self.state.show_cpg;
+                    self.state.add_message(if self.state.show_cpg {
+                        "Marking CpG dinucleotides in the sequence".to_string()
+                    } else {
+                        "CpG dinucleotides are not marked".to_string()
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::ToggleRevcomp) => {
+                    self.state.set_revcomp(!self.state.revcomp);
+                    self.state.add_message(if self.state.revcomp {
+                        "Showing the minus strand, 3' to 5'".to_string()
+                    } else {
+                        "Showing the plus strand, 5' to 3'".to_string()
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetMismatchQuality(min_quality)) => {
+                    self.state.set_min_mismatch_quality(min_quality);
+                    self.state.add_message(match min_quality {
+                        0 => "Showing mismatches of any base quality".to_string(),
+                        _ => format!(
+                            "Showing mismatches with base quality < {min_quality} as matches"
+                        ),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetRulerInterval(ruler_interval)) => {
+                    self.state.ruler_interval = ruler_interval;
+                    self.state.add_message(match ruler_interval {
+                        Some(interval) => format!(
+                            "Ruler major ticks every {} bp",
+                            to_thousand_separated(interval)
+                        ),
+                        None => "Ruler ticks spaced by the zoom".to_string(),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetCoordinateBase(coordinate_base)) => {
+                    self.state.coordinate_base = coordinate_base;
+                    self.state
+                        .add_message(format!("Showing {coordinate_base} coordinates"));
+                }
+
+                Message::Core(gv_core::message::Message::SetCoverageMax(coverage_max)) => {
+                    self.state.coverage_max = coverage_max;
+                    self.state.add_message(match coverage_max {
+                        Some(coverage_max) => {
+                            format!("Coverage axis capped at depth {coverage_max}")
+                        }
+                        None => "Coverage axis scaled to the view".to_string(),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::SetDownsample(downsample)) => {
+                    self.state
+                        .set_downsample(downsample, &self.alignment_view.focus)?;
+                    self.state.add_message(match self.state.downsample_depth() {
+                        Some(depth) => format!("Downsampling to {depth} reads per column"),
+                        None => "Downsampling off".to_string(),
+                    });
+                }
+
+                Message::Core(gv_core::message::Message::LookupCodon) => {
+                    let (codon, amino_acid) =
+                        self.state.codon_at(&self.alignment_view.cursor_focus())?;
+                    let genetic_code = self.state.genetic_code(&self.alignment_view.focus)?;
+                    self.state.add_message(format!(
+                        "Codon {} -> {} ({genetic_code})",
+                        String::from_utf8_lossy(&codon).to_uppercase(),
+                        amino_acid.map(char::from).unwrap_or('?'),
+                    ));
+                }
+
+                Message::Core(gv_core::message::Message::LookupGene) => {
+                    let cursor_focus = self.alignment_view.cursor_focus();
+                    let message = match self
+                        .state
+                        .nearest_gene(&cursor_focus, &mut self.repository)
+                        .await?
+                    {
+                        Some((gene, 0)) => format!(
+                            "{} ({}) {}: {}",
+                            gene.name,
+                            gene.strand,
+                            gene.id,
+                            gene.region_at(cursor_focus.position)
+                                .map_or("transcript".to_string(), |region| region.to_string())
+                        ),
+                        Some((gene, distance)) => format!(
+                            "No gene at the cursor. Nearest: {} ({}) {}, {} bp {}",
+                            gene.name,
+                            gene.strand,
+                            gene.id,
+                            to_thousand_separated(distance),
+                            if gene.transcription_start > cursor_focus.position {
+                                "right"
+                            } else {
+                                "left"
+                            }
+                        ),
+                        None => "No genes on this contig".to_string(),
+                    };
+                    self.state.add_message(message);
+                }
+
+                Message::Core(gv_core::message::Message::ShowInsertions) => {
+                    let cursor_focus = self.alignment_view.cursor_focus();
+                    let insertions = self.state.insertions_at(&cursor_focus)?;
+                    self.popup = Popup {
+                        title: format!(
+                            "Insertions at {}:{}",
+                            self.state.contig_name(&cursor_focus)?,
+                            cursor_focus.position
+                        ),
+                        lines: insertions
+                            .into_iter()
+                            .map(|(bases, count)| {
+                                format!(
+                                    "{} ({} bp): {count} read{}",
+                                    String::from_utf8_lossy(&bases),
+                                    bases.len(),
+                                    if count == 1 { "" } else { "s" }
+                                )
+                            })
+                            .collect(),
+                    };
+                    self.scene = Scene::Popup;
+                    self.registers.current = KeyRegisterType::Popup;
+                }
+
+                Message::Core(gv_core::message::Message::ShowIndelHistogram) => {
+                    let region = self.alignment_view.region(&self.layout.view_area());
+                    let histogram = self.state.indel_histogram(&region)?;
+                    self.popup = Popup {
+                        title: format!(
+                            "Indels in {}:{}-{}",
+                            self.state.contig_name(&region.focus)?,
+                            region.start(),
+                            region.end()
+                        ),
+                        lines: histogram.lines(self.settings.palette.glyphs.block()),
+                    };
+                    self.scene = Scene::Popup;
+                    self.registers.current = KeyRegisterType::Popup;
+                }
+
+                Message::Core(gv_core::message::Message::ShowVariantDetails) => {
+                    let cursor_focus = self.alignment_view.cursor_focus();
+                    let mut lines = Vec::new();
+                    for (index, variant) in self.state.variants_at(&cursor_focus)? {
+                        let sample_names = match self.repository.variant_repositories.get(index) {
+                            Some(variant_repository) => variant_repository.read_sample_names()?,
+                            None => Vec::new(),
+                        };
+                        if !lines.is_empty() {
+                            lines.push(String::new());
+                        }
+                        lines.extend(
+                            variant
+                                .details(self.state.variant_info_fields.as_deref(), &sample_names),
+                        );
+                    }
+                    self.popup = Popup {
+                        title: format!(
+                            "Variants at {}:{}",
+                            self.state.contig_name(&cursor_focus)?,
+                            cursor_focus.position
+                        ),
+                        lines,
+                    };
+                    self.scene = Scene::Popup;
+                    self.registers.current = KeyRegisterType::Popup;
+                }
+
+                Message::Core(gv_core::message::Message::SetVariantInfoFields(fields)) => {
+                    self.state.add_message(match &fields {
+                        Some(fields) => format!("Variant popup INFO fields: {}", fields.join(", ")),
+                        None => "Variant popup lists all INFO fields".to_string(),
+                    });
+                    self.state.variant_info_fields = fields;
+                }
+
+                Message::Core(gv_core::message::Message::ExportReads(path)) => {
+                    let path = shellexpand::tilde(&path).to_string();
+                    let region = self.alignment_view.region(&self.layout.view_area());
+                    let reads = self.state.shown_reads_overlapping(0, &region)?;
+                    let alignment_repository = self
+                        .repository
+                        .alignment_repositories
+                        .first()
+                        .ok_or(TGVError::StateError("No alignments are loaded".to_string()))?;
+                    let record_count = alignment_repository
+                        .write_bam(&path, reads.iter().map(|read| &read.record))?;
+                    log::info!(
+                        "Exported reads: path={} region={:?} reads={}",
+                        path,
+                        region,
+                        record_count
+                    );
+                    self.state
+                        .add_message(format!("Exported {record_count} reads to {path}"));
+                }
+
+                Message::Core(gv_core::message::Message::ExportCoverage(path, bin_size)) => {
+                    let path = shellexpand::tilde(&path).to_string();
+                    let region = self.alignment_view.region(&self.layout.view_area());
+                    let bedgraph = self.state.coverage_bedgraph(0, &region, bin_size)?;
+                    std::fs::write(&path, &bedgraph)?;
+                    log::info!(
+                        "Exported coverage: path={} region={:?} bin_size={}",
+                        path,
+                        region,
+                        bin_size
+                    );
+                    self.state.add_message(format!(
+                        "Exported coverage of {} bins to {path}",
+                        bedgraph.lines().count()
+                    ));
+                }
+
+                Message::Core(gv_core::message::Message::SetBookmark(name)) => {
+                    let focus = self.alignment_view.focus.clone();
+                    let locus = focus.to_locus_str(&self.state.contig_header)?;
+                    let message = match self.state.set_bookmark(name.clone(), focus) {
+                        Some(_) => format!("Moved bookmark @{name} to {locus}"),
+                        None => format!("Saved bookmark @{name} at {locus}"),
+                    };
+                    self.state.add_message(message);
+                }
+
+                Message::Core(gv_core::message::Message::ExportSequence(strand, path)) => {
+                    let region = self.alignment_view.region(&self.layout.view_area());
+                    if let Some(sequence_service) = self.repository.sequence_service.as_mut()
+                        && !self.state.sequence.has_complete_data(&region)
+                    {
+                        self.state
+                            .load_sequence_data(&region, sequence_service)
+                            .await?;
+                    }
+                    let fasta = self.state.reference_fasta(&region, &strand)?;
+                    log::info!(
+                        "Exporting sequence: path={:?} region={:?} strand={}",
+                        path,
+                        region,
+                        strand
+                    );
+                    match path {
+                        Some(path) => {
+                            let path = shellexpand::tilde(&path).to_string();
+                            std::fs::write(&path, fasta)?;
+                            self.state
+                                .add_message(format!("Exported the sequence in view to {path}"));
+                        }
+                        None => {
+                            self.exit_output.push(fasta);
+                            self.state.add_message(
+                                "The sequence in view will be printed on exit".to_string(),
+                            );
+                        }
+                    }
                 }
 
                 Message::Core(gv_core::message::Message::Message(message)) => {
@@ -354,6 +1393,9 @@ impl App {
                     if register == KeyRegisterType::ContigList {
                         self.registers.contig_list_cursor = self.alignment_view.focus.contig_index
                     }
+                    if register == KeyRegisterType::Snapshots {
+                        self.registers.snapshot_cursor = self.snapshots.len().saturating_sub(1)
+                    }
                     self.registers.current = register;
                     log::debug!(
                         "Switching key register: from={:?} to={:?}",
@@ -365,18 +1407,236 @@ impl App {
                     log::debug!("Clearing all key registers");
                     self.registers.clear();
                 }
+                Message::SetCursorMode(on) => {
+                    self.alignment_view.cursor = on.then_some(self.alignment_view.focus.position);
+                    self.state.add_message(if on {
+                        "Cursor mode on".to_string()
+                    } else {
+                        "Cursor mode off".to_string()
+                    });
+                }
+                Message::FrameRegion(region) => {
+                    let contig_length = self.state.contig_length(&region.focus)?;
+                    self.alignment_view
+                        .frame(&region, &self.layout.view_area(), contig_length);
+                    if self.alignment_view.cursor.is_some() {
+                        self.alignment_view.cursor = Some(self.alignment_view.focus.position);
+                    }
+                    self.load_data().await?
+                }
+                Message::PinReadDetails(details, split_read) => {
+                    log::debug!("Pinning read details: details={:?}", details);
+                    if let Some(split_read) = &split_read
+                        && !split_read.elsewhere.is_empty()
+                    {
+                        self.state.add_message(format!(
+                            "Also aligned to {}",
+                            split_read
+                                .elsewhere
+                                .iter()
+                                .map(|alignment| alignment.to_locus_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
+                    self.state.pinned_read = details;
+                    self.state.split_read = split_read;
+                }
+                Message::SetTheme(theme) => {
+                    log::debug!("Switching theme: theme={:?}", theme);
+                    self.settings.set_theme(theme);
+                }
+                Message::SetTrackHeight(track, height) => {
+                    self.layout.set_track_height(track, height);
+                    self.alignment_view.self_correct(
+                        &self.layout.view_area(),
+                        self.state.contig_length(&self.alignment_view.focus)?,
+                    );
+                }
+                Message::Snapshot(label) => {
+                    let label = match label {
+                        Some(label) => label,
+                        None => self
+                            .alignment_view
+                            .focus
+                            .to_locus_str(&self.state.contig_header)?,
+                    };
+                    log::info!(
+                        "Capturing snapshot: label={} area={:?} retained={}",
+                        label,
+                        self.layout.main_area,
+                        self.snapshots.len(),
+                    );
+                    self.capture_snapshot(label.clone())?;
+                    self.state
+                        .add_message(format!("Snapshot {} saved: {label}", self.snapshots.len()));
+                }
+                Message::ExportSvg(path) => {
+                    let path = shellexpand::tilde(&path).to_string();
+                    log::info!(
+                        "Exporting view: path={} area={:?}",
+                        path,
+                        self.layout.main_area
+                    );
+                    self.export_svg(&path)?;
+                    self.state.add_message(format!("Exported view to {path}"));
+                }
+                Message::Info => {
+                    let ucsc_host = match self
+                        .repository
+                        .track_service
+                        .as_ref()
+                        .and_then(|track_service| track_service.ucsc_host())
+                    {
+                        Some(ucsc_host) => ucsc_host.url(),
+                        None => "not in use".to_string(),
+                    };
+                    self.state.add_message(format!(
+                        "Reference: {} | UCSC host: {}",
+                        self.state.reference, ucsc_host
+                    ));
+                }
+                Message::ToggleModificationPileup => {
+                    log::debug!(
+                        "Toggling base modification pileup: alignment_count={}",
+                        self.state.alignments.len()
+                    );
+                    let shown = !self.state.show_modification_pileup;
+                    self.state.set_show_modification_pileup(shown)?;
+                    for index in 0..self.state.alignments.len() {
+                        self.layout
+                            .set_track_shown(AreaType::ModificationPileup(index), shown);
+                    }
+                    if shown {
+                        self.state.add_message(
+                            "Showing base modification probabilities with all modifications combined, e.g. 5mC and 5hmC. Each read counts its most likely modification at a position."
+                                .to_string(),
+                        );
+                    }
+                }
+                Message::ToggleTrack(area_type) => {
+                    log::debug!("Toggling track: track={:?}", area_type);
+                    self.layout.toggle_track(area_type);
+                    self.load_data().await?
+                }
+                Message::SetTrackShown(area_type, shown) => {
+                    log::debug!(
+                        "Setting track visibility: track={:?} shown={}",
+                        area_type,
+                        shown
+                    );
+                    self.layout.set_track_shown(area_type, shown);
+                    self.load_data().await?
+                }
+                Message::ToggleSplit => match self.layout.split {
+                    None => {
+                        self.inactive_pane =
+                            Some((self.alignment_view.clone(), self.state.empty_view_data()));
+                        self.layout.set_split(Some(Pane::Left));
+                        self.alignment_view.self_correct(
+                            &self.layout.view_area(),
+                            self.state.contig_length(&self.alignment_view.focus)?,
+                        );
+                        // The right pane opens at the same locus and becomes active.
+                        self.switch_pane().await?;
+                        self.state
+                            .add_message("Split the screen. gw switches panes".to_string());
+                    }
+                    Some(_) => {
+                        self.layout.set_split(None);
+                        self.inactive_pane = None;
+                        self.alignment_view.self_correct(
+                            &self.layout.view_area(),
+                            self.state.contig_length(&self.alignment_view.focus)?,
+                        );
+                        self.load_data().await?;
+                        self.state
+                            .add_message("Closed the split screen".to_string());
+                    }
+                },
+                Message::SetGcContentWindow(window) => {
+                    self.layout.gc_content_window = window;
+                    self.state.add_message(match window {
+                        Some(window) => format!("GC content window: {window} bp"),
+                        None => "GC content window: auto".to_string(),
+                    });
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Alignment tracks that commands act on: the track selected with gt, or all tracks.
+    fn selected_alignment_indexes(&self) -> Vec<usize> {
+        match self.registers.alignment_track {
+            Some(index) => vec![index],
+            None => (0..self.state.alignments.len()).collect(),
+        }
+    }
+
+    /// Make the other pane of a split screen active: swap in its view and data, and load data for its region.
+    async fn switch_pane(&mut self) -> Result<(), TGVError> {
+        let (Some(pane), Some((alignment_view, data))) =
+            (self.layout.split, self.inactive_pane.as_mut())
+        else {
+            return Err(TGVError::StateError(
+                "The screen is not split. Use :split".to_string(),
+            ));
+        };
+        std::mem::swap(&mut self.alignment_view, alignment_view);
+        self.state.swap_view_data(data);
+        self.layout.set_split(Some(pane.other()));
+        log::debug!(
+            "Switched pane: pane={:?} focus={:?}",
+            pane.other(),
+            self.alignment_view.focus
+        );
+
+        self.state
+            .reapply_alignment_options(&self.alignment_view.focus)?;
+        self.alignment_view.self_correct(
+            &self.layout.view_area(),
+            self.state.contig_length(&self.alignment_view.focus)?,
+        );
+        self.load_data().await
+    }
+
+    fn add_repeats_message(&mut self) {
+        self.state.add_message(match self.state.repeat_min_length {
+            Some(min_length) => {
+                format!("Underlining homopolymers and tandem repeats of at least {min_length} bp")
+            }
+            None => "Repeats are not underlined".to_string(),
+        });
+    }
+
+    /// Maximum number of genes listed by :gene.
+    const GENE_LIST_LIMIT: usize = 100;
+
+    /// Reads per column and row of `Downsample::Auto`. Extra reads are kept so that sorting and grouping can bring
+    /// rare reads into view.
+    const DOWNSAMPLE_AUTO_READS_PER_ROW: usize = 10;
+
+    /// Fit automatic downsampling to the tallest alignment track.
+    fn update_downsample_auto_depth(&mut self) -> Result<(), TGVError> {
+        if let Some(height) = self.layout.tallest_alignment_height() {
+            self.state.set_downsample_auto_depth(
+                usize::max(height as usize, 1) * Self::DOWNSAMPLE_AUTO_READS_PER_ROW,
+                &self.alignment_view.focus,
+            )?;
+        }
+        Ok(())
+    }
+
     async fn load_data(&mut self) -> Result<(), TGVError> {
+        self.update_downsample_auto_depth()?;
+
         // TODO: return whether data were loaded?
         // It's important to load sequence first!
         // Alignment IO requires calculating mismatches with the reference sequence.
         //
-        let region = self.alignment_view.region(&self.layout.main_area);
+        let region = self.alignment_view.region(&self.layout.view_area());
         log::debug!(
             "Evaluating data loads: display_region={:?} zoom={} focus={:?}",
             region,
@@ -384,7 +1644,58 @@ impl App {
             self.alignment_view.focus,
         );
 
-        if let Some(sequence_service) = self.repository.sequence_service.as_mut()
+        // Gaps and the minimap are read from the track service, which is out while the gene track loads.
+        if self.layout.tracks.contains(&AreaType::Gaps)
+            && !self.pending_loads.is_track_pending()
+            && self
+                .state
+                .gaps
+                .as_ref()
+                .map(|gap_track| gap_track.contig_index)
+                != Some(self.alignment_view.focus.contig_index)
+        {
+            self.state
+                .load_gaps(self.alignment_view.focus.contig_index, &mut self.repository)
+                .await?;
+        }
+        // Without a gap table, gaps are runs of Ns in the sequence, loaded as for the GC tracks.
+        let needs_gap_sequence = self.layout.tracks.contains(&AreaType::Gaps)
+            && self
+                .state
+                .gaps
+                .as_ref()
+                .is_some_and(|gap_track| gap_track.gaps.is_none());
+
+        if self.repository.sequence_service.is_some()
+            && !self.pending_loads.is_sequence_pending()
+            && (self.layout.tracks.contains(&AreaType::GcSkew)
+                || self.layout.tracks.contains(&AreaType::GcContent)
+                || self.layout.tracks.contains(&AreaType::CpgIslands)
+                || needs_gap_sequence)
+        {
+            let contig_length = self.state.contig_length(&self.alignment_view.focus)?;
+            match self
+                .alignment_view
+                .gc_skew_cache_region(region.clone(), contig_length)
+            {
+                Some(cache_region) if !self.state.sequence.has_complete_data(&cache_region) => {
+                    log::trace!(
+                        "GC sequence cache miss; requesting data load: display_region={:?} cache_region={:?}",
+                        region,
+                        cache_region,
+                    );
+                    self.load_sequence_in_background(cache_region);
+                }
+                Some(_) => {}
+                None => log::trace!(
+                    "Skipping GC sequence load because the region is too long: display_region={:?}",
+                    region,
+                ),
+            }
+        }
+
+        if self.repository.sequence_service.is_some()
+            && !self.pending_loads.is_sequence_pending()
             && self.alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_SEQUENCES
             && !self.state.sequence.has_complete_data(&region)
         {
@@ -395,12 +1706,60 @@ impl App {
                 cache_region,
                 self.alignment_view.zoom,
             );
-            self.state
-                .load_sequence_data(&cache_region, sequence_service)
-                .await?;
+            self.load_sequence_in_background(cache_region);
         }
 
-        if self.alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS {
+        if self.layout.tracks.contains(&AreaType::CoverageOverview)
+            && let Some(alignment_repository) = self.repository.alignment_repositories.first()
+            && self
+                .state
+                .coverage_overview
+                .as_ref()
+                .map(|overview| overview.contig_index)
+                != Some(self.alignment_view.focus.contig_index)
+        {
+            self.state.load_coverage_overview(
+                self.alignment_view.focus.contig_index,
+                alignment_repository,
+            )?;
+        }
+
+        if !self.pending_loads.is_track_pending()
+            && let Some((_, area)) = self
+                .layout
+                .areas
+                .iter()
+                .find(|(area_type, _)| *area_type == AreaType::Minimap)
+        {
+            let (start_x, end_x) = contig_overview_x_range(area, &self.state);
+            let bins = end_x.saturating_sub(start_x) as u64;
+            let contig_index = self.alignment_view.focus.contig_index;
+            // Contigs shorter than the strip have one bin per base.
+            if let Some(contig_length) = self.state.contig_length(&self.alignment_view.focus)?
+                && bins > 0
+                && self
+                    .state
+                    .minimap
+                    .as_ref()
+                    .map(|minimap| (minimap.contig_index, minimap.bins() as u64))
+                    != Some((contig_index, u64::min(bins, contig_length).max(1)))
+            {
+                log::debug!(
+                    "Loading minimap: contig_index={} bins={}",
+                    contig_index,
+                    bins
+                );
+                self.state.minimap = Some(
+                    self.state
+                        .contig_overview(&mut self.repository, contig_index, bins as usize)
+                        .await?,
+                );
+            }
+        }
+
+        if self.pending_loads.is_sequence_pending() {
+            log::trace!("Deferring alignment data loads until the sequence is loaded");
+        } else if self.alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS {
             for (index, alignment_repository) in self
                 .repository
                 .alignment_repositories
@@ -435,7 +1794,8 @@ impl App {
             );
         }
 
-        if let Some(track_service) = self.repository.track_service.as_mut()
+        if self.repository.track_service.is_some()
+            && !self.pending_loads.is_track_pending()
             && !self.state.track.has_complete_data(&region)
         {
             // viewing_window.zoom <= Self::MAX_ZOOM_TO_DISPLAY_FEATURES is always true
@@ -445,9 +1805,14 @@ impl App {
                 region,
                 cache_region,
             );
-            self.state
-                .load_track_data(&cache_region, track_service)
-                .await?;
+            if let Some(track_service) = self.repository.track_service.take() {
+                self.pending_loads.spawn_track(
+                    track_service,
+                    self.state.reference.clone(),
+                    cache_region,
+                    self.state.contig_header.clone(),
+                );
+            }
         }
 
         for (index, variant_repository) in
@@ -484,6 +1849,31 @@ impl App {
             }
         }
 
+        for (index, bigwig_repository) in self.repository.bigwig_repositories.iter_mut().enumerate()
+        {
+            if self.state.signals[index].has_complete_data(&region) {
+                continue;
+            }
+            match self.alignment_view.signal_cache_region(region.clone()) {
+                Some(cache_region) => {
+                    log::trace!(
+                        "bigWig cache miss; requesting data load: track={} display_region={:?} cache_region={:?}",
+                        index,
+                        region,
+                        cache_region,
+                    );
+                    self.state
+                        .load_signal_data(index, &cache_region, bigwig_repository)
+                        .await?;
+                }
+                None => log::trace!(
+                    "Skipping bigWig load because the region is too long: track={} display_region={:?}",
+                    index,
+                    region,
+                ),
+            }
+        }
+
         // Cytobands
         // TODO
         //
@@ -494,19 +1884,77 @@ impl App {
         Ok(())
     }
 
+    /// Load the sequence of region on a background task, with the sequence service taken out of the repository.
+    fn load_sequence_in_background(&mut self, region: Region) {
+        if let Some(sequence_service) = self.repository.sequence_service.take() {
+            self.pending_loads.spawn_sequence(
+                sequence_service,
+                region,
+                self.state.contig_header.clone(),
+            );
+        }
+    }
+
+    /// Fit the layout to the buffer and render. On a size change, the buffer is cleared and the alignment view is
+    /// corrected for the new area before rendering, so no cells or coordinate scales from the previous size remain.
+    /// Returns whether the size changed.
+    pub fn render_frame(&mut self, buf: &mut Buffer) -> Result<bool, TGVError> {
+        let resized = self.layout.set_area(buf.area);
+        if resized {
+            buf.reset();
+            self.alignment_view.self_correct(
+                &self.layout.view_area(),
+                self.state.contig_length(&self.alignment_view.focus)?,
+            );
+            if let (Some(inactive_layout), Some((alignment_view, _))) =
+                (self.layout.inactive_layout(), self.inactive_pane.as_mut())
+            {
+                alignment_view.self_correct(
+                    &inactive_layout.main_area,
+                    self.state.contig_length(&alignment_view.focus)?,
+                );
+            }
+            self.update_downsample_auto_depth()?;
+        }
+        self.render(buf)?;
+        Ok(resized)
+    }
+
     pub fn render(&mut self, buf: &mut Buffer) -> Result<(), TGVError> {
-        use crate::rendering::{render_contig_list, render_help, render_main};
+        use crate::rendering::{
+            render_bookmarks, render_contig_list, render_contig_overview, render_gene_list,
+            render_help, render_loading, render_main, render_popup, render_snapshots,
+        };
         match &self.scene {
-            Scene::Main => render_main(
+            Scene::Main => {
+                render_main(
+                    buf,
+                    &mut self.state,
+                    &self.registers,
+                    Panes {
+                        layout: &self.layout,
+                        alignment_view: &self.alignment_view,
+                        inactive_pane: self.inactive_pane.as_mut(),
+                    },
+                    &self.mouse_register,
+                    &self.settings.palette,
+                )?;
+                render_loading(
+                    buf,
+                    &self.layout,
+                    &self.alignment_view,
+                    &self.pending_loads.status(),
+                    &self.settings.palette,
+                );
+                Ok(())
+            }
+            Scene::Help => render_help(
+                &self.layout.main_area,
                 buf,
-                &mut self.state,
-                &self.registers,
-                &self.layout,
-                &self.alignment_view,
-                &self.mouse_register,
+                &self.registers.help_keyword,
+                &mut self.registers.help_scroll,
                 &self.settings.palette,
             ),
-            Scene::Help => render_help(&self.layout.main_area, buf),
             Scene::ContigList => render_contig_list(
                 &self.layout.main_area,
                 buf,
@@ -514,6 +1962,88 @@ impl App {
                 &self.registers,
                 &self.settings.palette,
             ),
+            Scene::GeneList => render_gene_list(
+                &self.layout.main_area,
+                buf,
+                &self.registers,
+                &self.settings.palette,
+            ),
+            Scene::ContigOverview => render_contig_overview(
+                &self.layout.main_area,
+                buf,
+                &self.state,
+                &self.registers,
+                &self.settings.palette,
+            ),
+            Scene::Bookmarks => render_bookmarks(
+                &self.layout.main_area,
+                buf,
+                &self.state,
+                &self.registers,
+                &self.settings.palette,
+            ),
+            Scene::Snapshots => render_snapshots(
+                &self.layout.main_area,
+                buf,
+                &self.snapshots,
+                &self.registers,
+            ),
+            Scene::Popup => {
+                render_main(
+                    buf,
+                    &mut self.state,
+                    &self.registers,
+                    Panes {
+                        layout: &self.layout,
+                        alignment_view: &self.alignment_view,
+                        inactive_pane: self.inactive_pane.as_mut(),
+                    },
+                    &self.mouse_register,
+                    &self.settings.palette,
+                )?;
+                render_loading(
+                    buf,
+                    &self.layout,
+                    &self.alignment_view,
+                    &self.pending_loads.status(),
+                    &self.settings.palette,
+                );
+                render_popup(&self.layout.main_area, buf, &self.popup)
+            }
         }
     }
 }
+
+/// Whether a message needs the track or sequence service, or the data they load, so that background loads must
+/// finish first. Navigation by coordinates and view changes don't wait: load_data starts loads for the new view once
+/// the services are back, and results for a region the view has left are dropped. Loci of `--loci` wait, since they
+/// may be gene names.
+fn needs_loaded_data(message: &Message) -> bool {
+    !matches!(
+        message,
+        Message::Core(
+            gv_core::message::Message::Move(
+                Movement::Left(_)
+                    | Movement::Right(_)
+                    | Movement::Position(_)
+                    | Movement::ContigNamePosition(_, _)
+                    | Movement::NextVariants(_)
+                    | Movement::PreviousVariants(_)
+                    | Movement::NextContig(_)
+                    | Movement::PreviousContig(_)
+                    | Movement::ContigIndex(_)
+                    | Movement::Bookmark(_)
+                    | Movement::NextRegions(_)
+                    | Movement::PreviousRegions(_)
+            ) | gv_core::message::Message::Zoom(_)
+                | gv_core::message::Message::Scroll(_)
+                | gv_core::message::Message::Quit
+        ) | Message::SwitchScene(_)
+            | Message::SwitchKeyRegister(_)
+            | Message::ClearAllKeyRegisters
+            | Message::SetCursorMode(_)
+            | Message::FrameRegion(_)
+            | Message::SetTheme(_)
+            | Message::SetTrackHeight(_, _)
+    )
+}
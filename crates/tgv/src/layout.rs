@@ -3,60 +3,155 @@ use gv_core::{
     alignment::Alignment,
     error::TGVError,
     intervals::{Focus, GenomeInterval, Region},
-    message::{Scroll, Zoom},
+    message::{CursorAnchor, Scroll, Zoom},
     repository::RepositoryFileIndex,
+    settings::FilePath,
 };
 use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AreaType {
     Cytoband,
+    CoverageOverview,
+    Minimap,
     Coordinate,
     Coverage(usize),
     Alignment(usize),
     AlignmentDivider { upper: usize, lower: usize },
     Sequence,
+    Translation,
     GeneTrack,
     Console,
     Error,
     Variant(usize),
     Bed(usize),
+    GcSkew,
+    GcContent,
+    CpgIslands,
+    Gaps,
+    Ideogram,
+    ModificationPileup(usize),
+    Signal(usize),
+}
+
+/// Tracks with a configurable height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizableTrack {
+    Coverage,
+    Alignment,
+    Sequence,
+    Gene,
+}
+
+impl std::str::FromStr for ResizableTrack {
+    type Err = TGVError;
+
+    fn from_str(s: &str) -> Result<Self, TGVError> {
+        match s {
+            "coverage" => Ok(Self::Coverage),
+            "alignment" => Ok(Self::Alignment),
+            "sequence" => Ok(Self::Sequence),
+            "gene" => Ok(Self::Gene),
+            _ => Err(TGVError::ParsingError(format!(
+                "Invalid track `{s}`. Expected \"coverage\", \"alignment\", \"sequence\", or \"gene\"."
+            ))),
+        }
+    }
+}
+
+/// Configured track heights in rows. None uses the default height.
+/// The alignment height is the requested height of each alignment track; remaining space still goes to alignments.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackHeights {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alignment: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gene: Option<u16>,
+}
+
+impl TrackHeights {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn set(&mut self, track: ResizableTrack, height: u16) {
+        let height = Some(height);
+        match track {
+            ResizableTrack::Coverage => self.coverage = height,
+            ResizableTrack::Alignment => self.alignment = height,
+            ResizableTrack::Sequence => self.sequence = height,
+            ResizableTrack::Gene => self.gene = height,
+        }
+    }
+
+    /// Configured height of a fixed-height area.
+    fn get(&self, area_type: &AreaType) -> Option<u16> {
+        match area_type {
+            AreaType::Coverage(_) => self.coverage,
+            AreaType::Sequence => self.sequence,
+            AreaType::GeneTrack => self.gene,
+            _ => None,
+        }
+    }
 }
 
 impl AreaType {
     fn desired_height(&self) -> Option<u16> {
         match self {
             AreaType::Cytoband => Some(2),
+            AreaType::CoverageOverview => Some(1),
+            AreaType::Minimap => Some(1),
             AreaType::Coordinate => Some(2),
             AreaType::Coverage(_) => Some(MainLayout::COVERAGE_HEIGHT),
             AreaType::Alignment(_) => None,
             AreaType::AlignmentDivider { .. } => Some(1),
             AreaType::Sequence => Some(1),
+            AreaType::Translation => Some(1),
             AreaType::GeneTrack => Some(2),
             AreaType::Console => Some(2),
             AreaType::Error => Some(2),
             AreaType::Variant(_) => Some(1),
             AreaType::Bed(_) => Some(1),
+            AreaType::GcSkew => Some(2),
+            AreaType::GcContent => Some(2),
+            AreaType::CpgIslands => Some(1),
+            AreaType::Gaps => Some(1),
+            AreaType::Ideogram => Some(4),
+            AreaType::ModificationPileup(_) => Some(3),
+            AreaType::Signal(_) => Some(MainLayout::SIGNAL_HEIGHT),
         }
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct AlignmentView {
     pub focus: Focus,
     pub zoom: u64,
     pub y: Vec<usize>,
+
+    /// Cursor position in cursor mode. None if cursor mode is off and the cursor is the focus.
+    /// 1-based, on the focus contig.
+    pub cursor: Option<u64>,
 }
 
 /// States for the alignment view
 impl AlignmentView {
     pub const MAX_ZOOM_TO_DISPLAY_ALIGNMENTS: u64 = 32;
     pub const MAX_ZOOM_TO_DISPLAY_SEQUENCES: u64 = 2;
+    /// Amino acids are drawn only if each base of a codon has its own column.
+    pub const MAX_ZOOM_TO_DISPLAY_TRANSLATION: u64 = 1;
 
     pub fn new(focus: Focus, alignment_count: usize) -> Self {
         AlignmentView {
             focus,
             zoom: 1,
             y: vec![0; alignment_count],
+            cursor: None,
         }
     }
     const ALIGNMENT_CACHE_RATIO: u64 = 3;
@@ -77,6 +172,43 @@ impl AlignmentView {
         }
     }
 
+    /// Longest sequence loaded for the GC skew and GC content tracks.
+    const MAX_GC_SKEW_SEQUENCE_LENGTH: u64 = 10_000_000;
+
+    /// Sequence region for the GC skew and GC content tracks: the whole contig if it is short enough so that the
+    /// cumulative skew starts at the contig origin, otherwise the sequence cache region. None if the region is too long
+    /// to load.
+    pub fn gc_skew_cache_region(
+        &self,
+        region: Region,
+        contig_length: Option<u64>,
+    ) -> Option<Region> {
+        if let Some(contig_length) = contig_length
+            && contig_length <= Self::MAX_GC_SKEW_SEQUENCE_LENGTH
+        {
+            // Region start is clamped to 1, so this covers exactly [1, contig_length].
+            return Some(Region {
+                focus: Focus {
+                    contig_index: region.contig_index(),
+                    position: contig_length.div_ceil(2),
+                },
+                half_width: contig_length / 2,
+            });
+        }
+
+        let cache_region = self.sequence_cache_region(region);
+        (cache_region.length() <= Self::MAX_GC_SKEW_SEQUENCE_LENGTH).then_some(cache_region)
+    }
+
+    /// Longest region loaded for bigWig signal tracks.
+    const MAX_SIGNAL_REGION_LENGTH: u64 = 10_000_000;
+
+    /// bigWig region for signal tracks. None if the region is too long to load.
+    pub fn signal_cache_region(&self, region: Region) -> Option<Region> {
+        let cache_region = self.alignment_cache_region(region);
+        (cache_region.length() <= Self::MAX_SIGNAL_REGION_LENGTH).then_some(cache_region)
+    }
+
     const TRACK_CACHE_RATIO: u64 = 10;
 
     pub fn track_cache_region(&self, region: Region) -> Region {
@@ -155,12 +287,55 @@ impl AlignmentView {
 
                 self.zoom * r // will be bounded and self-corrected later
             }
+            Zoom::Preset(preset) => {
+                self.focus = self.cursor_focus();
+                preset.zoom(area.width)
+            }
         };
 
         self.self_correct(area, contig_length);
         Ok(())
     }
 
+    /// Center the view on a region and zoom so that the region fits in the area.
+    pub fn frame(&mut self, region: &Region, area: &Rect, contig_length: Option<u64>) {
+        self.focus = region.focus.clone();
+        self.zoom = u64::max(1, region.length().div_ceil(area.width as u64));
+        self.self_correct(area, contig_length);
+    }
+
+    /// Position under the cursor: the cursor in cursor mode, otherwise the focus.
+    pub fn cursor_focus(&self) -> Focus {
+        match self.cursor {
+            Some(position) => self.focus.clone().move_to(position),
+            None => self.focus.clone(),
+        }
+    }
+
+    /// Move the cursor to position. The view pans only if the cursor leaves the viewing window.
+    pub fn move_cursor(&mut self, position: u64, area: &Rect) {
+        let position = position.max(1);
+        let (left, right) = (self.left(area), self.right(area));
+        if position < left {
+            self.focus = self.focus.clone().move_left(left - position);
+        } else if position > right {
+            self.focus = self.focus.clone().move_right(position - right);
+        }
+        self.cursor = Some(position);
+    }
+
+    /// Pan the view so that the cursor is at the anchor, clamped at the contig ends. The cursor position is kept.
+    pub fn center_cursor(&mut self, anchor: CursorAnchor, area: &Rect, contig_length: Option<u64>) {
+        let cursor = self.cursor_focus().position;
+        let half_width = self.region(area).half_width;
+        self.focus.position = match anchor {
+            CursorAnchor::Center => cursor,
+            CursorAnchor::Start => cursor + half_width,
+            CursorAnchor::End => cursor.saturating_sub(half_width),
+        };
+        self.self_correct(area, contig_length);
+    }
+
     /// Set the top track # of the viewing window.
     /// 0-based.
     pub fn set_y(&mut self, index: usize, y: usize, depth: usize) {
@@ -204,6 +379,15 @@ impl AlignmentView {
             .focus
             .position
             .max(1 + (area.width as u64 * self.zoom) / 2);
+
+        // 3. Cursor: within the viewing window and the contig
+        if let Some(cursor) = self.cursor {
+            let mut right = self.right(area);
+            if let Some(contig_length) = contig_length {
+                right = u64::min(right, contig_length);
+            }
+            self.cursor = Some(cursor.clamp(self.left(area), right.max(self.left(area))));
+        }
     }
 
     /// Height of the viewing window.
@@ -277,6 +461,23 @@ impl AlignmentView {
     }
 }
 
+/// Side of a split screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pane {
+    #[default]
+    Left,
+    Right,
+}
+
+impl Pane {
+    pub fn other(self) -> Self {
+        match self {
+            Pane::Left => Pane::Right,
+            Pane::Right => Pane::Left,
+        }
+    }
+}
+
 /// Main page layout
 pub struct MainLayout {
     pub tracks: Vec<AreaType>,
@@ -284,19 +485,43 @@ pub struct MainLayout {
     pub main_area: Rect,
 
     pub areas: Vec<(AreaType, Rect)>,
+
+    pub track_heights: TrackHeights,
+
+    /// GC content window in bases. None derives the window from the zoom level.
+    pub gc_content_window: Option<u64>,
+
+    /// Active pane of a split screen. None shows one view across the full width.
+    pub split: Option<Pane>,
+
+    /// Areas of the inactive pane of a split screen. Empty without a split.
+    pub inactive_areas: Vec<(AreaType, Rect)>,
+
+    /// File names of the alignment tracks, by alignment index.
+    pub alignment_labels: Vec<String>,
 }
 
 impl MainLayout {
     const ALIGNMENT_MIN_HEIGHT: u16 = 1;
     const COVERAGE_HEIGHT: u16 = 6;
+    const SIGNAL_HEIGHT: u16 = 3;
 
     pub fn new(settings: &Settings, repository_file_indexes: &[RepositoryFileIndex]) -> Self {
         let mut tracks = vec![];
         if settings.core.reference.needs_track() {
             tracks.push(AreaType::Cytoband);
+            if repository_file_indexes
+                .iter()
+                .any(|index| matches!(index, RepositoryFileIndex::Alignment(_)))
+            {
+                tracks.push(AreaType::CoverageOverview);
+            }
         }
 
-        if settings.core.reference.needs_sequence() || settings.core.reference.needs_track() {
+        let has_gene_track =
+            settings.core.reference.needs_track() || settings.core.has_gene_annotation_file();
+
+        if settings.core.reference.needs_sequence() || has_gene_track {
             tracks.push(AreaType::Coordinate);
         }
 
@@ -316,23 +541,42 @@ impl MainLayout {
                 }
                 RepositoryFileIndex::Variant(index) => tracks.push(AreaType::Variant(*index)),
                 RepositoryFileIndex::Bed(index) => tracks.push(AreaType::Bed(*index)),
+                RepositoryFileIndex::BigWig(index) => tracks.push(AreaType::Signal(*index)),
             }
         }
 
         if settings.core.reference.needs_sequence() {
             tracks.push(AreaType::Sequence);
+            if has_gene_track {
+                tracks.push(AreaType::Translation);
+            }
         }
-        if settings.core.reference.needs_track() {
+        if has_gene_track {
             tracks.push(AreaType::GeneTrack);
         }
 
         tracks.push(AreaType::Console);
         tracks.push(AreaType::Error);
 
+        let alignment_labels = settings
+            .core
+            .file_paths
+            .iter()
+            .filter_map(|file_path| match file_path {
+                FilePath::AlignmentPath(alignment_path) => Some(alignment_path.label()),
+                _ => None,
+            })
+            .collect();
+
         MainLayout {
             tracks,
             main_area: Rect::default(),
             areas: Vec::new(),
+            track_heights: settings.track_heights.clone(),
+            gc_content_window: None,
+            split: None,
+            inactive_areas: Vec::new(),
+            alignment_labels,
         }
     }
 
@@ -356,6 +600,123 @@ impl MainLayout {
         }
     }
 
+    /// Split the screen into two panes side by side, with pane active, or show one view across the full width.
+    pub fn set_split(&mut self, split: Option<Pane>) {
+        let alignment_heights = self.current_alignment_heights();
+        self.split = split;
+        self.recalculate_areas(&alignment_heights);
+    }
+
+    /// Area of the active view: the active pane of a split screen, or the full main area.
+    pub fn view_area(&self) -> Rect {
+        self.split
+            .map_or(self.main_area, |pane| self.pane_area(pane))
+    }
+
+    /// Layout of the inactive pane of a split screen, to render it like the main layout.
+    pub fn inactive_layout(&self) -> Option<MainLayout> {
+        let pane = self.split?.other();
+        Some(MainLayout {
+            tracks: self.tracks.clone(),
+            main_area: self.pane_area(pane),
+            areas: self.inactive_areas.clone(),
+            track_heights: self.track_heights.clone(),
+            gc_content_window: self.gc_content_window,
+            split: None,
+            inactive_areas: Vec::new(),
+            alignment_labels: self.alignment_labels.clone(),
+        })
+    }
+
+    /// Label of an alignment track, drawn over its coverage track. Labels are only shown with more than one
+    /// alignment track.
+    pub fn alignment_label(&self, index: usize) -> Option<&str> {
+        if self.alignment_labels.len() < 2 {
+            return None;
+        }
+        self.alignment_labels.get(index).map(String::as_str)
+    }
+
+    /// Columns of a pane. The right pane takes the odd column.
+    fn pane_area(&self, pane: Pane) -> Rect {
+        let left_width = self.main_area.width / 2;
+        match pane {
+            Pane::Left => Rect {
+                width: left_width,
+                ..self.main_area
+            },
+            Pane::Right => Rect {
+                x: self.main_area.x + left_width,
+                width: self.main_area.width - left_width,
+                ..self.main_area
+            },
+        }
+    }
+
+    /// Show or hide an optional track. Per-alignment tracks are placed right below the alignment's coverage, and the
+    /// minimap right above the coordinates. Other tracks are placed right above the sequence, gene, and console
+    /// tracks.
+    pub fn toggle_track(&mut self, area_type: AreaType) {
+        let alignment_heights = self.current_alignment_heights();
+        if let Some(index) = self.tracks.iter().position(|track| *track == area_type) {
+            self.tracks.remove(index);
+        } else {
+            let index = match area_type {
+                AreaType::ModificationPileup(alignment_index) => self
+                    .tracks
+                    .iter()
+                    .position(|track| *track == AreaType::Coverage(alignment_index))
+                    .map(|index| index + 1),
+                AreaType::CoverageOverview | AreaType::Ideogram => Some(
+                    self.tracks
+                        .iter()
+                        .position(|track| *track == AreaType::Cytoband)
+                        .map_or(0, |index| index + 1),
+                ),
+                AreaType::Minimap => Some(
+                    self.tracks
+                        .iter()
+                        .position(|track| *track == AreaType::Coordinate)
+                        .or_else(|| {
+                            self.tracks
+                                .iter()
+                                .rposition(|track| {
+                                    matches!(track, AreaType::Cytoband | AreaType::CoverageOverview)
+                                })
+                                .map(|index| index + 1)
+                        })
+                        .unwrap_or(0),
+                ),
+                AreaType::GcContent | AreaType::CpgIslands => self
+                    .tracks
+                    .iter()
+                    .position(|track| *track == AreaType::Sequence)
+                    .map(|index| index + 1)
+                    .or_else(|| {
+                        self.tracks.iter().position(|track| {
+                            matches!(track, AreaType::GeneTrack | AreaType::Console)
+                        })
+                    }),
+                _ => self.tracks.iter().position(|track| {
+                    matches!(
+                        track,
+                        AreaType::Sequence | AreaType::GeneTrack | AreaType::Console
+                    )
+                }),
+            }
+            .unwrap_or(self.tracks.len());
+            self.tracks.insert(index, area_type);
+        }
+        self.recalculate_areas(&alignment_heights);
+    }
+
+    /// Show or hide an optional track. Does nothing if the track is already in the requested state.
+    pub fn set_track_shown(&mut self, area_type: AreaType, shown: bool) {
+        if self.tracks.contains(&area_type) != shown {
+            self.toggle_track(area_type);
+        }
+    }
+
     pub fn resize_alignment_pair(&mut self, upper: usize, lower: usize, delta_rows: i32) {
         if delta_rows == 0 {
             return;
@@ -412,18 +773,37 @@ impl MainLayout {
         );
     }
 
+    /// Set the height of a track. Alignment heights apply to every alignment track.
+    pub fn set_track_height(&mut self, track: ResizableTrack, height: u16) {
+        self.track_heights.set(track, height);
+        let alignment_heights = match track {
+            ResizableTrack::Alignment => vec![height; self.alignment_count()],
+            _ => self.current_alignment_heights(),
+        };
+        self.recalculate_areas(&alignment_heights);
+        log::debug!(
+            "Track height set: track={:?} height={} fixed_heights={:?} alignment_heights={:?}",
+            track,
+            height,
+            self.fixed_heights(),
+            self.current_alignment_heights(),
+        );
+    }
+
     fn recalculate_areas(&mut self, alignment_heights: &[u16]) {
         let alignment_heights = self.resolved_alignment_heights(alignment_heights);
+        let fixed_heights = self.fixed_heights();
         let mut y = self.main_area.y;
         let mut remaining_height = self.main_area.height;
 
         self.areas = self
             .tracks
             .iter()
-            .map(|track| {
+            .zip(fixed_heights)
+            .map(|(track, fixed_height)| {
                 let desired_height = match track {
                     AreaType::Alignment(index) => alignment_heights[*index],
-                    _ => track.desired_height().unwrap_or_default(),
+                    _ => fixed_height.unwrap_or_default(),
                 };
                 let height = u16::min(desired_height, remaining_height);
                 let rect = Rect::new(self.main_area.x, y, self.main_area.width, height);
@@ -432,6 +812,27 @@ impl MainLayout {
                 (*track, rect)
             })
             .collect();
+
+        // Each pane gets its own column of every track. The console and the status bar span both panes.
+        self.inactive_areas.clear();
+        if let Some(pane) = self.split {
+            let (active, inactive) = (self.pane_area(pane), self.pane_area(pane.other()));
+            for (area_type, rect) in self.areas.iter_mut() {
+                if matches!(area_type, AreaType::Console | AreaType::Error) {
+                    continue;
+                }
+                self.inactive_areas.push((
+                    *area_type,
+                    Rect {
+                        x: inactive.x,
+                        width: inactive.width,
+                        ..*rect
+                    },
+                ));
+                rect.x = active.x;
+                rect.width = active.width;
+            }
+        }
     }
 
     fn resolved_alignment_heights(&self, alignment_heights: &[u16]) -> Vec<u16> {
@@ -483,7 +884,12 @@ impl MainLayout {
 
     fn current_alignment_heights(&self) -> Vec<u16> {
         let alignment_count = self.alignment_count();
-        let mut alignment_heights = vec![Self::ALIGNMENT_MIN_HEIGHT; alignment_count];
+        let mut alignment_heights = vec![
+            self.track_heights
+                .alignment
+                .unwrap_or(Self::ALIGNMENT_MIN_HEIGHT);
+            alignment_count
+        ];
 
         for (area_type, area) in &self.areas {
             if let AreaType::Alignment(index) = area_type {
@@ -503,10 +909,60 @@ impl MainLayout {
     }
 
     fn fixed_desired_height(&self) -> u16 {
+        self.fixed_heights()
+            .into_iter()
+            .flatten()
+            .fold(0, u16::saturating_add)
+    }
+
+    /// Heights of fixed-height tracks, in track order. None for alignment tracks.
+    /// Configured heights are at least 1 row. Heights above the default are clamped so that the total fits and
+    /// alignment tracks keep their minimum height.
+    fn fixed_heights(&self) -> Vec<Option<u16>> {
+        // Heights with configured heights capped at the defaults; the rest is granted from the spare rows in order.
+        let base_heights = self
+            .tracks
+            .iter()
+            .map(|track| {
+                track.desired_height().map(|default_height| {
+                    self.track_heights
+                        .get(track)
+                        .map_or(default_height, |height| height.clamp(1, default_height))
+                })
+            })
+            .collect::<Vec<_>>();
+        let base_height = base_heights
+            .iter()
+            .flatten()
+            .copied()
+            .fold(0, u16::saturating_add);
+        let mut spare_height = self
+            .main_area
+            .height
+            .saturating_sub(base_height.saturating_add(
+                (self.alignment_count() as u16).saturating_mul(Self::ALIGNMENT_MIN_HEIGHT),
+            ));
+
         self.tracks
             .iter()
-            .filter_map(AreaType::desired_height)
-            .fold(0, u16::saturating_add)
+            .zip(base_heights)
+            .map(|(track, base_height)| {
+                let base_height = base_height?;
+                let height = self.track_heights.get(track).unwrap_or_default();
+                let extra_height = u16::min(height.saturating_sub(base_height), spare_height);
+                spare_height -= extra_height;
+                Some(base_height + extra_height)
+            })
+            .collect()
+    }
+
+    /// Rows of the tallest alignment track. None if there are no alignment tracks.
+    pub fn tallest_alignment_height(&self) -> Option<u16> {
+        self.areas
+            .iter()
+            .filter(|(area_type, _)| matches!(area_type, AreaType::Alignment(_)))
+            .map(|(_, area)| area.height)
+            .max()
     }
 
     fn alignment_count(&self) -> usize {
@@ -626,6 +1082,7 @@ pub fn linear_scale(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use gv_core::message::ZoomPreset;
     use gv_core::reference::Reference;
     use rstest::rstest;
 
@@ -718,6 +1175,234 @@ mod tests {
         assert_eq!(layout.tracks, expected_tracks);
     }
 
+    #[rstest]
+    #[case(1, vec![None])]
+    #[case(2, vec![Some("sample0.bam"), Some("sample1.bam")])]
+    #[case(3, vec![Some("sample0.bam"), Some("sample1.bam"), Some("sample2.bam")])]
+    fn each_alignment_block_is_labeled_with_its_file(
+        #[case] alignment_count: usize,
+        #[case] expected_labels: Vec<Option<&str>>,
+    ) {
+        let mut settings = settings_without_reference();
+        settings.core.file_paths = (0..alignment_count)
+            .map(|index| {
+                FilePath::AlignmentPath(gv_core::settings::AlignmentPath::Bam {
+                    path: format!("data/sample{index}.bam"),
+                    index: format!("data/sample{index}.bam.bai"),
+                    source: gv_core::settings::BamSource::Local,
+                })
+            })
+            .collect();
+        let repository_file_indexes = (0..alignment_count)
+            .map(RepositoryFileIndex::Alignment)
+            .collect::<Vec<_>>();
+        let mut layout = MainLayout::new(&settings, &repository_file_indexes);
+        layout.set_area(Rect::new(0, 0, 80, 40));
+
+        let labels = layout
+            .areas
+            .iter()
+            .filter_map(|(area_type, _)| match area_type {
+                AreaType::Coverage(index) => Some(layout.alignment_label(*index)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(labels, expected_labels);
+        assert_eq!(
+            layout
+                .areas
+                .iter()
+                .filter(|(area_type, _)| matches!(area_type, AreaType::Alignment(_)))
+                .count(),
+            alignment_count
+        );
+    }
+
+    #[rstest]
+    #[case(Some(9), Some((1, 9)))]
+    #[case(Some(10), Some((1, 10)))]
+    #[case(Some(20_000_000), Some((999_400, 1_000_600)))]
+    #[case(None, Some((999_400, 1_000_600)))]
+    fn gc_skew_cache_region_covers_short_contigs(
+        #[case] contig_length: Option<u64>,
+        #[case] expected: Option<(u64, u64)>,
+    ) {
+        let alignment_view = AlignmentView::new(Focus::default(), 0);
+        let region = Region {
+            focus: Focus {
+                contig_index: 0,
+                position: 1_000_000,
+            },
+            half_width: 100,
+        };
+
+        assert_eq!(
+            alignment_view
+                .gc_skew_cache_region(region, contig_length)
+                .map(|region| (region.start(), region.end())),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case(80, Pane::Left)]
+    #[case(81, Pane::Right)]
+    fn split_panes_do_not_overlap_and_fill_the_width(#[case] width: u16, #[case] pane: Pane) {
+        let settings = settings_without_reference();
+        let mut layout = MainLayout::new(
+            &settings,
+            &[
+                RepositoryFileIndex::Alignment(0),
+                RepositoryFileIndex::Alignment(1),
+            ],
+        );
+        layout.set_area(Rect::new(0, 0, width, 30));
+        let full_areas = layout.areas.clone();
+
+        layout.set_split(Some(pane));
+        assert_eq!(layout.areas.len(), full_areas.len());
+        assert_eq!(layout.inactive_areas.len(), full_areas.len() - 2);
+        for ((area_type, active), (full_area_type, full)) in layout.areas.iter().zip(&full_areas) {
+            assert_eq!(area_type, full_area_type);
+            assert_eq!((active.y, active.height), (full.y, full.height));
+            match layout
+                .inactive_areas
+                .iter()
+                .find(|(inactive_type, _)| inactive_type == area_type)
+            {
+                Some((_, inactive)) => {
+                    assert!(!active.intersects(*inactive));
+                    assert_eq!(active.union(*inactive), *full);
+                    assert_eq!(active.width + inactive.width, width);
+                }
+                None => {
+                    assert!(matches!(area_type, AreaType::Console | AreaType::Error));
+                    assert_eq!(active, full);
+                }
+            }
+        }
+        assert_eq!(
+            layout.view_area().x,
+            match pane {
+                Pane::Left => 0,
+                Pane::Right => width / 2,
+            }
+        );
+        let inactive_layout = layout.inactive_layout().unwrap();
+        assert_eq!(inactive_layout.areas, layout.inactive_areas);
+        assert_eq!(
+            inactive_layout.main_area.width + layout.view_area().width,
+            width
+        );
+
+        layout.set_split(None);
+        assert_eq!(layout.areas, full_areas);
+        assert!(layout.inactive_areas.is_empty());
+        assert_eq!(layout.view_area(), layout.main_area);
+    }
+
+    #[test]
+    fn setting_track_shown_is_idempotent() {
+        let mut layout = alignment_layout(1, 24);
+
+        layout.set_track_shown(AreaType::GcContent, true);
+        layout.set_track_shown(AreaType::GcContent, true);
+        // Without a sequence track, GC content goes before the console.
+        assert_eq!(
+            layout.tracks,
+            vec![
+                AreaType::Coverage(0),
+                AreaType::Alignment(0),
+                AreaType::GcContent,
+                AreaType::Console,
+                AreaType::Error,
+            ]
+        );
+
+        layout.set_track_shown(AreaType::GcContent, false);
+        layout.set_track_shown(AreaType::GcContent, false);
+        assert!(!layout.tracks.contains(&AreaType::GcContent));
+    }
+
+    #[test]
+    fn toggling_track_inserts_before_console_and_removes_again() {
+        let mut layout = alignment_layout(1, 24);
+
+        layout.toggle_track(AreaType::GcSkew);
+        assert_eq!(
+            layout.tracks,
+            vec![
+                AreaType::Coverage(0),
+                AreaType::Alignment(0),
+                AreaType::GcSkew,
+                AreaType::Console,
+                AreaType::Error,
+            ]
+        );
+        assert_eq!(area_height(&layout, AreaType::GcSkew), 2);
+
+        layout.toggle_track(AreaType::GcSkew);
+        assert!(!layout.tracks.contains(&AreaType::GcSkew));
+        assert!(
+            !layout
+                .areas
+                .iter()
+                .any(|(area_type, _)| *area_type == AreaType::GcSkew)
+        );
+    }
+
+    #[test]
+    fn toggling_modification_pileup_inserts_below_coverage() {
+        let mut layout = alignment_layout(2, 30);
+
+        layout.toggle_track(AreaType::ModificationPileup(1));
+        assert_eq!(
+            layout.tracks,
+            vec![
+                AreaType::Coverage(0),
+                AreaType::Alignment(0),
+                AreaType::AlignmentDivider { upper: 0, lower: 1 },
+                AreaType::Coverage(1),
+                AreaType::ModificationPileup(1),
+                AreaType::Alignment(1),
+                AreaType::Console,
+                AreaType::Error,
+            ]
+        );
+        assert_eq!(area_height(&layout, AreaType::ModificationPileup(1)), 3);
+    }
+
+    #[test]
+    fn toggling_minimap_inserts_above_coordinates() {
+        let mut layout = alignment_layout(1, 24);
+        layout.tracks.insert(0, AreaType::Cytoband);
+        layout.tracks.insert(1, AreaType::Coordinate);
+
+        layout.toggle_track(AreaType::Minimap);
+        assert_eq!(
+            &layout.tracks[..3],
+            &[AreaType::Cytoband, AreaType::Minimap, AreaType::Coordinate]
+        );
+        assert_eq!(area_height(&layout, AreaType::Minimap), 1);
+
+        layout.toggle_track(AreaType::Minimap);
+        layout.tracks.remove(1);
+        layout.toggle_track(AreaType::Minimap);
+        assert_eq!(
+            &layout.tracks[..2],
+            &[AreaType::Cytoband, AreaType::Minimap]
+        );
+    }
+
+    #[test]
+    fn toggling_coverage_overview_inserts_at_top_without_cytoband() {
+        let mut layout = alignment_layout(1, 24);
+
+        layout.toggle_track(AreaType::CoverageOverview);
+        assert_eq!(layout.tracks[0], AreaType::CoverageOverview);
+        assert_eq!(area_height(&layout, AreaType::CoverageOverview), 1);
+    }
+
     #[test]
     fn alignment_view_scrolls_only_the_requested_alignment() {
         let alignments = vec![alignment_with_depth(10), alignment_with_depth(10)];
@@ -736,6 +1421,106 @@ mod tests {
         assert_eq!(alignment_view.top(1), 2);
     }
 
+    #[test]
+    fn alignment_view_cursor_pans_only_past_the_edges() {
+        let area = Rect::new(0, 0, 80, 24);
+        let mut alignment_view = AlignmentView::new(
+            Focus {
+                contig_index: 0,
+                position: 1000,
+            },
+            0,
+        );
+        let left = alignment_view.left(&area);
+
+        alignment_view.move_cursor(left, &area);
+        assert_eq!(alignment_view.cursor, Some(left));
+        assert_eq!(alignment_view.focus.position, 1000);
+
+        alignment_view.move_cursor(left - 5, &area);
+        assert_eq!(alignment_view.cursor, Some(left - 5));
+        assert_eq!(alignment_view.focus.position, 995);
+
+        // The cursor is clamped to the contig end.
+        alignment_view.cursor = Some(1100);
+        alignment_view.self_correct(&area, Some(1000));
+        assert_eq!(alignment_view.cursor, Some(1000));
+    }
+
+    #[rstest]
+    // 80 columns at 1 bp per column: the view spans focus - 40 to focus + 40.
+    #[case(CursorAnchor::Center, 1, 5000, 5000, 4960, 5040)]
+    #[case(CursorAnchor::Start, 1, 5000, 5040, 5000, 5080)]
+    #[case(CursorAnchor::End, 1, 5000, 4960, 4920, 5000)]
+    #[case(CursorAnchor::Center, 10, 5000, 5000, 4600, 5400)]
+    // Clamped at the contig start
+    #[case(CursorAnchor::Center, 1, 10, 41, 1, 81)]
+    #[case(CursorAnchor::End, 1, 60, 41, 1, 81)]
+    // Clamped at the contig end
+    #[case(CursorAnchor::Center, 1, 9990, 9960, 9920, 10000)]
+    #[case(CursorAnchor::Start, 1, 9950, 9960, 9920, 10000)]
+    fn center_cursor_pans_the_view_around_the_cursor(
+        #[case] anchor: CursorAnchor,
+        #[case] zoom: u64,
+        #[case] cursor: u64,
+        #[case] expected_focus: u64,
+        #[case] expected_left: u64,
+        #[case] expected_right: u64,
+    ) {
+        let area = Rect::new(0, 0, 80, 24);
+        let mut alignment_view = AlignmentView::new(
+            Focus {
+                contig_index: 0,
+                position: 1000,
+            },
+            0,
+        );
+        alignment_view.zoom = zoom;
+        alignment_view.move_cursor(cursor, &area);
+
+        alignment_view.center_cursor(anchor, &area, Some(10_000));
+        assert_eq!(alignment_view.focus.position, expected_focus);
+        assert_eq!(alignment_view.left(&area), expected_left);
+        assert_eq!(alignment_view.right(&area), expected_right);
+        assert_eq!(alignment_view.cursor, Some(cursor));
+    }
+
+    #[rstest]
+    // 80 columns: 1 bp, 13 bp (1 kb), and 63 bp (5 kb) per column.
+    #[case(ZoomPreset::Base, 16, 5000, 4960, 5040)]
+    #[case(ZoomPreset::Gene, 16, 5000, 4480, 5520)]
+    #[case(ZoomPreset::Kilobases, 1, 5000, 2480, 7520)]
+    #[case(ZoomPreset::Base, 1, 3000, 2960, 3040)]
+    // Clamped at the contig start and end
+    #[case(ZoomPreset::Kilobases, 2, 100, 1, 5041)]
+    #[case(ZoomPreset::Gene, 4, 9990, 8960, 10000)]
+    fn zoom_presets_frame_the_cursor(
+        #[case] preset: ZoomPreset,
+        #[case] zoom: u64,
+        #[case] cursor: u64,
+        #[case] expected_left: u64,
+        #[case] expected_right: u64,
+    ) {
+        let area = Rect::new(0, 0, 80, 24);
+        let mut alignment_view = AlignmentView::new(
+            Focus {
+                contig_index: 0,
+                position: 5000,
+            },
+            0,
+        );
+        alignment_view.zoom = zoom;
+        alignment_view.move_cursor(cursor, &area);
+
+        alignment_view
+            .zoom(Zoom::Preset(preset), &area, Some(10_000))
+            .unwrap();
+        assert_eq!(alignment_view.zoom, preset.zoom(area.width));
+        assert_eq!(alignment_view.left(&area), expected_left);
+        assert_eq!(alignment_view.right(&area), expected_right);
+        assert_eq!(alignment_view.cursor, Some(cursor));
+    }
+
     #[rstest]
     #[case(1, 1)]
     #[case(2, 1)]
@@ -800,6 +1585,30 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case(3, 3, 17)]
+    #[case(0, 1, 19)]
+    #[case(30, 19, 1)]
+    fn configured_coverage_height_is_clamped_to_fit(
+        #[case] height: u16,
+        #[case] expected_coverage_height: u16,
+        #[case] expected_alignment_height: u16,
+    ) {
+        let mut layout = alignment_layout(1, 24);
+        layout.set_track_height(ResizableTrack::Coverage, height);
+
+        assert_eq!(
+            area_height(&layout, AreaType::Coverage(0)),
+            expected_coverage_height
+        );
+        assert_eq!(
+            area_height(&layout, AreaType::Alignment(0)),
+            expected_alignment_height
+        );
+        assert_eq!(area_height(&layout, AreaType::Console), 2);
+        assert_eq!(area_height(&layout, AreaType::Error), 2);
+    }
+
     #[test]
     fn small_windows_allocate_layout_top_first() {
         let layout = alignment_layout(3, 16);
@@ -1,8 +1,11 @@
 pub mod app;
 pub mod layout;
+pub mod loading;
 pub mod message;
 pub mod mouse;
 pub mod register;
 pub mod rendering;
 pub mod session;
 pub mod settings;
+pub mod snapshot;
+pub mod svg;
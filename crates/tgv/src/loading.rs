@@ -0,0 +1,144 @@
+//! Background loading of the gene track and the reference sequence.
+//!
+//! Queries to remote services (e.g. UCSC) can take seconds. They run on background tasks so that the main loop
+//! keeps rendering the layers already loaded, with a spinner in the areas that wait for data.
+use gv_core::{
+    contig_header::ContigHeader,
+    error::TGVError,
+    feature::Gene,
+    intervals::Region,
+    reference::Reference,
+    sequence::{Sequence, SequenceRepositoryEnum},
+    track::Track,
+    tracks::{TrackService, TrackServiceEnum},
+};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Data loaded on a background task, sent back with the service that loaded it.
+pub enum LoadedData {
+    Track {
+        service: Box<TrackServiceEnum>,
+        region: Region,
+        result: Result<Track<Gene>, TGVError>,
+    },
+    Sequence {
+        service: Box<SequenceRepositoryEnum>,
+        region: Region,
+        result: Result<Sequence, TGVError>,
+    },
+}
+
+/// Layers waiting for a background load, and the spinner frame to draw in their areas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadingStatus {
+    pub track: bool,
+    pub sequence: bool,
+    pub spinner_frame: usize,
+}
+
+impl LoadingStatus {
+    pub fn is_loading(&self) -> bool {
+        self.track || self.sequence
+    }
+}
+
+/// Loads in flight. A task owns its service until it finishes; the service is put back into the repository when
+/// the result is applied.
+#[derive(Default)]
+pub struct PendingLoads {
+    track: Option<JoinHandle<LoadedData>>,
+    sequence: Option<JoinHandle<LoadedData>>,
+
+    /// When the oldest load in flight started. Drives the spinner.
+    started: Option<Instant>,
+}
+
+impl PendingLoads {
+    /// Time between spinner frames.
+    pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn is_empty(&self) -> bool {
+        self.track.is_none() && self.sequence.is_none()
+    }
+
+    pub fn is_track_pending(&self) -> bool {
+        self.track.is_some()
+    }
+
+    pub fn is_sequence_pending(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    pub fn status(&self) -> LoadingStatus {
+        LoadingStatus {
+            track: self.is_track_pending(),
+            sequence: self.is_sequence_pending(),
+            spinner_frame: self.started.map_or(0, |started| {
+                (started.elapsed().as_millis() / Self::SPINNER_INTERVAL.as_millis()) as usize
+            }),
+        }
+    }
+
+    /// Query the gene track of region on a background task.
+    pub fn spawn_track(
+        &mut self,
+        mut service: TrackServiceEnum,
+        reference: Reference,
+        region: Region,
+        contig_header: ContigHeader,
+    ) {
+        log::debug!("Loading reference track data in the background: region={region:?}");
+        self.started.get_or_insert_with(Instant::now);
+        self.track = Some(tokio::spawn(async move {
+            let result = service
+                .query_gene_track(&reference, &region, &contig_header)
+                .await;
+            LoadedData::Track {
+                service: Box::new(service),
+                region,
+                result,
+            }
+        }));
+    }
+
+    /// Query the sequence of region on a background task.
+    pub fn spawn_sequence(
+        &mut self,
+        mut service: SequenceRepositoryEnum,
+        region: Region,
+        contig_header: ContigHeader,
+    ) {
+        log::debug!("Loading sequence data in the background: region={region:?}");
+        self.started.get_or_insert_with(Instant::now);
+        self.sequence = Some(tokio::spawn(async move {
+            let result = service.query_sequence(&region, &contig_header).await;
+            LoadedData::Sequence {
+                service: Box::new(service),
+                region,
+                result,
+            }
+        }));
+    }
+
+    /// Take the results of the loads in flight. Without wait, only loads that have finished are taken. A task that
+    /// panicked is an error; its service is lost.
+    pub async fn take_finished(&mut self, wait: bool) -> Vec<Result<LoadedData, TGVError>> {
+        let mut loaded = Vec::new();
+        for handle in [&mut self.track, &mut self.sequence] {
+            if handle
+                .as_ref()
+                .is_some_and(|handle| wait || handle.is_finished())
+                && let Some(handle) = handle.take()
+            {
+                loaded.push(handle.await.map_err(|e| {
+                    TGVError::StateError(format!("Background data load failed: {e}"))
+                }));
+            }
+        }
+        if self.is_empty() {
+            self.started = None;
+        }
+        loaded
+    }
+}
@@ -6,13 +6,19 @@ use crossterm::{
 use gv_core::error::TGVError;
 use gv_core::logging::{init_file_logging_with_level, timestamped_log_file_name};
 use gv_core::reference::Reference;
-use gv_core::tracks::{UCSCDownloader, UcscDbTrackService};
+use gv_core::retry::RetryPolicy;
+use gv_core::settings::create_cache_dir;
+use gv_core::tracks::{AssemblyFilter, UCSCDownloader, UcscDbTrackService};
 use std::{io::stdout, path::PathBuf};
 use tgv::{
     app::App,
     session::SessionFile,
     settings::{Cli, Commands, Settings},
 };
+
+/// Normal mode key binding file.
+const DEFAULT_KEYS_PATH: &str = "~/.config/tgv/keys.toml";
+
 #[tokio::main]
 async fn main() -> Result<(), TGVError> {
     let cli = Cli::parse();
@@ -29,21 +35,33 @@ async fn main() -> Result<(), TGVError> {
         Some(Commands::Download {
             reference,
             cache_dir,
+            no_retry,
         }) => {
             log::info!("Starting download for reference {reference}");
-            let cache_dir = shellexpand::tilde(&cache_dir).to_string();
-            let downloader = UCSCDownloader::new(reference.parse::<Reference>()?, &cache_dir)?;
+            let cache_dir = gv_core::settings::cache_dir(cache_dir.as_deref());
+            create_cache_dir(&cache_dir)?;
+            let retry = if *no_retry {
+                RetryPolicy::NONE
+            } else {
+                RetryPolicy::default()
+            };
+            let downloader =
+                UCSCDownloader::new(reference.parse::<Reference>()?, &cache_dir, retry)?;
             downloader.download().await?;
             return Ok(());
         }
-        Some(Commands::List { all }) => {
-            log::info!("Listing reference genomes");
+        Some(Commands::List { all, filter, clade }) => {
+            log::info!("Listing reference genomes: filter={filter:?} clade={clade:?}");
+            let filter = AssemblyFilter {
+                text: filter.clone(),
+                clade: clade.clone(),
+            };
             if *all {
-                let n = print_ucsc_assemblies().await?;
+                let n = print_ucsc_assemblies(&filter).await?;
                 println!("{} UCSC assemblies", n);
                 println!("Browse a genome: tgv -g <genome> (e.g. tgv -g rn7)");
             } else {
-                let n = print_common_genomes()?;
+                let n = print_common_genomes(&filter)?;
                 println!("{} common genomes", n);
                 println!("Browse a genome: tgv -g <genome> (e.g. tgv -g rat)");
             }
@@ -56,7 +74,14 @@ async fn main() -> Result<(), TGVError> {
     // then apply CLI overrides on top.
     let session_path = cli.session_path();
     let mut settings = if session_path.exists() {
-        match SessionFile::from_path(&session_path).and_then(Settings::try_from) {
+        let session = SessionFile::from_path(&session_path).map(|mut session| {
+            for warning in session.remove_missing_files() {
+                log::warn!("{warning}");
+                eprintln!("Warning: {warning}");
+            }
+            session
+        });
+        match session.and_then(Settings::try_from) {
             Ok(s) => {
                 log::info!("Loaded session from {}", session_path.display());
                 s
@@ -90,6 +115,8 @@ async fn main() -> Result<(), TGVError> {
         Settings::default()
     };
     cli.apply_overrides(&mut settings)?;
+    create_cache_dir(&settings.core.cache_dir)?;
+    settings.keys_path = Some(shellexpand::tilde(DEFAULT_KEYS_PATH).to_string());
     log::info!(
         "Settings are ready: session={} reference={} tracks={} test_mode={}",
         session_path.display(),
@@ -139,6 +166,10 @@ async fn main() -> Result<(), TGVError> {
         }
     }
 
+    for output in &app.exit_output {
+        print!("{output}");
+    }
+
     app.close().await?;
     match &app_result {
         Ok(()) => log::info!("The app exited successfully"),
@@ -151,18 +182,25 @@ fn default_log_file_path() -> PathBuf {
     PathBuf::from(shellexpand::tilde("~/.tgv").as_ref()).join(timestamped_log_file_name())
 }
 
-fn print_common_genomes() -> Result<usize, TGVError> {
-    println!("{}", Reference::HG19);
-    println!("{}", Reference::HG38);
-    let genomes = Reference::get_common_genome_names()?;
-    for (genome, name) in &genomes {
-        println!("{} (UCSC assembly: {})", genome, name);
+fn print_common_genomes(filter: &AssemblyFilter) -> Result<usize, TGVError> {
+    let mut n = 0;
+    for name in [Reference::HG19, Reference::HG38] {
+        if filter.matches(name, "Human") {
+            println!("{}", name);
+            n += 1;
+        }
+    }
+    for (genome, name) in Reference::get_common_genome_names()? {
+        if filter.matches(&name, &genome) {
+            println!("{} (UCSC assembly: {})", genome, name);
+            n += 1;
+        }
     }
-    Ok(genomes.len() + 2)
+    Ok(n)
 }
 
-async fn print_ucsc_assemblies() -> Result<usize, TGVError> {
-    let assemblies = UcscDbTrackService::list_assemblies(None).await?;
+async fn print_ucsc_assemblies(filter: &AssemblyFilter) -> Result<usize, TGVError> {
+    let assemblies = UcscDbTrackService::list_assemblies(None, filter).await?;
 
     for (name, common_name) in &assemblies {
         println!("{} (Organism: {})", name, common_name);
@@ -1,5 +1,11 @@
-use crate::{app::Scene, register::KeyRegisterType};
-pub use gv_core::message::{Movement, Scroll};
+use crate::{
+    app::Scene,
+    layout::{AreaType, ResizableTrack},
+    register::KeyRegisterType,
+    rendering::Theme,
+};
+pub use gv_core::message::{Movement, Scroll, Zoom};
+use gv_core::{alignment::SplitRead, intervals::Region};
 use strum::Display;
 
 /// TGV messages
@@ -12,6 +18,45 @@ pub enum Message {
     SwitchKeyRegister(KeyRegisterType),
 
     ClearAllKeyRegisters,
+
+    /// Capture the current main view into the snapshot gallery with an optional label.
+    Snapshot(Option<String>),
+
+    /// Export the main view to an SVG file at the path.
+    ExportSvg(String),
+
+    /// Show the reference and data sources in use.
+    Info,
+
+    /// Show or hide an optional track.
+    ToggleTrack(AreaType),
+
+    /// Show or hide an optional track explicitly.
+    SetTrackShown(AreaType, bool),
+
+    /// Set the GC content window in bases. None derives the window from the zoom level.
+    SetGcContentWindow(Option<u64>),
+
+    /// Show or hide the base modification pileup of all alignments. All modifications are combined.
+    ToggleModificationPileup,
+
+    /// Split the screen into two panes side by side, or close the split and keep the active pane.
+    ToggleSplit,
+
+    /// Turn cursor mode on or off. In cursor mode, h / l move a cursor and the view pans only at the edges.
+    SetCursorMode(bool),
+
+    /// Set the height of a track in rows.
+    SetTrackHeight(ResizableTrack, u16),
+
+    /// Switch the color theme.
+    SetTheme(Theme),
+
+    /// Center the view on a region and zoom so that it fits.
+    FrameRegion(Region),
+
+    /// Pin read details to the status bar, with the other alignments of a split read to draw. None unpins them.
+    PinReadDetails(Option<String>, Option<SplitRead>),
 }
 
 impl Message {
@@ -38,3 +83,9 @@ impl From<gv_core::message::Scroll> for Message {
         gv_core::message::Message::Scroll(scroll).into()
     }
 }
+
+impl From<gv_core::message::Zoom> for Message {
+    fn from(zoom: gv_core::message::Zoom) -> Self {
+        gv_core::message::Message::Zoom(zoom).into()
+    }
+}
@@ -1,9 +1,14 @@
 use crate::{
     layout::{AlignmentView, AreaType, MainLayout},
-    message::{Message, Movement, Scroll},
+    message::{Message, Movement, Scroll, Zoom},
+    rendering::contig_overview_x_range,
 };
 use crossterm::event;
-use gv_core::{alignment::BaseCoverage, error::TGVError, state::State};
+use gv_core::{
+    alignment::{BaseCoverage, SplitRead},
+    error::TGVError,
+    state::State,
+};
 use itertools::Itertools;
 
 pub struct MouseRegister {
@@ -40,6 +45,55 @@ impl Default for MouseRegister {
     }
 }
 
+/// Wheel notches to pan across the window. Shift+wheel pans the full window per notch.
+const WHEEL_NOTCHES_PER_WINDOW: u16 = 8;
+
+/// Zoom factor per Ctrl+wheel notch.
+const WHEEL_ZOOM_FACTOR: u64 = 2;
+
+/// Movement for a vertical wheel notch over a window of window_width columns: wheel up pans left and wheel down pans
+/// right. Shift pans a full window, and Ctrl zooms in (wheel up) or out (wheel down) instead.
+fn wheel_message(
+    kind: event::MouseEventKind,
+    modifiers: event::KeyModifiers,
+    window_width: u16,
+) -> Option<Message> {
+    let up = match kind {
+        event::MouseEventKind::ScrollUp => true,
+        event::MouseEventKind::ScrollDown => false,
+        _ => return None,
+    };
+
+    if modifiers.contains(event::KeyModifiers::CONTROL) {
+        return Some(if up {
+            Zoom::In(WHEEL_ZOOM_FACTOR).into()
+        } else {
+            Zoom::Out(WHEEL_ZOOM_FACTOR).into()
+        });
+    }
+
+    let columns = if modifiers.contains(event::KeyModifiers::SHIFT) {
+        window_width
+    } else {
+        window_width / WHEEL_NOTCHES_PER_WINDOW
+    };
+    let columns = u64::max(1, columns as u64);
+    Some(if up {
+        Movement::Left(columns).into()
+    } else {
+        Movement::Right(columns).into()
+    })
+}
+
+/// Contig position at column x of a whole-contig strip (the coverage overview or the minimap) drawn from start_x to
+/// end_x. None outside the strip.
+fn overview_position(x: u16, start_x: u16, end_x: u16, contig_length: u64) -> Option<u64> {
+    if x < start_x || x >= end_x {
+        return None;
+    }
+    Some((x - start_x) as u64 * contig_length / (end_x - start_x) as u64 + 1)
+}
+
 impl MouseRegister {
     pub fn handle_mouse_event(
         &mut self,
@@ -73,6 +127,27 @@ impl MouseRegister {
                         self.resizing = true;
                     }
                     self.mouse_down_area_type = *area_type;
+                    if matches!(
+                        area_type,
+                        AreaType::CoverageOverview | AreaType::Minimap | AreaType::Ideogram
+                    ) && let Some(contig_length) = state.contig_length(&alignment_view.focus)?
+                    {
+                        let (start_x, end_x) = contig_overview_x_range(area, state);
+                        if let Some(position) = overview_position(
+                            event.column.saturating_sub(area.x),
+                            start_x,
+                            end_x,
+                            contig_length,
+                        ) {
+                            log::debug!(
+                                "Whole-contig strip click: track={:?} column={} position={}",
+                                area_type,
+                                event.column,
+                                position,
+                            );
+                            messages.push(Movement::Position(position).into());
+                        }
+                    }
                     if matches!(area_type, AreaType::AlignmentDivider { .. }) {
                         self.resizing = true;
                         self.active_divider = Some(*area_type);
@@ -129,6 +204,33 @@ impl MouseRegister {
             }
 
             event::MouseEventKind::Up(_) => {
+                // A click without dragging pins the details of the read under it, or unpins them on empty space.
+                if !self.resizing
+                    && event.column == self.mouse_down_x
+                    && event.row == self.mouse_down_y
+                {
+                    match Self::read_index_at(
+                        state,
+                        layout,
+                        alignment_view,
+                        event.column,
+                        event.row,
+                    ) {
+                        Some((index, read_index)) => {
+                            let read = &state.alignments[index].reads[read_index];
+                            messages.push(Message::PinReadDetails(
+                                Some(read.describe()?),
+                                SplitRead::new(
+                                    index,
+                                    alignment_view.focus.contig_index,
+                                    read,
+                                    &state.contig_header,
+                                )?,
+                            ));
+                        }
+                        None => messages.push(Message::PinReadDetails(None, None)),
+                    }
+                }
                 if let Some(active_divider) = self.active_divider {
                     log::debug!(
                         "Finished alignment divider drag: divider={:?} column={} row={}",
@@ -216,6 +318,7 @@ impl MouseRegister {
                                         right_coordinate,
                                     )?
                                     .into_iter()
+                                    .filter(|variant| state.variant_filter.passes_variant(variant))
                                     .for_each(|variant| {
                                         messages.push(Message::message(variant.describe()));
                                     });
@@ -244,31 +347,40 @@ impl MouseRegister {
                 }
             }
 
-            event::MouseEventKind::ScrollDown => {
+            event::MouseEventKind::ScrollDown | event::MouseEventKind::ScrollUp
+                if event.modifiers.contains(event::KeyModifiers::ALT) =>
+            {
                 if let Some(index) =
                     Self::alignment_index_at_position(layout, event.column, event.row)
                 {
+                    let up = event.kind == event::MouseEventKind::ScrollUp;
                     log::debug!(
-                        "Mouse wheel generated vertical scroll: alignment_index={} direction=down column={} row={}",
+                        "Mouse wheel generated vertical scroll: alignment_index={} direction={} column={} row={}",
                         index,
+                        if up { "up" } else { "down" },
                         event.column,
                         event.row,
                     );
-                    messages.push(Scroll::Down { index, n: 1 }.into());
+                    messages.push(if up {
+                        Scroll::Up { index, n: 1 }.into()
+                    } else {
+                        Scroll::Down { index, n: 1 }.into()
+                    });
                 }
             }
 
-            event::MouseEventKind::ScrollUp => {
-                if let Some(index) =
-                    Self::alignment_index_at_position(layout, event.column, event.row)
+            event::MouseEventKind::ScrollDown | event::MouseEventKind::ScrollUp => {
+                if let Some(message) =
+                    wheel_message(event.kind, event.modifiers, layout.view_area().width)
                 {
                     log::debug!(
-                        "Mouse wheel generated vertical scroll: alignment_index={} direction=up column={} row={}",
-                        index,
+                        "Mouse wheel generated movement: message={:?} modifiers={:?} column={} row={}",
+                        message,
+                        event.modifiers,
                         event.column,
                         event.row,
                     );
-                    messages.push(Scroll::Up { index, n: 1 }.into());
+                    messages.push(message);
                 }
             }
 
@@ -309,6 +421,27 @@ impl MouseRegister {
         };
     }
 
+    /// Alignment track index and read index of the read drawn at (x, y). None if no read is drawn there.
+    pub fn read_index_at(
+        state: &State,
+        layout: &MainLayout,
+        alignment_view: &AlignmentView,
+        x: u16,
+        y: u16,
+    ) -> Option<(usize, usize)> {
+        let Some((AreaType::Alignment(index), area)) = layout.get_area_type_at_position(x, y)
+        else {
+            return None;
+        };
+        let (left, right) = alignment_view.coordinates_of_onscreen_x(x, area)?;
+        let y = alignment_view.coordinate_of_onscreen_y(*index, y, area)?;
+        state
+            .alignments
+            .get(*index)?
+            .read_index_overlapping(left, right, y)
+            .map(|read_index| (*index, read_index))
+    }
+
     fn alignment_index_at_position(layout: &MainLayout, x: u16, y: u16) -> Option<usize> {
         layout
             .get_area_type_at_position(x, y)
@@ -322,3 +455,42 @@ impl MouseRegister {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyModifiers, MouseEventKind};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(MouseEventKind::ScrollUp, KeyModifiers::NONE, 80, Some(Movement::Left(10).into()))]
+    #[case(MouseEventKind::ScrollDown, KeyModifiers::NONE, 80, Some(Movement::Right(10).into()))]
+    #[case(MouseEventKind::ScrollUp, KeyModifiers::NONE, 5, Some(Movement::Left(1).into()))]
+    #[case(MouseEventKind::ScrollUp, KeyModifiers::SHIFT, 80, Some(Movement::Left(80).into()))]
+    #[case(MouseEventKind::ScrollDown, KeyModifiers::SHIFT, 80, Some(Movement::Right(80).into()))]
+    #[case(MouseEventKind::ScrollUp, KeyModifiers::CONTROL, 80, Some(Zoom::In(2).into()))]
+    #[case(MouseEventKind::ScrollDown, KeyModifiers::CONTROL, 80, Some(Zoom::Out(2).into()))]
+    #[case(MouseEventKind::ScrollDown, KeyModifiers::CONTROL | KeyModifiers::SHIFT, 80, Some(Zoom::Out(2).into()))]
+    #[case(MouseEventKind::ScrollLeft, KeyModifiers::NONE, 80, None)]
+    #[case(MouseEventKind::Moved, KeyModifiers::CONTROL, 80, None)]
+    fn test_wheel_message(
+        #[case] kind: MouseEventKind,
+        #[case] modifiers: KeyModifiers,
+        #[case] window_width: u16,
+        #[case] expected: Option<Message>,
+    ) {
+        assert_eq!(wheel_message(kind, modifiers, window_width), expected);
+    }
+
+    #[rstest]
+    // A strip of 100 columns from x = 10 over a 1 kb contig: 10 bp per column.
+    #[case(10, Some(1))]
+    #[case(11, Some(11))]
+    #[case(60, Some(501))]
+    #[case(109, Some(991))]
+    #[case(9, None)]
+    #[case(110, None)]
+    fn overview_clicks_jump_to_the_contig_position(#[case] x: u16, #[case] expected: Option<u64>) {
+        assert_eq!(overview_position(x, 10, 110, 1_000), expected);
+    }
+}
@@ -1,17 +1,54 @@
 use crate::{
     app::Scene,
+    layout::AreaType,
     message::{Message, Movement},
+    snapshot::SnapshotGallery,
 };
 use crossterm::event::{KeyCode, KeyEvent};
-use gv_core::normal::update_by_char;
-use gv_core::{error::TGVError, state::State};
+use gv_core::normal::{Action, KeyBindings};
+use gv_core::{
+    contig_overview::ContigOverview,
+    error::TGVError,
+    intervals::{Focus, Region},
+    state::State,
+};
 use itertools::Itertools;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+
+/// While a :find motif is set, n / N jump to its matches instead of the loci.
+fn repeat_motif_search(messages: Vec<Message>, state: &State) -> Vec<Message> {
+    if state.motif.is_none() {
+        return messages;
+    }
+    messages
+        .into_iter()
+        .map(|message| match message {
+            Message::Core(gv_core::message::Message::Move(Movement::NextLocus(n))) => {
+                Movement::NextMatches(n).into()
+            }
+            Message::Core(gv_core::message::Message::Move(Movement::PreviousLocus(n))) => {
+                Movement::PreviousMatches(n).into()
+            }
+            message => message,
+        })
+        .collect()
+}
+
+/// Maximum number of submitted commands kept in the command history.
+const COMMAND_HISTORY_CAPACITY: usize = 100;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum KeyRegisterType {
     Normal,
     Command,
     Help,
     ContigList,
+    GeneList,
+    ContigOverview,
+    Snapshots,
+    Bookmarks,
+    Popup,
     // ContigListCommand,
 }
 
@@ -24,6 +61,48 @@ pub struct Registers {
     /// Index of the current focused contig.
     /// Indexes in the contig list view is identical to the contig header.
     pub contig_list_cursor: usize,
+
+    /// Gene names to pick from, as listed by :gene.
+    pub gene_list: Vec<String>,
+
+    /// Index of the selected gene in the gene list.
+    pub gene_list_cursor: usize,
+
+    /// Binned genes and coverage of the contig shown by the contig overview.
+    pub contig_overview: ContigOverview,
+
+    /// Index of the selected bin in the contig overview.
+    pub contig_overview_cursor: usize,
+
+    /// Index of the displayed snapshot in the snapshot gallery.
+    pub snapshot_cursor: usize,
+
+    /// Index of the selected bookmark, sorted by name.
+    pub bookmark_cursor: usize,
+
+    /// Keyword filtering the help page. Empty shows all entries.
+    /// Not reset by clear() because it is set right before the help page is opened.
+    pub help_keyword: String,
+
+    /// Lines of the help page scrolled past. Not reset by clear() because it is reset when the help page is opened.
+    pub help_scroll: usize,
+
+    /// Submitted commands, oldest first. Kept across command mode sessions.
+    /// Not reset by clear().
+    pub command_history: VecDeque<String>,
+
+    /// Index of the recalled entry in the command history. None if no entry is recalled.
+    command_history_cursor: Option<usize>,
+
+    /// Input typed before the first recall. Restored when Down moves past the most recent entry.
+    command_draft: String,
+
+    /// Normal mode key bindings. Defaults overridden by the key binding file.
+    pub key_bindings: KeyBindings,
+
+    /// Alignment track that keys and commands act on, cycled with gt. None acts on all tracks, and keys scroll the
+    /// first one. Not reset by clear().
+    pub alignment_track: Option<usize>,
 }
 
 impl Default for Registers {
@@ -35,21 +114,123 @@ impl Default for Registers {
             command_cursor: 0,
 
             contig_list_cursor: 0,
+            gene_list: Vec::new(),
+            gene_list_cursor: 0,
+            contig_overview: ContigOverview::default(),
+            contig_overview_cursor: 0,
+            snapshot_cursor: 0,
+            bookmark_cursor: 0,
+            help_keyword: "".to_string(),
+            help_scroll: 0,
+            command_history: VecDeque::new(),
+            command_history_cursor: None,
+            command_draft: "".to_string(),
+            key_bindings: KeyBindings::default(),
+            alignment_track: None,
         }
     }
 }
 
+impl Registers {
+    /// Registers with the normal mode key bindings of a key binding file. The default bindings are used if there
+    /// is no file.
+    pub fn new(keys_path: Option<&str>) -> Result<Self, TGVError> {
+        let key_bindings = match keys_path {
+            Some(path) if Path::new(path).exists() => load_key_bindings(path)?,
+            _ => KeyBindings::default(),
+        };
+        Ok(Self {
+            key_bindings,
+            ..Self::default()
+        })
+    }
+}
+
+/// Parse a key binding file: a TOML table of action names to keys, e.g. `pan_left = "a"`. Actions that are not
+/// listed keep their default keys.
+pub fn parse_key_bindings(content: &str) -> Result<KeyBindings, TGVError> {
+    let overrides = toml::from_str::<BTreeMap<String, String>>(content).map_err(|e| {
+        TGVError::ParsingError(format!(
+            "Key bindings must map action names to keys, e.g. pan_left = \"a\": {e}"
+        ))
+    })?;
+    KeyBindings::with_overrides(
+        overrides
+            .iter()
+            .map(|(action, keys)| (action.as_str(), keys.as_str())),
+    )
+}
+
+fn load_key_bindings(path: &str) -> Result<KeyBindings, TGVError> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| TGVError::IOError(format!("Failed to read key bindings {path}: {e}")))?;
+    parse_key_bindings(&content).map_err(|e| match e {
+        TGVError::ParsingError(message) => {
+            TGVError::ParsingError(format!("Invalid key bindings in {path}: {message}"))
+        }
+        e => e,
+    })
+}
+
 impl Registers {
     pub fn clear(&mut self) {
         self.normal.clear();
         self.command.clear();
 
         self.command_cursor = 0;
+        self.command_history_cursor = None;
+        self.command_draft.clear();
         self.contig_list_cursor = 0;
+        self.gene_list.clear();
+        self.gene_list_cursor = 0;
+        self.contig_overview = ContigOverview::default();
+        self.contig_overview_cursor = 0;
+        self.snapshot_cursor = 0;
+        self.bookmark_cursor = 0;
     }
 }
 
 impl Registers {
+    /// Add the command input to the history. Empty inputs and repeats of the most recent entry are skipped.
+    fn push_command_history(&mut self) {
+        let command = self.command.trim();
+        if command.is_empty()
+            || self
+                .command_history
+                .back()
+                .is_some_and(|last| last == command)
+        {
+            return;
+        }
+        self.command_history.push_back(command.to_string());
+        if self.command_history.len() > COMMAND_HISTORY_CAPACITY {
+            self.command_history.pop_front();
+        }
+    }
+
+    /// Replace the command input with an older (Up) or newer (Down) history entry.
+    fn recall_command_history(&mut self, older: bool) {
+        let cursor = match (self.command_history_cursor, older) {
+            (_, true) if self.command_history.is_empty() => return,
+            (None, true) => {
+                self.command_draft = self.command.clone();
+                Some(self.command_history.len() - 1)
+            }
+            (Some(cursor), true) => Some(cursor.saturating_sub(1)),
+            (None, false) => return,
+            (Some(cursor), false) => {
+                (cursor + 1 < self.command_history.len()).then_some(cursor + 1)
+            }
+        };
+
+        self.command_history_cursor = cursor;
+        self.command = match cursor {
+            Some(cursor) => self.command_history[cursor].clone(),
+            None => std::mem::take(&mut self.command_draft),
+        };
+        self.command_cursor = self.command.len();
+    }
+
     fn handle_help(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
         match key_event.code {
             KeyCode::Esc => Ok(vec![
@@ -57,6 +238,32 @@ impl Registers {
                 Message::SwitchKeyRegister(KeyRegisterType::Normal),
             ]), // TODO: when handling this, should switch register too.
             // This ensures that switching scene and switching register are always together.
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.help_scroll = self.help_scroll.saturating_add(1);
+                Ok(vec![])
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.help_scroll = self.help_scroll.saturating_sub(1);
+                Ok(vec![])
+            }
+            KeyCode::Char('}') | KeyCode::PageDown => {
+                self.help_scroll = self.help_scroll.saturating_add(30);
+                Ok(vec![])
+            }
+            KeyCode::Char('{') | KeyCode::PageUp => {
+                self.help_scroll = self.help_scroll.saturating_sub(30);
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    fn handle_popup(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
+        match key_event.code {
+            KeyCode::Esc => Ok(vec![
+                Message::SwitchScene(Scene::Main),
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+            ]),
             _ => Ok(vec![]),
         }
     }
@@ -112,7 +319,161 @@ impl Registers {
         }
     }
 
+    /// Move the selected gene up or down. Enter goes to the gene.
+    /// Move the selected bin left or right. Enter zooms into the bin.
+    fn handle_contig_overview(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
+        let last_index = self.contig_overview.bins().saturating_sub(1);
+        match key_event.code {
+            KeyCode::Enter => {
+                let (start, end) = self.contig_overview.bin_range(self.contig_overview_cursor);
+                Ok(vec![
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                    Message::SwitchScene(Scene::Main),
+                    Message::FrameRegion(Region {
+                        focus: Focus {
+                            contig_index: self.contig_overview.contig_index,
+                            position: start + (end - start) / 2,
+                        },
+                        half_width: (end - start) / 2,
+                    }),
+                ])
+            }
+            KeyCode::Esc | KeyCode::Char('O') => Ok(vec![
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]),
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.contig_overview_cursor =
+                    usize::min(self.contig_overview_cursor.saturating_add(1), last_index);
+                Ok(vec![])
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.contig_overview_cursor = self.contig_overview_cursor.saturating_sub(1);
+                Ok(vec![])
+            }
+            KeyCode::Char('p') => {
+                self.contig_overview_cursor =
+                    usize::min(self.contig_overview_cursor.saturating_add(10), last_index);
+                Ok(vec![])
+            }
+            KeyCode::Char('y') => {
+                self.contig_overview_cursor = self.contig_overview_cursor.saturating_sub(10);
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    fn handle_gene_list(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
+        let last_index = self.gene_list.len().saturating_sub(1);
+        match key_event.code {
+            KeyCode::Enter => Ok(vec![
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]
+            .into_iter()
+            .chain(
+                self.gene_list
+                    .get(self.gene_list_cursor)
+                    .map(|gene_name| Movement::Gene(gene_name.clone()).into()),
+            )
+            .collect()),
+
+            KeyCode::Esc => Ok(vec![
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]),
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.gene_list_cursor =
+                    usize::min(self.gene_list_cursor.saturating_add(1), last_index);
+                Ok(vec![])
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.gene_list_cursor = self.gene_list_cursor.saturating_sub(1);
+                Ok(vec![])
+            }
+            KeyCode::Char('}') => {
+                self.gene_list_cursor =
+                    usize::min(self.gene_list_cursor.saturating_add(30), last_index);
+                Ok(vec![])
+            }
+            KeyCode::Char('{') => {
+                self.gene_list_cursor = self.gene_list_cursor.saturating_sub(30);
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Flip through snapshots.
+    fn handle_snapshots(
+        &mut self,
+        key_event: KeyEvent,
+        snapshots: &SnapshotGallery,
+    ) -> Result<Vec<Message>, TGVError> {
+        match key_event.code {
+            KeyCode::Esc => Ok(vec![
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]),
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.snapshot_cursor = usize::min(
+                    self.snapshot_cursor.saturating_add(1),
+                    snapshots.len().saturating_sub(1),
+                );
+                Ok(vec![])
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.snapshot_cursor = self.snapshot_cursor.saturating_sub(1);
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// Move the selected bookmark up or down, and jump to it with Enter.
+    fn handle_bookmarks(
+        &mut self,
+        key_event: KeyEvent,
+        state: &State,
+    ) -> Result<Vec<Message>, TGVError> {
+        let names = state.bookmark_names();
+        match key_event.code {
+            KeyCode::Enter => Ok([
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]
+            .into_iter()
+            .chain(
+                names
+                    .get(self.bookmark_cursor)
+                    .map(|name| Movement::Bookmark(name.to_string()).into()),
+            )
+            .collect()),
+            KeyCode::Esc => Ok(vec![
+                Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                Message::SwitchScene(Scene::Main),
+            ]),
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.bookmark_cursor = usize::min(
+                    self.bookmark_cursor.saturating_add(1),
+                    names.len().saturating_sub(1),
+                );
+                Ok(vec![])
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.bookmark_cursor = self.bookmark_cursor.saturating_sub(1);
+                Ok(vec![])
+            }
+            _ => Ok(vec![]),
+        }
+    }
+
     fn handle_command(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
+        if key_event.code == KeyCode::Enter {
+            self.push_command_history();
+        }
+
         match key_event.code {
             KeyCode::Esc => Ok(vec![
                 Message::ClearAllKeyRegisters,
@@ -120,16 +481,204 @@ impl Registers {
             ]),
 
             KeyCode::Enter => match self.command.as_ref() {
-                "h" => Ok(vec![
-                    Message::ClearAllKeyRegisters,
-                    Message::SwitchScene(Scene::Help),
-                    Message::SwitchKeyRegister(KeyRegisterType::Help),
-                ]),
+                command if command == "h" || command == "help" || command.starts_with("help ") => {
+                    self.help_keyword = command
+                        .strip_prefix("help")
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string();
+                    self.help_scroll = 0;
+                    Ok(vec![
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchScene(Scene::Help),
+                        Message::SwitchKeyRegister(KeyRegisterType::Help),
+                    ])
+                }
                 "ls" | "contigs" => Ok(vec![
                     Message::ClearAllKeyRegisters,
                     Message::SwitchScene(Scene::ContigList),
                     Message::SwitchKeyRegister(KeyRegisterType::ContigList),
                 ]),
+                "marks" => Ok(vec![
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchScene(Scene::Bookmarks),
+                    Message::SwitchKeyRegister(KeyRegisterType::Bookmarks),
+                ]),
+                "snapshots" => Ok(vec![
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchScene(Scene::Snapshots),
+                    Message::SwitchKeyRegister(KeyRegisterType::Snapshots),
+                ]),
+                "info" => Ok(vec![
+                    Message::Info,
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "mod pileup" => Ok(vec![
+                    Message::ToggleModificationPileup,
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "overview" => Ok(vec![
+                    Message::ToggleTrack(AreaType::CoverageOverview),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "split" => Ok(vec![
+                    Message::ToggleSplit,
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "cursor on" => Ok(vec![
+                    Message::SetCursorMode(true),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "cursor off" => Ok(vec![
+                    Message::SetCursorMode(false),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "gaps" => Ok(vec![
+                    Message::ToggleTrack(AreaType::Gaps),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "gcskew" => Ok(vec![
+                    Message::ToggleTrack(AreaType::GcSkew),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "ideogram" => Ok(vec![
+                    Message::ToggleTrack(AreaType::Ideogram),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "cpg islands" => Ok(vec![
+                    Message::ToggleTrack(AreaType::CpgIslands),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "minimap on" => Ok(vec![
+                    Message::SetTrackShown(AreaType::Minimap, true),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "minimap off" => Ok(vec![
+                    Message::SetTrackShown(AreaType::Minimap, false),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "gc on" => Ok(vec![
+                    Message::SetTrackShown(AreaType::GcContent, true),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                "gc off" => Ok(vec![
+                    Message::SetTrackShown(AreaType::GcContent, false),
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                ]),
+                command if command.starts_with("gc window ") => {
+                    let window = command["gc window".len()..].trim();
+                    let messages = match window {
+                        "auto" => vec![
+                            Message::SetGcContentWindow(None),
+                            Message::SetTrackShown(AreaType::GcContent, true),
+                        ],
+                        _ => match window.parse::<u64>() {
+                            Ok(window) if window > 0 => vec![
+                                Message::SetGcContentWindow(Some(window)),
+                                Message::SetTrackShown(AreaType::GcContent, true),
+                            ],
+                            _ => vec![Message::message(format!(
+                                "Invalid GC content window `{window}`"
+                            ))],
+                        },
+                    };
+                    Ok(messages
+                        .into_iter()
+                        .chain([
+                            Message::ClearAllKeyRegisters,
+                            Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                        ])
+                        .collect())
+                }
+                command if command.starts_with("theme ") || command.starts_with("palette ") => {
+                    let theme = command
+                        .split_once(' ')
+                        .map_or("", |(_, theme)| theme.trim());
+                    let message = match theme.parse() {
+                        Ok(theme) => Message::SetTheme(theme),
+                        Err(e) => Message::message(format!("{e}")),
+                    };
+                    Ok(vec![
+                        message,
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                    ])
+                }
+                command if command.starts_with("height ") => {
+                    let message = match command["height".len()..].split_whitespace().collect_vec()[..]
+                    {
+                        [track, height] => match (track.parse(), height.parse::<u16>()) {
+                            (Ok(track), Ok(height)) => Message::SetTrackHeight(track, height),
+                            (Err(e), _) => Message::message(format!("{e}")),
+                            (_, Err(_)) => Message::message(format!("Invalid height `{height}`")),
+                        },
+                        _ => Message::message(
+                            "Usage: height coverage|alignment|sequence|gene ROWS".to_string(),
+                        ),
+                    };
+                    Ok(vec![
+                        message,
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                    ])
+                }
+                command if command == "export" || command.starts_with("export ") => {
+                    let path = command["export".len()..].trim();
+                    let message = if path.to_lowercase().ends_with(".svg") {
+                        Message::ExportSvg(path.to_string())
+                    } else {
+                        Message::message("Usage: export PATH.svg".to_string())
+                    };
+                    // Export after leaving command mode so that the console is not in the image.
+                    Ok(vec![
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                        message,
+                    ])
+                }
+                command if command.starts_with("gene ") => {
+                    let messages = gv_core::command::parse(command)
+                        .map(|m| m.into_iter().map(Message::Core).collect_vec())
+                        .unwrap_or_else(|e| vec![Message::message(format!("{e}"))]);
+                    // Find genes after leaving command mode so that the gene list stays open.
+                    Ok(vec![
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                    ]
+                    .into_iter()
+                    .chain(messages)
+                    .collect())
+                }
+                "indels" => Ok(vec![
+                    Message::ClearAllKeyRegisters,
+                    Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                    // Show the popup after leaving command mode so that Esc closes it.
+                    Message::Core(gv_core::message::Message::ShowIndelHistogram),
+                ]),
+                command if command == "snapshot" || command.starts_with("snapshot ") => {
+                    let label = command["snapshot".len()..].trim();
+                    let label = (!label.is_empty()).then(|| label.to_string());
+                    // Capture after leaving command mode so that the console is not in the snapshot.
+                    Ok(vec![
+                        Message::ClearAllKeyRegisters,
+                        Message::SwitchKeyRegister(KeyRegisterType::Normal),
+                        Message::Snapshot(label),
+                    ])
+                }
                 _ => Ok(gv_core::command::parse(self.command.as_str())
                     .map(|m| m.into_iter().map(Message::Core).collect_vec())
                     .unwrap_or_else(|e| {
@@ -168,6 +717,14 @@ impl Registers {
                     .clamp(0, self.command.len());
                 Ok(vec![])
             }
+            KeyCode::Up => {
+                self.recall_command_history(true);
+                Ok(vec![])
+            }
+            KeyCode::Down => {
+                self.recall_command_history(false);
+                Ok(vec![])
+            }
             _ => Err(TGVError::RegisterError(format!(
                 "Invalid command mode input: {:?}",
                 key_event
@@ -175,32 +732,44 @@ impl Registers {
         }
     }
 
+    /// Arrow keys keep their actions regardless of key bindings.
+    fn handle_normal_action(&mut self, action: Action) -> Result<Vec<Message>, TGVError> {
+        Ok(self
+            .key_bindings
+            .update_by_action(
+                &mut self.normal,
+                action,
+                self.alignment_track.unwrap_or_default(),
+            )?
+            .into_iter()
+            .map(|m| m.into())
+            .collect_vec())
+    }
+
     fn handle_normal(&mut self, key_event: KeyEvent) -> Result<Vec<Message>, TGVError> {
         match key_event.code {
             KeyCode::Char(':') => Ok(vec![
                 Message::ClearAllKeyRegisters,
                 Message::SwitchKeyRegister(KeyRegisterType::Command),
             ]),
-            KeyCode::Char(char) => Ok(update_by_char(&mut self.normal, char)?
-                .into_iter()
-                .map(|m| m.into())
-                .collect_vec()),
-            KeyCode::Left => Ok(update_by_char(&mut self.normal, 'h')?
-                .into_iter()
-                .map(|m| m.into())
-                .collect_vec()),
-            KeyCode::Up => Ok(update_by_char(&mut self.normal, 'k')?
-                .into_iter()
-                .map(|m| m.into())
-                .collect_vec()),
-            KeyCode::Down => Ok(update_by_char(&mut self.normal, 'j')?
-                .into_iter()
-                .map(|m| m.into())
-                .collect_vec()),
-            KeyCode::Right => Ok(update_by_char(&mut self.normal, 'l')?
+            KeyCode::Char(char) => Ok(self
+                .key_bindings
+                .update_by_char(
+                    &mut self.normal,
+                    char,
+                    self.alignment_track.unwrap_or_default(),
+                )?
                 .into_iter()
                 .map(|m| m.into())
                 .collect_vec()),
+            KeyCode::Left => self.handle_normal_action(Action::PanLeft),
+            KeyCode::Up => self.handle_normal_action(Action::ScrollUp),
+            KeyCode::Down => self.handle_normal_action(Action::ScrollDown),
+            KeyCode::Right => self.handle_normal_action(Action::PanRight),
+            KeyCode::Esc => {
+                self.clear();
+                Ok(vec![Message::PinReadDetails(None, None)])
+            }
 
             _ => {
                 self.clear();
@@ -216,12 +785,20 @@ impl Registers {
         &mut self,
         key_event: KeyEvent,
         state: &State,
+        snapshots: &SnapshotGallery,
     ) -> Result<Vec<Message>, TGVError> {
         Ok(match self.current {
-            KeyRegisterType::Normal => self.handle_normal(key_event),
+            KeyRegisterType::Normal => self
+                .handle_normal(key_event)
+                .map(|messages| repeat_motif_search(messages, state)),
             KeyRegisterType::Command => self.handle_command(key_event),
             KeyRegisterType::Help => self.handle_help(key_event),
             KeyRegisterType::ContigList => self.handle_contig_list(key_event, state),
+            KeyRegisterType::GeneList => self.handle_gene_list(key_event),
+            KeyRegisterType::ContigOverview => self.handle_contig_overview(key_event),
+            KeyRegisterType::Snapshots => self.handle_snapshots(key_event, snapshots),
+            KeyRegisterType::Bookmarks => self.handle_bookmarks(key_event, state),
+            KeyRegisterType::Popup => self.handle_popup(key_event),
             // KeyRegisterType::ContigListCommand => {
             //     self.contig_list_command.handle_key_event(key_event)
             // }
@@ -234,3 +811,173 @@ impl Registers {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use rstest::rstest;
+
+    fn type_keys(registers: &mut Registers, key_codes: &[KeyCode]) {
+        for key_code in key_codes {
+            registers
+                .handle_command(KeyEvent::new(*key_code, KeyModifiers::empty()))
+                .unwrap();
+        }
+    }
+
+    fn submit(registers: &mut Registers, command: &str) {
+        type_keys(
+            registers,
+            &command
+                .chars()
+                .map(KeyCode::Char)
+                .chain([KeyCode::Enter])
+                .collect_vec(),
+        );
+        // Enter clears the registers through Message::ClearAllKeyRegisters.
+        registers.clear();
+    }
+
+    #[test]
+    fn up_recalls_the_most_recent_command() {
+        let mut registers = Registers::default();
+        submit(&mut registers, "goto chr1:100");
+        submit(&mut registers, "mapq 20");
+
+        type_keys(&mut registers, &[KeyCode::Up]);
+        assert_eq!(registers.command, "mapq 20");
+        assert_eq!(registers.command_cursor, "mapq 20".len());
+
+        type_keys(&mut registers, &[KeyCode::Up, KeyCode::Up]);
+        assert_eq!(registers.command, "goto chr1:100");
+
+        type_keys(&mut registers, &[KeyCode::Down]);
+        assert_eq!(registers.command, "mapq 20");
+    }
+
+    #[test]
+    fn down_past_the_most_recent_command_restores_the_draft() {
+        let mut registers = Registers::default();
+        submit(&mut registers, "mapq 20");
+
+        type_keys(
+            &mut registers,
+            &[KeyCode::Char('g'), KeyCode::Up, KeyCode::Down],
+        );
+        assert_eq!(registers.command, "g");
+    }
+
+    #[test]
+    fn editing_a_recalled_command_does_not_change_the_history() {
+        let mut registers = Registers::default();
+        submit(&mut registers, "goto chr1:100");
+        submit(&mut registers, "mapq 20");
+
+        type_keys(
+            &mut registers,
+            &[KeyCode::Up, KeyCode::Backspace, KeyCode::Char('5')],
+        );
+        assert_eq!(registers.command, "mapq 25");
+        assert_eq!(registers.command_history, ["goto chr1:100", "mapq 20"]);
+
+        type_keys(&mut registers, &[KeyCode::Enter]);
+        registers.clear();
+        assert_eq!(
+            registers.command_history,
+            ["goto chr1:100", "mapq 20", "mapq 25"]
+        );
+    }
+
+    #[test]
+    fn command_history_is_capped() {
+        let mut registers = Registers::default();
+        for i in 0..COMMAND_HISTORY_CAPACITY + 5 {
+            submit(&mut registers, &format!("mapq {i}"));
+        }
+        submit(&mut registers, "mapq 104");
+
+        assert_eq!(registers.command_history.len(), COMMAND_HISTORY_CAPACITY);
+        assert_eq!(registers.command_history.front().unwrap(), "mapq 5");
+        assert_eq!(registers.command_history.back().unwrap(), "mapq 104");
+    }
+
+    fn press(registers: &mut Registers, key_code: KeyCode) -> Vec<Message> {
+        registers
+            .handle_normal(KeyEvent::new(key_code, KeyModifiers::empty()))
+            .unwrap()
+    }
+
+    #[test]
+    fn remapped_keys_produce_their_actions() {
+        let mut registers = Registers {
+            key_bindings: parse_key_bindings(
+                "# Swap zoom keys and pan with a / d\npan_left = \"a\"\npan_right = \"d\"\nzoom_in = \"o\"\nzoom_out = \"z\"\n",
+            )
+            .unwrap(),
+            ..Registers::default()
+        };
+
+        assert_eq!(
+            press(&mut registers, KeyCode::Char('a')),
+            vec![Movement::Left(1).into()]
+        );
+        assert_eq!(
+            press(&mut registers, KeyCode::Char('o')),
+            vec![gv_core::message::Zoom::In(2).into()]
+        );
+        assert_eq!(press(&mut registers, KeyCode::Char('4')), vec![]);
+        assert_eq!(
+            press(&mut registers, KeyCode::Char('d')),
+            vec![Movement::Right(4).into()]
+        );
+        // Arrow keys keep their actions.
+        assert_eq!(
+            press(&mut registers, KeyCode::Left),
+            vec![Movement::Left(1).into()]
+        );
+        assert!(
+            registers
+                .handle_normal(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::empty()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn scroll_keys_act_on_the_selected_alignment_track() {
+        let mut registers = Registers {
+            alignment_track: Some(1),
+            ..Registers::default()
+        };
+        assert_eq!(
+            press(&mut registers, KeyCode::Char('j')),
+            vec![gv_core::message::Scroll::Down { index: 1, n: 1 }.into()]
+        );
+        assert_eq!(
+            press(&mut registers, KeyCode::Up),
+            vec![gv_core::message::Scroll::Up { index: 1, n: 1 }.into()]
+        );
+    }
+
+    #[rstest]
+    #[case("pan_sideways = \"a\"", "Unknown action `pan_sideways`")]
+    #[case(
+        "pan_left = \"a\"\nzoom_in = \"a\"",
+        "Key `a` is bound to both pan_left and zoom_in"
+    )]
+    #[case("pan_left = 1", "Key bindings must map action names to keys")]
+    fn invalid_key_bindings_are_errors(#[case] content: &str, #[case] expected: &str) {
+        match parse_key_bindings(content) {
+            Err(TGVError::ParsingError(message)) => {
+                assert!(message.starts_with(expected), "{message}")
+            }
+            result => panic!("Expected a parsing error, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn missing_key_binding_file_uses_the_defaults() {
+        let registers = Registers::new(Some("/nonexistent/tgv/keys.toml")).unwrap();
+        assert_eq!(registers.key_bindings, KeyBindings::default());
+    }
+}
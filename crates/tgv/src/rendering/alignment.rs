@@ -4,32 +4,56 @@ use crate::{
 };
 use gv_core::{
     alignment::{
-        Alignment, PairedAlignment, RenderingContext, RenderingContextKind,
-        RenderingContextModifier,
+        AlignedRead, Alignment, PairedAlignment, RenderingContext, RenderingContextKind,
+        RenderingContextModifier, SplitRead,
     },
     error::TGVError,
-    sequence::Sequence,
+    message::AlignmentColor,
+    sequence::{Sequence, complement},
+    strand::Strand,
+    variant::VariantAllele,
 };
 use ratatui::{
     buffer::Buffer,
     layout::{Position, Rect},
-    style::Style,
+    style::{Color, Modifier, Style},
 };
 use std::collections::HashMap;
 
+/// What alignment tracks are drawn with, besides the alignment itself.
+pub struct AlignmentContext<'a> {
+    pub alignment_view: &'a AlignmentView,
+
+    /// Reference that read bases are compared with.
+    pub reference_sequence: &'a Sequence,
+
+    /// Allele of `:support`. Reads carrying it are emphasized and the rest are dimmed.
+    pub supported_allele: Option<&'a VariantAllele>,
+
+    /// Clicked split read, linked to its other alignments.
+    pub split_read: Option<&'a SplitRead>,
+
+    pub pallete: &'a Palette,
+}
+
 /// Render an alignment on the alignment area.
 pub fn render_alignment(
     index: usize,
     area: &Rect,
     buf: &mut Buffer,
     alignment: &mut Alignment,
-    alignment_view: &AlignmentView,
-    reference_sequence: &Sequence,
-    pallete: &Palette,
+    context: &AlignmentContext,
 ) -> Result<(), TGVError> {
     if area.height < 1 {
         return Ok(());
     }
+    let AlignmentContext {
+        alignment_view,
+        reference_sequence,
+        supported_allele,
+        split_read,
+        pallete,
+    } = *context;
 
     let visible_reads = alignment
         .ys_index
@@ -40,33 +64,125 @@ pub fn render_alignment(
         .collect::<Vec<_>>();
 
     for (y, read_index) in visible_reads {
+        let onscreen_y = match alignment_view.onscreen_y_coordinate(index, y, area) {
+            OnScreenCoordinate::OnScreen(y_start) => y_start as u16,
+            _ => continue,
+        };
         let context_index =
             if let Some(context_index) = alignment.get_rendering_context_index(read_index) {
                 context_index
             } else {
                 alignment.calculate_read_rendering_context(read_index, reference_sequence)?
             };
+        let read_style = ReadStyle {
+            match_color: read_match_color(&alignment.reads[read_index], &alignment.color, pallete),
+            complement_bases: alignment.complement_bases,
+        };
         for context in alignment.rendering_contexts[context_index as usize].iter() {
-            render_contexts(context, index, y, buf, alignment_view, area, pallete)?;
+            render_contexts(
+                context,
+                onscreen_y,
+                buf,
+                alignment_view,
+                area,
+                &read_style,
+                pallete,
+            )?;
+        }
+
+        if let Some(allele) = supported_allele {
+            let read = &alignment.reads[read_index];
+            render_support(
+                read.stacking_start(),
+                read.stacking_end(),
+                read.supports(allele),
+                onscreen_y,
+                buf,
+                alignment_view,
+                area,
+            );
+        }
+
+        if let Some(split_read) = split_read
+            && split_read.is_read(&alignment.reads[read_index])
+        {
+            render_split_read(
+                split_read,
+                &alignment.reads[read_index],
+                onscreen_y,
+                buf,
+                alignment_view,
+                area,
+                pallete,
+            );
         }
     }
 
+    render_group_labels(index, area, buf, alignment, alignment_view, pallete);
+
     Ok(())
 }
 
+/// Label the haplotype blocks of `:group hp` with the haplotype and its depth at the cursor.
+fn render_group_labels(
+    index: usize,
+    area: &Rect,
+    buf: &mut Buffer,
+    alignment: &Alignment,
+    alignment_view: &AlignmentView,
+    pallete: &Palette,
+) {
+    let group_labels = alignment.group_labels();
+    if group_labels.is_empty() {
+        return;
+    }
+    let depths = alignment.haplotype_depths_at(alignment_view.cursor_focus().position);
+    for (y, haplotype) in group_labels {
+        let OnScreenCoordinate::OnScreen(onscreen_y) =
+            alignment_view.onscreen_y_coordinate(index, y, area)
+        else {
+            continue;
+        };
+        let depth = depths.get(&haplotype).copied().unwrap_or_default();
+        buf.set_stringn(
+            area.x,
+            area.y + onscreen_y as u16,
+            format!("{haplotype}  depth {depth}"),
+            area.width as usize,
+            Style::default()
+                .fg(pallete.HIGHLIGHT_COLOR)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+}
+
+/// Color of the aligned bases of a read.
+fn read_match_color(read: &AlignedRead, color: &AlignmentColor, pallete: &Palette) -> Color {
+    match color {
+        AlignmentColor::Default => pallete.MATCH_COLOR,
+        AlignmentColor::ReadLength => pallete.read_length_color(read.reference_span()),
+        AlignmentColor::Tag(tag) => pallete.tag_color(read.tag_value(tag).as_deref()),
+    }
+}
+
 pub fn render_paired_alignment(
     index: usize,
     area: &Rect,
     buf: &mut Buffer,
     alignment: &mut Alignment,
-    alignment_view: &AlignmentView,
     paired_alignment: &mut PairedAlignment,
-    reference_sequence: &Sequence,
-    pallete: &Palette,
+    context: &AlignmentContext,
 ) -> Result<(), TGVError> {
     if area.height < 1 {
         return Ok(());
     }
+    let AlignmentContext {
+        alignment_view,
+        reference_sequence,
+        supported_allele,
+        split_read,
+        pallete,
+    } = *context;
 
     let visible_pairs = paired_alignment
         .ys_index
@@ -77,6 +193,10 @@ pub fn render_paired_alignment(
         .collect::<Vec<_>>();
 
     for (y, pair_index) in visible_pairs {
+        let onscreen_y = match alignment_view.onscreen_y_coordinate(index, y, area) {
+            OnScreenCoordinate::OnScreen(y_start) => y_start as u16,
+            _ => continue,
+        };
         let context_index = if let Some(context_index) =
             paired_alignment.get_pair_rendering_context_index(pair_index)
         {
@@ -88,28 +208,207 @@ pub fn render_paired_alignment(
                 reference_sequence,
             )?
         };
+        let pair_gap_color =
+            pallete.pair_gap_color(paired_alignment.pair_class(&alignment.reads, pair_index));
+        // Both reads of a pair are drawn in the color of the first read.
+        let read_style = ReadStyle {
+            match_color: read_match_color(
+                &alignment.reads[paired_alignment.read_pairs[pair_index].read_1_index],
+                &alignment.color,
+                pallete,
+            ),
+            complement_bases: alignment.complement_bases,
+        };
         for context in paired_alignment.rendering_contexts[context_index as usize].iter() {
-            render_contexts(context, index, y, buf, alignment_view, area, pallete)?;
+            if matches!(context.kind, RenderingContextKind::PairGap) {
+                render_pair_gap(
+                    context,
+                    onscreen_y,
+                    buf,
+                    alignment_view,
+                    area,
+                    Style::new().bg(pallete.background).fg(pair_gap_color),
+                );
+                continue;
+            }
+            render_contexts(
+                context,
+                onscreen_y,
+                buf,
+                alignment_view,
+                area,
+                &read_style,
+                pallete,
+            )?;
+        }
+
+        if let Some(allele) = supported_allele {
+            let read_pair = &paired_alignment.read_pairs[pair_index];
+            let supports = std::iter::once(read_pair.read_1_index)
+                .chain(read_pair.read_2_index)
+                .any(|read_index| alignment.reads[read_index].supports(allele));
+            render_support(
+                read_pair.stacking_start(&alignment.reads),
+                read_pair.stacking_end(&alignment.reads),
+                supports,
+                onscreen_y,
+                buf,
+                alignment_view,
+                area,
+            );
+        }
+
+        if let Some(split_read) = split_read {
+            let read_pair = &paired_alignment.read_pairs[pair_index];
+            if let Some(read) = std::iter::once(read_pair.read_1_index)
+                .chain(read_pair.read_2_index)
+                .map(|read_index| &alignment.reads[read_index])
+                .find(|read| split_read.is_read(read))
+            {
+                render_split_read(
+                    split_read,
+                    read,
+                    onscreen_y,
+                    buf,
+                    alignment_view,
+                    area,
+                    pallete,
+                );
+            }
         }
     }
 
     Ok(())
 }
 
-fn render_contexts(
+/// Draw the gap between mates, colored by the pair class.
+fn render_pair_gap(
     context: &RenderingContext,
-    index: usize,
-    y: usize,
+    onscreen_y: u16,
+    buf: &mut Buffer,
+    alignment_view: &AlignmentView,
+    area: &Rect,
+    style: Style,
+) {
+    let Some((onscreen_x, length)) = OnScreenCoordinate::onscreen_start_and_length(
+        &alignment_view.onscreen_x_coordinate(context.start, area),
+        &alignment_view.onscreen_x_coordinate(context.end, area),
+        area,
+    ) else {
+        return;
+    };
+
+    buf.set_string(
+        area.x + onscreen_x,
+        area.y + onscreen_y,
+        "-".repeat(length as usize),
+        style,
+    );
+}
+
+/// Bold and underline a read that carries the alternate allele. Dim it otherwise.
+fn render_support(
+    start: u64,
+    end: u64,
+    supports: bool,
+    onscreen_y: u16,
+    buf: &mut Buffer,
+    alignment_view: &AlignmentView,
+    area: &Rect,
+) {
+    let Some((onscreen_x, length)) = OnScreenCoordinate::onscreen_start_and_length(
+        &alignment_view.onscreen_x_coordinate(start, area),
+        &alignment_view.onscreen_x_coordinate(end, area),
+        area,
+    ) else {
+        return;
+    };
+
+    let modifier = if supports {
+        Modifier::BOLD | Modifier::UNDERLINED
+    } else {
+        Modifier::DIM
+    };
+    buf.set_style(
+        Rect::new(area.x + onscreen_x, area.y + onscreen_y, length, 1),
+        Style::default().add_modifier(modifier),
+    );
+}
+
+/// Link a split read to its other alignments on the contig: `~` on the empty cells between them, and `>` / `<` by
+/// strand on the empty cells of the other alignment. Reads in the way are not overdrawn.
+fn render_split_read(
+    split_read: &SplitRead,
+    read: &AlignedRead,
+    onscreen_y: u16,
     buf: &mut Buffer,
     alignment_view: &AlignmentView,
     area: &Rect,
     pallete: &Palette,
-) -> Result<(), TGVError> {
-    let onscreen_y = match alignment_view.onscreen_y_coordinate(index, y, area) {
-        OnScreenCoordinate::OnScreen(y_start) => y_start as u16,
-        _ => return Ok(()),
+) {
+    let (read_start, read_end) = (read.stacking_start(), read.stacking_end());
+    let style = Style::default()
+        .fg(pallete.HIGHLIGHT_COLOR)
+        .add_modifier(Modifier::DIM);
+    let mut draw = |start: u64, end: u64, symbol: &str| {
+        let Some((onscreen_x, length)) = OnScreenCoordinate::onscreen_start_and_length(
+            &alignment_view.onscreen_x_coordinate(start, area),
+            &alignment_view.onscreen_x_coordinate(end, area),
+            area,
+        ) else {
+            return;
+        };
+        for x in onscreen_x..onscreen_x + length {
+            if let Some(cell) = buf.cell_mut(Position::new(area.x + x, area.y + onscreen_y))
+                && cell.symbol() == " "
+            {
+                cell.set_symbol(symbol).set_style(style);
+            }
+        }
     };
 
+    for alignment in split_read.linked.iter() {
+        let (start, end) = (alignment.position, alignment.end());
+        if start > read_end + 1 {
+            draw(read_end + 1, start - 1, "~");
+        } else if end + 1 < read_start {
+            draw(end + 1, read_start - 1, "~");
+        }
+        let symbol = match alignment.strand {
+            Strand::Forward => ">",
+            Strand::Reverse => "<",
+        };
+        draw(start, end, symbol);
+    }
+}
+
+/// Drawing options shared by the rendering contexts of a read.
+struct ReadStyle {
+    match_color: Color,
+
+    /// Draw the complement of read bases, to match the reference under `:revcomp`.
+    complement_bases: bool,
+}
+
+impl ReadStyle {
+    fn base(&self, base: u8) -> u8 {
+        if self.complement_bases {
+            complement(base)
+        } else {
+            base
+        }
+    }
+}
+
+fn render_contexts(
+    context: &RenderingContext,
+    onscreen_y: u16,
+    buf: &mut Buffer,
+    alignment_view: &AlignmentView,
+    area: &Rect,
+    read_style: &ReadStyle,
+    pallete: &Palette,
+) -> Result<(), TGVError> {
     let start_onscreen_coordinate = alignment_view.onscreen_x_coordinate(context.start, area);
     let end_onscreen_coordinate = alignment_view.onscreen_x_coordinate(context.end, area);
 
@@ -130,7 +429,7 @@ fn render_contexts(
                 area.y + onscreen_y,
                 "-".repeat(length as usize),
                 Style::default()
-                    .bg(pallete.MATCH_COLOR)
+                    .bg(read_style.match_color)
                     .fg(pallete.MATCH_FG_COLOR),
             );
         }
@@ -144,12 +443,15 @@ fn render_contexts(
                 .fg(pallete.DELETION_COLOR),
         ),
 
-        RenderingContextKind::SoftClip(base) => buf.set_string(
-            area.x + onscreen_x,
-            area.y + onscreen_y,
-            String::from_utf8(vec![base])?, // FIXME
-            Style::default().bg(pallete.softclip_color(base)),
-        ),
+        RenderingContextKind::SoftClip(base) => {
+            let base = read_style.base(base);
+            buf.set_string(
+                area.x + onscreen_x,
+                area.y + onscreen_y,
+                String::from_utf8(vec![base])?, // FIXME
+                Style::default().bg(pallete.softclip_color(base)),
+            )
+        }
 
         RenderingContextKind::PairGap => buf.set_string(
             area.x + onscreen_x,
@@ -195,7 +497,7 @@ fn render_contexts(
                     && let Some(cell) =
                         buf.cell_mut(Position::new(area.x + x as u16, area.y + onscreen_y))
                 {
-                    cell.set_symbol("►");
+                    cell.set_symbol(pallete.glyphs.forward_arrow());
                 }
             }
 
@@ -204,16 +506,16 @@ fn render_contexts(
                     && let Some(cell) =
                         buf.cell_mut(Position::new(area.x + x as u16, area.y + onscreen_y))
                 {
-                    cell.set_symbol("◄");
+                    cell.set_symbol(pallete.glyphs.reverse_arrow());
                 }
             }
 
-            RenderingContextModifier::Insertion(_l) => {
+            RenderingContextModifier::Insertion(_, _) => {
                 if let OnScreenCoordinate::OnScreen(x) = start_onscreen_coordinate
                     && let Some(cell) =
                         buf.cell_mut(Position::new(area.x + x as u16, area.y + onscreen_y))
                 {
-                    cell.set_symbol("▌")
+                    cell.set_symbol(pallete.glyphs.left_half())
                         .set_style(Style::default().fg(pallete.INSERTION_COLOR));
                 }
             }
@@ -224,8 +526,9 @@ fn render_contexts(
                     && let Some(cell) =
                         buf.cell_mut(Position::new(area.x + x as u16, area.y + onscreen_y))
                 {
-                    cell.set_char(*base as char)
-                        .set_style(Style::default().fg(pallete.mismatch_color(*base)));
+                    let base = read_style.base(*base);
+                    cell.set_char(base as char)
+                        .set_style(Style::default().fg(pallete.mismatch_color(base)));
                 }
             }
 
@@ -260,3 +563,73 @@ fn render_contexts(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::colors::DARK_THEME;
+    use noodles::sam::alignment::{
+        RecordBuf,
+        record::{
+            cigar::{Op, op::Kind},
+            data::field::Tag,
+        },
+        record_buf::data::field::Value,
+    };
+
+    fn read(tags: Vec<(Tag, Value)>) -> AlignedRead {
+        let record = RecordBuf::builder()
+            .set_alignment_start(noodles::core::Position::try_from(100).unwrap())
+            .set_cigar([Op::new(Kind::Match, 10)].into_iter().collect())
+            .set_data(tags.into_iter().collect())
+            .build();
+        AlignedRead::try_from(record).unwrap()
+    }
+
+    #[test]
+    fn reads_are_colored_by_tag_value() {
+        const HP: Tag = Tag::new(b'H', b'P');
+        let palette = DARK_THEME;
+        let color = AlignmentColor::Tag("HP".to_string());
+        let reads = [
+            read(vec![(HP, Value::from(1u8))]),
+            read(vec![(HP, Value::from(2i32))]),
+            read(vec![(HP, Value::from(1i16))]),
+            // A string tag with the same value as an integer one
+            read(vec![(HP, Value::from("2"))]),
+            read(vec![(Tag::READ_GROUP, Value::from("sample1"))]),
+            read(vec![]),
+        ];
+
+        let colors = reads
+            .iter()
+            .map(|read| read_match_color(read, &color, &palette))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            colors,
+            vec![
+                palette.TAG_COLORS[1],
+                palette.TAG_COLORS[2],
+                palette.TAG_COLORS[1],
+                palette.TAG_COLORS[2],
+                palette.MATCH_COLOR,
+                palette.MATCH_COLOR,
+            ]
+        );
+
+        let read_group = AlignmentColor::Tag("RG".to_string());
+        assert_eq!(
+            read_match_color(&reads[4], &read_group, &palette),
+            palette.tag_color(Some("sample1"))
+        );
+        assert_ne!(
+            read_match_color(&reads[4], &read_group, &palette),
+            palette.MATCH_COLOR
+        );
+        assert_eq!(
+            read_match_color(&reads[0], &read_group, &palette),
+            palette.MATCH_COLOR
+        );
+    }
+}
@@ -1,10 +1,25 @@
 use crate::{
-    layout::AlignmentView,
-    rendering::{colors::Palette, intervals::render_simple_intervals},
+    layout::{AlignmentView, OnScreenCoordinate},
+    rendering::colors::Palette,
 };
-use gv_core::{bed::BedTrack, error::TGVError, intervals::GenomeInterval};
-use ratatui::{buffer::Buffer, layout::Rect};
+use gv_core::{
+    bed::{BedSegmentKind, BedTrack},
+    error::TGVError,
+    intervals::GenomeInterval,
+    strand::Strand,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+};
+
+const THICK_ARROW_GAP: u16 = 5;
+const GAP_ARROW_GAP: u16 = 10;
 
+/// Render BED features colored by itemRgb, or by the track colors. Thick parts (thickStart to thickEnd) are full
+/// cells, thin parts are lower blocks, and gaps between blocks are lines. Stranded features have arrows. Names are
+/// printed on features wide enough to fit them.
 pub fn render_bed(
     area: &Rect,
     buf: &mut Buffer,
@@ -15,17 +30,74 @@ pub fn render_bed(
     let region = alignment_view.region(area);
     let intervals =
         bed_intervals.overlapping(region.contig_index(), region.start(), region.end())?; // Optimize?
-    if !intervals.is_empty() {
-        let first_color_index = intervals[0].index % 2;
-        render_simple_intervals(
-            area,
-            buf,
-            intervals,
-            alignment_view,
-            vec![pallete.BED1, pallete.BED2],
-            first_color_index,
-        )?;
+    let Some(first_interval) = intervals.first() else {
+        return Ok(());
+    };
+    let first_color_index = first_interval.index % 2;
+
+    for (i, interval) in intervals.iter().enumerate() {
+        let onscreen_start = alignment_view.onscreen_x_coordinate(interval.start(), area);
+        let onscreen_end = alignment_view.onscreen_x_coordinate(interval.end(), area);
+        let Some((x, length)) =
+            OnScreenCoordinate::onscreen_start_and_length(&onscreen_start, &onscreen_end, area)
+        else {
+            continue;
+        };
+
+        let color = pallete.bed_color(interval.item_rgb, first_color_index + i);
+        for segment in interval.segments() {
+            let onscreen_start = alignment_view.onscreen_x_coordinate(segment.start, area);
+            let onscreen_end = alignment_view.onscreen_x_coordinate(segment.end, area);
+            let Some((segment_x, segment_length)) =
+                OnScreenCoordinate::onscreen_start_and_length(&onscreen_start, &onscreen_end, area)
+            else {
+                continue;
+            };
+
+            let (string, style) = match segment.kind {
+                BedSegmentKind::Thick => (
+                    arrows(segment_length, &interval.strand, THICK_ARROW_GAP, " "),
+                    Style::default()
+                        .bg(color)
+                        .fg(pallete.BED_FOREGROUND_COLOR)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                BedSegmentKind::Thin => (
+                    pallete.glyphs.block().repeat(segment_length as usize),
+                    Style::default().fg(color),
+                ),
+                BedSegmentKind::Gap => (
+                    arrows(segment_length, &interval.strand, GAP_ARROW_GAP, "-"),
+                    Style::default().fg(color),
+                ),
+            };
+            buf.set_string(area.x + segment_x, area.y, string, style);
+        }
+
+        let style = Style::default().bg(color);
+        if let Some(name) = &interval.name
+            && name.chars().count() <= length as usize
+        {
+            buf.set_string(
+                area.x + x,
+                area.y,
+                name,
+                style.fg(pallete.BED_FOREGROUND_COLOR),
+            );
+        }
     }
 
     Ok(())
 }
+
+/// Strand arrows every `gap` cells, separated by `fill`.
+fn arrows(length: u16, strand: &Option<Strand>, gap: u16, fill: &str) -> String {
+    let arrow = match strand {
+        Some(Strand::Forward) => ">",
+        Some(Strand::Reverse) => "<",
+        None => fill,
+    };
+    (0..length)
+        .map(|i| if i % gap == 0 { arrow } else { fill })
+        .collect()
+}
@@ -0,0 +1,71 @@
+use crate::{register::Registers, rendering::colors::Palette};
+use gv_core::{error::TGVError, state::State};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 2;
+
+/// Render bookmarks sorted by name below a one-line title. The selected bookmark is in the highlighted middle row,
+/// as in the contig list.
+pub fn render_bookmarks(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    registers: &Registers,
+    pallete: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let names = state.bookmark_names();
+    if names.is_empty() {
+        buf.set_string(
+            area.x,
+            area.y,
+            "No bookmarks. Use :mark NAME to save the current locus. <ESC> to close.",
+            Style::default(),
+        );
+        return Ok(());
+    }
+
+    buf.set_string(
+        area.x,
+        area.y,
+        "Bookmarks    j/k: select  <ENTER>: go to  <ESC>: close",
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+
+    // Highlight the selection row
+    let selection_row = area.height / 2;
+    for x in area.x..area.x + area.width {
+        if let Some(cell) = buf.cell_mut(Position::new(x, area.y + selection_row)) {
+            cell.set_char(' ');
+            cell.set_bg(pallete.HIGHLIGHT_COLOR);
+        }
+    }
+
+    let name_width = names.iter().map(|name| name.len()).max().unwrap_or(0) + 3;
+    for (y, index) in
+        super::contig_list::get_indexes(area.height, names.len(), registers.bookmark_cursor)
+    {
+        let name = names[index];
+        let focus = state.bookmark(name)?;
+        buf.set_string(
+            area.x,
+            area.y + y,
+            format!(
+                "@{:<name_width$}{}",
+                name,
+                focus.to_locus_str(&state.contig_header)?
+            ),
+            Style::default(),
+        );
+    }
+
+    Ok(())
+}
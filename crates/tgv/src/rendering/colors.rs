@@ -1,5 +1,7 @@
-use gv_core::cytoband::Stain;
-use ratatui::style::{Color, palette::tailwind};
+use crate::rendering::GlyphSet;
+use clap::ValueEnum;
+use gv_core::{alignment::PairClass, cytoband::Stain, error::TGVError};
+use ratatui::style::{Color, Modifier, Style, palette::tailwind};
 
 use noodles::sam::record::data::field::value::base_modifications::group::{
     Modification, modification,
@@ -19,6 +21,12 @@ pub struct Palette {
     pub MISMATCH_COLOR: Color,
     pub DELETION_COLOR: Color,
     pub PAIRGAP_COLOR: Color,
+    /// Gaps of pairs with an insert size below the expected range.
+    pub PAIRGAP_SMALL_COLOR: Color,
+    /// Gaps of pairs with an insert size above the expected range.
+    pub PAIRGAP_LARGE_COLOR: Color,
+    /// Gaps of pairs with an orientation other than F1R2 / F2R1.
+    pub PAIRGAP_DISCORDANT_COLOR: Color,
     pub PAIR_OVERLAP_COLOR: Color,
     pub REFSKIP_COLOR: Color,
     pub INSERTION_COLOR: Color,
@@ -42,15 +50,15 @@ pub struct Palette {
     pub COVERAGE_N: Color,
     pub COVERAGE_TOTAL: Color,
     pub COVERAGE_SOFTCLIP: Color,
+    /// Top row of coverage columns deeper than the axis maximum.
+    pub COVERAGE_CLIPPED: Color,
 
     // Cytoband
     pub HIGHLIGHT_COLOR: Color,
-    // pub CYTOBAND_DEFAULT_COLOR: Color,
-    //  GNEG_COLOR: Color = tailwind::GREEN.c100;
-    pub GPOS25_COLOR: Color,
-    pub GPOS50_COLOR: Color,
-    pub GPOS75_COLOR: Color,
-    pub GPOS100_COLOR: Color,
+    pub GNEG_COLOR: Color,
+    /// gpos stains are interpolated from GPOS_MIN_COLOR (gpos0) to GPOS_MAX_COLOR (gpos100).
+    pub GPOS_MIN_COLOR: Color,
+    pub GPOS_MAX_COLOR: Color,
 
     pub ACEN_COLOR: Color,
     pub GVAR_COLOR: Color,
@@ -65,11 +73,19 @@ pub struct Palette {
     pub BASE_T: Color,
     pub BASE_N: Color,
 
+    // Translation
+    pub CODON_BACKGROUND_COLOR: Color,
+    pub CODON_ALT_BACKGROUND_COLOR: Color,
+    pub START_CODON_BACKGROUND_COLOR: Color,
+    pub STOP_CODON_BACKGROUND_COLOR: Color,
+
     // Intervals
     pub VCF1: Color,
     pub VCF2: Color,
     pub BED1: Color,
     pub BED2: Color,
+    /// Names on BED features.
+    pub BED_FOREGROUND_COLOR: Color,
 
     // Gene track
     pub EXON_BACKGROUND_COLOR: Color,
@@ -78,6 +94,27 @@ pub struct Palette {
     pub NON_CDS_EXON_BACKGROUND_COLOR: Color,
     pub INTRON_FOREGROUND_COLOR: Color,
 
+    // Read length gradient (:color length)
+    pub READ_LENGTH_SHORT: Color,
+    pub READ_LENGTH_LONG: Color,
+
+    /// Colors of tag values (:color tag). Reads without the tag are MATCH_COLOR.
+    pub TAG_COLORS: [Color; 7],
+
+    // GC skew
+    pub GC_SKEW_POSITIVE: Color,
+    pub GC_SKEW_NEGATIVE: Color,
+    pub GC_SKEW_CUMULATIVE: Color,
+
+    // GC content
+    pub GC_CONTENT_COLOR: Color,
+
+    // CpG islands
+    pub CPG_ISLAND_COLOR: Color,
+
+    // bigWig signal
+    pub SIGNAL_COLOR: Color,
+
     // Base modifications (MM/ML tags)
     /// 5mC high probability (>70%): warm orange
     pub MOD_5MC_HIGH: Color,
@@ -89,9 +126,28 @@ pub struct Palette {
     pub MOD_5HMC: Color,
     /// 6mA: purple
     pub MOD_6MA: Color,
+
+    /// Glyphs for bars, arrows, and blocks
+    pub glyphs: GlyphSet,
+
+    /// Theme the colors come from
+    pub theme: Theme,
 }
 
 impl Palette {
+    /// Weight of the background in the color of soft-masked reference bases.
+    const MASKED_BASE_DIMMING: f64 = 0.5;
+
+    /// Color of the gap between mates.
+    pub fn pair_gap_color(&self, pair_class: Option<PairClass>) -> Color {
+        match pair_class {
+            None | Some(PairClass::Normal) => self.PAIRGAP_COLOR,
+            Some(PairClass::TooSmall) => self.PAIRGAP_SMALL_COLOR,
+            Some(PairClass::TooLarge) => self.PAIRGAP_LARGE_COLOR,
+            Some(PairClass::Discordant) => self.PAIRGAP_DISCORDANT_COLOR,
+        }
+    }
+
     pub fn softclip_color(&self, base: u8) -> Color {
         match base {
             b'A' => self.SOFTCLIP_A,
@@ -103,6 +159,15 @@ impl Palette {
         }
     }
 
+    /// BED feature color: itemRgb if present, otherwise the track colors alternating by feature.
+    pub fn bed_color(&self, item_rgb: Option<(u8, u8, u8)>, index: usize) -> Color {
+        match item_rgb {
+            Some((red, green, blue)) => Color::Rgb(red, green, blue),
+            None if index.is_multiple_of(2) => self.BED1,
+            None => self.BED2,
+        }
+    }
+
     pub fn base_color(&self, base: u8) -> Color {
         match base {
             b'A' | b'a' => self.BASE_A,
@@ -113,6 +178,31 @@ impl Palette {
         }
     }
 
+    /// Background color of a reference base. Soft-masked (lowercase) bases are dimmed toward the background.
+    pub fn sequence_base_color(&self, base: u8) -> Color {
+        if base.is_ascii_lowercase() {
+            interpolate_color(
+                self.base_color(base),
+                self.background,
+                Self::MASKED_BASE_DIMMING,
+            )
+        } else {
+            self.base_color(base)
+        }
+    }
+
+    /// Style of a reference base letter. Soft-masked (lowercase) bases are dimmed.
+    pub fn sequence_base_style(&self, base: u8) -> Style {
+        let style = Style::default()
+            .fg(self.SEQUENCE_FOREGROUND_COLOR)
+            .bg(self.sequence_base_color(base));
+        if base.is_ascii_lowercase() {
+            style.add_modifier(Modifier::DIM)
+        } else {
+            style
+        }
+    }
+
     pub fn mismatch_color(&self, base: u8) -> Color {
         match base {
             b'A' | b'a' => self.MISMATCH_A,
@@ -143,32 +233,108 @@ impl Palette {
         }
     }
 
+    /// Interpolates between short and long read colors on a log scale from 100 bp to 100 kb.
+    pub fn read_length_color(&self, length: u64) -> Color {
+        const MIN_LOG_LENGTH: f64 = 2.0;
+        const MAX_LOG_LENGTH: f64 = 5.0;
+
+        let t =
+            ((length.max(1) as f64).log10() - MIN_LOG_LENGTH) / (MAX_LOG_LENGTH - MIN_LOG_LENGTH);
+
+        interpolate_color(self.READ_LENGTH_SHORT, self.READ_LENGTH_LONG, t)
+    }
+
+    /// Color of a tag value. The same value gets the same color across loads and sessions: integers cycle through
+    /// the colors, so that HP 1 and 2 differ, and other values are hashed. Reads without the tag are MATCH_COLOR.
+    pub fn tag_color(&self, value: Option<&str>) -> Color {
+        let Some(value) = value else {
+            return self.MATCH_COLOR;
+        };
+        let n = self.TAG_COLORS.len() as u64;
+        let index = match value.parse::<i64>() {
+            Ok(value) => value.rem_euclid(n as i64) as u64,
+            // FNV-1a
+            Err(_) => {
+                value.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+                    (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+                }) % n
+            }
+        };
+        self.TAG_COLORS[index as usize]
+    }
+
     /// Returns the color associated with the stain type.
     pub fn cytoband_color(&self, stain: Stain) -> Color {
-        // FIXME: This function is AI code. I haven't verified the correctness.
-        // FIXME: Mvoe to Pallete.
         match stain {
-            Stain::Gneg => Color::from_u32(0xffffff),
+            Stain::Gneg => self.GNEG_COLOR,
             Stain::Gpos(p) => {
-                let start_r = 240.0;
-                let start_g = 253.0;
-                let start_b = 244.0;
-                let end_r = 5.0;
-                let end_g = 46.0;
-                let end_b = 22.0;
-
-                let t = p as f32 / 100.0;
+                interpolate_color(self.GPOS_MIN_COLOR, self.GPOS_MAX_COLOR, p as f64 / 100.0)
+            }
+            Stain::Acen => self.ACEN_COLOR,
+            Stain::Gvar => self.GVAR_COLOR,
+            Stain::Stalk => self.STALK_COLOR,
+            Stain::Other(_) => self.OTHER_COLOR,
+        }
+    }
+}
 
-                let r = (start_r * (1.0 - t) + end_r * t).round() as u8;
-                let g = (start_g * (1.0 - t) + end_g * t).round() as u8;
-                let b = (start_b * (1.0 - t) + end_b * t).round() as u8;
+/// Linear interpolation between two colors at t in [0, 1]. Non-RGB colors switch at t = 0.5.
+fn interpolate_color(start: Color, end: Color, t: f64) -> Color {
+    let t = t.clamp(0.0, 1.0);
 
-                Color::Rgb(r, g, b)
+    match (start, end) {
+        (Color::Rgb(start_r, start_g, start_b), Color::Rgb(end_r, end_g, end_b)) => {
+            let interpolate =
+                |start: u8, end: u8| (start as f64 * (1.0 - t) + end as f64 * t).round() as u8;
+            Color::Rgb(
+                interpolate(start_r, end_r),
+                interpolate(start_g, end_g),
+                interpolate(start_b, end_b),
+            )
+        }
+        (start, end) => {
+            if t < 0.5 {
+                start
+            } else {
+                end
             }
-            Stain::Acen => Color::from_u32(0xdc2626),
-            Stain::Gvar => Color::from_u32(0x60a5fa),
-            Stain::Stalk => Color::from_u32(0xc026d3),
-            Stain::Other(_) => Color::from_u32(0x4b5563),
+        }
+    }
+}
+
+/// Color theme, selected with `--palette` and switched with `:theme` or `:palette`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    /// Dark theme with Okabe-Ito colors for bases, variants, and cytoband stains.
+    Colorblind,
+}
+
+impl Theme {
+    /// Palette of the theme with the given glyphs.
+    pub fn palette(&self, glyphs: GlyphSet) -> Palette {
+        let palette = match self {
+            Theme::Dark => DARK_THEME,
+            Theme::Light => LIGHT_THEME,
+            Theme::Colorblind => COLORBLIND_THEME,
+        };
+        Palette { glyphs, ..palette }
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = TGVError;
+
+    fn from_str(s: &str) -> Result<Self, TGVError> {
+        match s {
+            "dark" => Ok(Self::Dark),
+            "light" => Ok(Self::Light),
+            "colorblind" => Ok(Self::Colorblind),
+            _ => Err(TGVError::ParsingError(format!(
+                "Invalid theme `{s}`. Expected \"dark\", \"light\", or \"colorblind\"."
+            ))),
         }
     }
 }
@@ -184,6 +350,9 @@ pub const DARK_THEME: Palette = Palette {
     MISMATCH_COLOR: Color::Rgb(251, 198, 207),
     DELETION_COLOR: Color::Red,
     PAIRGAP_COLOR: Color::LightRed,
+    PAIRGAP_SMALL_COLOR: Color::LightBlue,
+    PAIRGAP_LARGE_COLOR: Color::LightMagenta,
+    PAIRGAP_DISCORDANT_COLOR: Color::LightGreen,
     PAIR_OVERLAP_COLOR: tailwind::GRAY.c900,
     REFSKIP_COLOR: Color::Red,
     INSERTION_COLOR: Color::Magenta,
@@ -208,19 +377,18 @@ pub const DARK_THEME: Palette = Palette {
     COVERAGE_N: Color::LightMagenta,
     COVERAGE_TOTAL: Color::Gray,
     COVERAGE_SOFTCLIP: Color::Cyan, // TODO
+    COVERAGE_CLIPPED: Color::LightYellow,
 
     // Cytoband
     HIGHLIGHT_COLOR: tailwind::RED.c800,
-    //  GNEG_COLOR: Color = tailwind::GREEN.c100;
-    GPOS25_COLOR: tailwind::GREEN.c200,
-    GPOS50_COLOR: tailwind::GREEN.c500,
-    GPOS75_COLOR: tailwind::GREEN.c700,
-    GPOS100_COLOR: tailwind::GREEN.c900,
+    GNEG_COLOR: Color::from_u32(0xffffff),
+    GPOS_MIN_COLOR: Color::from_u32(0xf0fdf4),
+    GPOS_MAX_COLOR: Color::from_u32(0x052e16),
 
-    ACEN_COLOR: tailwind::RED.c300,
-    GVAR_COLOR: tailwind::GRAY.c300,
-    STALK_COLOR: tailwind::GRAY.c300,
-    OTHER_COLOR: tailwind::GRAY.c300,
+    ACEN_COLOR: Color::from_u32(0xdc2626),
+    GVAR_COLOR: Color::from_u32(0x60a5fa),
+    STALK_COLOR: Color::from_u32(0xc026d3),
+    OTHER_COLOR: Color::from_u32(0x4b5563),
 
     // Sequence
     SEQUENCE_FOREGROUND_COLOR: tailwind::GRAY.c900,
@@ -230,11 +398,18 @@ pub const DARK_THEME: Palette = Palette {
     BASE_T: tailwind::YELLOW.c300,
     BASE_N: tailwind::GRAY.c300,
 
+    // Translation
+    CODON_BACKGROUND_COLOR: tailwind::SLATE.c600,
+    CODON_ALT_BACKGROUND_COLOR: tailwind::SLATE.c700,
+    START_CODON_BACKGROUND_COLOR: tailwind::GREEN.c700,
+    STOP_CODON_BACKGROUND_COLOR: tailwind::RED.c700,
+
     // Intervals
     VCF1: tailwind::VIOLET.c900,
     VCF2: tailwind::VIOLET.c400,
     BED1: tailwind::INDIGO.c900,
     BED2: tailwind::INDIGO.c400,
+    BED_FOREGROUND_COLOR: tailwind::WHITE,
 
     // Gene track
     EXON_BACKGROUND_COLOR: tailwind::BLUE.c800,
@@ -243,10 +418,219 @@ pub const DARK_THEME: Palette = Palette {
     NON_CDS_EXON_BACKGROUND_COLOR: tailwind::BLUE.c500,
     INTRON_FOREGROUND_COLOR: tailwind::BLUE.c300,
 
+    READ_LENGTH_SHORT: tailwind::SLATE.c600,
+    READ_LENGTH_LONG: tailwind::FUCHSIA.c600,
+
+    TAG_COLORS: [
+        tailwind::SKY.c500,
+        tailwind::AMBER.c500,
+        tailwind::EMERALD.c500,
+        tailwind::ROSE.c500,
+        tailwind::VIOLET.c500,
+        tailwind::LIME.c500,
+        tailwind::ORANGE.c500,
+    ],
+
+    GC_SKEW_POSITIVE: tailwind::EMERALD.c500,
+    GC_SKEW_NEGATIVE: tailwind::ROSE.c500,
+    GC_SKEW_CUMULATIVE: tailwind::SKY.c400,
+    GC_CONTENT_COLOR: tailwind::AMBER.c400,
+    CPG_ISLAND_COLOR: tailwind::LIME.c400,
+    SIGNAL_COLOR: tailwind::TEAL.c400,
+
     // Base modifications
     MOD_5MC_HIGH: tailwind::ORANGE.c600, // >70% — warm orange (highly methylated)
     MOD_5MC_MED: tailwind::YELLOW.c700,  // 30-70% — muted yellow (ambiguous)
     MOD_5MC_LOW: tailwind::BLUE.c700,    // <30% — cool blue (unmethylated)
     MOD_5HMC: tailwind::TEAL.c500,       // 5hmC — teal
     MOD_6MA: tailwind::PURPLE.c600,      // 6mA — purple
+
+    glyphs: GlyphSet::Block,
+    theme: Theme::Dark,
+};
+
+/// Palette for terminals with a light background. Background colors are lighter and foreground colors darker than in
+/// the dark theme.
+pub const LIGHT_THEME: Palette = Palette {
+    background: Color::from_u32(0xfafafa),
+
+    // Alignment
+    MATCH_COLOR: tailwind::GRAY.c300,
+    MATCH_FG_COLOR: tailwind::GRAY.c900,
+    MISMATCH_COLOR: tailwind::RED.c200,
+    DELETION_COLOR: tailwind::RED.c600,
+    PAIRGAP_COLOR: tailwind::RED.c300,
+    PAIRGAP_SMALL_COLOR: tailwind::BLUE.c500,
+    PAIRGAP_LARGE_COLOR: tailwind::FUCHSIA.c600,
+    PAIRGAP_DISCORDANT_COLOR: tailwind::GREEN.c600,
+    PAIR_OVERLAP_COLOR: tailwind::GRAY.c400,
+    REFSKIP_COLOR: tailwind::RED.c600,
+    INSERTION_COLOR: tailwind::FUCHSIA.c600,
+
+    SOFTCLIP_A: tailwind::RED.c600,
+    SOFTCLIP_C: tailwind::GREEN.c600,
+    SOFTCLIP_G: tailwind::BLUE.c600,
+    SOFTCLIP_T: tailwind::AMBER.c600,
+    SOFTCLIP_N: tailwind::FUCHSIA.c600,
+
+    MISMATCH_A: tailwind::RED.c600,
+    MISMATCH_C: tailwind::GREEN.c600,
+    MISMATCH_G: tailwind::BLUE.c600,
+    MISMATCH_T: tailwind::AMBER.c600,
+    MISMATCH_N: tailwind::FUCHSIA.c600,
+
+    COVERAGE_ALT: tailwind::RED.c600,
+    COVERAGE_A: tailwind::RED.c600,
+    COVERAGE_T: tailwind::AMBER.c600,
+    COVERAGE_C: tailwind::GREEN.c600,
+    COVERAGE_G: tailwind::BLUE.c600,
+    COVERAGE_N: tailwind::FUCHSIA.c600,
+    COVERAGE_TOTAL: tailwind::GRAY.c500,
+    COVERAGE_SOFTCLIP: tailwind::CYAN.c600,
+    COVERAGE_CLIPPED: tailwind::AMBER.c600,
+
+    // Cytoband
+    HIGHLIGHT_COLOR: tailwind::RED.c300,
+    GNEG_COLOR: tailwind::GRAY.c200,
+    GPOS_MIN_COLOR: tailwind::GREEN.c200,
+    GPOS_MAX_COLOR: tailwind::GREEN.c950,
+
+    ACEN_COLOR: tailwind::RED.c600,
+    GVAR_COLOR: tailwind::BLUE.c500,
+    STALK_COLOR: tailwind::FUCHSIA.c600,
+    OTHER_COLOR: tailwind::GRAY.c500,
+
+    // Sequence
+    SEQUENCE_FOREGROUND_COLOR: tailwind::GRAY.c900,
+    BASE_A: tailwind::RED.c200,
+    BASE_C: tailwind::GREEN.c200,
+    BASE_G: tailwind::BLUE.c200,
+    BASE_T: tailwind::YELLOW.c200,
+    BASE_N: tailwind::GRAY.c200,
+
+    // Translation
+    CODON_BACKGROUND_COLOR: tailwind::SLATE.c500,
+    CODON_ALT_BACKGROUND_COLOR: tailwind::SLATE.c600,
+    START_CODON_BACKGROUND_COLOR: tailwind::GREEN.c600,
+    STOP_CODON_BACKGROUND_COLOR: tailwind::RED.c600,
+
+    // Intervals
+    VCF1: tailwind::VIOLET.c700,
+    VCF2: tailwind::VIOLET.c300,
+    BED1: tailwind::INDIGO.c700,
+    BED2: tailwind::INDIGO.c300,
+    BED_FOREGROUND_COLOR: tailwind::WHITE,
+
+    // Gene track
+    EXON_BACKGROUND_COLOR: tailwind::BLUE.c700,
+    EXON_FOREGROUND_COLOR: tailwind::WHITE,
+    GENE_BACKGROUND_COLOR: tailwind::BLUE.c500,
+    NON_CDS_EXON_BACKGROUND_COLOR: tailwind::BLUE.c400,
+    INTRON_FOREGROUND_COLOR: tailwind::BLUE.c600,
+
+    READ_LENGTH_SHORT: tailwind::SLATE.c400,
+    READ_LENGTH_LONG: tailwind::FUCHSIA.c600,
+
+    TAG_COLORS: [
+        tailwind::SKY.c600,
+        tailwind::AMBER.c600,
+        tailwind::EMERALD.c600,
+        tailwind::ROSE.c600,
+        tailwind::VIOLET.c600,
+        tailwind::LIME.c600,
+        tailwind::ORANGE.c600,
+    ],
+
+    GC_SKEW_POSITIVE: tailwind::EMERALD.c600,
+    GC_SKEW_NEGATIVE: tailwind::ROSE.c600,
+    GC_SKEW_CUMULATIVE: tailwind::SKY.c600,
+    GC_CONTENT_COLOR: tailwind::AMBER.c600,
+    CPG_ISLAND_COLOR: tailwind::LIME.c600,
+    SIGNAL_COLOR: tailwind::TEAL.c600,
+
+    // Base modifications
+    MOD_5MC_HIGH: tailwind::ORANGE.c500,
+    MOD_5MC_MED: tailwind::YELLOW.c400,
+    MOD_5MC_LOW: tailwind::BLUE.c400,
+    MOD_5HMC: tailwind::TEAL.c400,
+    MOD_6MA: tailwind::PURPLE.c400,
+
+    glyphs: GlyphSet::Block,
+    theme: Theme::Light,
+};
+
+/// Okabe-Ito colors, distinguishable with the common forms of color blindness.
+/// See: https://jfly.uni-koeln.de/color/
+mod okabe_ito {
+    use ratatui::style::Color;
+
+    pub const ORANGE: Color = Color::Rgb(230, 159, 0);
+    pub const SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+    pub const BLUISH_GREEN: Color = Color::Rgb(0, 158, 115);
+    pub const YELLOW: Color = Color::Rgb(240, 228, 66);
+    pub const BLUE: Color = Color::Rgb(0, 114, 178);
+    pub const VERMILLION: Color = Color::Rgb(213, 94, 0);
+    pub const REDDISH_PURPLE: Color = Color::Rgb(204, 121, 167);
+    pub const GREY: Color = Color::Rgb(153, 153, 153);
+}
+
+/// Dark theme with Okabe-Ito base, variant, and cytoband colors. Bases follow the IGV hues: A green, C blue, G orange,
+/// and T vermillion. Variants are reddish purple and sky blue so that they stand apart from the bases.
+pub const COLORBLIND_THEME: Palette = Palette {
+    SOFTCLIP_A: okabe_ito::BLUISH_GREEN,
+    SOFTCLIP_C: okabe_ito::BLUE,
+    SOFTCLIP_G: okabe_ito::ORANGE,
+    SOFTCLIP_T: okabe_ito::VERMILLION,
+    SOFTCLIP_N: okabe_ito::GREY,
+
+    MISMATCH_A: okabe_ito::BLUISH_GREEN,
+    MISMATCH_C: okabe_ito::BLUE,
+    MISMATCH_G: okabe_ito::ORANGE,
+    MISMATCH_T: okabe_ito::VERMILLION,
+    MISMATCH_N: okabe_ito::GREY,
+
+    COVERAGE_ALT: okabe_ito::YELLOW,
+    COVERAGE_A: okabe_ito::BLUISH_GREEN,
+    COVERAGE_T: okabe_ito::VERMILLION,
+    COVERAGE_C: okabe_ito::BLUE,
+    COVERAGE_G: okabe_ito::ORANGE,
+    COVERAGE_N: okabe_ito::GREY,
+    COVERAGE_CLIPPED: okabe_ito::REDDISH_PURPLE,
+
+    // Cytoband
+    GPOS_MIN_COLOR: Color::Rgb(230, 230, 230),
+    GPOS_MAX_COLOR: Color::Rgb(40, 40, 40),
+    ACEN_COLOR: okabe_ito::VERMILLION,
+    GVAR_COLOR: okabe_ito::SKY_BLUE,
+    STALK_COLOR: okabe_ito::REDDISH_PURPLE,
+    OTHER_COLOR: okabe_ito::GREY,
+
+    // Sequence
+    BASE_A: okabe_ito::BLUISH_GREEN,
+    BASE_C: okabe_ito::BLUE,
+    BASE_G: okabe_ito::ORANGE,
+    BASE_T: okabe_ito::VERMILLION,
+    BASE_N: okabe_ito::GREY,
+
+    // Pairs
+    PAIRGAP_SMALL_COLOR: okabe_ito::SKY_BLUE,
+    PAIRGAP_LARGE_COLOR: okabe_ito::REDDISH_PURPLE,
+    PAIRGAP_DISCORDANT_COLOR: okabe_ito::BLUISH_GREEN,
+
+    // Intervals
+    VCF1: okabe_ito::REDDISH_PURPLE,
+    VCF2: okabe_ito::SKY_BLUE,
+
+    TAG_COLORS: [
+        okabe_ito::SKY_BLUE,
+        okabe_ito::ORANGE,
+        okabe_ito::BLUISH_GREEN,
+        okabe_ito::VERMILLION,
+        okabe_ito::REDDISH_PURPLE,
+        okabe_ito::YELLOW,
+        okabe_ito::BLUE,
+    ],
+
+    theme: Theme::Colorblind,
+    ..DARK_THEME
 };
@@ -95,7 +95,57 @@ pub fn render_contig_list(
     Ok(())
 }
 
-fn get_indexes(height: u16, n_contigs: usize, selected_index: usize) -> Vec<(u16, usize)> {
+/// List of gene names from :gene, with the selected gene on the highlighted middle row.
+pub fn render_gene_list(
+    area: &Rect,
+    buf: &mut Buffer,
+    registers: &Registers,
+    pallete: &Palette,
+) -> Result<(), TGVError> {
+    if area.height <= 1 {
+        return Ok(());
+    }
+
+    // First line: number of genes
+    buf.set_string(
+        area.x,
+        area.y,
+        format!("{} matching genes", registers.gene_list.len()),
+        Style::default(),
+    );
+
+    // Highlight the selection row
+    let selection_row = area.height / 2;
+    for x in area.x..area.x + area.width {
+        let cell = buf.cell_mut(Position::new(x, area.y + selection_row));
+        if let Some(cell) = cell {
+            cell.set_char(' ');
+            cell.set_bg(pallete.HIGHLIGHT_COLOR);
+        }
+    }
+
+    for (y, gene_index) in get_indexes(
+        area.height,
+        registers.gene_list.len(),
+        registers.gene_list_cursor,
+    ) {
+        buf.set_stringn(
+            area.x,
+            area.y + y,
+            &registers.gene_list[gene_index],
+            area.width as usize,
+            Style::default(),
+        );
+    }
+
+    Ok(())
+}
+
+pub(super) fn get_indexes(
+    height: u16,
+    n_contigs: usize,
+    selected_index: usize,
+) -> Vec<(u16, usize)> {
     if n_contigs == 0 {
         return vec![];
     }
@@ -123,7 +173,7 @@ fn render_contig_at_y(
     left_spacing: u16,
     max_contig_length: Option<u64>,
     y: u16,
-    _pallete: &Palette,
+    pallete: &Palette,
 ) -> Result<(), TGVError> {
     let contig_name = contig.name.clone();
     let contig_length = contig.length;
@@ -152,7 +202,7 @@ fn render_contig_at_y(
         buf.set_string(
             area.x + left_spacing,
             area.y + y,
-            "▅".repeat(contig_length_x),
+            pallete.glyphs.block().repeat(contig_length_x),
             Style::default(),
         );
     }
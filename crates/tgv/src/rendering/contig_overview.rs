@@ -0,0 +1,149 @@
+use crate::{
+    register::Registers,
+    rendering::{colors::Palette, to_thousand_separated},
+};
+use gv_core::{error::TGVError, state::State};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
+};
+
+/// Title, two histogram labels, and the selected bin description.
+const TEXT_ROWS: u16 = 4;
+
+/// Render the whole contig as histograms of gene counts and coverage, one column per bin. The selected bin is
+/// highlighted.
+pub fn render_contig_overview(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    registers: &Registers,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    let overview = &registers.contig_overview;
+    if area.height < TEXT_ROWS + 2 || overview.bins() == 0 {
+        return Ok(());
+    }
+    let contig_name = &state.contig_header.try_get(overview.contig_index)?.name;
+    let histogram_height = (area.height - TEXT_ROWS) / 2;
+
+    buf.set_stringn(
+        area.x,
+        area.y,
+        format!(
+            "{}: {} bp in {} bins. h / l: select, Enter: zoom in, Esc: exit",
+            contig_name,
+            to_thousand_separated(overview.contig_length),
+            overview.bins()
+        ),
+        area.width as usize,
+        Style::default(),
+    );
+
+    let gene_counts = overview
+        .gene_counts
+        .iter()
+        .map(|count| *count as u64)
+        .collect::<Vec<_>>();
+    let genes_y = area.y + 1;
+    buf.set_string(
+        area.x,
+        genes_y,
+        format!(
+            "Genes (up to {} per bin)",
+            gene_counts.iter().max().unwrap_or(&0)
+        ),
+        Style::default(),
+    );
+    render_histogram(
+        &Rect::new(area.x, genes_y + 1, area.width, histogram_height),
+        buf,
+        &gene_counts,
+        palette.GENE_BACKGROUND_COLOR,
+        palette,
+    );
+
+    let coverage_y = genes_y + 1 + histogram_height;
+    if overview.coverage.is_empty() {
+        buf.set_string(
+            area.x,
+            coverage_y,
+            "Coverage (not available without a BAM index)",
+            Style::default(),
+        );
+    } else {
+        buf.set_string(area.x, coverage_y, "Coverage", Style::default());
+        render_histogram(
+            &Rect::new(area.x, coverage_y + 1, area.width, histogram_height),
+            buf,
+            &overview.coverage,
+            palette.COVERAGE_TOTAL,
+            palette,
+        );
+    }
+
+    // Highlight the selected bin
+    let cursor = registers.contig_overview_cursor;
+    let cursor_x = area.x + cursor as u16;
+    for y in genes_y + 1..coverage_y + 1 + histogram_height {
+        if y == coverage_y {
+            continue;
+        }
+        if let Some(cell) = buf.cell_mut(Position::new(cursor_x, y)) {
+            cell.set_bg(palette.HIGHLIGHT_COLOR);
+        }
+    }
+
+    let (start, end) = overview.bin_range(cursor);
+    let mut description = format!(
+        "{}:{}-{}  {} genes",
+        contig_name,
+        to_thousand_separated(start),
+        to_thousand_separated(end),
+        overview.gene_counts[cursor]
+    );
+    if let (Some(coverage), Some(max_coverage)) = (
+        overview.coverage.get(cursor),
+        overview.coverage.iter().max().filter(|max| **max > 0),
+    ) {
+        description += &format!("  coverage {}% of max", coverage * 100 / max_coverage);
+    }
+    buf.set_stringn(
+        area.x,
+        coverage_y + 1 + histogram_height,
+        description,
+        area.width as usize,
+        Style::default(),
+    );
+
+    Ok(())
+}
+
+/// Bars scaled to the maximum value, growing up from the bottom of the area.
+fn render_histogram(
+    area: &Rect,
+    buf: &mut Buffer,
+    values: &[u64],
+    color: Color,
+    palette: &Palette,
+) {
+    let Some(max_value) = values.iter().copied().max().filter(|max| *max > 0) else {
+        return;
+    };
+    for (x, value) in values.iter().take(area.width as usize).enumerate() {
+        let height = *value as f64 / max_value as f64 * area.height as f64;
+        for row in 0..area.height {
+            let fraction = height - row as f64;
+            if fraction <= 0.0 {
+                break;
+            }
+            buf.set_string(
+                area.x + x as u16,
+                area.bottom() - 1 - row,
+                palette.glyphs.bar(fraction),
+                Style::default().fg(color),
+            );
+        }
+    }
+}
@@ -1,7 +1,6 @@
 use crate::layout::{AlignmentView, OnScreenCoordinate};
 use gv_core::{error::TGVError, state::State};
 
-use itertools::izip;
 use ratatui::{buffer::Buffer, layout::Rect, style::Style};
 
 const MIN_AREA_WIDTH: u16 = 2;
@@ -18,120 +17,153 @@ pub fn render_coordinates(
     }
 
     let contig_length = state.contig_length(&alignment_view.focus)?;
+    let left = alignment_view.left(area);
+    let right = match contig_length {
+        Some(length) => u64::min(alignment_view.right(area), length),
+        None => alignment_view.right(area),
+    };
+    // Ticks sit on round displayed coordinates, which are one less than the positions in 0-based mode.
+    let base = state.coordinate_base;
+    let ruler = Ruler::new(
+        base.position(left),
+        base.position(right),
+        alignment_view.zoom,
+        state.ruler_interval,
+    );
 
-    let (coordinate_texts, coordinate_texts_xs, markers_onscreen_x) =
-        calculate_coordinates(alignment_view, area, contig_length);
+    let onscreen_x =
+        |tick: u64| match alignment_view.onscreen_x_coordinate(base.from_displayed(tick), area) {
+            OnScreenCoordinate::OnScreen(x) if x < area.width as usize => Some(x as u16),
+            _ => None,
+        };
 
-    for (text, text_x, marker_x) in izip!(
-        coordinate_texts.iter(),
-        coordinate_texts_xs.iter(),
-        markers_onscreen_x.iter()
-    ) {
+    if area.height >= 2 {
+        for x in ruler.minor.iter().filter_map(|tick| onscreen_x(*tick)) {
+            buf.set_string(area.x + x, area.y + 1, MINOR_TICK, Style::default());
+        }
+    }
+
+    // Labels are centered on the major ticks and are skipped if they would overlap the previous label.
+    let mut label_end = 0;
+    for (tick, x) in ruler
+        .major
+        .iter()
+        .filter_map(|tick| onscreen_x(*tick).map(|x| (*tick, x)))
+    {
+        if area.height >= 2 {
+            buf.set_string(area.x + x, area.y + 1, MAJOR_TICK, Style::default());
+        }
+
+        let label = to_thousand_separated(tick);
+        let label_start = x.saturating_sub(label.len() as u16 / 2);
+        if label_start < label_end {
+            continue;
+        }
+        // Labels near the left edge are clipped from the left so that they stay centered.
+        let clipped = (label.len() / 2).saturating_sub(x as usize);
         buf.set_stringn(
-            area.x + *text_x,
+            area.x + label_start,
             area.y,
-            text,
-            area.width as usize - *text_x as usize,
+            &label[clipped..],
+            (area.width - label_start) as usize,
+            Style::default(),
+        );
+        label_end = label_start + (label.len() - clipped) as u16 + MIN_LABEL_GAP;
+    }
+
+    // Under :revcomp, the displayed bases are the minus strand, which reads 3' to 5' from left to right.
+    if state.revcomp && area.height >= 2 && area.width >= 2 * REVCOMP_END_LABEL_WIDTH {
+        buf.set_string(area.x, area.y + 1, "3'", Style::default());
+        buf.set_string(
+            area.right() - REVCOMP_END_LABEL_WIDTH,
+            area.y + 1,
+            "5'",
             Style::default(),
         );
-        if area.height >= 2 {
-            buf.set_stringn(
-                area.x + *marker_x,
-                area.y + 1,
-                "|",
-                area.width as usize - *marker_x as usize,
-                Style::default(),
-            );
-        }
     }
 
     Ok(())
 }
 
-const MIN_SPACING_BETWEEN_MARKERS: u16 = 15;
+/// Width of the 3' and 5' labels at the ends of the marker row under `:revcomp`.
+const REVCOMP_END_LABEL_WIDTH: u16 = 2;
 
-/// Calculate coordinate markers.
-/// left and right are 1-based, inclusive.
-fn calculate_coordinates(
-    alignment_view: &AlignmentView,
-    area: &Rect,
-    contig_length: Option<u64>,
-) -> (Vec<String>, Vec<u16>, Vec<u16>) {
-    let (intermarker_distance, power) = calculate_intermarker_distance(alignment_view.zoom);
+const MAJOR_TICK: &str = "|";
+const MINOR_TICK: &str = "'";
 
-    let mut pivot = (alignment_view.left(area) / intermarker_distance + 1) * intermarker_distance; // First marker
-    let mut markers_onscreen_x: Vec<u16> = Vec::new();
-    let mut coordinate_texts: Vec<String> = Vec::new();
-    let mut coordinate_texts_xs: Vec<u16> = Vec::new();
+/// Minimum number of blank columns between coordinate labels.
+const MIN_LABEL_GAP: u16 = 4;
 
-    let render_bound = match contig_length {
-        Some(length) => u64::min(alignment_view.right(area), length),
-        None => alignment_view.right(area),
-    };
+/// Minimum number of columns between automatically spaced major ticks.
+const MIN_MAJOR_TICK_SPACING: u64 = 10;
 
-    while pivot < render_bound {
-        let marker_text = get_abbreviated_coordinate_text(pivot, power);
-
-        let onscreen_marker_coordinate = alignment_view.onscreen_x_coordinate(pivot, area);
-
-        match onscreen_marker_coordinate {
-            OnScreenCoordinate::OnScreen(x) => {
-                if x >= marker_text.len() / 2 {
-                    markers_onscreen_x.push(x as u16);
-                    coordinate_texts_xs.push((x - marker_text.len() / 2) as u16);
-                    coordinate_texts.push(marker_text);
-                } else {
-                    markers_onscreen_x.push(x as u16);
-                    coordinate_texts_xs.push(0_u16);
-                    coordinate_texts.push(marker_text[(marker_text.len() / 2 - x)..].to_string());
-                }
-            }
-            _ => {
-                continue;
-            }
-        }
+/// Minimum number of columns between minor ticks. Minor ticks are not drawn if they would be denser.
+const MIN_MINOR_TICK_SPACING: u64 = 2;
 
-        pivot += intermarker_distance;
-    }
+/// Ticks of the coordinate ruler over [left, right], at multiples of the tick intervals.
+/// 1-based, inclusive.
+#[derive(Debug, PartialEq)]
+struct Ruler {
+    major: Vec<u64>,
 
-    (coordinate_texts, coordinate_texts_xs, markers_onscreen_x)
+    /// Minor ticks between major ticks. Positions of major ticks are excluded.
+    minor: Vec<u64>,
 }
 
-fn calculate_intermarker_distance(zoom: u64) -> (u64, u64) {
-    let mut distance = zoom * MIN_SPACING_BETWEEN_MARKERS as u64;
+impl Ruler {
+    /// Ruler with the major tick interval, or an automatic one if None.
+    /// An interval narrower than one column is replaced by the automatic interval.
+    fn new(left: u64, right: u64, zoom: u64, interval: Option<u64>) -> Self {
+        let major_interval = match interval {
+            Some(interval) if interval >= zoom => interval,
+            _ => auto_major_interval(zoom, to_thousand_separated(right).len() as u64),
+        };
 
-    // Find the smallest number among [1,2,10,20,50,100, ....] that is greater than or equal to min_distance
-    let mut power: usize = 1;
-    while distance > 10 {
-        distance /= 10;
-        power += 1;
-    }
+        let major = ticks(left, right, major_interval);
+        let minor = match minor_interval(major_interval) {
+            Some(minor_interval) if minor_interval >= zoom * MIN_MINOR_TICK_SPACING => {
+                ticks(left, right, minor_interval)
+                    .into_iter()
+                    .filter(|tick| tick % major_interval != 0)
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
 
-    if distance < 2 {
-        (2 * 10u64.pow(power as u32 - 1), power as u64 - 1)
-    } else {
-        (10u64.pow(power as u32), power as u64)
+        Self { major, minor }
     }
 }
 
-fn get_abbreviated_coordinate_text(coordinate: u64, power: u64) -> String {
-    if power < 3 {
-        format!("{}bp", to_thousand_separated(coordinate))
-    } else if power < 6 {
-        format!("{}kb", to_thousand_separated(coordinate / 1_000))
-    } else if power < 9 {
-        format!("{}Mb", to_thousand_separated(coordinate / 1_000_000))
-    } else if power < 12 {
-        format!("{}Gb", to_thousand_separated(coordinate / 1_000_000_000))
-    } else {
-        format!(
-            "{}Tb",
-            to_thousand_separated(coordinate / 1_000_000_000_000)
-        )
+/// Smallest interval of 1, 2, or 5 times a power of ten that leaves room for labels of label_width characters.
+fn auto_major_interval(zoom: u64, label_width: u64) -> u64 {
+    let min_interval = zoom * u64::max(label_width + MIN_LABEL_GAP as u64, MIN_MAJOR_TICK_SPACING);
+    let mut power = 1;
+    loop {
+        for mantissa in [1, 2, 5] {
+            if mantissa * power >= min_interval {
+                return mantissa * power;
+            }
+        }
+        power *= 10;
     }
 }
 
-fn to_thousand_separated(number: u64) -> String {
+/// Interval of minor ticks: a fifth, quarter, or half of the major interval. None if the major interval does not
+/// divide evenly.
+fn minor_interval(major_interval: u64) -> Option<u64> {
+    [5, 4, 2]
+        .into_iter()
+        .find(|divisor| major_interval.is_multiple_of(*divisor))
+        .map(|divisor| major_interval / divisor)
+}
+
+/// Multiples of interval in [left, right].
+fn ticks(left: u64, right: u64, interval: u64) -> Vec<u64> {
+    let first = left.div_ceil(interval).max(1) * interval;
+    (first..=right).step_by(interval as usize).collect()
+}
+
+pub(crate) fn to_thousand_separated(number: u64) -> String {
     if number < 1000 {
         return format!("{}", number);
     }
@@ -152,3 +184,86 @@ fn to_thousand_separated(number: u64) -> String {
 
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(1, 4, 10)]
+    #[case(1, 6, 10)]
+    #[case(1, 7, 20)]
+    #[case(3, 7, 50)]
+    #[case(10, 11, 200)]
+    #[case(1000, 11, 20_000)]
+    fn test_auto_major_interval(
+        #[case] zoom: u64,
+        #[case] label_width: u64,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(auto_major_interval(zoom, label_width), expected);
+    }
+
+    #[rstest]
+    #[case(10, Some(2))]
+    #[case(20, Some(4))]
+    #[case(50, Some(10))]
+    #[case(10_000, Some(2_000))]
+    #[case(12, Some(3))]
+    #[case(7, None)]
+    fn test_minor_interval(#[case] major_interval: u64, #[case] expected: Option<u64>) {
+        assert_eq!(minor_interval(major_interval), expected);
+    }
+
+    #[rstest]
+    #[case(1, 35, 10, vec![10, 20, 30])]
+    #[case(10, 30, 10, vec![10, 20, 30])]
+    #[case(11, 29, 10, vec![20])]
+    #[case(12_345, 56_789, 10_000, vec![20_000, 30_000, 40_000, 50_000])]
+    #[case(1, 9, 10, vec![])]
+    fn test_ticks(
+        #[case] left: u64,
+        #[case] right: u64,
+        #[case] interval: u64,
+        #[case] expected: Vec<u64>,
+    ) {
+        assert_eq!(ticks(left, right, interval), expected);
+    }
+
+    #[rstest]
+    // 80 bp at 1x: ticks every 10 bp, minor ticks every 2 bp.
+    #[case(1, 80, 1, None, vec![10, 20, 30, 40, 50, 60, 70, 80], vec![2, 4, 6, 8])]
+    // 8 kb at 100x: ticks every 1 kb, minor ticks every 200 bp.
+    #[case(1_001, 9_000, 100, None, vec![2_000, 3_000, 4_000, 5_000, 6_000, 7_000, 8_000, 9_000], vec![1_200, 1_400, 1_600, 1_800])]
+    // Ticks align to round numbers, not to the window edge.
+    #[case(123_457, 131_456, 100, None, vec![124_000, 126_000, 128_000, 130_000], vec![123_600, 124_400])]
+    #[case(123_457, 131_456, 100, Some(5_000), vec![125_000, 130_000], vec![124_000, 126_000, 127_000, 128_000, 129_000, 131_000])]
+    // User intervals narrower than a column fall back to auto.
+    #[case(1, 800, 10, Some(5), vec![100, 200, 300, 400, 500, 600, 700, 800], vec![20, 40, 60, 80])]
+    fn test_ruler(
+        #[case] left: u64,
+        #[case] right: u64,
+        #[case] zoom: u64,
+        #[case] interval: Option<u64>,
+        #[case] major: Vec<u64>,
+        #[case] minor_prefix: Vec<u64>,
+    ) {
+        let ruler = Ruler::new(left, right, zoom, interval);
+        assert_eq!(ruler.major, major);
+        assert_eq!(ruler.minor[..minor_prefix.len()], minor_prefix);
+        assert!(ruler.minor.iter().all(|tick| !ruler.major.contains(tick)));
+    }
+
+    #[rstest]
+    #[case(0, "0")]
+    #[case(999, "999")]
+    #[case(1_000, "1,000")]
+    #[case(12_345, "12,345")]
+    #[case(1_000_001, "1,000,001")]
+    #[case(1_234_567, "1,234,567")]
+    #[case(248_956_422, "248,956,422")]
+    fn test_to_thousand_separated(#[case] number: u64, #[case] expected: &str) {
+        assert_eq!(to_thousand_separated(number), expected);
+    }
+}
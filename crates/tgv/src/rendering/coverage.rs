@@ -14,16 +14,22 @@ use gv_core::{
     error::TGVError,
 };
 
-use crate::{layout::AlignmentView, rendering::Palette};
+use crate::{
+    layout::AlignmentView,
+    rendering::{GlyphSet, Palette, glyphs::braille_bars},
+};
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
 /// Render the coverage barplot.
+/// The y-axis maximum is coverage_max if set; otherwise, it scales to the deepest coverage in view.
+/// Columns deeper than the maximum are clipped and their top row is marked.
 pub fn render_coverage(
     area: &Rect,
     buf: &mut Buffer,
     alignment: &Alignment,
     alignment_view: &AlignmentView,
+    coverage_max: Option<usize>,
     palette: &Palette,
 ) -> Result<(), TGVError> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
@@ -40,20 +46,39 @@ pub fn render_coverage(
         return Ok(());
     };
 
-    let mut binned_coverage =
-        calculate_binned_coverage(alignment, left, right, plot_area.width as usize)?;
-
-    let y_max: usize = round_up_max_coverage(
-        (0..binned_coverage[0].len())
-            .map(|i| binned_coverage[0][i] + binned_coverage[1][i])
-            .max()
-            .unwrap_or(0),
-    );
-    StackedSparkline::default()
-        .add_data(binned_coverage.remove(0), palette.COVERAGE_ALT)
-        .add_data(binned_coverage.remove(0), palette.COVERAGE_TOTAL)
-        .max(y_max)
-        .render(plot_area, buf);
+    let y_max = if palette.glyphs == GlyphSet::Braille {
+        render_braille_coverage(
+            &plot_area,
+            buf,
+            alignment,
+            left,
+            right,
+            coverage_max,
+            palette,
+        )?
+    } else {
+        let mut binned_coverage =
+            calculate_binned_coverage(alignment, left, right, plot_area.width as usize)?;
+
+        let y_max = coverage_y_max(&binned_coverage, coverage_max);
+        let clipped = clip_binned_coverage(&mut binned_coverage, y_max);
+        binned_coverage
+            .into_iter()
+            .zip(stack_colors(palette, alignment.complement_bases))
+            .fold(StackedSparkline::default(), |sparkline, (data, color)| {
+                sparkline.add_data(data, color)
+            })
+            .max(y_max)
+            .bar_set(palette.glyphs.bar_set())
+            .render(plot_area, buf);
+
+        for (x, _) in clipped.iter().enumerate().filter(|(_, clipped)| **clipped) {
+            buf[(plot_area.x + x as u16, plot_area.top())]
+                .set_symbol(palette.glyphs.bar_set().full)
+                .set_style(Style::default().fg(palette.COVERAGE_CLIPPED));
+        }
+        y_max
+    };
 
     if area.height > MIN_AREA_HEIGHT {
         buf.set_string(area.x, area.y, format!("[0-{}]", y_max,), Style::default());
@@ -62,6 +87,147 @@ pub fn render_coverage(
     Ok(())
 }
 
+/// Render coverage with braille patterns: two columns and four rows of dots per cell.
+/// Each dot column is a bin when the region is wide enough; otherwise, each cell's coverage is drawn in both columns.
+/// Returns the y-axis maximum.
+fn render_braille_coverage(
+    area: &Rect,
+    buf: &mut Buffer,
+    alignment: &Alignment,
+    left: u64,
+    right: u64,
+    coverage_max: Option<usize>,
+    palette: &Palette,
+) -> Result<usize, TGVError> {
+    let n_columns = area.width as usize * 2;
+    let binned_coverage = if right - left + 1 >= n_columns as u64 {
+        calculate_binned_coverage(alignment, left, right, n_columns)?
+    } else {
+        calculate_binned_coverage(alignment, left, right, area.width as usize)?
+            .into_iter()
+            .map(|stack| stack.into_iter().flat_map(|depth| [depth, depth]).collect())
+            .collect()
+    };
+
+    let y_max = coverage_y_max(&binned_coverage, coverage_max);
+    let mut clipped_coverage = binned_coverage.clone();
+    let clipped = clip_binned_coverage(&mut clipped_coverage, y_max);
+
+    let n_dots = area.height as usize * GlyphSet::BRAILLE_CELL_HEIGHT;
+    let dots: Vec<usize> = bin_totals(&clipped_coverage)
+        .into_iter()
+        .map(|total| total * n_dots / y_max)
+        .collect();
+
+    // A braille cell has one color, so mixed positions are drawn in the alt color.
+    for x in 0..area.width as usize {
+        let has_alt = binned_coverage[..ALLELE_STACK_BASES.len()]
+            .iter()
+            .any(|stack| stack[2 * x] > 0 || stack[2 * x + 1] > 0);
+        let color = if has_alt {
+            palette.COVERAGE_ALT
+        } else {
+            palette.COVERAGE_TOTAL
+        };
+        let top_color = if clipped[2 * x] || clipped[2 * x + 1] {
+            palette.COVERAGE_CLIPPED
+        } else {
+            color
+        };
+
+        for j in 0..area.height as usize {
+            // j = 0 is the bottom row
+            let floor = j * GlyphSet::BRAILLE_CELL_HEIGHT;
+            let (left_dots, right_dots) = (
+                dots[2 * x].saturating_sub(floor),
+                dots[2 * x + 1].saturating_sub(floor),
+            );
+            if left_dots == 0 && right_dots == 0 {
+                break;
+            }
+
+            let color = if j + 1 == area.height as usize {
+                top_color
+            } else {
+                color
+            };
+            buf[(area.x + x as u16, area.bottom() - 1 - j as u16)]
+                .set_char(braille_bars(left_dots, right_dots))
+                .set_style(Style::default().fg(color));
+        }
+    }
+
+    Ok(y_max)
+}
+
+/// Bases of the per-base stacks, which split the coverage bar at mixed positions.
+/// Stacks from bottom to top: A, C, G, T, N, and the depth of the other positions.
+const ALLELE_STACK_BASES: [u8; 5] = [b'A', b'C', b'G', b'T', b'N'];
+
+/// Colors of the coverage stacks, from bottom to top. Complemented bases take the color of their complement, as the
+/// reads do under `:revcomp`.
+fn stack_colors(
+    palette: &Palette,
+    complement_bases: bool,
+) -> [Color; ALLELE_STACK_BASES.len() + 1] {
+    let (a, c, g, t) = if complement_bases {
+        (
+            palette.COVERAGE_T,
+            palette.COVERAGE_G,
+            palette.COVERAGE_C,
+            palette.COVERAGE_A,
+        )
+    } else {
+        (
+            palette.COVERAGE_A,
+            palette.COVERAGE_C,
+            palette.COVERAGE_G,
+            palette.COVERAGE_T,
+        )
+    };
+    [a, c, g, t, palette.COVERAGE_N, palette.COVERAGE_TOTAL]
+}
+
+/// Depth of each base in the per-base stacks.
+fn allele_stack_depths(coverage: &BaseCoverage) -> [usize; ALLELE_STACK_BASES.len()] {
+    [coverage.A, coverage.C, coverage.G, coverage.T, coverage.N]
+}
+
+/// Total depth of each bin across stacks.
+fn bin_totals(binned_coverage: &[Vec<usize>]) -> Vec<usize> {
+    (0..binned_coverage[0].len())
+        .map(|i| binned_coverage.iter().map(|stack| stack[i]).sum())
+        .collect()
+}
+
+/// Y-axis maximum of the coverage plot: coverage_max if set; otherwise, the rounded-up deepest bin.
+fn coverage_y_max(binned_coverage: &[Vec<usize>], coverage_max: Option<usize>) -> usize {
+    coverage_max.unwrap_or_else(|| {
+        round_up_max_coverage(bin_totals(binned_coverage).into_iter().max().unwrap_or(0))
+    })
+}
+
+/// Clip bins deeper than y_max to y_max, trimming stacks from the top.
+/// Returns whether each bin was clipped.
+fn clip_binned_coverage(binned_coverage: &mut [Vec<usize>], y_max: usize) -> Vec<bool> {
+    bin_totals(binned_coverage)
+        .into_iter()
+        .enumerate()
+        .map(|(i, total)| {
+            if total <= y_max {
+                return false;
+            }
+            let mut excess = total - y_max;
+            for stack in binned_coverage.iter_mut().rev() {
+                let trimmed = usize::min(stack[i], excess);
+                stack[i] -= trimmed;
+                excess -= trimmed;
+            }
+            true
+        })
+        .collect()
+}
+
 fn displayed_coverage_bounds(alignment_view: &AlignmentView, area: &Rect) -> Option<(u64, u64)> {
     let (left, _) = alignment_view.coordinates_of_onscreen_x(area.left(), area)?;
     let (_, right) =
@@ -142,24 +308,20 @@ fn calculate_binned_coverage(
         return Err(TGVError::ValueError("n_bins is 0".to_string()));
     }
 
+    let rest = ALLELE_STACK_BASES.len();
     if right - left + 1 == n_bins as u64 {
         // 1x zoom. Not need to calulate binned coverage.
-
-        // Stack 0: alt allele if above a threshold
-        // Stack 1: non-alt alleles
-        let mut output = vec![vec![0; n_bins]; 2];
+        // Mixed positions are split into the per-base stacks. Other positions are in the last stack.
+        let mut output = vec![vec![0; n_bins]; rest + 1];
         (left..right + 1).enumerate().for_each(|(i, x)| {
             let coverage = alignment.coverage_at(x);
-            let max_alt_depth = coverage.max_alt_depth().unwrap_or(0);
-
-            if max_alt_depth * BaseCoverage::MAX_DISPLAY_ALLELE_FREQUENCY_RECIPROCOL
-                > coverage.total
-            {
-                output[0][i] = max_alt_depth;
-                output[1][i] = coverage.total - max_alt_depth;
+            if coverage.is_mixed(BaseCoverage::DEFAULT_ALLELE_FRACTION_THRESHOLD) {
+                allele_stack_depths(coverage)
+                    .into_iter()
+                    .enumerate()
+                    .for_each(|(i_stack, depth)| output[i_stack][i] = depth);
             } else {
-                output[0][i] = 0;
-                output[1][i] = coverage.total;
+                output[rest][i] = coverage.total;
             }
         });
         return Ok(output);
@@ -167,12 +329,13 @@ fn calculate_binned_coverage(
 
     let linear_space = get_linear_space(left, right, n_bins)?;
 
-    let mut output = vec![vec![0; linear_space.len()]; 2];
+    let mut output = vec![vec![0; linear_space.len()]; rest + 1];
     linear_space
         .into_iter()
         .enumerate()
         .for_each(|(i, (bin_left, bin_right))| {
-            (bin_left..bin_right + 1).for_each(|x| output[1][i] += alignment.coverage_at(x).total);
+            (bin_left..bin_right + 1)
+                .for_each(|x| output[rest][i] += alignment.coverage_at(x).total);
         });
 
     Ok(output)
@@ -209,6 +372,11 @@ impl StackedSparkline {
         self
     }
 
+    pub fn bar_set(mut self, bar_set: Set) -> Self {
+        self.bar_set = bar_set;
+        self
+    }
+
     const fn symbol_for_height(&self, height: usize) -> &str {
         match height {
             0 => self.bar_set.empty,
@@ -373,6 +541,51 @@ mod tests {
         assert_eq!(round_up_max_coverage(input), expected);
     }
 
+    #[rstest]
+    #[case(vec![vec![0, 0], vec![99, 100]], 100, vec![vec![0, 0], vec![99, 100]], vec![false, false])]
+    #[case(vec![vec![0, 0], vec![100, 101]], 100, vec![vec![0, 0], vec![100, 100]], vec![false, true])]
+    #[case(vec![vec![60, 0], vec![60, 0]], 100, vec![vec![60, 0], vec![40, 0]], vec![true, false])]
+    #[case(vec![vec![150], vec![10]], 100, vec![vec![100], vec![0]], vec![true])]
+    fn test_clip_binned_coverage(
+        #[case] binned_coverage: Vec<Vec<usize>>,
+        #[case] y_max: usize,
+        #[case] expected_coverage: Vec<Vec<usize>>,
+        #[case] expected_clipped: Vec<bool>,
+    ) {
+        let mut binned_coverage = binned_coverage;
+        let clipped = clip_binned_coverage(&mut binned_coverage, y_max);
+        assert_eq!(binned_coverage, expected_coverage);
+        assert_eq!(clipped, expected_clipped);
+    }
+
+    #[test]
+    fn test_allele_stacks_of_a_het() {
+        let mut coverage = BaseCoverage::new(b'C');
+        b"CCGG".iter().for_each(|base| coverage.update(*base));
+        assert_eq!(allele_stack_depths(&coverage), [0, 2, 2, 0, 0]);
+
+        // A 50/50 het fills the C and G stacks and is clipped evenly from the top.
+        let mut binned_coverage = allele_stack_depths(&coverage)
+            .into_iter()
+            .map(|depth| vec![depth])
+            .chain(std::iter::once(vec![0]))
+            .collect::<Vec<_>>();
+        assert_eq!(clip_binned_coverage(&mut binned_coverage, 3), vec![true]);
+        assert_eq!(bin_totals(&binned_coverage), vec![3]);
+        assert_eq!(binned_coverage[1..3], [vec![2], vec![1]]);
+    }
+
+    #[rstest]
+    #[case(None, 110)]
+    #[case(Some(50), 50)]
+    #[case(Some(1000), 1000)]
+    fn test_coverage_y_max(#[case] coverage_max: Option<usize>, #[case] expected: usize) {
+        assert_eq!(
+            coverage_y_max(&[vec![10, 0], vec![91, 20]], coverage_max),
+            expected
+        );
+    }
+
     #[rstest]
     #[case(1, 5, 0, Err(TGVError::ValueError("n_bins is 0".to_string())))]
     #[case(1, 5, 5, Ok(vec![(1,1), (2,2), (3,3), (4,4), (5,5)]))]
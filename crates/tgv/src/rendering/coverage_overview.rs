@@ -0,0 +1,77 @@
+use crate::{
+    layout::{AlignmentView, linear_scale},
+    rendering::{colors::Palette, cytoband::contig_overview_x_range},
+};
+use gv_core::{error::TGVError, state::State};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Style,
+};
+
+const MIN_AREA_WIDTH: u16 = 20;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render coverage density across the whole contig, aligned with the cytoband. The current view window is highlighted.
+pub fn render_coverage_overview(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width <= MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let (Some(overview), Some(contig_length)) = (
+        state.coverage_overview.as_ref(),
+        state.contig_length(&alignment_view.focus)?,
+    ) else {
+        return Ok(());
+    };
+    if overview.contig_index != alignment_view.focus.contig_index || contig_length == 0 {
+        return Ok(());
+    }
+
+    let (start_x, end_x) = contig_overview_x_range(area, state);
+    if end_x <= start_x {
+        return Ok(());
+    }
+    let width = (end_x - start_x) as u64;
+
+    buf.set_string(area.x, area.y, "Coverage", Style::default());
+
+    let densities = (0..width)
+        .map(|x| {
+            overview.density_in(
+                x * contig_length / width + 1,
+                (x + 1) * contig_length / width,
+            )
+        })
+        .collect::<Vec<u64>>();
+    let max_density = densities.iter().copied().max().unwrap_or(0);
+    if max_density > 0 {
+        for (x, density) in densities.iter().enumerate() {
+            buf.set_string(
+                area.x + start_x + x as u16,
+                area.y,
+                palette.glyphs.bar(*density as f64 / max_density as f64),
+                Style::default().fg(palette.COVERAGE_TOTAL),
+            );
+        }
+    }
+
+    // Highlight the current viewing window
+    let viewing_window_start =
+        linear_scale(alignment_view.left(area), contig_length, start_x, end_x)?;
+    let viewing_window_end =
+        linear_scale(alignment_view.right(area), contig_length, start_x, end_x)?;
+    for x in viewing_window_start..viewing_window_end + 1 {
+        if let Some(cell) = buf.cell_mut(Position::new(area.x + x, area.y)) {
+            cell.set_bg(palette.HIGHLIGHT_COLOR);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::{
+    layout::{AlignmentView, OnScreenCoordinate},
+    rendering::colors::Palette,
+};
+use gv_core::{
+    error::TGVError,
+    intervals::GenomeInterval,
+    sequence::{CPG_ISLAND_WINDOW, Sequence, cpg_islands},
+};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+/// Render CpG island candidates in the loaded sequence as bars. Bases within a window of the view are scanned, so
+/// that islands crossing the view edges are found.
+pub fn render_cpg_islands(
+    area: &Rect,
+    buf: &mut Buffer,
+    sequence: &Sequence,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if sequence.contig_index != alignment_view.focus.contig_index || sequence.len() == 0 {
+        return Ok(());
+    }
+
+    let region = alignment_view.region(area);
+    let start = u64::max(
+        region.start().saturating_sub(CPG_ISLAND_WINDOW as u64),
+        sequence.start,
+    );
+    let end = u64::min(region.end() + CPG_ISLAND_WINDOW as u64, sequence.end());
+    if start > end {
+        return Ok(());
+    }
+
+    let bases =
+        &sequence.sequence[(start - sequence.start) as usize..=(end - sequence.start) as usize];
+    let style = Style::default().fg(palette.CPG_ISLAND_COLOR);
+    for island in cpg_islands(bases, CPG_ISLAND_WINDOW) {
+        let onscreen_start =
+            alignment_view.onscreen_x_coordinate(start + island.start as u64, area);
+        let onscreen_end =
+            alignment_view.onscreen_x_coordinate(start + island.end as u64 - 1, area);
+        let Some((x, length)) =
+            OnScreenCoordinate::onscreen_start_and_length(&onscreen_start, &onscreen_end, area)
+        else {
+            continue;
+        };
+        buf.set_string(
+            area.x + x,
+            area.y,
+            palette.glyphs.block().repeat(length as usize),
+            style,
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::DARK_THEME;
+    use gv_core::intervals::Focus;
+
+    #[test]
+    fn islands_are_drawn_as_bars() {
+        // A 400-bp CpG island between 1-kb AT-rich flanks.
+        let sequence = Sequence {
+            start: 1,
+            sequence: [b"AT".repeat(500), b"CG".repeat(200), b"AT".repeat(500)].concat(),
+            contig_index: 0,
+        };
+        let area = Rect::new(0, 0, 60, 1);
+        let mut alignment_view = AlignmentView::new(
+            Focus {
+                contig_index: 0,
+                position: 1200,
+            },
+            0,
+        );
+        alignment_view.zoom = 40;
+        let mut buf = Buffer::empty(area);
+        render_cpg_islands(&area, &mut buf, &sequence, &alignment_view, &DARK_THEME).unwrap();
+
+        let drawn = (0..area.width)
+            .filter(|x| buf[(*x, 0)].symbol() == DARK_THEME.glyphs.block())
+            .collect::<Vec<_>>();
+        assert!(!drawn.is_empty());
+        // The island covers [1001, 1400] and extends into the flanks by less than a window.
+        let (left, _) = alignment_view
+            .coordinates_of_onscreen_x(area.x + drawn[0], &area)
+            .unwrap();
+        let (_, right) = alignment_view
+            .coordinates_of_onscreen_x(area.x + drawn[drawn.len() - 1], &area)
+            .unwrap();
+        assert!((800..=1001).contains(&left), "{left}");
+        assert!((1400..=1600).contains(&right), "{right}");
+    }
+}
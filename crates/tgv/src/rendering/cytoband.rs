@@ -38,10 +38,7 @@ pub fn render_cytobands(
     let reference_description = state.reference.to_string();
     let contig_description = state.contig_name(&alignment_view.focus)?;
 
-    let cytoband_left_spacing = u16::max(
-        CYTOBAND_TEXT_MIN_LEFT_SPACING,
-        reference_description.len() as u16 + 1,
-    );
+    let (cytoband_left_spacing, cytoband_right) = contig_overview_x_range(area, state);
 
     // Left labels
     buf.set_string(area.x, area.y, reference_description, Style::default());
@@ -63,7 +60,7 @@ pub fn render_cytobands(
         for (x, string, style) in get_cytoband_xs_strings_and_styles(
             cytoband,
             cytoband_left_spacing,
-            area.width - CYTOBAND_TEXT_RIGHT_SPACING,
+            cytoband_right,
             pallete,
         )? {
             buf.set_string(area.x + x, area.y, string, style);
@@ -72,7 +69,9 @@ pub fn render_cytobands(
         buf.set_string(
             area.x + cytoband_left_spacing,
             area.y,
-            "▅".repeat((area.width - cytoband_left_spacing - CYTOBAND_TEXT_RIGHT_SPACING) as usize),
+            pallete.glyphs.block().repeat(
+                (area.width - cytoband_left_spacing - CYTOBAND_TEXT_RIGHT_SPACING) as usize,
+            ),
             Style::default(),
         );
     }
@@ -83,13 +82,13 @@ pub fn render_cytobands(
             alignment_view.left(area),
             contig_length,
             cytoband_left_spacing,
-            area.width - CYTOBAND_TEXT_RIGHT_SPACING,
+            cytoband_right,
         )?;
         let viewing_window_end = linear_scale(
             alignment_view.right(area),
             contig_length,
             cytoband_left_spacing,
-            area.width - CYTOBAND_TEXT_RIGHT_SPACING,
+            cytoband_right,
         )?;
 
         for x in viewing_window_start..viewing_window_end + 1 {
@@ -104,7 +103,20 @@ pub fn render_cytobands(
     Ok(())
 }
 
-fn get_cytoband_xs_strings_and_styles(
+/// Onscreen x range [start, end) of whole-contig bars, relative to area.x. Shared by the cytoband and the coverage
+/// overview so that their viewing windows line up.
+pub fn contig_overview_x_range(area: &Rect, state: &State) -> (u16, u16) {
+    let left_spacing = u16::max(
+        CYTOBAND_TEXT_MIN_LEFT_SPACING,
+        state.reference.to_string().len() as u16 + 1,
+    );
+    (
+        left_spacing,
+        area.width.saturating_sub(CYTOBAND_TEXT_RIGHT_SPACING),
+    )
+}
+
+pub(super) fn get_cytoband_xs_strings_and_styles(
     cytoband: &Cytoband,
     area_start: u16,
     area_end: u16,
@@ -160,7 +172,10 @@ fn get_cytoband_segment_x_string_and_style(
             Ok(Some((onscreen_x_start, string, style)))
         }
         _ => {
-            let string = "▅".repeat((onscreen_x_end - onscreen_x_start) as usize);
+            let string = palette
+                .glyphs
+                .block()
+                .repeat((onscreen_x_end - onscreen_x_start) as usize);
             Ok(Some((onscreen_x_start, string, style)))
         }
     }
@@ -0,0 +1,57 @@
+use crate::{
+    layout::{AlignmentView, OnScreenCoordinate},
+    rendering::{colors::Palette, get_abbreviated_length_string},
+};
+use gv_core::{error::TGVError, intervals::GenomeInterval, state::State};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+/// Hatch drawn over gaps, distinct from bases and features.
+const GAP_HATCH: &str = "/";
+
+/// Render assembly gaps as hatched intervals, labeled with their type (or N for runs of Ns) and length where they
+/// fit.
+pub fn render_gaps(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    let Some(gap_track) = state
+        .gaps
+        .as_ref()
+        .filter(|gap_track| gap_track.contig_index == alignment_view.focus.contig_index)
+    else {
+        return Ok(());
+    };
+
+    let region = alignment_view.region(area);
+    for gap in gap_track.gaps_in(&state.sequence, region.start(), region.end()) {
+        let onscreen_start = alignment_view.onscreen_x_coordinate(gap.start(), area);
+        let onscreen_end = alignment_view.onscreen_x_coordinate(gap.end(), area);
+        let Some((x, length)) =
+            OnScreenCoordinate::onscreen_start_and_length(&onscreen_start, &onscreen_end, area)
+        else {
+            continue;
+        };
+
+        let style = Style::default().fg(palette.background).bg(palette.BASE_N);
+        buf.set_string(area.x + x, area.y, GAP_HATCH.repeat(length as usize), style);
+
+        let label = format!(
+            " {} {} ",
+            gap.gap_type.as_deref().unwrap_or("N"),
+            get_abbreviated_length_string(gap.length())
+        );
+        if label.len() <= length as usize {
+            buf.set_string(
+                area.x + x + (length - label.len() as u16) / 2,
+                area.y,
+                label,
+                style,
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::{layout::AlignmentView, rendering::colors::Palette};
+use gv_core::{error::TGVError, sequence::Sequence};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Default GC content window in columns. The window in bases scales with the zoom level.
+const DEFAULT_GC_CONTENT_WINDOW_COLUMNS: u64 = 5;
+
+/// Smallest default GC content window.
+const MIN_GC_CONTENT_WINDOW: u64 = 20;
+
+/// Render the GC content track: a histogram of (G + C) / (A + C + G + T) in a window centered on each column.
+/// The full area height is 100%. Columns without A, C, G, or T in the window are left blank.
+pub fn render_gc(
+    area: &Rect,
+    buf: &mut Buffer,
+    sequence: &Sequence,
+    alignment_view: &AlignmentView,
+    window: Option<u64>,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    if sequence.contig_index != alignment_view.focus.contig_index || sequence.len() == 0 {
+        return Ok(());
+    }
+
+    let window = window.unwrap_or(u64::max(
+        alignment_view.zoom * DEFAULT_GC_CONTENT_WINDOW_COLUMNS,
+        MIN_GC_CONTENT_WINDOW,
+    ));
+    let style = Style::default().fg(palette.GC_CONTENT_COLOR);
+
+    for x in 0..area.width {
+        let Some((left, right)) = alignment_view.coordinates_of_onscreen_x(area.x + x, area) else {
+            continue;
+        };
+
+        let middle = left + (right - left) / 2;
+        let Some(gc_content) = sequence
+            .base_composition(middle.saturating_sub(window / 2), middle + window / 2)
+            .gc_content()
+        else {
+            continue;
+        };
+
+        // Height in rows from the bottom of the area.
+        let height = gc_content * area.height as f64;
+        for j in 0..area.height {
+            let fraction = height - j as f64;
+            if fraction <= 0.0 {
+                break;
+            }
+            buf.set_string(
+                area.x + x,
+                area.bottom() - 1 - j,
+                palette.glyphs.bar(fraction),
+                style,
+            );
+        }
+    }
+
+    Ok(())
+}
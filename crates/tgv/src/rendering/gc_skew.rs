@@ -0,0 +1,98 @@
+use crate::{layout::AlignmentView, rendering::colors::Palette};
+use gv_core::{error::TGVError, sequence::Sequence};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Smallest window for the windowed GC skew. At higher zoom levels, the window is one column.
+const MIN_GC_SKEW_WINDOW: u64 = 100;
+
+/// Render the GC skew track.
+/// The first row is the windowed skew (G - C) / (G + C). Above-zero windows are colored positive, below-zero windows
+/// negative. The second row is the cumulative G - C from the start of the loaded sequence, scaled to the view.
+pub fn render_gc_skew(
+    area: &Rect,
+    buf: &mut Buffer,
+    sequence: &Sequence,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    if sequence.contig_index != alignment_view.focus.contig_index || sequence.len() == 0 {
+        return Ok(());
+    }
+
+    let window = u64::max(alignment_view.zoom, MIN_GC_SKEW_WINDOW);
+
+    let mut cumulative_skews: Vec<Option<i64>> = vec![None; area.width as usize];
+    let mut cumulative_skew: i64 = 0;
+    let mut cumulative_end = sequence.start - 1;
+
+    for x in 0..area.width {
+        let Some((left, right)) = alignment_view.coordinates_of_onscreen_x(area.x + x, area) else {
+            continue;
+        };
+
+        let middle = left + (right - left) / 2;
+        let composition =
+            sequence.base_composition(middle.saturating_sub(window / 2), middle + window / 2);
+        if let Some(skew) = composition.gc_skew() {
+            let color = if skew >= 0.0 {
+                palette.GC_SKEW_POSITIVE
+            } else {
+                palette.GC_SKEW_NEGATIVE
+            };
+            buf.set_string(
+                area.x + x,
+                area.y,
+                palette.glyphs.bar(skew.abs()),
+                Style::default().fg(color),
+            );
+        }
+
+        if right < sequence.start || left > sequence.end() {
+            continue;
+        }
+        let right = u64::min(right, sequence.end());
+        if right > cumulative_end {
+            let composition = sequence.base_composition(cumulative_end + 1, right);
+            cumulative_skew += composition.g as i64 - composition.c as i64;
+            cumulative_end = right;
+        }
+        cumulative_skews[x as usize] = Some(cumulative_skew);
+    }
+
+    if area.height < 2 {
+        return Ok(());
+    }
+
+    let (Some(min), Some(max)) = (
+        cumulative_skews.iter().flatten().min(),
+        cumulative_skews.iter().flatten().max(),
+    ) else {
+        return Ok(());
+    };
+
+    for (x, cumulative_skew) in cumulative_skews.iter().enumerate() {
+        let Some(cumulative_skew) = cumulative_skew else {
+            continue;
+        };
+        let fraction = if max > min {
+            (cumulative_skew - min) as f64 / (max - min) as f64
+        } else {
+            0.5
+        };
+        buf.set_string(
+            area.x + x as u16,
+            area.y + 1,
+            palette.glyphs.bar(fraction),
+            Style::default().fg(palette.GC_SKEW_CUMULATIVE),
+        );
+    }
+
+    Ok(())
+}
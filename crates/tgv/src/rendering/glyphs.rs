@@ -0,0 +1,215 @@
+use ratatui::symbols::bar::{NINE_LEVELS, Set};
+
+/// Bar glyphs in ASCII, from empty to full.
+const ASCII_LEVELS: Set = Set {
+    empty: " ",
+    one_eighth: "_",
+    one_quarter: "_",
+    three_eighths: ".",
+    half: "-",
+    five_eighths: "-",
+    three_quarters: "=",
+    seven_eighths: "=",
+    full: "#",
+};
+
+/// First braille pattern codepoint (no dots).
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// Braille dot bits of the left and right columns, from the bottom dot to the top dot.
+const BRAILLE_LEFT_DOTS: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+const BRAILLE_RIGHT_DOTS: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+/// Glyphs used for bars, arrows, and blocks.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GlyphSet {
+    /// Unicode block elements.
+    #[default]
+    Block,
+
+    /// ASCII characters only, for maximal terminal compatibility.
+    Ascii,
+
+    /// Block elements, but continuous tracks (coverage and bigWig signals) are drawn with braille patterns: 2 x 4 dots
+    /// per cell.
+    Braille,
+}
+
+impl GlyphSet {
+    /// Number of vertical subpixels in a braille cell.
+    pub const BRAILLE_CELL_HEIGHT: usize = 4;
+
+    /// Bar glyphs, from empty to full.
+    pub fn bar_set(&self) -> Set {
+        match self {
+            GlyphSet::Ascii => ASCII_LEVELS,
+            GlyphSet::Block | GlyphSet::Braille => NINE_LEVELS,
+        }
+    }
+
+    /// Bar glyph for a fraction in [0, 1].
+    pub fn bar(&self, fraction: f64) -> &'static str {
+        let set = self.bar_set();
+        match (fraction.clamp(0.0, 1.0) * 8.0).round() as u8 {
+            0 => set.empty,
+            1 => set.one_eighth,
+            2 => set.one_quarter,
+            3 => set.three_eighths,
+            4 => set.half,
+            5 => set.five_eighths,
+            6 => set.three_quarters,
+            7 => set.seven_eighths,
+            _ => set.full,
+        }
+    }
+
+    /// Bar glyph hanging from the top of a cell, for a fraction in [0, 1]. There is no upper block for each eighth,
+    /// so the fraction is rounded to a half.
+    pub fn hanging_bar(&self, fraction: f64) -> &'static str {
+        match (self, (fraction.clamp(0.0, 1.0) * 2.0).round() as u8) {
+            (_, 0) => " ",
+            (GlyphSet::Ascii, 1) => "\"",
+            (GlyphSet::Ascii, _) => "#",
+            (GlyphSet::Block | GlyphSet::Braille, 1) => "▀",
+            (GlyphSet::Block | GlyphSet::Braille, _) => "█",
+        }
+    }
+
+    /// Full block used for cytobands, contigs, and non-coding exons.
+    pub fn block(&self) -> &'static str {
+        match self {
+            GlyphSet::Ascii => "=",
+            GlyphSet::Block | GlyphSet::Braille => "▅",
+        }
+    }
+
+    /// Left half block. The foreground and background colors show two items in one cell.
+    pub fn left_half(&self) -> &'static str {
+        match self {
+            GlyphSet::Ascii => "|",
+            GlyphSet::Block | GlyphSet::Braille => "▌",
+        }
+    }
+
+    pub fn forward_arrow(&self) -> &'static str {
+        match self {
+            GlyphSet::Ascii => ">",
+            GlyphSet::Block | GlyphSet::Braille => "►",
+        }
+    }
+
+    pub fn reverse_arrow(&self) -> &'static str {
+        match self {
+            GlyphSet::Ascii => "<",
+            GlyphSet::Block | GlyphSet::Braille => "◄",
+        }
+    }
+
+    /// Centromere marker under the ideogram.
+    pub fn centromere(&self) -> &'static str {
+        match self {
+            GlyphSet::Ascii => "^",
+            GlyphSet::Block | GlyphSet::Braille => "▲",
+        }
+    }
+
+    /// Left corner, horizontal line, and right corner of the top (or bottom) edge of a box.
+    pub fn box_edge(&self, top: bool) -> [&'static str; 3] {
+        match (self, top) {
+            (GlyphSet::Ascii, _) => ["+", "-", "+"],
+            (GlyphSet::Block | GlyphSet::Braille, true) => ["┌", "─", "┐"],
+            (GlyphSet::Block | GlyphSet::Braille, false) => ["└", "─", "┘"],
+        }
+    }
+
+    /// Spinner frame shown in areas that wait for data. Frames wrap around.
+    pub fn spinner(&self, frame: usize) -> &'static str {
+        let frames: &[&'static str] = match self {
+            GlyphSet::Ascii => &["|", "/", "-", "\\"],
+            GlyphSet::Block | GlyphSet::Braille => {
+                &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]
+            }
+        };
+        frames[frame % frames.len()]
+    }
+}
+
+/// Braille cell with the bottom `left` and `right` dots of each column filled. Heights are clamped to 4.
+pub fn braille_bars(left: usize, right: usize) -> char {
+    let dots = BRAILLE_LEFT_DOTS[..left.min(GlyphSet::BRAILLE_CELL_HEIGHT)]
+        .iter()
+        .chain(BRAILLE_RIGHT_DOTS[..right.min(GlyphSet::BRAILLE_CELL_HEIGHT)].iter())
+        .fold(BRAILLE_BLANK, |pattern, dot| pattern | dot);
+    char::from_u32(dots).unwrap_or(' ')
+}
+
+/// Braille cell with the top `left` and `right` dots of each column filled. Heights are clamped to 4.
+pub fn hanging_braille_bars(left: usize, right: usize) -> char {
+    let dots = BRAILLE_LEFT_DOTS
+        .iter()
+        .rev()
+        .take(left)
+        .chain(BRAILLE_RIGHT_DOTS.iter().rev().take(right))
+        .fold(BRAILLE_BLANK, |pattern, dot| pattern | dot);
+    char::from_u32(dots).unwrap_or(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, 0, '⠀')]
+    #[case(1, 0, '⡀')]
+    #[case(0, 1, '⢀')]
+    #[case(2, 4, '⣼')]
+    #[case(4, 4, '⣿')]
+    #[case(9, 0, '⡇')]
+    fn test_braille_bars(#[case] left: usize, #[case] right: usize, #[case] expected: char) {
+        assert_eq!(braille_bars(left, right), expected);
+    }
+
+    #[rstest]
+    #[case(0, 0, '⠀')]
+    #[case(1, 0, '⠁')]
+    #[case(0, 1, '⠈')]
+    #[case(2, 4, '⢻')]
+    #[case(9, 4, '⣿')]
+    fn test_hanging_braille_bars(
+        #[case] left: usize,
+        #[case] right: usize,
+        #[case] expected: char,
+    ) {
+        assert_eq!(hanging_braille_bars(left, right), expected);
+    }
+
+    #[rstest]
+    #[case(GlyphSet::Block, 0.0, " ")]
+    #[case(GlyphSet::Block, 0.5, "▄")]
+    #[case(GlyphSet::Block, 1.0, "█")]
+    #[case(GlyphSet::Ascii, 0.5, "-")]
+    #[case(GlyphSet::Ascii, 1.0, "#")]
+    fn test_bar(#[case] glyphs: GlyphSet, #[case] fraction: f64, #[case] expected: &str) {
+        assert_eq!(glyphs.bar(fraction), expected);
+    }
+
+    #[rstest]
+    #[case(GlyphSet::Block, 0.1, " ")]
+    #[case(GlyphSet::Block, 0.5, "▀")]
+    #[case(GlyphSet::Block, 1.0, "█")]
+    #[case(GlyphSet::Ascii, 0.5, "\"")]
+    #[case(GlyphSet::Ascii, 1.0, "#")]
+    fn test_hanging_bar(#[case] glyphs: GlyphSet, #[case] fraction: f64, #[case] expected: &str) {
+        assert_eq!(glyphs.hanging_bar(fraction), expected);
+    }
+
+    #[rstest]
+    #[case(GlyphSet::Block, 0, "⠋")]
+    #[case(GlyphSet::Block, 11, "⠙")]
+    #[case(GlyphSet::Ascii, 1, "/")]
+    #[case(GlyphSet::Ascii, 4, "|")]
+    fn test_spinner(#[case] glyphs: GlyphSet, #[case] frame: usize, #[case] expected: &str) {
+        assert_eq!(glyphs.spinner(frame), expected);
+    }
+}
@@ -1,7 +1,9 @@
+use crate::rendering::colors::Palette;
 use gv_core::error::TGVError;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
+    style::{Modifier, Style},
     text::{Line, Text},
     widgets::{Paragraph, Widget},
 };
@@ -9,47 +11,164 @@ use ratatui::{
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
 
-pub fn render_help(area: &Rect, buf: &mut Buffer) -> Result<(), TGVError> {
-    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
-        return Ok(());
-    }
+/// Help entries. Sections are separated by blank lines and are filtered as a unit by `:help KEYWORD`.
+const HELP_ENTRIES: &str =
+    " |:q|    Quit           |<ESC>|     Switch to normal mode / Close this window
+ |:h|    Help           |:|         Switch to command mode
+ |:help _keyword_|                  Show help entries mentioning _keyword_
+ |j / k / { / }|                    Scroll this window by a line / by a page
+ |:ls / :contigs|                   Switch chromosomes
+ |:gene _prefix_|                   Pick from genes matching _prefix_
+ |:info|                            Show the reference and UCSC host
+ |:palette _name_|                  Use dark, light, or colorblind colors
+ |:reload|                          Re-read the BAM, VCF, BED, and bigWig files
 
-    let help_text = format!(
-        "
- Terminal Genome Viewer - version {}
- ------------------------------------------------------------------------------
+ |:snapshot [label]|                Capture the current view
+ |:snapshots|                       Browse snapshots
+ |:export _path_.svg|               Save the view as an SVG image
+ |:export-reads _path_.bam|         Save the reads in view as BAM
+ |:export-cov _path_.bedgraph|      Save the coverage in view as bedGraph
 
- See more at: https://github.com/zeqianli/tgv
+ |:track list|                      List gene tracks
+ |:track _name_|                    Use gene track _name_
+ |:mod pileup|                      Show / hide base modification probabilities across reads, all modifications (e.g. 5mC and 5hmC) combined
+ |:gcskew|                          Show / hide GC skew
+ |:gc on/off|                       Show / hide GC content
+ |:overview|                        Show / hide whole-contig coverage (click to jump)
+ |:minimap on/off|                  Show / hide a whole-contig strip with gene-dense regions (click to jump)
+ |:ideogram|                        Show / hide a large ideogram (click to jump)
+ |:gaps|                            Show / hide assembly gaps
+ |:cpg|                             Mark CG dinucleotides in the sequence
+ |:cpg islands|                     Show / hide CpG island candidates
+ |:masking on/off|                  Dim soft-masked bases
+ |:repeats [_n_/off]|               Underline repeats of at least _n_ bases
+ |:split|                           Compare two regions side by side (gw switches panes)
+ |gt|                               Select the next BAM track
 
- |:q|    Quit           |<ESC>|     Switch to normal mode / Close this window
- |:h|    Help           |:|         Switch to command mode
- |:ls / :contigs|                   Switch chromosomes
+ |:cursor on/off|                   h / l move a cursor; the view pans only at the edges
+ |gz / :center|                     Center the view on the cursor
+ |:ruler _len_|                     Ruler ticks every _len_ (or auto)
+ |:coords 0/1|                      Show 0-based or 1-based coordinates
+ |:codon|                           Translate the codon at the cursor
+ |:aa code _name_|                  Use genetic code _name_ (standard, vertmito, invertmito, or auto)
+ |:whatgene|                        Report the gene, exon, or intron at the cursor
+ |:seq [-] [path]|                  Print the reference in view as FASTA
+ |:revcomp|                         Toggle complementing bases (the view is not reversed)
 
  |h / j / k / l|   Move left / down / up / right
  |y / p|           Move left / right faster
- |w / b / W / B|   Beginning of the next exon / previous exon / next gene / previous gene
+ |{ / }|           Move up / down faster
+ |w / b / W / B|   Beginning of the next exon / previous exon / next gene / previous gene (continues on the adjacent contig)
  |e / ge / E / gE| End of the next exon / previous exon / next gene / previous gene
  |z / o|           Zoom in / out
- |{{ / }}|         Move up / down faster
+ |g1 / g2 / g3|    1 bp per column / 1 kb / 5 kb around the cursor
+ |i|               Insertions at the cursor
+ |V|               Variant details at the cursor
+ |]v / [v|         Next / previous variant
+ |O|               Whole-contig overview of genes and coverage (Enter zooms into a bin)
+ |n / N|           Next / previous locus of the --loci list
+ |]r / [r|         Next / previous --regions target
 
  |<num><key>|      Repeat movements. Examples:
      - 5h: Move left by 5 bases
      - 11B: Move left by 11 genes
      - 16o: Zoom out by 16x
 
- |:_pos_|          Go to position on same contig.       Example: :1000
- |:_contig_:_pos_| Go to position on a contig.          Example: 17:7572659
- |:_gene_|         Go to _gene_                         Example: :KRAS
+ |:_pos_|                        Go to position on same contig   Example: :1000
+ |:_contig_:_pos_|               Go to position on a contig      Example: :17:7572659
+ |:_gene_|                       Go to _gene_                    Example: :KRAS
+ |:goto _gene_ +_flank_|         Frame _gene_ with flanks        Example: :goto KRAS +2kb
+ |:goto _gene_ _n_%|             Frame _gene_ in a window        Example: :goto KRAS 150%
+ |:goto _band_|                  Frame a cytoband                Example: :goto 17q21
+ |:goto _gene_:p._n_|            Codon of residue _n_            Example: :goto KRAS:p.12
+ |:mark _name_|                  Bookmark the current locus      Example: :mark site1
+ |:goto @_name_|                 Go to a bookmark                Example: :goto @site1
+ |:marks|                        List bookmarks
+ |:loci next / :loci prev|       Next / previous locus of the --loci list
+ |:find [-r] _bases_|            Search the reference; n / N for the next / previous match
+ |:peak / :peak next|            Go to the highest / next coverage peak
+
  |filter base(_pos_) = _base_|   Filter by base         Example: :filter base(123)=A
+ |filter length>=_n_|            Filter by read length  Example: :filter length>=1000
+ |:filter clear|                 Remove read filters
+ |:mapq _n_|                     Hide reads with MAPQ < n. :mapq 0 to reset
+ |:mismatch-qual _n_|            Hide mismatches with base quality < n
+ |:show hidden|                  Show unmapped / secondary / supplementary / duplicate reads
+ |:show / :hide _category_|      Show or hide a read category      Example: :show supplementary
+ |:isolate alt / ref / off|      Show only reads with the alt / ref allele of the variant at the cursor
+ |:downsample _n_|               Display at most n reads per column. :downsample auto / off
+ |:vcf-filter|                   Filter variants        Example: :vcf-filter PASS qual>30
+
+ |:view pairs / :view linear|    View reads as pairs with insert-size gap colors / one by one
  |:paired|                       View reads as pairs
+ |:color length|                 Color reads by length. :color default to reset
+ |:colorby tag _tag_|            Color reads by tag value          Example: :colorby tag HP
+ |:support|                      Highlight reads with the alt allele of the nearest variant. :support clear to reset
+ |:cov-max _n_|                  Cap the coverage axis at depth n. :cov-max auto to reset
+ |:indels|                       Show indel lengths in view
+
+ |:group orientation / go|       Group reads by pair orientation (F1R2, F2R1, ...). :group default to reset
+ |:group hp|                     Group reads by haplotype
+ |:sort _key_|                   Sort reads             Example: :sort base
+ |:sort base|                    Sort by the base at the cursor (A, C, G, T, deletions). :sort none to reset
  |:clear|                        Reset alignment display options
- ",
+ |:height _track_ _rows_|        Track height (coverage, alignment, sequence, or gene)";
+
+/// Render the help page. If keyword is not empty, only sections mentioning the keyword are shown, with matching lines
+/// highlighted in the palette's highlight color. scroll: lines scrolled past. Clamped so that the last line stays at the bottom.
+pub fn render_help(
+    area: &Rect,
+    buf: &mut Buffer,
+    keyword: &str,
+    scroll: &mut usize,
+    pallete: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let header = format!(
+        "
+ Terminal Genome Viewer - version {}
+ ------------------------------------------------------------------------------
+
+ See more at: https://github.com/zeqianli/tgv
+",
         env!("CARGO_PKG_VERSION")
     );
+    let mut lines = header.lines().map(Line::from).collect::<Vec<Line>>();
+
+    let keyword = keyword.to_lowercase();
+    let highlight_style = Style::default()
+        .fg(pallete.HIGHLIGHT_COLOR)
+        .add_modifier(Modifier::BOLD);
+    let mut any_section_matches = false;
+    for section in HELP_ENTRIES.split("\n\n") {
+        if !keyword.is_empty() && !section.to_lowercase().contains(&keyword) {
+            continue;
+        }
+        any_section_matches = true;
+
+        lines.push(Line::from(""));
+        for line in section.lines() {
+            if !keyword.is_empty() && line.to_lowercase().contains(&keyword) {
+                lines.push(Line::styled(line, highlight_style));
+            } else {
+                lines.push(Line::from(line));
+            }
+        }
+    }
+
+    if !any_section_matches {
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            " No help entries mention \"{keyword}\". Use :h to see all entries."
+        )));
+    }
 
-    Paragraph::new(Text::from(
-        help_text.lines().map(Line::from).collect::<Vec<Line>>(),
-    ))
-    .render(*area, buf);
+    *scroll = usize::min(*scroll, lines.len().saturating_sub(area.height as usize));
+    Paragraph::new(Text::from(lines))
+        .scroll((*scroll as u16, 0))
+        .render(*area, buf);
     Ok(())
 }
@@ -0,0 +1,211 @@
+use crate::{
+    layout::{AlignmentView, linear_scale},
+    rendering::{
+        colors::Palette,
+        cytoband::{contig_overview_x_range, get_cytoband_xs_strings_and_styles},
+    },
+};
+use gv_core::{cytoband::Cytoband, error::TGVError, state::State};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+/// Rows of the window box above and below the bands, and one row of bands.
+const MIN_AREA_HEIGHT: u16 = 3;
+
+/// Render the ideogram of the focus contig: bands as tall as the area allows, the current window boxed, and the
+/// centromere marked under the bands. Contigs without cytoband data are drawn as a plain bar.
+/// The bar spans the same columns as the cytoband, so that the windows line up.
+pub fn render_ideogram(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+    let Some(contig_length) = state.contig_length(&alignment_view.focus)? else {
+        return Ok(());
+    };
+
+    buf.set_string(
+        area.x,
+        area.y + 1,
+        state.contig_name(&alignment_view.focus)?,
+        Style::default(),
+    );
+
+    draw_ideogram(
+        area,
+        buf,
+        state.current_cytoband(&alignment_view.focus)?,
+        contig_length,
+        contig_overview_x_range(area, state),
+        (alignment_view.left(area), alignment_view.right(area)),
+        palette,
+    )
+}
+
+/// Draw the ideogram bar in columns [bar_start, bar_end) relative to area.x, boxing window: [left, right], 1-based.
+fn draw_ideogram(
+    area: &Rect,
+    buf: &mut Buffer,
+    cytoband: Option<&Cytoband>,
+    contig_length: u64,
+    (bar_start, bar_end): (u16, u16),
+    (left, right): (u64, u64),
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if contig_length == 0 || bar_end <= bar_start + 1 || bar_end > area.width {
+        return Ok(());
+    }
+    // Bands
+    for y in area.y + 1..area.bottom() - 1 {
+        match cytoband {
+            Some(cytoband) => {
+                for (x, string, style) in
+                    get_cytoband_xs_strings_and_styles(cytoband, bar_start, bar_end, palette)?
+                {
+                    buf.set_string(area.x + x, y, string, style);
+                }
+            }
+            None => {
+                buf.set_string(
+                    area.x + bar_start,
+                    y,
+                    palette
+                        .glyphs
+                        .block()
+                        .repeat((bar_end - bar_start) as usize),
+                    Style::default(),
+                );
+            }
+        }
+    }
+
+    // Current window
+    let window_start = linear_scale(left.saturating_sub(1), contig_length, bar_start, bar_end)?;
+    let window_end = u16::min(
+        u16::max(
+            linear_scale(right, contig_length, bar_start, bar_end)?,
+            window_start + 2,
+        ),
+        bar_end,
+    );
+    let window_style = Style::default().fg(palette.HIGHLIGHT_COLOR);
+    for (y, top) in [(area.y, true), (area.bottom() - 1, false)] {
+        let [left_corner, line, right_corner] = palette.glyphs.box_edge(top);
+        buf.set_string(area.x + window_start, y, left_corner, window_style);
+        for x in window_start + 1..window_end - 1 {
+            buf.set_string(area.x + x, y, line, window_style);
+        }
+        buf.set_string(area.x + window_end - 1, y, right_corner, window_style);
+    }
+
+    // Centromere
+    if let Some(centromere) = cytoband.and_then(Cytoband::centromere) {
+        let x = u16::min(
+            linear_scale(centromere, contig_length, bar_start, bar_end)?,
+            bar_end - 1,
+        );
+        buf.set_string(
+            area.x + x,
+            area.bottom() - 1,
+            palette.glyphs.centromere(),
+            Style::default().fg(palette.ACEN_COLOR),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::DARK_THEME;
+    use gv_core::cytoband::{CytobandSegment, Stain};
+
+    fn cytoband(stains: &[(u64, u64, Stain)]) -> Cytoband {
+        Cytoband {
+            reference: None,
+            contig_index: 0,
+            segments: stains
+                .iter()
+                .map(|(start, end, stain)| CytobandSegment {
+                    contig_index: 0,
+                    start: *start,
+                    end: *end,
+                    name: String::new(),
+                    stain: stain.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn row(buf: &Buffer, y: u16) -> String {
+        (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect()
+    }
+
+    #[test]
+    fn centromere_is_marked_under_the_acen_bands() {
+        let cytoband = cytoband(&[
+            (1, 100, Stain::Gneg),
+            (101, 150, Stain::Acen),
+            (151, 200, Stain::Acen),
+            (201, 400, Stain::Gpos(50)),
+        ]);
+        let area = Rect::new(0, 0, 60, 4);
+        let mut buf = Buffer::empty(area);
+        draw_ideogram(
+            &area,
+            &mut buf,
+            Some(&cytoband),
+            400,
+            (10, 50),
+            (301, 400),
+            &DARK_THEME,
+        )
+        .unwrap();
+
+        // 400 bp over 40 columns: the centromere at 150 bp is 15 columns into the bar.
+        let bottom = row(&buf, 3);
+        assert_eq!(bottom.chars().nth(25), Some('▲'));
+        assert_eq!(bottom.matches('▲').count(), 1);
+        assert_eq!(buf[(25, 3)].fg, DARK_THEME.ACEN_COLOR);
+
+        // The acen bands are drawn as arrows pointing at the centromere on every band row.
+        for y in 1..3 {
+            let bands = row(&buf, y);
+            assert_eq!(bands.chars().nth(24), Some('>'), "{bands}");
+            assert_eq!(bands.chars().nth(25), Some('<'), "{bands}");
+        }
+
+        // The window [301, 400] is boxed over the last quarter of the bar.
+        assert_eq!(
+            row(&buf, 0),
+            format!("{}┌{}┐{}", " ".repeat(40), "─".repeat(8), " ".repeat(10))
+        );
+    }
+
+    #[test]
+    fn contigs_without_cytobands_are_plain_bars() {
+        let area = Rect::new(0, 0, 30, 3);
+        let mut buf = Buffer::empty(area);
+        draw_ideogram(
+            &area,
+            &mut buf,
+            None,
+            1000,
+            (10, 30),
+            (1, 1000),
+            &DARK_THEME,
+        )
+        .unwrap();
+
+        assert_eq!(
+            row(&buf, 1),
+            format!("{}{}", " ".repeat(10), DARK_THEME.glyphs.block().repeat(20))
+        );
+        assert!(!row(&buf, 2).contains('▲'));
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    layout::{AlignmentView, AreaType, MainLayout},
+    loading::LoadingStatus,
+    rendering::colors::Palette,
+};
+use ratatui::{buffer::Buffer, style::Style};
+
+/// Draw a spinner in the areas of the active pane that wait for a background load. Alignments wait for the
+/// sequence, since mismatches are computed against it.
+pub fn render_loading(
+    buf: &mut Buffer,
+    layout: &MainLayout,
+    alignment_view: &AlignmentView,
+    loading: &LoadingStatus,
+    palette: &Palette,
+) {
+    if !loading.is_loading() {
+        return;
+    }
+
+    let label = format!("{} Loading", palette.glyphs.spinner(loading.spinner_frame));
+    for (area_type, rect) in layout.areas.iter() {
+        let waiting = match area_type {
+            AreaType::GeneTrack => loading.track,
+            AreaType::Sequence => {
+                loading.sequence
+                    && alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_SEQUENCES
+            }
+            AreaType::Alignment(_) => {
+                loading.sequence
+                    && alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS
+            }
+            AreaType::GcSkew | AreaType::GcContent | AreaType::CpgIslands | AreaType::Gaps => {
+                loading.sequence
+            }
+            _ => false,
+        };
+        let rect = rect.intersection(buf.area);
+        if !waiting || rect.is_empty() {
+            continue;
+        }
+        buf.set_stringn(
+            rect.x,
+            rect.y,
+            &label,
+            rect.width as usize,
+            Style::default(),
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use crate::{
+    layout::{AlignmentView, linear_scale},
+    rendering::{colors::Palette, cytoband::contig_overview_x_range},
+};
+use gv_core::{error::TGVError, state::State};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Style,
+};
+
+const MIN_AREA_WIDTH: u16 = 20;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render the whole contig in one row, aligned with the cytoband, with ticks at gene-dense bins. The current view
+/// window is highlighted.
+pub fn render_minimap(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width <= MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let Some(contig_length) = state.contig_length(&alignment_view.focus)? else {
+        return Ok(());
+    };
+    if contig_length == 0 {
+        return Ok(());
+    }
+
+    let (start_x, end_x) = contig_overview_x_range(area, state);
+    if end_x <= start_x {
+        return Ok(());
+    }
+
+    buf.set_string(area.x, area.y, "Minimap", Style::default());
+
+    let minimap = state
+        .minimap
+        .as_ref()
+        .filter(|minimap| minimap.contig_index == alignment_view.focus.contig_index);
+    let gene_dense_bins = minimap
+        .map(|minimap| minimap.gene_dense_bins())
+        .unwrap_or_default();
+    let width = (end_x - start_x) as u64;
+    for x in start_x..end_x {
+        let gene_dense = minimap.is_some_and(|minimap| {
+            let position = (x - start_x) as u64 * contig_length / width + 1;
+            gene_dense_bins[minimap.bin_index(position)]
+        });
+        let (glyph, color) = if gene_dense {
+            (palette.glyphs.bar(1.0), palette.GENE_BACKGROUND_COLOR)
+        } else {
+            (palette.glyphs.bar(0.25), palette.INTRON_FOREGROUND_COLOR)
+        };
+        buf.set_string(area.x + x, area.y, glyph, Style::default().fg(color));
+    }
+
+    // Highlight the current viewing window
+    let viewing_window_start =
+        linear_scale(alignment_view.left(area), contig_length, start_x, end_x)?;
+    let viewing_window_end =
+        linear_scale(alignment_view.right(area), contig_length, start_x, end_x)?;
+    for x in viewing_window_start..viewing_window_end + 1 {
+        if let Some(cell) = buf.cell_mut(Position::new(area.x + x, area.y)) {
+            cell.set_bg(palette.HIGHLIGHT_COLOR);
+        }
+    }
+
+    Ok(())
+}
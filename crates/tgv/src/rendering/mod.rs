@@ -1,42 +1,136 @@
 mod alignment;
 mod bed;
+mod bookmarks;
 mod colors;
 mod console;
 mod contig_list;
+mod contig_overview;
 mod coordinate;
 mod coverage;
+mod coverage_overview;
+mod cpg_islands;
 mod cytoband;
+mod gaps;
+mod gc_content;
+mod gc_skew;
+mod glyphs;
 mod help;
+mod ideogram;
 mod intervals;
+mod loading;
+mod minimap;
+mod modification_pileup;
+mod popup;
 mod sequence;
+mod signal;
+mod snapshots;
 mod status_bar;
 mod track;
+mod translation;
 mod variants;
-pub use alignment::{render_alignment, render_paired_alignment};
+pub use alignment::{AlignmentContext, render_alignment, render_paired_alignment};
 pub use bed::render_bed;
-pub use colors::{DARK_THEME, Palette};
+pub use bookmarks::render_bookmarks;
+pub use colors::{COLORBLIND_THEME, DARK_THEME, LIGHT_THEME, Palette, Theme};
 pub use console::render_console;
-pub use contig_list::render_contig_list;
+pub use contig_list::{render_contig_list, render_gene_list};
+pub use contig_overview::render_contig_overview;
 pub use coordinate::render_coordinates;
+pub(crate) use coordinate::to_thousand_separated;
 pub use coverage::render_coverage;
-pub use cytoband::render_cytobands;
+pub use coverage_overview::render_coverage_overview;
+pub use cpg_islands::render_cpg_islands;
+pub use cytoband::{contig_overview_x_range, render_cytobands};
+pub use gaps::render_gaps;
+pub use gc_content::render_gc;
+pub use gc_skew::render_gc_skew;
+pub use glyphs::GlyphSet;
 pub use help::render_help;
+pub use ideogram::render_ideogram;
+pub use loading::render_loading;
+pub use minimap::render_minimap;
+pub use modification_pileup::render_modification_pileup;
+pub use popup::{Popup, render_popup};
 pub use sequence::render_sequence;
+pub use signal::render_signal;
+pub use snapshots::render_snapshots;
 pub use status_bar::render_status_bar;
 pub use track::render_track;
+pub use translation::render_translation;
 pub use variants::render_variants;
 
 use crate::{
-    layout::{AlignmentView, AreaType, MainLayout},
+    layout::{AlignmentView, AreaType, MainLayout, OnScreenCoordinate},
     mouse::MouseRegister,
     register::{KeyRegisterType, Registers},
 };
 
-use gv_core::{error::TGVError, message::AlignmentDisplayOption, state::State};
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use gv_core::{
+    error::TGVError,
+    message::AlignmentDisplayOption,
+    state::{State, ViewData},
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+};
+
+/// The panes of the main view: the active one, and in a split screen, the inactive one.
+pub struct Panes<'a> {
+    pub layout: &'a MainLayout,
+
+    /// View of the active pane.
+    pub alignment_view: &'a AlignmentView,
 
-/// Render all areas in the layout
+    /// View and data of the inactive pane. None without a split screen.
+    pub inactive_pane: Option<&'a mut (AlignmentView, ViewData)>,
+}
+
+/// Render all areas in the layout. In a split screen, the inactive pane is rendered with its own view and data.
 pub fn render_main(
+    buf: &mut Buffer,
+    state: &mut State,
+    registers: &Registers,
+    panes: Panes,
+    mouse_register: &MouseRegister,
+    pallete: &Palette,
+) -> Result<(), TGVError> {
+    let Panes {
+        layout,
+        alignment_view,
+        inactive_pane,
+    } = panes;
+    render_areas(
+        buf,
+        state,
+        registers,
+        layout,
+        alignment_view,
+        mouse_register,
+        pallete,
+    )?;
+
+    if let Some(inactive_layout) = layout.inactive_layout()
+        && let Some((inactive_view, inactive_data)) = inactive_pane
+    {
+        state.swap_view_data(inactive_data);
+        let result = render_areas(
+            buf,
+            state,
+            registers,
+            &inactive_layout,
+            inactive_view,
+            mouse_register,
+            pallete,
+        );
+        state.swap_view_data(inactive_data);
+        result?;
+    }
+    Ok(())
+}
+
+fn render_areas(
     buf: &mut Buffer,
     state: &mut State,
     registers: &Registers,
@@ -47,22 +141,59 @@ pub fn render_main(
 ) -> Result<(), TGVError> {
     // Render each area based on its type
     for (area_type, rect) in layout.areas.iter() {
-        if rect.y >= buf.area.height || rect.x >= buf.area.width {
+        // Areas are clipped to the buffer, so a layout that has not caught up with a resize never draws out of bounds.
+        let rect = &rect.intersection(buf.area);
+        if rect.is_empty() {
             continue;
         }
 
         match area_type {
             AreaType::Cytoband => render_cytobands(rect, buf, state, alignment_view, pallete)?,
+            AreaType::CoverageOverview => {
+                render_coverage_overview(rect, buf, state, alignment_view, pallete)?
+            }
+            AreaType::Minimap => render_minimap(rect, buf, state, alignment_view, pallete)?,
             AreaType::Coordinate => render_coordinates(rect, buf, alignment_view, state)?,
             AreaType::Coverage(index) => {
                 if alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS
                     && let Some(alignment) = state.alignments.get(*index)
                 {
-                    render_coverage(rect, buf, alignment, alignment_view, pallete)?;
+                    render_coverage(
+                        rect,
+                        buf,
+                        alignment,
+                        alignment_view,
+                        state.coverage_max,
+                        pallete,
+                    )?;
+                }
+                if let Some(label) = layout.alignment_label(*index) {
+                    render_alignment_label(
+                        rect,
+                        buf,
+                        label,
+                        registers.alignment_track == Some(*index),
+                        pallete,
+                    );
                 }
             }
             AreaType::Alignment(index) => {
                 if alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS {
+                    let supported_allele = state
+                        .supported_allele
+                        .as_ref()
+                        .filter(|allele| allele.contig_index == alignment_view.focus.contig_index);
+                    let split_read = state.split_read.as_ref().filter(|split_read| {
+                        split_read.alignment_index == *index
+                            && split_read.contig_index == alignment_view.focus.contig_index
+                    });
+                    let context = AlignmentContext {
+                        alignment_view,
+                        reference_sequence: &state.sequence,
+                        supported_allele,
+                        split_read,
+                        pallete,
+                    };
                     if state.alignment_options[*index]
                         .contains(&AlignmentDisplayOption::ViewAsPairs)
                     {
@@ -78,10 +209,8 @@ pub fn render_main(
                             rect,
                             buf,
                             &mut state.alignments[*index],
-                            alignment_view,
                             paired_alignment,
-                            &state.sequence,
-                            pallete,
+                            &context,
                         )?;
                     } else {
                         render_alignment(
@@ -89,9 +218,7 @@ pub fn render_main(
                             rect,
                             buf,
                             &mut state.alignments[*index],
-                            alignment_view,
-                            &state.sequence,
-                            pallete,
+                            &context,
                         )?;
                     }
                 }
@@ -107,6 +234,11 @@ pub fn render_main(
                     render_sequence(rect, buf, state, alignment_view, pallete)?;
                 }
             }
+            AreaType::Translation => {
+                if alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_TRANSLATION {
+                    render_translation(rect, buf, state, alignment_view, pallete)?;
+                }
+            }
             AreaType::GeneTrack => {
                 render_track(rect, buf, state, alignment_view, pallete)?;
             }
@@ -121,12 +253,22 @@ pub fn render_main(
                     buf,
                     state,
                     alignment_view,
-                    mouse_register.hovered_alignment,
+                    mouse_register
+                        .hovered_alignment
+                        .or(registers.alignment_track),
+                    pallete,
                 )?;
             }
             AreaType::Variant(index) => {
                 if let Some(variants) = state.variants.get(*index) {
-                    render_variants(rect, buf, variants, alignment_view, pallete)?;
+                    render_variants(
+                        rect,
+                        buf,
+                        variants,
+                        &state.variant_filter,
+                        alignment_view,
+                        pallete,
+                    )?;
                 }
             }
             AreaType::Bed(index) => {
@@ -134,11 +276,119 @@ pub fn render_main(
                     render_bed(rect, buf, bed_intervals, alignment_view, pallete)?;
                 }
             }
+            AreaType::ModificationPileup(index) => {
+                if alignment_view.zoom <= AlignmentView::MAX_ZOOM_TO_DISPLAY_ALIGNMENTS
+                    && let Some(alignment) = state.alignments.get(*index)
+                {
+                    render_modification_pileup(rect, buf, alignment, alignment_view, pallete)?;
+                }
+            }
+            AreaType::GcSkew => {
+                render_gc_skew(rect, buf, &state.sequence, alignment_view, pallete)?;
+            }
+            AreaType::GcContent => {
+                render_gc(
+                    rect,
+                    buf,
+                    &state.sequence,
+                    alignment_view,
+                    layout.gc_content_window,
+                    pallete,
+                )?;
+            }
+            AreaType::Ideogram => {
+                render_ideogram(rect, buf, state, alignment_view, pallete)?;
+            }
+            AreaType::CpgIslands => {
+                render_cpg_islands(rect, buf, &state.sequence, alignment_view, pallete)?;
+            }
+            AreaType::Gaps => render_gaps(rect, buf, state, alignment_view, pallete)?,
+            AreaType::Signal(index) => {
+                if let Some(signal) = state.signals.get(*index) {
+                    render_signal(rect, buf, signal, alignment_view, pallete)?;
+                }
+            }
         };
     }
+
+    if let Some(cursor) = alignment_view.cursor {
+        render_cursor_column(buf, layout, alignment_view, cursor);
+    }
     Ok(())
 }
 
+/// Reverse the column under the cursor in areas drawn in genome coordinates.
+fn render_cursor_column(
+    buf: &mut Buffer,
+    layout: &MainLayout,
+    alignment_view: &AlignmentView,
+    cursor: u64,
+) {
+    for (area_type, rect) in layout.areas.iter() {
+        if !matches!(
+            area_type,
+            AreaType::Coordinate
+                | AreaType::Coverage(_)
+                | AreaType::Alignment(_)
+                | AreaType::Sequence
+                | AreaType::Translation
+                | AreaType::GeneTrack
+                | AreaType::Variant(_)
+                | AreaType::Bed(_)
+                | AreaType::ModificationPileup(_)
+                | AreaType::GcSkew
+                | AreaType::GcContent
+                | AreaType::CpgIslands
+                | AreaType::Gaps
+                | AreaType::Signal(_)
+        ) {
+            continue;
+        }
+
+        let OnScreenCoordinate::OnScreen(x) = alignment_view.onscreen_x_coordinate(cursor, rect)
+        else {
+            continue;
+        };
+        let x = rect.x + x as u16;
+        if x >= rect.right() || x >= buf.area.right() {
+            continue;
+        }
+        for y in rect.top()..u16::min(rect.bottom(), buf.area.bottom()) {
+            if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                cell.modifier.insert(Modifier::REVERSED);
+            }
+        }
+    }
+}
+
+/// File name of an alignment track at the top left of its coverage track. The track that keys and commands act on
+/// is highlighted.
+fn render_alignment_label(
+    area: &Rect,
+    buf: &mut Buffer,
+    label: &str,
+    selected: bool,
+    palette: &Palette,
+) {
+    if area.height == 0 || area.width == 0 {
+        return;
+    }
+    let style = if selected {
+        Style::default()
+            .bg(palette.HIGHLIGHT_COLOR)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::BOLD)
+    };
+    buf.set_stringn(
+        area.x,
+        area.y,
+        format!(" {label} "),
+        area.width as usize,
+        style,
+    );
+}
+
 fn render_alignment_divider(area: &Rect, buf: &mut Buffer, palette: &Palette, highlighted: bool) {
     let style = if highlighted {
         Style::default().bg(palette.HIGHLIGHT_COLOR)
@@ -0,0 +1,64 @@
+use crate::{layout::AlignmentView, rendering::colors::Palette};
+use gv_core::{
+    alignment::{Alignment, ModificationCoverage},
+    error::TGVError,
+};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render base modification probabilities aggregated across reads. Modifications are not told apart, e.g. 5mC and
+/// 5hmC calls are combined.
+/// Rows are high (>= 70%), medium, and low (< 30%) probability calls. The bar height in each cell is the fraction of
+/// calls at that column falling into the row's bin.
+pub fn render_modification_pileup(
+    area: &Rect,
+    buf: &mut Buffer,
+    alignment: &Alignment,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    for x in 0..area.width {
+        let Some((left, right)) = alignment_view.coordinates_of_onscreen_x(area.x + x, area) else {
+            continue;
+        };
+
+        let mut column_coverage = ModificationCoverage::default();
+        for position in left..=right {
+            if let Some(coverage) = alignment.modification_coverage_at(position) {
+                column_coverage.high += coverage.high;
+                column_coverage.medium += coverage.medium;
+                column_coverage.low += coverage.low;
+            }
+        }
+
+        let total = column_coverage.total();
+        if total == 0 {
+            continue;
+        }
+
+        for (y, (count, color)) in [
+            (column_coverage.high, palette.MOD_5MC_HIGH),
+            (column_coverage.medium, palette.MOD_5MC_MED),
+            (column_coverage.low, palette.MOD_5MC_LOW),
+        ]
+        .into_iter()
+        .enumerate()
+        .take(area.height as usize)
+        {
+            buf.set_string(
+                area.x + x,
+                area.y + y as u16,
+                palette.glyphs.bar(count as f64 / total as f64),
+                Style::default().fg(color),
+            );
+        }
+    }
+
+    Ok(())
+}
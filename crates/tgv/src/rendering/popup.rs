@@ -0,0 +1,66 @@
+use gv_core::error::TGVError;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Text},
+    widgets::{Block, Clear, Padding, Paragraph, Widget, Wrap},
+};
+
+const MIN_AREA_WIDTH: u16 = 8;
+const MIN_AREA_HEIGHT: u16 = 3;
+
+/// Transient window drawn over the main view. Closed with Esc.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Popup {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+/// Render the popup centered in the area. Long lines are wrapped.
+pub fn render_popup(area: &Rect, buf: &mut Buffer, popup: &Popup) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let content_width = popup
+        .lines
+        .iter()
+        .chain(std::iter::once(&popup.title))
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let width = content_width.saturating_add(4).min(area.width);
+    // Borders and padding take 4 columns.
+    let text_width = width.saturating_sub(4).max(1);
+    let wrapped_line_count = popup
+        .lines
+        .iter()
+        .map(|line| (line.chars().count() as u16).div_ceil(text_width).max(1))
+        .sum::<u16>();
+    let height = wrapped_line_count.saturating_add(2).min(area.height);
+
+    let popup_area = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    Clear.render(popup_area, buf);
+    Paragraph::new(Text::from(
+        popup
+            .lines
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect::<Vec<_>>(),
+    ))
+    .wrap(Wrap { trim: false })
+    .block(
+        Block::bordered()
+            .padding(Padding::horizontal(1))
+            .title(format!(" {} ", popup.title)),
+    )
+    .render(popup_area, buf);
+
+    Ok(())
+}
@@ -1,6 +1,15 @@
 use crate::{layout::AlignmentView, rendering::colors::Palette};
-use gv_core::{error::TGVError, intervals::Region, sequence::Sequence, state::State};
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use gv_core::{
+    error::TGVError,
+    intervals::Region,
+    sequence::{Sequence, complement, cpg_mask, tandem_repeats},
+    state::State,
+};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style},
+};
 
 const MIN_AREA_WIDTH: u16 = 2;
 const MIN_AREA_HEIGHT: u16 = 1;
@@ -13,35 +22,100 @@ pub fn render_sequence(
     pallete: &Palette,
 ) -> Result<(), TGVError> {
     let region = alignment_view.region(area);
+    let bases = displayed_bases(&state.sequence, &region, state.revcomp, state.show_masking);
+    let in_repeat = match (&bases, state.repeat_min_length) {
+        (Some(bases), Some(min_length)) => repeat_mask(bases, min_length),
+        _ => Vec::new(),
+    };
+    // CG is its own reverse complement, so CpGs are at the same positions under `:revcomp`.
+    let in_cpg = match &bases {
+        Some(bases) if state.show_cpg => cpg_mask(bases),
+        _ => Vec::new(),
+    };
 
     match alignment_view.zoom {
-        1 => render_sequence_at_1x(area, buf, &region, &state.sequence, pallete),
-        2 => render_sequence_at_2x(area, buf, &region, &state.sequence, pallete),
+        1 => render_sequence_at_1x(area, buf, bases, &in_repeat, &in_cpg, pallete),
+        2 => render_sequence_at_2x(area, buf, bases, &in_repeat, &in_cpg, pallete),
         _ => Ok(()),
     }
 }
 
+/// Whether each displayed base is part of a homopolymer or short tandem repeat. Only the visible window is scanned, so
+/// repeats cut off at the edges are underlined only if the visible part is long enough.
+fn repeat_mask(bases: &[u8], min_length: usize) -> Vec<bool> {
+    let mut in_repeat = vec![false; bases.len()];
+    for repeat in tandem_repeats(bases, min_length) {
+        in_repeat[repeat.start..repeat.end].fill(true);
+    }
+    in_repeat
+}
+
+fn repeat_style(style: Style, in_repeat: bool) -> Style {
+    if in_repeat {
+        style.add_modifier(Modifier::UNDERLINED)
+    } else {
+        style
+    }
+}
+
+/// Bases of CpG dinucleotides are bold.
+fn cpg_style(style: Style, in_cpg: bool) -> Style {
+    if in_cpg {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
+}
+
+/// Reference bases in a region, complemented under `:revcomp`. Read left to right, the complement is the minus strand
+/// from 3' to 5'. Soft-masked (lowercase) bases are uppercased unless masking is shown.
+fn displayed_bases(
+    sequence: &Sequence,
+    region: &Region,
+    revcomp: bool,
+    show_masking: bool,
+) -> Option<Vec<u8>> {
+    sequence.get_sequence(region).map(|bases| {
+        bases
+            .into_iter()
+            .map(|base| if revcomp { complement(base) } else { base })
+            .map(|base| {
+                if show_masking {
+                    base
+                } else {
+                    base.to_ascii_uppercase()
+                }
+            })
+            .collect()
+    })
+}
+
 fn render_sequence_at_1x(
     area: &Rect,
     buf: &mut Buffer,
-    region: &Region,
-    sequence: &Sequence,
+    sequence: Option<Vec<u8>>,
+    in_repeat: &[bool],
+    in_cpg: &[bool],
     pallete: &Palette,
 ) -> Result<(), TGVError> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return Ok(());
     }
 
-    let sequence_string = String::from_utf8(sequence.get_sequence(region).unwrap_or_default())?;
+    let sequence_string = String::from_utf8(sequence.unwrap_or_default())?;
 
     for (i, base) in sequence_string.chars().enumerate() {
         buf.set_string(
             area.x + i as u16,
             area.y,
             base.to_string(),
-            Style::default()
-                .fg(pallete.SEQUENCE_FOREGROUND_COLOR)
-                .bg(pallete.base_color(base as u8)),
+            cpg_style(
+                repeat_style(
+                    pallete.sequence_base_style(base as u8),
+                    in_repeat.get(i).copied().unwrap_or(false),
+                ),
+                in_cpg.get(i).copied().unwrap_or(false),
+            ),
         );
     }
 
@@ -52,18 +126,20 @@ fn render_sequence_at_1x(
 /// for every 2 bases, render the left base using foreground color of the
 /// half-block unicode character and the right base using background color.
 /// See: https://ratatui.rs/examples/style/colors_rgb/#_top
+/// A cell is underlined if either base is part of a repeat, and bold if either base is part of a CpG.
 fn render_sequence_at_2x(
     area: &Rect,
     buf: &mut Buffer,
-    region: &Region,
-    sequence: &Sequence,
+    sequence: Option<Vec<u8>>,
+    in_repeat: &[bool],
+    in_cpg: &[bool],
     palette: &Palette,
 ) -> Result<(), TGVError> {
     if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
         return Ok(());
     }
 
-    if let Some(sequence) = sequence.get_sequence(region) {
+    if let Some(sequence) = sequence {
         for i in 0..sequence.len() / 2 {
             let base1 = sequence[i * 2];
             let base2 = sequence[i * 2 + 1];
@@ -71,13 +147,129 @@ fn render_sequence_at_2x(
             buf.set_string(
                 area.x + i as u16,
                 area.y,
-                "▌",
-                Style::default()
-                    .fg(palette.base_color(base1))
-                    .bg(palette.base_color(base2)),
+                palette.glyphs.left_half(),
+                cpg_style(
+                    repeat_style(
+                        Style::default()
+                            .fg(palette.sequence_base_color(base1))
+                            .bg(palette.sequence_base_color(base2)),
+                        in_repeat
+                            .get(i * 2..i * 2 + 2)
+                            .is_some_and(|cell| cell.contains(&true)),
+                    ),
+                    in_cpg
+                        .get(i * 2..i * 2 + 2)
+                        .is_some_and(|cell| cell.contains(&true)),
+                ),
             );
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::DARK_THEME;
+    use gv_core::intervals::Focus;
+    use ratatui::style::Modifier;
+    use rstest::rstest;
+
+    fn region(position: u64, half_width: u64) -> Region {
+        Region {
+            focus: Focus {
+                contig_index: 0,
+                position,
+            },
+            half_width,
+        }
+    }
+
+    #[rstest]
+    #[case(false, true, b"ACgtN".to_vec())]
+    #[case(false, false, b"ACGTN".to_vec())]
+    #[case(true, true, b"TGcaN".to_vec())]
+    fn test_displayed_bases(
+        #[case] revcomp: bool,
+        #[case] show_masking: bool,
+        #[case] expected: Vec<u8>,
+    ) {
+        let sequence = Sequence {
+            start: 1,
+            sequence: b"ACgtN".to_vec(),
+            contig_index: 0,
+        };
+        assert_eq!(
+            displayed_bases(&sequence, &region(3, 2), revcomp, show_masking),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn soft_masked_bases_are_dimmed() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        render_sequence_at_1x(
+            &area,
+            &mut buf,
+            Some(b"AagG".to_vec()),
+            &[],
+            &[],
+            &DARK_THEME,
+        )
+        .unwrap();
+
+        let symbols = (0..4).map(|x| buf[(x, 0)].symbol()).collect::<String>();
+        assert_eq!(symbols, "AagG");
+        let dimmed = (0..4)
+            .map(|x| buf[(x, 0)].modifier.contains(Modifier::DIM))
+            .collect::<Vec<_>>();
+        assert_eq!(dimmed, [false, true, true, false]);
+
+        assert_eq!(buf[(0, 0)].bg, DARK_THEME.BASE_A);
+        assert_eq!(buf[(3, 0)].bg, DARK_THEME.BASE_G);
+        assert_ne!(buf[(1, 0)].bg, DARK_THEME.BASE_A);
+        assert_ne!(buf[(1, 0)].bg, DARK_THEME.background);
+    }
+
+    #[test]
+    fn repeats_are_underlined() {
+        let bases = b"GAAAAATCACACAG".to_vec();
+        let in_repeat = repeat_mask(&bases, 5);
+        let area = Rect::new(0, 0, bases.len() as u16, 1);
+        let mut buf = Buffer::empty(area);
+        render_sequence_at_1x(&area, &mut buf, Some(bases), &in_repeat, &[], &DARK_THEME).unwrap();
+
+        let underlined = (0..area.width)
+            .map(|x| {
+                if buf[(x, 0)].modifier.contains(Modifier::UNDERLINED) {
+                    '^'
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>();
+        assert_eq!(underlined, " ^^^^^ ^^^^^^ ");
+    }
+
+    #[test]
+    fn cpgs_are_bold() {
+        let bases = b"ACGTcgAG".to_vec();
+        let in_cpg = cpg_mask(&bases);
+        let area = Rect::new(0, 0, bases.len() as u16, 1);
+        let mut buf = Buffer::empty(area);
+        render_sequence_at_1x(&area, &mut buf, Some(bases), &[], &in_cpg, &DARK_THEME).unwrap();
+
+        let bold = (0..area.width)
+            .map(|x| {
+                if buf[(x, 0)].modifier.contains(Modifier::BOLD) {
+                    '^'
+                } else {
+                    ' '
+                }
+            })
+            .collect::<String>();
+        assert_eq!(bold, " ^^ ^^  ");
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{
+    layout::AlignmentView,
+    rendering::{
+        GlyphSet,
+        colors::Palette,
+        glyphs::{braille_bars, hanging_braille_bars},
+    },
+};
+use gv_core::{bigwig::BigWigTrack, error::TGVError};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render a bigWig signal as a histogram. Each column shows the minimum and maximum signal in the column: positive
+/// values are drawn upward from zero and negative values downward, each scaled to the extreme in view. With braille
+/// glyphs, each cell holds two columns. Positions without data are drawn as zero. The first row shows the scale if
+/// the area is taller than one row.
+pub fn render_signal(
+    area: &Rect,
+    buf: &mut Buffer,
+    signal: &BigWigTrack,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let plot_area = if area.height > MIN_AREA_HEIGHT {
+        Rect::new(area.x, area.y + 1, area.width, area.height - 1)
+    } else {
+        *area
+    };
+
+    let braille = palette.glyphs == GlyphSet::Braille;
+    let ranges = column_value_ranges(
+        &plot_area,
+        signal,
+        alignment_view,
+        if braille { 2 } else { 1 },
+    );
+    let y_min = ranges
+        .iter()
+        .flatten()
+        .map(|(min, _)| *min)
+        .fold(0.0, f32::min);
+    let y_max = ranges
+        .iter()
+        .flatten()
+        .map(|(_, max)| *max)
+        .fold(0.0, f32::max);
+
+    if area.height > MIN_AREA_HEIGHT {
+        let scale = if y_min < 0.0 {
+            format!(
+                "[{}, {}]",
+                format_signal_value(y_min),
+                format_signal_value(y_max)
+            )
+        } else {
+            format!("[0-{}]", format_signal_value(y_max))
+        };
+        buf.set_string(area.x, area.y, scale, Style::default());
+    }
+
+    if y_max <= y_min {
+        return Ok(());
+    }
+
+    // Rows below zero, in proportion to the negative part of the scale. Both parts get a row if there are both
+    // positive and negative values.
+    let mut negative_rows =
+        (-y_min as f64 / (y_max - y_min) as f64 * plot_area.height as f64).round() as u16;
+    if y_min < 0.0 && y_max > 0.0 && plot_area.height > 1 {
+        negative_rows = negative_rows.clamp(1, plot_area.height - 1);
+    }
+    let positive_area = Rect::new(
+        plot_area.x,
+        plot_area.y,
+        plot_area.width,
+        plot_area.height - negative_rows,
+    );
+    let negative_area = Rect::new(
+        plot_area.x,
+        positive_area.bottom(),
+        plot_area.width,
+        negative_rows,
+    );
+
+    let style = Style::default().fg(palette.SIGNAL_COLOR);
+    let maxima = ranges
+        .iter()
+        .map(|range| range.map_or(0.0, |(_, max)| max.max(0.0)))
+        .collect::<Vec<_>>();
+    let minima = ranges
+        .iter()
+        .map(|range| range.map_or(0.0, |(min, _)| (-min).max(0.0)))
+        .collect::<Vec<_>>();
+    for (area, values, y_extreme, hanging) in [
+        (positive_area, maxima, y_max, false),
+        (negative_area, minima, -y_min, true),
+    ] {
+        if y_extreme <= 0.0 || area.height == 0 {
+            continue;
+        }
+        if braille {
+            render_braille_signal(&area, buf, &values, y_extreme, hanging, style);
+        } else {
+            render_block_signal(
+                &area,
+                buf,
+                &values,
+                y_extreme,
+                hanging,
+                palette.glyphs,
+                style,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Minimum and maximum signal of each column, with columns_per_cell columns per cell. A cell narrower than
+/// columns_per_cell bases has its range in each of its columns. None for cells outside the contig.
+fn column_value_ranges(
+    area: &Rect,
+    signal: &BigWigTrack,
+    alignment_view: &AlignmentView,
+    columns_per_cell: u64,
+) -> Vec<Option<(f32, f32)>> {
+    (0..area.width)
+        .flat_map(|x| {
+            let range = alignment_view.coordinates_of_onscreen_x(area.x + x, area);
+            (0..columns_per_cell).map(move |i| {
+                range.map(|(left, right)| {
+                    let width = right - left + 1;
+                    if width < columns_per_cell {
+                        return signal.value_range(left, right);
+                    }
+                    signal.value_range(
+                        left + width * i / columns_per_cell,
+                        left + width * (i + 1) / columns_per_cell - 1,
+                    )
+                })
+            })
+        })
+        .collect()
+}
+
+/// Render non-negative values as bars of block glyphs, one column per cell, scaled so that y_max fills the area. Bars
+/// grow from the bottom of the area, or hang from the top of the area if hanging.
+fn render_block_signal(
+    area: &Rect,
+    buf: &mut Buffer,
+    values: &[f32],
+    y_max: f32,
+    hanging: bool,
+    glyphs: GlyphSet,
+    style: Style,
+) {
+    for (x, value) in values.iter().enumerate() {
+        // Height in rows from the zero line.
+        let height = *value as f64 / y_max as f64 * area.height as f64;
+        for j in 0..area.height {
+            let fraction = height - j as f64;
+            if fraction <= 0.0 {
+                break;
+            }
+            let (y, glyph) = if hanging {
+                (area.y + j, glyphs.hanging_bar(fraction))
+            } else {
+                (area.bottom() - 1 - j, glyphs.bar(fraction))
+            };
+            buf.set_string(area.x + x as u16, y, glyph, style);
+        }
+    }
+}
+
+/// Render non-negative values with braille patterns, two columns and four rows of dots per cell, scaled so that y_max
+/// fills the area. Bars grow from the bottom of the area, or hang from the top of the area if hanging.
+fn render_braille_signal(
+    area: &Rect,
+    buf: &mut Buffer,
+    values: &[f32],
+    y_max: f32,
+    hanging: bool,
+    style: Style,
+) {
+    let n_dots = area.height as usize * GlyphSet::BRAILLE_CELL_HEIGHT;
+    let dots = values
+        .iter()
+        .map(|value| (*value as f64 / y_max as f64 * n_dots as f64).round() as usize)
+        .collect::<Vec<_>>();
+
+    for x in 0..area.width as usize {
+        for j in 0..area.height as usize {
+            // j = 0 is the row at the zero line
+            let floor = j * GlyphSet::BRAILLE_CELL_HEIGHT;
+            let (left_dots, right_dots) = (
+                dots[2 * x].saturating_sub(floor),
+                dots[2 * x + 1].saturating_sub(floor),
+            );
+            if left_dots == 0 && right_dots == 0 {
+                break;
+            }
+            let (y, glyph) = if hanging {
+                (
+                    area.y + j as u16,
+                    hanging_braille_bars(left_dots, right_dots),
+                )
+            } else {
+                (
+                    area.bottom() - 1 - j as u16,
+                    braille_bars(left_dots, right_dots),
+                )
+            };
+            buf[(area.x + x as u16, y)].set_char(glyph).set_style(style);
+        }
+    }
+}
+
+/// Signal value with up to three significant digits.
+fn format_signal_value(value: f32) -> String {
+    if value.abs() >= 100.0 {
+        format!("{:.0}", value)
+    } else if value.abs() >= 10.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::DARK_THEME;
+    use gv_core::{
+        bigwig::SignalInterval,
+        intervals::{Focus, Region},
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(GlyphSet::Block, "█", "▄")]
+    #[case(GlyphSet::Braille, "⣿", "⢠")]
+    fn signal_is_drawn_with_the_palette_glyphs(
+        #[case] glyphs: GlyphSet,
+        #[case] first_cell: &str,
+        #[case] second_cell: &str,
+    ) {
+        let area = Rect::new(0, 0, 4, 1);
+        let focus = Focus {
+            contig_index: 0,
+            position: 100,
+        };
+        let mut alignment_view = AlignmentView::new(focus.clone(), 0);
+        alignment_view.zoom = 2;
+        let (left, _) = alignment_view.coordinates_of_onscreen_x(0, &area).unwrap();
+        // Cell 0: 1.0 in both columns. Cell 1: 0 in the left column and 0.5 in the right one.
+        let signal = BigWigTrack::new(
+            Region {
+                focus,
+                half_width: 100,
+            },
+            vec![
+                SignalInterval {
+                    start: left,
+                    end: left + 1,
+                    value: 1.0,
+                },
+                SignalInterval {
+                    start: left + 3,
+                    end: left + 3,
+                    value: 0.5,
+                },
+            ],
+        );
+        let palette = Palette {
+            glyphs,
+            ..DARK_THEME
+        };
+        let mut buf = Buffer::empty(area);
+        render_signal(&area, &mut buf, &signal, &alignment_view, &palette).unwrap();
+
+        assert_eq!(buf[(0, 0)].symbol(), first_cell);
+        assert_eq!(buf[(1, 0)].symbol(), second_cell);
+        assert_eq!(buf[(2, 0)].symbol(), " ");
+    }
+
+    #[test]
+    fn negative_signal_is_drawn_downward_from_zero() {
+        let area = Rect::new(0, 0, 4, 3);
+        let focus = Focus {
+            contig_index: 0,
+            position: 100,
+        };
+        let mut alignment_view = AlignmentView::new(focus.clone(), 0);
+        alignment_view.zoom = 2;
+        let plot_area = Rect::new(0, 1, 4, 2);
+        let (left, _) = alignment_view
+            .coordinates_of_onscreen_x(0, &plot_area)
+            .unwrap();
+        // Cell 0: 1.0. Cell 1: -1.0.
+        let signal = BigWigTrack::new(
+            Region {
+                focus,
+                half_width: 100,
+            },
+            vec![
+                SignalInterval {
+                    start: left,
+                    end: left + 1,
+                    value: 1.0,
+                },
+                SignalInterval {
+                    start: left + 2,
+                    end: left + 3,
+                    value: -1.0,
+                },
+            ],
+        );
+        let mut buf = Buffer::empty(area);
+        render_signal(&area, &mut buf, &signal, &alignment_view, &DARK_THEME).unwrap();
+
+        let row = |y: u16| (0..4).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert_eq!(row(0), "[-1.");
+        assert_eq!(row(1), "█   ");
+        assert_eq!(row(2), " █  ");
+    }
+
+    #[rstest]
+    #[case(0.0, "0.00")]
+    #[case(2.5, "2.50")]
+    #[case(12.34, "12.3")]
+    #[case(1234.4, "1234")]
+    #[case(-12.34, "-12.3")]
+    fn test_format_signal_value(#[case] value: f32, #[case] expected: &str) {
+        assert_eq!(format_signal_value(value), expected);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{register::Registers, snapshot::SnapshotGallery};
+use gv_core::error::TGVError;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+};
+
+const MIN_AREA_WIDTH: u16 = 2;
+const MIN_AREA_HEIGHT: u16 = 2;
+
+/// Render the snapshot under the cursor below a one-line title.
+pub fn render_snapshots(
+    area: &Rect,
+    buf: &mut Buffer,
+    snapshots: &SnapshotGallery,
+    registers: &Registers,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let Some(snapshot) = snapshots.snapshots.get(registers.snapshot_cursor) else {
+        buf.set_string(
+            area.x,
+            area.y,
+            "No snapshots. Use :snapshot [label] to capture the current view. <ESC> to close.",
+            Style::default(),
+        );
+        return Ok(());
+    };
+
+    buf.set_string(
+        area.x,
+        area.y,
+        format!(
+            "Snapshot {}/{}: {}    h/l: previous/next  <ESC>: close",
+            registers.snapshot_cursor + 1,
+            snapshots.len(),
+            snapshot.label
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    );
+
+    // The snapshot is clipped if the terminal shrank since capture.
+    let source_area = snapshot.buffer.area;
+    for dy in 0..u16::min(source_area.height, area.height - 1) {
+        for dx in 0..u16::min(source_area.width, area.width) {
+            if let Some(source_cell) = snapshot
+                .buffer
+                .cell(Position::new(source_area.x + dx, source_area.y + dy))
+                && let Some(cell) = buf.cell_mut(Position::new(area.x + dx, area.y + 1 + dy))
+            {
+                *cell = source_cell.clone();
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,27 @@
-use gv_core::{error::TGVError, state::State};
+use clap::ValueEnum;
+use gv_core::{
+    alignment::{Alignment, HiddenReadCategory},
+    error::TGVError,
+    intervals::Focus,
+    message::{AlignmentColor, AlignmentDisplayOption, CoordinateBase, Downsample},
+    reference::Reference,
+    sequence::Sequence,
+    state::State,
+};
 
 use itertools::Itertools;
-use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style},
+};
+use std::collections::HashSet;
+use std::ops::Range;
 
-use crate::layout::AlignmentView;
+use crate::{
+    layout::AlignmentView,
+    rendering::{Palette, Theme, coordinate::to_thousand_separated},
+};
 
 pub fn render_status_bar(
     area: &Rect,
@@ -11,31 +29,43 @@ pub fn render_status_bar(
     state: &State,
     alignment_view: &AlignmentView,
     hovered_alignment: Option<usize>,
+    palette: &Palette,
 ) -> Result<(), TGVError> {
     if area.width < 1 || area.height < 2 {
         return Ok(());
     }
 
-    // Messages
-    let index_start = state.messages.len().saturating_sub(area.height as usize);
-    let index_end = state.messages.len();
-
-    if index_start < index_end {
-        for (i, error) in state.messages[index_start..index_end].iter().enumerate() {
-            if i >= area.height as usize {
-                break;
-            }
-            buf.set_string(area.x, area.y + i as u16, error.clone(), Style::default());
-        }
+    // Pinned read details, then messages
+    let message_rows = (area.height as usize).saturating_sub(state.pinned_read.is_some() as usize);
+    let index_start = state.messages.len().saturating_sub(message_rows);
+    for (i, message) in state
+        .pinned_read
+        .iter()
+        .chain(state.messages[index_start..].iter())
+        .enumerate()
+    {
+        buf.set_string(area.x, area.y + i as u16, message.clone(), Style::default());
     }
 
     // X and y coordinates
 
-    let x_coordinate_string = format!(
+    let focus = alignment_view.cursor_focus();
+    let mut x_coordinate_string = format!(
         "{}: {}",
-        state.contig_name(&alignment_view.focus)?,
-        alignment_view.focus.position
+        state.contig_name(&focus)?,
+        displayed_position(state.coordinate_base, focus.position)
     );
+    if let Some(progress) = state.loci.progress() {
+        x_coordinate_string = format!("Locus {progress}  {x_coordinate_string}");
+    }
+    if alignment_view.cursor.is_some()
+        && let Some(context) = reference_context(
+            (state.reference != Reference::NoReference).then_some(&state.sequence),
+            &focus,
+        )
+    {
+        x_coordinate_string += &format!(" ({context})");
+    }
 
     let alignment_index = (!state.alignments.is_empty()).then(|| hovered_alignment.unwrap_or(0));
     let mut y_coordinate_string = if let Some(alignment_index) = alignment_index {
@@ -52,27 +82,54 @@ pub fn render_status_bar(
         String::new()
     };
 
-    // Alignment options
+    // Shown and hidden read counts
 
     if let Some(alignment_index) = alignment_index {
-        let alignment_options = &state.alignment_options[alignment_index];
-        if !alignment_options.is_empty() {
-            let alignment_option_string = alignment_options
-                .iter()
-                .map(|option| format!("{}", option))
-                .join(",");
+        let alignment = &state.alignments[alignment_index];
+        let shown = alignment.show_read.iter().filter(|show| **show).count();
+        y_coordinate_string += &format!(
+            "  {} shown, {} hidden",
+            to_thousand_separated(shown as u64),
+            to_thousand_separated(alignment.hidden_read_counts.total() as u64)
+        );
+        let downsampled = alignment.downsampled_count();
+        if downsampled > 0 {
+            y_coordinate_string += &format!(
+                ", {} downsampled",
+                to_thousand_separated(downsampled as u64)
+            );
+        }
+    }
+    // Badges of active filters and modes, in the room left of the coordinates
 
-            y_coordinate_string = y_coordinate_string + " (" + &alignment_option_string + ")";
+    let tag_legend = alignment_index.and_then(|alignment_index| {
+        let alignment = &state.alignments[alignment_index];
+        match &alignment.color {
+            AlignmentColor::Tag(tag) => Some(TagLegend::new(alignment, tag, palette)),
+            _ => None,
         }
+    });
+    let badges = status_badges(state, alignment_index, tag_legend.as_ref(), palette.theme);
+    let coordinate_width = if area.height == 1 {
+        x_coordinate_string.len() + 2 + y_coordinate_string.len()
+    } else {
+        y_coordinate_string.len()
+    };
+    let badge_string = fit_badges(
+        &badges,
+        (area.width as usize).saturating_sub(coordinate_width + 2),
+    );
+    if !badge_string.is_empty() {
+        y_coordinate_string = badge_string + "  " + &y_coordinate_string;
     }
+
     if area.height == 1 {
         let string = x_coordinate_string + "  " + &y_coordinate_string;
-        buf.set_string(
-            area.x + area.width.saturating_sub(string.len() as u16),
-            area.y,
-            string,
-            Style::default(),
-        );
+        let x = area.x + area.width.saturating_sub(string.len() as u16);
+        buf.set_string(x, area.y, &string, Style::default());
+        if let Some(tag_legend) = &tag_legend {
+            tag_legend.color(buf, x, area.y, &string);
+        }
     } else if area.height > 1 {
         buf.set_string(
             area.x + area.width.saturating_sub(x_coordinate_string.len() as u16),
@@ -81,13 +138,302 @@ pub fn render_status_bar(
             Style::default(),
         );
 
-        buf.set_string(
-            area.x + area.width.saturating_sub(y_coordinate_string.len() as u16),
-            area.y + 1,
-            y_coordinate_string,
-            Style::default(),
-        );
+        let x = area.x + area.width.saturating_sub(y_coordinate_string.len() as u16);
+        buf.set_string(x, area.y + 1, &y_coordinate_string, Style::default());
+        if let Some(tag_legend) = &tag_legend {
+            tag_legend.color(buf, x, area.y + 1, &y_coordinate_string);
+        }
     }
 
     Ok(())
 }
+
+/// Reference bases shown on each side of the base at the cursor.
+const REFERENCE_CONTEXT_FLANK: u64 = 10;
+
+/// Reference base at the focus in uppercase between 10-bp flanks in lowercase, e.g. `acgtacgtacGttagcatgca`, for
+/// reading off primers. `N/A` without a reference, and None if the base is not loaded.
+fn reference_context(sequence: Option<&Sequence>, focus: &Focus) -> Option<String> {
+    let Some(sequence) = sequence else {
+        return Some("N/A".to_string());
+    };
+    if sequence.contig_index != focus.contig_index {
+        return None;
+    }
+    let (left, base, right) = sequence.base_context(focus.position, REFERENCE_CONTEXT_FLANK)?;
+    Some(
+        [
+            String::from_utf8_lossy(left).to_ascii_lowercase(),
+            char::from(base).to_ascii_uppercase().to_string(),
+            String::from_utf8_lossy(right).to_ascii_lowercase(),
+        ]
+        .concat(),
+    )
+}
+
+/// Values of `:color tag` listed in the legend. Values past them are counted, e.g. `HP: 1 2 3 4 +2 none`.
+const TAG_LEGEND_VALUES: usize = 4;
+
+/// Legend of `:color tag`: the tag and its values on the shown reads, each in its color. Reads without the tag are
+/// listed as `none`.
+struct TagLegend {
+    text: String,
+
+    /// Byte ranges of the values in the text, and their colors.
+    colors: Vec<(Range<usize>, Color)>,
+}
+
+impl TagLegend {
+    fn new(alignment: &Alignment, tag: &str, palette: &Palette) -> Self {
+        let values = alignment
+            .reads
+            .iter()
+            .zip(alignment.show_read.iter())
+            .filter(|(_, show)| **show)
+            .map(|(read, _)| read.tag_value(tag))
+            .collect::<HashSet<_>>();
+        Self::from_values(tag, values, palette)
+    }
+
+    fn from_values(tag: &str, values: HashSet<Option<String>>, palette: &Palette) -> Self {
+        // Integers in numeric order, then strings
+        let sorted_values = values
+            .iter()
+            .flatten()
+            .sorted_by_key(|value| match value.parse::<i64>() {
+                Ok(n) => (false, n, ""),
+                Err(_) => (true, 0, value.as_str()),
+            })
+            .collect::<Vec<_>>();
+
+        let mut text = format!("{tag}:");
+        let mut colors = Vec::new();
+        let mut push = |text: &mut String, value: &str, color: Color| {
+            text.push(' ');
+            colors.push((text.len()..text.len() + value.len(), color));
+            text.push_str(value);
+        };
+        for value in sorted_values.iter().take(TAG_LEGEND_VALUES) {
+            push(&mut text, value, palette.tag_color(Some(value)));
+        }
+        if sorted_values.len() > TAG_LEGEND_VALUES {
+            text += &format!(" +{}", sorted_values.len() - TAG_LEGEND_VALUES);
+        }
+        if values.contains(&None) {
+            push(&mut text, "none", palette.tag_color(None));
+        }
+        Self { text, colors }
+    }
+
+    /// Color the values of the legend badge in a status bar row drawn at x.
+    fn color(&self, buf: &mut Buffer, x: u16, y: u16, row: &str) {
+        let Some(offset) = row.find(&format!("[{}]", self.text)) else {
+            return;
+        };
+        for (range, color) in self.colors.iter() {
+            for i in range.clone() {
+                if let Some(cell) = buf.cell_mut(Position::new(x + (offset + 1 + i) as u16, y)) {
+                    cell.set_fg(*color);
+                }
+            }
+        }
+    }
+}
+
+/// Active filters and modes: reads left unloaded by the max reads cap, the display options of the alignment track,
+/// downsampling, hidden reads shown, the minus strand, 0-based coordinates, a theme other than the default, and where
+/// the reference is served from.
+fn status_badges(
+    state: &State,
+    alignment_index: Option<usize>,
+    tag_legend: Option<&TagLegend>,
+    theme: Theme,
+) -> Vec<String> {
+    let mut badges = Vec::new();
+    if let Some(alignment_index) = alignment_index {
+        // Listed first so that it is the last badge to be dropped for room.
+        let alignment = &state.alignments[alignment_index];
+        if alignment.overflow {
+            badges.push(format!(
+                "Showing {} of many reads",
+                to_thousand_separated(alignment.reads.len() as u64)
+            ));
+        }
+
+        badges.extend(
+            state.alignment_options[alignment_index]
+                .iter()
+                .map(|option| match option {
+                    AlignmentDisplayOption::Filter(filter) => filter.to_string(),
+                    AlignmentDisplayOption::ViewAsPairs => "Pairs".to_string(),
+                    AlignmentDisplayOption::Color(AlignmentColor::Tag(_)) => tag_legend
+                        .map(|tag_legend| tag_legend.text.clone())
+                        .unwrap_or_else(|| option.to_string()),
+                    option => option.to_string(),
+                }),
+        );
+
+        match state.downsample {
+            Downsample::Off => badges.push("Downsample off".to_string()),
+            Downsample::Depth(depth) => badges.push(format!("Downsample {depth}")),
+            Downsample::Auto => {
+                if let Some(depth) = alignment.downsample_depth
+                    && alignment.downsampled_count() > 0
+                {
+                    badges.push(format!("Downsample {depth}"));
+                }
+            }
+        }
+    }
+    for category in HiddenReadCategory::all() {
+        match (
+            state.shown_read_categories.contains(&category),
+            HiddenReadCategory::shown_by_default().contains(&category),
+        ) {
+            (true, false) => badges.push(format!("Show {category}")),
+            (false, true) => badges.push(format!("Hide {category}")),
+            _ => {}
+        }
+    }
+    if state.revcomp {
+        badges.push("Minus strand".to_string());
+    }
+    if state.coordinate_base != CoordinateBase::default() {
+        badges.push(state.coordinate_base.to_string());
+    }
+    if theme != Theme::default()
+        && let Some(value) = theme.to_possible_value()
+    {
+        badges.push(value.get_name().to_string());
+    }
+    if let Some(data_source) = state.data_source {
+        badges.push(data_source.to_string());
+    }
+    badges
+}
+
+/// A 1-based position as displayed: the position itself, or the half-open interval of the base in 0-based mode, e.g.
+/// `99-100` for position 100.
+fn displayed_position(coordinate_base: CoordinateBase, position: u64) -> String {
+    match coordinate_base.range(position, position) {
+        (start, end) if start == end => start.to_string(),
+        (start, end) => format!("{start}-{end}"),
+    }
+}
+
+/// Badges in brackets that fit in width. Badges that don't fit are dropped from the end and counted, e.g.
+/// `[MAPQ>=20] [+2]`.
+fn fit_badges(badges: &[String], width: usize) -> String {
+    (0..=badges.len())
+        .rev()
+        .map(|n| {
+            badges[..n]
+                .iter()
+                .map(|badge| format!("[{badge}]"))
+                .chain((n < badges.len()).then(|| format!("[+{}]", badges.len() - n)))
+                .join(" ")
+        })
+        .find(|string| string.len() <= width)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(vec![], 80, "")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 80, "[MAPQ>=20] [Pairs] [light]")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 26, "[MAPQ>=20] [Pairs] [light]")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 25, "[MAPQ>=20] [Pairs] [+1]")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 15, "[MAPQ>=20] [+2]")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 4, "[+3]")]
+    #[case(vec!["MAPQ>=20", "Pairs", "light"], 3, "")]
+    fn badges_are_truncated_to_the_width(
+        #[case] badges: Vec<&str>,
+        #[case] width: usize,
+        #[case] expected: &str,
+    ) {
+        let badges = badges.into_iter().map(String::from).collect::<Vec<_>>();
+        assert_eq!(fit_badges(&badges, width), expected);
+    }
+
+    #[rstest]
+    #[case(CoordinateBase::One, 100, "100")]
+    #[case(CoordinateBase::Zero, 100, "99-100")]
+    #[case(CoordinateBase::One, 1, "1")]
+    #[case(CoordinateBase::Zero, 1, "0-1")]
+    fn positions_are_displayed_in_the_coordinate_base(
+        #[case] coordinate_base: CoordinateBase,
+        #[case] position: u64,
+        #[case] expected: &str,
+    ) {
+        assert_eq!(displayed_position(coordinate_base, position), expected);
+    }
+
+    #[rstest]
+    #[case(100, Some("acgcgcgcgcTatatatatat"))]
+    #[case(91, Some("aaaaaaaaaaCgcgcgcgcta"))]
+    // Flanks are clipped at the sequence start.
+    #[case(3, Some("aaAaaaaaaaaaa"))]
+    #[case(201, None)]
+    fn reference_context_is_the_base_between_its_flanks(
+        #[case] position: u64,
+        #[case] expected: Option<&str>,
+    ) {
+        // A at 1..=90, CG repeats at 91..=99, T at 100, then AT repeats at 101..=200.
+        let mut bases = [b"A".repeat(90), b"CG".repeat(5), b"AT".repeat(50)].concat();
+        bases[99] = b'T';
+        let sequence = Sequence {
+            start: 1,
+            sequence: bases,
+            contig_index: 0,
+        };
+        let focus = Focus {
+            contig_index: 0,
+            position,
+        };
+        let context = reference_context(Some(&sequence), &focus);
+        assert_eq!(context.as_deref(), expected);
+
+        // The base is the one at the position, between the flanks extracted from the sequence.
+        if let Some(context) = context {
+            let (left, base, right) = sequence
+                .base_context(position, REFERENCE_CONTEXT_FLANK)
+                .unwrap();
+            assert_eq!(base, sequence.base_at(position).unwrap());
+            assert_eq!(context.len(), left.len() + 1 + right.len());
+            assert_eq!(context.as_bytes()[left.len()], base.to_ascii_uppercase());
+        }
+    }
+
+    #[test]
+    fn reference_context_without_a_reference_is_na() {
+        let focus = Focus {
+            contig_index: 0,
+            position: 1,
+        };
+        assert_eq!(reference_context(None, &focus).as_deref(), Some("N/A"));
+    }
+
+    #[rstest]
+    #[case(vec![Some("2"), Some("1"), None], "HP: 1 2 none")]
+    #[case(vec![Some("10"), Some("9"), Some("-1")], "HP: -1 9 10")]
+    #[case(vec![Some("b"), Some("a"), Some("3"), Some("20"), Some("1")], "HP: 1 3 20 a +1")]
+    #[case(vec![None], "HP: none")]
+    fn tag_legends_list_sorted_values(#[case] values: Vec<Option<&str>>, #[case] expected: &str) {
+        let palette = crate::rendering::colors::DARK_THEME;
+        let values = values
+            .into_iter()
+            .map(|value| value.map(String::from))
+            .collect::<HashSet<_>>();
+        let legend = TagLegend::from_values("HP", values, &palette);
+        assert_eq!(legend.text, expected);
+        for (range, color) in legend.colors {
+            let value = &legend.text[range];
+            let expected_color = palette.tag_color((value != "none").then_some(value));
+            assert_eq!(color, expected_color, "{value}");
+        }
+    }
+}
@@ -219,7 +219,7 @@ fn get_feature_segment_string_and_style(
             .map(|i| if i % EXON_ARROW_GAP == 0 { ">" } else { " " })
             .collect::<String>(),
         (Strand::Forward, SubGeneFeatureType::NonCDSExon) => {
-            (0..length).map(|_| "▅").collect::<String>()
+            pallete.glyphs.block().repeat(length as usize)
         }
         (Strand::Forward, SubGeneFeatureType::Intron) => (0..length)
             .map(|i| if i % INTRON_ARROW_GAP == 0 { ">" } else { "-" })
@@ -228,7 +228,7 @@ fn get_feature_segment_string_and_style(
             .map(|i| if i % EXON_ARROW_GAP == 0 { "<" } else { "-" })
             .collect::<String>(),
         (Strand::Reverse, SubGeneFeatureType::NonCDSExon) => {
-            (0..length).map(|_| "▅").collect::<String>()
+            pallete.glyphs.block().repeat(length as usize)
         }
         (Strand::Reverse, SubGeneFeatureType::Intron) => (0..length)
             .map(|i| if i % INTRON_ARROW_GAP == 0 { "<" } else { "-" })
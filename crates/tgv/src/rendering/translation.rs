@@ -0,0 +1,69 @@
+use crate::{
+    layout::{AlignmentView, OnScreenCoordinate},
+    rendering::colors::Palette,
+};
+use gv_core::{error::TGVError, intervals::GenomeInterval, state::State};
+use ratatui::{buffer::Buffer, layout::Rect, style::Style};
+
+const MIN_AREA_WIDTH: u16 = 3;
+const MIN_AREA_HEIGHT: u16 = 1;
+
+/// Render the amino acids of coding sequences in view. Each codon is drawn over its three bases with the one-letter
+/// amino acid at the middle base. Minus-strand genes are read on the reverse complement. Codons with bases outside of
+/// the loaded sequence are skipped.
+pub fn render_translation(
+    area: &Rect,
+    buf: &mut Buffer,
+    state: &State,
+    alignment_view: &AlignmentView,
+    palette: &Palette,
+) -> Result<(), TGVError> {
+    if area.width < MIN_AREA_WIDTH || area.height < MIN_AREA_HEIGHT {
+        return Ok(());
+    }
+
+    let region = alignment_view.region(area);
+    if state.sequence.contig_index != region.contig_index() {
+        return Ok(());
+    }
+
+    let genetic_code = state.genetic_code(&alignment_view.focus)?;
+    for gene in state
+        .track
+        .genes()
+        .iter()
+        .filter(|gene| gene.contig_index == region.contig_index())
+    {
+        for (residue, positions) in gene.codons_overlapping(region.start(), region.end()) {
+            let Some(amino_acid) = state
+                .sequence
+                .codon(&positions, &gene.strand)
+                .and_then(|codon| genetic_code.translate(&codon))
+            else {
+                continue;
+            };
+
+            let background = match amino_acid {
+                b'*' => palette.STOP_CODON_BACKGROUND_COLOR,
+                b'M' => palette.START_CODON_BACKGROUND_COLOR,
+                _ if residue % 2 == 1 => palette.CODON_BACKGROUND_COLOR,
+                _ => palette.CODON_ALT_BACKGROUND_COLOR,
+            };
+            let style = Style::default()
+                .fg(palette.EXON_FOREGROUND_COLOR)
+                .bg(background);
+
+            for (i, position) in positions.iter().enumerate() {
+                let OnScreenCoordinate::OnScreen(x) =
+                    alignment_view.onscreen_x_coordinate(*position, area)
+                else {
+                    continue;
+                };
+                let symbol = if i == 1 { amino_acid as char } else { ' ' };
+                buf.set_string(area.x + x as u16, area.y, symbol.to_string(), style);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,8 @@
-use gv_core::{error::TGVError, intervals::GenomeInterval, variant::VariantTrack};
+use gv_core::{
+    error::TGVError,
+    intervals::GenomeInterval,
+    variant::{VariantFilter, VariantTrack},
+};
 
 use crate::{
     layout::AlignmentView,
@@ -6,15 +10,21 @@ use crate::{
 };
 use ratatui::{buffer::Buffer, layout::Rect};
 
+/// Render variants passing the filter.
 pub fn render_variants(
     area: &Rect,
     buf: &mut Buffer,
     variants: &VariantTrack,
+    variant_filter: &VariantFilter,
     alignment_view: &AlignmentView,
     pallete: &Palette,
 ) -> Result<(), TGVError> {
     let region = alignment_view.region(area);
-    let variants = variants.overlapping(region.contig_index(), region.start(), region.end())?;
+    let variants = variants
+        .overlapping(region.contig_index(), region.start(), region.end())?
+        .into_iter()
+        .filter(|variant| variant_filter.passes_variant(variant))
+        .collect::<Vec<_>>();
     if !variants.is_empty() {
         let first_color_index = variants[0].index % 2;
         render_simple_intervals(
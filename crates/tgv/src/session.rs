@@ -4,20 +4,24 @@
 //! restored on the next launch. The file format is documented in the tgv
 //! book under "Session files".
 
-use crate::{app::App, message::Message, settings::Settings};
+use crate::{app::App, layout::TrackHeights, message::Message, settings::Settings};
 use gv_core::{
-    alignment::is_url,
+    alignment::{InsertRange, default_bam_index, path_without_query},
     error::TGVError,
-    message::Movement,
+    message::{AlignmentDisplayOption, AlignmentFilter, Movement},
     reference::Reference,
     settings::{AlignmentPath, BackendType, BamSource, FilePath},
     tracks::UcscHost,
+    variant::VariantFilter,
 };
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
 
 const MIN_SUPPORTED_VERSION: u32 = 1;
-const CURRENT_VERSION: u32 = 2;
+const CURRENT_VERSION: u32 = 3;
 
 /// On-disk representation of a tgv session.
 ///
@@ -33,6 +37,25 @@ pub struct SessionFile {
     pub zoom: u64,
     #[serde(default)]
     pub tracks: Vec<TrackEntry>,
+    /// bigBed gene annotation used as the gene track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bigbed: Option<String>,
+    /// GTF or GFF3 gene annotation used as the gene track.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gtf: Option<String>,
+    /// Track heights in rows.
+    #[serde(default, skip_serializing_if = "TrackHeights::is_empty")]
+    pub heights: TrackHeights,
+    /// Variant filter from `:vcf-filter`.
+    #[serde(default, skip_serializing_if = "is_default")]
+    pub variant_filter: VariantFilter,
+    /// Bookmark name -> locus, from `:mark`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bookmarks: BTreeMap<String, String>,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
 }
 
 impl Default for SessionFile {
@@ -44,6 +67,11 @@ impl Default for SessionFile {
             ucsc_host: UcscHost::auto(),
             zoom: 1,
             tracks: Vec::new(),
+            bigbed: None,
+            gtf: None,
+            heights: TrackHeights::default(),
+            variant_filter: VariantFilter::default(),
+            bookmarks: BTreeMap::new(),
         }
     }
 }
@@ -61,6 +89,9 @@ pub struct TrackEntry {
     /// CRAM only: `.fai` index for the decoding FASTA. Inferred as `reference + ".fai"` when absent.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reference_index: Option<String>,
+    /// BAM and CRAM only: alignment filters of the track, e.g. from `:filter` and `:mapq`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<AlignmentFilter>,
 }
 
 // ─── AlignmentPath → TrackEntry ─────────────────────────────────────────────
@@ -75,6 +106,7 @@ impl TryFrom<&AlignmentPath> for TrackEntry {
                 index: Some(index.clone()),
                 reference: None,
                 reference_index: None,
+                filters: Vec::new(),
             }),
             AlignmentPath::Cram {
                 path,
@@ -86,7 +118,11 @@ impl TryFrom<&AlignmentPath> for TrackEntry {
                 index: Some(crai.clone()),
                 reference: Some(fasta.clone()),
                 reference_index: Some(fai.clone()),
+                filters: Vec::new(),
             }),
+            AlignmentPath::Stdin => Err(TGVError::ValueError(
+                "Reads piped from stdin cannot be saved as a session track".to_string(),
+            )),
         }
     }
 }
@@ -124,7 +160,7 @@ impl SessionFile {
 
     /// Parse a session file from a TOML string.
     pub fn parse(content: &str) -> Result<Self, TGVError> {
-        let session: Self = toml::from_str(content)
+        let mut session: Self = toml::from_str(content)
             .map_err(|e| TGVError::ParsingError(format!("Failed to parse session file: {e}")))?;
         if !(MIN_SUPPORTED_VERSION..=CURRENT_VERSION).contains(&session.version) {
             return Err(TGVError::ParsingError(format!(
@@ -132,9 +168,62 @@ impl SessionFile {
                 session.version, MIN_SUPPORTED_VERSION, CURRENT_VERSION
             )));
         }
+        if session.version < CURRENT_VERSION {
+            session.upgrade()?;
+        }
         Ok(session)
     }
 
+    /// Upgrade a session of version 1 or 2 to the current version.
+    ///
+    /// Version 3 added the gene annotation, track heights, filters, and bookmarks. Older sessions leave them at their
+    /// defaults, so setting any of them is an error rather than being silently read with the older schema.
+    fn upgrade(&mut self) -> Result<(), TGVError> {
+        let has_version_3_fields = self.bigbed.is_some()
+            || self.gtf.is_some()
+            || !self.heights.is_empty()
+            || self.tracks.iter().any(|track| !track.filters.is_empty())
+            || !is_default(&self.variant_filter)
+            || !self.bookmarks.is_empty();
+        if has_version_3_fields {
+            return Err(TGVError::ParsingError(format!(
+                "Session file version {} does not support gene annotations, track heights, filters, or bookmarks. Set `version = {}` to use them.",
+                self.version, CURRENT_VERSION
+            )));
+        }
+        self.version = CURRENT_VERSION;
+        Ok(())
+    }
+
+    /// Drop tracks and gene annotations whose local files do not exist, so that the rest of the session still loads.
+    /// Return a warning for each dropped file. Remote paths (e.g. `s3://`) are kept.
+    pub fn remove_missing_files(&mut self) -> Vec<String> {
+        let is_missing = |path: &str| {
+            !path.contains("://") && !Path::new(shellexpand::tilde(path).as_ref()).exists()
+        };
+        let mut warnings = Vec::new();
+
+        self.tracks.retain(|track| {
+            let missing = is_missing(&track.path);
+            if missing {
+                warnings.push(format!(
+                    "Track file {} does not exist. Skipped.",
+                    track.path
+                ));
+            }
+            !missing
+        });
+        for annotation in [&mut self.bigbed, &mut self.gtf] {
+            if let Some(path) = annotation.take_if(|path| is_missing(path)) {
+                warnings.push(format!(
+                    "Gene annotation file {path} does not exist. Skipped."
+                ));
+            }
+        }
+
+        warnings
+    }
+
     /// Serialize `self` to TOML and write it to `path`, creating parent directories as needed.
     pub fn write_to_path(&self, path: &Path) -> Result<(), TGVError> {
         if let Some(parent) = path.parent() {
@@ -179,17 +268,17 @@ impl TryFrom<SessionFile> for Settings {
         let initial_state_messages = parse_locus(&session.locus)?;
 
         let mut file_paths = Vec::new();
+        let mut alignment_filters = Vec::new();
 
         for track in session.tracks {
-            let lower = track.path.to_lowercase();
+            let lower = path_without_query(&track.path).to_lowercase();
             if lower.ends_with(".bam") {
-                let index = track.index.unwrap_or_else(|| format!("{}.bai", track.path));
+                let index = track
+                    .index
+                    .unwrap_or_else(|| default_bam_index(&track.path));
+                alignment_filters.push(track.filters);
                 file_paths.push(FilePath::AlignmentPath(AlignmentPath::Bam {
-                    source: if is_url(&track.path) {
-                        BamSource::S3
-                    } else {
-                        BamSource::Local
-                    },
+                    source: BamSource::of(&track.path),
                     path: track.path,
                     index,
                 }));
@@ -206,6 +295,7 @@ impl TryFrom<SessionFile> for Settings {
                 let fai = track
                     .reference_index
                     .unwrap_or_else(|| format!("{fasta}.fai"));
+                alignment_filters.push(track.filters);
                 file_paths.push(FilePath::AlignmentPath(AlignmentPath::Cram {
                     path: track.path,
                     crai,
@@ -216,6 +306,8 @@ impl TryFrom<SessionFile> for Settings {
                 file_paths.push(FilePath::VariantPath(track.path));
             } else if lower.ends_with(".bed") || lower.ends_with(".bed.gz") {
                 file_paths.push(FilePath::BedPath(track.path));
+            } else if lower.ends_with(".bw") || lower.ends_with(".bigwig") {
+                file_paths.push(FilePath::BigWigPath(track.path));
             }
         }
 
@@ -226,12 +318,29 @@ impl TryFrom<SessionFile> for Settings {
                 backend: BackendType::Default,
                 ucsc_host: session.ucsc_host,
                 cache_dir: gv_core::settings::Settings::default().cache_dir,
+                bigbed_path: session.bigbed,
+                gtf_path: session.gtf,
+                track_cache: true,
+                gene_track: None,
+                shown_read_categories: gv_core::alignment::HiddenReadCategory::shown_by_default(),
+                insert_range: InsertRange::default(),
+                max_reads: gv_core::settings::DEFAULT_MAX_READS,
+                retry: gv_core::retry::RetryPolicy::default(),
+                source_preference: gv_core::data_source::SourcePreference::default(),
             },
             initial_state_messages,
             zoom: Some(session.zoom),
             test_mode: false,
             debug: false,
             palette: crate::rendering::DARK_THEME,
+            theme: crate::rendering::Theme::Dark,
+            track_heights: session.heights,
+            alignment_filters,
+            variant_filter: session.variant_filter,
+            bookmarks: session.bookmarks,
+            loci_path: None,
+            regions_path: None,
+            keys_path: None,
         })
     }
 }
@@ -253,22 +362,50 @@ impl TryFrom<&App> for SessionFile {
 
         let mut tracks = Vec::new();
 
+        // Alignment tracks are indexed in the order of the alignment paths, including stdin.
+        let mut alignment_options = app.state.alignment_options.iter();
         for file_path in &app.settings.core.file_paths {
             match file_path {
+                // Reads piped from stdin cannot be reopened from a session.
+                FilePath::AlignmentPath(AlignmentPath::Stdin) => {
+                    alignment_options.next();
+                }
                 FilePath::AlignmentPath(alignment_path) => {
-                    tracks.push(TrackEntry::try_from(alignment_path)?);
+                    let filters = alignment_options
+                        .next()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|option| match option {
+                            AlignmentDisplayOption::Filter(filter) => Some(filter.clone()),
+                            _ => None,
+                        })
+                        .collect();
+                    tracks.push(TrackEntry {
+                        filters,
+                        ..TrackEntry::try_from(alignment_path)?
+                    });
                 }
-                FilePath::VariantPath(path) | FilePath::BedPath(path) => {
+                FilePath::VariantPath(path)
+                | FilePath::BedPath(path)
+                | FilePath::BigWigPath(path) => {
                     tracks.push(TrackEntry {
                         path: path.clone(),
                         index: None,
                         reference: None,
                         reference_index: None,
+                        filters: Vec::new(),
                     });
                 }
             }
         }
 
+        let bookmarks = app
+            .state
+            .bookmarks
+            .iter()
+            .map(|(name, focus)| Ok((name.clone(), focus.to_locus_str(&app.state.contig_header)?)))
+            .collect::<Result<_, TGVError>>()?;
+
         Ok(SessionFile {
             version: CURRENT_VERSION,
             locus,
@@ -276,6 +413,158 @@ impl TryFrom<&App> for SessionFile {
             ucsc_host: app.settings.core.ucsc_host.clone(),
             zoom: app.alignment_view.zoom,
             tracks,
+            bigbed: app.settings.core.bigbed_path.clone(),
+            gtf: app.settings.core.gtf_path.clone(),
+            heights: app.layout.track_heights.clone(),
+            variant_filter: app.state.variant_filter.clone(),
+            bookmarks,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gv_core::{strand::Strand, variant::QualThreshold};
+    use rstest::rstest;
+
+    fn track(path: &str) -> TrackEntry {
+        TrackEntry {
+            path: path.to_string(),
+            index: None,
+            reference: None,
+            reference_index: None,
+            filters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn session_round_trips_filters_and_bookmarks() {
+        let session = SessionFile {
+            locus: "chr17:7572659".to_string(),
+            zoom: 4,
+            tracks: vec![
+                TrackEntry {
+                    filters: vec![
+                        AlignmentFilter::MinMapq(20),
+                        AlignmentFilter::Strand(Strand::Reverse)
+                            .and(AlignmentFilter::ReadLengthGE(100)),
+                    ],
+                    ..track("/data/sample.bam")
+                },
+                track("/data/calls.vcf.gz"),
+                TrackEntry {
+                    filters: vec![AlignmentFilter::MinMapq(30)],
+                    ..track("/data/other.bam")
+                },
+            ],
+            variant_filter: VariantFilter {
+                pass_only: true,
+                qual: Some(QualThreshold::Above(30.0)),
+                hide_missing_qual: false,
+            },
+            bookmarks: BTreeMap::from([
+                ("tp53".to_string(), "chr17:7572659".to_string()),
+                ("kras".to_string(), "chr12:25245350".to_string()),
+            ]),
+            ..SessionFile::default()
+        };
+
+        let content = toml::to_string_pretty(&session).unwrap();
+        let settings = Settings::try_from(SessionFile::parse(&content).unwrap()).unwrap();
+
+        assert_eq!(settings.zoom, Some(4));
+        assert_eq!(
+            settings.core.file_paths,
+            vec![
+                FilePath::AlignmentPath(AlignmentPath::Bam {
+                    source: BamSource::of("/data/sample.bam"),
+                    path: "/data/sample.bam".to_string(),
+                    index: "/data/sample.bam.bai".to_string(),
+                }),
+                FilePath::VariantPath("/data/calls.vcf.gz".to_string()),
+                FilePath::AlignmentPath(AlignmentPath::Bam {
+                    source: BamSource::of("/data/other.bam"),
+                    path: "/data/other.bam".to_string(),
+                    index: "/data/other.bam.bai".to_string(),
+                }),
+            ]
+        );
+        assert_eq!(
+            settings.alignment_filters,
+            vec![
+                session.tracks[0].filters.clone(),
+                session.tracks[2].filters.clone()
+            ]
+        );
+        assert_eq!(settings.variant_filter, session.variant_filter);
+        assert_eq!(settings.bookmarks, session.bookmarks);
+    }
+
+    #[test]
+    fn session_without_filters_and_bookmarks_omits_them() {
+        let content = toml::to_string_pretty(&SessionFile::default()).unwrap();
+        assert!(!content.contains("filters"));
+        assert!(!content.contains("variant_filter"));
+        assert!(!content.contains("bookmarks"));
+
+        let settings = Settings::try_from(SessionFile::parse(&content).unwrap()).unwrap();
+        assert!(settings.alignment_filters.is_empty());
+        assert_eq!(settings.variant_filter, VariantFilter::default());
+        assert!(settings.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn older_session_versions_are_upgraded() {
+        let session =
+            SessionFile::parse("version = 2\nlocus = \"chr1:100\"\ngenome = \"hg38\"\nucsc_host = \"auto\"\nzoom = 1\n")
+                .unwrap();
+        assert_eq!(session.version, CURRENT_VERSION);
+    }
+
+    #[rstest]
+    #[case("gtf = \"genes.gtf\"\n")]
+    #[case("[bookmarks]\ntp53 = \"chr17:7572659\"\n")]
+    #[case("[[tracks]]\npath = \"sample.bam\"\nfilters = [{ MinMapq = 20 }]\n")]
+    fn older_session_versions_with_newer_fields_are_errors(#[case] fields: &str) {
+        let content = format!(
+            "version = 2\nlocus = \"chr1:100\"\ngenome = \"hg38\"\nucsc_host = \"auto\"\nzoom = 1\n{fields}"
+        );
+        assert_eq!(
+            SessionFile::parse(&content).unwrap_err().to_string(),
+            "Parsing error: Session file version 2 does not support gene annotations, track heights, filters, or bookmarks. Set `version = 3` to use them."
+        );
+    }
+
+    #[test]
+    fn remove_missing_files_keeps_existing_and_remote_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let existing_path = temp_dir.path().join("peaks.bed").display().to_string();
+        std::fs::write(&existing_path, "").unwrap();
+        let missing_path = temp_dir.path().join("missing.bam").display().to_string();
+        let mut session = SessionFile {
+            tracks: vec![
+                track(&existing_path),
+                track(&missing_path),
+                track("s3://bucket/sample.bam"),
+            ],
+            gtf: Some(temp_dir.path().join("genes.gtf").display().to_string()),
+            ..SessionFile::default()
+        };
+
+        let warnings = session.remove_missing_files();
+
+        assert_eq!(
+            session
+                .tracks
+                .iter()
+                .map(|track| track.path.as_str())
+                .collect::<Vec<_>>(),
+            vec![existing_path.as_str(), "s3://bucket/sample.bam"]
+        );
+        assert_eq!(session.gtf, None);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("missing.bam"));
+        assert!(warnings[1].contains("genes.gtf"));
+    }
+}
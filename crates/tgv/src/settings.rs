@@ -1,35 +1,21 @@
 use crate::{
+    layout::TrackHeights,
     message::Message,
-    rendering::{DARK_THEME, Palette},
+    rendering::{DARK_THEME, GlyphSet, Palette, Theme},
 };
-use clap::{Parser, Subcommand, ValueEnum};
-use gv_core::alignment::is_url;
+use clap::{Parser, Subcommand};
+use gv_core::alignment::{
+    HiddenReadCategory, InsertRange, default_bam_index, is_url, path_without_query,
+};
+use gv_core::data_source::SourcePreference;
 use gv_core::error::TGVError;
-use gv_core::message::Movement;
+use gv_core::message::{AlignmentFilter, Movement};
 use gv_core::reference::Reference;
+use gv_core::retry::RetryPolicy;
 use gv_core::settings::{AlignmentPath, BackendType, BamSource, FilePath};
 use gv_core::tracks::UcscHost;
-use std::path::PathBuf;
-
-#[derive(Debug, Clone, Eq, PartialEq, ValueEnum)]
-pub enum UcscHostCli {
-    #[value(name = "us")]
-    Us,
-    #[value(name = "eu")]
-    Eu,
-    #[value(name = "auto")]
-    Auto,
-}
-
-impl From<UcscHostCli> for UcscHost {
-    fn from(val: UcscHostCli) -> Self {
-        match val {
-            UcscHostCli::Us => UcscHost::Us,
-            UcscHostCli::Eu => UcscHost::Eu,
-            UcscHostCli::Auto => UcscHost::auto(),
-        }
-    }
-}
+use gv_core::variant::VariantFilter;
+use std::{collections::BTreeMap, path::PathBuf};
 
 #[derive(Subcommand, Clone, Debug)]
 pub enum Commands {
@@ -38,9 +24,14 @@ pub enum Commands {
         /// Reference genome to download.
         reference: String,
 
-        /// Cache directory.
-        #[arg(long = "cache-dir", default_value = "~/.tgv")]
-        cache_dir: String,
+        /// Cache directory. Defaults to $TGV_CACHE_DIR, then $XDG_CACHE_HOME/tgv, then ~/.cache/tgv. A cache of an earlier
+        /// version in ~/.tgv is used until the default directory exists.
+        #[arg(long = "cache-dir")]
+        cache_dir: Option<String>,
+
+        /// Fail on the first UCSC API or MySQL connection error instead of retrying with backoff.
+        #[arg(long = "no-retry", default_value_t = false)]
+        no_retry: bool,
     },
 
     /// List reference genomes.
@@ -48,14 +39,24 @@ pub enum Commands {
         /// List all UCSC assemblies instead of the common genome names.
         #[arg(long = "all")]
         all: bool,
+
+        /// Only list genomes whose name or organism contains this text, ignoring case (e.g. zebrafish or danRer).
+        #[arg(long, value_name = "TEXT")]
+        filter: Option<String>,
+
+        /// Only list UCSC assemblies of this clade (e.g. mammal, vertebrate, or insect). Requires --all.
+        #[arg(long, value_name = "CLADE", requires = "all")]
+        clade: Option<String>,
     },
 }
 
 #[derive(Parser, Clone)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
-    /// Input files. Supported track formats: .bam, .vcf, .vcf.gz, .bed, .bed.gz.
-    /// BAM index files are inferred automatically as .bam.bai.
+    /// Input files. Supported track formats: .bam, .cram, .vcf, .vcf.gz, .bed, .bed.gz.
+    /// BAM and CRAM index files are inferred automatically as .bam.bai and .cram.crai.
+    /// CRAM files are decoded with the FASTA reference passed with -g.
+    /// `-` reads an unindexed BAM stream from stdin, e.g. `samtools view -b in.bam chr1:1000-2000 | tgv -`.
     /// To set the viewer reference, including a custom FASTA or 2bit file, use -g.
     #[arg(value_name = "files")]
     files: Vec<String>,
@@ -71,6 +72,37 @@ pub struct Cli {
     #[arg(short = 'g', long = "reference")]
     reference: Option<String>,
 
+    /// bigBed gene annotation (e.g. genes.bb) to use as the gene track. Queried directly from the file.
+    /// Requires a custom FASTA or 2bit reference (-g) or --no-reference.
+    #[arg(long)]
+    bigbed: Option<String>,
+
+    /// GTF or GFF3 gene annotation (e.g. genes.gff3 or genes.gtf.gz) to use as the gene track. The whole file is read on startup.
+    /// Requires a custom FASTA or 2bit reference (-g) or --no-reference.
+    #[arg(long, conflicts_with = "bigbed")]
+    gtf: Option<String>,
+
+    /// Always fetch UCSC API gene tracks instead of reading them from the disk cache.
+    #[arg(long, default_value_t = false)]
+    no_track_cache: bool,
+
+    /// Fail on the first UCSC API or MySQL connection error instead of retrying with backoff, e.g. for scripting.
+    #[arg(long, default_value_t = false)]
+    no_retry: bool,
+
+    /// Gene track of the reference (e.g. refGene) to use instead of the default. List tracks with :track list.
+    #[arg(long = "track", value_name = "NAME")]
+    gene_track: Option<String>,
+
+    /// BED file (e.g. peaks.bed) to display as an interval track. Repeat to add a track for each file.
+    /// Features are colored by itemRgb (column 9) and labeled by name (column 4) when present.
+    #[arg(short = 'b', long = "bed", value_name = "FILE")]
+    beds: Vec<String>,
+
+    /// bigWig signal file (e.g. signal.bw) to display as a histogram track. Repeat to stack multiple signal tracks.
+    #[arg(long = "bigwig", value_name = "FILE")]
+    bigwigs: Vec<String>,
+
     /// Do not display the reference genome.
     /// This flag cannot be used when no alignment file is provided.
     #[arg(long)]
@@ -84,18 +116,70 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     online: bool,
 
+    /// Read the reference from the local cache if downloaded, and fall back to UCSC otherwise. This is the default.
+    #[arg(long, default_value_t = false)]
+    prefer_cache: bool,
+
+    /// Read the reference from UCSC if reachable, and fall back to the local cache otherwise.
+    #[arg(long, default_value_t = false)]
+    prefer_live: bool,
+
+    /// Gene annotation backend. `--offline` and `--online` are shortcuts for `--backend local` and
+    /// `--backend ucsc`.
+    #[arg(long, value_enum)]
+    backend: Option<BackendType>,
+
     /// [For development only] Save all log information in the log file.
     #[arg(long)]
     debug: bool,
 
-    /// Choose the UCSC host. Defaults to auto when not specified.
-    #[arg(long, value_enum)]
-    host: Option<UcscHostCli>,
+    /// Choose the UCSC host: us, eu, auto (by timezone; the default), or a mirror such as mirror.example.org or
+    /// mirror.example.org:3306 (MySQL port). If us or eu is unreachable, the other host is tried.
+    #[arg(long, visible_alias = "ucsc-host", value_name = "us|eu|auto|HOST")]
+    host: Option<UcscHost>,
 
-    /// Cache directory. Defaults to ~/.tgv when not specified.
+    /// Cache directory. Defaults to $TGV_CACHE_DIR, then $XDG_CACHE_HOME/tgv, then ~/.cache/tgv. A cache of an earlier
+    /// version in ~/.tgv is used until the default directory exists.
     #[arg(long)]
     cache_dir: Option<String>,
 
+    /// Draw with ASCII characters only, for terminals and fonts without Unicode block elements.
+    #[arg(long)]
+    ascii_only: bool,
+
+    /// Draw coverage and bigWig signals with braille patterns, for twice the horizontal and four times the vertical resolution.
+    #[arg(long)]
+    braille: bool,
+
+    /// Color palette: dark (default), light for light terminal backgrounds, or colorblind for Okabe-Ito base and
+    /// variant colors.
+    #[arg(long, value_enum, value_name = "theme")]
+    palette: Option<Theme>,
+
+    /// Include reads of a category: unmapped, secondary, supplementary, or duplicate. Secondary and supplementary reads
+    /// are hidden by default. Repeat to include several categories.
+    #[arg(long, value_enum, value_name = "category")]
+    show_hidden: Vec<HiddenReadCategory>,
+
+    /// Expected insert size range of paired reads as MIN,MAX (e.g. 200,600). Defaults to 50,1000.
+    /// In the paired view, gaps of pairs outside the range or with unexpected orientations are colored.
+    #[arg(long, value_name = "MIN,MAX")]
+    insert_range: Option<InsertRange>,
+
+    /// Maximum number of reads loaded per alignment track. Loading stops at the cap, and the status bar notes that
+    /// more reads were not loaded. Defaults to 250,000.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_reads: Option<u64>,
+
+    /// Locus list to review, one CONTIG:POS or gene per line. Visit the loci in order with n / N.
+    #[arg(long, value_name = "FILE")]
+    loci: Option<String>,
+
+    /// Target regions in a BED file, e.g. the capture regions of a panel. Panning and goto stay within them, and
+    /// ]r / [r jump between them.
+    #[arg(long, value_name = "FILE", visible_alias = "region-file")]
+    regions: Option<String>,
+
     /// Session file to load. Accepts a full path, `~`, or a named session.
     #[arg(long)]
     pub session: Option<String>,
@@ -110,6 +194,70 @@ impl Cli {
         self.debug
     }
 
+    /// Glyph set from --ascii-only or --braille. None if neither flag is used.
+    fn glyphs(&self) -> Result<Option<GlyphSet>, TGVError> {
+        match (self.ascii_only, self.braille) {
+            (true, true) => Err(TGVError::CliError(
+                "Both --ascii-only and --braille flags are used. Please use only one.".to_string(),
+            )),
+            (true, false) => Ok(Some(GlyphSet::Ascii)),
+            (false, true) => Ok(Some(GlyphSet::Braille)),
+            (false, false) => Ok(None),
+        }
+    }
+
+    /// Backend from --backend, --offline, or --online. None if none of the flags is used.
+    fn backend(&self) -> Result<Option<BackendType>, TGVError> {
+        match (self.offline, self.online, &self.backend) {
+            (false, false, backend) => Ok(backend.clone()),
+            (true, false, None) => Ok(Some(BackendType::Local)),
+            (false, true, None) => Ok(Some(BackendType::Ucsc)),
+            (true, true, _) => Err(TGVError::CliError(
+                "Both --offline and --online flags are used. Please use only one.".to_string(),
+            )),
+            (_, _, Some(_)) => Err(TGVError::CliError(
+                "--backend cannot be used with --offline or --online. Please use only one."
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Fallback order of the default backend from --prefer-cache or --prefer-live. None if neither flag is used.
+    fn source_preference(&self) -> Result<Option<SourcePreference>, TGVError> {
+        let preference = match (self.prefer_cache, self.prefer_live) {
+            (true, true) => {
+                return Err(TGVError::CliError(
+                    "Both --prefer-cache and --prefer-live flags are used. Please use only one."
+                        .to_string(),
+                ));
+            }
+            (true, false) => SourcePreference::Cache,
+            (false, true) => SourcePreference::Live,
+            (false, false) => return Ok(None),
+        };
+        if !matches!(self.backend()?, None | Some(BackendType::Default)) {
+            return Err(TGVError::CliError(
+                "--prefer-cache and --prefer-live cannot be used with --offline, --online, or --backend."
+                    .to_string(),
+            ));
+        }
+        Ok(Some(preference))
+    }
+
+    /// BED tracks from -b, in the order of the flags.
+    fn bed_paths(&self) -> impl Iterator<Item = FilePath> + '_ {
+        self.beds
+            .iter()
+            .map(|path| FilePath::BedPath(shellexpand::tilde(path).to_string()))
+    }
+
+    /// Signal tracks from --bigwig, in the order of the flags.
+    fn bigwig_paths(&self) -> impl Iterator<Item = FilePath> + '_ {
+        self.bigwigs
+            .iter()
+            .map(|path| FilePath::BigWigPath(shellexpand::tilde(path).to_string()))
+    }
+
     pub fn initial_movement(&self) -> Result<Vec<Message>, TGVError> {
         let region_string = match &self.region {
             Some(region_string) => region_string,
@@ -161,6 +309,28 @@ impl Cli {
             settings.core.reference = r.parse::<Reference>()?;
         }
 
+        if let Some(ref bigbed) = self.bigbed {
+            settings.core.bigbed_path = Some(shellexpand::tilde(bigbed).to_string());
+            settings.core.gtf_path = None;
+        }
+
+        if let Some(ref gtf) = self.gtf {
+            settings.core.gtf_path = Some(shellexpand::tilde(gtf).to_string());
+            settings.core.bigbed_path = None;
+        }
+
+        if self.no_track_cache {
+            settings.core.track_cache = false;
+        }
+
+        if self.no_retry {
+            settings.core.retry = RetryPolicy::NONE;
+        }
+
+        if self.gene_track.is_some() {
+            settings.core.gene_track = self.gene_track.clone();
+        }
+
         // Region / initial locus override.
         if self.region.is_some() {
             settings.initial_state_messages = self.initial_movement()?;
@@ -168,33 +338,89 @@ impl Cli {
 
         // Track override: if any files were provided, replace all session tracks.
         if !self.files.is_empty() {
-            settings.core.file_paths = classify_and_build_tracks(&self.files)?;
+            settings.core.file_paths =
+                classify_and_build_tracks(&self.files, &settings.core.reference)?;
+            settings.alignment_filters.clear();
         }
 
-        // Backend override: only when explicitly requested.
-        match (self.offline, self.online) {
-            (true, true) => {
-                return Err(TGVError::CliError(
-                    "Both --offline and --online flags are used. Please use only one.".to_string(),
-                ));
+        // BED override: -b files add to positional BED files, or replace the session BED tracks.
+        if !self.beds.is_empty() {
+            if self.files.is_empty() {
+                settings
+                    .core
+                    .file_paths
+                    .retain(|file_path| !matches!(file_path, FilePath::BedPath(_)));
             }
-            (true, false) => settings.core.backend = BackendType::Local,
-            (false, true) => settings.core.backend = BackendType::Ucsc,
-            (false, false) => {}
+            settings.core.file_paths.extend(self.bed_paths());
+        }
+
+        // Signal override: if any bigWig files were provided, replace the session signal tracks.
+        if !self.bigwigs.is_empty() {
+            settings
+                .core
+                .file_paths
+                .retain(|file_path| !matches!(file_path, FilePath::BigWigPath(_)));
+            settings.core.file_paths.extend(self.bigwig_paths());
+        }
+
+        // Backend override: only when explicitly requested.
+        if let Some(backend) = self.backend()? {
+            settings.core.backend = backend;
+        }
+
+        if let Some(source_preference) = self.source_preference()? {
+            settings.core.source_preference = source_preference;
         }
 
         if let Some(ref h) = self.host {
-            settings.core.ucsc_host = h.clone().into();
+            settings.core.ucsc_host = h.clone();
         }
 
         if let Some(ref d) = self.cache_dir {
-            settings.core.cache_dir = shellexpand::tilde(d).to_string();
+            settings.core.cache_dir = gv_core::settings::cache_dir(Some(d));
+        }
+
+        if let Some(insert_range) = self.insert_range {
+            settings.core.insert_range = insert_range;
+        }
+
+        settings
+            .core
+            .shown_read_categories
+            .extend(self.show_hidden.iter().copied());
+
+        if let Some(max_reads) = self.max_reads {
+            settings.core.max_reads = max_reads as usize;
+        }
+
+        if let Some(ref loci) = self.loci {
+            settings.loci_path = Some(shellexpand::tilde(loci).to_string());
+        }
+
+        if let Some(ref regions) = self.regions {
+            settings.regions_path = Some(shellexpand::tilde(regions).to_string());
         }
 
         settings.debug = self.debug_enabled();
 
+        if let Some(theme) = self.palette {
+            settings.set_theme(theme);
+        }
+
+        if let Some(glyphs) = self.glyphs()? {
+            settings.palette.glyphs = glyphs;
+        }
+
+        // Validate: gene annotation files replace the gene track of custom references only.
+        if settings.core.has_gene_annotation_file() && settings.core.reference.needs_track() {
+            return Err(TGVError::CliError(
+                "--bigbed and --gtf require a custom FASTA or 2bit reference (-g) or --no-reference"
+                    .to_string(),
+            ));
+        }
+
         // Validate: if no reference is provided, the initial messages cannot contain GoToGene.
-        if !settings.core.reference.needs_track() {
+        if !settings.core.reference.needs_track() && !settings.core.has_gene_annotation_file() {
             for m in settings.initial_state_messages.iter() {
                 if let Message::Core(gv_core::message::Message::Move(
                     gv_core::message::Movement::Gene(gene_name),
@@ -220,13 +446,28 @@ impl Cli {
     }
 }
 
-/// Classify input files and build the track paths.
+/// Input file name that reads an unindexed BAM stream from stdin.
+const STDIN_PATH: &str = "-";
+
+/// Classify input files and build the track paths. CRAM files are decoded with the reference, which must be a custom
+/// FASTA file.
 ///
 /// Returns file paths in the same order as the CLI arguments.
-fn classify_and_build_tracks(files: &[String]) -> Result<Vec<FilePath>, TGVError> {
+fn classify_and_build_tracks(
+    files: &[String],
+    reference: &Reference,
+) -> Result<Vec<FilePath>, TGVError> {
+    if files.iter().filter(|file| *file == STDIN_PATH).count() > 1 {
+        return Err(TGVError::CliError(
+            "Only one input can be read from stdin (-).".to_string(),
+        ));
+    }
+
     for file in files {
-        let lower = file.to_lowercase();
-        if lower.ends_with(".fa")
+        let lower = path_without_query(file).to_lowercase();
+        if file == STDIN_PATH {
+            continue;
+        } else if lower.ends_with(".fa")
             || lower.ends_with(".fasta")
             || lower.ends_with(".fa.gz")
             || lower.ends_with(".fasta.gz")
@@ -234,10 +475,8 @@ fn classify_and_build_tracks(files: &[String]) -> Result<Vec<FilePath>, TGVError
             return Err(TGVError::CliError(
                 "FASTA reference files must be passed with -g/--reference, not as positional input files.".to_string(),
             ));
-        } else if lower.ends_with(".cram") {
-            return Err(TGVError::CliError(
-                "CRAM format is not yet supported as a CLI input format.".to_string(),
-            ));
+        } else if is_cram(file) {
+            cram_reference(file, reference)?;
         } else if !(lower.ends_with(".bam")
             || lower.ends_with(".vcf")
             || lower.ends_with(".vcf.gz")
@@ -245,7 +484,7 @@ fn classify_and_build_tracks(files: &[String]) -> Result<Vec<FilePath>, TGVError
             || lower.ends_with(".bed.gz"))
         {
             return Err(TGVError::CliError(format!(
-                "Unrecognized file format: {}. Supported track formats: .bam, .vcf, .vcf.gz, .bed, .bed.gz. Use -g for custom FASTA or 2bit reference genomes.",
+                "Unrecognized file format: {}. Supported track formats: .bam, .cram, .vcf, .vcf.gz, .bed, .bed.gz. Use -g for custom FASTA or 2bit reference genomes.",
                 file
             )));
         }
@@ -253,17 +492,23 @@ fn classify_and_build_tracks(files: &[String]) -> Result<Vec<FilePath>, TGVError
 
     let mut file_paths = Vec::new();
     for file in files {
-        let lower = file.to_lowercase();
-        if lower.ends_with(".bam") {
-            let index = format!("{file}.bai");
+        let lower = path_without_query(file).to_lowercase();
+        if file == STDIN_PATH {
+            file_paths.push(FilePath::AlignmentPath(AlignmentPath::Stdin));
+        } else if is_cram(file) {
+            let (fasta, fai) = cram_reference(file, reference)?;
+            file_paths.push(FilePath::AlignmentPath(AlignmentPath::Cram {
+                path: file.clone(),
+                crai: format!("{file}.crai"),
+                fasta,
+                fai,
+            }));
+        } else if lower.ends_with(".bam") {
+            let index = default_bam_index(file);
             file_paths.push(FilePath::AlignmentPath(AlignmentPath::Bam {
                 path: file.clone(),
                 index,
-                source: if is_url(file.as_str()) {
-                    BamSource::S3
-                } else {
-                    BamSource::Local
-                },
+                source: BamSource::of(file),
             }));
         } else if lower.ends_with(".vcf") || lower.ends_with(".vcf.gz") {
             file_paths.push(FilePath::VariantPath(file.clone()));
@@ -275,6 +520,40 @@ fn classify_and_build_tracks(files: &[String]) -> Result<Vec<FilePath>, TGVError
     Ok(file_paths)
 }
 
+/// Whether an input file is CRAM: by the .cram extension, or by the `CRAM` magic bytes of a local file.
+fn is_cram(file: &str) -> bool {
+    use std::io::Read;
+
+    if path_without_query(file).to_lowercase().ends_with(".cram") {
+        return true;
+    }
+    if is_url(file) {
+        return false;
+    }
+    let mut magic = [0; 4];
+    std::fs::File::open(file)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == b"CRAM")
+}
+
+/// FASTA reference and .fai index used to decode a CRAM file.
+fn cram_reference(file: &str, reference: &Reference) -> Result<(String, String), TGVError> {
+    if is_url(file) {
+        return Err(TGVError::CliError(format!(
+            "Remote CRAM files are not supported: {file}. Use a local CRAM file or a BAM file."
+        )));
+    }
+    match reference {
+        Reference::BYOIndexedFasta(fasta) => Ok((fasta.clone(), format!("{fasta}.fai"))),
+        Reference::NoReference => Err(TGVError::CliError(format!(
+            "CRAM file {file} cannot be decoded with --no-reference. Pass the FASTA reference it was written against with -g."
+        ))),
+        _ => Err(TGVError::CliError(format!(
+            "CRAM file {file} requires the FASTA reference it was written against. Pass it with -g (e.g. -g genome.fa)."
+        ))),
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Settings {
     pub core: gv_core::settings::Settings,
@@ -284,8 +563,40 @@ pub struct Settings {
     pub debug: bool,
     pub palette: Palette,
 
+    /// Color theme of the palette. Switched with `:theme` for the rest of the session.
+    pub theme: Theme,
+
     /// Initial zoom level to restore from a session file. `None` uses the default zoom.
     pub zoom: Option<u64>,
+
+    /// Track heights from a session file.
+    pub track_heights: TrackHeights,
+
+    /// Alignment filters from a session file, per alignment track in the order of the alignment paths.
+    pub alignment_filters: Vec<Vec<AlignmentFilter>>,
+
+    /// Variant filter from a session file.
+    pub variant_filter: VariantFilter,
+
+    /// Bookmark name -> locus, from a session file.
+    pub bookmarks: BTreeMap<String, String>,
+
+    /// Locus list file from `--loci`.
+    pub loci_path: Option<String>,
+
+    /// Target region BED file from `--regions`.
+    pub regions_path: Option<String>,
+
+    /// Normal mode key binding file. Default key bindings are used if None or the file does not exist.
+    pub keys_path: Option<String>,
+}
+
+impl Settings {
+    /// Switch the palette to the theme. Glyphs are kept.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.palette = theme.palette(self.palette.glyphs);
+    }
 }
 
 impl Default for Settings {
@@ -301,7 +612,23 @@ impl Default for Settings {
 
             palette: DARK_THEME,
 
+            theme: Theme::Dark,
+
             zoom: None,
+
+            track_heights: TrackHeights::default(),
+
+            alignment_filters: Vec::new(),
+
+            variant_filter: VariantFilter::default(),
+
+            bookmarks: BTreeMap::new(),
+
+            loci_path: None,
+
+            regions_path: None,
+
+            keys_path: None,
         }
     }
 }
@@ -329,19 +656,29 @@ impl TryFrom<Cli> for Settings {
 
         let initial_state_messages = cli.initial_movement()?;
 
-        let backend = match (cli.offline, cli.online) {
-            (true, true) => {
-                return Err(TGVError::CliError(
-                    "Both --offline and --online flags are used. Please use only one.".to_string(),
-                ));
-            }
-            (true, false) => BackendType::Local,
-            (false, true) => BackendType::Ucsc,
-            (false, false) => BackendType::Default,
-        };
+        let backend = cli.backend()?.unwrap_or_default();
+
+        let bigbed_path = cli
+            .bigbed
+            .as_deref()
+            .map(|path| shellexpand::tilde(path).to_string());
+
+        let gtf_path = cli
+            .gtf
+            .as_deref()
+            .map(|path| shellexpand::tilde(path).to_string());
+
+        // Validate: gene annotation files replace the gene track of custom references only.
+        let has_gene_annotation_file = bigbed_path.is_some() || gtf_path.is_some();
+        if has_gene_annotation_file && reference.needs_track() {
+            return Err(TGVError::CliError(
+                "--bigbed and --gtf require a custom FASTA or 2bit reference (-g) or --no-reference"
+                    .to_string(),
+            ));
+        }
 
         // Validate: no-reference + gene movement is invalid.
-        if !reference.needs_track() {
+        if !reference.needs_track() && !has_gene_annotation_file {
             for m in initial_state_messages.iter() {
                 if let Message::Core(gv_core::message::Message::Move(
                     gv_core::message::Movement::Gene(gene_name),
@@ -356,32 +693,70 @@ impl TryFrom<Cli> for Settings {
         }
 
         // Input data and reference cannot both be absent.
-        if cli.files.is_empty() && cli.no_reference {
+        if cli.files.is_empty() && cli.beds.is_empty() && cli.bigwigs.is_empty() && cli.no_reference
+        {
             return Err(TGVError::CliError(
                 "Input files and reference cannot both be none".to_string(),
             ));
         }
 
-        let file_paths = classify_and_build_tracks(&cli.files)?;
+        let mut file_paths = classify_and_build_tracks(&cli.files, &reference)?;
+        file_paths.extend(cli.bed_paths());
+        file_paths.extend(cli.bigwig_paths());
 
-        let cache_dir =
-            shellexpand::tilde(cli.cache_dir.as_deref().unwrap_or("~/.tgv")).to_string();
+        let cache_dir = gv_core::settings::cache_dir(cli.cache_dir.as_deref());
         let debug = cli.debug_enabled();
+        let glyphs = cli.glyphs()?.unwrap_or_default();
+        let theme = cli.palette.unwrap_or_default();
 
         Ok(Self {
             core: gv_core::settings::Settings {
                 file_paths,
                 reference,
                 backend,
-                ucsc_host: cli.host.unwrap_or(UcscHostCli::Auto).into(),
+                ucsc_host: cli.host.clone().unwrap_or_else(UcscHost::auto),
                 cache_dir,
+                bigbed_path,
+                gtf_path,
+                track_cache: !cli.no_track_cache,
+                gene_track: cli.gene_track.clone(),
+                shown_read_categories: HiddenReadCategory::shown_by_default()
+                    .into_iter()
+                    .chain(cli.show_hidden.iter().copied())
+                    .collect(),
+                insert_range: cli.insert_range.unwrap_or_default(),
+                max_reads: cli
+                    .max_reads
+                    .map_or(gv_core::settings::DEFAULT_MAX_READS, |max_reads| {
+                        max_reads as usize
+                    }),
+                retry: if cli.no_retry {
+                    RetryPolicy::NONE
+                } else {
+                    RetryPolicy::default()
+                },
+                source_preference: cli.source_preference()?.unwrap_or_default(),
             },
             initial_state_messages,
 
             test_mode: false,
             debug,
-            palette: DARK_THEME,
+            palette: theme.palette(glyphs),
+            theme,
             zoom: None,
+            track_heights: TrackHeights::default(),
+            alignment_filters: Vec::new(),
+            variant_filter: VariantFilter::default(),
+            bookmarks: BTreeMap::new(),
+            loci_path: cli
+                .loci
+                .as_deref()
+                .map(|path| shellexpand::tilde(path).to_string()),
+            regions_path: cli
+                .regions
+                .as_deref()
+                .map(|path| shellexpand::tilde(path).to_string()),
+            keys_path: None,
         })
     }
 }
@@ -390,9 +765,11 @@ impl TryFrom<Cli> for Settings {
 mod tests {
     use super::*;
 
+    use crate::rendering::{COLORBLIND_THEME, LIGHT_THEME};
     use gv_core::reference::Reference;
     use gv_core::settings::{AlignmentPath, BamSource, FilePath};
     use rstest::rstest;
+    use std::collections::BTreeSet;
 
     fn bam(path: &str) -> AlignmentPath {
         AlignmentPath::Bam {
@@ -413,6 +790,28 @@ mod tests {
         },
         ..Settings::default()
     }))]
+    #[case("tgv https://example.org/input.bam", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(AlignmentPath::Bam {
+            path: "https://example.org/input.bam".to_string(),
+            index: "https://example.org/input.bam.bai".to_string(),
+            source: BamSource::Http,
+        })],
+        ..gv_core::settings::Settings::default()
+        },
+        ..Settings::default()
+    }))]
+    #[case("tgv https://example.org/input.bam?X-Amz-Expires=3600&X-Amz-Signature=abc", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(AlignmentPath::Bam {
+            path: "https://example.org/input.bam?X-Amz-Expires=3600&X-Amz-Signature=abc".to_string(),
+            index: "https://example.org/input.bam.bai?X-Amz-Expires=3600&X-Amz-Signature=abc".to_string(),
+            source: BamSource::Http,
+        })],
+        ..gv_core::settings::Settings::default()
+        },
+        ..Settings::default()
+    }))]
     #[case("tgv input.bam some.bed", Ok(Settings {
         core: gv_core::settings::Settings {
         file_paths: vec![
@@ -448,6 +847,58 @@ mod tests {
         ..gv_core::settings::Settings::default()},
         ..Settings::default()
     }))]
+    #[case("tgv input.bam --backend ensembl", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        backend: BackendType::Ensembl,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --backend ensembl --online", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --prefer-live", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        source_preference: SourcePreference::Live,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --prefer-cache --prefer-live", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --prefer-live --offline", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --track refGene", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        gene_track: Some("refGene".to_string()),
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --no-track-cache", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        track_cache: false,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --no-retry", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        retry: RetryPolicy::NONE,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --max-reads 1000", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        max_reads: 1000,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --insert-range 200,600", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        insert_range: InsertRange { min: 200, max: 600 },
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
     #[case("tgv input.bam --debug", Ok(Settings {
         core: gv_core::settings::Settings {
         file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
@@ -512,7 +963,143 @@ mod tests {
         ..gv_core::settings::Settings::default()},
         ..Settings::default()
     }))]
+    #[case("tgv input.bam --ucsc-host eu", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ucsc_host: UcscHost::Eu,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --host us", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ucsc_host: UcscHost::Us,
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --ucsc-host mirror.example.org:3307", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ucsc_host: UcscHost::Custom("mirror.example.org:3307".to_string()),
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam -r TP53 --no-reference --bigbed genes.bb", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        reference: Reference::NoReference,
+        bigbed_path: Some("genes.bb".to_string()),
+        ..gv_core::settings::Settings::default()},
+        initial_state_messages: vec![Movement::Gene("TP53".to_string()).into()],
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --bigbed genes.bb", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam -r TP53 --no-reference --gtf genes.gff3", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        reference: Reference::NoReference,
+        gtf_path: Some("genes.gff3".to_string()),
+        ..gv_core::settings::Settings::default()},
+        initial_state_messages: vec![Movement::Gene("TP53".to_string()).into()],
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --gtf genes.gtf", Err(TGVError::CliError("".to_string())))]
     #[case("tgv input.txt", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv - a.bed", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![
+            FilePath::AlignmentPath(AlignmentPath::Stdin),
+            FilePath::BedPath("a.bed".to_string()),
+        ],
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv - -", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv tests/data/covid.sorted.cram -g tests/data/covid.fa", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(AlignmentPath::Cram {
+            path: "tests/data/covid.sorted.cram".to_string(),
+            crai: "tests/data/covid.sorted.cram.crai".to_string(),
+            fasta: "tests/data/covid.fa".to_string(),
+            fai: "tests/data/covid.fa.fai".to_string(),
+        })],
+        reference: Reference::BYOIndexedFasta("tests/data/covid.fa".to_string()),
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv tests/data/covid.sorted.cram --no-reference", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv tests/data/covid.sorted.cram -g hg19", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv s3://bucket/input.cram -g tests/data/covid.fa", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --bigwig a.bw --bigwig b.bigwig", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![
+            FilePath::AlignmentPath(bam("input.bam")),
+            FilePath::BigWigPath("a.bw".to_string()),
+            FilePath::BigWigPath("b.bigwig".to_string()),
+        ],
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam a.bed -b peaks.bed --bed blacklist.bed.gz", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![
+            FilePath::AlignmentPath(bam("input.bam")),
+            FilePath::BedPath("a.bed".to_string()),
+            FilePath::BedPath("peaks.bed".to_string()),
+            FilePath::BedPath("blacklist.bed.gz".to_string()),
+        ],
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --ascii-only", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ..gv_core::settings::Settings::default()},
+        palette: Palette { glyphs: GlyphSet::Ascii, ..DARK_THEME },
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --braille", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ..gv_core::settings::Settings::default()},
+        palette: Palette { glyphs: GlyphSet::Braille, ..DARK_THEME },
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --palette colorblind", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ..gv_core::settings::Settings::default()},
+        palette: COLORBLIND_THEME,
+        theme: Theme::Colorblind,
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --palette light --ascii-only", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        ..gv_core::settings::Settings::default()},
+        palette: Palette { glyphs: GlyphSet::Ascii, ..LIGHT_THEME },
+        theme: Theme::Light,
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --ascii-only --braille", Err(TGVError::CliError("".to_string())))]
+    #[case("tgv input.bam --show-hidden supplementary", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        shown_read_categories: BTreeSet::from([
+            HiddenReadCategory::Unmapped,
+            HiddenReadCategory::Supplementary,
+            HiddenReadCategory::Duplicate,
+        ]),
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
+    #[case("tgv input.bam --show-hidden supplementary --show-hidden secondary", Ok(Settings {
+        core: gv_core::settings::Settings {
+        file_paths: vec![FilePath::AlignmentPath(bam("input.bam"))],
+        shown_read_categories: HiddenReadCategory::all(),
+        ..gv_core::settings::Settings::default()},
+        ..Settings::default()
+    }))]
     fn test_cli_parsing(
         #[case] command_line: &str,
         #[case] expected_settings: Result<Settings, TGVError>,
@@ -530,4 +1117,32 @@ mod tests {
             ),
         }
     }
+
+    #[rstest]
+    #[case("tgv download hg38", false)]
+    #[case("tgv download hg38 --no-retry", true)]
+    fn test_download_no_retry(#[case] command_line: &str, #[case] expected: bool) {
+        let cli = Cli::parse_from(shlex::split(command_line).unwrap());
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Download { no_retry, .. }) if no_retry == expected
+        ));
+    }
+
+    #[rstest]
+    #[case("tests/data/covid.sorted.cram", true)]
+    #[case("input.CRAM", true)]
+    #[case("tests/data/covid.sorted.bam", false)]
+    #[case("missing.bam", false)]
+    fn test_is_cram(#[case] file: &str, #[case] expected: bool) {
+        assert_eq!(is_cram(file), expected);
+    }
+
+    #[test]
+    fn cram_is_detected_by_magic_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reads.alignments");
+        std::fs::copy("tests/data/covid.sorted.cram", &path).unwrap();
+        assert!(is_cram(path.to_str().unwrap()));
+    }
 }
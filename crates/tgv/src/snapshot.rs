@@ -0,0 +1,61 @@
+use ratatui::buffer::Buffer;
+
+/// A rendered main view captured by `:snapshot`.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub label: String,
+
+    /// The rendered view at capture time.
+    pub buffer: Buffer,
+}
+
+/// In-memory gallery of snapshots. Snapshots are not persisted and are dropped on exit.
+#[derive(Debug, Default)]
+pub struct SnapshotGallery {
+    /// Oldest first.
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl SnapshotGallery {
+    /// Maximum number of retained snapshots. The oldest snapshot is dropped when the gallery is full.
+    pub const MAX_SNAPSHOTS: usize = 20;
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        if self.snapshots.len() >= Self::MAX_SNAPSHOTS {
+            self.snapshots.remove(0);
+        }
+        self.snapshots.push(snapshot);
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn push_drops_oldest_snapshot_when_full() {
+        let mut gallery = SnapshotGallery::default();
+        for i in 0..SnapshotGallery::MAX_SNAPSHOTS + 2 {
+            gallery.push(Snapshot {
+                label: format!("s{i}"),
+                buffer: Buffer::empty(Rect::new(0, 0, 1, 1)),
+            });
+        }
+
+        assert_eq!(gallery.len(), SnapshotGallery::MAX_SNAPSHOTS);
+        assert_eq!(gallery.snapshots[0].label, "s2");
+        assert_eq!(
+            gallery.snapshots.last().unwrap().label,
+            format!("s{}", SnapshotGallery::MAX_SNAPSHOTS + 1)
+        );
+    }
+}
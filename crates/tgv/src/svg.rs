@@ -0,0 +1,201 @@
+use crate::rendering::Palette;
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::{Color, Modifier},
+};
+use std::fmt::Write;
+
+/// Cell size in pixels.
+const CELL_WIDTH: u16 = 10;
+const CELL_HEIGHT: u16 = 20;
+
+const FONT_SIZE: u16 = 16;
+
+/// Baseline offset from the top of a cell.
+const TEXT_BASELINE: u16 = 15;
+
+/// Write a rendered buffer as an SVG image. Each cell is a colored rect plus its symbol as text. Cells without a
+/// background color use the palette background.
+pub fn buffer_to_svg(buffer: &Buffer, palette: &Palette) -> String {
+    let area = buffer.area;
+    let default_background = palette.background;
+    let default_foreground = if is_dark(default_background) {
+        Color::Rgb(229, 229, 229)
+    } else {
+        Color::Rgb(30, 30, 30)
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="monospace" font-size="{FONT_SIZE}">"#,
+        area.width * CELL_WIDTH,
+        area.height * CELL_HEIGHT,
+        area.width * CELL_WIDTH,
+        area.height * CELL_HEIGHT,
+    );
+
+    for y in 0..area.height {
+        for x in 0..area.width {
+            let cell = &buffer[(area.x + x, area.y + y)];
+            let (foreground, background) =
+                cell_colors(cell, default_foreground, default_background);
+            let (px, py) = (x * CELL_WIDTH, y * CELL_HEIGHT);
+
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{px}" y="{py}" width="{CELL_WIDTH}" height="{CELL_HEIGHT}" fill="{}"/>"#,
+                hex(background)
+            );
+
+            let symbol = cell.symbol();
+            if symbol.trim().is_empty() {
+                continue;
+            }
+            let mut attributes = String::new();
+            if cell.modifier.contains(Modifier::BOLD) {
+                attributes.push_str(r#" font-weight="bold""#);
+            }
+            if cell.modifier.contains(Modifier::DIM) {
+                attributes.push_str(r#" opacity="0.5""#);
+            }
+            let _ = writeln!(
+                svg,
+                r#"<text x="{px}" y="{}" fill="{}"{attributes}>{}</text>"#,
+                py + TEXT_BASELINE,
+                hex(foreground),
+                escape(symbol)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Foreground and background of a cell. Reset colors fall back to the defaults, and reversed cells are swapped.
+fn cell_colors(
+    cell: &Cell,
+    default_foreground: Color,
+    default_background: Color,
+) -> (Color, Color) {
+    let foreground = match cell.fg {
+        Color::Reset => default_foreground,
+        color => color,
+    };
+    let background = match cell.bg {
+        Color::Reset => default_background,
+        color => color,
+    };
+
+    if cell.modifier.contains(Modifier::REVERSED) {
+        (background, foreground)
+    } else {
+        (foreground, background)
+    }
+}
+
+/// RGB components of a color. Named and indexed colors use the xterm defaults.
+fn rgb(color: Color) -> (u8, u8, u8) {
+    const ANSI: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (128, 0, 0),
+        (0, 128, 0),
+        (128, 128, 0),
+        (0, 0, 128),
+        (128, 0, 128),
+        (0, 128, 128),
+        (192, 192, 192),
+        (128, 128, 128),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (0, 0, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Reset | Color::Black => ANSI[0],
+        Color::Red => ANSI[1],
+        Color::Green => ANSI[2],
+        Color::Yellow => ANSI[3],
+        Color::Blue => ANSI[4],
+        Color::Magenta => ANSI[5],
+        Color::Cyan => ANSI[6],
+        Color::Gray => ANSI[7],
+        Color::DarkGray => ANSI[8],
+        Color::LightRed => ANSI[9],
+        Color::LightGreen => ANSI[10],
+        Color::LightYellow => ANSI[11],
+        Color::LightBlue => ANSI[12],
+        Color::LightMagenta => ANSI[13],
+        Color::LightCyan => ANSI[14],
+        Color::White => ANSI[15],
+        Color::Indexed(index @ 0..=15) => ANSI[index as usize],
+        Color::Indexed(index @ 16..=231) => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        Color::Indexed(index) => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn hex(color: Color) -> String {
+    let (r, g, b) = rgb(color);
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn is_dark(color: Color) -> bool {
+    let (r, g, b) = rgb(color);
+    (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000 < 128
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rendering::DARK_THEME;
+    use ratatui::{layout::Rect, style::Style};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Color::Rgb(1, 2, 255), "#0102ff")]
+    #[case(Color::LightRed, "#ff0000")]
+    #[case(Color::Indexed(16), "#000000")]
+    #[case(Color::Indexed(196), "#ff0000")]
+    #[case(Color::Indexed(244), "#808080")]
+    fn test_hex(#[case] color: Color, #[case] expected: &str) {
+        assert_eq!(hex(color), expected);
+    }
+
+    #[test]
+    fn buffer_to_svg_writes_a_rect_per_cell_and_escapes_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 2));
+        buffer.set_string(0, 0, "A<", Style::default().bg(Color::Rgb(255, 0, 0)));
+
+        let svg = buffer_to_svg(&buffer, &DARK_THEME);
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="40""#)
+        );
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect ").count(), 6);
+        assert_eq!(svg.matches("<text ").count(), 2);
+        assert!(svg.contains(r##"<rect x="0" y="0" width="10" height="20" fill="#ff0000"/>"##));
+        assert!(svg.contains(">&lt;</text>"));
+        // Cells without a background use the palette background.
+        assert!(svg.contains(r##"<rect x="20" y="20" width="10" height="20" fill="#1e1e1e"/>"##));
+    }
+}
@@ -1,12 +1,25 @@
 mod support;
 
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use gv_core::alignment::{Haplotype, HiddenReadCategory};
+use gv_core::intervals::GenomeInterval;
 use gv_core::message::{
-    AlignmentDisplayOption, AlignmentSort, Message as CoreMessage, Movement, Scroll, Zoom,
+    AlignmentColor, AlignmentDisplayOption, AlignmentFilter, AlignmentGroup, AlignmentSort,
+    Message as CoreMessage, Movement, Scroll, Zoom,
 };
 use rstest::rstest;
-use support::{AppHarness, test_data_path};
+use support::{AppHarness, cli_from_args, test_data_path};
 use tempfile::TempDir;
-use tgv::{app::Scene, message::Message, session::SessionFile};
+use tgv::{
+    app::Scene,
+    layout::{AreaType, Pane, TrackHeights},
+    message::Message,
+    mouse::MouseRegister,
+    register::KeyRegisterType,
+    rendering::{COLORBLIND_THEME, DARK_THEME, LIGHT_THEME, Popup, Theme, contig_overview_x_range},
+    session::SessionFile,
+    settings::Settings,
+};
 
 fn absolutize_fixture_args(args: &str) -> String {
     args.replace(
@@ -47,6 +60,39 @@ async fn offline_initialization_succeeds(#[case] args: &str) {
     harness.close().await.unwrap();
 }
 
+#[tokio::test]
+async fn offline_cram_reads_match_the_bam_they_were_written_from() {
+    let args = "-g tests/data/covid.fa -r MN908947.3:100 --offline";
+    let mut bam = AppHarness::from_args(&offline_case_args(Some("covid.sorted.bam"), args))
+        .await
+        .unwrap();
+    let mut cram = AppHarness::from_args(&offline_case_args(Some("covid.sorted.cram"), args))
+        .await
+        .unwrap();
+
+    for position in [100, 15_000, 100] {
+        bam.handle_movement(Movement::Position(position))
+            .await
+            .unwrap();
+        cram.handle_movement(Movement::Position(position))
+            .await
+            .unwrap();
+
+        let bam_alignment = &bam.app.state.alignments[0];
+        let cram_alignment = &cram.app.state.alignments[0];
+        assert_eq!(cram_alignment.reads.len(), bam_alignment.reads.len());
+        assert_eq!(cram_alignment.depth(), bam_alignment.depth());
+        assert_eq!(
+            cram_alignment.coverage_at(position).total,
+            bam_alignment.coverage_at(position).total
+        );
+    }
+    assert!(!cram.app.state.alignments[0].reads.is_empty());
+
+    bam.close().await.unwrap();
+    cram.close().await.unwrap();
+}
+
 #[tokio::test]
 async fn offline_sequence_navigates_and_zooms() {
     let args = offline_case_args(
@@ -113,6 +159,108 @@ async fn offline_sequence_updates_tracks_and_scenes() {
     harness.close().await.unwrap();
 }
 
+#[tokio::test]
+async fn offline_variant_navigation_visits_variants_in_order_across_contigs() {
+    let temp_dir = TempDir::new().unwrap();
+    let vcf_path = temp_dir.path().join("unsorted.vcf");
+    std::fs::write(
+        &vcf_path,
+        "##fileformat=VCFv4.2\n\
+         #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n\
+         chr22\t33121300\t.\tA\tG\t.\t.\t.\n\
+         chr21\t100\t.\tC\tT\t.\t.\t.\n\
+         chrX\t50\t.\tG\tA\t.\t.\t.\n\
+         chr22\t33121200\t.\tT\tC\t.\t.\t.\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        &format!(
+            "{} -r chr22:33121120 --no-reference --offline",
+            vcf_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let next_variant = [KeyCode::Char(']'), KeyCode::Char('v')];
+    let previous_variant = [KeyCode::Char('['), KeyCode::Char('v')];
+
+    harness.handle_key_codes(next_variant).await.unwrap();
+    assert_eq!(harness.locus(), "chr22:33121200");
+    assert!(harness.app.state.messages.is_empty());
+    harness.handle_key_codes(next_variant).await.unwrap();
+    assert_eq!(harness.locus(), "chr22:33121300");
+
+    // chrM has no variants.
+    harness.handle_key_codes(next_variant).await.unwrap();
+    assert_eq!(harness.locus(), "chrX:50");
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["No more variants on chr22. Moved to chrX".to_string()]
+    );
+
+    // Wrap around after the last variant.
+    harness.handle_key_codes(next_variant).await.unwrap();
+    assert_eq!(harness.locus(), "chr21:100");
+    harness.handle_key_codes(previous_variant).await.unwrap();
+    assert_eq!(harness.locus(), "chrX:50");
+
+    harness
+        .handle_key_codes([KeyCode::Char('2'), KeyCode::Char('['), KeyCode::Char('v')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33121200");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_vcf_filter_hides_failing_variants() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr20:14370 tests/data/simple.vcf --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let variant_row_is_empty = |harness: &AppHarness| {
+        let area = harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(area_type, area)| (*area_type == AreaType::Variant(0)).then_some(*area))
+            .unwrap();
+        let buffer = harness.terminal_backend().buffer();
+        area.positions()
+            .all(|position| buffer[position].bg == ratatui::style::Color::Reset)
+    };
+    assert!(!variant_row_is_empty(&harness));
+
+    // 14370 (PASS, QUAL 29) and 17330 (q10, QUAL 3) are hidden.
+    harness
+        .handle_command("vcf-filter PASS qual>30")
+        .await
+        .unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Showing 3 of 5 variants: PASS, QUAL > 30, missing QUAL shown".to_string()]
+    );
+    assert!(variant_row_is_empty(&harness));
+
+    harness
+        .handle_key_codes([KeyCode::Char(']'), KeyCode::Char('v')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr20:88108");
+
+    harness.handle_command("vcf-filter clear").await.unwrap();
+    harness
+        .handle_movement(Movement::Position(14370))
+        .await
+        .unwrap();
+    assert!(!variant_row_is_empty(&harness));
+
+    harness.close().await.unwrap();
+}
+
 #[tokio::test]
 async fn offline_sequence_handles_sorting_command() {
     let args = offline_case_args(
@@ -138,39 +286,3730 @@ async fn offline_sequence_handles_sorting_command() {
 }
 
 #[tokio::test]
-async fn offline_sequence_saves_session_and_save_and_quit() {
+async fn offline_sort_base_uses_the_cursor_and_sort_none_restores_start_order() {
     let args = offline_case_args(
-        Some("ncbi.sorted.bam"),
-        "-r chr22:33121120 --no-reference --offline",
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let default_ys = harness.app.state.alignments[0].ys.clone();
+
+    harness.handle_command("cursor on").await.unwrap();
+    harness.handle_movement(Movement::Left(21)).await.unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(79));
+    assert_eq!(harness.app.alignment_view.focus.position, 100);
+
+    harness.handle_command("sort base").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(79))]
+    );
+
+    // Reads with A at the cursor are stacked above the three with T.
+    let alignment = &harness.app.state.alignments[0];
+    let bases = alignment
+        .ys_index
+        .iter()
+        .filter_map(|row| row.iter().find_map(|i| alignment.reads[*i].base_at(79)))
+        .collect::<Vec<_>>();
+    assert_eq!(bases.iter().filter(|base| **base == b'T').count(), 3);
+    assert!(bases.is_sorted_by_key(|base| *base == b'T'));
+
+    harness.handle_command("sort none").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].ys, default_ys);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_theme_switch_renders_the_same_view_with_the_light_palette() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
     );
     let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("snapshot dark").await.unwrap();
+    harness.handle_command("theme light").await.unwrap();
+    assert_eq!(harness.app.settings.theme, Theme::Light);
+    assert_eq!(harness.app.settings.palette, LIGHT_THEME);
+    harness.handle_command("snapshot light").await.unwrap();
+
+    let dark = &harness.app.snapshots.snapshots[0].buffer;
+    let light = &harness.app.snapshots.snapshots[1].buffer;
+    // The status bar differs by the theme badge only.
+    let status_bar = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(area_type, area)| (*area_type == AreaType::Error).then_some(*area))
+        .unwrap();
+    let symbols = |buffer: &ratatui::buffer::Buffer| {
+        buffer
+            .area
+            .positions()
+            .filter(|position| !status_bar.contains(*position))
+            .map(|position| buffer[position].symbol().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(symbols(dark), symbols(light));
+    assert_ne!(dark, light);
+    let status_text = |buffer: &ratatui::buffer::Buffer| {
+        status_bar
+            .positions()
+            .map(|position| buffer[position].symbol())
+            .collect::<String>()
+    };
+    assert!(!status_text(dark).contains("[dark]"));
+    assert!(status_text(light).contains("[light]"));
+
+    // Sequence bases are drawn on the theme's base colors.
+    let base_backgrounds = |buffer: &ratatui::buffer::Buffer| {
+        buffer
+            .content()
+            .iter()
+            .filter(|cell| cell.symbol() == "A")
+            .map(|cell| cell.bg)
+            .collect::<std::collections::HashSet<_>>()
+    };
+    assert!(base_backgrounds(dark).contains(&DARK_THEME.BASE_A));
+    assert!(base_backgrounds(light).contains(&LIGHT_THEME.BASE_A));
+    assert!(!base_backgrounds(light).contains(&DARK_THEME.BASE_A));
+
+    harness.handle_command("theme sepia").await.unwrap();
+    assert_eq!(harness.app.settings.theme, Theme::Light);
+
+    harness.handle_command("theme dark").await.unwrap();
+    assert_eq!(harness.app.settings.palette, DARK_THEME);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_colorblind_palette_colors_bases_and_variants() {
     let temp_dir = TempDir::new().unwrap();
-    let save_path = temp_dir.path().join("saved-session.toml");
+    let vcf_path = temp_dir.path().join("covid.vcf");
+    std::fs::write(
+        &vcf_path,
+        "##fileformat=VCFv4.2\n\
+         #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n\
+         MN908947.3\t113\t.\tAG\tA\t.\t.\t.\n\
+         MN908947.3\t120\t.\tC\tT\t.\t.\t.\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "{} -g tests/data/covid.fa -r MN908947.3:110 --palette colorblind --offline",
+            vcf_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.settings.theme, Theme::Colorblind);
+
+    harness.handle_command("snapshot colorblind").await.unwrap();
+    harness.handle_command("palette dark").await.unwrap();
+    assert_eq!(harness.app.settings.palette, DARK_THEME);
+    harness.handle_command("snapshot dark").await.unwrap();
+
+    let colorblind = &harness.app.snapshots.snapshots[0].buffer;
+    let dark = &harness.app.snapshots.snapshots[1].buffer;
+    let area_colors = |buffer: &ratatui::buffer::Buffer, expected_area_type: AreaType| {
+        let area = harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(area_type, area)| (*area_type == expected_area_type).then_some(*area))
+            .unwrap();
+        area.positions()
+            .flat_map(|position| [buffer[position].fg, buffer[position].bg])
+            .collect::<std::collections::HashSet<_>>()
+    };
+
+    let variant_colors = area_colors(colorblind, AreaType::Variant(0));
+    assert!(variant_colors.contains(&COLORBLIND_THEME.VCF1));
+    assert!(variant_colors.contains(&COLORBLIND_THEME.VCF2));
+    assert!(area_colors(dark, AreaType::Variant(0)).contains(&DARK_THEME.VCF1));
+
+    let sequence_colors = area_colors(colorblind, AreaType::Sequence);
+    for base in [b'A', b'C', b'G', b'T'] {
+        assert!(sequence_colors.contains(&COLORBLIND_THEME.base_color(base)));
+        assert!(!sequence_colors.contains(&DARK_THEME.base_color(base)));
+    }
+
+    // Mismatches in reads use the colorblind base colors.
+    let alignment_colors = area_colors(colorblind, AreaType::Alignment(0));
+    assert!(
+        [b'A', b'C', b'G', b'T']
+            .iter()
+            .any(|base| alignment_colors.contains(&COLORBLIND_THEME.mismatch_color(*base)))
+    );
+    assert!(
+        [b'A', b'C', b'G', b'T']
+            .iter()
+            .all(|base| !alignment_colors.contains(&DARK_THEME.mismatch_color(*base)))
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_export_writes_the_main_view_as_svg() {
+    let temp_dir = TempDir::new().unwrap();
+    let svg_path = temp_dir.path().join("view.svg");
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness.resize(40, 20);
 
     harness
-        .handle_core(vec![CoreMessage::SaveSession(Some(
-            save_path.display().to_string(),
-        ))])
+        .handle_command(&format!("export {}", svg_path.display()))
         .await
         .unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!("Exported view to {}", svg_path.display())]
+    );
 
-    assert_eq!(harness.app.session_path, save_path);
-    assert!(save_path.exists());
+    let svg = std::fs::read_to_string(&svg_path).unwrap();
+    let main_area = harness.app.layout.main_area;
+    assert!(svg.starts_with(&format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}""#,
+        main_area.width * 10,
+        main_area.height * 20
+    )));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(
+        svg.matches("<rect ").count(),
+        (main_area.width * main_area.height) as usize
+    );
+    assert_eq!(
+        svg.matches("<text ").count(),
+        svg.matches("</text>").count()
+    );
 
-    let session = SessionFile::from_path(&save_path).unwrap();
-    assert_eq!(session.locus, harness.locus());
+    harness.handle_command("export view.png").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Usage: export PATH.svg".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_export_reads_round_trips_the_filtered_reads_in_view() {
+    let temp_dir = TempDir::new().unwrap();
+    let bam_path = temp_dir.path().join("out.bam");
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let region = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.main_area);
+    let read_names = |harness: &AppHarness| {
+        harness
+            .app
+            .state
+            .shown_reads_overlapping(0, &region)
+            .unwrap()
+            .iter()
+            .map(|read| read.record.name().unwrap().to_vec())
+            .collect::<Vec<_>>()
+    };
+    let all_read_names = read_names(&harness);
+
+    harness.handle_command("filter length>=148").await.unwrap();
+    let filtered_read_names = read_names(&harness);
+    assert!(!filtered_read_names.is_empty());
+    assert!(filtered_read_names.len() < all_read_names.len());
 
-    let quit_path = temp_dir.path().join("quit-session.toml");
     harness
-        .handle_core(vec![CoreMessage::SaveAndQuit(Some(
-            quit_path.display().to_string(),
-        ))])
+        .handle_command(&format!("export-reads {}", bam_path.display()))
         .await
         .unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!(
+            "Exported {} reads to {}",
+            filtered_read_names.len(),
+            bam_path.display()
+        )]
+    );
 
-    assert!(harness.app.exit);
-    assert_eq!(harness.app.session_path, quit_path);
-    assert!(quit_path.exists());
+    let mut reader = noodles::bam::io::reader::Builder
+        .build_from_path(&bam_path)
+        .unwrap();
+    let header = reader.read_header().unwrap();
+    assert_eq!(
+        header
+            .reference_sequences()
+            .keys()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>(),
+        vec!["MN908947.3".to_string()]
+    );
+    let exported_read_names = reader
+        .record_bufs(&header)
+        .map(|record| record.unwrap().name().unwrap().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(exported_read_names, filtered_read_names);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_export_cov_writes_the_filtered_coverage_in_view() {
+    let temp_dir = TempDir::new().unwrap();
+    let bedgraph_path = temp_dir.path().join("out.bedgraph");
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let region = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.main_area);
+
+    harness.handle_command("filter length>=148").await.unwrap();
+    harness
+        .handle_command(&format!("export-cov {}", bedgraph_path.display()))
+        .await
+        .unwrap();
+
+    let bedgraph = std::fs::read_to_string(&bedgraph_path).unwrap();
+    let lines = bedgraph.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len() as u64, region.end() - region.start() + 1);
+    let alignment = &harness.app.state.alignments[0];
+    for (line, position) in lines.iter().zip(region.start()..) {
+        assert_eq!(
+            *line,
+            format!(
+                "MN908947.3\t{}\t{position}\t{}",
+                position - 1,
+                alignment.coverage_at(position).total
+            )
+        );
+    }
+    assert!(lines.iter().any(|line| !line.ends_with("\t0")));
+
+    harness
+        .handle_command(&format!("export-cov {} 10", bedgraph_path.display()))
+        .await
+        .unwrap();
+    let binned = std::fs::read_to_string(&bedgraph_path).unwrap();
+    assert_eq!(
+        binned.lines().count() as u64,
+        (region.end() - region.start()) / 10 + 1
+    );
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!(
+            "Exported coverage of {} bins to {}",
+            binned.lines().count(),
+            bedgraph_path.display()
+        )]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_export_reads_without_alignments_reports_an_error() {
+    let args = offline_case_args(None, "-g tests/data/covid.fa -r MN908947.3:154 --offline");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let error = harness
+        .handle_command("export-reads out.bam")
+        .await
+        .unwrap_err();
+    assert_eq!(format!("{error}"), "State error: No alignments are loaded");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_seq_exports_the_reference_in_view_on_both_strands() {
+    let temp_dir = TempDir::new().unwrap();
+    let fasta_path = temp_dir.path().join("view.fa");
+    let args = offline_case_args(None, "-g tests/data/covid.fa -r MN908947.3:29900 --offline");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let contig_end = std::fs::read_to_string(test_data_path("covid.fa"))
+        .unwrap()
+        .lines()
+        .skip(1)
+        .collect::<String>()
+        .split_off(29903 - 10);
+
+    harness
+        .handle_command(&format!("seq {}", fasta_path.display()))
+        .await
+        .unwrap();
+    let fasta = std::fs::read_to_string(&fasta_path).unwrap();
+    let (header, bases) = fasta.split_once('\n').unwrap();
+    assert!(header.starts_with(">MN908947.3:"));
+    assert!(header.ends_with("-29903"));
+    assert!(bases.replace('\n', "").ends_with(&contig_end));
+    assert!(bases.lines().all(|line| line.len() <= 60));
+
+    harness.handle_command("seq -").await.unwrap();
+    assert!(harness.app.exit_output[0].starts_with(&format!("{}(-)\n", header)));
+    let reverse_complement = contig_end
+        .bytes()
+        .rev()
+        .map(|base| gv_core::sequence::complement(base) as char)
+        .collect::<String>();
+    assert!(
+        harness.app.exit_output[0]
+            .replace('\n', "")
+            .contains(&format!("(-){reverse_complement}"))
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_revcomp_complements_the_sequence_row() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let row = |harness: &AppHarness, area_type: AreaType, line: u16| {
+        let area = harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(other, area)| (*other == area_type).then_some(*area))
+            .unwrap();
+        let buffer = harness.terminal_backend().buffer();
+        (area.left()..area.right())
+            .map(|x| buffer[(x, area.top() + line)].symbol())
+            .collect::<String>()
+    };
+    let forward = row(&harness, AreaType::Sequence, 0);
+    assert!(forward.starts_with("TGTTCTCTAAACGAACTTTA"));
+    assert!(!row(&harness, AreaType::Coordinate, 1).starts_with("3'"));
+
+    harness.handle_command("revcomp").await.unwrap();
+    assert!(harness.app.state.revcomp);
+    assert!(harness.app.state.alignments[0].complement_bases);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Showing the minus strand, 3' to 5'".to_string()]
+    );
+    let complemented = forward
+        .bytes()
+        .map(|base| gv_core::sequence::complement(base) as char)
+        .collect::<String>();
+    assert_eq!(row(&harness, AreaType::Sequence, 0), complemented);
+    assert!(complemented.starts_with("ACAAGAGATTTGCTTGAAAT"));
+    let coordinates = row(&harness, AreaType::Coordinate, 1);
+    assert!(coordinates.starts_with("3'"));
+    assert!(coordinates.ends_with("5'"));
+
+    harness.handle_command("revcomp").await.unwrap();
+    assert!(!harness.app.state.alignments[0].complement_bases);
+    assert_eq!(row(&harness, AreaType::Sequence, 0), forward);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_ruler_places_major_ticks_on_multiples_of_the_interval() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("ruler 25bp").await.unwrap();
+    assert_eq!(harness.app.state.ruler_interval, Some(25));
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Ruler major ticks every 25 bp".to_string()]
+    );
+
+    let area = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(other, area)| (*other == AreaType::Coordinate).then_some(*area))
+        .unwrap();
+    let left = harness.app.alignment_view.left(&area);
+    let buffer = harness.terminal_backend().buffer();
+    let major_ticks = (area.left()..area.right())
+        .filter(|x| buffer[(*x, area.top() + 1)].symbol() == "|")
+        .map(|x| left + (x - area.left()) as u64)
+        .collect::<Vec<_>>();
+    assert!(!major_ticks.is_empty());
+    assert!(major_ticks.iter().all(|tick| tick % 25 == 0));
+    let labels = (area.left()..area.right())
+        .map(|x| buffer[(x, area.top())].symbol())
+        .collect::<String>();
+    assert!(labels.contains(&major_ticks[1].to_string()));
+
+    harness.handle_command("ruler wide").await.unwrap();
+    assert_eq!(harness.app.state.ruler_interval, Some(25));
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Register error: Usage: ruler LENGTH (e.g. 10kb) or ruler auto".to_string()]
+    );
+    harness.handle_command("ruler auto").await.unwrap();
+    assert_eq!(harness.app.state.ruler_interval, None);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_coords_0_shifts_the_ruler_and_the_status_bar_readout() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness.handle_command("ruler 25bp").await.unwrap();
+
+    let area_of = |harness: &AppHarness, area_type: AreaType| {
+        harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(other, area)| (*other == area_type).then_some(*area))
+            .unwrap()
+    };
+    let text = |harness: &AppHarness, area: ratatui::layout::Rect| {
+        let buffer = harness.terminal_backend().buffer();
+        area.positions()
+            .map(|position| buffer[position].symbol())
+            .collect::<String>()
+    };
+    let major_ticks = |harness: &AppHarness| {
+        let area = area_of(harness, AreaType::Coordinate);
+        let left = harness.app.alignment_view.left(&area);
+        let buffer = harness.terminal_backend().buffer();
+        (area.left()..area.right())
+            .filter(|x| buffer[(*x, area.top() + 1)].symbol() == "|")
+            .map(|x| left + (x - area.left()) as u64)
+            .collect::<Vec<_>>()
+    };
+    let status_bar = area_of(&harness, AreaType::Error);
+
+    assert!(text(&harness, status_bar).contains("MN908947.3: 100"));
+    assert!(!text(&harness, status_bar).contains("[0-based]"));
+
+    // Position 101 is 100 in 0-based coordinates, so the 0-based ticks sit one base to the right.
+    harness.handle_command("coords 0").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Showing 0-based coordinates".to_string()]
+    );
+    let ticks = major_ticks(&harness);
+    assert!(ticks.contains(&101));
+    assert!(ticks.iter().all(|tick| (tick - 1) % 25 == 0));
+    let labels = text(&harness, area_of(&harness, AreaType::Coordinate));
+    assert!(labels.contains("100"));
+    assert!(!labels.contains("101"));
+    // The base under the cursor is the half-open interval [99, 100).
+    assert!(text(&harness, status_bar).contains("MN908947.3: 99-100"));
+    assert!(text(&harness, status_bar).contains("[0-based]"));
+
+    harness.handle_command("coords 1").await.unwrap();
+    let ticks = major_ticks(&harness);
+    assert!(ticks.contains(&100));
+    assert!(ticks.iter().all(|tick| tick % 25 == 0));
+    assert!(text(&harness, status_bar).contains("MN908947.3: 100"));
+    assert!(!text(&harness, status_bar).contains("[0-based]"));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_seq_without_a_reference_reports_an_error() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "--no-reference -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let error = harness.handle_command("seq").await.unwrap_err();
+    assert_eq!(
+        format!("{error}"),
+        "State error: No reference is loaded. Provide a reference with -g."
+    );
+    assert!(harness.app.exit_output.is_empty());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_cursor_without_a_reference_shows_na() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "--no-reference -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("cursor on").await.unwrap();
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("MN908947.3: 100 (N/A)"));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_captures_and_browses_snapshots() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("snapshot").await.unwrap();
+    harness
+        .handle_movement(Movement::Position(33_121_130))
+        .await
+        .unwrap();
+    harness.handle_command("snapshot after move").await.unwrap();
+
+    let labels = harness
+        .app
+        .snapshots
+        .snapshots
+        .iter()
+        .map(|snapshot| snapshot.label.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labels,
+        vec!["chr22:33121120".to_string(), "after move".to_string()]
+    );
+    assert_eq!(
+        harness.app.snapshots.snapshots[0].buffer.area,
+        harness.app.layout.main_area
+    );
+
+    harness.handle_command("snapshots").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Snapshots);
+    assert_eq!(harness.app.registers.snapshot_cursor, 1);
+
+    harness
+        .handle_key_codes([KeyCode::Char('h'), KeyCode::Char('h')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.registers.snapshot_cursor, 0);
+
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_lists_and_switches_gene_tracks() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("track list").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Available tracks (1): ncbiGene".to_string()]
+    );
+
+    assert!(harness.handle_command("track use refGene").await.is_err());
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+
+    harness.handle_command("track use ncbiGene").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Using gene track ncbiGene".to_string()]
+    );
+    assert!(!harness.app.state.track.genes().is_empty());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_track_flag_selects_the_gene_track_on_startup() {
+    let args = offline_case_args(
+        None,
+        "-g ecoli --offline --cache-dir tests/data/cache --track ncbiGene",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert!(!harness.app.state.track.genes().is_empty());
+
+    harness.handle_command("track refGene").await.unwrap_err();
+    harness.close().await.unwrap();
+
+    let args = offline_case_args(
+        None,
+        "-g ecoli --offline --cache-dir tests/data/cache --track refGene",
+    );
+    assert!(AppHarness::from_args(&args).await.is_err());
+}
+
+#[tokio::test]
+async fn offline_gene_command_picks_from_matching_genes() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("gene lac").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::GeneList);
+    assert_eq!(harness.app.registers.current, KeyRegisterType::GeneList);
+    // Prefix matches come first.
+    assert_eq!(
+        harness.app.registers.gene_list[..5],
+        ["lacA", "lacI", "lacY", "lacZ", "alaC"]
+    );
+
+    harness
+        .handle_key_codes([KeyCode::Down, KeyCode::Down, KeyCode::Down, KeyCode::Enter])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+    assert_eq!(harness.app.registers.current, KeyRegisterType::Normal);
+    let lacz_start = harness
+        .app
+        .state
+        .track
+        .gene_by_name("lacZ")
+        .unwrap()
+        .transcription_start;
+    assert_eq!(harness.app.alignment_view.focus.position, lacz_start + 1);
+
+    harness.handle_command("gene zzzz").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["No genes match zzzz".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_goto_frames_a_gene_with_flanks() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area = harness.app.layout.main_area;
+
+    harness.handle_command("goto lacZ +2kb").await.unwrap();
+    let lacz = harness
+        .app
+        .state
+        .track
+        .gene_by_name("lacZ")
+        .unwrap()
+        .clone();
+    let (start, end) = (lacz.transcription_start, lacz.transcription_end);
+    let view = &harness.app.alignment_view;
+    assert_eq!(view.zoom, (end - start + 4_000).div_ceil(area.width as u64));
+    assert!(view.left(&area) <= start - 2_000 + view.zoom);
+    assert!(view.right(&area) + view.zoom >= end + 2_000);
+
+    harness.handle_command("goto lacZ 200%").await.unwrap();
+    let view = &harness.app.alignment_view;
+    assert!(view.left(&area) <= start - (end - start) / 2 + view.zoom);
+    assert!(view.right(&area) + view.zoom >= end + (end - start) / 2);
+    assert!(view.left(&area) > start - (end - start));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_contig_overview_bins_genes_and_zooms_into_a_bin() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area = harness.app.layout.main_area;
+
+    harness
+        .handle_key_codes([KeyCode::Char('O')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::ContigOverview);
+    assert_eq!(
+        harness.app.registers.current,
+        KeyRegisterType::ContigOverview
+    );
+    let overview = harness.app.registers.contig_overview.clone();
+    assert_eq!(overview.bins(), area.width as usize);
+    assert!(overview.gene_counts.iter().sum::<usize>() > 1_000);
+    // No alignment file, so no coverage.
+    assert!(overview.coverage.is_empty());
+    let cursor = harness.app.registers.contig_overview_cursor;
+    assert_eq!(
+        cursor,
+        overview.bin_index(harness.app.alignment_view.focus.position)
+    );
+
+    harness
+        .handle_key_codes([KeyCode::Char('p'), KeyCode::Char('l'), KeyCode::Enter])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+    assert_eq!(harness.app.registers.current, KeyRegisterType::Normal);
+    let (start, end) = overview.bin_range(cursor + 11);
+    let view = &harness.app.alignment_view;
+    assert!(view.left(&area) <= start + view.zoom);
+    assert!(view.right(&area) + view.zoom >= end);
+    assert!(view.right(&area) - view.left(&area) < 2 * (end - start + 1));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_minimap_marks_gene_dense_bins_and_navigates_on_click() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("minimap on").await.unwrap();
+    let tracks = &harness.app.layout.tracks;
+    let minimap_index = tracks
+        .iter()
+        .position(|track| *track == AreaType::Minimap)
+        .unwrap();
+    assert_eq!(tracks[minimap_index + 1], AreaType::Coordinate);
+
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Minimap)
+        .copied()
+        .unwrap();
+    let (start_x, end_x) = contig_overview_x_range(&area, &harness.app.state);
+    let minimap = harness.app.state.minimap.clone().unwrap();
+    assert_eq!(minimap.bins(), (end_x - start_x) as usize);
+    assert!(minimap.gene_dense_bins().contains(&true));
+
+    let row = (area.x..area.right())
+        .map(|x| {
+            harness.terminal_backend().buffer()[(x, area.y)]
+                .symbol()
+                .to_string()
+        })
+        .collect::<String>();
+    assert!(row.starts_with("Minimap"));
+
+    // Clicking the middle of the strip jumps to the middle of the contig.
+    let messages = harness
+        .app
+        .mouse_register
+        .handle_mouse_event(
+            &harness.app.state,
+            &mut harness.app.layout,
+            &harness.app.alignment_view,
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: area.x + (start_x + end_x) / 2,
+                row: area.y,
+                modifiers: KeyModifiers::NONE,
+            },
+        )
+        .unwrap();
+    harness.handle(messages).await.unwrap();
+    let position = harness.app.alignment_view.focus.position;
+    let half = minimap.contig_length / 2;
+    assert!(position.abs_diff(half) <= minimap.contig_length / minimap.bins() as u64 + 1);
+
+    harness.handle_command("minimap off").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::Minimap));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_gaps_track_shows_runs_of_ns_without_a_gap_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let fasta_path = temp_dir.path().join("gapped.fa");
+    let bases = format!(
+        "{}{}{}",
+        "ACGT".repeat(100),
+        "N".repeat(50),
+        "ACGT".repeat(50)
+    );
+    std::fs::write(&fasta_path, format!(">chrT\n{bases}\n")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("gapped.fa.fai"),
+        format!("chrT\t{0}\t6\t{0}\t{1}\n", bases.len(), bases.len() + 1),
+    )
+    .unwrap();
+    let args = format!("-g {} -r chrT:425 --offline", fasta_path.display());
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("gaps").await.unwrap();
+    let gap_track = harness.app.state.gaps.clone().unwrap();
+    assert_eq!(gap_track.gaps, None);
+
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Gaps)
+        .copied()
+        .unwrap();
+    let row = (area.x..area.right())
+        .map(|x| {
+            harness.terminal_backend().buffer()[(x, area.y)]
+                .symbol()
+                .to_string()
+        })
+        .collect::<String>();
+    assert!(row.contains(" N 50bp "), "{row}");
+    let left = harness.app.alignment_view.left(&area);
+    let gap_x = (401 - left) as usize;
+    assert_eq!(&row[gap_x - 1..gap_x + 1], " /");
+
+    harness.handle_command("gaps").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::Gaps));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_locus_list_is_visited_with_n_and_wraps_around() {
+    let temp_dir = TempDir::new().unwrap();
+    let loci_path = temp_dir.path().join("loci.txt");
+    std::fs::write(
+        &loci_path,
+        "# loci to review\nchr22:33121200\nnot a locus\nchr22:33,125,000\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        &format!(
+            "-r chr22:33121120 --no-reference --offline --loci {}",
+            loci_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.state.loci.loci.len(), 2);
+    assert_eq!(harness.app.state.loci.progress(), None);
+
+    harness
+        .handle_key_codes([KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33121200");
+    assert_eq!(harness.app.state.loci.progress(), Some("1/2".to_string()));
+
+    harness
+        .handle_key_codes([KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33125000");
+    assert_eq!(harness.app.state.loci.progress(), Some("2/2".to_string()));
+
+    harness.handle_command("loci next").await.unwrap();
+    assert_eq!(harness.locus(), "chr22:33121200");
+
+    harness
+        .handle_key_codes([KeyCode::Char('N')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33125000");
+    harness.handle_command("loci prev").await.unwrap();
+    assert_eq!(harness.locus(), "chr22:33121200");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_target_regions_keep_navigation_on_target() {
+    let temp_dir = TempDir::new().unwrap();
+    let regions_path = temp_dir.path().join("panel.bed");
+    std::fs::write(
+        &regions_path,
+        "chr22\t33125000\t33125100\tEXON2\nchr22\t33121000\t33121300\tEXON1\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        &format!(
+            "-r chr22:33121120 --no-reference --offline --regions {}",
+            regions_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.state.target_regions.len(), 2);
+    assert_eq!(harness.locus(), "chr22:33121120");
+
+    harness
+        .handle_key_codes([KeyCode::Char(']'), KeyCode::Char('r')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33125001");
+    harness
+        .handle_key_codes([KeyCode::Char(']'), KeyCode::Char('r')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33121001");
+    harness
+        .handle_key_codes([KeyCode::Char('['), KeyCode::Char('r')])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33125001");
+
+    // Panning past the end of a region stops at its edge.
+    harness
+        .handle_key_codes([
+            KeyCode::Char('5'),
+            KeyCode::Char('0'),
+            KeyCode::Char('0'),
+            KeyCode::Char('l'),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chr22:33125100");
+    assert!(harness.app.state.messages.is_empty());
+
+    harness.handle_command("chr22:33122000").await.unwrap();
+    assert_eq!(harness.locus(), "chr22:33121300");
+    assert_eq!(
+        harness.app.state.messages,
+        vec![
+            "chr22:33122000 is outside target regions. Moved to the nearest region chr22:33121001-33121300"
+                .to_string()
+        ]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_stacked_alignments_are_labeled_and_selectable() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "{} -g tests/data/covid.fa -r MN908947.3:100 --offline",
+            test_data_path("covid.sorted.cram")
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.state.alignments.len(), 2);
+
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("covid.sorted.bam"));
+    assert!(screen.contains("covid.sorted.cram"));
+
+    harness
+        .handle_key_codes([KeyCode::Char('g'), KeyCode::Char('t')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.registers.alignment_track, Some(0));
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Keys and commands act on track 1/2: covid.sorted.bam".to_string()]
+    );
+
+    // Commands only change the selected track.
+    harness.handle_command("group orientation").await.unwrap();
+    assert!(!harness.app.state.alignment_options[0].is_empty());
+    assert!(harness.app.state.alignment_options[1].is_empty());
+
+    harness
+        .handle_key_codes([
+            KeyCode::Char('g'),
+            KeyCode::Char('t'),
+            KeyCode::Char('g'),
+            KeyCode::Char('t'),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.registers.alignment_track, None);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Commands act on all alignment tracks".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_locus_movement_without_a_locus_list_is_an_error() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let locus = harness.locus();
+
+    let error = harness
+        .handle_key_codes([KeyCode::Char('n')])
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "State error: No loci are loaded. Load a locus list with --loci FILE"
+    );
+    assert_eq!(harness.locus(), locus);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_split_screen_keeps_a_region_per_pane() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area = harness.app.layout.main_area;
+    let left_locus = harness.locus();
+
+    harness.handle_command("split").await.unwrap();
+    assert_eq!(harness.app.layout.split, Some(Pane::Right));
+    assert_eq!(harness.app.layout.view_area().x, area.width / 2);
+    assert_eq!(harness.locus(), left_locus);
+    assert!(!harness.app.state.alignments[0].reads.is_empty());
+
+    harness
+        .handle_movement(Movement::Position(33_122_000))
+        .await
+        .unwrap();
+    let right_locus = harness.locus();
+    assert_ne!(right_locus, left_locus);
+    let (left_view, _) = harness.app.inactive_pane.as_ref().unwrap();
+    assert_eq!(
+        left_view
+            .focus
+            .to_locus_str(&harness.app.state.contig_header)
+            .unwrap(),
+        left_locus
+    );
+
+    // Options apply to both panes, so the inactive pane renders in the paired view too.
+    harness.handle_command("view pairs").await.unwrap();
+    harness
+        .handle_key_codes([KeyCode::Char('g'), KeyCode::Char('w')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.layout.split, Some(Pane::Left));
+    assert_eq!(harness.locus(), left_locus);
+    assert!(harness.app.state.paired_alignments[0].is_some());
+
+    harness.handle_command("split").await.unwrap();
+    assert_eq!(harness.app.layout.split, None);
+    assert!(harness.app.inactive_pane.is_none());
+    assert_eq!(harness.app.layout.view_area(), area);
+    assert_eq!(harness.locus(), left_locus);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bigbed_gene_track_is_queried_from_the_file() {
+    let args = format!(
+        "-g {} --bigbed {} -r thrL --offline",
+        test_data_path("cache/GCF_000005845.2/GCF_000005845.2.2bit"),
+        test_data_path("cache/GCF_000005845.2/GCF_000005845.2_ASM584v2.ncbiGene.bb"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert!(harness.app.layout.tracks.contains(&AreaType::GeneTrack));
+
+    // thrL is a single block at NC_000913.3:190-255 (0-based chromStart 189).
+    let gene = harness.app.state.track.gene_by_name("thrL").unwrap();
+    assert_eq!(gene.id, "NP_414542.1");
+    assert_eq!(
+        (gene.transcription_start, gene.transcription_end),
+        (190, 255)
+    );
+    assert_eq!((gene.cds_start, gene.cds_end), (190, 255));
+    assert_eq!(
+        (gene.exon_starts.clone(), gene.exon_ends.clone()),
+        (vec![190], vec![255])
+    );
+
+    harness.handle_command("track list").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Available tracks (1): GCF_000005845.2_ASM584v2.ncbiGene".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_whatgene_reports_the_gene_region_or_the_nearest_gene() {
+    let args = offline_case_args(
+        None,
+        "-g ecoli --offline --cache-dir tests/data/cache -r NC_000913.3:200",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // thrL is a single coding exon at NC_000913.3:190-255.
+    let id = harness
+        .app
+        .state
+        .track
+        .gene_by_name("thrL")
+        .unwrap()
+        .id
+        .clone();
+    harness.handle_command("whatgene").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!("thrL (+) {id}: exon 1, CDS")]
+    );
+
+    harness.handle_command("NC_000913.3:100").await.unwrap();
+    harness.handle_command("whatgene").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!(
+            "No gene at the cursor. Nearest: thrL (+) {id}, 90 bp right"
+        )]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_gff3_gene_track_groups_records_into_genes() {
+    let args = format!(
+        "-g {} --gtf {} -r S --offline",
+        test_data_path("covid.fa"),
+        test_data_path("covid.gff3"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert!(harness.app.layout.tracks.contains(&AreaType::GeneTrack));
+
+    // S has a CDS record parented directly to the gene record.
+    let gene = harness.app.state.track.gene_by_name("S").unwrap();
+    assert_eq!(gene.id, "gene-S");
+    assert_eq!(
+        (gene.transcription_start, gene.transcription_end),
+        (21563, 25384)
+    );
+    assert_eq!((gene.cds_start, gene.cds_end), (21563, 25384));
+    assert_eq!(
+        (gene.exon_starts.clone(), gene.exon_ends.clone()),
+        (vec![21563], vec![25384])
+    );
+
+    // ORF1ab is named by the gene record, the parent of its mRNA.
+    harness.handle_command("ORF1ab").await.unwrap();
+    let gene = harness.app.state.track.gene_by_name("ORF1ab").unwrap();
+    assert_eq!(gene.id, "rna-ORF1ab");
+    assert_eq!(
+        (gene.transcription_start, gene.transcription_end),
+        (266, 21555)
+    );
+
+    harness
+        .handle_movement(Movement::NextGenesStart(2))
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:26245");
+
+    harness.handle_command("track list").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Available tracks (1): covid".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_gene_navigation_continues_on_the_adjacent_contig() {
+    let temp_dir = TempDir::new().unwrap();
+    let fasta_path = temp_dir.path().join("two_contigs.fa");
+    let bases = "ACGT".repeat(500);
+    std::fs::write(&fasta_path, format!(">chrA\n{bases}\n>chrB\n{bases}\n")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("two_contigs.fa.fai"),
+        format!(
+            "chrA\t{0}\t6\t{0}\t{1}\nchrB\t{0}\t{2}\t{0}\t{1}\n",
+            bases.len(),
+            bases.len() + 1,
+            6 + bases.len() + 1 + 6
+        ),
+    )
+    .unwrap();
+    let gff_path = temp_dir.path().join("two_contigs.gff3");
+    let records = [
+        ("chrA", "a1", 101, 200),
+        ("chrB", "b1", 301, 400),
+        ("chrB", "b2", 1001, 1100),
+    ]
+    .iter()
+    .map(|(contig, name, start, end)| {
+        format!(
+            "{contig}\ttest\tgene\t{start}\t{end}\t.\t+\t.\tID=gene-{name};Name={name}\n\
+                 {contig}\ttest\tmRNA\t{start}\t{end}\t.\t+\t.\tID=rna-{name};Parent=gene-{name}\n\
+                 {contig}\ttest\texon\t{start}\t{end}\t.\t+\t.\tParent=rna-{name}\n"
+        )
+    })
+    .collect::<String>();
+    std::fs::write(&gff_path, format!("##gff-version 3\n{records}")).unwrap();
+    let args = format!(
+        "-g {} --gtf {} -r chrA:500 --offline",
+        fasta_path.display(),
+        gff_path.display()
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // Past the last gene of chrA, W lands on the first gene of chrB.
+    harness
+        .handle_movement(Movement::NextGenesStart(1))
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chrB:301");
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Past the last gene of chrA, moved to chrB".to_string()]
+    );
+
+    // B is symmetric: before the first gene of chrB, it lands on the last gene of chrA.
+    harness.handle_command("chrB:250").await.unwrap();
+    harness
+        .handle_movement(Movement::PreviousGenesStart(1))
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chrA:100");
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Past the first gene of chrB, moved to chrA".to_string()]
+    );
+
+    // Past the last gene of the last contig, navigation wraps around to the first contig.
+    harness.handle_command("chrB:1500").await.unwrap();
+    harness
+        .handle_movement(Movement::NextGenesStart(1))
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "chrA:101");
+
+    // Exon navigation continues on the adjacent contig as well.
+    harness.handle_command("chrA:500").await.unwrap();
+    harness
+        .handle_movement(Movement::NextExonsStart(1))
+        .await
+        .unwrap();
+    assert!(harness.locus().starts_with("chrB:"));
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Past the last exon of chrA, moved to chrB".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bigwig_signal_tracks_stack_and_scale_to_the_view() {
+    let args = format!(
+        "{} -g {} --bigwig {} --bigwig {} -r MN908947.3:150 --offline",
+        test_data_path("covid.sorted.bam"),
+        test_data_path("covid.fa"),
+        test_data_path("covid.signal.bw"),
+        test_data_path("covid.signal.bw"),
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+
+    let signal_areas = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .filter(|(area_type, _)| matches!(area_type, AreaType::Signal(_)))
+        .map(|(_, rect)| *rect)
+        .collect::<Vec<_>>();
+    assert_eq!(signal_areas.len(), 2);
+    assert!(signal_areas[0].bottom() <= signal_areas[1].top());
+
+    // The fixture has 5.0 over 101-150 and 10.0 over 151-200. 201-300 has no data.
+    let signal = &harness.app.state.signals[0];
+    assert_eq!(signal.max_value(101, 150), 5.0);
+    assert_eq!(signal.max_value(120, 160), 10.0);
+    assert_eq!(signal.max_value(201, 300), 0.0);
+
+    let buffer = harness.terminal_backend().buffer();
+    let row = |y: u16| {
+        (signal_areas[0].left()..signal_areas[0].right())
+            .map(|x| buffer[(x, y)].symbol())
+            .collect::<String>()
+    };
+    assert!(row(signal_areas[0].top()).starts_with("[0-10.0]"));
+    assert!(row(signal_areas[0].bottom() - 1).contains("█"));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_translation_shows_amino_acids_under_codons() {
+    let args = format!(
+        "-g {} --gtf {} -r MN908947.3:21600 --offline",
+        test_data_path("covid.fa"),
+        test_data_path("covid.gff3"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.alignment_view.zoom, 1);
+
+    let translation_area = |harness: &AppHarness| {
+        harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(area_type, area)| (*area_type == AreaType::Translation).then_some(*area))
+            .unwrap()
+    };
+    let row = |harness: &AppHarness| {
+        let area = translation_area(harness);
+        let buffer = harness.terminal_backend().buffer();
+        (area.left()..area.right())
+            .map(|x| buffer[(x, area.top())].symbol())
+            .collect::<String>()
+    };
+
+    // S starts with MFVFLVLL at 21563.
+    assert!(row(&harness).contains(" M  F  V  F  L  V  L  L "));
+
+    // Codons are not drawn unless each base has its own column.
+    harness
+        .handle_core(vec![CoreMessage::Zoom(Zoom::Out(2))])
+        .await
+        .unwrap();
+    assert!(row(&harness).trim().is_empty());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_goto_gene_residue_centers_on_the_codon() {
+    let args = format!(
+        "-g {} --bigbed {} -r thrL --offline",
+        test_data_path("cache/GCF_000005845.2/GCF_000005845.2.2bit"),
+        test_data_path("cache/GCF_000005845.2/GCF_000005845.2_ASM584v2.ncbiGene.bb"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // thrL is a forward-strand, single-exon gene with CDS 190-255: 22 codons including the stop codon.
+    harness.handle_command("goto thrL:p.2").await.unwrap();
+    assert_eq!(harness.locus(), "NC_000913.3:193");
+
+    harness.handle_command("goto thrL:p.22").await.unwrap();
+    assert_eq!(harness.locus(), "NC_000913.3:253");
+
+    assert!(harness.handle_command("goto thrL:p.23").await.is_err());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_info_reports_reference_and_ucsc_host() {
+    let args = offline_case_args(None, "-g ecoli --offline --cache-dir tests/data/cache");
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("info").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Reference: GCF_000005845.2 | UCSC host: not in use".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_toggles_modification_pileup() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    assert!(!harness.app.state.show_modification_pileup);
+    harness.handle_command("mod pileup").await.unwrap();
+    assert!(harness.app.state.show_modification_pileup);
+    assert!(
+        harness
+            .app
+            .state
+            .messages
+            .last()
+            .unwrap()
+            .contains("all modifications combined, e.g. 5mC and 5hmC")
+    );
+    assert!(
+        harness
+            .app
+            .layout
+            .tracks
+            .contains(&AreaType::ModificationPileup(0))
+    );
+
+    harness.handle_command("mod pileup").await.unwrap();
+    assert!(!harness.app.state.show_modification_pileup);
+    assert!(
+        !harness
+            .app
+            .layout
+            .tracks
+            .contains(&AreaType::ModificationPileup(0))
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_help_filters_entries_by_keyword() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let help_page = |harness: &AppHarness| {
+        let buffer = harness.terminal_backend().buffer();
+        buffer
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>()
+    };
+
+    harness.handle_command("help sort").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Help);
+    assert_eq!(harness.app.registers.help_keyword, "sort");
+    assert!(help_page(&harness).contains(":sort _key_"));
+    assert!(!help_page(&harness).contains("Switch to command mode"));
+
+    // Matching lines are drawn in the palette's highlight color.
+    let page = help_page(&harness);
+    let buffer = harness.terminal_backend().buffer();
+    let row = page.find(":sort _key_").unwrap() / buffer.area.width as usize;
+    let x = (buffer.area.width as usize * row..buffer.area.width as usize * (row + 1))
+        .find(|i| buffer.content()[*i].symbol() != " ")
+        .unwrap();
+    assert_eq!(
+        buffer.content()[x].fg,
+        harness.app.settings.palette.HIGHLIGHT_COLOR
+    );
+
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    harness.handle_command("h").await.unwrap();
+    assert_eq!(harness.app.registers.help_keyword, "");
+    assert!(help_page(&harness).contains("Switch to command mode"));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_help_scrolls_to_the_last_entry() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    let help_page = |harness: &AppHarness| {
+        let buffer = harness.terminal_backend().buffer();
+        buffer
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>()
+    };
+
+    harness.handle_command("h").await.unwrap();
+    assert!(help_page(&harness).contains("Terminal Genome Viewer"));
+    assert!(!help_page(&harness).contains(":height _track_ _rows_"));
+
+    // Scrolling stops with the last entry at the bottom.
+    harness
+        .handle_key_codes([KeyCode::Char('}'); 10])
+        .await
+        .unwrap();
+    assert!(!help_page(&harness).contains("Terminal Genome Viewer"));
+    assert!(help_page(&harness).contains(":height _track_ _rows_"));
+    let bottom = harness.app.registers.help_scroll;
+    harness
+        .handle_key_codes([KeyCode::Char('k')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.registers.help_scroll, bottom - 1);
+    harness
+        .handle_key_codes([KeyCode::Char('j'), KeyCode::Char('j')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.registers.help_scroll, bottom);
+
+    // Reopening the help page starts at the top.
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    harness.handle_command("h").await.unwrap();
+    assert_eq!(harness.app.registers.help_scroll, 0);
+    assert!(help_page(&harness).contains("Terminal Genome Viewer"));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_indels_show_a_histogram_of_the_shown_reads() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // Two reads in the fixture have a 1-bp deletion at 114.
+    harness.handle_command("indels").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Popup);
+    let view = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.view_area());
+    assert_eq!(
+        harness.app.popup.title,
+        format!("Indels in MN908947.3:{}-{}", view.start(), view.end())
+    );
+    assert_eq!(
+        harness.app.popup.lines,
+        vec![
+            "Insertions: none".to_string(),
+            String::new(),
+            "Deletions: 2".to_string(),
+            format!(
+                "1 bp {} 2",
+                harness.app.settings.palette.glyphs.block().repeat(30)
+            ),
+        ]
+    );
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("Deletions: 2"));
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+
+    // Reads hidden by filters are not counted.
+    harness.handle_command("filter length>=1000").await.unwrap();
+    let error = harness.handle_command("indels").await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "State error: No insertions or deletions in the view"
+    );
+    assert_eq!(harness.app.scene, Scene::Main);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_insertion_popup_opens_over_the_main_view_and_closes_on_esc() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // The fixture has no insertions.
+    let error = harness
+        .handle_key_codes([KeyCode::Char('i')])
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "State error: No insertion at the cursor");
+    assert_eq!(harness.app.scene, Scene::Main);
+    harness.close().await.unwrap();
+
+    // Two reads insert TTAG and one read inserts TA after 154.
+    let args = offline_case_args(
+        Some("insertions.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness
+        .handle_key_codes([KeyCode::Char('i')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::Popup);
+    assert_eq!(harness.app.registers.current, KeyRegisterType::Popup);
+    assert_eq!(
+        harness.app.popup,
+        Popup {
+            title: "Insertions at MN908947.3:154".to_string(),
+            lines: vec![
+                "TTAG (4 bp): 2 reads".to_string(),
+                "TA (2 bp): 1 read".to_string(),
+            ],
+        }
+    );
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains(" Insertions at MN908947.3:154 "));
+    assert!(screen.contains("TTAG (4 bp): 2 reads"));
+
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+    assert_eq!(harness.app.registers.current, KeyRegisterType::Normal);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_variant_popup_lists_record_fields_and_samples() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr20:14370 tests/data/simple.vcf --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness
+        .handle_key_codes([KeyCode::Char('V')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::Popup);
+    assert_eq!(
+        harness.app.popup,
+        Popup {
+            title: "Variants at chr20:14370".to_string(),
+            lines: vec![
+                "REF: G  ALT: A".to_string(),
+                "QUAL: 29  FILTER: PASS".to_string(),
+                "INFO: NS=3 DP=14 AF=0.5 DB H2".to_string(),
+                "NA00001: GT=0|0 DP=1 AD=.".to_string(),
+                "NA00002: GT=1|0 DP=8 AD=.".to_string(),
+                "NA00003: GT=1/1 DP=5 AD=.".to_string(),
+            ],
+        }
+    );
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains(" Variants at chr20:14370 "));
+    assert!(screen.contains("NA00002: GT=1|0 DP=8 AD=."));
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+
+    harness.handle_command("vcf-info AF,DB,AA").await.unwrap();
+    harness
+        .handle_key_codes([KeyCode::Char('V')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.popup.lines[2], "INFO: AF=0.5 DB AA=.");
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+
+    harness
+        .handle_movement(Movement::Position(14380))
+        .await
+        .unwrap();
+    let error = harness
+        .handle_key_codes([KeyCode::Char('V')])
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "State error: No variant at the cursor");
+    assert_eq!(harness.app.scene, Scene::Main);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bed_tracks_use_item_rgb_and_print_names() {
+    let temp_dir = TempDir::new().unwrap();
+    let peaks_path = temp_dir.path().join("peaks.bed");
+    std::fs::write(
+        &peaks_path,
+        "MN908947.3\t60\t100\tpeak1\t0\t+\t60\t100\t255,0,0\n\
+         MN908947.3\t110\t112\tlongname\t0\t+\t110\t112\t0\n",
+    )
+    .unwrap();
+    let blacklist_path = temp_dir.path().join("blacklist.bed");
+    std::fs::write(&blacklist_path, "MN908947.3\t90\t120\n").unwrap();
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "-g tests/data/covid.fa -r MN908947.3:100 -b {} -b {} --offline",
+            peaks_path.display(),
+            blacklist_path.display()
+        ),
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+
+    let row = |area_type: AreaType| {
+        let area = harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(a, area)| (*a == area_type).then_some(*area))
+            .unwrap();
+        let buffer = harness.terminal_backend().buffer();
+        area.positions()
+            .map(|position| buffer[position].clone())
+            .collect::<Vec<_>>()
+    };
+    let peaks = row(AreaType::Bed(0));
+    let peaks_text = peaks.iter().map(|cell| cell.symbol()).collect::<String>();
+    assert!(peaks_text.contains("peak1"));
+    assert!(!peaks_text.contains("longname"));
+    let peaks_colors = peaks
+        .iter()
+        .map(|cell| cell.bg)
+        .collect::<std::collections::HashSet<_>>();
+    assert!(peaks_colors.contains(&ratatui::style::Color::Rgb(255, 0, 0)));
+    // "0" falls back to the track colors.
+    assert!(peaks_colors.contains(&DARK_THEME.BED2));
+
+    let blacklist_colors = row(AreaType::Bed(1))
+        .iter()
+        .map(|cell| cell.bg)
+        .collect::<std::collections::HashSet<_>>();
+    assert!(blacklist_colors.contains(&DARK_THEME.BED1));
+    assert!(!blacklist_colors.contains(&ratatui::style::Color::Rgb(255, 0, 0)));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bed12_tracks_draw_thick_thin_and_gap_segments() {
+    let temp_dir = TempDir::new().unwrap();
+    let bed_path = temp_dir.path().join("transcripts.bed");
+    std::fs::write(
+        &bed_path,
+        "MN908947.3\t80\t120\t.\t0\t+\t85\t115\t255,0,0\t2\t10,10,\t0,30,\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "-g tests/data/covid.fa -r MN908947.3:100 -b {} --offline",
+            bed_path.display()
+        ),
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+
+    let area = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(area_type, area)| (*area_type == AreaType::Bed(0)).then_some(*area))
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let cells = area
+        .positions()
+        .map(|position| buffer[position].clone())
+        .collect::<Vec<_>>();
+    let red = ratatui::style::Color::Rgb(255, 0, 0);
+
+    // Blocks 81-90 and 111-120, thick 86-115.
+    let thick = cells
+        .iter()
+        .filter(|cell| cell.bg == red && cell.modifier.contains(ratatui::style::Modifier::BOLD))
+        .count();
+    let thin = cells
+        .iter()
+        .filter(|cell| cell.fg == red && cell.symbol() == "▅")
+        .count();
+    let gap = cells
+        .iter()
+        .filter(|cell| cell.fg == red && ["-", ">"].contains(&cell.symbol()))
+        .count();
+    assert_eq!((thick, thin, gap), (10, 10, 20));
+    assert!(
+        cells
+            .iter()
+            .any(|cell| cell.bg == red && cell.symbol() == ">")
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bookmarks_are_saved_overwritten_and_jumped_to() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("mark site").await.unwrap();
+    harness.handle_command("mark other").await.unwrap();
+    harness.handle_command("500").await.unwrap();
+    harness.handle_command("goto @site").await.unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:100");
+
+    harness.handle_command("1000").await.unwrap();
+    harness.handle_command("mark site").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages.last().unwrap(),
+        "Moved bookmark @site to MN908947.3:1000"
+    );
+    harness.handle_command("200").await.unwrap();
+    harness.handle_command("goto @site").await.unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:1000");
+
+    let error = harness.handle_command("goto @missing").await.unwrap_err();
+    assert_eq!(
+        format!("{error}"),
+        "State error: Unknown bookmark @missing. List bookmarks with :marks"
+    );
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+
+    // Bookmarks are listed by name: "other" is selected first.
+    harness.handle_command("marks").await.unwrap();
+    assert_eq!(harness.app.scene, Scene::Bookmarks);
+    let screen = harness
+        .terminal_backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("@other   MN908947.3:100"));
+    assert!(screen.contains("@site    MN908947.3:1000"));
+    harness
+        .handle_key_codes([KeyCode::Char('j'), KeyCode::Char('k'), KeyCode::Enter])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.scene, Scene::Main);
+    assert_eq!(harness.locus(), "MN908947.3:100");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_toggles_gc_skew_track() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("gcskew").await.unwrap();
+    assert!(harness.app.layout.tracks.contains(&AreaType::GcSkew));
+
+    // The whole covid contig is loaded so that the cumulative skew starts at the contig origin.
+    assert_eq!(harness.app.state.sequence.start, 1);
+    assert_eq!(harness.app.state.sequence.len(), 29_903);
+
+    harness.handle_command("gcskew").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::GcSkew));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_marks_cpgs_and_shows_cpg_islands() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("cpg").await.unwrap();
+    assert!(harness.app.state.show_cpg);
+
+    harness.handle_command("cpg islands").await.unwrap();
+    let sequence_index = harness
+        .app
+        .layout
+        .tracks
+        .iter()
+        .position(|track| *track == AreaType::Sequence)
+        .unwrap();
+    assert_eq!(
+        harness.app.layout.tracks[sequence_index + 1],
+        AreaType::CpgIslands
+    );
+    assert_eq!(harness.app.state.sequence.len(), 29_903);
+
+    harness.handle_command("cpg islands").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::CpgIslands));
+    harness.handle_command("cpg").await.unwrap();
+    assert!(!harness.app.state.show_cpg);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_shows_gc_content_track_under_the_sequence() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("gc on").await.unwrap();
+    let sequence_index = harness
+        .app
+        .layout
+        .tracks
+        .iter()
+        .position(|track| *track == AreaType::Sequence)
+        .unwrap();
+    assert_eq!(
+        harness.app.layout.tracks[sequence_index + 1],
+        AreaType::GcContent
+    );
+    assert_eq!(harness.app.state.sequence.len(), 29_903);
+
+    // Showing an already-shown track keeps it.
+    harness.handle_command("gc on").await.unwrap();
+    assert!(harness.app.layout.tracks.contains(&AreaType::GcContent));
+
+    harness.handle_command("gc window 50").await.unwrap();
+    assert_eq!(harness.app.layout.gc_content_window, Some(50));
+    harness.handle_command("gc window auto").await.unwrap();
+    assert_eq!(harness.app.layout.gc_content_window, None);
+
+    harness.handle_command("gc off").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::GcContent));
+    harness.handle_command("gc off").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::GcContent));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_translates_codon_with_genetic_code_override() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("codon").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Codon TGA -> * (standard)".to_string()]
+    );
+
+    harness.handle_command("aa code vertmito").await.unwrap();
+    harness.handle_command("codon").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Codon TGA -> W (vertmito)".to_string()]
+    );
+
+    harness.handle_command("aa code auto").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Using genetic code by contig (standard)".to_string()]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_cursor_mode_moves_cursor_and_pans_at_edges() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:1000 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("cursor on").await.unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(1000));
+
+    harness
+        .handle_key_codes([KeyCode::Char('l'), KeyCode::Char('l'), KeyCode::Char('h')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(1001));
+    assert_eq!(harness.app.alignment_view.focus.position, 1000);
+
+    let buffer = harness.terminal_backend().buffer();
+    let screen = buffer
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect::<String>();
+    assert!(screen.contains("MN908947.3: 1001 ("));
+    // The reference base at the cursor, between its flanks.
+    let (left, base, right) = harness.app.state.sequence.base_context(1001, 10).unwrap();
+    assert_eq!((left.len(), right.len()), (10, 10));
+    assert!(screen.contains(&format!(
+        "({}{}{})",
+        String::from_utf8_lossy(left).to_ascii_lowercase(),
+        char::from(base).to_ascii_uppercase(),
+        String::from_utf8_lossy(right).to_ascii_lowercase(),
+    )));
+
+    // Cursor past the right edge pans the view by the overshoot.
+    let area = harness.app.layout.main_area;
+    let right = harness.app.alignment_view.right(&area);
+    harness
+        .handle_movement(Movement::Right(right - 1001 + 10))
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(right + 10));
+    assert_eq!(harness.app.alignment_view.focus.position, 1010);
+
+    harness.handle_command("cursor off").await.unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, None);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_center_moves_the_view_around_the_cursor() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:1000 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area = harness.app.layout.view_area();
+
+    harness.handle_command("cursor on").await.unwrap();
+    harness.handle_movement(Movement::Left(30)).await.unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(970));
+
+    harness
+        .handle_key_codes([KeyCode::Char('g'), KeyCode::Char('z')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 970);
+    assert_eq!(harness.app.alignment_view.cursor, Some(970));
+
+    harness.handle_command("center start").await.unwrap();
+    assert_eq!(harness.app.alignment_view.left(&area), 970);
+    harness.handle_command("center end").await.unwrap();
+    assert_eq!(harness.app.alignment_view.right(&area), 970);
+    assert_eq!(harness.app.alignment_view.cursor, Some(970));
+
+    harness.handle_command("cursor off").await.unwrap();
+    let focus = harness.app.alignment_view.focus.clone();
+    harness.handle_command("center start").await.unwrap();
+    assert_eq!(harness.app.alignment_view.focus, focus);
+    assert_eq!(
+        harness.app.state.messages,
+        vec![
+            "The cursor is always centered with cursor mode off. Turn it on with :cursor on"
+                .to_string()
+        ]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_find_jumps_to_motif_matches() {
+    // AAATTGGC is at 1037, 3008, and 28521, and its reverse complement at 5480.
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:1000 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("find aaattggc").await.unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 1037);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Found AAATTGGC at MN908947.3:1037 (+)".to_string()]
+    );
+
+    harness
+        .handle_key_codes([KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 3008);
+    harness
+        .handle_key_codes([KeyCode::Char('2'), KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 1037);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Search wrapped around MN908947.3. Found AAATTGGC at MN908947.3:1037 (+)".to_string()]
+    );
+    harness
+        .handle_key_codes([KeyCode::Char('N')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 28521);
+
+    // The reverse complement matches with -r.
+    harness.handle_command("find -r AAATTGGC").await.unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 1037);
+    harness
+        .handle_key_codes([KeyCode::Char('2'), KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.focus.position, 5480);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Found AAATTGGC (both strands) at MN908947.3:5480 (-)".to_string()]
+    );
+
+    // Searches start from the cursor.
+    harness.handle_command("cursor on").await.unwrap();
+    harness.handle_movement(Movement::Left(3000)).await.unwrap();
+    harness
+        .handle_key_codes([KeyCode::Char('n')])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.alignment_view.cursor, Some(3008));
+
+    harness.handle_command("find clear").await.unwrap();
+    assert_eq!(harness.app.state.motif, None);
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Search cleared. n / N jump to loci again".to_string()]
+    );
+
+    let focus = harness.app.alignment_view.focus.clone();
+    let error = harness
+        .handle_command("find GGGGGGGGGGGGGGGGGGGG")
+        .await
+        .unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("GGGGGGGGGGGGGGGGGGGG not found on MN908947.3")
+    );
+    assert_eq!(harness.app.alignment_view.focus, focus);
+
+    harness.close().await.unwrap();
+}
+
+#[rstest]
+#[case("-r U00096.3:100", "U00096.3 is NC_000913.3 in the reference")]
+#[case(
+    "-r chrNC_000913.3:100",
+    "chrNC_000913.3 is NC_000913.3 in the reference"
+)]
+#[tokio::test]
+async fn offline_contig_aliases_are_resolved(#[case] region: &str, #[case] message: &str) {
+    // The E. coli hub names its contig NC_000913.3, with the chromAlias U00096.3.
+    let args = offline_case_args(
+        None,
+        &format!("-g ecoli --offline --cache-dir tests/data/cache {region}"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.locus(), "NC_000913.3:100");
+    assert_eq!(harness.app.state.messages, vec![message.to_string()]);
+
+    harness.handle_command("NC_000913.3:2000").await.unwrap();
+    assert_eq!(harness.locus(), "NC_000913.3:2000");
+    assert!(harness.app.state.messages.is_empty());
+
+    let error = harness.handle_command("chr22:2000").await.unwrap_err();
+    assert_eq!(error.to_string(), "State error: Contig chr22 not found");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_peak_jumps_to_coverage_peaks() {
+    // The reads of covid.sorted.bam start in the first 16 kb window of the index.
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:20000 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // The coverage is flat from the first base, so the view moves to the left end of the contig.
+    harness.handle_command("peak").await.unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:41");
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Coverage peak 1/1 on MN908947.3".to_string()]
+    );
+
+    // The only peak is visited again.
+    harness.handle_command("peak next").await.unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:41");
+    assert_eq!(harness.app.state.peak_rank, Some((0, 0)));
+    harness.close().await.unwrap();
+
+    let args = offline_case_args(
+        Some("covid.sorted.cram"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let error = harness.handle_command("peak").await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "State error: Coverage peaks need a BAM index. CRAM files and stdin are not supported"
+    );
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_peak_visits_peaks_in_descending_coverage() {
+    // chr1 of peaks.sorted.bam has reads in three 16 kb windows: 20 reads at 5001 and 5021, 40 reads at 40001 and
+    // 40051, and 10 reads at 70001. All reads are 100 bp.
+    let args = offline_case_args(
+        Some("peaks.sorted.bam"),
+        "-r chr1:1 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("peak").await.unwrap();
+    assert_eq!(harness.locus(), "chr1:40051");
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Coverage peak 1/3 on chr1".to_string()]
+    );
+
+    harness.handle_command("peak next").await.unwrap();
+    assert_eq!(harness.locus(), "chr1:5021");
+    harness.handle_command("peak next").await.unwrap();
+    assert_eq!(harness.locus(), "chr1:70001");
+    assert_eq!(harness.app.state.peak_rank, Some((0, 2)));
+
+    // Wraps around to the highest peak.
+    harness.handle_command("peak next").await.unwrap();
+    assert_eq!(harness.locus(), "chr1:40051");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_resize_smaller_then_larger_leaves_no_stale_cells() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.resize(100, 30);
+    let large_frame = harness.terminal_backend().buffer().clone();
+    assert_eq!(harness.app.layout.main_area.width, 100);
+
+    harness.resize(60, 16);
+    assert_eq!(harness.app.layout.main_area.height, 16);
+
+    harness.resize(100, 30);
+    assert_eq!(harness.terminal_backend().buffer(), &large_frame);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_glyph_flags_draw_braille_coverage_and_ascii_only_screens() {
+    let braille_args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline --braille",
+    );
+    let harness = AppHarness::from_args(&braille_args).await.unwrap();
+    assert!(
+        harness
+            .terminal_backend()
+            .buffer()
+            .content()
+            .iter()
+            .any(|cell| cell
+                .symbol()
+                .chars()
+                .any(|c| ('\u{2801}'..='\u{28FF}').contains(&c)))
+    );
+    harness.close().await.unwrap();
+
+    let ascii_args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline --ascii-only",
+    );
+    let harness = AppHarness::from_args(&ascii_args).await.unwrap();
+    assert!(
+        harness
+            .terminal_backend()
+            .buffer()
+            .content()
+            .iter()
+            .all(|cell| cell.symbol().is_ascii())
+    );
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bam_coverage_overview_loads_from_index_and_navigates_on_click() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    // Without a reference, there is no cytoband and the overview is toggled on by :overview.
+    harness.handle_command("overview").await.unwrap();
+    let overview = harness.app.state.coverage_overview.as_ref().unwrap();
+    assert_eq!(
+        overview.contig_index,
+        harness.app.alignment_view.focus.contig_index
+    );
+    assert!(overview.densities.iter().sum::<u64>() > 0);
+
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::CoverageOverview)
+        .copied()
+        .unwrap();
+    let (start_x, _) = contig_overview_x_range(&area, &harness.app.state);
+    let messages = harness
+        .app
+        .mouse_register
+        .handle_mouse_event(
+            &harness.app.state,
+            &mut harness.app.layout,
+            &harness.app.alignment_view,
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: area.x + start_x,
+                row: area.y,
+                modifiers: KeyModifiers::NONE,
+            },
+        )
+        .unwrap();
+    harness.handle(messages).await.unwrap();
+
+    // The left edge of the overview is the contig start.
+    assert!(harness.app.alignment_view.focus.position < 33_121_120);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_mouse_wheel_pans_within_the_contig() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:29800 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area = harness.app.layout.main_area;
+    let wheel = |harness: &mut AppHarness, kind: MouseEventKind, modifiers: KeyModifiers| {
+        harness
+            .app
+            .mouse_register
+            .handle_mouse_event(
+                &harness.app.state,
+                &mut harness.app.layout,
+                &harness.app.alignment_view,
+                MouseEvent {
+                    kind,
+                    column: area.x + 1,
+                    row: area.y + 1,
+                    modifiers,
+                },
+            )
+            .unwrap()
+    };
+
+    let right = harness.app.alignment_view.right(&area);
+    let messages = wheel(
+        &mut harness,
+        MouseEventKind::ScrollDown,
+        KeyModifiers::SHIFT,
+    );
+    harness.handle(messages).await.unwrap();
+    // A full-window pan past the contig end stops at the end.
+    assert!(right < 29_903);
+    assert_eq!(harness.app.alignment_view.right(&area), 29_903);
+
+    let messages = wheel(&mut harness, MouseEventKind::ScrollUp, KeyModifiers::NONE);
+    harness.handle(messages).await.unwrap();
+    assert_eq!(
+        harness.app.alignment_view.right(&area),
+        29_903 - (area.width / 8) as u64
+    );
+
+    let messages = wheel(
+        &mut harness,
+        MouseEventKind::ScrollDown,
+        KeyModifiers::CONTROL,
+    );
+    harness.handle(messages).await.unwrap();
+    assert_eq!(harness.app.alignment_view.zoom, 2);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_click_pins_read_details_to_the_status_bar() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Alignment(0))
+        .copied()
+        .unwrap();
+    let click = |harness: &mut AppHarness, column: u16, row: u16| {
+        [
+            MouseEventKind::Down(MouseButton::Left),
+            MouseEventKind::Up(MouseButton::Left),
+        ]
+        .into_iter()
+        .flat_map(|kind| {
+            harness
+                .app
+                .mouse_register
+                .handle_mouse_event(
+                    &harness.app.state,
+                    &mut harness.app.layout,
+                    &harness.app.alignment_view,
+                    MouseEvent {
+                        kind,
+                        column,
+                        row,
+                        modifiers: KeyModifiers::NONE,
+                    },
+                )
+                .unwrap()
+        })
+        .collect::<Vec<_>>()
+    };
+    let read_at = |harness: &AppHarness, column: u16, row: u16| {
+        MouseRegister::read_index_at(
+            &harness.app.state,
+            &harness.app.layout,
+            &harness.app.alignment_view,
+            column,
+            row,
+        )
+    };
+
+    // The clicked read is the one stacked on the clicked row that overlaps the clicked column.
+    let (column, row) = (area.x + 5, area.y + 1);
+    let (index, read_index) = read_at(&harness, column, row).unwrap();
+    assert_eq!(index, 0);
+    let alignment = &harness.app.state.alignments[0];
+    let position = harness.app.alignment_view.left(&area) + 5;
+    let read = &alignment.reads[read_index];
+    assert!(read.full_read_overlaps(position, position));
+    assert_eq!(
+        alignment.ys[read_index],
+        harness.app.alignment_view.top(0) + 1
+    );
+
+    let details = read.describe().unwrap();
+    let messages = click(&mut harness, column, row);
+    harness.handle(messages).await.unwrap();
+    assert_eq!(harness.app.state.pinned_read, Some(details.clone()));
+    let (_, status_bar) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Error)
+        .copied()
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let first_row = (status_bar.left()..status_bar.right())
+        .map(|x| buffer[(x, status_bar.top())].symbol())
+        .collect::<String>();
+    assert!(first_row.starts_with(&details[..20]));
+
+    // The details stay pinned across other messages, and Esc unpins them.
+    harness.handle_movement(Movement::Right(1)).await.unwrap();
+    assert_eq!(harness.app.state.pinned_read, Some(details));
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    assert_eq!(harness.app.state.pinned_read, None);
+
+    // Clicking empty space unpins the details.
+    let messages = click(&mut harness, column, row);
+    harness.handle(messages).await.unwrap();
+    assert!(harness.app.state.pinned_read.is_some());
+    let empty = (area.top()..area.bottom())
+        .flat_map(|row| (area.left() + 1..area.right() - 1).map(move |column| (column, row)))
+        .find(|(column, row)| read_at(&harness, *column, *row).is_none())
+        .unwrap();
+    let messages = click(&mut harness, empty.0, empty.1);
+    harness.handle(messages).await.unwrap();
+    assert_eq!(harness.app.state.pinned_read, None);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_bam_filters_and_colors_by_read_length() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness
+        .handle_command("filter length>=100000")
+        .await
+        .unwrap();
+    assert!(
+        harness.app.state.alignments[0]
+            .show_read
+            .iter()
+            .all(|show| !show)
+    );
+
+    harness.handle_command("filter clear").await.unwrap();
+    assert!(
+        harness.app.state.alignments[0]
+            .show_read
+            .iter()
+            .all(|show| *show)
+    );
+
+    harness.handle_command("color length").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignments[0].color,
+        AlignmentColor::ReadLength
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_isolate_shows_only_reads_carrying_an_allele() {
+    let temp_dir = TempDir::new().unwrap();
+    let vcf_path = temp_dir.path().join("covid.vcf");
+    std::fs::write(
+        &vcf_path,
+        "##fileformat=VCFv4.2\n\
+         #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n\
+         MN908947.3\t79\t.\tA\tT\t.\t.\t.\n\
+         MN908947.3\t113\t.\tAG\tA\t.\t.\t.\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "{} -g tests/data/covid.fa -r MN908947.3:113 --offline",
+            vcf_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let shown = |harness: &AppHarness| {
+        harness.app.state.alignments[0]
+            .show_read
+            .iter()
+            .filter(|show| **show)
+            .count()
+    };
+
+    harness.handle_command("isolate alt").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Showing 2 reads with ALT 113 AG>A".to_string()]
+    );
+    assert_eq!(shown(&harness), 2);
+
+    // Replaces the isolated allele and keeps other filters.
+    harness.handle_command("mapq 1").await.unwrap();
+    harness.handle_command("isolate ref").await.unwrap();
+    let reference_reads = shown(&harness);
+    assert!(reference_reads > 2);
+    assert_eq!(harness.app.state.alignment_options[0].len(), 1);
+
+    harness.handle_command("isolate off").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Showing reads of all alleles".to_string()]
+    );
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![AlignmentDisplayOption::Filter(AlignmentFilter::MinMapq(1))]
+    );
+
+    harness
+        .handle(vec![
+            Movement::ContigNamePosition("MN908947.3".to_string(), 200).into(),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(
+        harness
+            .handle_command("isolate alt")
+            .await
+            .unwrap_err()
+            .to_string(),
+        "State error: No variant at the cursor. Move the cursor onto a variant to isolate its reads"
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_support_highlights_reads_carrying_the_alt_allele() {
+    let temp_dir = TempDir::new().unwrap();
+    let vcf_path = temp_dir.path().join("covid.vcf");
+    std::fs::write(
+        &vcf_path,
+        "##fileformat=VCFv4.2\n\
+         #CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n\
+         MN908947.3\t79\t.\tA\tT\t.\t.\t.\n\
+         MN908947.3\t113\t.\tAG\tA\t.\t.\t.\n",
+    )
+    .unwrap();
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!(
+            "{} -g tests/data/covid.fa -r MN908947.3:110 --offline",
+            vcf_path.display()
+        ),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    // Tall enough to show all reads.
+    harness.resize(100, 80);
+
+    harness.handle_command("support").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["2 of 42 reads support 113 AG>A".to_string()]
+    );
+    let buffer = harness.terminal_backend().buffer();
+    assert!(
+        buffer
+            .content()
+            .iter()
+            .any(|cell| cell.modifier.contains(ratatui::style::Modifier::BOLD))
+    );
+    assert!(
+        buffer
+            .content()
+            .iter()
+            .any(|cell| cell.modifier.contains(ratatui::style::Modifier::DIM))
+    );
+
+    harness
+        .handle(vec![
+            Movement::ContigNamePosition("MN908947.3".to_string(), 80).into(),
+        ])
+        .await
+        .unwrap();
+    harness.handle_command("support").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["3 of 42 reads support 79 A>T".to_string()]
+    );
+
+    harness.handle_command("support clear").await.unwrap();
+    assert_eq!(harness.app.state.supported_allele, None);
+    assert!(
+        !harness
+            .terminal_backend()
+            .buffer()
+            .content()
+            .iter()
+            .any(|cell| cell.modifier.contains(ratatui::style::Modifier::DIM))
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_view_pairs_and_linear_keep_other_alignment_options() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("mapq 10").await.unwrap();
+    harness.handle_command("view pairs").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![
+            AlignmentDisplayOption::Filter(AlignmentFilter::MinMapq(10)),
+            AlignmentDisplayOption::ViewAsPairs,
+        ]
+    );
+    assert!(harness.app.state.paired_alignments[0].is_some());
+    assert_eq!(
+        harness.app.state.messages.last(),
+        Some(&"Viewing reads as pairs".to_string())
+    );
+
+    harness.handle_command("view linear").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![AlignmentDisplayOption::Filter(AlignmentFilter::MinMapq(10))]
+    );
+    assert!(harness.app.state.paired_alignments[0].is_none());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_color_length_applies_to_the_paired_view() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness.handle_command("color length").await.unwrap();
+    harness.handle_command("view pairs").await.unwrap();
+    assert!(harness.app.state.paired_alignments[0].is_some());
+
+    let area = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(other, area)| (*other == AreaType::Alignment(0)).then_some(*area))
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let backgrounds = area
+        .positions()
+        .map(|position| buffer[position].bg)
+        .collect::<std::collections::HashSet<_>>();
+    let palette = &harness.app.settings.palette;
+    assert!(!backgrounds.contains(&palette.MATCH_COLOR));
+    assert!(
+        harness.app.state.alignments[0]
+            .reads
+            .iter()
+            .any(|read| backgrounds.contains(&palette.read_length_color(read.reference_span())))
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_filter_keeps_sort_and_other_alignment_options() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("sort base").await.unwrap();
+    harness.handle_command("color length").await.unwrap();
+    harness.handle_command("view pairs").await.unwrap();
+    harness.handle_command("filter length>=100").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![
+            AlignmentDisplayOption::Filter(AlignmentFilter::ReadLengthGE(100)),
+            AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(100)),
+            AlignmentDisplayOption::Color(AlignmentColor::ReadLength),
+            AlignmentDisplayOption::ViewAsPairs,
+        ]
+    );
+    assert!(harness.app.state.paired_alignments[0].is_some());
+
+    // An option of the same kind replaces the current one.
+    harness.handle_command("color default").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![
+            AlignmentDisplayOption::Filter(AlignmentFilter::ReadLengthGE(100)),
+            AlignmentDisplayOption::Sort(AlignmentSort::BaseAt(100)),
+            AlignmentDisplayOption::ViewAsPairs,
+            AlignmentDisplayOption::Color(AlignmentColor::Default),
+        ]
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_downsample_caps_displayed_reads_and_keeps_coverage() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let total = harness.app.state.alignments[0].coverage_at(100).total;
+    assert_eq!(harness.app.state.alignments[0].downsampled_count(), 0);
+
+    harness.handle_command("downsample 5").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Downsampling to 5 reads per column".to_string()]
+    );
+    let alignment = &harness.app.state.alignments[0];
+    assert!(alignment.downsampled_count() > 0);
+    assert_eq!(alignment.coverage_at(100).total, total);
+    let displayed_depth = |position: u64| {
+        alignment
+            .reads
+            .iter()
+            .zip(alignment.displayed_reads())
+            .filter(|(read, displayed)| {
+                *displayed && read.start <= position && read.end >= position
+            })
+            .count()
+    };
+    assert!((1..=200).all(|position| displayed_depth(position) <= 5));
+    assert!(displayed_depth(100) > 0);
+
+    harness.handle_command("downsample off").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].downsampled_count(), 0);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_max_reads_stops_loading_at_the_cap() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+    let total = harness.app.state.alignments[0].reads.len();
+    assert!(total > 10);
+    assert!(!harness.app.state.alignments[0].overflow);
+    harness.close().await.unwrap();
+
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline --max-reads 10",
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+    let alignment = &harness.app.state.alignments[0];
+    assert_eq!(alignment.reads.len(), 10);
+    assert!(alignment.overflow);
+
+    let status_bar = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(area_type, area)| (*area_type == AreaType::Error).then_some(*area))
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let status_text = status_bar
+        .positions()
+        .map(|position| buffer[position].symbol())
+        .collect::<String>();
+    assert!(
+        status_text.contains("[Showing 10 of many reads]"),
+        "{status_text}"
+    );
+    harness.close().await.unwrap();
+
+    // A cap equal to the number of reads loads all of them without overflowing.
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        &format!("-g tests/data/covid.fa -r MN908947.3:100 --offline --max-reads {total}"),
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].reads.len(), total);
+    assert!(!harness.app.state.alignments[0].overflow);
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_group_orientation_stacks_orientation_blocks_and_survives_reloads() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let ungrouped_depth = harness.app.state.alignments[0].depth();
+
+    harness
+        .handle_key_codes([KeyCode::Char('g'), KeyCode::Char('o')])
+        .await
+        .unwrap();
+    assert_eq!(
+        harness.app.state.alignment_options[0],
+        vec![AlignmentDisplayOption::Group(AlignmentGroup::Orientation)]
+    );
+    let alignment = &harness.app.state.alignments[0];
+    let grouped_depth = alignment.depth();
+    assert!(grouped_depth > ungrouped_depth);
+    assert!(alignment.ys_index.iter().any(|reads| reads.is_empty()));
+
+    // Reads keep their groups after moving away and back.
+    harness
+        .handle_movement(Movement::ContigNamePosition(
+            "MN908947.3".to_string(),
+            20000,
+        ))
+        .await
+        .unwrap();
+    harness
+        .handle_movement(Movement::ContigNamePosition("MN908947.3".to_string(), 154))
+        .await
+        .unwrap();
+    assert_eq!(harness.app.state.alignments[0].depth(), grouped_depth);
+
+    harness.handle_command("group default").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].depth(), ungrouped_depth);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_height_command_sets_track_heights_and_saves_them_to_the_session() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let area_height = |harness: &AppHarness, expected_area_type: AreaType| {
+        harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(area_type, area)| (*area_type == expected_area_type).then_some(area.height))
+            .unwrap()
+    };
+    let alignment_height = area_height(&harness, AreaType::Alignment(0));
+
+    harness.handle_command("height coverage 3").await.unwrap();
+    harness.handle_command("height sequence 2").await.unwrap();
+    assert_eq!(area_height(&harness, AreaType::Coverage(0)), 3);
+    assert_eq!(area_height(&harness, AreaType::Sequence), 2);
+    assert_eq!(
+        area_height(&harness, AreaType::Alignment(0)),
+        alignment_height + 2
+    );
+
+    harness.handle_command("height reads 3").await.unwrap();
+    assert!(harness.app.state.messages[0].contains("Invalid track `reads`"));
+
+    let temp_dir = TempDir::new().unwrap();
+    let save_path = temp_dir.path().join("heights.toml");
+    harness
+        .handle_command(&format!("w {}", save_path.display()))
+        .await
+        .unwrap();
+    let content = std::fs::read_to_string(&save_path).unwrap();
+    let heights: TrackHeights = toml::from_str::<toml::Table>(&content).unwrap()["heights"]
+        .clone()
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        heights,
+        TrackHeights {
+            coverage: Some(3),
+            sequence: Some(2),
+            ..TrackHeights::default()
+        }
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_sequence_saves_session_and_save_and_quit() {
+    let args = offline_case_args(
+        Some("ncbi.sorted.bam"),
+        "-r chr22:33121120 --no-reference --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let save_path = temp_dir.path().join("saved-session.toml");
+
+    harness
+        .handle_core(vec![CoreMessage::SaveSession(Some(
+            save_path.display().to_string(),
+        ))])
+        .await
+        .unwrap();
+
+    assert_eq!(harness.app.session_path, save_path);
+    assert!(save_path.exists());
+
+    let session = SessionFile::from_path(&save_path).unwrap();
+    assert_eq!(session.locus, harness.locus());
+
+    let quit_path = temp_dir.path().join("quit-session.toml");
+    harness
+        .handle_core(vec![CoreMessage::SaveAndQuit(Some(
+            quit_path.display().to_string(),
+        ))])
+        .await
+        .unwrap();
+
+    assert!(harness.app.exit);
+    assert_eq!(harness.app.session_path, quit_path);
+    assert!(quit_path.exists());
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_session_restores_filters_and_bookmarks() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness.handle_command("filter length>=148").await.unwrap();
+    harness.handle_command("mapq 20").await.unwrap();
+    harness.handle_command("vcf-filter PASS").await.unwrap();
+    harness.handle_command("mark site").await.unwrap();
+    harness.handle_command("300").await.unwrap();
+    let alignment_options = harness.app.state.alignment_options.clone();
+
+    let temp_dir = TempDir::new().unwrap();
+    let save_path = temp_dir.path().join("panel.toml");
+    harness
+        .handle_command(&format!("session save {}", save_path.display()))
+        .await
+        .unwrap();
+    harness.close().await.unwrap();
+
+    let mut session = SessionFile::from_path(&save_path).unwrap();
+    assert!(session.remove_missing_files().is_empty());
+    let mut settings = Settings::try_from(session).unwrap();
+    cli_from_args("--offline")
+        .apply_overrides(&mut settings)
+        .unwrap();
+    let harness = AppHarness::from_settings(settings).await.unwrap();
+
+    assert_eq!(harness.locus(), "MN908947.3:300");
+    assert_eq!(harness.app.state.alignment_options, alignment_options);
+    assert!(harness.app.state.variant_filter.pass_only);
+    assert_eq!(
+        harness
+            .app
+            .state
+            .bookmark("site")
+            .unwrap()
+            .to_locus_str(&harness.app.state.contig_header)
+            .unwrap(),
+        "MN908947.3:154"
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_supplementary_reads_are_hidden_from_reads_and_coverage_until_shown() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let alignment = &harness.app.state.alignments[0];
+    assert!(
+        alignment
+            .reads
+            .iter()
+            .all(|read| !read.record.flags().is_supplementary())
+    );
+    let hidden_coverage = alignment.coverage_at(50).total;
+
+    harness.handle_command("show supplementary").await.unwrap();
+    let alignment = &harness.app.state.alignments[0];
+    assert_eq!(alignment.reads.len(), 95);
+    let supplementary_at_50 = alignment
+        .reads
+        .iter()
+        .filter(|read| read.record.flags().is_supplementary() && read.base_at(50).is_some())
+        .count();
+    assert!(supplementary_at_50 > 0);
+    assert_eq!(
+        alignment.coverage_at(50).total,
+        hidden_coverage + supplementary_at_50
+    );
+
+    harness.handle_command("hide supplementary").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages.last().unwrap(),
+        "Hiding supplementary reads"
+    );
+    assert_eq!(harness.app.state.alignments[0].reads.len(), 72);
+    assert_eq!(
+        harness.app.state.alignments[0].coverage_at(50).total,
+        hidden_coverage
+    );
+    harness.close().await.unwrap();
+
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline --show-hidden supplementary",
+    );
+    let harness = AppHarness::from_args(&args).await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].reads.len(), 95);
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_show_and_hide_apply_to_each_read_category_independently() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let alignment = &harness.app.state.alignments[0];
+    assert_eq!(alignment.reads.len(), 72);
+    assert_eq!(alignment.hidden_read_counts.supplementary, 23);
+    assert_eq!(alignment.hidden_read_counts.total(), 23);
+
+    harness.handle_command("show supplementary").await.unwrap();
+    harness.handle_command("show duplicate").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].reads.len(), 95);
+
+    // Hiding duplicates again leaves supplementary reads shown.
+    harness.handle_command("hide duplicate").await.unwrap();
+    assert_eq!(
+        harness.app.state.shown_read_categories,
+        std::collections::BTreeSet::from([
+            HiddenReadCategory::Unmapped,
+            HiddenReadCategory::Supplementary
+        ])
+    );
+    assert_eq!(harness.app.state.alignments[0].reads.len(), 95);
+
+    harness.handle_command("hide supplementary").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].reads.len(), 72);
+
+    harness.handle_command("show hidden").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages.last().unwrap(),
+        "Showing unmapped, secondary, supplementary, and duplicate reads"
+    );
+    assert_eq!(
+        harness.app.state.shown_read_categories,
+        HiddenReadCategory::all()
+    );
+    let alignment = &harness.app.state.alignments[0];
+    assert_eq!(alignment.reads.len(), 95);
+    assert_eq!(alignment.hidden_read_counts.total(), 0);
+
+    harness.close().await.unwrap();
+}
+
+#[rstest]
+#[case(vec![], "                                                1% (1 / 72)  72 shown, 23 hidden")]
+#[case(vec!["mapq 20"], "                                    [MAPQ>=20]  1% (1 / 71)  71 shown, 23 hidden")]
+#[case(
+    vec!["mapq 20", "view pairs", "show secondary", "theme light"],
+    "   [MAPQ>=20] [Pairs] [Show secondary] [light]  1% (1 / 71)  71 shown, 23 hidden"
+)]
+// Badges that don't fit are counted.
+#[case(
+    vec!["mapq 20", "filter length>=100", "view pairs", "sort base", "hide duplicate", "theme colorblind"],
+    "       [MAPQ>=20 AND Length>=100] [Pairs] [+3]  2% (1 / 42)  42 shown, 23 hidden"
+)]
+#[case(vec!["colorby tag NM"], "                              [NM: 0 1 2 3 +3]  1% (1 / 72)  72 shown, 23 hidden")]
+#[tokio::test]
+async fn offline_status_bar_shows_active_filters_and_modes(
+    #[case] commands: Vec<&str>,
+    #[case] expected: &str,
+) {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    for command in commands {
+        harness.handle_command(command).await.unwrap();
+    }
+
+    let status_bar = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find_map(|(area_type, area)| (*area_type == AreaType::Error).then_some(*area))
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let last_row = (status_bar.left()..status_bar.right())
+        .map(|x| buffer[(x, status_bar.bottom() - 1)].symbol())
+        .collect::<String>();
+    assert_eq!(last_row, expected);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_reads_are_colored_by_tag() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    harness.handle_command("colorby tag NM").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignments[0].color,
+        AlignmentColor::Tag("NM".to_string())
+    );
+
+    let area = |harness: &AppHarness, target: AreaType| {
+        harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(area_type, area)| (*area_type == target).then_some(*area))
+            .unwrap()
+    };
+    let palette = harness.app.settings.palette;
+    let buffer = harness.terminal_backend().buffer().clone();
+
+    // Every read has NM, so none has the neutral color.
+    let alignment_area = area(&harness, AreaType::Alignment(0));
+    let read_colors = (alignment_area.top()..alignment_area.bottom())
+        .flat_map(|y| (alignment_area.left()..alignment_area.right()).map(move |x| (x, y)))
+        .map(|(x, y)| buffer[(x, y)].bg)
+        .collect::<std::collections::HashSet<_>>();
+    assert!(read_colors.contains(&palette.tag_color(Some("0"))));
+    assert!(!read_colors.contains(&palette.MATCH_COLOR));
+
+    // Legend values are drawn in their colors.
+    let status_bar = area(&harness, AreaType::Error);
+    let y = status_bar.bottom() - 1;
+    let row = (status_bar.left()..status_bar.right())
+        .map(|x| buffer[(x, y)].symbol().to_string())
+        .collect::<Vec<_>>();
+    let legend_x = row.join("").find("[NM: 0 1").unwrap() as u16 + status_bar.left();
+    assert_eq!(buffer[(legend_x + 5, y)].fg, palette.tag_color(Some("0")));
+    assert_eq!(buffer[(legend_x + 7, y)].fg, palette.tag_color(Some("1")));
+
+    harness.handle_command("color default").await.unwrap();
+    assert_eq!(
+        harness.app.state.alignments[0].color,
+        AlignmentColor::Default
+    );
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_haplotype_groups_are_labeled() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:154 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let depth = harness.app.state.alignments[0].depth();
+    harness.handle_command("group hp").await.unwrap();
+
+    // The reads are not phased: they are stacked as before under the unphased label row.
+    let alignment = &harness.app.state.alignments[0];
+    assert_eq!(alignment.depth(), depth + 1);
+    assert_eq!(alignment.group_labels(), vec![(0, Haplotype::Unphased)]);
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Alignment(0))
+        .copied()
+        .unwrap();
+    let buffer = harness.terminal_backend().buffer();
+    let label_row = (area.left()..area.right())
+        .map(|x| buffer[(x, area.top())].symbol())
+        .collect::<String>();
+    let reads_at_cursor = harness.app.state.alignments[0]
+        .haplotype_depths_at(154)
+        .values()
+        .sum::<usize>();
+    assert!(
+        label_row.starts_with(&format!("Unphased  depth {reads_at_cursor} ")),
+        "{label_row}"
+    );
+
+    harness.handle_command("group default").await.unwrap();
+    assert_eq!(harness.app.state.alignments[0].depth(), depth);
+    assert_eq!(harness.app.state.alignments[0].group_labels(), vec![]);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_clicked_split_reads_are_linked_to_their_supplementary_alignments() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Alignment(0))
+        .copied()
+        .unwrap();
+
+    // A leader read at 1-75 in the third row, with a supplementary alignment at 21552 (+).
+    let (column, row) = (area.x + 5, area.y + 2);
+    let (_, read_index) = MouseRegister::read_index_at(
+        &harness.app.state,
+        &harness.app.layout,
+        &harness.app.alignment_view,
+        column,
+        row,
+    )
+    .unwrap();
+    let read = &harness.app.state.alignments[0].reads[read_index];
+    assert_eq!(read.end, 75);
+    let read_end = read.stacking_end();
+    let messages = [
+        MouseEventKind::Down(MouseButton::Left),
+        MouseEventKind::Up(MouseButton::Left),
+    ]
+    .into_iter()
+    .flat_map(|kind| {
+        harness
+            .app
+            .mouse_register
+            .handle_mouse_event(
+                &harness.app.state,
+                &mut harness.app.layout,
+                &harness.app.alignment_view,
+                MouseEvent {
+                    kind,
+                    column,
+                    row,
+                    modifiers: KeyModifiers::NONE,
+                },
+            )
+            .unwrap()
+    })
+    .collect::<Vec<_>>();
+    harness.handle(messages).await.unwrap();
+
+    let split_read = harness.app.state.split_read.clone().unwrap();
+    assert_eq!(split_read.linked.len(), 1);
+    assert_eq!(split_read.linked[0].position, 21552);
+    assert!(split_read.elsewhere.is_empty());
+
+    // The connector runs from the read end, after its soft clip, toward the supplementary alignment on the right.
+    let row_symbols = |harness: &AppHarness| {
+        let buffer = harness.terminal_backend().buffer();
+        (area.left()..area.right())
+            .map(|x| buffer[(x, row)].symbol().to_string())
+            .collect::<Vec<_>>()
+    };
+    let after_read = (read_end + 1 - harness.app.alignment_view.left(&area)) as usize;
+    assert_eq!(row_symbols(&harness)[after_read], "~");
+
+    harness.handle_key_codes([KeyCode::Esc]).await.unwrap();
+    assert_eq!(harness.app.state.split_read, None);
+    assert!(!row_symbols(&harness).contains(&"~".to_string()));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_background_loads_show_a_spinner_until_the_data_arrive() {
+    let args = format!(
+        "{} -g {} --gtf {} -r MN908947.3:100 --offline",
+        test_data_path("covid.sorted.bam"),
+        test_data_path("covid.fa"),
+        test_data_path("covid.gff3"),
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    assert!(harness.app.pending_loads.is_empty());
+
+    let row = |harness: &AppHarness, area_type: AreaType| {
+        let area = harness
+            .app
+            .layout
+            .areas
+            .iter()
+            .find_map(|(other, area)| (*other == area_type).then_some(*area))
+            .unwrap();
+        let buffer = harness.terminal_backend().buffer();
+        (area.left()..area.right())
+            .map(|x| buffer[(x, area.top())].symbol())
+            .collect::<String>()
+    };
+
+    // The sequence at the new locus loads in the background. Alignments wait for it.
+    harness
+        .handle_in_background(vec![Message::Core(CoreMessage::Move(Movement::Position(
+            25_000,
+        )))])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:25000");
+    assert!(harness.app.pending_loads.is_sequence_pending());
+    assert!(harness.app.repository.sequence_service.is_none());
+    assert!(row(&harness, AreaType::Sequence).starts_with("⠋ Loading"));
+    assert!(row(&harness, AreaType::Alignment(0)).starts_with("⠋ Loading"));
+    assert!(
+        !harness.app.state.sequence.has_complete_data(
+            &harness
+                .app
+                .alignment_view
+                .region(&harness.app.layout.view_area())
+        )
+    );
+
+    harness.finish_loads().await.unwrap();
+    assert!(harness.app.pending_loads.is_empty());
+    assert!(harness.app.repository.sequence_service.is_some());
+    assert!(!row(&harness, AreaType::Sequence).contains("Loading"));
+    assert!(
+        row(&harness, AreaType::Sequence)
+            .chars()
+            .all(|base| "ACGTN".contains(base))
+    );
+    let region = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.view_area());
+    assert!(harness.app.state.sequence.has_complete_data(&region));
+    assert!(harness.app.state.alignments[0].has_complete_data(&region));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_gene_locus_waits_for_the_pending_track_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let loci_path = temp_dir.path().join("loci.txt");
+    std::fs::write(&loci_path, "S\n").unwrap();
+    let args = format!(
+        "{} -g {} --gtf {} -r MN908947.3:100 --offline --loci {}",
+        test_data_path("covid.sorted.bam"),
+        test_data_path("covid.fa"),
+        test_data_path("covid.gff3"),
+        loci_path.display()
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness
+        .handle_in_background(vec![Message::Core(CoreMessage::Move(Movement::Position(
+            25_000,
+        )))])
+        .await
+        .unwrap();
+    assert!(harness.app.pending_loads.is_track_pending());
+    assert!(harness.app.repository.track_service.is_none());
+
+    // The gene is looked up once the track service is back.
+    harness
+        .handle_in_background(vec![Message::Core(CoreMessage::Move(Movement::NextLocus(
+            1,
+        )))])
+        .await
+        .unwrap();
+    assert_eq!(harness.app.state.loci.progress(), Some("1/1".to_string()));
+    // S spans 21,563-25,384.
+    assert_eq!(harness.locus(), "MN908947.3:21564");
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_navigation_does_not_wait_for_background_loads_and_replaces_stale_ones() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:100 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness
+        .handle_in_background(vec![Message::Core(CoreMessage::Move(Movement::Position(
+            25_000,
+        )))])
+        .await
+        .unwrap();
+    assert!(harness.app.pending_loads.is_sequence_pending());
+
+    // The next move is handled while the sequence of the previous locus is still out.
+    harness
+        .handle_in_background(vec![Message::Core(CoreMessage::Move(Movement::Position(
+            1_000,
+        )))])
+        .await
+        .unwrap();
+    assert_eq!(harness.locus(), "MN908947.3:1000");
+    assert!(harness.app.pending_loads.is_sequence_pending());
+    assert_eq!(harness.app.state.sequence.base_at(25_000), None);
+
+    // The sequence around 25,000 is dropped, and the sequence around 1,000 is loaded instead.
+    harness.finish_loads().await.unwrap();
+    let region = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.view_area());
+    assert!(harness.app.state.sequence.has_complete_data(&region));
+    assert_eq!(harness.app.state.sequence.base_at(25_000), None);
+    assert!(harness.app.state.alignments[0].has_complete_data(&region));
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_reload_reads_files_rewritten_on_disk() {
+    let temp_dir = TempDir::new().unwrap();
+    let bam_path = temp_dir.path().join("sample.bam");
+    let bai_path = temp_dir.path().join("sample.bam.bai");
+    let vcf_path = temp_dir.path().join("sample.vcf");
+    let bed_path = temp_dir.path().join("sample.bed");
+    std::fs::copy(test_data_path("covid.sorted.bam"), &bam_path).unwrap();
+    std::fs::copy(test_data_path("covid.sorted.bam.bai"), &bai_path).unwrap();
+    let write_vcf = |positions: &[u64]| {
+        let records = positions
+            .iter()
+            .map(|position| format!("MN908947.3\t{position}\t.\tA\tG\t.\t.\t.\n"))
+            .collect::<String>();
+        std::fs::write(
+            &vcf_path,
+            format!(
+                "##fileformat=VCFv4.2\n#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n{records}"
+            ),
+        )
+        .unwrap();
+    };
+    write_vcf(&[150]);
+    std::fs::write(&bed_path, "MN908947.3\t100\t200\n").unwrap();
+
+    let args = format!(
+        "{} {} {} -g {} -r MN908947.3:154 --offline",
+        bam_path.display(),
+        vcf_path.display(),
+        bed_path.display(),
+        test_data_path("covid.fa")
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+    let region = harness
+        .app
+        .alignment_view
+        .region(&harness.app.layout.main_area);
+    let read_count = |harness: &AppHarness| {
+        harness
+            .app
+            .state
+            .shown_reads_overlapping(0, &region)
+            .unwrap()
+            .len()
+    };
+    let all_reads = read_count(&harness);
+    assert_eq!(harness.app.state.variants[0].intervals.len(), 1);
+    assert_eq!(harness.app.state.bed_intervals[0].intervals.len(), 1);
+
+    // The pipeline rewrites the files: a BAM of the long reads with a new index, and more variants and intervals.
+    let long_bam_path = temp_dir.path().join("long.bam");
+    harness.handle_command("filter length>=148").await.unwrap();
+    let long_reads = read_count(&harness);
+    assert!(long_reads < all_reads);
+    harness
+        .handle_command(&format!("export-reads {}", long_bam_path.display()))
+        .await
+        .unwrap();
+    harness.handle_command("filter clear").await.unwrap();
+    std::fs::copy(&long_bam_path, &bam_path).unwrap();
+    noodles::bam::bai::fs::write(&bai_path, &noodles::bam::fs::index(&bam_path).unwrap()).unwrap();
+    write_vcf(&[150, 160]);
+    std::fs::write(&bed_path, "MN908947.3\t100\t200\nMN908947.3\t300\t400\n").unwrap();
+
+    harness.handle_command("reload").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec!["Reloaded 3 files".to_string()]
+    );
+    assert_eq!(read_count(&harness), long_reads);
+    assert_eq!(harness.app.state.variants[0].intervals.len(), 2);
+    assert_eq!(harness.app.state.bed_intervals[0].intervals.len(), 2);
+
+    // A BAM rewritten after its index is reloaded with a warning.
+    std::fs::copy(&long_bam_path, &bam_path).unwrap();
+    let bai_modified = std::fs::metadata(&bai_path).unwrap().modified().unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&bam_path)
+        .unwrap()
+        .set_modified(bai_modified + std::time::Duration::from_secs(1))
+        .unwrap();
+    harness.handle_command("reload").await.unwrap();
+    assert_eq!(
+        harness.app.state.messages,
+        vec![format!(
+            "Reloaded 3 files. {} is older than {}. Re-index the file to read the new records.",
+            bai_path.display(),
+            bam_path.display()
+        )]
+    );
+
+    // A missing file is an error, and the loaded data are kept.
+    std::fs::remove_file(&vcf_path).unwrap();
+    let error = harness.handle_command("reload").await.unwrap_err();
+    assert_eq!(
+        format!("{error}"),
+        format!(
+            "IO Error: Cannot reload {}: the file no longer exists",
+            vcf_path.display()
+        )
+    );
+    assert_eq!(read_count(&harness), long_reads);
+    assert_eq!(harness.app.state.variants[0].intervals.len(), 2);
+
+    harness.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn offline_ideogram_without_cytobands_is_a_plain_bar_and_navigates_on_click() {
+    let args = offline_case_args(
+        Some("covid.sorted.bam"),
+        "-g tests/data/covid.fa -r MN908947.3:20000 --offline",
+    );
+    let mut harness = AppHarness::from_args(&args).await.unwrap();
+
+    harness.handle_command("ideogram").await.unwrap();
+    let (_, area) = harness
+        .app
+        .layout
+        .areas
+        .iter()
+        .find(|(area_type, _)| *area_type == AreaType::Ideogram)
+        .copied()
+        .unwrap();
+    assert_eq!(area.height, 4);
+
+    let (start_x, end_x) = contig_overview_x_range(&area, &harness.app.state);
+    let buffer = harness.terminal_backend().buffer();
+    let bar = (start_x..end_x)
+        .map(|x| buffer[(area.x + x, area.y + 1)].symbol())
+        .collect::<String>();
+    assert_eq!(bar, "▅".repeat((end_x - start_x) as usize));
+
+    let messages = harness
+        .app
+        .mouse_register
+        .handle_mouse_event(
+            &harness.app.state,
+            &mut harness.app.layout,
+            &harness.app.alignment_view,
+            MouseEvent {
+                kind: MouseEventKind::Down(MouseButton::Left),
+                column: area.x + start_x,
+                row: area.y + 1,
+                modifiers: KeyModifiers::NONE,
+            },
+        )
+        .unwrap();
+    harness.handle(messages).await.unwrap();
+    assert!(harness.app.alignment_view.focus.position < 20_000);
+
+    harness.handle_command("ideogram").await.unwrap();
+    assert!(!harness.app.layout.tracks.contains(&AreaType::Ideogram));
 
     harness.close().await.unwrap();
 }
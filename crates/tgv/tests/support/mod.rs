@@ -28,7 +28,10 @@ pub struct AppHarness {
 impl AppHarness {
     pub async fn from_args(args: &str) -> Result<Self, TGVError> {
         let cli = cli_from_args(args);
-        let mut settings: Settings = cli.try_into()?;
+        Self::from_settings(cli.try_into()?).await
+    }
+
+    pub async fn from_settings(mut settings: Settings) -> Result<Self, TGVError> {
         settings.test_mode = true;
 
         let app = App::new(settings, SessionFile::default_path()).await?;
@@ -48,18 +51,36 @@ impl AppHarness {
         self.app
             .handle(self.app.settings.initial_state_messages.clone())
             .await?;
+        self.app.finish_loads().await?;
         self.self_correct()?;
         self.render();
         Ok(())
     }
 
     pub async fn handle(&mut self, messages: Vec<Message>) -> Result<(), TGVError> {
+        self.app.handle(messages).await?;
+        self.app.finish_loads().await?;
+        self.self_correct()?;
+        self.render();
+        Ok(())
+    }
+
+    /// Handle messages and render without waiting for background loads, as the main loop does.
+    pub async fn handle_in_background(&mut self, messages: Vec<Message>) -> Result<(), TGVError> {
         self.app.handle(messages).await?;
         self.self_correct()?;
         self.render();
         Ok(())
     }
 
+    /// Wait for background loads and render their data.
+    pub async fn finish_loads(&mut self) -> Result<(), TGVError> {
+        self.app.finish_loads().await?;
+        self.self_correct()?;
+        self.render();
+        Ok(())
+    }
+
     pub async fn handle_core(&mut self, messages: Vec<CoreMessage>) -> Result<(), TGVError> {
         self.handle(messages.into_iter().map(Message::Core).collect())
             .await
@@ -70,12 +91,14 @@ impl AppHarness {
         key_codes: impl IntoIterator<Item = KeyCode>,
     ) -> Result<(), TGVError> {
         for key_code in key_codes {
-            let messages = self
-                .app
-                .registers
-                .handle_key_event(KeyEvent::new(key_code, KeyModifiers::NONE), &self.app.state)?;
+            let messages = self.app.registers.handle_key_event(
+                KeyEvent::new(key_code, KeyModifiers::NONE),
+                &self.app.state,
+                &self.app.snapshots,
+            )?;
             self.app.handle(messages).await?;
         }
+        self.app.finish_loads().await?;
         self.self_correct()?;
         self.render();
         Ok(())
@@ -116,17 +139,20 @@ impl AppHarness {
             .contig_length(&self.app.alignment_view.focus)?;
         self.app
             .alignment_view
-            .self_correct(&self.app.layout.main_area, contig_length);
+            .self_correct(&self.app.layout.view_area(), contig_length);
         Ok(())
     }
 
     fn render(&mut self) {
         self.terminal
             .draw(|frame| {
-                let buffer = frame.buffer_mut();
-                let _ = self.app.layout.set_area(buffer.area);
-                self.app.render(buffer).expect("render");
+                self.app.render_frame(frame.buffer_mut()).expect("render");
             })
             .expect("terminal render");
     }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.terminal.backend_mut().resize(width, height);
+        self.render();
+    }
 }